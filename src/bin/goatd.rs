@@ -0,0 +1,27 @@
+//! Headless GOATd Kernel CLI
+//!
+//! Builds, audits, benchmarks and manages kernels without the egui frontend,
+//! suitable for SSH sessions, CI pipelines and scripting.
+//!
+//! Usage: goatd <build|audit|bench|list|install|uninstall|help> [OPTIONS]
+//!
+//! Run `goatd help` for the full option list. Exit codes are documented on
+//! `goatd_kernel::cli::exit_code_for_error`.
+
+use goatd_kernel::cli::{self, EXIT_USAGE};
+
+#[tokio::main]
+async fn main() {
+    let args: Vec<String> = std::env::args().skip(1).collect();
+
+    let command = match cli::parse_args(&args) {
+        Ok(command) => command,
+        Err(e) => {
+            eprintln!("goatd: {}\n\n{}", e, cli::USAGE);
+            std::process::exit(EXIT_USAGE);
+        }
+    };
+
+    let code = cli::run(command).await;
+    std::process::exit(code);
+}
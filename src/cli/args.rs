@@ -0,0 +1,765 @@
+//! Argument parsing for every `goatd` subcommand.
+
+use std::path::PathBuf;
+
+use crate::error::AppError;
+use crate::models::{CompilerCache, HardeningLevel, LtoType, PgoMethod, PgoStage};
+use crate::orchestrator::MatrixEntry;
+
+/// Options for `goatd build`.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct BuildArgs {
+    pub config_file: Option<PathBuf>,
+    pub resume: Option<PathBuf>,
+    pub from_manifest: Option<PathBuf>,
+    pub workspace: Option<PathBuf>,
+    pub variant: Option<String>,
+    pub version: Option<String>,
+    pub profile: Option<String>,
+    pub lto: Option<LtoType>,
+    pub hardening: Option<HardeningLevel>,
+    pub options: Vec<(String, String)>,
+    pub modprobed: Option<bool>,
+    pub whitelist: Option<bool>,
+    pub polly: Option<bool>,
+    pub mglru: Option<bool>,
+    pub native: Option<bool>,
+    pub secure_boot: Option<bool>,
+    pub mok_key: Option<PathBuf>,
+    pub mok_cert: Option<PathBuf>,
+    pub compiler_cache: Option<CompilerCache>,
+    pub cache_dir: Option<PathBuf>,
+    pub cmdline_add: Vec<String>,
+    pub cmdline_remove: Vec<String>,
+    pub cmdline_boot_entry: Option<bool>,
+    pub pgo_stage: Option<PgoStage>,
+    pub pgo_method: Option<PgoMethod>,
+    pub pgo_profile: Option<PathBuf>,
+    pub jobs: Option<usize>,
+    pub matrix: Vec<String>,
+    pub parallel: Option<usize>,
+    pub nice: Option<i32>,
+    pub ionice_idle: bool,
+    pub cgroup: bool,
+    pub cpu_quota: Option<u32>,
+    pub memory_max: Option<u64>,
+    pub thermal_limit: Option<f32>,
+    pub strict_kconfig: bool,
+}
+
+/// Options for `goatd bench`.
+#[derive(Debug, Clone, PartialEq)]
+pub struct BenchArgs {
+    pub duration_secs: u64,
+    pub core_id: usize,
+    pub interval_us: u64,
+    pub threshold_us: u64,
+}
+
+impl Default for BenchArgs {
+    fn default() -> Self {
+        BenchArgs {
+            duration_secs: 10,
+            core_id: 0,
+            interval_us: 1000,
+            threshold_us: 500,
+        }
+    }
+}
+
+/// Options for `goatd pgo collect`.
+#[derive(Debug, Clone, PartialEq)]
+pub struct PgoCollectArgs {
+    pub workspace: Option<PathBuf>,
+    pub version: Option<String>,
+    pub method: Option<PgoMethod>,
+    pub command: Option<String>,
+    pub phase_secs: u64,
+    pub vmlinux: Option<PathBuf>,
+}
+
+impl Default for PgoCollectArgs {
+    fn default() -> Self {
+        PgoCollectArgs {
+            workspace: None,
+            version: None,
+            method: None,
+            command: None,
+            phase_secs: 30,
+            vmlinux: None,
+        }
+    }
+}
+
+/// Options for `goatd mirror sync`.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct MirrorSyncArgs {
+    pub dir: Option<PathBuf>,
+    pub variants: Vec<String>,
+}
+
+/// A parsed `goatd` invocation.
+#[derive(Debug, Clone, PartialEq)]
+pub enum Command {
+    Build(Box<BuildArgs>),
+    Audit {
+        deep: bool,
+    },
+    Bench(BenchArgs),
+    List {
+        workspace: Option<PathBuf>,
+    },
+    Install {
+        package: PathBuf,
+        boot_entry: bool,
+        next_boot: bool,
+    },
+    Uninstall {
+        package: String,
+    },
+    ConfigDiff {
+        old: String,
+        new: String,
+        json: bool,
+    },
+    BootCheck,
+    KconfigCheck {
+        source: PathBuf,
+        build: Box<BuildArgs>,
+    },
+    PgoCollect(PgoCollectArgs),
+    MirrorSync(MirrorSyncArgs),
+    Help,
+}
+
+/// Parse command-line arguments (excluding the program name).
+pub fn parse_args(args: &[String]) -> std::result::Result<Command, AppError> {
+    let Some((subcommand, rest)) = args.split_first() else {
+        return Ok(Command::Help);
+    };
+
+    match subcommand.as_str() {
+        "build" => parse_build_args(rest).map(|b| Command::Build(Box::new(b))),
+        "audit" => {
+            let mut deep = false;
+            for arg in rest {
+                match arg.as_str() {
+                    "--deep" => deep = true,
+                    other => return Err(unknown_flag("audit", other)),
+                }
+            }
+            Ok(Command::Audit { deep })
+        }
+        "bench" => parse_bench_args(rest).map(Command::Bench),
+        "list" => {
+            let mut workspace = None;
+            let mut iter = rest.iter();
+            while let Some(arg) = iter.next() {
+                match arg.as_str() {
+                    "--workspace" => workspace = Some(PathBuf::from(flag_value(&mut iter, arg)?)),
+                    other => return Err(unknown_flag("list", other)),
+                }
+            }
+            Ok(Command::List { workspace })
+        }
+        "install" => {
+            let mut boot_entry = true;
+            let mut next_boot = false;
+            let mut packages = Vec::new();
+            for arg in rest {
+                match arg.as_str() {
+                    "--next-boot" => next_boot = true,
+                    "--no-boot-entry" => boot_entry = false,
+                    flag if flag.starts_with("--") => return Err(unknown_flag("install", flag)),
+                    package => packages.push(PathBuf::from(package)),
+                }
+            }
+            match <[PathBuf; 1]>::try_from(packages) {
+                Ok([package]) => Ok(Command::Install {
+                    package,
+                    boot_entry,
+                    next_boot,
+                }),
+                Err(_) => Err(AppError::InvalidInput(
+                    "install expects exactly one package path".to_string(),
+                )),
+            }
+        }
+        "uninstall" => match rest {
+            [package] => Ok(Command::Uninstall {
+                package: package.clone(),
+            }),
+            _ => Err(AppError::InvalidInput(
+                "uninstall expects exactly one package name".to_string(),
+            )),
+        },
+        "config-diff" => {
+            let mut json = false;
+            let mut sides = Vec::new();
+            for arg in rest {
+                match arg.as_str() {
+                    "--json" => json = true,
+                    flag if flag.starts_with("--") => {
+                        return Err(unknown_flag("config-diff", flag))
+                    }
+                    side => sides.push(side.to_string()),
+                }
+            }
+            match <[String; 2]>::try_from(sides) {
+                Ok([old, new]) => Ok(Command::ConfigDiff { old, new, json }),
+                Err(_) => Err(AppError::InvalidInput(
+                    "config-diff expects <OLD> and <NEW>".to_string(),
+                )),
+            }
+        }
+        "boot-check" => match rest {
+            [] => Ok(Command::BootCheck),
+            [flag, ..] => Err(unknown_flag("boot-check", flag)),
+        },
+        "kconfig-check" => match rest.split_first() {
+            Some((source, flags)) if !source.starts_with("--") => Ok(Command::KconfigCheck {
+                source: PathBuf::from(source),
+                build: Box::new(parse_build_args(flags)?),
+            }),
+            _ => Err(AppError::InvalidInput(
+                "kconfig-check expects <SOURCE_DIR>".to_string(),
+            )),
+        },
+        "pgo" => match rest.split_first() {
+            Some((action, flags)) if action == "collect" => {
+                parse_pgo_collect_args(flags).map(Command::PgoCollect)
+            }
+            _ => Err(AppError::InvalidInput(
+                "pgo expects the 'collect' action".to_string(),
+            )),
+        },
+        "mirror" => match rest.split_first() {
+            Some((action, flags)) if action == "sync" => {
+                parse_mirror_sync_args(flags).map(Command::MirrorSync)
+            }
+            _ => Err(AppError::InvalidInput(
+                "mirror expects the 'sync' action".to_string(),
+            )),
+        },
+        "help" | "--help" | "-h" => Ok(Command::Help),
+        other => Err(AppError::InvalidInput(format!(
+            "Unknown command '{}'",
+            other
+        ))),
+    }
+}
+
+fn parse_build_args(args: &[String]) -> std::result::Result<BuildArgs, AppError> {
+    let mut build = BuildArgs::default();
+    let mut iter = args.iter();
+
+    while let Some(arg) = iter.next() {
+        match arg.as_str() {
+            "--config" => build.config_file = Some(PathBuf::from(flag_value(&mut iter, arg)?)),
+            "--resume" => build.resume = Some(PathBuf::from(flag_value(&mut iter, arg)?)),
+            "--from-manifest" => {
+                build.from_manifest = Some(PathBuf::from(flag_value(&mut iter, arg)?))
+            }
+            "--workspace" => build.workspace = Some(PathBuf::from(flag_value(&mut iter, arg)?)),
+            "--variant" => build.variant = Some(flag_value(&mut iter, arg)?),
+            "--version" => build.version = Some(flag_value(&mut iter, arg)?),
+            "--profile" => build.profile = Some(flag_value(&mut iter, arg)?),
+            "--lto" => {
+                build.lto = Some(match flag_value(&mut iter, arg)?.to_lowercase().as_str() {
+                    "none" => LtoType::None,
+                    "thin" => LtoType::Thin,
+                    "full" => LtoType::Full,
+                    other => {
+                        return Err(AppError::InvalidInput(format!(
+                            "Unknown LTO mode '{}' (expected none, thin or full)",
+                            other
+                        )))
+                    }
+                })
+            }
+            "--hardening" => {
+                build.hardening = Some(
+                    flag_value(&mut iter, arg)?
+                        .parse::<HardeningLevel>()
+                        .map_err(AppError::InvalidInput)?,
+                )
+            }
+            "--option" => {
+                let raw = flag_value(&mut iter, arg)?;
+                let (key, value) = raw.split_once('=').ok_or_else(|| {
+                    AppError::InvalidInput(format!("--option expects KEY=VALUE, got '{}'", raw))
+                })?;
+                build.options.push((key.to_string(), value.to_string()));
+            }
+            "--modprobed" => build.modprobed = Some(true),
+            "--no-modprobed" => build.modprobed = Some(false),
+            "--whitelist" => build.whitelist = Some(true),
+            "--no-whitelist" => build.whitelist = Some(false),
+            "--polly" => build.polly = Some(true),
+            "--no-polly" => build.polly = Some(false),
+            "--mglru" => build.mglru = Some(true),
+            "--no-mglru" => build.mglru = Some(false),
+            "--native" => build.native = Some(true),
+            "--no-native" => build.native = Some(false),
+            "--secure-boot" => build.secure_boot = Some(true),
+            "--no-secure-boot" => build.secure_boot = Some(false),
+            "--mok-key" => build.mok_key = Some(PathBuf::from(flag_value(&mut iter, arg)?)),
+            "--mok-cert" => build.mok_cert = Some(PathBuf::from(flag_value(&mut iter, arg)?)),
+            "--compiler-cache" => {
+                build.compiler_cache =
+                    Some(match flag_value(&mut iter, arg)?.to_lowercase().as_str() {
+                        "none" => CompilerCache::None,
+                        "ccache" => CompilerCache::Ccache,
+                        "sccache" => CompilerCache::Sccache,
+                        other => {
+                            return Err(AppError::InvalidInput(format!(
+                                "Unknown compiler cache '{}' (expected none, ccache or sccache)",
+                                other
+                            )))
+                        }
+                    })
+            }
+            "--cache-dir" => build.cache_dir = Some(PathBuf::from(flag_value(&mut iter, arg)?)),
+            "--cmdline-add" => build.cmdline_add.push(flag_value(&mut iter, arg)?),
+            "--cmdline-remove" => build.cmdline_remove.push(flag_value(&mut iter, arg)?),
+            "--cmdline-boot-entry" => build.cmdline_boot_entry = Some(true),
+            "--no-cmdline-boot-entry" => build.cmdline_boot_entry = Some(false),
+            "--pgo" => {
+                build.pgo_stage = Some(match flag_value(&mut iter, arg)?.to_lowercase().as_str() {
+                    "off" => PgoStage::Off,
+                    "instrument" => PgoStage::Instrument,
+                    "optimize" => PgoStage::Optimize,
+                    other => {
+                        return Err(AppError::InvalidInput(format!(
+                            "Unknown PGO stage '{}' (expected off, instrument or optimize)",
+                            other
+                        )))
+                    }
+                })
+            }
+            "--pgo-method" => {
+                build.pgo_method = Some(parse_pgo_method(&flag_value(&mut iter, arg)?)?)
+            }
+            "--pgo-profile" => build.pgo_profile = Some(PathBuf::from(flag_value(&mut iter, arg)?)),
+            "--jobs" => build.jobs = Some(flag_number(&mut iter, arg)?),
+            "--matrix" => {
+                let spec = flag_value(&mut iter, arg)?;
+                MatrixEntry::parse(&spec, LtoType::Thin).map_err(AppError::InvalidInput)?;
+                build.matrix.push(spec);
+            }
+            "--parallel" => build.parallel = Some(flag_number(&mut iter, arg)?),
+            "--nice" => build.nice = Some(flag_number(&mut iter, arg)?),
+            "--ionice-idle" => build.ionice_idle = true,
+            "--cgroup" => build.cgroup = true,
+            "--cpu-quota" => build.cpu_quota = Some(flag_number(&mut iter, arg)?),
+            "--memory-max" => build.memory_max = Some(flag_number(&mut iter, arg)?),
+            "--thermal-limit" => build.thermal_limit = Some(flag_number(&mut iter, arg)?),
+            "--strict-kconfig" => build.strict_kconfig = true,
+            other => return Err(unknown_flag("build", other)),
+        }
+    }
+
+    if !build.matrix.is_empty() && (build.resume.is_some() || build.from_manifest.is_some()) {
+        return Err(AppError::InvalidInput(
+            "--matrix cannot be combined with --resume or --from-manifest".to_string(),
+        ));
+    }
+
+    Ok(build)
+}
+
+fn parse_pgo_method(raw: &str) -> std::result::Result<PgoMethod, AppError> {
+    match raw.to_lowercase().as_str() {
+        "autofdo" => Ok(PgoMethod::AutoFdo),
+        "instrumented" => Ok(PgoMethod::Instrumented),
+        other => Err(AppError::InvalidInput(format!(
+            "Unknown PGO method '{}' (expected autofdo or instrumented)",
+            other
+        ))),
+    }
+}
+
+fn parse_pgo_collect_args(args: &[String]) -> std::result::Result<PgoCollectArgs, AppError> {
+    let mut pgo = PgoCollectArgs::default();
+    let mut iter = args.iter();
+
+    while let Some(arg) = iter.next() {
+        match arg.as_str() {
+            "--workspace" => pgo.workspace = Some(PathBuf::from(flag_value(&mut iter, arg)?)),
+            "--version" => pgo.version = Some(flag_value(&mut iter, arg)?),
+            "--method" => pgo.method = Some(parse_pgo_method(&flag_value(&mut iter, arg)?)?),
+            "--command" => pgo.command = Some(flag_value(&mut iter, arg)?),
+            "--phase-secs" => pgo.phase_secs = flag_number(&mut iter, arg)?,
+            "--vmlinux" => pgo.vmlinux = Some(PathBuf::from(flag_value(&mut iter, arg)?)),
+            other => return Err(unknown_flag("pgo collect", other)),
+        }
+    }
+
+    if pgo.phase_secs == 0 {
+        return Err(AppError::InvalidInput(
+            "--phase-secs must be greater than zero".to_string(),
+        ));
+    }
+
+    Ok(pgo)
+}
+
+fn parse_mirror_sync_args(args: &[String]) -> std::result::Result<MirrorSyncArgs, AppError> {
+    let mut mirror = MirrorSyncArgs::default();
+    let mut iter = args.iter();
+
+    while let Some(arg) = iter.next() {
+        match arg.as_str() {
+            "--dir" => mirror.dir = Some(PathBuf::from(flag_value(&mut iter, arg)?)),
+            "--variant" => mirror.variants.push(flag_value(&mut iter, arg)?),
+            other => return Err(unknown_flag("mirror sync", other)),
+        }
+    }
+
+    Ok(mirror)
+}
+
+fn parse_bench_args(args: &[String]) -> std::result::Result<BenchArgs, AppError> {
+    let mut bench = BenchArgs::default();
+    let mut iter = args.iter();
+
+    while let Some(arg) = iter.next() {
+        match arg.as_str() {
+            "--duration" => bench.duration_secs = flag_number(&mut iter, arg)?,
+            "--core" => bench.core_id = flag_number(&mut iter, arg)?,
+            "--interval-us" => bench.interval_us = flag_number(&mut iter, arg)?,
+            "--threshold-us" => bench.threshold_us = flag_number(&mut iter, arg)?,
+            other => return Err(unknown_flag("bench", other)),
+        }
+    }
+
+    if bench.duration_secs == 0 || bench.interval_us == 0 {
+        return Err(AppError::InvalidInput(
+            "--duration and --interval-us must be greater than zero".to_string(),
+        ));
+    }
+
+    Ok(bench)
+}
+
+fn flag_value<'a>(
+    iter: &mut impl Iterator<Item = &'a String>,
+    flag: &str,
+) -> std::result::Result<String, AppError> {
+    iter.next()
+        .cloned()
+        .ok_or_else(|| AppError::InvalidInput(format!("{} requires a value", flag)))
+}
+
+fn flag_number<'a, T: std::str::FromStr>(
+    iter: &mut impl Iterator<Item = &'a String>,
+    flag: &str,
+) -> std::result::Result<T, AppError> {
+    let raw = flag_value(iter, flag)?;
+    raw.parse::<T>()
+        .map_err(|_| AppError::InvalidInput(format!("{} expects a number, got '{}'", flag, raw)))
+}
+
+fn unknown_flag(command: &str, flag: &str) -> AppError {
+    AppError::InvalidInput(format!("Unknown option '{}' for '{}'", flag, command))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::cli::resolve_build_config;
+
+    fn args(list: &[&str]) -> Vec<String> {
+        list.iter().map(|s| s.to_string()).collect()
+    }
+
+    #[test]
+    fn test_parse_no_args_is_help() {
+        assert_eq!(parse_args(&[]).unwrap(), Command::Help);
+    }
+
+    #[test]
+    fn test_parse_build_flags() {
+        let cmd = parse_args(&args(&[
+            "build",
+            "--variant",
+            "linux-zen",
+            "--profile",
+            "Gaming",
+            "--lto",
+            "full",
+            "--hardening",
+            "hardened",
+            "--option",
+            "CONFIG_HZ_1000=y",
+            "--no-modprobed",
+            "--polly",
+            "--mok-key",
+            "/etc/mok/MOK.key",
+            "--compiler-cache",
+            "sccache",
+            "--cmdline-add",
+            "threadirqs",
+            "--cmdline-remove",
+            "nowatchdog",
+            "--cmdline-boot-entry",
+        ]))
+        .unwrap();
+
+        let Command::Build(build) = cmd else {
+            panic!("expected build command");
+        };
+        assert_eq!(build.variant.as_deref(), Some("linux-zen"));
+        assert_eq!(build.profile.as_deref(), Some("Gaming"));
+        assert_eq!(build.lto, Some(LtoType::Full));
+        assert_eq!(build.hardening, Some(HardeningLevel::Hardened));
+        assert_eq!(
+            build.options,
+            vec![("CONFIG_HZ_1000".to_string(), "y".to_string())]
+        );
+        assert_eq!(build.modprobed, Some(false));
+        assert_eq!(build.polly, Some(true));
+        assert_eq!(build.whitelist, None);
+        assert_eq!(build.mok_key, Some(PathBuf::from("/etc/mok/MOK.key")));
+        assert_eq!(build.mok_cert, None);
+        assert_eq!(build.compiler_cache, Some(CompilerCache::Sccache));
+        assert_eq!(build.cache_dir, None);
+        assert_eq!(build.cmdline_add, vec!["threadirqs".to_string()]);
+        assert_eq!(build.cmdline_remove, vec!["nowatchdog".to_string()]);
+        assert_eq!(build.cmdline_boot_entry, Some(true));
+    }
+
+    #[test]
+    fn test_parse_build_resource_limits() {
+        let cmd = parse_args(&args(&[
+            "build",
+            "--nice",
+            "10",
+            "--ionice-idle",
+            "--cpu-quota",
+            "800",
+            "--memory-max",
+            "16384",
+            "--thermal-limit",
+            "85",
+        ]))
+        .unwrap();
+
+        let Command::Build(build) = cmd else {
+            panic!("expected build command");
+        };
+        let limits = resolve_build_config(&build).unwrap().resources;
+        assert_eq!(limits.nice, Some(10));
+        assert!(limits.ionice_idle);
+        assert!(!limits.cgroup);
+        assert_eq!(limits.cpu_quota_percent, Some(800));
+        assert_eq!(limits.memory_max_mb, Some(16384));
+        assert_eq!(limits.thermal_limit_c, Some(85.0));
+
+        assert!(parse_args(&args(&["build", "--cpu-quota", "half"])).is_err());
+        assert!(parse_args(&args(&["build", "--nice"])).is_err());
+    }
+
+    #[test]
+    fn test_parse_build_matrix() {
+        let cmd = parse_args(&args(&[
+            "build",
+            "--matrix",
+            "linux:Gaming",
+            "--matrix",
+            "linux-zen:Server:full",
+            "--jobs",
+            "24",
+            "--parallel",
+            "2",
+            "--strict-kconfig",
+        ]))
+        .unwrap();
+
+        let Command::Build(build) = cmd else {
+            panic!("expected build command");
+        };
+        assert_eq!(build.matrix, vec!["linux:Gaming", "linux-zen:Server:full"]);
+        assert_eq!(build.jobs, Some(24));
+        assert_eq!(build.parallel, Some(2));
+        assert_eq!(resolve_build_config(&build).unwrap().build_jobs, Some(24));
+        assert!(resolve_build_config(&build).unwrap().strict_kconfig);
+
+        assert!(parse_args(&args(&["build", "--matrix", "linux"])).is_err());
+        assert!(parse_args(&args(&["build", "--matrix", "linux:Gaming:fat"])).is_err());
+        assert!(parse_args(&args(&["build", "--jobs", "many"])).is_err());
+        assert!(parse_args(&args(&[
+            "build",
+            "--matrix",
+            "linux:Gaming",
+            "--resume",
+            "checkpoint.json",
+        ]))
+        .is_err());
+    }
+
+    #[test]
+    fn test_parse_rejects_bad_input() {
+        assert!(parse_args(&args(&["frobnicate"])).is_err());
+        assert!(parse_args(&args(&["build", "--lto"])).is_err());
+        assert!(parse_args(&args(&["build", "--lto", "fat"])).is_err());
+        assert!(parse_args(&args(&["build", "--option", "NOVALUE"])).is_err());
+        assert!(parse_args(&args(&["bench", "--duration", "abc"])).is_err());
+        assert!(parse_args(&args(&["install"])).is_err());
+        assert!(parse_args(&args(&["config-diff", "running"])).is_err());
+        assert!(parse_args(&args(&["config-diff", "a", "b", "--yaml"])).is_err());
+    }
+
+    #[test]
+    fn test_parse_install_flags() {
+        assert_eq!(
+            parse_args(&args(&[
+                "install",
+                "--next-boot",
+                "linux-goatd.pkg.tar.zst"
+            ]))
+            .unwrap(),
+            Command::Install {
+                package: PathBuf::from("linux-goatd.pkg.tar.zst"),
+                boot_entry: true,
+                next_boot: true,
+            }
+        );
+        let Command::Install { boot_entry, .. } =
+            parse_args(&args(&["install", "a.pkg.tar.zst", "--no-boot-entry"])).unwrap()
+        else {
+            panic!("expected install command");
+        };
+        assert!(!boot_entry);
+        assert!(parse_args(&args(&["install", "a", "b"])).is_err());
+        assert_eq!(
+            parse_args(&args(&["boot-check"])).unwrap(),
+            Command::BootCheck
+        );
+        assert!(parse_args(&args(&["boot-check", "--force"])).is_err());
+    }
+
+    #[test]
+    fn test_parse_from_manifest() {
+        let Command::Build(build) = parse_args(&args(&[
+            "build",
+            "--from-manifest",
+            "linux-goatd-6.12.1-1-x86_64.pkg.tar.zst.manifest.json",
+        ]))
+        .unwrap() else {
+            panic!("expected build command");
+        };
+        assert_eq!(
+            build.from_manifest,
+            Some(PathBuf::from(
+                "linux-goatd-6.12.1-1-x86_64.pkg.tar.zst.manifest.json"
+            ))
+        );
+        assert!(parse_args(&args(&["build", "--from-manifest"])).is_err());
+    }
+
+    #[test]
+    fn test_parse_kconfig_check() {
+        let Command::KconfigCheck { source, build } = parse_args(&args(&[
+            "kconfig-check",
+            "/src/linux",
+            "--profile",
+            "Gaming",
+        ]))
+        .unwrap() else {
+            panic!("expected kconfig-check command");
+        };
+        assert_eq!(source, PathBuf::from("/src/linux"));
+        assert_eq!(build.profile.as_deref(), Some("Gaming"));
+        assert!(parse_args(&args(&["kconfig-check"])).is_err());
+        assert!(parse_args(&args(&["kconfig-check", "--profile", "Gaming"])).is_err());
+    }
+
+    #[test]
+    fn test_parse_pgo() {
+        let Command::Build(build) = parse_args(&args(&[
+            "build",
+            "--pgo",
+            "optimize",
+            "--pgo-method",
+            "instrumented",
+            "--pgo-profile",
+            "/tmp/vmlinux.profdata",
+        ]))
+        .unwrap() else {
+            panic!("expected build command");
+        };
+        assert_eq!(build.pgo_stage, Some(PgoStage::Optimize));
+        assert_eq!(build.pgo_method, Some(PgoMethod::Instrumented));
+        let config = resolve_build_config(&build).unwrap();
+        assert_eq!(config.pgo.stage, PgoStage::Optimize);
+        assert_eq!(
+            config.pgo.profile,
+            Some(PathBuf::from("/tmp/vmlinux.profdata"))
+        );
+
+        let Command::PgoCollect(collect) = parse_args(&args(&[
+            "pgo",
+            "collect",
+            "--method",
+            "autofdo",
+            "--command",
+            "make -j8",
+        ]))
+        .unwrap() else {
+            panic!("expected pgo collect command");
+        };
+        assert_eq!(collect.method, Some(PgoMethod::AutoFdo));
+        assert_eq!(collect.command.as_deref(), Some("make -j8"));
+        assert_eq!(collect.phase_secs, 30);
+
+        assert!(parse_args(&args(&["build", "--pgo", "train"])).is_err());
+        assert!(parse_args(&args(&["pgo"])).is_err());
+        assert!(parse_args(&args(&["pgo", "collect", "--phase-secs", "0"])).is_err());
+    }
+
+    #[test]
+    fn test_parse_mirror_sync() {
+        assert_eq!(
+            parse_args(&args(&[
+                "mirror",
+                "sync",
+                "--dir",
+                "/srv/mirror",
+                "--variant",
+                "linux",
+                "--variant",
+                "linux-lts",
+            ]))
+            .unwrap(),
+            Command::MirrorSync(MirrorSyncArgs {
+                dir: Some(PathBuf::from("/srv/mirror")),
+                variants: vec!["linux".to_string(), "linux-lts".to_string()],
+            })
+        );
+        assert_eq!(
+            parse_args(&args(&["mirror", "sync"])).unwrap(),
+            Command::MirrorSync(MirrorSyncArgs::default())
+        );
+        assert!(parse_args(&args(&["mirror"])).is_err());
+        assert!(parse_args(&args(&["mirror", "sync", "--variant"])).is_err());
+    }
+
+    #[test]
+    fn test_parse_config_diff() {
+        assert_eq!(
+            parse_args(&args(&[
+                "config-diff",
+                "running",
+                "build/.config",
+                "--json"
+            ]))
+            .unwrap(),
+            Command::ConfigDiff {
+                old: "running".to_string(),
+                new: "build/.config".to_string(),
+                json: true,
+            }
+        );
+    }
+}
@@ -0,0 +1,141 @@
+//! `goatd audit` and `goatd bench`.
+
+use std::error::Error;
+use std::sync::atomic::Ordering;
+use std::time::{Duration, Instant};
+
+use super::BenchArgs;
+use crate::error::AppError;
+use crate::kernel::audit::SystemAudit;
+use crate::system::performance::collector::LatencyProcessor;
+use crate::system::performance::{diagnostic_buffer, forensics, LatencyCollector, MonitoringState};
+
+pub async fn run_audit(deep: bool) -> std::result::Result<(), Box<dyn Error>> {
+    let summary = SystemAudit::get_summary().map_err(AppError::Audit)?;
+    println!("Kernel:     {}", summary.kernel_version);
+    println!(
+        "GOATd:      {}",
+        if summary.is_goatd { "yes" } else { "no" }
+    );
+
+    if deep {
+        let audit = SystemAudit::run_deep_audit_async()
+            .await
+            .map_err(AppError::Audit)?;
+        println!("Compiler:   {}", audit.compiler);
+        println!("LTO:        {}", audit.lto_status);
+        println!(
+            "Modules:    {} ({} MB)",
+            audit.module_count, audit.module_size_mb
+        );
+        println!("CPU:        {}", audit.cpu_context);
+        println!("Hardening:  {}", audit.hardening_status);
+        println!("Timer:      {}", audit.timer_frequency);
+        println!("Preemption: {}", audit.preemption_model);
+        println!("I/O sched:  {}", audit.io_scheduler);
+        println!("CPU sched:  {}", audit.cpu_scheduler);
+        println!("MGLRU:      {}", audit.mglru);
+    }
+
+    Ok(())
+}
+
+pub async fn run_bench(args: BenchArgs) -> std::result::Result<(), Box<dyn Error>> {
+    let _ = diagnostic_buffer::init_global_buffer(4096);
+
+    let capacity =
+        ((args.duration_secs * 1_000_000 / args.interval_us) as usize).clamp(1024, 1 << 22);
+    let (producer, mut consumer) = rtrb::RingBuffer::new(capacity);
+    let (event_producer, event_consumer) = rtrb::RingBuffer::new(1000);
+    let state = MonitoringState::default();
+
+    let _event_handle = diagnostic_buffer::spawn_collector_event_consumer(
+        event_consumer,
+        state.smi_correlated_spikes.clone(),
+    );
+    let forensics_thread = forensics::spawn_spike_forensics(
+        args.core_id,
+        state.stop_flag.clone(),
+        state.spike_count.clone(),
+        state.spike_forensics.clone(),
+    );
+
+    println!(
+        "[bench] Collecting for {}s on core {} (interval {}µs, spike threshold {}µs)",
+        args.duration_secs, args.core_id, args.interval_us, args.threshold_us
+    );
+
+    let collector_state = state.clone();
+    let core_id = args.core_id;
+    let interval = Duration::from_micros(args.interval_us);
+    let threshold_ns = args.threshold_us * 1000;
+    let collector_thread = std::thread::spawn(move || {
+        let mut cpu_set = nix::sched::CpuSet::new();
+        if cpu_set.set(core_id).is_ok() {
+            if let Err(e) = nix::sched::sched_setaffinity(nix::unistd::Pid::from_raw(0), &cpu_set) {
+                eprintln!("goatd: warning: failed to pin to core {}: {}", core_id, e);
+            }
+        }
+
+        LatencyCollector::new(
+            interval,
+            producer,
+            event_producer,
+            collector_state.stop_flag.clone(),
+            collector_state.dropped_samples.clone(),
+            threshold_ns,
+            collector_state.spike_count.clone(),
+            collector_state.smi_correlated_spikes.clone(),
+            collector_state.total_smi_count.clone(),
+            None,
+        )
+        .run();
+    });
+
+    let mut processor = LatencyProcessor::new()?;
+    let start = Instant::now();
+    let duration = Duration::from_secs(args.duration_secs);
+    while start.elapsed() < duration {
+        while let Ok(sample_ns) = consumer.pop() {
+            processor.record_sample(sample_ns)?;
+        }
+        tokio::time::sleep(Duration::from_millis(10)).await;
+    }
+
+    state.request_stop();
+    let _ = tokio::task::spawn_blocking(move || collector_thread.join()).await;
+    let _ = tokio::task::spawn_blocking(move || forensics_thread.join()).await;
+    while let Ok(sample_ns) = consumer.pop() {
+        processor.record_sample(sample_ns)?;
+    }
+
+    println!("Samples:    {}", processor.sample_count());
+    println!("Average:    {:.2} µs", processor.average());
+    println!("P99:        {:.2} µs", processor.p99());
+    println!("P99.9:      {:.2} µs", processor.p99_9());
+    println!("Max:        {:.2} µs", processor.max());
+    println!(
+        "Spikes:     {} (SMI-correlated: {})",
+        state.spike_count.load(Ordering::Relaxed),
+        state.smi_correlated_spikes.load(Ordering::Relaxed)
+    );
+    println!("Dropped:    {}", state.dropped_count());
+    for (rank, offender) in state.top_spike_offenders().iter().enumerate() {
+        let detail = if offender.detail.is_empty() {
+            String::new()
+        } else {
+            format!(" [{}]", offender.detail)
+        };
+        println!(
+            "Offender {}: {} {}{} ({} spikes attributed, seen in {})",
+            rank + 1,
+            offender.kind.label(),
+            offender.name,
+            detail,
+            offender.attributed_spikes,
+            offender.seen_in_spikes
+        );
+    }
+
+    Ok(())
+}
@@ -0,0 +1,457 @@
+//! `goatd build` and `goatd kconfig-check`.
+
+use std::error::Error;
+use std::path::Path;
+use std::sync::Arc;
+
+use tokio::sync::mpsc;
+
+use super::{format_build_event, resolve_workspace, start_log_collector, BuildArgs};
+use crate::error::{AppError, BuildError, ConfigError};
+use crate::models::KernelConfig;
+use crate::orchestrator::{
+    AsyncOrchestrator, BuildManifest, BuildMatrix, BuildPhaseState, MatrixEntry,
+};
+use crate::ui::controller::BuildEvent;
+use crate::LogCollector;
+
+/// Build the KernelConfig for a CLI build: file (if any), then flag overrides.
+pub fn resolve_build_config(args: &BuildArgs) -> std::result::Result<KernelConfig, ConfigError> {
+    let mut config = match &args.config_file {
+        Some(path) if path.extension().is_some_and(|ext| ext == "json") => {
+            crate::config::loader::load_config_from_file(path)?
+        }
+        Some(path) => crate::config::loader::load_config_from_toml(path)?,
+        None => KernelConfig::default(),
+    };
+
+    if let Some(variant) = &args.variant {
+        config.kernel_variant = variant.clone();
+    }
+    if config.kernel_variant.is_empty() {
+        config.kernel_variant = "linux".to_string();
+    }
+    if let Some(version) = &args.version {
+        config.version = version.clone();
+    }
+    if let Some(profile) = &args.profile {
+        config.profile = profile.clone();
+    }
+    if let Some(lto) = args.lto {
+        config.lto_type = lto;
+        config.user_toggled_lto = true;
+    }
+    if let Some(hardening) = args.hardening {
+        config.hardening = hardening;
+        config.user_toggled_hardening = true;
+    }
+    if let Some(modprobed) = args.modprobed {
+        config.use_modprobed = modprobed;
+    }
+    if let Some(whitelist) = args.whitelist {
+        config.use_whitelist = whitelist;
+    }
+    if let Some(polly) = args.polly {
+        config.use_polly = polly;
+        config.user_toggled_polly = true;
+    }
+    if let Some(mglru) = args.mglru {
+        config.use_mglru = mglru;
+        config.user_toggled_mglru = true;
+    }
+    if let Some(native) = args.native {
+        config.native_optimizations = native;
+        config.user_toggled_native_optimizations = true;
+    }
+    if let Some(secure_boot) = args.secure_boot {
+        config.secure_boot = secure_boot;
+    }
+    if let Some(key) = &args.mok_key {
+        config.mok_key_path = Some(key.clone());
+    }
+    if let Some(cert) = &args.mok_cert {
+        config.mok_cert_path = Some(cert.clone());
+    }
+    if let Some(backend) = args.compiler_cache {
+        config.compiler_cache.backend = backend;
+    }
+    if let Some(dir) = &args.cache_dir {
+        config.compiler_cache.dir = Some(dir.clone());
+    }
+    config
+        .cmdline
+        .additions
+        .extend(args.cmdline_add.iter().cloned());
+    config
+        .cmdline
+        .removals
+        .extend(args.cmdline_remove.iter().cloned());
+    if let Some(boot_entry) = args.cmdline_boot_entry {
+        config.cmdline.boot_entry = boot_entry;
+    }
+    if let Some(stage) = args.pgo_stage {
+        config.pgo.stage = stage;
+    }
+    if let Some(method) = args.pgo_method {
+        config.pgo.method = method;
+    }
+    if let Some(profile) = &args.pgo_profile {
+        config.pgo.profile = Some(profile.clone());
+    }
+    if let Some(jobs) = args.jobs {
+        config.build_jobs = Some(jobs);
+    }
+    if let Some(nice) = args.nice {
+        config.resources.nice = Some(nice);
+    }
+    if args.ionice_idle {
+        config.resources.ionice_idle = true;
+    }
+    if args.cgroup {
+        config.resources.cgroup = true;
+    }
+    if let Some(quota) = args.cpu_quota {
+        config.resources.cpu_quota_percent = Some(quota);
+    }
+    if let Some(mb) = args.memory_max {
+        config.resources.memory_max_mb = Some(mb);
+    }
+    if let Some(limit) = args.thermal_limit {
+        config.resources.thermal_limit_c = Some(limit);
+    }
+    if args.strict_kconfig {
+        config.strict_kconfig = true;
+    }
+    for (key, value) in &args.options {
+        config.config_options.insert(key.clone(), value.clone());
+    }
+
+    Ok(config)
+}
+
+pub async fn run_build(args: BuildArgs) -> std::result::Result<(), Box<dyn Error>> {
+    let log_collector = start_log_collector()?;
+    let session_name = format!(
+        "build_{}.log",
+        chrono::Local::now().format("%Y%m%d_%H%M%S%.3f")
+    );
+    match log_collector.start_new_session(&session_name).await {
+        Ok(path) => println!("[status] Full build log: {}", path.display()),
+        Err(e) => eprintln!("goatd: warning: failed to start log session: {}", e),
+    }
+
+    let (build_tx, mut build_rx) = mpsc::channel::<BuildEvent>(65536);
+    let (cancel_tx, cancel_rx) = tokio::sync::watch::channel(false);
+
+    let printer = tokio::spawn(async move {
+        while let Some(event) = build_rx.recv().await {
+            if let Some(line) = format_build_event(&event) {
+                println!("{}", line);
+            }
+        }
+    });
+
+    tokio::spawn(async move {
+        if tokio::signal::ctrl_c().await.is_ok() {
+            eprintln!("goatd: interrupt received, cancelling build");
+            let _ = cancel_tx.send(true);
+        }
+    });
+
+    if !args.matrix.is_empty() {
+        let result = run_matrix(&args, build_tx.clone(), cancel_rx, log_collector.clone()).await;
+        let _ = build_tx.send(BuildEvent::Finished(result.is_ok())).await;
+        drop(build_tx);
+        let _ = printer.await;
+        if let Err(e) = log_collector.wait_for_empty().await {
+            eprintln!("goatd: warning: failed to flush build log: {}", e);
+        }
+        return result;
+    }
+
+    let orch = match &args.resume {
+        Some(checkpoint_path) => {
+            println!("[status] Resuming from {}", checkpoint_path.display());
+            AsyncOrchestrator::resume(
+                checkpoint_path,
+                Some(build_tx.clone()),
+                cancel_rx,
+                Some(log_collector.clone()),
+                None,
+            )
+            .await
+            .map_err(|e| BuildError::PreparationFailed(e.to_string()))?
+        }
+        None => {
+            // A manifest rebuild takes every build input from the manifest
+            let (config, reference) = match &args.from_manifest {
+                Some(path) => {
+                    let manifest = BuildManifest::load(path)?;
+                    println!(
+                        "[status] Rebuilding from manifest {} (commit {})",
+                        path.display(),
+                        manifest.source_commit.as_deref().unwrap_or("unknown")
+                    );
+                    (manifest.rebuild_config(), Some(manifest))
+                }
+                None => (resolve_build_config(&args)?, None),
+            };
+            let workspace = resolve_workspace(args.workspace.as_deref())?;
+            crate::kernel::validator::validate_kbuild_path(&workspace)?;
+
+            let hardware = crate::hardware::HardwareDetector::new()
+                .detect_all()
+                .map_err(|e| AppError::HardwareDetection(e.to_string()))?;
+
+            println!(
+                "[status] Building {} ({}) profile={} lto={:?} in {}",
+                config.kernel_variant,
+                config.version,
+                config.profile,
+                config.lto_type,
+                workspace.display()
+            );
+
+            let kernel_path = workspace.join(&config.kernel_variant);
+            let checkpoint_dir = workspace.join(".checkpoints");
+            let mut orch = AsyncOrchestrator::new(
+                hardware,
+                config,
+                checkpoint_dir,
+                kernel_path,
+                Some(build_tx.clone()),
+                cancel_rx,
+                Some(log_collector.clone()),
+                None,
+                None,
+            )
+            .await
+            .map_err(|e| BuildError::PreparationFailed(e.to_string()))?;
+            if let Some(manifest) = reference {
+                orch.set_reference_manifest(manifest);
+            }
+            orch
+        }
+    };
+
+    let mut result = run_phases(&orch).await;
+    if result.is_ok() {
+        if let Some(drift) = orch.manifest_drift().await.filter(|d| d.is_drifted()) {
+            result = Err(BuildError::ValidationFailed(format!(
+                "Rebuild drifted from manifest: {}",
+                drift.report().join("; ")
+            )));
+        }
+    }
+
+    let _ = build_tx.send(BuildEvent::Finished(result.is_ok())).await;
+    drop(build_tx);
+    drop(orch);
+    let _ = printer.await;
+
+    if let Err(e) = log_collector.wait_for_empty().await {
+        eprintln!("goatd: warning: failed to flush build log: {}", e);
+    }
+
+    result.map_err(|e| Box::new(e) as Box<dyn Error>)
+}
+
+/// Run every `--matrix` entry from shared source checkouts and print the combined summary.
+pub async fn run_matrix(
+    args: &BuildArgs,
+    build_tx: mpsc::Sender<BuildEvent>,
+    cancel_rx: tokio::sync::watch::Receiver<bool>,
+    log_collector: Arc<LogCollector>,
+) -> std::result::Result<(), Box<dyn Error>> {
+    let base = resolve_build_config(args)?;
+    let entries = args
+        .matrix
+        .iter()
+        .map(|spec| MatrixEntry::parse(spec, base.lto_type))
+        .collect::<std::result::Result<Vec<_>, _>>()
+        .map_err(AppError::InvalidInput)?;
+    let workspace = resolve_workspace(args.workspace.as_deref())?;
+    crate::kernel::validator::validate_kbuild_path(&workspace)?;
+
+    let hardware = crate::hardware::HardwareDetector::new()
+        .detect_all()
+        .map_err(|e| AppError::HardwareDetection(e.to_string()))?;
+
+    let mut matrix = BuildMatrix::new(base, entries, workspace);
+    if let Some(jobs) = args.jobs {
+        matrix.job_budget = jobs;
+    }
+    if let Some(parallel) = args.parallel {
+        matrix.max_parallel = parallel;
+    }
+    println!(
+        "[status] Build matrix of {} builds in {} ({} at a time, {} make jobs each)",
+        matrix.entries.len(),
+        matrix.workspace.display(),
+        matrix.max_parallel.min(matrix.entries.len()),
+        matrix.jobs_per_build()
+    );
+
+    let summary = matrix
+        .run(hardware, Some(build_tx), cancel_rx, Some(log_collector))
+        .await
+        .map_err(|e| BuildError::PreparationFailed(e.to_string()))?;
+    for line in summary.report() {
+        println!("{}", line);
+    }
+    println!(
+        "[status] Matrix summary: {}",
+        matrix
+            .workspace
+            .join(crate::orchestrator::matrix::SUMMARY_FILE)
+            .display()
+    );
+
+    if summary.all_succeeded() {
+        Ok(())
+    } else {
+        let failed = summary.outcomes.iter().filter(|o| !o.success).count();
+        Err(Box::new(BuildError::BuildFailed(format!(
+            "{} of {} matrix builds failed",
+            failed,
+            summary.outcomes.len()
+        ))))
+    }
+}
+
+/// Run the remaining orchestrator phases, tagging each failure with the phase it came from.
+///
+/// Starts at the orchestrator's current phase so resumed builds skip completed work.
+pub async fn run_phases(orch: &AsyncOrchestrator) -> std::result::Result<(), BuildError> {
+    if orch.current_phase().await == BuildPhaseState::Preparation {
+        orch.prepare()
+            .await
+            .map_err(|e| phase_error(e, BuildError::PreparationFailed))?;
+    }
+    if orch.current_phase().await == BuildPhaseState::Configuration {
+        orch.configure()
+            .await
+            .map_err(|e| phase_error(e, BuildError::ConfigurationFailed))?;
+    }
+    if orch.current_phase().await == BuildPhaseState::Patching {
+        orch.patch()
+            .await
+            .map_err(|e| phase_error(e, BuildError::PatchingFailed))?;
+    }
+    if orch.current_phase().await == BuildPhaseState::Building {
+        orch.build()
+            .await
+            .map_err(|e| phase_error(e, BuildError::BuildFailed))?;
+    }
+    if orch.current_phase().await == BuildPhaseState::Validation {
+        orch.validate()
+            .await
+            .map_err(|e| phase_error(e, BuildError::ValidationFailed))?;
+    }
+    Ok(())
+}
+
+fn phase_error(err: Box<dyn Error>, wrap: fn(String) -> BuildError) -> BuildError {
+    match err.downcast::<BuildError>() {
+        Ok(build_err) => *build_err,
+        Err(other) => wrap(other.to_string()),
+    }
+}
+
+/// `goatd kconfig-check`: validate the resolved build options against a Kconfig tree.
+pub fn run_kconfig_check(
+    source: &Path,
+    args: &BuildArgs,
+) -> std::result::Result<(), Box<dyn Error>> {
+    use crate::kernel::kconfig_tree::check_kernel_config;
+
+    let hardware = crate::hardware::HardwareDetector::new()
+        .detect_all()
+        .map_err(|e| AppError::HardwareDetection(e.to_string()))?;
+    let config =
+        crate::config::finalizer::finalize_kernel_config(resolve_build_config(args)?, &hardware)
+            .map_err(|e| BuildError::ConfigurationFailed(e.to_string()))?;
+
+    let report = check_kernel_config(source, &config)?.ok_or_else(|| {
+        AppError::InvalidInput(format!(
+            "No kernel source with a Kconfig in {}",
+            source.display()
+        ))
+    })?;
+    for line in report.lines() {
+        println!("{}", line);
+    }
+    if report.is_clean() {
+        println!(
+            "All {} requested options resolve in the Kconfig tree",
+            report.checked
+        );
+        Ok(())
+    } else {
+        Err(Box::new(BuildError::ConfigurationFailed(format!(
+            "{} of {} requested options will not apply as set",
+            report.issues.len(),
+            report.checked
+        ))))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::LtoType;
+
+    #[test]
+    fn test_flags_override_defaults() {
+        let build = BuildArgs {
+            profile: Some("Server".to_string()),
+            lto: Some(LtoType::None),
+            polly: Some(true),
+            ..Default::default()
+        };
+        let config = resolve_build_config(&build).unwrap();
+
+        assert_eq!(config.kernel_variant, "linux");
+        assert_eq!(config.profile, "Server");
+        assert_eq!(config.lto_type, LtoType::None);
+        assert!(config.user_toggled_lto);
+        assert!(config.use_polly && config.user_toggled_polly);
+    }
+
+    #[test]
+    fn test_flags_override_toml_file() {
+        let dir = tempfile::TempDir::new().unwrap();
+        let path = dir.path().join("kernel.toml");
+        std::fs::write(
+            &path,
+            "kernel_variant = \"linux-lts\"\nprofile = \"Workstation\"\nuse_modprobed = true\n",
+        )
+        .unwrap();
+
+        let build = BuildArgs {
+            config_file: Some(path),
+            profile: Some("Gaming".to_string()),
+            ..Default::default()
+        };
+        let config = resolve_build_config(&build).unwrap();
+
+        assert_eq!(config.kernel_variant, "linux-lts");
+        assert_eq!(config.profile, "Gaming");
+        assert!(config.use_modprobed);
+    }
+
+    #[test]
+    fn test_phase_error_preserves_build_error() {
+        let cancelled: Box<dyn Error> = Box::new(BuildError::BuildCancelled);
+        assert!(matches!(
+            phase_error(cancelled, BuildError::BuildFailed),
+            BuildError::BuildCancelled
+        ));
+
+        let plain: Box<dyn Error> = "make exited with 2".into();
+        assert!(matches!(
+            phase_error(plain, BuildError::BuildFailed),
+            BuildError::BuildFailed(msg) if msg == "make exited with 2"
+        ));
+    }
+}
@@ -0,0 +1,19 @@
+//! `goatd config-diff`.
+
+use std::error::Error;
+
+pub fn run_config_diff(
+    old: &str,
+    new: &str,
+    json: bool,
+) -> std::result::Result<(), Box<dyn Error>> {
+    use crate::kernel::config_diff::{diff_sources, ConfigSource};
+
+    let diff = diff_sources(&ConfigSource::from_arg(old), &ConfigSource::from_arg(new))?;
+    if json {
+        println!("{}", diff.to_json()?);
+    } else {
+        print!("{}", diff.to_text());
+    }
+    Ok(())
+}
@@ -0,0 +1,147 @@
+//! `goatd list`, `install`, `uninstall` and `boot-check`.
+
+use std::error::Error;
+use std::path::PathBuf;
+
+use super::resolve_workspace;
+use crate::error::AppError;
+use crate::ui::{KernelManagerTrait, SystemWrapper};
+
+pub fn run_list(workspace: Option<PathBuf>) -> std::result::Result<(), Box<dyn Error>> {
+    let manager = crate::kernel::manager::KernelManagerImpl::new().map_err(AppError::ModuleInit)?;
+
+    println!("Installed kernels:");
+    for pkg in manager.list_installed() {
+        let marker = if pkg.is_goatd { " [GOATd]" } else { "" };
+        println!("  {} {}{}", pkg.name, pkg.version, marker);
+    }
+
+    let workspace = resolve_workspace(workspace.as_deref())?;
+    println!("Built packages in {}:", workspace.display());
+    for pkg in manager.scan_workspace(&workspace.to_string_lossy()) {
+        match &pkg.path {
+            Some(path) => println!("  {} {} ({})", pkg.name, pkg.version, path.display()),
+            None => println!("  {} {}", pkg.name, pkg.version),
+        }
+    }
+
+    Ok(())
+}
+
+pub fn run_install(
+    package: PathBuf,
+    boot_entry: bool,
+    next_boot: bool,
+) -> std::result::Result<(), Box<dyn Error>> {
+    let system = crate::system::SystemImpl::new().map_err(AppError::ModuleInit)?;
+    let pkgbase = crate::system::bootloader::pkgbase_from_package(&package);
+    system
+        .install_package(package.clone())
+        .map_err(|reason| AppError::OsCommand {
+            cmd: "pacman -U".to_string(),
+            reason,
+        })?;
+    println!("[install] complete");
+
+    if boot_entry {
+        let pkgbase = pkgbase.ok_or_else(|| {
+            AppError::InvalidInput("Cannot derive the package base from the file name".to_string())
+        })?;
+        let options = crate::orchestrator::manifest::boot_entry_options_for_package(
+            &package,
+            &crate::system::bootloader::current_cmdline()?,
+        );
+        install_boot_entry(&system, &pkgbase, &options, next_boot)?;
+    }
+    Ok(())
+}
+
+/// Create (or update) and verify the boot entry for an installed kernel.
+fn install_boot_entry(
+    system: &crate::system::SystemImpl,
+    pkgbase: &str,
+    options: &str,
+    next_boot: bool,
+) -> std::result::Result<(), Box<dyn Error>> {
+    use crate::system::bootloader::{BootEntry, BootloaderManager, KernelImages};
+
+    let manager = BootloaderManager::detect()?;
+    let entry = BootEntry::new(
+        KernelImages::for_pkgbase(&manager.layout.boot, pkgbase),
+        options,
+    );
+    let staging = std::env::temp_dir().join("goatd-boot-entries");
+    let commands = manager.install_commands(&entry, &staging, next_boot)?;
+    system
+        .batch_privileged_commands(commands.iter().map(String::as_str).collect())
+        .map_err(|reason| AppError::OsCommand {
+            cmd: "install boot entry".to_string(),
+            reason,
+        })?;
+    println!(
+        "[boot] {} entry '{}' written{}",
+        manager.kind.as_str(),
+        entry.id,
+        if next_boot {
+            " (one-shot default for next boot)"
+        } else {
+            ""
+        }
+    );
+
+    match manager.verify_entry(&entry.id) {
+        Ok(verification) if verification.is_bootable() => {
+            println!("[boot] verified: kernel and initramfs present");
+            crate::system::boot_tracking::record_install(pkgbase, Some(entry.id.clone()))?;
+            println!(
+                "[boot] {} is on trial until `goatd boot-check` confirms it",
+                pkgbase
+            );
+            Ok(())
+        }
+        Ok(verification) => Err(Box::new(AppError::InvalidPath(format!(
+            "Boot entry '{}' is not bootable: {}",
+            entry.id,
+            verification.problems.join("; ")
+        )))),
+        // The ESP is often readable by root only
+        Err(e) => {
+            eprintln!("goatd: warning: could not verify boot entry: {}", e);
+            Ok(())
+        }
+    }
+}
+
+pub fn run_uninstall(package: &str) -> std::result::Result<(), Box<dyn Error>> {
+    let system = crate::system::SystemImpl::new().map_err(AppError::ModuleInit)?;
+    system
+        .uninstall_package(package)
+        .map_err(|reason| AppError::OsCommand {
+            cmd: "pacman -R".to_string(),
+            reason,
+        })?;
+    println!("[uninstall] complete");
+    Ok(())
+}
+
+pub fn run_boot_check() -> std::result::Result<(), Box<dyn Error>> {
+    use crate::system::boot_tracking::{check_current_boot, default_state_path, BootTracker};
+
+    let system = crate::system::SystemImpl::new().map_err(AppError::ModuleInit)?;
+    let path = &default_state_path()?;
+    match check_current_boot(path, &system)? {
+        None => println!("[boot] no kernels are tracked"),
+        Some(outcome) if outcome.already_checked => {
+            println!("[boot] this boot was already checked")
+        }
+        Some(outcome) => {
+            for msg in outcome.messages() {
+                println!("[boot] {}", msg);
+            }
+        }
+    }
+    for (pkgbase, kernel) in &BootTracker::load(path)?.kernels {
+        println!("  {:<32} {}", pkgbase, kernel.status.as_str());
+    }
+    Ok(())
+}
@@ -0,0 +1,61 @@
+//! `goatd mirror sync`.
+
+use std::error::Error;
+
+use super::MirrorSyncArgs;
+use crate::error::AppError;
+
+pub async fn run_mirror_sync(args: MirrorSyncArgs) -> std::result::Result<(), Box<dyn Error>> {
+    use crate::kernel::mirror::SourceMirror;
+    use crate::kernel::sources::KernelSourceDB;
+
+    let state = crate::config::SettingsManager::load().unwrap_or_default();
+    let mirror = match &args.dir {
+        Some(dir) => SourceMirror::new(dir),
+        None => SourceMirror::from_settings(&state),
+    };
+    let source_db = KernelSourceDB::with_custom_sources(&state.custom_sources);
+    let variants: Vec<String> = if args.variants.is_empty() {
+        source_db
+            .available_variants()
+            .into_iter()
+            .map(str::to_string)
+            .collect()
+    } else {
+        args.variants.clone()
+    };
+    if let Some(unknown) = variants
+        .iter()
+        .find(|variant| source_db.get_source(variant).is_none())
+    {
+        return Err(AppError::InvalidInput(format!("Unknown kernel variant '{}'", unknown)).into());
+    }
+
+    println!(
+        "[mirror] Syncing {} source(s) into {}",
+        variants.len(),
+        mirror.root().display()
+    );
+    let mut failed = Vec::new();
+    for variant in &variants {
+        let Some(source) = source_db.get_source(variant) else {
+            continue;
+        };
+        println!("[mirror] {} <- {}", variant, source.git_url);
+        match mirror.sync(variant, source).await {
+            Ok(tags) => println!("[mirror] ✓ {} ({} tags)", variant, tags),
+            Err(e) => {
+                println!("[mirror] ✗ {}: {}", variant, e);
+                failed.push(variant.as_str());
+            }
+        }
+    }
+
+    if !failed.is_empty() {
+        return Err(format!("Mirror sync failed for: {}", failed.join(", ")).into());
+    }
+    if !state.offline_sources {
+        println!("[mirror] Enable offline sources in the settings to build from this mirror");
+    }
+    Ok(())
+}
@@ -0,0 +1,353 @@
+//! Headless command-line front-end for GOATd Kernel.
+//!
+//! Drives the same `AsyncOrchestrator`, audit, benchmark and package-management
+//! paths as the egui UI so kernels can be built over SSH, in CI, or from scripts.
+//! Build events are streamed to stdout as plain text and every failure maps to a
+//! stable process exit code (see [`exit_code_for_error`]).
+//!
+//! Arguments are parsed in `args`; each subcommand runs from its own module.
+
+mod args;
+mod audit;
+mod build;
+mod config_diff;
+mod kernels;
+mod mirror;
+mod pgo;
+
+use std::error::Error;
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+
+use tokio::sync::mpsc;
+
+use crate::error::{AppError, BuildError, ConfigError};
+use crate::log_collector::{ensure_logs_dir_exists, get_global_logs_path};
+use crate::ui::controller::BuildEvent;
+use crate::{LogCollector, LogLine};
+
+pub use args::{parse_args, BenchArgs, BuildArgs, Command, MirrorSyncArgs, PgoCollectArgs};
+pub use build::resolve_build_config;
+
+/// Exit code for a successful run.
+pub const EXIT_OK: i32 = 0;
+/// Exit code for errors that do not map to a more specific category.
+pub const EXIT_FAILURE: i32 = 1;
+/// Exit code for invalid command-line usage.
+pub const EXIT_USAGE: i32 = 2;
+/// Exit code for configuration file errors.
+pub const EXIT_CONFIG: i32 = 3;
+
+/// Usage text printed by `goatd help` and on argument errors.
+pub const USAGE: &str = "\
+goatd - headless GOATd Kernel builder
+
+USAGE:
+    goatd <COMMAND> [OPTIONS]
+
+COMMANDS:
+    build       Build a kernel through the full orchestration pipeline
+    audit       Print the booted kernel audit (add --deep for the full audit)
+    bench       Run a latency benchmark on one core
+    list        List installed kernels and built packages in the workspace
+    install     Install a built kernel package (.pkg.tar.zst) and its boot entry
+    uninstall   Uninstall a kernel package by name
+    config-diff Diff the Kconfig of two kernels (see CONFIG-DIFF)
+    boot-check  Confirm or fail kernels on trial after a reboot (see BOOT-CHECK)
+    kconfig-check Check build options against a source's Kconfig (see KCONFIG-CHECK)
+    pgo         Collect PGO/AutoFDO profiles on a stage-1 kernel (see PGO)
+    mirror      Populate the local source mirror for offline builds (see MIRROR)
+    help        Print this message
+
+BUILD OPTIONS:
+    --config <FILE>         Load a KernelConfig from a TOML (or .json) file
+    --resume <CHECKPOINT>   Resume an interrupted build from its checkpoint
+                            (<workspace>/.checkpoints/build_checkpoint.json)
+    --from-manifest <FILE>  Rebuild the exact inputs of a <pkg>.manifest.json and
+                            fail if the resulting .config or commit drifts
+    --workspace <DIR>       Workspace directory (default: saved setting or CWD)
+    --variant <NAME>        Kernel variant (linux, linux-lts, linux-zen, ...)
+    --version <VER>         Kernel version (default: latest)
+    --profile <NAME>        Build profile (Generic, Gaming, Workstation, ...) or a
+                            user profile from
+                            ~/.config/goatdkernel/profiles/<NAME>.toml
+    --lto <none|thin|full>  LTO mode
+    --hardening <LEVEL>     minimal, standard or hardened
+    --option <KEY=VALUE>    Extra kernel config option (repeatable)
+    --[no-]modprobed        Toggle modprobed-db module stripping
+    --[no-]whitelist        Toggle the module whitelist
+    --[no-]polly            Toggle Polly loop optimizations
+    --[no-]mglru            Toggle MGLRU
+    --[no-]native           Toggle -march=native
+    --[no-]secure-boot      Toggle Secure Boot preparation
+    --mok-key <FILE>        MOK private key for signing the image and modules
+    --mok-cert <FILE>       MOK certificate matching --mok-key
+    --compiler-cache <none|ccache|sccache>
+                            Wrap the compilers with a compiler cache
+    --cache-dir <DIR>       Compiler cache directory (default: ~/.cache/<backend>)
+    --cmdline-add <PARAM>   Kernel command line parameter to add (repeatable)
+    --cmdline-remove <KEY>  Profile default parameter to drop (repeatable)
+    --[no-]cmdline-boot-entry
+                            Also write the command line into the boot entry
+    --pgo <off|instrument|optimize>
+                            PGO stage: build the profiling kernel, or consume
+                            the merged profile collected with `goatd pgo collect`
+    --pgo-method <autofdo|instrumented>
+                            Sampled AutoFDO (default) or instrumented PGO
+    --pgo-profile <FILE>    Merged profile for --pgo optimize (default: the
+                            workspace profile of the kernel version)
+    --jobs <N>              make jobs (default: all CPUs; with --matrix, the
+                            total budget split across concurrent builds)
+    --matrix <VARIANT:PROFILE[:LTO]>
+                            Build matrix entry (repeatable): every entry builds
+                            in a git worktree of one shared source checkout
+    --parallel <N>          Matrix builds compiling at once (default: all)
+    --nice <N>              Run the build at this nice level
+    --ionice-idle           Run the build in the idle I/O scheduling class
+    --cgroup                Run the build in a transient cgroup v2 scope
+    --cpu-quota <PERCENT>   cgroup CPU quota (100 = one CPU; implies --cgroup)
+    --memory-max <MIB>      cgroup memory limit (implies --cgroup)
+    --thermal-limit <C>     Lower the CPU quota while the CPU package is hotter
+                            than this (implies --cgroup; fails without cgroup v2)
+    --strict-kconfig        Fail the build when the kernel's Kconfig would drop
+                            or override a requested option
+
+BENCH OPTIONS:
+    --duration <SECS>       Collection time in seconds (default: 10)
+    --core <ID>             Core to pin the collector to (default: 0)
+    --interval-us <US>      Sampling interval in microseconds (default: 1000)
+    --threshold-us <US>     Spike threshold in microseconds (default: 500)
+
+LIST OPTIONS:
+    --workspace <DIR>       Workspace to scan for built packages
+
+INSTALL OPTIONS:
+    --next-boot             Boot the new kernel once on the next reboot
+    --no-boot-entry         Skip creating the systemd-boot/GRUB/Limine entry
+    Installed kernels with a verified entry are on trial until `boot-check`
+    (or the GUI at startup) confirms they booted.
+
+CONFIG-DIFF:
+    goatd config-diff <OLD> <NEW> [--json]
+    Each side is a .config / config.gz file, a built .pkg.tar.zst, or
+    `running` for the booted kernel. --json prints the grouped diff as JSON.
+
+BOOT-CHECK:
+    goatd boot-check
+    Promotes a trial kernel that booted healthy to the default boot entry, or
+    marks it failed and restores the last known-good kernel as default.
+
+KCONFIG-CHECK:
+    goatd kconfig-check <SOURCE_DIR> [BUILD OPTIONS]
+    Resolves the build options like `build` and reports every option that
+    `make olddefconfig` would drop: unknown symbols, unmet `depends on`,
+    disabled options forced on by `select`, and promptless symbols.
+
+PGO:
+    goatd pgo collect [OPTIONS]
+    Run on the booted `--pgo instrument` kernel. Records a profile of the
+    workload into <workspace>/pgo/<version>/raw and merges every recorded
+    profile into the one consumed by `goatd build --pgo optimize`.
+    --workspace <DIR>       Workspace holding the profiles
+    --version <VER>         Kernel version the profile is for (default: uname -r)
+    --method <autofdo|instrumented>
+                            Default: instrumented if the kernel exposes
+                            /sys/kernel/debug/pgo, AutoFDO otherwise
+    --command <CMD>         Workload to profile (default: benchmark phases)
+    --phase-secs <SECS>     Seconds per benchmark phase (default: 30)
+    --vmlinux <FILE>        vmlinux with debug info for AutoFDO
+                            (default: /usr/lib/modules/<uname -r>/build/vmlinux)
+
+MIRROR:
+    goatd mirror sync [OPTIONS]
+    Mirror kernel source repositories (bare, all branches and tags), their
+    PKGBUILDs and the PKGBUILDs' source downloads into the mirror directory.
+    With offline sources enabled in the settings, builds clone from the mirror,
+    resolve `latest` from its tags and take makepkg's downloads from it.
+    --dir <DIR>             Mirror directory (default: saved setting or
+                            ~/.cache/goatd/mirror)
+    --variant <NAME>        Variant to mirror (repeatable; default: all)
+";
+
+/// Map an error to the process exit code reported by `goatd`.
+///
+/// | Code  | Meaning                                   |
+/// |-------|-------------------------------------------|
+/// | 1     | Unclassified failure                      |
+/// | 2     | Invalid usage (`AppError::InvalidInput`)  |
+/// | 3     | Configuration file error (`ConfigError`)  |
+/// | 10    | Preparation phase failed                  |
+/// | 11    | Configuration phase failed                |
+/// | 12    | Patching phase failed                     |
+/// | 13    | Build phase failed                        |
+/// | 14    | Validation phase failed                   |
+/// | 20-27 | Other `AppError` categories               |
+/// | 130   | Build cancelled (SIGINT)                  |
+pub fn exit_code_for_error(err: &(dyn Error + 'static)) -> i32 {
+    if let Some(build_err) = err.downcast_ref::<BuildError>() {
+        return match build_err {
+            BuildError::PreparationFailed(_) => 10,
+            BuildError::ConfigurationFailed(_) => 11,
+            BuildError::PatchingFailed(_) => 12,
+            BuildError::BuildFailed(_) | BuildError::Diagnosed { .. } => 13,
+            BuildError::ValidationFailed(_) => 14,
+            BuildError::BuildCancelled => 130,
+        };
+    }
+
+    if let Some(app_err) = err.downcast_ref::<AppError>() {
+        return match app_err {
+            AppError::InvalidInput(_) => EXIT_USAGE,
+            AppError::OsCommand { .. } => 20,
+            AppError::HardwareDetection(_) => 21,
+            AppError::KernelConfig(_) => 22,
+            AppError::Io(_) => 23,
+            AppError::Settings(_) => 24,
+            AppError::ModuleInit(_) => 25,
+            AppError::InvalidPath(_) => 26,
+            AppError::Audit(_) => 27,
+        };
+    }
+
+    if err.downcast_ref::<ConfigError>().is_some() {
+        return EXIT_CONFIG;
+    }
+
+    EXIT_FAILURE
+}
+
+/// Render a build event as a single line of terminal output.
+///
+/// Returns `None` for events that only make sense in the GUI (timer ticks).
+pub fn format_build_event(event: &BuildEvent) -> Option<String> {
+    match event {
+        BuildEvent::Progress(p) => Some(format!("[progress] {:.0}%", p * 100.0)),
+        BuildEvent::StatusUpdate(s) | BuildEvent::Status(s) => Some(format!("[status] {}", s)),
+        BuildEvent::Log(line) => Some(line.clone()),
+        BuildEvent::PhaseChanged(phase) => Some(format!("[phase] {}", phase)),
+        BuildEvent::Finished(ok) => Some(format!(
+            "[finished] {}",
+            if *ok { "success" } else { "failure" }
+        )),
+        BuildEvent::TimerUpdate(_) => None,
+        BuildEvent::Error(msg) => Some(format!("[error] {}", msg)),
+        BuildEvent::InstallationComplete(ok) => Some(format!(
+            "[install] {}",
+            if *ok { "complete" } else { "failed" }
+        )),
+        BuildEvent::KernelUninstalled => Some("[uninstall] complete".to_string()),
+        BuildEvent::LatestVersionUpdate(variant, version) => {
+            Some(format!("[version] {} -> {}", variant, version))
+        }
+        BuildEvent::JitterAuditComplete(summary) => Some(format!(
+            "[bench] {} max={:.2}µs p99.9={:.2}µs",
+            summary.mode_name, summary.final_metrics.max_us, summary.final_metrics.p99_9_us
+        )),
+        BuildEvent::ArtifactDeleted => Some("[workspace] artifact deleted".to_string()),
+        BuildEvent::VersionResolved(version) => Some(format!("[version] resolved {}", version)),
+        BuildEvent::WorkspaceChanged => None,
+        BuildEvent::QueueChanged(_) => None,
+        BuildEvent::QueuedBuildStarted(label) => Some(format!("[queue] starting {}", label)),
+        BuildEvent::FailureDiagnosed(diagnosis) => {
+            Some(format!("[diagnosis] {}", diagnosis.title()))
+        }
+    }
+}
+
+/// Run a parsed command and return the process exit code.
+pub async fn run(command: Command) -> i32 {
+    let result: std::result::Result<(), Box<dyn Error>> = match command {
+        Command::Help => {
+            println!("{}", USAGE);
+            Ok(())
+        }
+        Command::Build(args) => build::run_build(*args).await,
+        Command::Audit { deep } => audit::run_audit(deep).await,
+        Command::Bench(args) => audit::run_bench(args).await,
+        Command::List { workspace } => kernels::run_list(workspace),
+        Command::Install {
+            package,
+            boot_entry,
+            next_boot,
+        } => kernels::run_install(package, boot_entry, next_boot),
+        Command::BootCheck => kernels::run_boot_check(),
+        Command::Uninstall { package } => kernels::run_uninstall(&package),
+        Command::ConfigDiff { old, new, json } => config_diff::run_config_diff(&old, &new, json),
+        Command::KconfigCheck { source, build } => build::run_kconfig_check(&source, &build),
+        Command::PgoCollect(args) => pgo::run_pgo_collect(args).await,
+        Command::MirrorSync(args) => mirror::run_mirror_sync(args).await,
+    };
+
+    match result {
+        Ok(()) => EXIT_OK,
+        Err(e) => {
+            eprintln!("goatd: {}", e);
+            exit_code_for_error(e.as_ref())
+        }
+    }
+}
+
+/// Resolve the workspace: explicit flag, then the saved UI setting, then CWD.
+fn resolve_workspace(explicit: Option<&Path>) -> std::result::Result<PathBuf, AppError> {
+    let workspace = match explicit {
+        Some(path) => path.to_path_buf(),
+        None => {
+            let saved = crate::config::SettingsManager::load()
+                .map(|state| state.workspace_path)
+                .unwrap_or_default();
+            if saved.is_empty() {
+                std::env::current_dir()?
+            } else {
+                PathBuf::from(saved)
+            }
+        }
+    };
+
+    std::fs::create_dir_all(&workspace)?;
+    Ok(workspace.canonicalize()?)
+}
+
+/// Set up a LogCollector whose UI feed is printed to stdout.
+fn start_log_collector() -> std::result::Result<Arc<LogCollector>, AppError> {
+    let log_dir = get_global_logs_path().map_err(AppError::ModuleInit)?;
+    ensure_logs_dir_exists(&log_dir).map_err(AppError::ModuleInit)?;
+
+    let (log_tx, mut log_rx) = mpsc::channel::<LogLine>(1024);
+    let collector = LogCollector::new(log_dir, log_tx).map_err(AppError::ModuleInit)?;
+
+    tokio::spawn(async move {
+        while let Some(line) = log_rx.recv().await {
+            println!("{}", line.message);
+        }
+    });
+
+    Ok(Arc::new(collector))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_exit_codes() {
+        assert_eq!(
+            exit_code_for_error(&BuildError::PatchingFailed("x".into())),
+            12
+        );
+        assert_eq!(
+            exit_code_for_error(&BuildError::ValidationFailed("x".into())),
+            14
+        );
+        assert_eq!(exit_code_for_error(&BuildError::BuildCancelled), 130);
+        assert_eq!(
+            exit_code_for_error(&AppError::InvalidInput("x".into())),
+            EXIT_USAGE
+        );
+        assert_eq!(
+            exit_code_for_error(&ConfigError::InvalidToml("x".into())),
+            EXIT_CONFIG
+        );
+        let other: Box<dyn Error> = "plain".into();
+        assert_eq!(exit_code_for_error(other.as_ref()), EXIT_FAILURE);
+    }
+}
@@ -0,0 +1,60 @@
+//! `goatd pgo collect`.
+
+use std::error::Error;
+
+use super::{resolve_workspace, PgoCollectArgs};
+use crate::error::AppError;
+
+/// `goatd pgo collect`: record a profile on the stage-1 kernel and merge it.
+pub async fn run_pgo_collect(args: PgoCollectArgs) -> std::result::Result<(), Box<dyn Error>> {
+    use crate::kernel::pgo::{self, ProfileStore, Workload};
+
+    let release = std::process::Command::new("uname")
+        .arg("-r")
+        .output()
+        .map(|output| String::from_utf8_lossy(&output.stdout).trim().to_string())
+        .map_err(|e| AppError::OsCommand {
+            cmd: "uname -r".to_string(),
+            reason: e.to_string(),
+        })?;
+    let workspace = resolve_workspace(args.workspace.as_deref())?;
+    let version = args.version.clone().unwrap_or_else(|| release.clone());
+    let method = args.method.unwrap_or_else(pgo::detect_method);
+    let vmlinux = args
+        .vmlinux
+        .clone()
+        .unwrap_or_else(|| pgo::default_vmlinux(&release));
+    let workload = match &args.command {
+        Some(command) => Workload::Command(command.clone()),
+        None => Workload::Benchmark {
+            phase_secs: args.phase_secs,
+        },
+    };
+
+    let store = ProfileStore::new(&workspace, &version);
+    println!(
+        "[pgo] Collecting {} profile for {} into {}",
+        method.as_str(),
+        version,
+        store.dir().display()
+    );
+
+    let merged = tokio::task::spawn_blocking(move || {
+        pgo::collect(&store, method, &workload, &vmlinux)?;
+        let merged = store.merge(method)?;
+        Ok::<_, AppError>((merged, store.raw_profiles(method).len()))
+    })
+    .await
+    .map_err(|e| AppError::ModuleInit(format!("PGO collection task failed: {}", e)))??;
+
+    println!(
+        "[pgo] Merged {} raw profile(s) into {}",
+        merged.1,
+        merged.0.display()
+    );
+    println!(
+        "[pgo] Next: goatd build --pgo optimize --version {}",
+        version
+    );
+    Ok(())
+}
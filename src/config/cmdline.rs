@@ -0,0 +1,431 @@
+//! Kernel command line model.
+//!
+//! The command line baked into `CONFIG_CMDLINE` is resolved from three layers,
+//! lowest precedence first:
+//! 1. Parameters derived from first-class `KernelConfig` fields: `preempt=` from
+//!    the preemption model, `lru_gen.enabled=7` with MGLRU and `mitigations=off`
+//!    with Minimal hardening
+//! 2. Profile defaults ([`ProfileDefinition::cmdline`](super::profiles::ProfileDefinition),
+//!    `cmdline = [...]` in user profiles)
+//! 3. The user's [`KernelCmdlineConfig`]: additions replace lower-layer parameters
+//!    with the same key, removals drop a key (`preempt`) or an exact parameter
+//!    (`preempt=full`)
+//!
+//! Two different values for the same key within one layer (e.g. `preempt=full`
+//! and `preempt=none` in the user additions) are a conflict, as is adding and
+//! removing the same key. The finalizer stores the rendered result under
+//! [`CMDLINE_KEY`] in `config_options`, where the patcher picks it up.
+
+use super::profiles::{self, ProfileDefinition};
+use crate::error::ConfigError;
+use crate::models::{HardeningLevel, KernelCmdlineConfig, KernelConfig};
+
+/// `config_options` key holding the rendered command line.
+pub const CMDLINE_KEY: &str = "_CMDLINE";
+
+/// Longest command line the kernel accepts on x86 (`COMMAND_LINE_SIZE` - 1).
+pub const MAX_CMDLINE_LEN: usize = 2047;
+
+/// Keys the kernel accepts more than once (every occurrence takes effect).
+pub const REPEATABLE_KEYS: [&str; 2] = ["console", "earlycon"];
+
+/// Layer a parameter came from.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ParamSource {
+    Derived,
+    Profile,
+    User,
+}
+
+impl ParamSource {
+    /// Short label for previews and logs.
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            ParamSource::Derived => "derived",
+            ParamSource::Profile => "profile",
+            ParamSource::User => "user",
+        }
+    }
+}
+
+/// One `key[=value]` kernel parameter.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CmdlineParam {
+    pub key: String,
+    pub value: Option<String>,
+    pub source: ParamSource,
+}
+
+impl CmdlineParam {
+    /// Parse a single parameter, rejecting whitespace, quotes and empty keys.
+    pub fn parse(token: &str, source: ParamSource) -> Result<Self, ConfigError> {
+        let token = token.trim();
+        if token.is_empty() {
+            return Err(ConfigError::ValidationFailed(
+                "Kernel parameter cannot be empty".to_string(),
+            ));
+        }
+        if token
+            .chars()
+            .any(|c| c.is_whitespace() || c == '"' || c == '\'' || c.is_control())
+        {
+            return Err(ConfigError::ValidationFailed(format!(
+                "Kernel parameter '{}' must not contain whitespace or quotes",
+                token
+            )));
+        }
+        let (key, value) = match token.split_once('=') {
+            Some((key, value)) => (key, Some(value.to_string())),
+            None => (token, None),
+        };
+        if key.is_empty() {
+            return Err(ConfigError::ValidationFailed(format!(
+                "Kernel parameter '{}' has no name",
+                token
+            )));
+        }
+        Ok(CmdlineParam {
+            key: key.to_string(),
+            value,
+            source,
+        })
+    }
+
+    /// `key` or `key=value`.
+    pub fn render(&self) -> String {
+        match &self.value {
+            Some(value) => format!("{}={}", self.key, value),
+            None => self.key.clone(),
+        }
+    }
+
+    fn is_repeatable(&self) -> bool {
+        REPEATABLE_KEYS.contains(&self.key.as_str())
+    }
+
+    /// Whether a removal entry (`key` or `key=value`) matches this parameter.
+    fn matches_removal(&self, removal: &CmdlineParam) -> bool {
+        self.key == removal.key && (removal.value.is_none() || removal.value == self.value)
+    }
+}
+
+/// A resolved kernel command line.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct KernelCmdline {
+    /// Parameters in command line order
+    pub params: Vec<CmdlineParam>,
+    /// Lower-layer parameters dropped by a removal or replaced by a higher layer
+    pub dropped: Vec<CmdlineParam>,
+}
+
+impl KernelCmdline {
+    /// Resolve the three layers (see module docs).
+    pub fn resolve(
+        derived: &[String],
+        profile: &[String],
+        user: &KernelCmdlineConfig,
+    ) -> Result<Self, ConfigError> {
+        let mut cmdline = KernelCmdline::default();
+        cmdline.apply_layer(&parse_layer(derived, ParamSource::Derived)?)?;
+        cmdline.apply_layer(&parse_layer(profile, ParamSource::Profile)?)?;
+
+        let additions = parse_layer(&user.additions, ParamSource::User)?;
+        let removals = parse_layer(&user.removals, ParamSource::User)?;
+        if let Some((added, removed)) = additions.iter().find_map(|added| {
+            removals
+                .iter()
+                .find(|removed| added.matches_removal(removed))
+                .map(|removed| (added, removed))
+        }) {
+            return Err(ConfigError::ConflictDetected(format!(
+                "Kernel parameter '{}' is both added and removed ('{}')",
+                added.render(),
+                removed.render()
+            )));
+        }
+
+        let (kept, dropped): (Vec<_>, Vec<_>) = std::mem::take(&mut cmdline.params)
+            .into_iter()
+            .partition(|param| !removals.iter().any(|r| param.matches_removal(r)));
+        cmdline.params = kept;
+        cmdline.dropped.extend(dropped);
+        cmdline.apply_layer(&additions)?;
+
+        let rendered = cmdline.render();
+        if rendered.len() > MAX_CMDLINE_LEN {
+            return Err(ConfigError::ValidationFailed(format!(
+                "Kernel command line is {} bytes, the kernel accepts at most {}",
+                rendered.len(),
+                MAX_CMDLINE_LEN
+            )));
+        }
+        Ok(cmdline)
+    }
+
+    /// Merge one layer: same-key parameters of lower layers are replaced.
+    fn apply_layer(&mut self, layer: &[CmdlineParam]) -> Result<(), ConfigError> {
+        for (i, param) in layer.iter().enumerate() {
+            if let Some(other) = layer[..i].iter().find(|other| {
+                other.key == param.key && other.value != param.value && !param.is_repeatable()
+            }) {
+                return Err(ConfigError::ConflictDetected(format!(
+                    "Conflicting {} kernel parameters '{}' and '{}'",
+                    param.source.as_str(),
+                    other.render(),
+                    param.render()
+                )));
+            }
+        }
+
+        for param in layer {
+            if self.params.contains(param) {
+                continue;
+            }
+            if !param.is_repeatable() {
+                let (replaced, kept): (Vec<_>, Vec<_>) = std::mem::take(&mut self.params)
+                    .into_iter()
+                    .partition(|existing| {
+                        existing.key == param.key && existing.source != param.source
+                    });
+                self.params = kept;
+                self.dropped.extend(replaced);
+            }
+            self.params.push(param.clone());
+        }
+        Ok(())
+    }
+
+    /// Look up the parameter for `key` (the last one for repeatable keys).
+    pub fn get(&self, key: &str) -> Option<&CmdlineParam> {
+        self.params.iter().rev().find(|param| param.key == key)
+    }
+
+    /// Space-separated command line, as written to `CONFIG_CMDLINE`.
+    pub fn render(&self) -> String {
+        self.params
+            .iter()
+            .map(CmdlineParam::render)
+            .collect::<Vec<_>>()
+            .join(" ")
+    }
+
+    /// One line per parameter with its source, plus the dropped ones.
+    pub fn preview_lines(&self) -> Vec<String> {
+        let mut lines: Vec<String> = self
+            .params
+            .iter()
+            .map(|param| format!("{} ({})", param.render(), param.source.as_str()))
+            .collect();
+        lines.extend(
+            self.dropped
+                .iter()
+                .map(|param| format!("{} ({}, dropped)", param.render(), param.source.as_str())),
+        );
+        lines
+    }
+}
+
+fn parse_layer(tokens: &[String], source: ParamSource) -> Result<Vec<CmdlineParam>, ConfigError> {
+    tokens
+        .iter()
+        .flat_map(|token| token.split_whitespace())
+        .map(|token| CmdlineParam::parse(token, source))
+        .collect()
+}
+
+/// Parameters implied by first-class fields of a finalized config.
+pub fn derived_params(config: &KernelConfig) -> Vec<String> {
+    let mut params = Vec::new();
+    let preempt = match config.preemption.as_str() {
+        "Full" | "Full-RT" => Some("full"),
+        "Voluntary" => Some("voluntary"),
+        "Server" => Some("none"),
+        _ => None,
+    };
+    if let Some(preempt) = preempt {
+        params.push(format!("preempt={}", preempt));
+    }
+    if config.use_mglru {
+        params.push("lru_gen.enabled=7".to_string());
+    }
+    if config.hardening == HardeningLevel::Minimal {
+        params.push("mitigations=off".to_string());
+    }
+    params
+}
+
+/// Resolve the command line of a config whose first-class fields are final.
+pub fn resolve(
+    config: &KernelConfig,
+    profile_def: &ProfileDefinition,
+) -> Result<KernelCmdline, ConfigError> {
+    KernelCmdline::resolve(
+        &derived_params(config),
+        &profile_def.cmdline,
+        &config.cmdline,
+    )
+}
+
+/// Resolve the command line for an unfinalized config, e.g. for the Build tab
+/// preview: the preemption model comes from the profile.
+pub fn preview(config: &KernelConfig) -> Result<KernelCmdline, ConfigError> {
+    let profile_def = profiles::get_profile(&config.profile).ok_or_else(|| {
+        ConfigError::ValidationFailed(format!("Unknown profile: {}", config.profile))
+    })?;
+    let config = KernelConfig {
+        preemption: profile_def.preemption.clone(),
+        ..config.clone()
+    };
+    resolve(&config, &profile_def)
+}
+
+/// Options for a bootloader entry of a kernel built with `config`.
+///
+/// Returns `base` unchanged unless the build opted into `boot_entry`; otherwise
+/// the baked-in parameters replace same-key parameters of `base`, removed keys
+/// are dropped from `base`, and the baked-in parameters are appended.
+pub fn boot_entry_options(base: &str, config: &KernelConfig) -> String {
+    let Some(baked) = config
+        .config_options
+        .get(CMDLINE_KEY)
+        .filter(|_| config.cmdline.boot_entry)
+    else {
+        return base.to_string();
+    };
+
+    let baked: Vec<CmdlineParam> = baked
+        .split_whitespace()
+        .filter_map(|token| CmdlineParam::parse(token, ParamSource::User).ok())
+        .collect();
+    let removals: Vec<CmdlineParam> = config
+        .cmdline
+        .removals
+        .iter()
+        .filter_map(|token| CmdlineParam::parse(token, ParamSource::User).ok())
+        .collect();
+
+    let mut options: Vec<String> = base
+        .split_whitespace()
+        .filter(
+            |token| match CmdlineParam::parse(token, ParamSource::User) {
+                Ok(param) => {
+                    !removals.iter().any(|r| param.matches_removal(r))
+                        && (param.is_repeatable() || !baked.iter().any(|b| b.key == param.key))
+                }
+                Err(_) => true,
+            },
+        )
+        .map(str::to_string)
+        .collect();
+    for param in &baked {
+        let rendered = param.render();
+        if !options.contains(&rendered) {
+            options.push(rendered);
+        }
+    }
+    options.join(" ")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn strings(items: &[&str]) -> Vec<String> {
+        items.iter().map(|s| s.to_string()).collect()
+    }
+
+    #[test]
+    fn test_resolve_layers_and_overrides() {
+        let user = KernelCmdlineConfig {
+            additions: strings(&["preempt=lazy", "split_lock_detect=off"]),
+            removals: strings(&["nowatchdog"]),
+            boot_entry: false,
+        };
+        let cmdline = KernelCmdline::resolve(
+            &strings(&["preempt=full", "lru_gen.enabled=7"]),
+            &strings(&["nowatchdog", "quiet"]),
+            &user,
+        )
+        .unwrap();
+        assert_eq!(
+            cmdline.render(),
+            "lru_gen.enabled=7 quiet preempt=lazy split_lock_detect=off"
+        );
+        assert_eq!(cmdline.get("preempt").unwrap().source, ParamSource::User);
+        assert_eq!(cmdline.dropped.len(), 2);
+        assert!(cmdline
+            .preview_lines()
+            .contains(&"preempt=full (derived, dropped)".to_string()));
+    }
+
+    #[test]
+    fn test_resolve_detects_conflicts() {
+        let user = KernelCmdlineConfig {
+            additions: strings(&["preempt=full preempt=none"]),
+            ..Default::default()
+        };
+        assert!(matches!(
+            KernelCmdline::resolve(&[], &[], &user),
+            Err(ConfigError::ConflictDetected(_))
+        ));
+
+        let user = KernelCmdlineConfig {
+            additions: strings(&["mitigations=off"]),
+            removals: strings(&["mitigations"]),
+            boot_entry: false,
+        };
+        assert!(matches!(
+            KernelCmdline::resolve(&[], &[], &user),
+            Err(ConfigError::ConflictDetected(_))
+        ));
+
+        // Repeatable keys and exact duplicates are fine
+        let user = KernelCmdlineConfig {
+            additions: strings(&["console=tty0", "console=ttyS0,115200", "quiet", "quiet"]),
+            ..Default::default()
+        };
+        let cmdline = KernelCmdline::resolve(&[], &[], &user).unwrap();
+        assert_eq!(cmdline.render(), "console=tty0 console=ttyS0,115200 quiet");
+    }
+
+    #[test]
+    fn test_param_parse_rejects_bad_tokens() {
+        assert!(CmdlineParam::parse("=1", ParamSource::User).is_err());
+        assert!(CmdlineParam::parse("foo=\"bar\"", ParamSource::User).is_err());
+        let param = CmdlineParam::parse("rcu_nocbs=1-3", ParamSource::User).unwrap();
+        assert_eq!(param.key, "rcu_nocbs");
+        assert_eq!(param.value.as_deref(), Some("1-3"));
+    }
+
+    #[test]
+    fn test_derived_params_follow_config() {
+        let config = KernelConfig {
+            preemption: "Server".to_string(),
+            use_mglru: true,
+            hardening: HardeningLevel::Minimal,
+            ..KernelConfig::default()
+        };
+        assert_eq!(
+            derived_params(&config),
+            strings(&["preempt=none", "lru_gen.enabled=7", "mitigations=off"])
+        );
+    }
+
+    #[test]
+    fn test_boot_entry_options_merge() {
+        let mut config = KernelConfig::default();
+        config.config_options.insert(
+            CMDLINE_KEY.to_string(),
+            "nowatchdog preempt=full".to_string(),
+        );
+        let base = "root=UUID=1234 rw quiet preempt=voluntary";
+        assert_eq!(boot_entry_options(base, &config), base);
+
+        config.cmdline.boot_entry = true;
+        config.cmdline.removals = strings(&["quiet"]);
+        assert_eq!(
+            boot_entry_options(base, &config),
+            "root=UUID=1234 rw nowatchdog preempt=full"
+        );
+    }
+}
@@ -6,7 +6,8 @@
 //! 2. Setting MGLRU tuning parameters based on profile
 //! 3. Applying GPU-aware driver exclusions and LTO shielding
 //! 4. Generating derived config_options strings
-//! 5. Validation before returning finalized config
+//! 5. Resolving the baked-in kernel command line
+//! 6. Validation before returning finalized config
 //!
 //! # Design Principle: Hierarchical Resolution
 //!
@@ -31,10 +32,11 @@
 //!  4. Apply GPU exclusions (hardware-aware)
 //!  5. Determine LTO shielding modules (GPU-based)
 //!  6. Generate derived config_options strings
-//!  7. Return finalized config
+//!  7. Resolve the kernel command line (profile + user layers)
+//!  8. Return finalized config
 //! ```
 
-use super::{exclusions, profiles};
+use super::{cmdline, exclusions, profiles};
 use crate::error::ConfigError;
 use crate::models::{GpuVendor, HardwareInfo, KernelConfig, LtoType};
 
@@ -47,6 +49,7 @@ use crate::models::{GpuVendor, HardwareInfo, KernelConfig, LtoType};
 /// - GPU-aware driver exclusions to reduce kernel size
 /// - LTO shielding for sensitive GPU modules
 /// - Derived config_options strings for the build system
+/// - The kernel command line, rejecting conflicting parameters
 ///
 /// # Arguments
 ///
@@ -146,6 +149,14 @@ pub fn finalize_kernel_config(
     config.use_modprobed = profile_def.enable_module_stripping;
     config.use_whitelist = profile_def.enable_module_stripping;
 
+    // User profiles may carry extra kernel options; explicit user options win.
+    for (key, value) in &profile_def.config_options {
+        config
+            .config_options
+            .entry(key.clone())
+            .or_insert_with(|| value.clone());
+    }
+
     // =========================================================================
     // CRITICAL SAFETY CONSTRAINT: Whitelist depends on modprobed-db
     // =========================================================================
@@ -210,7 +221,20 @@ pub fn finalize_kernel_config(
     eprintln!("[Finalizer] [STEP 7] SCX configuration ensured");
 
     // =========================================================================
-    // Phase 8: Return Finalized Config
+    // Phase 8: Resolve the Kernel Command Line
+    // =========================================================================
+    // Derived (preempt/MGLRU/mitigations) < profile defaults < user additions/removals
+    let kernel_cmdline = cmdline::resolve(&config, &profile_def)?;
+    config
+        .config_options
+        .insert(cmdline::CMDLINE_KEY.to_string(), kernel_cmdline.render());
+    eprintln!(
+        "[Finalizer] [STEP 8] Resolved kernel command line: '{}'",
+        kernel_cmdline.render()
+    );
+
+    // =========================================================================
+    // Phase 9: Return Finalized Config
     // =========================================================================
     eprintln!(
         "[Finalizer] [COMPLETE] Configuration finalized: {} profile ready for build",
@@ -534,6 +558,33 @@ mod tests {
         assert_eq!(finalized.preemption, "Voluntary");
     }
 
+    #[test]
+    fn test_finalize_resolves_cmdline() {
+        let mut config = KernelConfig {
+            profile: "Gaming".to_string(),
+            ..KernelConfig::default()
+        };
+        config.cmdline.additions = vec!["threadirqs".to_string()];
+        config.cmdline.removals = vec!["nowatchdog".to_string()];
+
+        let finalized = finalize_kernel_config(config, &HardwareInfo::default()).unwrap();
+        assert_eq!(
+            finalized.config_options[cmdline::CMDLINE_KEY],
+            "preempt=full lru_gen.enabled=7 threadirqs"
+        );
+
+        // Two preempt= values from the user are rejected before the build starts
+        let mut config = KernelConfig {
+            profile: "Gaming".to_string(),
+            ..KernelConfig::default()
+        };
+        config.cmdline.additions = vec!["preempt=none".to_string(), "preempt=full".to_string()];
+        assert!(matches!(
+            finalize_kernel_config(config, &HardwareInfo::default()),
+            Err(ConfigError::ConflictDetected(_))
+        ));
+    }
+
     #[test]
     fn test_derived_config_options_generation() {
         let mut config = KernelConfig {
@@ -49,6 +49,55 @@ pub fn load_config_from_file(path: &Path) -> Result<KernelConfig, ConfigError> {
     Ok(config)
 }
 
+/// Load config from a TOML file.
+///
+/// Keys missing from the file fall back to `KernelConfig::default()`, so a TOML
+/// file only needs to list the settings it wants to change.
+pub fn load_config_from_toml(path: &Path) -> Result<KernelConfig, ConfigError> {
+    let content = fs::read_to_string(path).map_err(|e| {
+        if e.kind() == std::io::ErrorKind::NotFound {
+            ConfigError::FileNotFound(format!(
+                "Configuration file not found at: {}",
+                path.display()
+            ))
+        } else {
+            ConfigError::IoError(e)
+        }
+    })?;
+
+    parse_config_toml(&content)
+}
+
+/// Parse a (possibly partial) TOML document into a KernelConfig.
+pub fn parse_config_toml(content: &str) -> Result<KernelConfig, ConfigError> {
+    let overrides: toml::Table =
+        toml::from_str(content).map_err(|e| ConfigError::InvalidToml(e.to_string()))?;
+
+    // Overlay user keys onto the serialized defaults
+    let mut merged = toml::Table::try_from(KernelConfig::default())
+        .map_err(|e| ConfigError::InvalidToml(e.to_string()))?;
+    merge_toml_tables(&mut merged, overrides);
+
+    merged
+        .try_into()
+        .map_err(|e: toml::de::Error| ConfigError::InvalidToml(e.to_string()))
+}
+
+/// Overlay `overrides` onto `base`, descending into tables present in both so a
+/// partial `[section]` keeps the defaults of the keys it doesn't list.
+fn merge_toml_tables(base: &mut toml::Table, overrides: toml::Table) {
+    for (key, value) in overrides {
+        match (base.get_mut(&key), value) {
+            (Some(toml::Value::Table(existing)), toml::Value::Table(nested)) => {
+                merge_toml_tables(existing, nested);
+            }
+            (_, value) => {
+                base.insert(key, value);
+            }
+        }
+    }
+}
+
 /// Save config to JSON file.
 pub fn save_config_to_file(config: &KernelConfig, path: &Path) -> Result<(), ConfigError> {
     // Ensure parent directory exists
@@ -321,6 +370,96 @@ mod tests {
         );
     }
 
+    #[test]
+    fn test_parse_partial_toml_uses_defaults() {
+        let config = parse_config_toml(
+            r#"
+            profile = "Gaming"
+            lto_type = "full"
+
+            [config_options]
+            CONFIG_HZ_1000 = "y"
+            "#,
+        )
+        .expect("Failed to parse TOML");
+
+        assert_eq!(config.profile, "Gaming");
+        assert_eq!(config.lto_type, LtoType::Full);
+        assert_eq!(
+            config.config_options.get("CONFIG_HZ_1000"),
+            Some(&"y".to_string())
+        );
+        // Unspecified keys keep their defaults
+        assert_eq!(config.version, "latest");
+        assert_eq!(config.hardening, HardeningLevel::Standard);
+    }
+
+    #[test]
+    fn test_merge_toml_tables_is_recursive() {
+        let mut base: toml::Table = toml::from_str(
+            r#"
+            profile = "Generic"
+
+            [resources]
+            nice = 10
+            cgroup = true
+
+            [resources.limits]
+            memory = 4096
+            "#,
+        )
+        .unwrap();
+        let overrides: toml::Table = toml::from_str(
+            r#"
+            [resources]
+            cgroup = false
+
+            [resources.limits]
+            cpu = 200
+            "#,
+        )
+        .unwrap();
+
+        merge_toml_tables(&mut base, overrides);
+
+        assert_eq!(base["profile"].as_str(), Some("Generic"));
+        let resources = base["resources"].as_table().unwrap();
+        assert_eq!(resources["nice"].as_integer(), Some(10));
+        assert_eq!(resources["cgroup"].as_bool(), Some(false));
+        let limits = resources["limits"].as_table().unwrap();
+        assert_eq!(limits["memory"].as_integer(), Some(4096));
+        assert_eq!(limits["cpu"].as_integer(), Some(200));
+    }
+
+    #[test]
+    fn test_parse_partial_nested_table() {
+        let config = parse_config_toml(
+            r#"
+            [resources]
+            thermal_limit_c = 85.0
+
+            [pgo]
+            stage = "instrument"
+            "#,
+        )
+        .expect("Failed to parse TOML");
+
+        // Keys missing from a partial section keep their defaults
+        assert_eq!(config.resources, {
+            let mut expected = KernelConfig::default().resources;
+            expected.thermal_limit_c = Some(85.0);
+            expected
+        });
+        assert_eq!(config.pgo.stage, crate::models::PgoStage::Instrument);
+        assert_eq!(config.pgo.method, KernelConfig::default().pgo.method);
+    }
+
+    #[test]
+    fn test_parse_invalid_toml() {
+        let result = parse_config_toml("profile = ");
+        assert!(matches!(result, Err(ConfigError::InvalidToml(_))));
+    }
+
     #[test]
     fn test_list_config_files_nonexistent_dir() {
         let result = list_config_files(Path::new("/nonexistent/directory"));
@@ -11,6 +11,7 @@
 //! - `modprobed`: Manages modprobed-db integration for module filtering
 //! - `whitelist`: Manages driver whitelist functionality
 //! - `exclusions`: Manages driver exclusion lists
+//! - `cmdline`: Resolves the baked-in kernel command line from profile and user layers
 //!
 //! # Configuration Flow
 //!
@@ -27,6 +28,7 @@
 //! - Persists state to `config/settings.json`
 //! - Handles serialization/deserialization
 
+pub mod cmdline;
 pub mod exclusions;
 pub mod finalizer;
 pub mod loader;
@@ -35,11 +37,29 @@ pub mod profiles;
 pub mod validator;
 pub mod whitelist;
 
-use crate::error::ConfigError;
-use crate::models::{HardeningLevel, KernelConfig, LtoType};
+use crate::error::{AppError, ConfigError};
+use crate::models::{CpuSelection, CustomKernelSource, HardeningLevel, KernelConfig, LtoType};
 use std::path::PathBuf;
 use std::sync::{Arc, RwLock};
 
+/// Directory under the user config dir that holds GOATd's persistent state.
+pub const APP_CONFIG_DIR_NAME: &str = "goatdkernel";
+
+/// `~/.config/goatdkernel` (honours `XDG_CONFIG_HOME`).
+///
+/// Fails when no user config directory can be determined instead of falling
+/// back to a shared location.
+pub fn app_config_dir() -> Result<PathBuf, AppError> {
+    dirs::config_dir()
+        .map(|dir| dir.join(APP_CONFIG_DIR_NAME))
+        .ok_or_else(|| {
+            AppError::Settings(
+                "Cannot determine the user config directory (HOME/XDG_CONFIG_HOME unset)"
+                    .to_string(),
+            )
+        })
+}
+
 /// Application state for managing build configuration and settings
 #[derive(Clone, Debug, serde::Serialize, serde::Deserialize)]
 #[serde(default)]
@@ -52,11 +72,21 @@ pub struct AppState {
     pub selected_scx_mode: String,
     pub kernel_hardening: HardeningLevel,
     pub secure_boot: bool,
+    /// MOK private key used to sign the kernel for Secure Boot (empty = unset)
+    pub mok_key_path: String,
+    /// MOK certificate matching `mok_key_path` (empty = unset)
+    pub mok_cert_path: String,
     pub use_modprobed: bool,
     pub use_whitelist: bool,
     pub use_polly: bool,
     pub use_mglru: bool,
     pub native_optimizations: bool,
+    /// Kernel parameters added on top of the profile command line
+    pub cmdline_additions: Vec<String>,
+    /// Kernel parameter keys (or exact `key=value`) removed from the profile command line
+    pub cmdline_removals: Vec<String>,
+    /// Also write the baked-in parameters into the bootloader entry
+    pub cmdline_boot_entry: bool,
 
     // Override flags: Track if user manually toggled a feature
     // These prevent profile changes from wiping out user customizations
@@ -75,6 +105,12 @@ pub struct AppState {
 
     // Kernel source path (where the repository was cloned)
     pub kernel_source_path: String,
+    /// User-registered kernel sources, merged into `KernelSourceDB` by name
+    pub custom_sources: Vec<CustomKernelSource>,
+    /// Local source mirror directory (empty = `~/.cache/goatd/mirror`)
+    pub source_mirror_path: String,
+    /// Clone sources and resolve "latest" from the mirror only (air-gapped hosts)
+    pub offline_sources: bool,
 
     // Security & Verification Settings
     pub verify_signatures: bool,
@@ -97,6 +133,8 @@ pub struct AppState {
     pub perf_background_enabled: bool,
     /// Alert threshold for background spikes (microseconds)
     pub perf_alert_threshold_us: f32,
+    /// CPUs measured by benchmarks (single core, P-cores, E-cores or all)
+    pub perf_cpu_selection: CpuSelection,
 }
 
 impl Default for AppState {
@@ -109,11 +147,16 @@ impl Default for AppState {
             selected_scx_mode: "Auto".to_string(),
             kernel_hardening: HardeningLevel::Standard,
             secure_boot: false,
+            mok_key_path: String::new(),
+            mok_cert_path: String::new(),
             use_modprobed: true,
             use_whitelist: true,
             use_polly: false,
             use_mglru: false,
             native_optimizations: true,
+            cmdline_additions: Vec::new(),
+            cmdline_removals: Vec::new(),
+            cmdline_boot_entry: false,
             user_toggled_polly: false,
             user_toggled_mglru: false,
             user_toggled_hardening: false,
@@ -125,6 +168,9 @@ impl Default for AppState {
             theme_mode: "4rchCybrPnk".to_string(),
             minimize_to_tray: false,
             kernel_source_path: String::new(),
+            custom_sources: Vec::new(),
+            source_mirror_path: String::new(),
+            offline_sources: false,
             verify_signatures: true,
             theme_idx: 0,
             ui_font_size: 12.0,
@@ -137,6 +183,7 @@ impl Default for AppState {
             audit_on_startup: false,
             perf_background_enabled: true,
             perf_alert_threshold_us: 500.0,
+            perf_cpu_selection: CpuSelection::Single,
         }
     }
 }
@@ -1,8 +1,61 @@
 //! Kernel build profiles.
+//!
+//! Five profiles are built in. Additional profiles are read from TOML files in
+//! the user profile directory (`~/.config/goatdkernel/profiles/*.toml`); the
+//! file stem becomes the profile key. A user profile starts from its `base`
+//! profile (built-in or another user profile, `generic` when omitted) and
+//! overrides individual fields:
+//!
+//! ```toml
+//! name = "Team Gaming"
+//! base = "gaming"
+//! description = "Gaming + BBR3"
+//! hz = 750
+//! lto = "full"
+//!
+//! cmdline = ["nowatchdog", "split_lock_detect=off"]
+//!
+//! [config_options]
+//! CONFIG_TCP_CONG_BBR = "y"
+//! ```
+//!
+//! Every resolved user profile is validated through
+//! [`validator::validate_all`](super::validator::validate_all) before it is offered.
 
-use crate::models::{HardeningLevel, LtoType};
+use super::{cmdline, validator};
+use crate::error::{AppError, ConfigError};
+use crate::models::{HardeningLevel, KernelConfig, LtoType};
 use lazy_static::lazy_static;
+use serde::Deserialize;
 use std::collections::HashMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+use std::time::{Duration, Instant, SystemTime};
+
+/// Directory under the user config dir holding user-defined profile files.
+pub const USER_PROFILES_DIR_NAME: &str = "profiles";
+
+/// Minimum time between two scans of the user profile directory. The profile
+/// list is read on every UI frame; edits show up after at most this long.
+pub const USER_PROFILES_RESCAN_INTERVAL: Duration = Duration::from_secs(2);
+
+/// Profile a user profile inherits from when it does not set `base`.
+pub const DEFAULT_BASE_PROFILE: &str = "generic";
+
+/// Built-in profiles offered in the UI, in display order. `generic` only serves
+/// as the default base and is not listed.
+pub const UI_BUILTIN_PROFILES: [&str; 4] = ["gaming", "workstation", "laptop", "server"];
+
+/// Timer frequencies accepted in user profiles.
+pub const SUPPORTED_HZ: [u32; 7] = [100, 250, 300, 500, 600, 750, 1000];
+
+/// Kernel parameters every built-in profile bakes into `CONFIG_CMDLINE`.
+/// `preempt=` is derived from the preemption model (see [`super::cmdline`]).
+pub const DEFAULT_CMDLINE: [&str; 1] = ["nowatchdog"];
+
+/// Preemption models understood by the finalizer.
+pub const SUPPORTED_PREEMPTION: [&str; 4] = ["Voluntary", "Full", "Full-RT", "Server"];
 
 lazy_static! {
     static ref PROFILES: HashMap<String, ProfileDefinition> = {
@@ -73,6 +126,7 @@ lazy_static! {
 
         profiles
     };
+    static ref USER_PROFILE_CACHE: Mutex<Option<UserProfileCache>> = Mutex::new(None);
 }
 
 /// Profile definition.
@@ -90,6 +144,12 @@ pub struct ProfileDefinition {
     pub use_polly: bool,                 // Polly?
     pub use_mglru: bool,                 // MGLRU?
     pub native_optimizations: bool,      // Native optimizations (-march=native)?
+    /// Extra kernel config options (`CONFIG_*` -> value) applied by this profile
+    pub config_options: HashMap<String, String>,
+    /// Default kernel command line parameters of this profile
+    pub cmdline: Vec<String>,
+    /// File the profile was loaded from (`None` for built-in profiles)
+    pub source_path: Option<PathBuf>,
 }
 
 impl ProfileDefinition {
@@ -121,20 +181,365 @@ impl ProfileDefinition {
             use_polly,
             use_mglru,
             native_optimizations,
+            config_options: HashMap::new(),
+            cmdline: DEFAULT_CMDLINE.iter().map(|p| p.to_string()).collect(),
+            source_path: None,
+        }
+    }
+
+    /// Whether this profile was loaded from a user profile file.
+    pub fn is_user_defined(&self) -> bool {
+        self.source_path.is_some()
+    }
+
+    /// Build a `KernelConfig` carrying this profile's settings, used for validation.
+    pub fn to_kernel_config(&self, key: &str) -> KernelConfig {
+        KernelConfig {
+            profile: key.to_string(),
+            lto_type: self.default_lto,
+            hardening: self.hardening_level,
+            use_modprobed: self.enable_module_stripping,
+            use_whitelist: self.enable_module_stripping,
+            use_polly: self.use_polly,
+            use_mglru: self.use_mglru,
+            native_optimizations: self.native_optimizations,
+            force_clang: self.use_clang,
+            hz: self.hz,
+            preemption: self.preemption.clone(),
+            config_options: self.config_options.clone(),
+            ..KernelConfig::default()
+        }
+    }
+}
+
+/// On-disk layout of a user profile file. Every field except `config_options`
+/// falls back to the `base` profile when omitted; `cmdline` replaces the base
+/// profile's parameters as a whole.
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct UserProfileFile {
+    pub name: Option<String>,
+    pub base: Option<String>,
+    pub description: Option<String>,
+    pub explanation: Option<String>,
+    pub use_clang: Option<bool>,
+    pub lto: Option<LtoType>,
+    pub enable_module_stripping: Option<bool>,
+    pub hardening: Option<HardeningLevel>,
+    pub preemption: Option<String>,
+    pub hz: Option<u32>,
+    pub use_polly: Option<bool>,
+    pub use_mglru: Option<bool>,
+    pub native_optimizations: Option<bool>,
+    pub cmdline: Option<Vec<String>>,
+    pub config_options: HashMap<String, String>,
+}
+
+/// Result of scanning a user profile directory.
+#[derive(Debug, Default)]
+pub struct UserProfileLoad {
+    /// Valid, fully resolved user profiles keyed by lowercase file stem
+    pub profiles: HashMap<String, ProfileDefinition>,
+    /// Files that were skipped, with the reason
+    pub errors: Vec<(PathBuf, ConfigError)>,
+}
+
+/// Cached user profiles, reloaded when any profile file changes.
+struct UserProfileCache {
+    stamp: Vec<(PathBuf, Option<SystemTime>, u64)>,
+    profiles: HashMap<String, ProfileDefinition>,
+    /// When the directory was last scanned
+    checked_at: Instant,
+}
+
+/// Whether `key` is usable as a profile key (lowercase ASCII, digits, `-`, `_`).
+fn is_valid_profile_key(key: &str) -> bool {
+    !key.is_empty()
+        && key
+            .chars()
+            .all(|c| c.is_ascii_lowercase() || c.is_ascii_digit() || c == '-' || c == '_')
+}
+
+/// List the `*.toml` files of a profile directory in sorted order.
+fn profile_files(dir: &Path) -> Vec<PathBuf> {
+    let mut files: Vec<PathBuf> = match fs::read_dir(dir) {
+        Ok(entries) => entries
+            .filter_map(|entry| entry.ok())
+            .map(|entry| entry.path())
+            .filter(|path| path.is_file() && path.extension().is_some_and(|ext| ext == "toml"))
+            .collect(),
+        Err(_) => Vec::new(),
+    };
+    files.sort();
+    files
+}
+
+/// Load, resolve and validate every user profile in `dir`.
+///
+/// A missing directory yields no profiles. Files that fail to parse, shadow a
+/// built-in profile, reference an unknown or cyclic `base`, or fail validation
+/// are reported in [`UserProfileLoad::errors`] and left out.
+pub fn load_user_profiles(dir: &Path) -> UserProfileLoad {
+    let mut load = UserProfileLoad::default();
+    let mut raw: HashMap<String, (PathBuf, UserProfileFile)> = HashMap::new();
+
+    for path in profile_files(dir) {
+        let key = path
+            .file_stem()
+            .map(|stem| stem.to_string_lossy().to_lowercase())
+            .unwrap_or_default();
+
+        if !is_valid_profile_key(&key) {
+            load.errors.push((
+                path.clone(),
+                ConfigError::ValidationFailed(format!(
+                    "Invalid profile name '{}': use lowercase letters, digits, '-' or '_'",
+                    key
+                )),
+            ));
+            continue;
+        }
+        if PROFILES.contains_key(&key) {
+            load.errors.push((
+                path.clone(),
+                ConfigError::ConflictDetected(format!(
+                    "User profile '{}' shadows the built-in profile of the same name",
+                    key
+                )),
+            ));
+            continue;
+        }
+
+        let parsed = fs::read_to_string(&path)
+            .map_err(ConfigError::IoError)
+            .and_then(|content| {
+                toml::from_str::<UserProfileFile>(&content)
+                    .map_err(|e| ConfigError::InvalidToml(e.to_string()))
+            });
+        match parsed {
+            Ok(file) => {
+                raw.insert(key, (path, file));
+            }
+            Err(e) => load.errors.push((path, e)),
+        }
+    }
+
+    let mut keys: Vec<String> = raw.keys().cloned().collect();
+    keys.sort();
+    for key in keys {
+        let path = raw[&key].0.clone();
+        match resolve_user_profile(&key, &raw, &mut Vec::new()).and_then(|profile| {
+            validate_profile(&key, &profile)?;
+            Ok(profile)
+        }) {
+            Ok(profile) => {
+                load.profiles.insert(key, profile);
+            }
+            Err(e) => load.errors.push((path, e)),
+        }
+    }
+
+    load
+}
+
+/// Resolve `key` against its chain of base profiles.
+fn resolve_user_profile(
+    key: &str,
+    raw: &HashMap<String, (PathBuf, UserProfileFile)>,
+    chain: &mut Vec<String>,
+) -> Result<ProfileDefinition, ConfigError> {
+    if chain.iter().any(|seen| seen == key) {
+        chain.push(key.to_string());
+        return Err(ConfigError::ConflictDetected(format!(
+            "Profile inheritance cycle: {}",
+            chain.join(" -> ")
+        )));
+    }
+    chain.push(key.to_string());
+
+    let (path, file) = &raw[key];
+    let base_key = file
+        .base
+        .as_deref()
+        .unwrap_or(DEFAULT_BASE_PROFILE)
+        .to_lowercase();
+
+    let base = if let Some(builtin) = PROFILES.get(&base_key) {
+        builtin.clone()
+    } else if raw.contains_key(&base_key) {
+        resolve_user_profile(&base_key, raw, chain)?
+    } else {
+        return Err(ConfigError::ValidationFailed(format!(
+            "Profile '{}' has unknown base profile '{}'",
+            key, base_key
+        )));
+    };
+
+    Ok(merge_profile(base, file, key, path))
+}
+
+/// Apply the fields set in `file` on top of `base`.
+fn merge_profile(
+    base: ProfileDefinition,
+    file: &UserProfileFile,
+    key: &str,
+    path: &Path,
+) -> ProfileDefinition {
+    let mut config_options = base.config_options;
+    config_options.extend(
+        file.config_options
+            .iter()
+            .map(|(k, v)| (k.clone(), v.clone())),
+    );
+
+    ProfileDefinition {
+        name: file.name.clone().unwrap_or_else(|| key.to_string()),
+        description: file
+            .description
+            .clone()
+            .unwrap_or_else(|| format!("Custom (based on {})", base.name)),
+        explanation: file.explanation.clone().unwrap_or(base.explanation),
+        use_clang: file.use_clang.unwrap_or(base.use_clang),
+        default_lto: file.lto.unwrap_or(base.default_lto),
+        enable_module_stripping: file
+            .enable_module_stripping
+            .unwrap_or(base.enable_module_stripping),
+        hardening_level: file.hardening.unwrap_or(base.hardening_level),
+        preemption: file.preemption.clone().unwrap_or(base.preemption),
+        hz: file.hz.unwrap_or(base.hz),
+        use_polly: file.use_polly.unwrap_or(base.use_polly),
+        use_mglru: file.use_mglru.unwrap_or(base.use_mglru),
+        native_optimizations: file
+            .native_optimizations
+            .unwrap_or(base.native_optimizations),
+        config_options,
+        cmdline: file.cmdline.clone().unwrap_or(base.cmdline),
+        source_path: Some(path.to_path_buf()),
+    }
+}
+
+/// Validate a resolved profile: HZ, preemption model, command line, then
+/// `validator::validate_all`.
+pub fn validate_profile(key: &str, profile: &ProfileDefinition) -> Result<(), ConfigError> {
+    if !SUPPORTED_HZ.contains(&profile.hz) {
+        return Err(ConfigError::ValidationFailed(format!(
+            "Profile '{}' has unsupported HZ {} (expected one of {:?})",
+            key, profile.hz, SUPPORTED_HZ
+        )));
+    }
+    if !SUPPORTED_PREEMPTION.contains(&profile.preemption.as_str()) {
+        return Err(ConfigError::ValidationFailed(format!(
+            "Profile '{}' has unknown preemption model '{}' (expected one of {:?})",
+            key, profile.preemption, SUPPORTED_PREEMPTION
+        )));
+    }
+    let config = profile.to_kernel_config(key);
+    cmdline::resolve(&config, profile).map_err(|e| match e {
+        ConfigError::ConflictDetected(msg) => {
+            ConfigError::ConflictDetected(format!("Profile '{}': {}", key, msg))
+        }
+        ConfigError::ValidationFailed(msg) => {
+            ConfigError::ValidationFailed(format!("Profile '{}': {}", key, msg))
+        }
+        other => other,
+    })?;
+    validator::validate_all(&config)
+}
+
+/// Modification stamp of every profile file, used to invalidate the cache.
+fn directory_stamp(dir: &Path) -> Vec<(PathBuf, Option<SystemTime>, u64)> {
+    profile_files(dir)
+        .into_iter()
+        .map(|path| {
+            let metadata = fs::metadata(&path).ok();
+            let modified = metadata.as_ref().and_then(|m| m.modified().ok());
+            let len = metadata.map(|m| m.len()).unwrap_or(0);
+            (path, modified, len)
+        })
+        .collect()
+}
+
+/// `~/.config/goatdkernel/profiles`.
+pub fn user_profiles_dir() -> Result<PathBuf, AppError> {
+    Ok(super::app_config_dir()?.join(USER_PROFILES_DIR_NAME))
+}
+
+/// User profiles from [`user_profiles_dir`].
+fn user_profiles() -> HashMap<String, ProfileDefinition> {
+    let Ok(dir) = user_profiles_dir() else {
+        return HashMap::new();
+    };
+    let mut cache = match USER_PROFILE_CACHE.lock() {
+        Ok(guard) => guard,
+        Err(poisoned) => poisoned.into_inner(),
+    };
+    cached_user_profiles(&mut cache, &dir, Instant::now())
+}
+
+/// Serve user profiles from `cache`, rescanning `dir` at most once per
+/// [`USER_PROFILES_RESCAN_INTERVAL`] and reparsing only when a file changed.
+fn cached_user_profiles(
+    cache: &mut Option<UserProfileCache>,
+    dir: &Path,
+    now: Instant,
+) -> HashMap<String, ProfileDefinition> {
+    if let Some(cached) = cache.as_mut() {
+        if now.saturating_duration_since(cached.checked_at) < USER_PROFILES_RESCAN_INTERVAL {
+            return cached.profiles.clone();
         }
     }
+
+    let stamp = directory_stamp(dir);
+    if let Some(cached) = cache.as_mut() {
+        if cached.stamp == stamp {
+            cached.checked_at = now;
+            return cached.profiles.clone();
+        }
+    }
+
+    let load = load_user_profiles(dir);
+    for (path, error) in &load.errors {
+        eprintln!(
+            "[Profiles] [WARNING] Skipping user profile {}: {}",
+            path.display(),
+            error
+        );
+    }
+    let profiles = load.profiles;
+    *cache = Some(UserProfileCache {
+        stamp,
+        profiles: profiles.clone(),
+        checked_at: now,
+    });
+    profiles
 }
 
 /// Get available profiles.
-/// Returns a clone of the lazily-initialized profiles HashMap.
+/// Returns the built-in profiles merged with the valid user profiles.
 pub fn get_available_profiles() -> HashMap<String, ProfileDefinition> {
-    PROFILES.clone()
+    let mut profiles = PROFILES.clone();
+    profiles.extend(user_profiles());
+    profiles
 }
 
-/// Get profile by name.
+/// Get profile by name (built-in or user-defined).
 pub fn get_profile(name: &str) -> Option<ProfileDefinition> {
     let lowercase_name = name.to_lowercase();
-    PROFILES.get(&lowercase_name).cloned()
+    PROFILES
+        .get(&lowercase_name)
+        .cloned()
+        .or_else(|| user_profiles().remove(&lowercase_name))
+}
+
+/// Profile keys offered in the UI: built-ins first, then user profiles sorted by key.
+pub fn ui_profile_keys() -> Vec<String> {
+    let mut user_keys: Vec<String> = user_profiles().into_keys().collect();
+    user_keys.sort();
+    UI_BUILTIN_PROFILES
+        .iter()
+        .map(|key| key.to_string())
+        .chain(user_keys)
+        .collect()
 }
 
 // NOTE: apply_profile has been removed. Profile application logic is now centralized
@@ -142,7 +547,8 @@ pub fn get_profile(name: &str) -> Option<ProfileDefinition> {
 // Hardware > User Overrides > Profile Defaults
 //
 // profiles.rs is now a pure data provider - it only exposes get_available_profiles()
-// and get_profile() for retrieving ProfileDefinition data structures.
+// and get_profile() for retrieving ProfileDefinition data structures (built-in or
+// loaded from user_profiles_dir()).
 
 #[cfg(test)]
 mod tests {
@@ -221,4 +627,138 @@ mod tests {
         assert_eq!(profile.hz, 1000);
         assert!(profile.native_optimizations);
     }
+
+    fn write_profile(dir: &Path, name: &str, content: &str) {
+        fs::write(dir.join(format!("{}.toml", name)), content).unwrap();
+    }
+
+    #[test]
+    fn test_user_profile_inherits_from_builtin() {
+        let dir = tempfile::TempDir::new().unwrap();
+        write_profile(
+            dir.path(),
+            "team-gaming",
+            r#"
+            name = "Team Gaming"
+            base = "gaming"
+            hz = 750
+            lto = "full"
+
+            [config_options]
+            CONFIG_TCP_CONG_BBR = "y"
+            "#,
+        );
+
+        let load = load_user_profiles(dir.path());
+        assert!(load.errors.is_empty(), "{:?}", load.errors);
+
+        let profile = &load.profiles["team-gaming"];
+        assert_eq!(profile.name, "Team Gaming");
+        assert_eq!(profile.hz, 750);
+        assert_eq!(profile.default_lto, LtoType::Full);
+        // Inherited from gaming
+        assert_eq!(profile.preemption, "Full");
+        assert!(profile.use_polly);
+        assert_eq!(
+            profile.config_options.get("CONFIG_TCP_CONG_BBR"),
+            Some(&"y".to_string())
+        );
+        assert!(profile.is_user_defined());
+    }
+
+    #[test]
+    fn test_user_profile_chain_and_default_base() {
+        let dir = tempfile::TempDir::new().unwrap();
+        write_profile(
+            dir.path(),
+            "base-lab",
+            "hz = 250\n[config_options]\nCONFIG_KASAN = \"n\"\n",
+        );
+        write_profile(
+            dir.path(),
+            "lab",
+            "base = \"base-lab\"\ncmdline = [\"quiet\"]\n[config_options]\nCONFIG_KCSAN = \"n\"\n",
+        );
+
+        let load = load_user_profiles(dir.path());
+        assert!(load.errors.is_empty(), "{:?}", load.errors);
+
+        // No base -> generic
+        let base_lab = &load.profiles["base-lab"];
+        assert_eq!(base_lab.preemption, "Voluntary");
+        assert_eq!(base_lab.hz, 250);
+        assert_eq!(base_lab.cmdline, vec!["nowatchdog".to_string()]);
+
+        // Options accumulate along the chain
+        let lab = &load.profiles["lab"];
+        assert_eq!(lab.hz, 250);
+        assert_eq!(lab.config_options.len(), 2);
+        // A profile's cmdline replaces the inherited one
+        assert_eq!(lab.cmdline, vec!["quiet".to_string()]);
+    }
+
+    #[test]
+    fn test_user_profile_rejections() {
+        let dir = tempfile::TempDir::new().unwrap();
+        write_profile(dir.path(), "gaming", "hz = 100\n");
+        write_profile(dir.path(), "loop-a", "base = \"loop-b\"\n");
+        write_profile(dir.path(), "loop-b", "base = \"loop-a\"\n");
+        write_profile(dir.path(), "orphan", "base = \"missing\"\n");
+        write_profile(dir.path(), "bad-hz", "hz = 123\n");
+        write_profile(
+            dir.path(),
+            "bad-option",
+            "[config_options]\nCONFIG_CMDLINE = \"quiet splash\"\n",
+        );
+        write_profile(
+            dir.path(),
+            "bad-cmdline",
+            "cmdline = [\"preempt=full\", \"preempt=none\"]\n",
+        );
+        write_profile(dir.path(), "typo", "hzz = 1000\n");
+        write_profile(dir.path(), "ok", "description = \"fine\"\n");
+
+        let load = load_user_profiles(dir.path());
+        assert_eq!(load.profiles.len(), 1);
+        assert!(load.profiles.contains_key("ok"));
+        assert_eq!(load.errors.len(), 8);
+        assert!(load
+            .errors
+            .iter()
+            .any(|(_, e)| e.to_string().contains("cycle")));
+    }
+
+    #[test]
+    fn test_user_profile_cache_rescans_after_interval() {
+        let dir = tempfile::TempDir::new().unwrap();
+        write_profile(dir.path(), "first", "hz = 250\n");
+        let start = Instant::now();
+        let mut cache = None;
+
+        let profiles = cached_user_profiles(&mut cache, dir.path(), start);
+        assert_eq!(profiles.len(), 1);
+
+        // Within the interval the directory is not looked at again
+        write_profile(dir.path(), "second", "hz = 500\n");
+        let soon = start + USER_PROFILES_RESCAN_INTERVAL / 2;
+        assert_eq!(cached_user_profiles(&mut cache, dir.path(), soon).len(), 1);
+
+        let later = start + USER_PROFILES_RESCAN_INTERVAL;
+        let profiles = cached_user_profiles(&mut cache, dir.path(), later);
+        assert_eq!(profiles.len(), 2);
+        assert_eq!(profiles["second"].hz, 500);
+    }
+
+    #[test]
+    fn test_user_profiles_dir_is_in_user_config_dir() {
+        let dir = user_profiles_dir().unwrap();
+        assert!(dir.ends_with("goatdkernel/profiles"));
+    }
+
+    #[test]
+    fn test_load_user_profiles_missing_dir() {
+        let load = load_user_profiles(Path::new("/nonexistent/goatd/profiles"));
+        assert!(load.profiles.is_empty());
+        assert!(load.errors.is_empty());
+    }
 }
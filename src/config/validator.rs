@@ -1,7 +1,7 @@
 //! Config validation.
 
 use crate::error::ConfigError;
-use crate::models::{KernelConfig, LtoType};
+use crate::models::{KernelCmdlineConfig, KernelConfig, LtoType};
 use std::collections::HashMap;
 
 /// Validate kernel version (X.Y.Z format or "latest" sentinel).
@@ -164,10 +164,17 @@ pub fn detect_conflicts(config: &KernelConfig) -> Result<(), ConfigError> {
     Ok(())
 }
 
+/// Validate the user's command line additions and removals on their own
+/// (parameter syntax, duplicate keys, added-and-removed keys).
+pub fn validate_cmdline(cmdline: &KernelCmdlineConfig) -> Result<(), ConfigError> {
+    super::cmdline::KernelCmdline::resolve(&[], &[], cmdline).map(|_| ())
+}
+
 /// Comprehensive validation of all config params.
 pub fn validate_all(config: &KernelConfig) -> Result<(), ConfigError> {
     validate_kernel_version(&config.version)?;
     validate_config_options(&config.config_options)?;
+    validate_cmdline(&config.cmdline)?;
     detect_conflicts(config)?;
     Ok(())
 }
@@ -404,6 +411,19 @@ mod tests {
         assert!(validate_all(&config).is_err());
     }
 
+    #[test]
+    fn test_validate_all_invalid_cmdline() {
+        let mut config = KernelConfig::default();
+        config.cmdline.additions = vec!["quiet".to_string(), "mitigations=off".to_string()];
+        assert!(validate_all(&config).is_ok());
+
+        config.cmdline.removals = vec!["mitigations".to_string()];
+        assert!(matches!(
+            validate_all(&config),
+            Err(ConfigError::ConflictDetected(_))
+        ));
+    }
+
     #[test]
     fn test_validate_all_conflict() {
         let config = KernelConfig {
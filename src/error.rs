@@ -3,6 +3,7 @@
 //! Provides structured error handling with HardwareError, ConfigError, BuildError,
 //! PatchError, ValidationError, and AppError.
 
+use crate::orchestrator::failure::BuildFailureDiagnosis;
 use std::io;
 use thiserror::Error;
 
@@ -34,6 +35,9 @@ pub enum ConfigError {
     #[error("Invalid JSON in config: {0}")]
     InvalidJson(#[from] serde_json::Error),
 
+    #[error("Invalid TOML in config: {0}")]
+    InvalidToml(String),
+
     #[error("Configuration validation failed: {0}")]
     ValidationFailed(String),
 
@@ -59,6 +63,12 @@ pub enum BuildError {
     #[error("Build phase failed: {0}")]
     BuildFailed(String),
 
+    #[error("Build phase failed: {message}\n{diagnosis}")]
+    Diagnosed {
+        message: String,
+        diagnosis: Box<BuildFailureDiagnosis>,
+    },
+
     #[error("Build cancelled by user")]
     BuildCancelled,
 
@@ -66,6 +76,16 @@ pub enum BuildError {
     ValidationFailed(String),
 }
 
+impl BuildError {
+    /// The recognised failure attached by the build-log analyzer, if any.
+    pub fn diagnosis(&self) -> Option<&BuildFailureDiagnosis> {
+        match self {
+            BuildError::Diagnosed { diagnosis, .. } => Some(diagnosis),
+            _ => None,
+        }
+    }
+}
+
 /// Kernel patching operation errors.
 #[derive(Error, Debug)]
 pub enum PatchError {
@@ -80,6 +100,9 @@ pub enum PatchError {
 
     #[error("Patch validation failed: {0}")]
     ValidationFailed(String),
+
+    #[error("Patch '{patch}' does not apply: {details}")]
+    Conflict { patch: String, details: String },
 }
 
 /// Build result validation errors.
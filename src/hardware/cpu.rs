@@ -1,8 +1,10 @@
 //! CPU detection and identification module.
 
 use crate::error::HardwareError;
+use crate::models::{CoreType, CpuSelection};
 use std::collections::HashSet;
 use std::fs;
+use std::path::Path;
 
 /// Parse /proc/cpuinfo and extract CPU model, cores, and threads.
 fn parse_cpuinfo() -> (String, u32, u32) {
@@ -90,6 +92,117 @@ pub fn detect_cpu_vendor() -> Result<String, HardwareError> {
     }
 }
 
+/// Online logical CPUs and their core types.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct CpuTopology {
+    /// Online logical CPUs, ascending
+    pub online: Vec<usize>,
+    /// CPUs listed by the `cpu_core` PMU (empty on non-hybrid parts)
+    pub performance: Vec<usize>,
+    /// CPUs listed by the `cpu_atom` PMU (empty on non-hybrid parts)
+    pub efficiency: Vec<usize>,
+}
+
+impl CpuTopology {
+    /// Whether the processor mixes performance and efficiency cores.
+    pub fn is_hybrid(&self) -> bool {
+        !self.performance.is_empty() && !self.efficiency.is_empty()
+    }
+
+    /// Core type of `cpu`.
+    pub fn core_type(&self, cpu: usize) -> CoreType {
+        if self.performance.contains(&cpu) {
+            CoreType::Performance
+        } else if self.efficiency.contains(&cpu) {
+            CoreType::Efficiency
+        } else {
+            CoreType::Standard
+        }
+    }
+
+    /// Online CPUs of the given core type.
+    pub fn cpus_of(&self, core_type: CoreType) -> Vec<usize> {
+        self.online
+            .iter()
+            .copied()
+            .filter(|&cpu| self.core_type(cpu) == core_type)
+            .collect()
+    }
+
+    /// Resolve a benchmark CPU selection to online CPUs, ascending.
+    ///
+    /// `Single` (and any selection that matches no online CPU) yields `core_id`.
+    pub fn resolve_selection(&self, selection: &CpuSelection, core_id: usize) -> Vec<usize> {
+        let cpus: Vec<usize> = match selection {
+            CpuSelection::Single => Vec::new(),
+            CpuSelection::Cpus(cpus) => {
+                let mut cpus: Vec<usize> = cpus
+                    .iter()
+                    .copied()
+                    .filter(|cpu| self.online.contains(cpu))
+                    .collect();
+                cpus.sort_unstable();
+                cpus.dedup();
+                cpus
+            }
+            CpuSelection::CoreType(core_type) => self.cpus_of(*core_type),
+            CpuSelection::All => self.online.clone(),
+        };
+        if cpus.is_empty() {
+            vec![core_id]
+        } else {
+            cpus
+        }
+    }
+}
+
+/// Parse a kernel CPU list such as "0-7,16,18-19".
+pub fn parse_cpu_list(list: &str) -> Vec<usize> {
+    let mut cpus: Vec<usize> = list
+        .trim()
+        .split(',')
+        .filter(|part| !part.is_empty())
+        .flat_map(|part| match part.split_once('-') {
+            Some((start, end)) => match (start.trim().parse(), end.trim().parse()) {
+                (Ok(start), Ok(end)) => (start..=end).collect(),
+                _ => Vec::new(),
+            },
+            None => part.trim().parse().into_iter().collect(),
+        })
+        .collect();
+    cpus.sort_unstable();
+    cpus.dedup();
+    cpus
+}
+
+/// Detect online CPUs and hybrid core types from sysfs.
+pub fn detect_cpu_topology() -> CpuTopology {
+    detect_cpu_topology_at(Path::new("/sys"))
+}
+
+/// Detect online CPUs and hybrid core types below `sys_root`.
+///
+/// Falls back to CPUs 0..threads when the online list is unavailable.
+pub fn detect_cpu_topology_at(sys_root: &Path) -> CpuTopology {
+    let read_list = |rel: &str| {
+        fs::read_to_string(sys_root.join(rel))
+            .map(|list| parse_cpu_list(&list))
+            .unwrap_or_default()
+    };
+
+    let mut online = read_list("devices/system/cpu/online");
+    if online.is_empty() {
+        let (_, _, threads) = parse_cpuinfo();
+        online = (0..threads as usize).collect();
+    }
+
+    CpuTopology {
+        online,
+        performance: read_list("devices/cpu_core/cpus"),
+        efficiency: read_list("devices/cpu_atom/cpus"),
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -128,4 +241,79 @@ mod tests {
         let threads = detect_cpu_threads().unwrap();
         assert!(cores <= threads);
     }
+
+    #[test]
+    fn test_parse_cpu_list() {
+        assert_eq!(parse_cpu_list("0-3,8,10-11\n"), vec![0, 1, 2, 3, 8, 10, 11]);
+        assert_eq!(parse_cpu_list("5"), vec![5]);
+        assert!(parse_cpu_list("").is_empty());
+    }
+
+    #[test]
+    fn test_detect_hybrid_topology_fake_sysfs() {
+        let sys_root = tempfile::tempdir().unwrap();
+        let write = |rel: &str, content: &str| {
+            let path = sys_root.path().join(rel);
+            fs::create_dir_all(path.parent().unwrap()).unwrap();
+            fs::write(path, content).unwrap();
+        };
+        write("devices/system/cpu/online", "0-11\n");
+        let topology = detect_cpu_topology_at(sys_root.path());
+        assert!(!topology.is_hybrid());
+        assert_eq!(topology.core_type(3), CoreType::Standard);
+
+        // 2 P-cores with SMT and 8 E-cores
+        write("devices/cpu_core/cpus", "0-3\n");
+        write("devices/cpu_atom/cpus", "4-11\n");
+        let topology = detect_cpu_topology_at(sys_root.path());
+        assert!(topology.is_hybrid());
+        assert_eq!(topology.online.len(), 12);
+        assert_eq!(topology.core_type(2), CoreType::Performance);
+        assert_eq!(topology.core_type(9), CoreType::Efficiency);
+        assert_eq!(topology.cpus_of(CoreType::Performance), vec![0, 1, 2, 3]);
+        assert_eq!(topology.cpus_of(CoreType::Efficiency).len(), 8);
+    }
+
+    fn hybrid_topology() -> CpuTopology {
+        CpuTopology {
+            online: (0..12).collect(),
+            performance: (0..4).collect(),
+            efficiency: (4..12).collect(),
+        }
+    }
+
+    #[test]
+    fn test_resolve_cpu_selection() {
+        let topology = hybrid_topology();
+        assert_eq!(
+            topology.resolve_selection(&CpuSelection::Single, 2),
+            vec![2]
+        );
+        assert_eq!(
+            topology.resolve_selection(&CpuSelection::CoreType(CoreType::Performance), 0),
+            vec![0, 1, 2, 3]
+        );
+        assert_eq!(
+            topology
+                .resolve_selection(&CpuSelection::CoreType(CoreType::Efficiency), 0)
+                .len(),
+            8
+        );
+        assert_eq!(topology.resolve_selection(&CpuSelection::All, 0).len(), 12);
+        // Offline and duplicate CPUs are dropped
+        assert_eq!(
+            topology.resolve_selection(&CpuSelection::Cpus(vec![5, 1, 5, 40]), 0),
+            vec![1, 5]
+        );
+        // Non-hybrid parts have no E-cores: fall back to the configured core
+        let flat = CpuTopology {
+            online: (0..4).collect(),
+            ..CpuTopology::default()
+        };
+        assert_eq!(
+            flat.resolve_selection(&CpuSelection::CoreType(CoreType::Efficiency), 1),
+            vec![1]
+        );
+        assert_eq!(flat.resolve_selection(&CpuSelection::Single, 1), vec![1]);
+    }
 }
@@ -336,7 +336,7 @@ fn get_booted_kernel_version() -> String {
 }
 
 /// Extract kernel config from vmlinuz binary using IKCFG_ST magic header
-fn extract_ikconfig(vmlinuz_path: &std::path::Path) -> Option<String> {
+pub(crate) fn extract_ikconfig(vmlinuz_path: &std::path::Path) -> Option<String> {
     match std::fs::read(vmlinuz_path) {
         Ok(binary) => {
             let magic = b"IKCFG_ST";
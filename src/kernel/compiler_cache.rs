@@ -0,0 +1,330 @@
+//! Compiler cache (ccache / sccache) integration.
+//!
+//! The kernel Makefile assigns `CC`/`HOSTCC` itself, so exporting
+//! `CC="ccache clang"` is not reliable. Instead a small directory of wrapper
+//! scripts (`clang`, `gcc`, `cc`, ...) is placed at the front of the purified
+//! `PATH`; each wrapper execs the cache with the absolute path of the real
+//! compiler. This covers both the Clang/LLVM and GCC toolchains.
+//!
+//! Cache statistics are snapshotted before and after the build and diffed into
+//! a [`CompilerCacheStats`] for the build log and `BuildResult`.
+
+use crate::error::AppError;
+use crate::models::{CompilerCache, CompilerCacheConfig, CompilerCacheStats};
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::process::Command;
+
+/// Directory (relative to the kernel source root) holding the wrapper scripts.
+pub const WRAPPER_DIR_NAME: &str = ".compiler_cache_bin";
+
+/// Compilers wrapped by the cache.
+pub const WRAPPED_COMPILERS: &[&str] = &["clang", "clang++", "gcc", "g++", "cc", "c++"];
+
+/// Default cache directory for a backend (`~/.cache/ccache`, `~/.cache/sccache`).
+pub fn default_cache_dir(backend: CompilerCache) -> Option<PathBuf> {
+    let name = backend.binary()?;
+    let base = dirs::cache_dir()
+        .or_else(|| dirs::home_dir().map(|h| h.join(".cache")))
+        .unwrap_or_else(|| PathBuf::from("/tmp"));
+    Some(base.join(name))
+}
+
+/// Find an executable named `name` in a colon-separated search path.
+pub fn find_in_path(name: &str, search_path: &str) -> Option<PathBuf> {
+    search_path
+        .split(':')
+        .filter(|dir| !dir.is_empty())
+        .map(|dir| Path::new(dir).join(name))
+        .find(|candidate| is_executable(candidate))
+}
+
+fn is_executable(path: &Path) -> bool {
+    use std::os::unix::fs::PermissionsExt;
+    fs::metadata(path)
+        .map(|m| m.is_file() && m.permissions().mode() & 0o111 != 0)
+        .unwrap_or(false)
+}
+
+/// Compiler cache wired into one kernel build.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CompilerCacheSession {
+    /// Selected backend (never `CompilerCache::None`)
+    pub backend: CompilerCache,
+    /// Cache storage directory
+    pub cache_dir: PathBuf,
+    /// Directory holding the compiler wrapper scripts
+    pub wrapper_dir: PathBuf,
+    /// Optional size limit passed to the backend
+    pub max_size: Option<String>,
+    /// Kernel source root, used as ccache base dir
+    pub base_dir: PathBuf,
+}
+
+impl CompilerCacheSession {
+    /// Build a session from the config, or None if the cache is disabled.
+    pub fn from_config(config: &CompilerCacheConfig, src_dir: &Path) -> Option<Self> {
+        if !config.is_enabled() {
+            return None;
+        }
+        let cache_dir = config
+            .dir
+            .clone()
+            .or_else(|| default_cache_dir(config.backend))?;
+        Some(CompilerCacheSession {
+            backend: config.backend,
+            cache_dir,
+            wrapper_dir: src_dir.join(WRAPPER_DIR_NAME),
+            max_size: config.max_size.clone(),
+            base_dir: src_dir.to_path_buf(),
+        })
+    }
+
+    /// Name of the backend executable.
+    pub fn binary(&self) -> &'static str {
+        self.backend.binary().unwrap_or("ccache")
+    }
+
+    /// Write wrapper scripts for every compiler found in `search_path`.
+    ///
+    /// The wrapper directory itself is skipped while resolving the real
+    /// compilers so a wrapper never execs itself. Returns the number of
+    /// wrappers written.
+    pub fn install_wrappers(&self, search_path: &str) -> Result<usize, AppError> {
+        use std::os::unix::fs::PermissionsExt;
+
+        let cache_bin = find_in_path(self.binary(), search_path).ok_or_else(|| {
+            AppError::KernelConfig(format!(
+                "{} is enabled but was not found in PATH",
+                self.binary()
+            ))
+        })?;
+
+        fs::create_dir_all(&self.wrapper_dir)?;
+        fs::create_dir_all(&self.cache_dir)?;
+
+        let wrapper_dir = self.wrapper_dir.to_string_lossy().to_string();
+        let real_path: Vec<&str> = search_path
+            .split(':')
+            .filter(|dir| *dir != wrapper_dir)
+            .collect();
+        let real_path = real_path.join(":");
+
+        let mut written = 0;
+        for compiler in WRAPPED_COMPILERS {
+            let wrapper = self.wrapper_dir.join(compiler);
+            let Some(real) = find_in_path(compiler, &real_path) else {
+                let _ = fs::remove_file(&wrapper);
+                continue;
+            };
+            let script = format!(
+                "#!/bin/sh\nexec '{}' '{}' \"$@\"\n",
+                cache_bin.display(),
+                real.display()
+            );
+            fs::write(&wrapper, script)?;
+            fs::set_permissions(&wrapper, fs::Permissions::from_mode(0o755))?;
+            written += 1;
+        }
+        Ok(written)
+    }
+
+    /// Environment variables configuring the backend.
+    pub fn env_vars(&self) -> Vec<(String, String)> {
+        let dir = self.cache_dir.to_string_lossy().to_string();
+        let mut vars = Vec::new();
+        match self.backend {
+            CompilerCache::Ccache => {
+                vars.push(("CCACHE_DIR".to_string(), dir));
+                vars.push((
+                    "CCACHE_BASEDIR".to_string(),
+                    self.base_dir.to_string_lossy().to_string(),
+                ));
+                // Kernel objects embed __DATE__/__TIME__ only via KBUILD_BUILD_TIMESTAMP
+                vars.push((
+                    "CCACHE_SLOPPINESS".to_string(),
+                    "time_macros,include_file_mtime,include_file_ctime".to_string(),
+                ));
+                if let Some(size) = &self.max_size {
+                    vars.push(("CCACHE_MAXSIZE".to_string(), size.clone()));
+                }
+            }
+            CompilerCache::Sccache => {
+                vars.push(("SCCACHE_DIR".to_string(), dir));
+                if let Some(size) = &self.max_size {
+                    vars.push(("SCCACHE_CACHE_SIZE".to_string(), size.clone()));
+                }
+            }
+            CompilerCache::None => {}
+        }
+        vars
+    }
+
+    /// Current cumulative statistics reported by the backend.
+    pub fn snapshot(&self) -> Option<CompilerCacheStats> {
+        let args: &[&str] = match self.backend {
+            CompilerCache::Ccache => &["--print-stats"],
+            CompilerCache::Sccache => &["--show-stats", "--stats-format=json"],
+            CompilerCache::None => return None,
+        };
+        let output = Command::new(self.binary())
+            .args(args)
+            .envs(self.env_vars())
+            .output()
+            .ok()?;
+        if !output.status.success() {
+            return None;
+        }
+        let text = String::from_utf8_lossy(&output.stdout);
+        match self.backend {
+            CompilerCache::Ccache => parse_ccache_stats(&text),
+            CompilerCache::Sccache => parse_sccache_stats(&text),
+            CompilerCache::None => None,
+        }
+    }
+}
+
+/// Parse `ccache --print-stats` (tab-separated `key<TAB>value` lines).
+pub fn parse_ccache_stats(text: &str) -> Option<CompilerCacheStats> {
+    let mut stats = CompilerCacheStats {
+        backend: CompilerCache::Ccache,
+        ..Default::default()
+    };
+    let mut seen = false;
+    for line in text.lines() {
+        let mut parts = line.split_whitespace();
+        let (Some(key), Some(value)) = (parts.next(), parts.next()) else {
+            continue;
+        };
+        let Ok(value) = value.parse::<u64>() else {
+            continue;
+        };
+        match key {
+            "direct_cache_hit" | "preprocessed_cache_hit" => stats.hits += value,
+            "cache_miss" => stats.misses += value,
+            "cache_size_kibibyte" => stats.size_bytes = value * 1024,
+            _ => continue,
+        }
+        seen = true;
+    }
+    seen.then_some(stats)
+}
+
+/// Parse `sccache --show-stats --stats-format=json`.
+pub fn parse_sccache_stats(text: &str) -> Option<CompilerCacheStats> {
+    let json: serde_json::Value = serde_json::from_str(text.trim()).ok()?;
+    let stats = json.get("stats")?;
+    let sum_counts = |key: &str| -> u64 {
+        stats
+            .get(key)
+            .and_then(|v| v.get("counts"))
+            .and_then(|c| c.as_object())
+            .map(|counts| counts.values().filter_map(|v| v.as_u64()).sum())
+            .unwrap_or(0)
+    };
+    Some(CompilerCacheStats {
+        backend: CompilerCache::Sccache,
+        hits: sum_counts("cache_hits"),
+        misses: sum_counts("cache_misses"),
+        size_bytes: json.get("cache_size").and_then(|v| v.as_u64()).unwrap_or(0),
+    })
+}
+
+/// Statistics attributable to one build: counters since `before`, size from `after`.
+pub fn stats_delta(
+    before: Option<CompilerCacheStats>,
+    after: CompilerCacheStats,
+) -> CompilerCacheStats {
+    let before = before.unwrap_or_default();
+    CompilerCacheStats {
+        backend: after.backend,
+        hits: after.hits.saturating_sub(before.hits),
+        misses: after.misses.saturating_sub(before.misses),
+        size_bytes: after.size_bytes,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    #[test]
+    fn test_parse_ccache_stats() {
+        let text = "stats_updated_timestamp\t1700000000\n\
+                    direct_cache_hit\t120\n\
+                    preprocessed_cache_hit\t30\n\
+                    cache_miss\t50\n\
+                    cache_size_kibibyte\t2048\n";
+        let stats = parse_ccache_stats(text).unwrap();
+        assert_eq!(stats.hits, 150);
+        assert_eq!(stats.misses, 50);
+        assert_eq!(stats.size_bytes, 2048 * 1024);
+        assert!((stats.hit_rate() - 75.0).abs() < f64::EPSILON);
+        assert!(parse_ccache_stats("garbage").is_none());
+    }
+
+    #[test]
+    fn test_parse_sccache_stats() {
+        let text = r#"{"stats":{"cache_hits":{"counts":{"C/C++":40,"Assembler":2}},
+            "cache_misses":{"counts":{"C/C++":8}}},"cache_size":1048576,"max_cache_size":10737418240}"#;
+        let stats = parse_sccache_stats(text).unwrap();
+        assert_eq!(stats.backend, CompilerCache::Sccache);
+        assert_eq!(stats.hits, 42);
+        assert_eq!(stats.misses, 8);
+        assert_eq!(stats.size_bytes, 1048576);
+    }
+
+    #[test]
+    fn test_stats_delta() {
+        let before = CompilerCacheStats {
+            backend: CompilerCache::Ccache,
+            hits: 100,
+            misses: 10,
+            size_bytes: 1,
+        };
+        let after = CompilerCacheStats {
+            backend: CompilerCache::Ccache,
+            hits: 130,
+            misses: 20,
+            size_bytes: 5,
+        };
+        let delta = stats_delta(Some(before), after);
+        assert_eq!((delta.hits, delta.misses, delta.size_bytes), (30, 10, 5));
+        assert_eq!(stats_delta(None, after), after);
+    }
+
+    #[test]
+    fn test_install_wrappers_resolves_real_compiler() {
+        use std::os::unix::fs::PermissionsExt;
+
+        let tmp = TempDir::new().unwrap();
+        let bin = tmp.path().join("bin");
+        fs::create_dir_all(&bin).unwrap();
+        for tool in ["ccache", "clang"] {
+            let path = bin.join(tool);
+            fs::write(&path, "#!/bin/sh\n").unwrap();
+            fs::set_permissions(&path, fs::Permissions::from_mode(0o755)).unwrap();
+        }
+
+        let config = CompilerCacheConfig {
+            backend: CompilerCache::Ccache,
+            dir: Some(tmp.path().join("cache")),
+            max_size: Some("5G".to_string()),
+        };
+        let src = tmp.path().join("src");
+        let session = CompilerCacheSession::from_config(&config, &src).unwrap();
+
+        let search_path = format!("{}:{}", session.wrapper_dir.display(), bin.display());
+        assert_eq!(session.install_wrappers(&search_path).unwrap(), 1);
+
+        let script = fs::read_to_string(session.wrapper_dir.join("clang")).unwrap();
+        assert!(script.contains(&bin.join("ccache").display().to_string()));
+        assert!(script.contains(&bin.join("clang").display().to_string()));
+        assert!(!session.wrapper_dir.join("gcc").exists());
+
+        let env = session.env_vars();
+        assert!(env.contains(&("CCACHE_MAXSIZE".to_string(), "5G".to_string())));
+        assert!(CompilerCacheSession::from_config(&CompilerCacheConfig::default(), &src).is_none());
+    }
+}
@@ -0,0 +1,827 @@
+//! Kconfig diffing between two kernel builds or against the running kernel.
+//!
+//! A `.config` can come from a plain file, a gzip-compressed file such as
+//! `/proc/config.gz`, or a built `.pkg.tar.zst` (the headers package's
+//! `build/.config`, falling back to the embedded ikconfig of `vmlinuz`).
+//!
+//! Symbols are compared by their effective value: a symbol that is missing or
+//! `# CONFIG_X is not set` counts as unset. The result lists added, removed and
+//! changed symbols grouped by subsystem and serializes to JSON for scripting.
+
+use serde::Serialize;
+use std::collections::BTreeMap;
+use std::fs;
+use std::io::Read;
+use std::path::{Path, PathBuf};
+use std::process::Command;
+
+use crate::error::AppError;
+use crate::kernel::audit::SystemAudit;
+use crate::kernel::manager::KernelPackage;
+
+/// Parsed Kconfig: symbol name without the `CONFIG_` prefix -> value.
+/// Unset symbols are stored with the value `n`.
+pub type KconfigMap = BTreeMap<String, String>;
+
+/// Subsystem buckets, checked in order; the first matching symbol prefix wins.
+const SUBSYSTEM_PREFIXES: &[(&str, &[&str])] = &[
+    (
+        "Compiler & Build",
+        &[
+            "CC_", "CLANG_", "GCC_", "LD_", "AS_", "LTO", "RUSTC_", "TOOLS_", "PAHOLE_", "CFI_",
+            "OBJTOOL",
+        ],
+    ),
+    (
+        "Debugging",
+        &[
+            "DEBUG_",
+            "KASAN",
+            "KCSAN",
+            "KMSAN",
+            "UBSAN",
+            "KGDB",
+            "FTRACE",
+            "FUNCTION_TRACER",
+            "KPROBE",
+            "BPF_KPROBE",
+            "LOCKDEP",
+            "PROVE_",
+            "DYNAMIC_DEBUG",
+        ],
+    ),
+    (
+        "Security",
+        &[
+            "SECURITY",
+            "LSM",
+            "HARDENED_",
+            "FORTIFY_",
+            "STACKPROTECTOR",
+            "INIT_ON_",
+            "RANDOMIZE_",
+            "MITIGATION_",
+            "RETPOLINE",
+            "INTEGRITY",
+            "IMA_",
+            "EVM_",
+            "LOCK_DOWN",
+            "MODULE_SIG",
+            "SYSTEM_TRUSTED",
+            "STRICT_",
+        ],
+    ),
+    ("Crypto", &["CRYPTO", "KEYS", "ASYMMETRIC_"]),
+    (
+        "Scheduler & Timers",
+        &[
+            "SCHED_",
+            "PREEMPT",
+            "HZ",
+            "NO_HZ",
+            "RCU_",
+            "TREE_RCU",
+            "BORE",
+            "SCX",
+            "CGROUP_SCHED",
+            "FAIR_GROUP",
+            "RT_GROUP",
+            "HIGH_RES_TIMERS",
+            "TICK_",
+        ],
+    ),
+    (
+        "Memory Management",
+        &[
+            "LRU_GEN",
+            "TRANSPARENT_HUGEPAGE",
+            "HUGETLB",
+            "ZSWAP",
+            "ZRAM",
+            "ZSMALLOC",
+            "SLUB",
+            "SLAB",
+            "MEMCG",
+            "NUMA",
+            "KSM",
+            "COMPACTION",
+            "MIGRATION",
+            "SWAP",
+            "SHMEM",
+            "PAGE_",
+            "MEMORY_",
+        ],
+    ),
+    (
+        "Power Management",
+        &[
+            "PM_",
+            "ACPI",
+            "CPU_FREQ",
+            "CPU_IDLE",
+            "X86_INTEL_PSTATE",
+            "X86_AMD_PSTATE",
+            "SUSPEND",
+            "HIBERNATE",
+            "THERMAL",
+            "ENERGY_",
+        ],
+    ),
+    (
+        "Virtualization",
+        &[
+            "KVM", "VIRTIO", "XEN", "HYPERV", "VHOST", "PARAVIRT", "VFIO",
+        ],
+    ),
+    (
+        "Networking",
+        &[
+            "NET",
+            "INET",
+            "IP_",
+            "IPV6",
+            "TCP_",
+            "NF_",
+            "NETFILTER",
+            "BRIDGE",
+            "VLAN",
+            "WLAN",
+            "WIREGUARD",
+            "BT_",
+            "CFG80211",
+            "MAC80211",
+            "WIRELESS",
+        ],
+    ),
+    (
+        "Block & Storage",
+        &[
+            "BLK_",
+            "BLOCK",
+            "IOSCHED",
+            "MQ_IOSCHED",
+            "SCSI",
+            "ATA",
+            "SATA_",
+            "NVME",
+            "MD_",
+            "DM_",
+            "BCACHE",
+        ],
+    ),
+    (
+        "Filesystems",
+        &[
+            "EXT4_",
+            "BTRFS_",
+            "XFS_",
+            "F2FS_",
+            "NTFS",
+            "VFAT_",
+            "FAT_",
+            "EXFAT_",
+            "NFS",
+            "CIFS",
+            "SMB",
+            "FUSE_",
+            "OVERLAY_FS",
+            "SQUASHFS",
+            "TMPFS",
+            "ZONEFS",
+            "BCACHEFS",
+            "FS_",
+        ],
+    ),
+    ("Graphics", &["DRM", "FB", "BACKLIGHT", "VGA_", "AGP"]),
+    ("Sound", &["SND", "SOUND", "AC97"]),
+    (
+        "USB & Input",
+        &[
+            "USB",
+            "TYPEC",
+            "HID",
+            "INPUT",
+            "KEYBOARD_",
+            "MOUSE_",
+            "JOYSTICK_",
+        ],
+    ),
+    ("Modules", &["MODULE", "MODULES", "MODVERSIONS"]),
+    (
+        "Processor",
+        &[
+            "X86_",
+            "CPU_SUP_",
+            "MCORE2",
+            "MZEN",
+            "MNATIVE",
+            "GENERIC_CPU",
+            "NR_CPUS",
+            "SMP",
+            "MICROCODE",
+            "64BIT",
+        ],
+    ),
+];
+
+/// Bucket name used for symbols no prefix matches.
+pub const OTHER_SUBSYSTEM: &str = "Other";
+
+/// Classify a symbol (without `CONFIG_`) into a subsystem bucket.
+pub fn subsystem_for(symbol: &str) -> &'static str {
+    SUBSYSTEM_PREFIXES
+        .iter()
+        .find(|(_, prefixes)| prefixes.iter().any(|p| symbol.starts_with(p)))
+        .map(|(name, _)| *name)
+        .unwrap_or(OTHER_SUBSYSTEM)
+}
+
+/// Parse `.config` content into a [`KconfigMap`].
+///
+/// Accepts `CONFIG_X=value` and `# CONFIG_X is not set`; everything else is ignored.
+pub fn parse_config(content: &str) -> KconfigMap {
+    let mut map = KconfigMap::new();
+    for line in content.lines() {
+        let line = line.trim();
+        if let Some(rest) = line.strip_prefix("# CONFIG_") {
+            if let Some(symbol) = rest.strip_suffix(" is not set") {
+                map.insert(symbol.to_string(), "n".to_string());
+            }
+        } else if let Some(rest) = line.strip_prefix("CONFIG_") {
+            if let Some((symbol, value)) = rest.split_once('=') {
+                map.insert(symbol.to_string(), value.to_string());
+            }
+        }
+    }
+    map
+}
+
+/// Whether a value means the symbol is enabled/set.
+fn is_set(value: Option<&String>) -> bool {
+    value.is_some_and(|v| v != "n")
+}
+
+/// Old and new value of a changed symbol.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct ValueChange {
+    pub old: String,
+    pub new: String,
+}
+
+/// Differences within one subsystem.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize)]
+pub struct SubsystemDiff {
+    /// Symbols set only in the new config, with their new value
+    pub added: BTreeMap<String, String>,
+    /// Symbols set only in the old config, with their old value
+    pub removed: BTreeMap<String, String>,
+    /// Symbols set in both with different values
+    pub changed: BTreeMap<String, ValueChange>,
+}
+
+impl SubsystemDiff {
+    /// Total number of differing symbols.
+    pub fn len(&self) -> usize {
+        self.added.len() + self.removed.len() + self.changed.len()
+    }
+
+    /// Whether there are no differences.
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+}
+
+/// Kconfig difference between two kernels.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize)]
+pub struct ConfigDiff {
+    /// Description of the old side (file path, package, or running kernel)
+    pub old_label: String,
+    /// Description of the new side
+    pub new_label: String,
+    /// Differences keyed by subsystem; subsystems without changes are omitted
+    pub subsystems: BTreeMap<String, SubsystemDiff>,
+}
+
+impl ConfigDiff {
+    /// Number of added symbols across all subsystems.
+    pub fn added_count(&self) -> usize {
+        self.subsystems.values().map(|s| s.added.len()).sum()
+    }
+
+    /// Number of removed symbols across all subsystems.
+    pub fn removed_count(&self) -> usize {
+        self.subsystems.values().map(|s| s.removed.len()).sum()
+    }
+
+    /// Number of changed symbols across all subsystems.
+    pub fn changed_count(&self) -> usize {
+        self.subsystems.values().map(|s| s.changed.len()).sum()
+    }
+
+    /// Whether the two configs are equivalent.
+    pub fn is_empty(&self) -> bool {
+        self.subsystems.is_empty()
+    }
+
+    /// One-line summary, e.g. `+12 -3 ~7`.
+    pub fn summary(&self) -> String {
+        format!(
+            "+{} -{} ~{}",
+            self.added_count(),
+            self.removed_count(),
+            self.changed_count()
+        )
+    }
+
+    /// Serialize the diff as pretty-printed JSON.
+    pub fn to_json(&self) -> Result<String, AppError> {
+        serde_json::to_string_pretty(self)
+            .map_err(|e| AppError::KernelConfig(format!("Failed to serialize config diff: {}", e)))
+    }
+
+    /// Human-readable report grouped by subsystem.
+    pub fn to_text(&self) -> String {
+        let mut out = format!(
+            "--- {}\n+++ {}\n{}\n",
+            self.old_label,
+            self.new_label,
+            self.summary()
+        );
+        for (subsystem, diff) in &self.subsystems {
+            out.push_str(&format!("\n[{}] ({})\n", subsystem, diff.len()));
+            for (symbol, value) in &diff.added {
+                out.push_str(&format!("  + CONFIG_{}={}\n", symbol, value));
+            }
+            for (symbol, value) in &diff.removed {
+                out.push_str(&format!("  - CONFIG_{}={}\n", symbol, value));
+            }
+            for (symbol, change) in &diff.changed {
+                out.push_str(&format!(
+                    "  ~ CONFIG_{}: {} -> {}\n",
+                    symbol, change.old, change.new
+                ));
+            }
+        }
+        out
+    }
+}
+
+/// Compare two parsed configs.
+pub fn diff_configs(
+    old_label: &str,
+    old: &KconfigMap,
+    new_label: &str,
+    new: &KconfigMap,
+) -> ConfigDiff {
+    let mut diff = ConfigDiff {
+        old_label: old_label.to_string(),
+        new_label: new_label.to_string(),
+        subsystems: BTreeMap::new(),
+    };
+
+    let mut symbols: Vec<&String> = old.keys().chain(new.keys()).collect();
+    symbols.sort();
+    symbols.dedup();
+
+    for symbol in symbols {
+        let old_value = old.get(symbol);
+        let new_value = new.get(symbol);
+        let old_set = is_set(old_value);
+        let new_set = is_set(new_value);
+        if (old_set, new_set) == (false, false) || (old_set && new_set && old_value == new_value) {
+            continue;
+        }
+
+        let old_value = old_value.cloned().unwrap_or_default();
+        let new_value = new_value.cloned().unwrap_or_default();
+        let bucket = diff
+            .subsystems
+            .entry(subsystem_for(symbol).to_string())
+            .or_default();
+        match (old_set, new_set) {
+            (false, true) => {
+                bucket.added.insert(symbol.clone(), new_value);
+            }
+            (true, false) => {
+                bucket.removed.insert(symbol.clone(), old_value);
+            }
+            _ => {
+                bucket.changed.insert(
+                    symbol.clone(),
+                    ValueChange {
+                        old: old_value,
+                        new: new_value,
+                    },
+                );
+            }
+        }
+    }
+
+    diff
+}
+
+/// Where a `.config` is read from.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ConfigSource {
+    /// Plain or gzip-compressed config file (`.config`, `config.gz`)
+    File(PathBuf),
+    /// Built kernel package (`.pkg.tar.zst`)
+    Package(PathBuf),
+    /// The booted kernel as reported by `SystemAudit`
+    Running,
+}
+
+impl ConfigSource {
+    /// Pick a source from a CLI-style argument: `running`, a package, or a file.
+    pub fn from_arg(arg: &str) -> Self {
+        if arg == "running" || arg == "booted" {
+            ConfigSource::Running
+        } else if arg.ends_with(".pkg.tar.zst") {
+            ConfigSource::Package(PathBuf::from(arg))
+        } else {
+            ConfigSource::File(PathBuf::from(arg))
+        }
+    }
+
+    /// Source for a built artifact found by `scan_workspace`.
+    pub fn for_artifact(pkg: &KernelPackage) -> Result<Self, AppError> {
+        pkg.path
+            .clone()
+            .map(ConfigSource::Package)
+            .ok_or_else(|| AppError::InvalidInput(format!("{} has no package path", pkg.name)))
+    }
+
+    /// Human-readable label for reports.
+    pub fn label(&self) -> String {
+        match self {
+            ConfigSource::File(path) | ConfigSource::Package(path) => path.display().to_string(),
+            ConfigSource::Running => match SystemAudit::get_summary() {
+                Ok(summary) => format!("running kernel ({})", summary.kernel_version),
+                Err(_) => "running kernel".to_string(),
+            },
+        }
+    }
+
+    /// Read and parse the config from this source.
+    pub fn load(&self) -> Result<KconfigMap, AppError> {
+        let content = match self {
+            ConfigSource::File(path) => read_config_file(path)?,
+            ConfigSource::Package(path) => read_package_config(path)?,
+            ConfigSource::Running => read_running_config()?,
+        };
+        let map = parse_config(&content);
+        if map.is_empty() {
+            return Err(AppError::KernelConfig(format!(
+                "No CONFIG_ symbols found in {}",
+                self.label()
+            )));
+        }
+        Ok(map)
+    }
+}
+
+/// Diff two config sources.
+pub fn diff_sources(old: &ConfigSource, new: &ConfigSource) -> Result<ConfigDiff, AppError> {
+    let old_map = old.load()?;
+    let new_map = new.load()?;
+    Ok(diff_configs(&old.label(), &old_map, &new.label(), &new_map))
+}
+
+/// Diff a workspace artifact against the booted kernel (artifact is the new side).
+pub fn diff_artifact_against_running(pkg: &KernelPackage) -> Result<ConfigDiff, AppError> {
+    diff_sources(&ConfigSource::Running, &ConfigSource::for_artifact(pkg)?)
+}
+
+/// Read a config file, transparently decompressing gzip.
+pub fn read_config_file(path: &Path) -> Result<String, AppError> {
+    let bytes = fs::read(path)
+        .map_err(|e| AppError::Io(format!("Failed to read {}: {}", path.display(), e)))?;
+    decode_config_bytes(&bytes)
+        .map_err(|e| AppError::KernelConfig(format!("{}: {}", path.display(), e)))
+}
+
+/// Decode config bytes, gunzipping when they carry the gzip magic.
+fn decode_config_bytes(bytes: &[u8]) -> Result<String, String> {
+    if bytes.starts_with(&[0x1f, 0x8b]) {
+        let mut decoded = String::new();
+        flate2::read::GzDecoder::new(bytes)
+            .read_to_string(&mut decoded)
+            .map_err(|e| format!("failed to decompress: {}", e))?;
+        Ok(decoded)
+    } else {
+        String::from_utf8(bytes.to_vec()).map_err(|e| format!("not valid UTF-8: {}", e))
+    }
+}
+
+/// Config of the booted kernel: `/proc/config.gz`, then `/boot/config-<release>`,
+/// then `/lib/modules/<release>/config`.
+pub fn read_running_config() -> Result<String, AppError> {
+    let proc_config = Path::new("/proc/config.gz");
+    if proc_config.exists() {
+        if let Ok(content) = read_config_file(proc_config) {
+            return Ok(content);
+        }
+    }
+
+    let release = SystemAudit::get_summary()
+        .map(|summary| summary.kernel_version)
+        .map_err(AppError::Audit)?;
+    for candidate in [
+        PathBuf::from(format!("/boot/config-{}", release)),
+        PathBuf::from(format!("/lib/modules/{}/config", release)),
+    ] {
+        if candidate.exists() {
+            return read_config_file(&candidate);
+        }
+    }
+
+    Err(AppError::KernelConfig(format!(
+        "No config found for running kernel {} (enable CONFIG_IKCONFIG_PROC)",
+        release
+    )))
+}
+
+/// Config embedded in a built kernel package.
+///
+/// Prefers `build/.config` from the matching headers package next to `package`
+/// (or inside `package` itself), then the ikconfig blob in `vmlinuz`.
+pub fn read_package_config(package: &Path) -> Result<String, AppError> {
+    if !package.is_file() {
+        return Err(AppError::InvalidPath(format!(
+            "Package not found: {}",
+            package.display()
+        )));
+    }
+
+    let mut candidates = vec![package.to_path_buf()];
+    if let Some(headers) = sibling_headers_package(package) {
+        candidates.insert(0, headers);
+    }
+
+    for candidate in &candidates {
+        let entries = list_package(candidate)?;
+        if let Some(entry) = entries.iter().find(|e| e.ends_with("/build/.config")) {
+            let bytes = extract_package_entry(candidate, entry)?;
+            return decode_config_bytes(&bytes).map_err(AppError::KernelConfig);
+        }
+    }
+
+    let entries = list_package(package)?;
+    if let Some(entry) = entries.iter().find(|e| e.ends_with("/vmlinuz")) {
+        let image = tempfile::NamedTempFile::new()?;
+        fs::write(image.path(), extract_package_entry(package, entry)?)?;
+        if let Some(config) = crate::kernel::audit::extract_ikconfig(image.path()) {
+            return Ok(config);
+        }
+    }
+
+    Err(AppError::KernelConfig(format!(
+        "{} contains no build/.config and its vmlinuz has no embedded config",
+        package.display()
+    )))
+}
+
+/// The `-headers-` package built alongside `package`, if present.
+fn sibling_headers_package(package: &Path) -> Option<PathBuf> {
+    let file_name = package.file_name()?.to_str()?;
+    if file_name.contains("-headers-") {
+        return None;
+    }
+    let parent = package.parent()?;
+    let (name, rest) = split_package_name(file_name)?;
+    let headers = parent.join(format!("{}-headers-{}", name, rest));
+    headers.is_file().then_some(headers)
+}
+
+/// Split `linux-goatd-gaming-6.18.3-1-x86_64.pkg.tar.zst` at the first
+/// `-<digit>` into the package name and the version/arch remainder.
+pub(crate) fn split_package_name(file_name: &str) -> Option<(&str, &str)> {
+    let bytes = file_name.as_bytes();
+    (1..bytes.len())
+        .find(|&i| bytes[i - 1] == b'-' && bytes[i].is_ascii_digit())
+        .map(|i| (&file_name[..i - 1], &file_name[i..]))
+}
+
+/// List the entries of a package archive.
+fn list_package(package: &Path) -> Result<Vec<String>, AppError> {
+    let output = Command::new("bsdtar")
+        .arg("-tf")
+        .arg(package)
+        .output()
+        .map_err(|e| AppError::OsCommand {
+            cmd: "bsdtar -tf".to_string(),
+            reason: e.to_string(),
+        })?;
+    if !output.status.success() {
+        return Err(AppError::OsCommand {
+            cmd: format!("bsdtar -tf {}", package.display()),
+            reason: String::from_utf8_lossy(&output.stderr).trim().to_string(),
+        });
+    }
+    Ok(String::from_utf8_lossy(&output.stdout)
+        .lines()
+        .map(|line| line.to_string())
+        .collect())
+}
+
+/// Extract a single archive entry to memory.
+fn extract_package_entry(package: &Path, entry: &str) -> Result<Vec<u8>, AppError> {
+    let output = Command::new("bsdtar")
+        .arg("-xOf")
+        .arg(package)
+        .arg(entry)
+        .output()
+        .map_err(|e| AppError::OsCommand {
+            cmd: "bsdtar -xOf".to_string(),
+            reason: e.to_string(),
+        })?;
+    if !output.status.success() {
+        return Err(AppError::OsCommand {
+            cmd: format!("bsdtar -xOf {} {}", package.display(), entry),
+            reason: String::from_utf8_lossy(&output.stderr).trim().to_string(),
+        });
+    }
+    Ok(output.stdout)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use flate2::write::GzEncoder;
+    use flate2::Compression;
+    use std::io::Write;
+    use tempfile::TempDir;
+
+    const OLD_CONFIG: &str = "\
+# Automatically generated file; DO NOT EDIT.
+CONFIG_HZ_1000=y
+CONFIG_HZ=1000
+CONFIG_PREEMPT=y
+# CONFIG_PREEMPT_VOLUNTARY is not set
+CONFIG_LTO_CLANG_THIN=y
+CONFIG_TCP_CONG_CUBIC=y
+CONFIG_DEFAULT_HOSTNAME=\"archlinux\"
+";
+
+    const NEW_CONFIG: &str = "\
+CONFIG_HZ_1000=y
+CONFIG_HZ=1000
+# CONFIG_PREEMPT is not set
+CONFIG_PREEMPT_VOLUNTARY=y
+CONFIG_LTO_CLANG_FULL=y
+CONFIG_TCP_CONG_CUBIC=m
+CONFIG_TCP_CONG_BBR=y
+CONFIG_DEFAULT_HOSTNAME=\"goatd\"
+";
+
+    #[test]
+    fn test_parse_config_handles_unset_and_strings() {
+        let map = parse_config(OLD_CONFIG);
+        assert_eq!(map.get("HZ"), Some(&"1000".to_string()));
+        assert_eq!(map.get("PREEMPT_VOLUNTARY"), Some(&"n".to_string()));
+        assert_eq!(
+            map.get("DEFAULT_HOSTNAME"),
+            Some(&"\"archlinux\"".to_string())
+        );
+        assert_eq!(map.len(), 7);
+    }
+
+    #[test]
+    fn test_diff_groups_by_subsystem() {
+        let diff = diff_configs(
+            "old",
+            &parse_config(OLD_CONFIG),
+            "new",
+            &parse_config(NEW_CONFIG),
+        );
+
+        let sched = &diff.subsystems["Scheduler & Timers"];
+        assert_eq!(sched.added.get("PREEMPT_VOLUNTARY"), Some(&"y".to_string()));
+        assert_eq!(sched.removed.get("PREEMPT"), Some(&"y".to_string()));
+        assert!(sched.changed.is_empty());
+
+        let build = &diff.subsystems["Compiler & Build"];
+        assert!(build.added.contains_key("LTO_CLANG_FULL"));
+        assert!(build.removed.contains_key("LTO_CLANG_THIN"));
+
+        let net = &diff.subsystems["Networking"];
+        assert_eq!(
+            net.changed.get("TCP_CONG_CUBIC"),
+            Some(&ValueChange {
+                old: "y".to_string(),
+                new: "m".to_string()
+            })
+        );
+        assert!(net.added.contains_key("TCP_CONG_BBR"));
+
+        assert!(diff.subsystems[OTHER_SUBSYSTEM]
+            .changed
+            .contains_key("DEFAULT_HOSTNAME"));
+        assert_eq!(diff.summary(), "+3 -2 ~2");
+    }
+
+    #[test]
+    fn test_subsystem_for() {
+        assert_eq!(subsystem_for("BTRFS_FS"), "Filesystems");
+        assert_eq!(subsystem_for("BT_HCIBTUSB"), "Networking");
+        assert_eq!(subsystem_for("LRU_GEN_ENABLED"), "Memory Management");
+        assert_eq!(subsystem_for("MODULE_SIG_FORCE"), "Security");
+        assert_eq!(subsystem_for("DRM_AMDGPU"), "Graphics");
+        assert_eq!(subsystem_for("LOCALVERSION"), OTHER_SUBSYSTEM);
+    }
+
+    #[test]
+    fn test_identical_configs_produce_empty_diff() {
+        let map = parse_config(OLD_CONFIG);
+        let diff = diff_configs("a", &map, "b", &map);
+        assert!(diff.is_empty());
+
+        // Missing and "is not set" are equivalent
+        let mut without_unset = map.clone();
+        without_unset.remove("PREEMPT_VOLUNTARY");
+        assert!(diff_configs("a", &map, "b", &without_unset).is_empty());
+    }
+
+    #[test]
+    fn test_diff_json_shape() {
+        let diff = diff_configs(
+            "old",
+            &parse_config(OLD_CONFIG),
+            "new",
+            &parse_config(NEW_CONFIG),
+        );
+        let json: serde_json::Value = serde_json::from_str(&diff.to_json().unwrap()).unwrap();
+        assert_eq!(json["old_label"], "old");
+        assert_eq!(
+            json["subsystems"]["Networking"]["changed"]["TCP_CONG_CUBIC"]["new"],
+            "m"
+        );
+    }
+
+    #[test]
+    fn test_read_gzip_config() {
+        let dir = TempDir::new().unwrap();
+        let path = dir.path().join("config.gz");
+        let mut encoder = GzEncoder::new(Vec::new(), Compression::default());
+        encoder.write_all(OLD_CONFIG.as_bytes()).unwrap();
+        fs::write(&path, encoder.finish().unwrap()).unwrap();
+
+        let source = ConfigSource::from_arg(path.to_str().unwrap());
+        assert_eq!(source, ConfigSource::File(path.clone()));
+        assert_eq!(source.load().unwrap(), parse_config(OLD_CONFIG));
+    }
+
+    #[test]
+    fn test_split_package_name() {
+        assert_eq!(
+            split_package_name("linux-goatd-gaming-6.18.3-1-x86_64.pkg.tar.zst"),
+            Some(("linux-goatd-gaming", "6.18.3-1-x86_64.pkg.tar.zst"))
+        );
+        assert_eq!(split_package_name("nodigits"), None);
+    }
+
+    #[test]
+    fn test_read_config_from_headers_package() {
+        if !crate::kernel::signing::tool_available("bsdtar") {
+            eprintln!("Skipping: bsdtar not available");
+            return;
+        }
+
+        let dir = TempDir::new().unwrap();
+        let root = dir.path().join("root");
+        let build = root.join("usr/lib/modules/6.18.3-goatd/build");
+        fs::create_dir_all(&build).unwrap();
+        fs::write(build.join(".config"), NEW_CONFIG).unwrap();
+
+        let main_pkg = dir.path().join("linux-goatd-6.18.3-1-x86_64.pkg.tar.zst");
+        let headers_pkg = dir
+            .path()
+            .join("linux-goatd-headers-6.18.3-1-x86_64.pkg.tar.zst");
+        for pkg in [&main_pkg, &headers_pkg] {
+            let status = Command::new("bsdtar")
+                .arg("--zstd")
+                .arg("-cf")
+                .arg(pkg)
+                .arg("-C")
+                .arg(&root)
+                .arg("usr")
+                .status()
+                .unwrap();
+            assert!(status.success());
+        }
+
+        assert_eq!(sibling_headers_package(&main_pkg), Some(headers_pkg));
+
+        let artifact = KernelPackage {
+            name: "linux-goatd".to_string(),
+            version: "6.18.3-1".to_string(),
+            is_goatd: true,
+            path: Some(main_pkg),
+        };
+        let map = ConfigSource::for_artifact(&artifact)
+            .unwrap()
+            .load()
+            .unwrap();
+        assert_eq!(map, parse_config(NEW_CONFIG));
+    }
+}
@@ -27,6 +27,9 @@ pub enum GitError {
     #[error("Invalid reference: {0}")]
     InvalidRef(String),
 
+    #[error("Patch apply error: {0}")]
+    Apply(String),
+
     #[error("IO error: {0}")]
     Io(#[from] std::io::Error),
 
@@ -174,6 +177,33 @@ pub fn get_latest_remote_version(url: &str) -> GitResult<String> {
     }
 }
 
+/// List the tag names advertised by a remote repository, without cloning it
+///
+/// Peeled entries (`^{}`) are folded into their tag; the result is unsorted.
+///
+/// # Errors
+/// Returns `GitError::Repository` if the remote cannot be reached
+pub fn list_remote_tags(url: &str) -> GitResult<Vec<String>> {
+    let temp_dir = tempfile::tempdir()?;
+    let repo = Repository::init_bare(temp_dir.path())?;
+    let mut remote = repo.remote_anonymous(url)?;
+    remote
+        .connect(git2::Direction::Fetch)
+        .map_err(|e| GitError::Repository(format!("Failed to connect to {}: {}", url, e)))?;
+
+    let mut tags = Vec::new();
+    let mut seen = HashSet::new();
+    for reference in remote.list()? {
+        if let Some(tag) = reference.name().strip_prefix("refs/tags/") {
+            let tag = tag.trim_end_matches("^{}");
+            if seen.insert(tag.to_string()) {
+                tags.push(tag.to_string());
+            }
+        }
+    }
+    Ok(tags)
+}
+
 /// Extract version number from a tag for semantic version comparison
 /// Handles common version formats:
 /// - "v6.12.9" → (6, 12, 9)
@@ -183,7 +213,7 @@ pub fn get_latest_remote_version(url: &str) -> GitResult<String> {
 ///
 /// Pre-release versions (rc, alpha, beta) are treated as lower precedence
 /// by reducing the patch version, ensuring v6.13-rc7 < v6.13.0.
-fn extract_version_number(tag: &str) -> (u32, u32, u32) {
+pub(crate) fn extract_version_number(tag: &str) -> (u32, u32, u32) {
     // Remove leading 'v' prefix
     let tag = tag.trim_start_matches('v');
 
@@ -295,6 +325,16 @@ impl GitManager {
         Ok(GitManager { repo_path })
     }
 
+    /// Creates a GitManager for a plain source tree that need not be a repository
+    ///
+    /// Extracted kernel tarballs are not git repositories, but `git apply` works on
+    /// them like GNU patch. Only the patch methods are meaningful on such a tree.
+    pub fn for_source_tree(tree_path: impl AsRef<Path>) -> Self {
+        GitManager {
+            repo_path: tree_path.as_ref().to_path_buf(),
+        }
+    }
+
     /// Clones a repository from a URL to the target path with shallow clone optimization
     ///
     /// This implementation uses shallow cloning (depth=1) to optimize network bandwidth
@@ -418,6 +458,39 @@ impl GitManager {
         Ok(())
     }
 
+    /// Fetches a single refspec from origin (depth=1 where the transport allows it)
+    ///
+    /// Used to bring a branch or tag other than the remote HEAD into a shallow clone,
+    /// e.g. `+refs/tags/v6.12-rt5:refs/tags/v6.12-rt5`.
+    ///
+    /// # Errors
+    /// Returns `GitError::Repository` if the fetch fails
+    pub fn fetch_ref(&self, refspec: &str) -> GitResult<()> {
+        let repo = Repository::open(&self.repo_path)
+            .map_err(|e| GitError::Repository(format!("Failed to open repository: {}", e)))?;
+        let find_origin = || {
+            repo.find_remote("origin")
+                .map_err(|e| GitError::Repository(format!("Failed to find origin remote: {}", e)))
+        };
+
+        let mut shallow = git2::FetchOptions::new();
+        shallow.depth(1);
+        let shallow_result = find_origin()?.fetch(&[refspec], Some(&mut shallow), None);
+        shallow_result
+            .or_else(|shallow_err| {
+                // Local and dumb transports do not support shallow fetches; the failed
+                // remote keeps its negotiated depth, so retry on a fresh handle
+                eprintln!(
+                    "[Git] [FETCH] ⚠ Shallow fetch of {} failed ({}), fetching full history",
+                    refspec, shallow_err
+                );
+                find_origin()
+                    .map_err(|e| git2::Error::from_str(&e.to_string()))?
+                    .fetch(&[refspec], None, None)
+            })
+            .map_err(|e| GitError::Repository(format!("Fetch of {} failed: {}", refspec, e)))
+    }
+
     /// Lists all available tags in the repository
     ///
     /// # Returns
@@ -507,6 +580,37 @@ impl GitManager {
         Ok(())
     }
 
+    /// Checks out an exact commit, deepening a shallow clone if the commit is missing
+    ///
+    /// Sources are cloned with depth=1, so an older commit recorded in a build
+    /// manifest is usually not present locally. In that case the history is
+    /// fetched with `git fetch --unshallow` and the checkout is retried.
+    ///
+    /// # Errors
+    /// Returns `GitError::RefNotFound` if the commit is still unknown after fetching
+    pub fn checkout_commit(&self, commit: &str) -> GitResult<()> {
+        match self.checkout(commit) {
+            Err(GitError::RefNotFound(_)) => {
+                eprintln!(
+                    "[Git] [CHECKOUT] Commit {} not in local history, fetching full history",
+                    commit
+                );
+                let output = std::process::Command::new("git")
+                    .arg("-C")
+                    .arg(&self.repo_path)
+                    .args(["fetch", "--unshallow", "origin"])
+                    .output()
+                    .map_err(|e| GitError::Repository(format!("Failed to run git fetch: {}", e)))?;
+                if !output.status.success() {
+                    // Already-complete repositories reject --unshallow; fall back to a plain fetch
+                    self.fetch()?;
+                }
+                self.checkout(commit)
+            }
+            other => other,
+        }
+    }
+
     /// Gets the current HEAD commit hash
     ///
     /// # Returns
@@ -529,6 +633,123 @@ impl GitManager {
         Ok(commit_id.to_string())
     }
 
+    /// Checks out HEAD into a linked worktree at `path`
+    ///
+    /// The worktree gets a branch named `name` reset to HEAD; a stale worktree
+    /// of the same name (and its directory) from an earlier run is replaced, so
+    /// repeated runs always start from the current commit.
+    ///
+    /// # Errors
+    /// Returns `GitError::Repository` if the worktree cannot be created
+    pub fn add_worktree(&self, name: &str, path: &Path) -> GitResult<GitManager> {
+        let repo = Repository::open(&self.repo_path)
+            .map_err(|e| GitError::Repository(format!("Failed to open repository: {}", e)))?;
+
+        if let Ok(stale) = repo.find_worktree(name) {
+            let mut prune = git2::WorktreePruneOptions::new();
+            prune.valid(true).locked(true).working_tree(true);
+            stale.prune(Some(&mut prune))?;
+        }
+        if path.exists() {
+            std::fs::remove_dir_all(path)?;
+        }
+
+        let head = repo
+            .head()
+            .and_then(|head| head.peel_to_commit())
+            .map_err(|e| GitError::Repository(format!("Failed to read HEAD: {}", e)))?;
+        let branch = repo.branch(name, &head, true)?;
+        let mut options = git2::WorktreeAddOptions::new();
+        options.reference(Some(branch.get()));
+        repo.worktree(name, path, Some(&options)).map_err(|e| {
+            GitError::Repository(format!(
+                "Failed to add worktree {} at {:?}: {}",
+                name, path, e
+            ))
+        })?;
+
+        GitManager::new(path)
+    }
+
+    /// Dry-runs a series of patches against the working tree (`git apply --check`)
+    ///
+    /// The patches are checked as one series, so later patches may build on
+    /// earlier ones.
+    ///
+    /// # Errors
+    /// Returns `GitError::Apply` with git's diagnostics if the series does not apply
+    pub fn check_patches(&self, patches: &[PathBuf]) -> GitResult<()> {
+        self.run_git_apply(&["--check"], patches)
+    }
+
+    /// Applies a series of patches to the working tree (`git apply`)
+    ///
+    /// # Errors
+    /// Returns `GitError::Apply` if any patch fails; git leaves the tree untouched
+    pub fn apply_patches(&self, patches: &[PathBuf]) -> GitResult<()> {
+        self.run_git_apply(&[], patches)
+    }
+
+    /// Whether a series of patches is already applied (its reverse applies cleanly)
+    pub fn patches_applied(&self, patches: &[PathBuf]) -> bool {
+        !patches.is_empty() && self.run_git_apply(&["--check", "--reverse"], patches).is_ok()
+    }
+
+    /// Runs `git apply` in the tree root with the patches concatenated on stdin
+    ///
+    /// git only stacks changes to the same file within a single input, so the
+    /// series is fed as one stream. Repository discovery is capped at the tree
+    /// itself so that a tree nested inside another checkout (e.g. `src/linux-6.x`
+    /// below a PKGBUILD clone) is patched relative to its own root.
+    fn run_git_apply(&self, args: &[&str], patches: &[PathBuf]) -> GitResult<()> {
+        use std::io::Write;
+        use std::process::{Command, Stdio};
+
+        let mut series = Vec::new();
+        for patch in patches {
+            let content = std::fs::read(patch).map_err(|e| {
+                GitError::Apply(format!("Failed to read {}: {}", patch.display(), e))
+            })?;
+            series.extend_from_slice(&content);
+            if !content.ends_with(b"\n") {
+                series.push(b'\n');
+            }
+        }
+        if series.is_empty() {
+            return Ok(());
+        }
+
+        let ceiling = self.repo_path.parent().unwrap_or(&self.repo_path);
+        let mut child = Command::new("git")
+            .arg("apply")
+            .args(args)
+            .current_dir(&self.repo_path)
+            .env("GIT_CEILING_DIRECTORIES", ceiling)
+            .stdin(Stdio::piped())
+            .stdout(Stdio::null())
+            .stderr(Stdio::piped())
+            .spawn()
+            .map_err(|e| GitError::Apply(format!("Failed to run git apply: {}", e)))?;
+
+        if let Some(mut stdin) = child.stdin.take() {
+            stdin
+                .write_all(&series)
+                .map_err(|e| GitError::Apply(format!("Failed to feed git apply: {}", e)))?;
+        }
+
+        let output = child
+            .wait_with_output()
+            .map_err(|e| GitError::Apply(format!("Failed to run git apply: {}", e)))?;
+
+        if output.status.success() {
+            Ok(())
+        } else {
+            Err(GitError::Apply(
+                String::from_utf8_lossy(&output.stderr).trim().to_string(),
+            ))
+        }
+    }
+
     /// Returns the path to the repository
     pub fn repo_path(&self) -> &Path {
         &self.repo_path
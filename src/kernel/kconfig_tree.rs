@@ -0,0 +1,1159 @@
+//! Kconfig tree parser and pre-build validation of requested options.
+//!
+//! Reads the `Kconfig` files of a checked-out kernel source (following
+//! `source`/`rsource`/`osource` with `$(SRCARCH)` substituted) and records, per
+//! symbol, its type, prompt, dependencies (including enclosing `if`, `menu` and
+//! `choice` blocks), unconditional defaults and reverse `select` edges.
+//!
+//! [`KconfigTree::check`] evaluates the options GOATd is about to write against
+//! a base `.config` and reports what `make olddefconfig` would silently drop:
+//! - unknown symbols
+//! - symbols whose `depends on` is not met
+//! - options disabled while an enabled symbol `select`s them
+//! - promptless symbols whose value is computed by Kconfig
+
+use std::collections::{BTreeMap, HashMap, HashSet};
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use crate::error::AppError;
+use crate::kernel::config_diff::{parse_config, KconfigMap};
+use crate::models::KernelConfig;
+
+/// `SRCARCH` for the architecture this binary runs on.
+pub fn host_srcarch() -> &'static str {
+    match std::env::consts::ARCH {
+        "x86_64" | "x86" => "x86",
+        "aarch64" => "arm64",
+        "riscv64" => "riscv",
+        "powerpc64" => "powerpc",
+        other => other,
+    }
+}
+
+/// Locate the kernel source root (the directory holding the top-level `Kconfig`).
+///
+/// Checks `kernel_path` itself, then the makepkg extraction dirs under `src/`.
+pub fn locate_source_root(kernel_path: &Path) -> Option<PathBuf> {
+    if kernel_path.join("Kconfig").is_file() {
+        return Some(kernel_path.to_path_buf());
+    }
+    fs::read_dir(kernel_path.join("src"))
+        .ok()?
+        .flatten()
+        .map(|entry| entry.path())
+        .find(|path| path.join("Kconfig").is_file() && path.join("Makefile").is_file())
+}
+
+/// Kconfig symbol type.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SymbolType {
+    Bool,
+    Tristate,
+    Int,
+    Hex,
+    String,
+    Unknown,
+}
+
+/// Tristate value: n < m < y.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum Tristate {
+    No,
+    Module,
+    Yes,
+}
+
+impl Tristate {
+    /// Interpret a `.config` value in a boolean context.
+    pub fn from_value(value: &str) -> Self {
+        match value.trim_matches('"') {
+            "y" => Tristate::Yes,
+            "m" => Tristate::Module,
+            "n" | "" => Tristate::No,
+            _ => Tristate::Yes,
+        }
+    }
+}
+
+/// Comparison operator in a Kconfig expression.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CmpOp {
+    Eq,
+    Ne,
+    Lt,
+    Le,
+    Gt,
+    Ge,
+}
+
+/// Kconfig dependency expression.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Expr {
+    Symbol(String),
+    Const(String),
+    Not(Box<Expr>),
+    And(Box<Expr>, Box<Expr>),
+    Or(Box<Expr>, Box<Expr>),
+    Cmp(CmpOp, Box<Expr>, Box<Expr>),
+    /// `$(...)` macro or anything else that cannot be evaluated statically
+    Unknown,
+}
+
+impl Expr {
+    /// Parse an expression such as `PCI && (X86_64 || COMPILE_TEST) && !FOO=m`.
+    pub fn parse(text: &str) -> Option<Expr> {
+        let tokens = tokenize(text);
+        let mut pos = 0;
+        let expr = parse_or(&tokens, &mut pos)?;
+        (pos == tokens.len()).then_some(expr)
+    }
+
+    /// Evaluate against symbol values (without `CONFIG_` prefix).
+    pub fn eval(&self, value_of: &dyn Fn(&str) -> Option<String>) -> Tristate {
+        match self {
+            Expr::Symbol(name) => value_of(name)
+                .map(|v| Tristate::from_value(&v))
+                .unwrap_or(Tristate::No),
+            Expr::Const(value) => Tristate::from_value(value),
+            Expr::Not(inner) => match inner.eval(value_of) {
+                Tristate::Yes => Tristate::No,
+                Tristate::Module => Tristate::Module,
+                Tristate::No => Tristate::Yes,
+            },
+            Expr::And(a, b) => a.eval(value_of).min(b.eval(value_of)),
+            Expr::Or(a, b) => a.eval(value_of).max(b.eval(value_of)),
+            Expr::Cmp(op, a, b) => {
+                let (a, b) = (a.operand(value_of), b.operand(value_of));
+                let ordering = match (parse_number(&a), parse_number(&b)) {
+                    (Some(x), Some(y)) => x.cmp(&y),
+                    _ => a.cmp(&b),
+                };
+                let holds = match op {
+                    CmpOp::Eq => ordering.is_eq(),
+                    CmpOp::Ne => ordering.is_ne(),
+                    CmpOp::Lt => ordering.is_lt(),
+                    CmpOp::Le => ordering.is_le(),
+                    CmpOp::Gt => ordering.is_gt(),
+                    CmpOp::Ge => ordering.is_ge(),
+                };
+                if holds {
+                    Tristate::Yes
+                } else {
+                    Tristate::No
+                }
+            }
+            Expr::Unknown => Tristate::Yes,
+        }
+    }
+
+    /// String value of a comparison operand.
+    fn operand(&self, value_of: &dyn Fn(&str) -> Option<String>) -> String {
+        match self {
+            Expr::Symbol(name) => value_of(name)
+                .map(|v| v.trim_matches('"').to_string())
+                .unwrap_or_else(|| "n".to_string()),
+            Expr::Const(value) => value.clone(),
+            _ => String::new(),
+        }
+    }
+
+    /// Symbols referenced by the expression, in order of appearance.
+    pub fn symbols(&self) -> Vec<String> {
+        let mut out = Vec::new();
+        self.collect_symbols(&mut out);
+        out
+    }
+
+    fn collect_symbols(&self, out: &mut Vec<String>) {
+        match self {
+            Expr::Symbol(name) => {
+                if !out.contains(name) {
+                    out.push(name.clone());
+                }
+            }
+            Expr::Not(inner) => inner.collect_symbols(out),
+            Expr::And(a, b) | Expr::Or(a, b) | Expr::Cmp(_, a, b) => {
+                a.collect_symbols(out);
+                b.collect_symbols(out);
+            }
+            Expr::Const(_) | Expr::Unknown => {}
+        }
+    }
+}
+
+fn parse_number(value: &str) -> Option<i64> {
+    match value
+        .strip_prefix("0x")
+        .or_else(|| value.strip_prefix("0X"))
+    {
+        Some(hex) => i64::from_str_radix(hex, 16).ok(),
+        None => value.parse().ok(),
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+enum Token {
+    Word(String),
+    Quoted(String),
+    Macro,
+    Op(&'static str),
+}
+
+fn tokenize(text: &str) -> Vec<Token> {
+    let chars: Vec<char> = text.chars().collect();
+    let mut tokens = Vec::new();
+    let mut i = 0;
+    while i < chars.len() {
+        let c = chars[i];
+        if c.is_whitespace() {
+            i += 1;
+        } else if c == '#' {
+            break;
+        } else if c == '$' && chars.get(i + 1) == Some(&'(') {
+            let mut depth = 0;
+            while i < chars.len() {
+                match chars[i] {
+                    '(' => depth += 1,
+                    ')' => {
+                        depth -= 1;
+                        if depth == 0 {
+                            i += 1;
+                            break;
+                        }
+                    }
+                    _ => {}
+                }
+                i += 1;
+            }
+            tokens.push(Token::Macro);
+        } else if c == '"' || c == '\'' {
+            let start = i + 1;
+            i += 1;
+            while i < chars.len() && chars[i] != c {
+                i += 1;
+            }
+            tokens.push(Token::Quoted(
+                chars[start..i.min(chars.len())].iter().collect(),
+            ));
+            i += 1;
+        } else if c.is_ascii_alphanumeric() || c == '_' || c == '-' || c == '.' {
+            let start = i;
+            while i < chars.len()
+                && (chars[i].is_ascii_alphanumeric() || matches!(chars[i], '_' | '-' | '.'))
+            {
+                i += 1;
+            }
+            tokens.push(Token::Word(chars[start..i].iter().collect()));
+        } else {
+            let two: String = chars[i..(i + 2).min(chars.len())].iter().collect();
+            let op = match two.as_str() {
+                "&&" => Some("&&"),
+                "||" => Some("||"),
+                "!=" => Some("!="),
+                "<=" => Some("<="),
+                ">=" => Some(">="),
+                _ => None,
+            };
+            if let Some(op) = op {
+                tokens.push(Token::Op(op));
+                i += 2;
+                continue;
+            }
+            tokens.push(Token::Op(match c {
+                '!' => "!",
+                '(' => "(",
+                ')' => ")",
+                '=' => "=",
+                '<' => "<",
+                '>' => ">",
+                _ => "?",
+            }));
+            i += 1;
+        }
+    }
+    tokens
+}
+
+fn parse_or(tokens: &[Token], pos: &mut usize) -> Option<Expr> {
+    let mut left = parse_and(tokens, pos)?;
+    while tokens.get(*pos) == Some(&Token::Op("||")) {
+        *pos += 1;
+        let right = parse_and(tokens, pos)?;
+        left = Expr::Or(Box::new(left), Box::new(right));
+    }
+    Some(left)
+}
+
+fn parse_and(tokens: &[Token], pos: &mut usize) -> Option<Expr> {
+    let mut left = parse_unary(tokens, pos)?;
+    while tokens.get(*pos) == Some(&Token::Op("&&")) {
+        *pos += 1;
+        let right = parse_unary(tokens, pos)?;
+        left = Expr::And(Box::new(left), Box::new(right));
+    }
+    Some(left)
+}
+
+fn parse_unary(tokens: &[Token], pos: &mut usize) -> Option<Expr> {
+    match tokens.get(*pos)? {
+        Token::Op("!") => {
+            *pos += 1;
+            Some(Expr::Not(Box::new(parse_unary(tokens, pos)?)))
+        }
+        Token::Op("(") => {
+            *pos += 1;
+            let inner = parse_or(tokens, pos)?;
+            if tokens.get(*pos) != Some(&Token::Op(")")) {
+                return None;
+            }
+            *pos += 1;
+            Some(inner)
+        }
+        _ => {
+            let left = parse_operand(tokens, pos)?;
+            let op = match tokens.get(*pos) {
+                Some(Token::Op("=")) => CmpOp::Eq,
+                Some(Token::Op("!=")) => CmpOp::Ne,
+                Some(Token::Op("<")) => CmpOp::Lt,
+                Some(Token::Op("<=")) => CmpOp::Le,
+                Some(Token::Op(">")) => CmpOp::Gt,
+                Some(Token::Op(">=")) => CmpOp::Ge,
+                _ => return Some(left),
+            };
+            *pos += 1;
+            let right = parse_operand(tokens, pos)?;
+            if left == Expr::Unknown || right == Expr::Unknown {
+                return Some(Expr::Unknown);
+            }
+            Some(Expr::Cmp(op, Box::new(left), Box::new(right)))
+        }
+    }
+}
+
+fn parse_operand(tokens: &[Token], pos: &mut usize) -> Option<Expr> {
+    let expr = match tokens.get(*pos)? {
+        Token::Word(word) => {
+            let is_const = matches!(word.as_str(), "y" | "m" | "n")
+                || word.starts_with(|c: char| c.is_ascii_digit() || c == '-');
+            if is_const {
+                Expr::Const(word.clone())
+            } else {
+                Expr::Symbol(word.clone())
+            }
+        }
+        Token::Quoted(text) => Expr::Const(text.clone()),
+        Token::Macro => Expr::Unknown,
+        Token::Op(_) => return None,
+    };
+    *pos += 1;
+    Some(expr)
+}
+
+/// One `config`/`menuconfig` definition of a symbol.
+#[derive(Debug, Clone)]
+pub struct SymbolDef {
+    /// Declared type
+    pub kind: SymbolType,
+    /// Whether the definition has a prompt (user-settable)
+    pub has_prompt: bool,
+    /// Conjunction of own `depends on` and enclosing if/menu/choice conditions
+    pub depends: Vec<Expr>,
+    /// Source text of `depends`, for reports
+    pub depends_text: Vec<String>,
+    /// Unconditional `default`/`def_bool` values
+    pub defaults: Vec<String>,
+    /// Kconfig file the definition came from (relative to the source root)
+    pub file: PathBuf,
+}
+
+impl SymbolDef {
+    fn new(file: PathBuf, enclosing: &[Frame]) -> Self {
+        SymbolDef {
+            kind: SymbolType::Unknown,
+            has_prompt: false,
+            depends: enclosing.iter().flat_map(|f| f.conds.clone()).collect(),
+            depends_text: enclosing.iter().flat_map(|f| f.texts.clone()).collect(),
+            defaults: Vec::new(),
+            file,
+        }
+    }
+
+    /// Evaluated dependency of this definition.
+    pub fn dependency(&self, value_of: &dyn Fn(&str) -> Option<String>) -> Tristate {
+        self.depends
+            .iter()
+            .map(|e| e.eval(value_of))
+            .min()
+            .unwrap_or(Tristate::Yes)
+    }
+}
+
+/// Reverse `select` edge: `selector` selects the symbol `if condition`.
+#[derive(Debug, Clone)]
+pub struct Selector {
+    pub selector: String,
+    pub condition: Option<Expr>,
+}
+
+/// Enclosing `if`/`menu`/`choice` block.
+#[derive(Debug, Clone, Default)]
+struct Frame {
+    kind: &'static str,
+    conds: Vec<Expr>,
+    texts: Vec<String>,
+}
+
+/// Entry the following attribute lines belong to.
+enum Current {
+    None,
+    Symbol(String, usize),
+    Block,
+}
+
+/// Parsed Kconfig tree of one kernel source.
+#[derive(Debug, Clone, Default)]
+pub struct KconfigTree {
+    /// Symbol (without `CONFIG_`) -> its definitions
+    pub symbols: HashMap<String, Vec<SymbolDef>>,
+    /// Symbol -> symbols that `select` it
+    pub selected_by: HashMap<String, Vec<Selector>>,
+    /// Number of Kconfig files read
+    pub files_parsed: usize,
+}
+
+impl KconfigTree {
+    /// Parse the tree rooted at `<source_root>/Kconfig` for `srcarch`.
+    pub fn load(source_root: &Path, srcarch: &str) -> Result<Self, AppError> {
+        let root = source_root.join("Kconfig");
+        if !root.is_file() {
+            return Err(AppError::KernelConfig(format!(
+                "No Kconfig found in {}",
+                source_root.display()
+            )));
+        }
+        let mut tree = KconfigTree::default();
+        let mut parser = Parser {
+            source_root,
+            srcarch,
+            frames: Vec::new(),
+            visited: HashSet::new(),
+        };
+        parser.parse_file(&mut tree, &root)?;
+        Ok(tree)
+    }
+
+    /// Whether the symbol (without `CONFIG_`) is defined anywhere.
+    pub fn contains(&self, symbol: &str) -> bool {
+        self.symbols.contains_key(symbol)
+    }
+
+    /// Check requested options against the tree.
+    ///
+    /// `base` is the `.config` the options are applied on top of; symbols it
+    /// does not mention are treated as `n`.
+    pub fn check(&self, requested: &[RequestedOption], base: &KconfigMap) -> KconfigReport {
+        let mut values: HashMap<String, String> =
+            base.iter().map(|(k, v)| (k.clone(), v.clone())).collect();
+        for option in requested {
+            values.insert(option.symbol.clone(), option.value.clone());
+        }
+        let value_of = |name: &str| values.get(name).cloned();
+
+        let mut issues = Vec::new();
+        for option in requested {
+            let Some(defs) = self.symbols.get(&option.symbol) else {
+                issues.push(KconfigIssue::UnknownSymbol {
+                    option: option.clone(),
+                });
+                continue;
+            };
+
+            let active_selectors: Vec<String> = self
+                .selected_by
+                .get(&option.symbol)
+                .map(|selectors| {
+                    selectors
+                        .iter()
+                        .filter(|s| {
+                            value_of(&s.selector)
+                                .is_some_and(|v| Tristate::from_value(&v) > Tristate::No)
+                                && s.condition
+                                    .as_ref()
+                                    .is_none_or(|c| c.eval(&value_of) > Tristate::No)
+                        })
+                        .map(|s| s.selector.clone())
+                        .collect()
+                })
+                .unwrap_or_default();
+
+            let wanted = Tristate::from_value(&option.value);
+            let is_bool_like = defs
+                .iter()
+                .any(|d| matches!(d.kind, SymbolType::Bool | SymbolType::Tristate));
+
+            if is_bool_like && wanted == Tristate::No {
+                if !active_selectors.is_empty() {
+                    issues.push(KconfigIssue::SelectConflict {
+                        option: option.clone(),
+                        selected_by: active_selectors,
+                    });
+                }
+                continue;
+            }
+
+            let is_tristate = defs.iter().any(|d| d.kind == SymbolType::Tristate);
+            let required = if is_tristate && wanted == Tristate::Yes {
+                Tristate::Yes
+            } else {
+                Tristate::Module
+            };
+            let best = defs
+                .iter()
+                .max_by_key(|d| d.dependency(&value_of))
+                .expect("symbol has at least one definition");
+            if best.dependency(&value_of) < required && active_selectors.is_empty() {
+                let unmet: Vec<String> = best
+                    .depends
+                    .iter()
+                    .zip(&best.depends_text)
+                    .filter(|(expr, _)| expr.eval(&value_of) < required)
+                    .map(|(_, text)| text.clone())
+                    .collect();
+                let blame = best
+                    .depends
+                    .iter()
+                    .filter(|expr| expr.eval(&value_of) < required)
+                    .flat_map(|expr| expr.symbols())
+                    .map(|sym| {
+                        let value = value_of(&sym).unwrap_or_else(|| "n".to_string());
+                        format!("{}={}", sym, value)
+                    })
+                    .collect();
+                issues.push(KconfigIssue::UnmetDependency {
+                    option: option.clone(),
+                    depends: unmet.join(" && "),
+                    current: blame,
+                });
+                continue;
+            }
+
+            let user_settable = defs.iter().any(|d| d.has_prompt);
+            let matches_default = defs.iter().any(|d| {
+                d.defaults
+                    .iter()
+                    .any(|v| v.trim_matches('"') == option.value.trim_matches('"'))
+            });
+            if !user_settable && active_selectors.is_empty() && !matches_default {
+                issues.push(KconfigIssue::NotUserSettable {
+                    option: option.clone(),
+                });
+            }
+        }
+
+        KconfigReport {
+            checked: requested.len(),
+            issues,
+        }
+    }
+}
+
+struct Parser<'a> {
+    source_root: &'a Path,
+    srcarch: &'a str,
+    frames: Vec<Frame>,
+    visited: HashSet<PathBuf>,
+}
+
+impl Parser<'_> {
+    fn parse_file(&mut self, tree: &mut KconfigTree, path: &Path) -> Result<(), AppError> {
+        if !self.visited.insert(path.to_path_buf()) {
+            return Ok(());
+        }
+        let text = fs::read_to_string(path)
+            .map_err(|e| AppError::Io(format!("Failed to read {}: {}", path.display(), e)))?;
+        tree.files_parsed += 1;
+        let relative = path
+            .strip_prefix(self.source_root)
+            .unwrap_or(path)
+            .to_path_buf();
+        let dir = path.parent().unwrap_or(self.source_root).to_path_buf();
+        self.parse_text(tree, &text, &relative, &dir);
+        Ok(())
+    }
+
+    fn parse_text(&mut self, tree: &mut KconfigTree, text: &str, file: &Path, dir: &Path) {
+        let mut current = Current::None;
+        let mut lines = join_continuations(text).into_iter().peekable();
+
+        while let Some(line) = lines.next() {
+            let trimmed = line.trim();
+            if trimmed.is_empty() || trimmed.starts_with('#') {
+                continue;
+            }
+            let (keyword, rest) = trimmed
+                .split_once(char::is_whitespace)
+                .map(|(k, r)| (k, r.trim()))
+                .unwrap_or((trimmed, ""));
+
+            match keyword {
+                "config" | "menuconfig" => {
+                    let name = rest.to_string();
+                    let defs = tree.symbols.entry(name.clone()).or_default();
+                    defs.push(SymbolDef::new(file.to_path_buf(), &self.frames));
+                    current = Current::Symbol(name, defs.len() - 1);
+                }
+                "choice" => {
+                    self.frames.push(Frame {
+                        kind: "choice",
+                        ..Default::default()
+                    });
+                    current = Current::Block;
+                }
+                "menu" => {
+                    self.frames.push(Frame {
+                        kind: "menu",
+                        ..Default::default()
+                    });
+                    current = Current::Block;
+                }
+                "if" => {
+                    let mut frame = Frame {
+                        kind: "if",
+                        ..Default::default()
+                    };
+                    frame.conds.push(Expr::parse(rest).unwrap_or(Expr::Unknown));
+                    frame.texts.push(rest.to_string());
+                    self.frames.push(frame);
+                    current = Current::None;
+                }
+                "endchoice" | "endmenu" | "endif" => {
+                    let kind = &keyword[3..];
+                    if let Some(idx) = self.frames.iter().rposition(|f| f.kind == kind) {
+                        self.frames.truncate(idx);
+                    }
+                    current = Current::None;
+                }
+                "comment" | "mainmenu" => current = Current::None,
+                "source" | "rsource" | "osource" | "orsource" => {
+                    current = Current::None;
+                    self.include(tree, keyword, rest, dir);
+                }
+                "help" | "---help---" => {
+                    skip_help(&mut lines, indent_of(&line));
+                }
+                "depends" => {
+                    let Some(expr_text) = rest.strip_prefix("on") else {
+                        continue;
+                    };
+                    let expr_text = expr_text.trim();
+                    let expr = Expr::parse(expr_text).unwrap_or(Expr::Unknown);
+                    match &current {
+                        Current::Symbol(name, idx) => {
+                            if let Some(def) =
+                                tree.symbols.get_mut(name).and_then(|d| d.get_mut(*idx))
+                            {
+                                def.depends.push(expr);
+                                def.depends_text.push(expr_text.to_string());
+                            }
+                        }
+                        Current::Block => {
+                            if let Some(frame) = self.frames.last_mut() {
+                                frame.conds.push(expr);
+                                frame.texts.push(expr_text.to_string());
+                            }
+                        }
+                        Current::None => {}
+                    }
+                }
+                _ => {
+                    if let Current::Symbol(name, idx) = &current {
+                        let name = name.clone();
+                        apply_attribute(tree, &name, *idx, keyword, rest);
+                    }
+                }
+            }
+        }
+    }
+
+    fn include(&mut self, tree: &mut KconfigTree, keyword: &str, rest: &str, dir: &Path) {
+        let raw = rest.trim().trim_matches('"');
+        let path = raw
+            .replace("$(SRCARCH)", self.srcarch)
+            .replace("$(ARCH)", self.srcarch)
+            .replace("$SRCARCH", self.srcarch);
+        if path.contains("$(") || path.contains('*') {
+            return;
+        }
+        let full = if keyword.starts_with('r') || keyword == "orsource" {
+            dir.join(&path)
+        } else {
+            self.source_root.join(&path)
+        };
+        if !full.is_file() {
+            if !keyword.starts_with('o') {
+                eprintln!(
+                    "[Kconfig] WARNING: sourced file not found: {}",
+                    full.display()
+                );
+            }
+            return;
+        }
+        if let Err(e) = self.parse_file(tree, &full) {
+            eprintln!("[Kconfig] WARNING: {}", e);
+        }
+    }
+}
+
+fn apply_attribute(tree: &mut KconfigTree, name: &str, idx: usize, keyword: &str, rest: &str) {
+    let (value_part, condition) = split_if(rest);
+    match keyword {
+        "select" => {
+            let target = value_part.trim().to_string();
+            tree.selected_by.entry(target).or_default().push(Selector {
+                selector: name.to_string(),
+                condition: condition.and_then(Expr::parse),
+            });
+            return;
+        }
+        "imply" | "range" | "option" | "modules" | "transitional" | "visible" => return,
+        _ => {}
+    }
+
+    let Some(def) = tree.symbols.get_mut(name).and_then(|d| d.get_mut(idx)) else {
+        return;
+    };
+    let kind = match keyword {
+        "bool" | "boolean" | "def_bool" => Some(SymbolType::Bool),
+        "tristate" | "def_tristate" => Some(SymbolType::Tristate),
+        "int" | "def_int" => Some(SymbolType::Int),
+        "hex" | "def_hex" => Some(SymbolType::Hex),
+        "string" | "def_string" => Some(SymbolType::String),
+        _ => None,
+    };
+    if let Some(kind) = kind {
+        def.kind = kind;
+        if keyword.starts_with("def_") {
+            if condition.is_none() {
+                def.defaults.push(value_part.trim().to_string());
+            }
+        } else if !value_part.trim().is_empty() {
+            def.has_prompt = true;
+        }
+    } else if keyword == "prompt" {
+        def.has_prompt = true;
+    } else if keyword == "default" && condition.is_none() {
+        def.defaults.push(value_part.trim().to_string());
+    }
+}
+
+/// Split `VALUE if EXPR` into the value and optional condition.
+fn split_if(rest: &str) -> (&str, Option<&str>) {
+    let mut in_quotes = false;
+    for (i, byte) in rest.bytes().enumerate() {
+        match byte {
+            b'"' => in_quotes = !in_quotes,
+            b' ' | b'\t' if !in_quotes => {
+                let tail = rest[i..].trim_start();
+                if let Some(cond) = tail
+                    .strip_prefix("if ")
+                    .or_else(|| tail.strip_prefix("if\t"))
+                {
+                    return (&rest[..i], Some(cond.trim()));
+                }
+            }
+            _ => {}
+        }
+    }
+    (rest, None)
+}
+
+/// Join backslash-continued lines.
+fn join_continuations(text: &str) -> Vec<String> {
+    let mut out = Vec::new();
+    let mut pending = String::new();
+    for line in text.lines() {
+        // Continuation lines keep only a single separating space
+        let line = if pending.is_empty() {
+            line
+        } else {
+            line.trim_start()
+        };
+        if let Some(stripped) = line.strip_suffix('\\') {
+            pending.push_str(stripped.trim_end());
+            pending.push(' ');
+        } else {
+            pending.push_str(line);
+            out.push(std::mem::take(&mut pending));
+        }
+    }
+    if !pending.is_empty() {
+        out.push(pending);
+    }
+    out
+}
+
+/// Indentation width with tabs expanded to 8 columns.
+fn indent_of(line: &str) -> usize {
+    let mut width = 0;
+    for c in line.chars() {
+        match c {
+            ' ' => width += 1,
+            '\t' => width = (width / 8 + 1) * 8,
+            _ => break,
+        }
+    }
+    width
+}
+
+/// Skip help text: every following line indented deeper than the help keyword
+/// (or blank) belongs to it.
+fn skip_help<I: Iterator<Item = String>>(
+    lines: &mut std::iter::Peekable<I>,
+    keyword_indent: usize,
+) {
+    let mut help_indent: Option<usize> = None;
+    while let Some(line) = lines.peek() {
+        if line.trim().is_empty() {
+            lines.next();
+            continue;
+        }
+        let indent = indent_of(line);
+        let min = *help_indent.get_or_insert(indent.max(keyword_indent + 1));
+        if indent < min {
+            break;
+        }
+        lines.next();
+    }
+}
+
+/// An option GOATd is about to write into `.config`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RequestedOption {
+    /// Symbol without `CONFIG_`
+    pub symbol: String,
+    /// Requested value
+    pub value: String,
+    /// `config_options` key it came from (e.g. `CONFIG_HZ_1000`, `_MGLRU_CONFIG_LRU_GEN`)
+    pub origin: String,
+}
+
+/// Collect the `.config` assignments a `KernelConfig` requests.
+///
+/// Includes plain `CONFIG_*` keys of `config_options` and the derived
+/// underscore keys whose value is a `CONFIG_X=value` assignment.
+pub fn requested_options(config: &KernelConfig) -> Vec<RequestedOption> {
+    let mut out: BTreeMap<String, RequestedOption> = BTreeMap::new();
+    for (key, value) in &config.config_options {
+        let assignment = if let Some(symbol) = key.strip_prefix("CONFIG_") {
+            Some((symbol.to_string(), value.clone()))
+        } else if key.starts_with('_') {
+            value
+                .strip_prefix("CONFIG_")
+                .and_then(|rest| rest.split_once('='))
+                .map(|(symbol, value)| (symbol.to_string(), value.to_string()))
+        } else {
+            None
+        };
+        if let Some((symbol, value)) = assignment {
+            out.insert(
+                symbol.clone(),
+                RequestedOption {
+                    symbol,
+                    value,
+                    origin: key.clone(),
+                },
+            );
+        }
+    }
+    out.into_values().collect()
+}
+
+/// Why a requested option would not survive `olddefconfig`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum KconfigIssue {
+    /// The symbol is not defined by this kernel's Kconfig
+    UnknownSymbol { option: RequestedOption },
+    /// `depends on` evaluates too low for the requested value
+    UnmetDependency {
+        option: RequestedOption,
+        depends: String,
+        current: Vec<String>,
+    },
+    /// Disabled, but an enabled symbol `select`s it
+    SelectConflict {
+        option: RequestedOption,
+        selected_by: Vec<String>,
+    },
+    /// No prompt: the value is computed from defaults/selects
+    NotUserSettable { option: RequestedOption },
+}
+
+impl KconfigIssue {
+    /// The option the issue is about.
+    pub fn option(&self) -> &RequestedOption {
+        match self {
+            KconfigIssue::UnknownSymbol { option }
+            | KconfigIssue::UnmetDependency { option, .. }
+            | KconfigIssue::SelectConflict { option, .. }
+            | KconfigIssue::NotUserSettable { option } => option,
+        }
+    }
+
+    /// Human-readable explanation of why the option is dropped.
+    pub fn explain(&self) -> String {
+        let option = self.option();
+        let setting = format!("CONFIG_{}={}", option.symbol, option.value);
+        let origin = if option.origin == format!("CONFIG_{}", option.symbol) {
+            String::new()
+        } else {
+            format!(" (from {})", option.origin)
+        };
+        match self {
+            KconfigIssue::UnknownSymbol { .. } => format!(
+                "{}{} will be dropped: CONFIG_{} does not exist in this kernel's Kconfig",
+                setting, origin, option.symbol
+            ),
+            KconfigIssue::UnmetDependency {
+                depends, current, ..
+            } => format!(
+                "{}{} will be dropped: unmet dependency `{}` ({})",
+                setting,
+                origin,
+                depends,
+                current.join(", ")
+            ),
+            KconfigIssue::SelectConflict { selected_by, .. } => format!(
+                "{}{} will be overridden: selected by {}",
+                setting,
+                origin,
+                selected_by
+                    .iter()
+                    .map(|s| format!("CONFIG_{}", s))
+                    .collect::<Vec<_>>()
+                    .join(", ")
+            ),
+            KconfigIssue::NotUserSettable { .. } => format!(
+                "{}{} will be recomputed: CONFIG_{} has no prompt and is set only by defaults or select",
+                setting, origin, option.symbol
+            ),
+        }
+    }
+}
+
+/// Result of [`KconfigTree::check`].
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct KconfigReport {
+    /// Number of requested options checked
+    pub checked: usize,
+    /// Problems found, in option order
+    pub issues: Vec<KconfigIssue>,
+}
+
+impl KconfigReport {
+    /// True if every requested option survives.
+    pub fn is_clean(&self) -> bool {
+        self.issues.is_empty()
+    }
+
+    /// One line per issue.
+    pub fn lines(&self) -> Vec<String> {
+        self.issues.iter().map(|i| i.explain()).collect()
+    }
+}
+
+/// Check a config against the Kconfig tree under `kernel_path`.
+///
+/// The base `.config` is the source root's `.config`, falling back to the
+/// PKGBUILD's `config` next to it. Returns `Ok(None)` if the sources are not
+/// extracted yet.
+pub fn check_kernel_config(
+    kernel_path: &Path,
+    config: &KernelConfig,
+) -> Result<Option<KconfigReport>, AppError> {
+    let Some(source_root) = locate_source_root(kernel_path) else {
+        return Ok(None);
+    };
+    let tree = KconfigTree::load(&source_root, host_srcarch())?;
+    let base = [source_root.join(".config"), kernel_path.join("config")]
+        .into_iter()
+        .find(|p| p.is_file())
+        .and_then(|p| fs::read_to_string(p).ok())
+        .map(|content| parse_config(&content))
+        .unwrap_or_default();
+    Ok(Some(tree.check(&requested_options(config), &base)))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    const KCONFIG: &str = r#"
+mainmenu "Test"
+
+config PCI
+	bool "PCI support"
+	help
+	  Enables PCI.
+
+	  depends on NOTHING (this is help text)
+
+config NET
+	bool "Networking"
+
+menu "Drivers"
+	depends on PCI
+
+config E1000
+	tristate "Intel e1000"
+	depends on NET
+	select CRC32
+
+endmenu
+
+config CRC32
+	tristate "CRC32"
+
+config CC_IS_CLANG
+	def_bool $(success,test x = x)
+
+if NET
+config BPF_JIT
+	bool "BPF JIT"
+	depends on HAVE_JIT || \
+		   COMPILE_TEST
+endif
+
+source "arch/$(SRCARCH)/Kconfig"
+"#;
+
+    fn tree() -> (TempDir, KconfigTree) {
+        let tmp = TempDir::new().unwrap();
+        fs::create_dir_all(tmp.path().join("arch/x86")).unwrap();
+        fs::write(
+            tmp.path().join("arch/x86/Kconfig"),
+            "config HAVE_JIT\n\tdef_bool y\n",
+        )
+        .unwrap();
+        fs::write(tmp.path().join("Kconfig"), KCONFIG).unwrap();
+        let tree = KconfigTree::load(tmp.path(), "x86").unwrap();
+        (tmp, tree)
+    }
+
+    fn option(symbol: &str, value: &str) -> RequestedOption {
+        RequestedOption {
+            symbol: symbol.to_string(),
+            value: value.to_string(),
+            origin: format!("CONFIG_{}", symbol),
+        }
+    }
+
+    #[test]
+    fn test_expr_parse_and_eval() {
+        let expr = Expr::parse("PCI && (X86_64 || COMPILE_TEST) && !FOO=m").unwrap();
+        let values: HashMap<&str, &str> = [("PCI", "y"), ("X86_64", "y"), ("FOO", "y")].into();
+        let value_of = |s: &str| values.get(s).map(|v| v.to_string());
+        assert_eq!(expr.eval(&value_of), Tristate::Yes);
+        assert_eq!(expr.symbols(), vec!["PCI", "X86_64", "COMPILE_TEST", "FOO"]);
+        assert_eq!(
+            Expr::parse("NR_CPUS >= 64")
+                .unwrap()
+                .eval(&|_| Some("32".to_string())),
+            Tristate::No
+        );
+        assert_eq!(Expr::parse("$(cc-option,-mfoo)"), Some(Expr::Unknown));
+    }
+
+    #[test]
+    fn test_parse_tree_structure() {
+        let (_tmp, tree) = tree();
+        assert_eq!(tree.files_parsed, 2);
+        assert!(tree.contains("HAVE_JIT"));
+        // Help text is not parsed as attributes
+        assert!(tree.symbols["PCI"][0].depends.is_empty());
+        // Menu dependency is inherited
+        assert_eq!(tree.symbols["E1000"][0].depends_text, vec!["PCI", "NET"]);
+        // `if` block and line continuation
+        assert_eq!(
+            tree.symbols["BPF_JIT"][0].depends_text,
+            vec!["NET", "HAVE_JIT || COMPILE_TEST"]
+        );
+        assert_eq!(tree.selected_by["CRC32"][0].selector, "E1000");
+        assert!(!tree.symbols["CC_IS_CLANG"][0].has_prompt);
+    }
+
+    #[test]
+    fn test_check_reports_dropped_options() {
+        let (_tmp, tree) = tree();
+        let base = parse_config("CONFIG_NET=y\n# CONFIG_PCI is not set\nCONFIG_HAVE_JIT=y\n");
+        let report = tree.check(
+            &[
+                option("E1000", "m"),
+                option("BPF_JIT", "y"),
+                option("DOES_NOT_EXIST", "y"),
+                option("CC_IS_CLANG", "n"),
+            ],
+            &base,
+        );
+        assert_eq!(report.checked, 4);
+        assert_eq!(report.issues.len(), 2);
+        assert!(matches!(
+            &report.issues[0],
+            KconfigIssue::UnmetDependency { option, current, .. }
+                if option.symbol == "E1000" && current == &vec!["PCI=n".to_string()]
+        ));
+        assert!(matches!(
+            &report.issues[1],
+            KconfigIssue::UnknownSymbol { .. }
+        ));
+        assert!(report.lines()[1].contains("does not exist"));
+    }
+
+    #[test]
+    fn test_check_select_conflict_and_promptless() {
+        let (_tmp, tree) = tree();
+        let base = parse_config("CONFIG_NET=y\nCONFIG_PCI=y\n");
+        let report = tree.check(
+            &[
+                option("E1000", "y"),
+                option("CRC32", "n"),
+                option("HAVE_JIT", "n"),
+            ],
+            &base,
+        );
+        assert_eq!(report.issues.len(), 1);
+        match &report.issues[0] {
+            KconfigIssue::SelectConflict { selected_by, .. } => {
+                assert_eq!(selected_by, &vec!["E1000".to_string()])
+            }
+            other => panic!("unexpected issue: {:?}", other),
+        }
+
+        let report = tree.check(&[option("HAVE_JIT", "m")], &base);
+        assert!(matches!(
+            report.issues[0],
+            KconfigIssue::NotUserSettable { .. }
+        ));
+    }
+
+    #[test]
+    fn test_requested_options_include_derived_keys() {
+        let mut config = KernelConfig::default();
+        config
+            .config_options
+            .insert("CONFIG_HZ_1000".to_string(), "y".to_string());
+        config.config_options.insert(
+            "_MGLRU_CONFIG_LRU_GEN".to_string(),
+            "CONFIG_LRU_GEN=y".to_string(),
+        );
+        config
+            .config_options
+            .insert("_FORCE_CLANG".to_string(), "1".to_string());
+        let requested = requested_options(&config);
+        assert_eq!(requested.len(), 2);
+        assert_eq!(requested[0].symbol, "HZ_1000");
+        assert_eq!(requested[1].symbol, "LRU_GEN");
+        assert_eq!(requested[1].origin, "_MGLRU_CONFIG_LRU_GEN");
+    }
+}
@@ -0,0 +1,599 @@
+//! Local Source Mirror
+//!
+//! Offline / air-gapped source mode. A mirror directory holds, per variant:
+//! - `git/<variant>.git`: a bare mirror of the variant's git repository
+//! - `pkgbuild/<variant>/PKGBUILD`: a snapshot of its raw PKGBUILD
+//! - `sources/`: makepkg's `SRCDEST` with the PKGBUILDs' `source=()`
+//!   downloads (tarballs, patches, signatures, VCS clones)
+//!
+//! `goatd mirror sync` populates the mirror while online. With
+//! `AppState::offline_sources` enabled, kernel sources are cloned from the
+//! mirror, `"latest"` versions are resolved from the mirror's tags (falling
+//! back to the mirrored PKGBUILD) and makepkg reads its downloads from
+//! `sources/`, so builds never touch the network.
+
+use crate::config::AppState;
+use crate::kernel::git::{GitError, GitResult};
+use crate::kernel::pkgbuild::{extract_pkgrel, extract_pkgver, fetch_pkgbuild};
+use crate::kernel::sources::{self, KernelSource};
+use crate::models::SourceRef;
+use git2::{Direction, FetchOptions, FetchPrune, Repository};
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::process::Command;
+
+/// Default mirror directory (`~/.cache/goatd/mirror`)
+pub fn default_mirror_dir() -> PathBuf {
+    dirs::cache_dir()
+        .or_else(|| dirs::home_dir().map(|h| h.join(".cache")))
+        .unwrap_or_else(|| PathBuf::from("/tmp"))
+        .join("goatd")
+        .join("mirror")
+}
+
+/// Local mirror of kernel source repositories and PKGBUILD snapshots
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SourceMirror {
+    root: PathBuf,
+}
+
+impl SourceMirror {
+    pub fn new(root: impl Into<PathBuf>) -> Self {
+        SourceMirror { root: root.into() }
+    }
+
+    /// The mirror configured in settings (`source_mirror_path`, or the default directory)
+    pub fn from_settings(state: &AppState) -> Self {
+        if state.source_mirror_path.trim().is_empty() {
+            Self::new(default_mirror_dir())
+        } else {
+            Self::new(state.source_mirror_path.trim())
+        }
+    }
+
+    /// The mirror builds must use, if offline mode is enabled in settings
+    pub fn offline() -> Option<Self> {
+        let state = crate::config::SettingsManager::load().ok()?;
+        state.offline_sources.then(|| Self::from_settings(&state))
+    }
+
+    pub fn root(&self) -> &Path {
+        &self.root
+    }
+
+    /// Bare repository mirroring a variant's git source
+    pub fn repo_path(&self, variant: &str) -> PathBuf {
+        self.root.join("git").join(format!("{}.git", variant))
+    }
+
+    /// Snapshot of a variant's raw PKGBUILD
+    pub fn pkgbuild_path(&self, variant: &str) -> PathBuf {
+        self.root.join("pkgbuild").join(variant).join("PKGBUILD")
+    }
+
+    /// makepkg download cache (`SRCDEST`) shared by all mirrored variants
+    pub fn sources_dir(&self) -> PathBuf {
+        self.root.join("sources")
+    }
+
+    /// Whether a variant's repository has been mirrored
+    pub fn has_repo(&self, variant: &str) -> bool {
+        Repository::open_bare(self.repo_path(variant)).is_ok()
+    }
+
+    /// Create or update the bare mirror of `url` for a variant
+    ///
+    /// All branches and tags are fetched (pruning deleted ones) and `HEAD`
+    /// follows the remote's default branch, so clones of the mirror behave
+    /// like clones of the remote.
+    ///
+    /// # Errors
+    /// Returns `GitError::Repository` if the remote cannot be reached or fetched
+    pub fn sync_repo(&self, variant: &str, url: &str) -> GitResult<()> {
+        let path = self.repo_path(variant);
+        let repo = match Repository::open_bare(&path) {
+            Ok(repo) => {
+                repo.remote_set_url("origin", url)?;
+                repo
+            }
+            Err(_) => {
+                fs::create_dir_all(&path)?;
+                let repo = Repository::init_bare(&path)?;
+                repo.remote_with_fetch("origin", url, "+refs/heads/*:refs/heads/*")?;
+                repo.remote_add_fetch("origin", "+refs/tags/*:refs/tags/*")?;
+                repo
+            }
+        };
+
+        let mut remote = repo.find_remote("origin")?;
+        remote
+            .connect(Direction::Fetch)
+            .map_err(|e| GitError::Repository(format!("Failed to connect to {}: {}", url, e)))?;
+        let default_branch = remote
+            .default_branch()
+            .ok()
+            .and_then(|branch| branch.as_str().map(str::to_string));
+        remote.disconnect()?;
+
+        let mut options = FetchOptions::new();
+        options.prune(FetchPrune::On);
+        remote
+            .fetch(&[] as &[&str], Some(&mut options), None)
+            .map_err(|e| GitError::Repository(format!("Failed to fetch {}: {}", url, e)))?;
+
+        if let Some(branch) = default_branch {
+            repo.set_head(&branch)?;
+        }
+        eprintln!("[Mirror] [SYNC] ✓ {} mirrored into {}", url, path.display());
+        Ok(())
+    }
+
+    /// Store a PKGBUILD snapshot, replacing the previous one atomically
+    pub fn store_pkgbuild(&self, variant: &str, content: &str) -> std::io::Result<()> {
+        let path = self.pkgbuild_path(variant);
+        if let Some(parent) = path.parent() {
+            fs::create_dir_all(parent)?;
+        }
+        let tmp = path.with_extension("tmp");
+        fs::write(&tmp, content)?;
+        fs::rename(&tmp, &path)
+    }
+
+    /// Mirror a variant: its git repository, then the PKGBUILD at the ref its policy selects
+    ///
+    /// Returns the number of tags now in the mirror.
+    pub async fn sync(&self, variant: &str, source: &KernelSource) -> Result<usize, String> {
+        let mirror = self.clone();
+        let (variant_owned, source_owned) = (variant.to_string(), source.clone());
+        let resolved = tokio::task::spawn_blocking(move || {
+            mirror.sync_repo(&variant_owned, &source_owned.git_url)?;
+            // Resolve LatestTag against the fresh mirror instead of the remote
+            mirror
+                .localize(&variant_owned, &source_owned)?
+                .resolve_ref()
+        })
+        .await
+        .map_err(|e| format!("Mirror task failed: {}", e))?
+        .map_err(|e| e.to_string())?;
+
+        let url = source.pkgbuild_url_for(resolved.as_deref());
+        let content = fetch_pkgbuild(&url).await?;
+        self.store_pkgbuild(variant, &content)
+            .map_err(|e| format!("Failed to store PKGBUILD snapshot: {}", e))?;
+        self.sync_sources(variant, source).await?;
+
+        self.tags(variant)
+            .map(|tags| tags.len())
+            .map_err(|e| e.to_string())
+    }
+
+    /// Download a variant's `source=()` entries into [`Self::sources_dir`]
+    ///
+    /// The mirrored repository is checked out at the ref its policy selects
+    /// (local files such as `config` come from there) and
+    /// `makepkg --verifysource` fetches and verifies the rest.
+    pub async fn sync_sources(&self, variant: &str, source: &KernelSource) -> Result<(), String> {
+        let local = self.localize(variant, source).map_err(|e| e.to_string())?;
+        let checkout = tempfile::Builder::new()
+            .prefix("goatd-mirror-")
+            .tempdir()
+            .map_err(|e| format!("Failed to create checkout directory: {}", e))?;
+        let target = checkout.path().join(variant);
+        let clone_target = target.clone();
+        tokio::task::spawn_blocking(move || sources::clone_source(&local, &clone_target))
+            .await
+            .map_err(|e| format!("Mirror task failed: {}", e))?
+            .map_err(|e| e.to_string())?;
+
+        let srcdest = self.sources_dir();
+        fs::create_dir_all(&srcdest)
+            .map_err(|e| format!("Failed to create {}: {}", srcdest.display(), e))?;
+        let status = tokio::process::Command::new("makepkg")
+            .args(["--verifysource", "--noconfirm"])
+            .current_dir(&target)
+            .env("SRCDEST", &srcdest)
+            .status()
+            .await
+            .map_err(|e| format!("Failed to run makepkg --verifysource: {}", e))?;
+        if !status.success() {
+            return Err(format!("makepkg --verifysource exited with {}", status));
+        }
+
+        let missing = self.missing_sources(&target)?;
+        if !missing.is_empty() {
+            return Err(format!("Sources not mirrored: {}", missing.join(", ")));
+        }
+        eprintln!(
+            "[Mirror] [SYNC] ✓ Sources of {} cached in {}",
+            variant,
+            srcdest.display()
+        );
+        Ok(())
+    }
+
+    /// `source=()` entries of the PKGBUILD in `dir` an offline build could not find
+    ///
+    /// Downloads must be in [`Self::sources_dir`], local files next to the PKGBUILD.
+    pub fn missing_sources(&self, dir: &Path) -> Result<Vec<String>, String> {
+        let srcdest = self.sources_dir();
+        Ok(pkgbuild_sources(dir)?
+            .into_iter()
+            .filter(|entry| {
+                let name = source_file_name(entry);
+                let location = if is_remote_source(entry) {
+                    srcdest.join(&name)
+                } else {
+                    dir.join(&name)
+                };
+                !location.exists()
+            })
+            .collect())
+    }
+
+    /// `source` with its git URL pointed at the mirrored repository
+    ///
+    /// # Errors
+    /// Returns `GitError::Repository` if the variant has not been mirrored
+    pub fn localize(&self, variant: &str, source: &KernelSource) -> GitResult<KernelSource> {
+        if !self.has_repo(variant) {
+            return Err(GitError::Repository(format!(
+                "No mirror of '{}' in {} - run `goatd mirror sync` while online",
+                variant,
+                self.root.display()
+            )));
+        }
+        Ok(KernelSource {
+            git_url: self.repo_path(variant).to_string_lossy().to_string(),
+            ..source.clone()
+        })
+    }
+
+    /// Tag names in a variant's mirrored repository
+    pub fn tags(&self, variant: &str) -> GitResult<Vec<String>> {
+        let repo = Repository::open_bare(self.repo_path(variant))?;
+        let names = repo.tag_names(None)?;
+        Ok(names.iter().flatten().map(str::to_string).collect())
+    }
+
+    /// Resolve `"latest"` for a variant from the mirror alone
+    ///
+    /// A tag policy (or the newest tag, for the default branch) wins; release
+    /// tags of packaging repos already read `pkgver-pkgrel`. Untagged repos and
+    /// branch policies fall back to the mirrored PKGBUILD at that ref, then to
+    /// the PKGBUILD snapshot.
+    pub fn latest_version(&self, variant: &str, source: &KernelSource) -> Result<String, String> {
+        let tags = self.tags(variant).map_err(|e| e.to_string())?;
+        let tag = match &source.reference {
+            SourceRef::Tag(tag) => tags.contains(tag).then(|| tag.clone()),
+            SourceRef::LatestTag(prefix) => sources::latest_tag(&tags, prefix),
+            SourceRef::DefaultBranch => sources::latest_tag(&tags, ""),
+            SourceRef::Branch(_) => None,
+        };
+        if let Some(tag) = tag {
+            eprintln!("[Mirror] [VERSION] {} resolved from tag '{}'", variant, tag);
+            return Ok(tag_version(&tag));
+        }
+
+        let content = self
+            .pkgbuild_at_ref(variant, &source.reference)
+            .or_else(|_| fs::read_to_string(self.pkgbuild_path(variant)))
+            .map_err(|e| format!("No tag or PKGBUILD for '{}' in the mirror: {}", variant, e))?;
+        Ok(format!(
+            "{}-{}",
+            extract_pkgver(&content)?,
+            extract_pkgrel(&content)?
+        ))
+    }
+
+    /// PKGBUILD blob at the ref a policy selects in the bare mirror
+    fn pkgbuild_at_ref(&self, variant: &str, reference: &SourceRef) -> std::io::Result<String> {
+        let spec = match reference {
+            SourceRef::Branch(branch) => format!("refs/heads/{}:PKGBUILD", branch),
+            SourceRef::Tag(tag) => format!("refs/tags/{}:PKGBUILD", tag),
+            SourceRef::DefaultBranch | SourceRef::LatestTag(_) => "HEAD:PKGBUILD".to_string(),
+        };
+        let blob = Repository::open_bare(self.repo_path(variant))
+            .and_then(|repo| {
+                repo.revparse_single(&spec)?
+                    .peel_to_blob()
+                    .map(|blob| String::from_utf8_lossy(blob.content()).into_owned())
+            })
+            .map_err(|e| std::io::Error::new(std::io::ErrorKind::NotFound, e.to_string()))?;
+        Ok(blob)
+    }
+}
+
+/// `source=()` entries of the PKGBUILD in `dir`, including the host's `source_<arch>`
+///
+/// The PKGBUILD is sourced by bash, as makepkg does, so variables are expanded.
+pub fn pkgbuild_sources(dir: &Path) -> Result<Vec<String>, String> {
+    let script = format!(
+        "source ./PKGBUILD >/dev/null 2>&1; printf '%s\\n' \"${{source[@]}}\" \"${{source_{}[@]}}\"",
+        std::env::consts::ARCH
+    );
+    let output = Command::new("bash")
+        .args(["-c", &script])
+        .current_dir(dir)
+        .output()
+        .map_err(|e| format!("Failed to read PKGBUILD sources: {}", e))?;
+    if !output.status.success() {
+        return Err(format!(
+            "Failed to read PKGBUILD sources in {}",
+            dir.display()
+        ));
+    }
+    Ok(String::from_utf8_lossy(&output.stdout)
+        .lines()
+        .map(str::trim)
+        .filter(|line| !line.is_empty())
+        .map(str::to_string)
+        .collect())
+}
+
+/// Whether makepkg downloads a source entry (into `SRCDEST`) rather than reading it locally
+fn is_remote_source(entry: &str) -> bool {
+    let location = entry.split_once("::").map_or(entry, |(_, url)| url);
+    location.contains("://")
+}
+
+/// Name makepkg stores a source entry under
+///
+/// `name::url` uses `name`; otherwise the last URL component, without the
+/// fragment/query of VCS sources and their `.git` suffix.
+fn source_file_name(entry: &str) -> String {
+    if let Some((name, _)) = entry.split_once("::") {
+        return name.to_string();
+    }
+    let base = entry.rsplit('/').next().unwrap_or(entry);
+    let scheme = entry.split("://").next().unwrap_or_default();
+    if scheme.starts_with("git") || scheme.contains('+') {
+        let base = base.split(['#', '?']).next().unwrap_or(base);
+        base.strip_suffix(".git").unwrap_or(base).to_string()
+    } else {
+        base.to_string()
+    }
+}
+
+/// Version string of a release tag (`v6.12.2` → `6.12.2`, `6.12.1.arch1-1` unchanged)
+fn tag_version(tag: &str) -> String {
+    match tag.strip_prefix('v') {
+        Some(rest) if rest.starts_with(|c: char| c.is_ascii_digit()) => rest.to_string(),
+        _ => tag.to_string(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use git2::{Oid, Signature};
+
+    /// Origin repository with tagged PKGBUILD releases, plus an untagged head commit
+    fn origin_repo(path: &Path, releases: &[(&str, &str)], head: &str) -> Repository {
+        let repo = Repository::init(path).unwrap();
+        let mut parent: Option<Oid> = None;
+        let mut commit = |content: &str| {
+            fs::write(path.join("PKGBUILD"), content).unwrap();
+            let mut index = repo.index().unwrap();
+            index.add_path(Path::new("PKGBUILD")).unwrap();
+            let tree = repo.find_tree(index.write_tree().unwrap()).unwrap();
+            let sig = Signature::now("GOATd", "goatd@example.com").unwrap();
+            let parents: Vec<git2::Commit> = parent
+                .map(|oid| vec![repo.find_commit(oid).unwrap()])
+                .unwrap_or_default();
+            let parent_refs: Vec<&git2::Commit> = parents.iter().collect();
+            let oid = repo
+                .commit(Some("HEAD"), &sig, &sig, content, &tree, &parent_refs)
+                .unwrap();
+            parent = Some(oid);
+            oid
+        };
+        for (tag, content) in releases {
+            let oid = commit(content);
+            let object = repo.find_object(oid, None).unwrap();
+            repo.tag_lightweight(tag, &object, false).unwrap();
+        }
+        commit(head);
+        drop(commit);
+        repo
+    }
+
+    fn source(url: &Path, reference: SourceRef) -> KernelSource {
+        KernelSource {
+            git_url: url.to_string_lossy().to_string(),
+            pkgbuild_url: String::new(),
+            reference,
+        }
+    }
+
+    #[test]
+    fn test_sync_and_resolve_latest_from_tags() {
+        let temp = tempfile::tempdir().unwrap();
+        let origin_path = temp.path().join("origin");
+        let origin = origin_repo(
+            &origin_path,
+            &[
+                ("6.12.1.arch1-1", "pkgver=6.12.1.arch1\npkgrel=1\n"),
+                ("6.12.2.arch1-1", "pkgver=6.12.2.arch1\npkgrel=1\n"),
+            ],
+            "pkgver=6.13.arch1\npkgrel=1\n",
+        );
+        let mirror = SourceMirror::new(temp.path().join("mirror"));
+        assert!(!mirror.has_repo("linux"));
+
+        mirror
+            .sync_repo("linux", &origin_path.to_string_lossy())
+            .unwrap();
+        assert!(mirror.has_repo("linux"));
+        let mut tags = mirror.tags("linux").unwrap();
+        tags.sort();
+        assert_eq!(tags, vec!["6.12.1.arch1-1", "6.12.2.arch1-1"]);
+
+        let upstream = source(&origin_path, SourceRef::DefaultBranch);
+        assert_eq!(
+            mirror.latest_version("linux", &upstream).unwrap(),
+            "6.12.2.arch1-1"
+        );
+        let pinned = source(&origin_path, SourceRef::Tag("6.12.1.arch1-1".to_string()));
+        assert_eq!(
+            mirror.latest_version("linux", &pinned).unwrap(),
+            "6.12.1.arch1-1"
+        );
+        let head_branch = origin.head().unwrap().shorthand().unwrap().to_string();
+        let branch = source(&origin_path, SourceRef::Branch(head_branch));
+        assert_eq!(
+            mirror.latest_version("linux", &branch).unwrap(),
+            "6.13.arch1-1"
+        );
+
+        // A re-sync picks up new releases
+        let head = origin.head().unwrap().peel_to_commit().unwrap();
+        origin
+            .tag_lightweight("6.13.arch1-1", head.as_object(), false)
+            .unwrap();
+        mirror
+            .sync_repo("linux", &origin_path.to_string_lossy())
+            .unwrap();
+        assert_eq!(
+            mirror.latest_version("linux", &upstream).unwrap(),
+            "6.13.arch1-1"
+        );
+    }
+
+    #[test]
+    fn test_untagged_mirror_falls_back_to_pkgbuild() {
+        let temp = tempfile::tempdir().unwrap();
+        let origin_path = temp.path().join("origin");
+        origin_repo(&origin_path, &[], "pkgver=6.12.9\npkgrel=3\n");
+        let mirror = SourceMirror::new(temp.path().join("mirror"));
+        mirror
+            .sync_repo("linux-acme", &origin_path.to_string_lossy())
+            .unwrap();
+
+        let upstream = source(&origin_path, SourceRef::DefaultBranch);
+        assert_eq!(
+            mirror.latest_version("linux-acme", &upstream).unwrap(),
+            "6.12.9-3"
+        );
+
+        // Missing tag: the PKGBUILD snapshot is the last resort
+        let pinned = source(&origin_path, SourceRef::Tag("v7.0".to_string()));
+        assert!(mirror.latest_version("linux-acme", &pinned).is_err());
+        mirror
+            .store_pkgbuild("linux-acme", "pkgver=7.0\npkgrel=1\n")
+            .unwrap();
+        assert_eq!(
+            mirror.latest_version("linux-acme", &pinned).unwrap(),
+            "7.0-1"
+        );
+    }
+
+    #[test]
+    fn test_localized_source_clones_from_mirror() {
+        let temp = tempfile::tempdir().unwrap();
+        let origin_path = temp.path().join("origin");
+        origin_repo(
+            &origin_path,
+            &[
+                ("v6.12.1", "pkgver=6.12.1\n"),
+                ("v6.12.2", "pkgver=6.12.2\n"),
+            ],
+            "pkgver=6.13.0\n",
+        );
+        let mirror = SourceMirror::new(temp.path().join("mirror"));
+        let upstream = source(&origin_path, SourceRef::LatestTag("v6.12".to_string()));
+        assert!(mirror.localize("linux-acme", &upstream).is_err());
+
+        mirror
+            .sync_repo("linux-acme", &origin_path.to_string_lossy())
+            .unwrap();
+        // Cut the mirror off from its origin: everything must come from the mirror
+        fs::remove_dir_all(&origin_path).unwrap();
+
+        let local = mirror.localize("linux-acme", &upstream).unwrap();
+        assert_eq!(local.reference, upstream.reference);
+        assert_eq!(local.resolve_ref().unwrap().as_deref(), Some("v6.12.2"));
+        assert_eq!(
+            mirror.latest_version("linux-acme", &upstream).unwrap(),
+            "6.12.2"
+        );
+
+        let target = temp.path().join("workspace").join("linux-acme");
+        sources::clone_source(&local, &target).unwrap();
+        assert_eq!(
+            fs::read_to_string(target.join("PKGBUILD")).unwrap(),
+            "pkgver=6.12.2\n"
+        );
+    }
+
+    #[test]
+    fn test_offline_checkout_has_all_sources() {
+        let temp = tempfile::tempdir().unwrap();
+        let origin_path = temp.path().join("origin");
+        origin_repo(
+            &origin_path,
+            &[(
+                "6.12.2.arch1-1",
+                "pkgver=6.12.2.arch1\npkgrel=1\n_srcname=linux-${pkgver%.*}\n\
+                 source=(\n  https://cdn.kernel.invalid/v6.x/${_srcname}.tar.xz\n  \
+                 ${_srcname}.tar.sign::https://cdn.kernel.invalid/v6.x/${_srcname}.tar.sign\n  \
+                 git+https://git.invalid/patches.git#tag=v6.12\n  PKGBUILD\n)\n",
+            )],
+            "pkgver=6.13.arch1\npkgrel=1\n",
+        );
+        let mirror = SourceMirror::new(temp.path().join("mirror"));
+        mirror
+            .sync_repo("linux", &origin_path.to_string_lossy())
+            .unwrap();
+        let upstream = source(&origin_path, SourceRef::LatestTag(String::new()));
+        let local = mirror.localize("linux", &upstream).unwrap();
+
+        // What `makepkg --verifysource` leaves in SRCDEST during `mirror sync`
+        let srcdest = mirror.sources_dir();
+        fs::create_dir_all(srcdest.join("patches")).unwrap();
+        fs::write(srcdest.join("linux-6.12.2.tar.xz"), b"tarball").unwrap();
+
+        // Network disabled: the origin is gone and every URL is unresolvable
+        fs::remove_dir_all(&origin_path).unwrap();
+        let target = temp.path().join("workspace").join("linux");
+        sources::clone_source(&local, &target).unwrap();
+        assert_eq!(
+            mirror.missing_sources(&target).unwrap(),
+            vec!["linux-6.12.2.tar.sign::https://cdn.kernel.invalid/v6.x/linux-6.12.2.tar.sign"]
+        );
+
+        fs::write(srcdest.join("linux-6.12.2.tar.sign"), b"signature").unwrap();
+        assert!(mirror.missing_sources(&target).unwrap().is_empty());
+    }
+
+    #[test]
+    fn test_mirror_layout() {
+        let mirror = SourceMirror::new("/srv/goatd-mirror");
+        assert_eq!(
+            mirror.repo_path("linux-lts"),
+            PathBuf::from("/srv/goatd-mirror/git/linux-lts.git")
+        );
+        assert_eq!(
+            mirror.pkgbuild_path("linux-lts"),
+            PathBuf::from("/srv/goatd-mirror/pkgbuild/linux-lts/PKGBUILD")
+        );
+        assert_eq!(
+            mirror.sources_dir(),
+            PathBuf::from("/srv/goatd-mirror/sources")
+        );
+        assert_eq!(
+            source_file_name("git+https://git.invalid/linux.git#tag=v6.12"),
+            "linux"
+        );
+        assert!(!is_remote_source("config"));
+
+        let mut state = AppState::default();
+        assert_eq!(
+            SourceMirror::from_settings(&state).root(),
+            default_mirror_dir()
+        );
+        state.source_mirror_path = "/srv/goatd-mirror".to_string();
+        assert_eq!(SourceMirror::from_settings(&state), mirror);
+        assert_eq!(tag_version("v6.12.2"), "6.12.2");
+        assert_eq!(tag_version("valhalla-1"), "valhalla-1");
+    }
+}
@@ -4,6 +4,14 @@
 //! - Git operations (cloning, fetching) and source integrity verification
 //! - Package management (listing, scanning, deletion)
 //! - System audits (kernel info, performance metrics)
+//! - Secure Boot signing of the kernel image and modules
+//! - User patch queues applied before configuration
+//! - Kconfig diffs between builds and the running kernel
+//! - Compiler cache (ccache/sccache) wrappers and statistics
+//! - Kconfig tree parsing and validation of requested options
+//! - Toolchain version probing and minimum-version checks
+//! - Profile-guided optimization (PGO/AutoFDO) profiles and collection
+//! - Local source mirror for offline / air-gapped builds
 
 // Phase 1: Package management submodule
 pub mod manager;
@@ -27,3 +35,27 @@ pub mod sources;
 
 // Phase 3: PKGBUILD version polling submodule
 pub mod pkgbuild;
+
+// Phase 4: Secure Boot (MOK) signing submodule
+pub mod signing;
+
+// Phase 4: User patch queue submodule
+pub mod patch_queue;
+
+// Phase 4: Kconfig diff submodule
+pub mod config_diff;
+
+// Phase 4: Compiler cache submodule
+pub mod compiler_cache;
+
+// Phase 4: Kconfig tree parser submodule
+pub mod kconfig_tree;
+
+// Phase 4: Toolchain probe submodule
+pub mod toolchain;
+
+// Phase 4: Profile-guided optimization submodule
+pub mod pgo;
+
+// Phase 4: Local source mirror submodule
+pub mod mirror;
@@ -0,0 +1,494 @@
+//! User patch queue applied to the kernel source before configuration.
+//!
+//! Patches live in a per-profile directory, `<workspace>/patches/<profile>/`.
+//! Their order comes from a quilt-style `series` file when one exists, and from
+//! the file names otherwise (`0001-*.patch`, `0002-*.patch`, ...).
+//!
+//! Every series is dry-run with `git apply --check` (via [`GitManager`]) before
+//! anything is applied. A patch that does not apply is reported as
+//! [`PatchError::Conflict`] naming the first failing patch. The applied list is
+//! recorded in `MPLMetadata` so a built kernel can be traced back to its patch set.
+
+use crate::error::PatchError;
+use crate::kernel::git::GitManager;
+use sha2::{Digest, Sha256};
+use std::fs;
+use std::path::{Path, PathBuf};
+
+/// Directory below the workspace holding one patch queue per profile.
+pub const PATCH_QUEUE_DIR: &str = "patches";
+
+/// Optional file listing the patch order, one file name per line.
+pub const SERIES_FILE: &str = "series";
+
+/// Directory inside the kernel source directory the queue is staged into for makepkg.
+pub const STAGED_QUEUE_DIR: &str = "goatd-patches";
+
+/// Result type for patch queue operations
+pub type PatchQueueResult<T> = std::result::Result<T, PatchError>;
+
+/// A single patch in the queue.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct QueuedPatch {
+    /// File name (e.g. `0001-sched-fair-fix.patch`)
+    pub name: String,
+    /// Absolute path of the patch file
+    pub path: PathBuf,
+    /// SHA-256 of the patch contents (hex)
+    pub sha256: String,
+}
+
+impl QueuedPatch {
+    fn load(path: PathBuf) -> PatchQueueResult<Self> {
+        let name = path
+            .file_name()
+            .map(|n| n.to_string_lossy().to_string())
+            .unwrap_or_default();
+        validate_patch_name(&name)?;
+
+        let content = fs::read(&path).map_err(|e| {
+            PatchError::FileNotFound(format!("Cannot read patch {}: {}", path.display(), e))
+        })?;
+
+        Ok(QueuedPatch {
+            name,
+            path,
+            sha256: format!("{:x}", Sha256::digest(&content)),
+        })
+    }
+
+    /// Entry recorded in `MPLMetadata::applied_patches` (`<name>@<sha256 prefix>`).
+    pub fn mpl_entry(&self) -> String {
+        format!("{}@{}", self.name, &self.sha256[..12])
+    }
+}
+
+/// Patch names end up in shell snippets and the space-separated MPL list.
+fn validate_patch_name(name: &str) -> PatchQueueResult<()> {
+    let valid = !name.is_empty()
+        && !name.starts_with('.')
+        && name
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || matches!(c, '-' | '_' | '.' | '+'));
+
+    if valid {
+        Ok(())
+    } else {
+        Err(PatchError::ValidationFailed(format!(
+            "Invalid patch file name '{}': use letters, digits, '-', '_', '.' and '+' only",
+            name
+        )))
+    }
+}
+
+/// Ordered set of user patches for one profile.
+#[derive(Debug, Clone, Default)]
+pub struct PatchQueue {
+    dir: PathBuf,
+    patches: Vec<QueuedPatch>,
+}
+
+impl PatchQueue {
+    /// Queue directory for a profile: `<workspace>/patches/<profile>` (lowercase).
+    pub fn profile_dir(workspace: &Path, profile: &str) -> PathBuf {
+        workspace.join(PATCH_QUEUE_DIR).join(profile.to_lowercase())
+    }
+
+    /// Load the queue configured for `profile` in `workspace`.
+    pub fn for_profile(workspace: &Path, profile: &str) -> PatchQueueResult<Self> {
+        Self::load(&Self::profile_dir(workspace, profile))
+    }
+
+    /// Load a queue directory. A missing directory is an empty queue.
+    pub fn load(dir: &Path) -> PatchQueueResult<Self> {
+        if !dir.is_dir() {
+            return Ok(PatchQueue {
+                dir: dir.to_path_buf(),
+                patches: Vec::new(),
+            });
+        }
+
+        let series_path = dir.join(SERIES_FILE);
+        let paths: Vec<PathBuf> = if series_path.is_file() {
+            let series = fs::read_to_string(&series_path).map_err(|e| {
+                PatchError::FileNotFound(format!("Cannot read {}: {}", series_path.display(), e))
+            })?;
+            series
+                .lines()
+                .map(|line| line.split('#').next().unwrap_or("").trim())
+                .filter(|line| !line.is_empty())
+                .map(|name| {
+                    let path = dir.join(name);
+                    if path.is_file() {
+                        Ok(path)
+                    } else {
+                        Err(PatchError::FileNotFound(format!(
+                            "Patch '{}' listed in {} does not exist",
+                            name,
+                            series_path.display()
+                        )))
+                    }
+                })
+                .collect::<PatchQueueResult<_>>()?
+        } else {
+            let entries = fs::read_dir(dir).map_err(|e| {
+                PatchError::FileNotFound(format!("Cannot read {}: {}", dir.display(), e))
+            })?;
+            let mut paths: Vec<PathBuf> = entries
+                .filter_map(|entry| entry.ok())
+                .map(|entry| entry.path())
+                .filter(|path| {
+                    path.is_file()
+                        && path
+                            .extension()
+                            .is_some_and(|ext| ext == "patch" || ext == "diff")
+                })
+                .collect();
+            paths.sort();
+            paths
+        };
+
+        let patches = paths
+            .into_iter()
+            .map(QueuedPatch::load)
+            .collect::<PatchQueueResult<Vec<_>>>()?;
+
+        Ok(PatchQueue {
+            dir: dir.to_path_buf(),
+            patches,
+        })
+    }
+
+    /// Directory the queue was loaded from.
+    pub fn dir(&self) -> &Path {
+        &self.dir
+    }
+
+    /// Patches in application order.
+    pub fn patches(&self) -> &[QueuedPatch] {
+        &self.patches
+    }
+
+    /// Whether the queue has no patches.
+    pub fn is_empty(&self) -> bool {
+        self.patches.is_empty()
+    }
+
+    /// Number of patches in the queue.
+    pub fn len(&self) -> usize {
+        self.patches.len()
+    }
+
+    /// Entries for `MPLMetadata::applied_patches`.
+    pub fn mpl_entries(&self) -> Vec<String> {
+        self.patches.iter().map(QueuedPatch::mpl_entry).collect()
+    }
+
+    fn paths(&self, count: usize) -> Vec<PathBuf> {
+        self.patches[..count]
+            .iter()
+            .map(|p| p.path.clone())
+            .collect()
+    }
+
+    /// Dry-run the whole queue against `tree` with `git apply --check`.
+    ///
+    /// On failure the series is re-checked patch by patch to name the first
+    /// patch that does not apply.
+    pub fn check(&self, tree: &GitManager) -> PatchQueueResult<()> {
+        if self.is_empty() {
+            return Ok(());
+        }
+        let series_error = match tree.check_patches(&self.paths(self.len())) {
+            Ok(()) => return Ok(()),
+            Err(e) => e,
+        };
+
+        for count in 1..self.len() {
+            if let Err(e) = tree.check_patches(&self.paths(count)) {
+                return Err(PatchError::Conflict {
+                    patch: self.patches[count - 1].name.clone(),
+                    details: e.to_string(),
+                });
+            }
+        }
+
+        // Every shorter prefix applies, so the last patch is the one that conflicts
+        Err(PatchError::Conflict {
+            patch: self.patches[self.len() - 1].name.clone(),
+            details: series_error.to_string(),
+        })
+    }
+
+    /// Dry-run the queue against a tree extracted by makepkg, before prepare()
+    /// applies the staged copy.
+    ///
+    /// A tree that already carries the whole queue (left over from an earlier
+    /// build's prepare()) passes as well.
+    pub fn check_extracted(&self, tree: &GitManager) -> PatchQueueResult<()> {
+        if self.is_empty() || tree.patches_applied(&self.paths(self.len())) {
+            return Ok(());
+        }
+        self.check(tree)
+    }
+
+    /// Dry-run, then apply the queue to `tree`. Returns the MPL entries.
+    ///
+    /// A tree that already carries the whole queue (e.g. a re-run on the same
+    /// source) is left untouched.
+    pub fn apply(&self, tree: &GitManager) -> PatchQueueResult<Vec<String>> {
+        if self.is_empty() {
+            return Ok(Vec::new());
+        }
+
+        let all = self.paths(self.len());
+        if tree.patches_applied(&all) {
+            eprintln!(
+                "[Patcher] [PATCH-QUEUE] {} patch(es) already applied to {}",
+                self.len(),
+                tree.repo_path().display()
+            );
+            return Ok(self.mpl_entries());
+        }
+
+        self.check(tree)?;
+        tree.apply_patches(&all).map_err(|e| PatchError::Conflict {
+            patch: self.patches[0].name.clone(),
+            details: e.to_string(),
+        })?;
+
+        for patch in &self.patches {
+            eprintln!("[Patcher] [PATCH-QUEUE] ✓ Applied {}", patch.name);
+        }
+        Ok(self.mpl_entries())
+    }
+
+    /// Copy the queue into `<kernel_path>/goatd-patches` with a `series` file.
+    ///
+    /// Used for PKGBUILD builds, where the kernel tree only exists once makepkg
+    /// extracts it; prepare() then applies the staged series. Any previously
+    /// staged queue is replaced.
+    pub fn stage(&self, kernel_path: &Path) -> PatchQueueResult<PathBuf> {
+        let staged = kernel_path.join(STAGED_QUEUE_DIR);
+        if staged.exists() {
+            fs::remove_dir_all(&staged).map_err(|e| {
+                PatchError::PatchFailed(format!("Cannot clear {}: {}", staged.display(), e))
+            })?;
+        }
+        fs::create_dir_all(&staged).map_err(|e| {
+            PatchError::PatchFailed(format!("Cannot create {}: {}", staged.display(), e))
+        })?;
+
+        let mut series = String::new();
+        for patch in &self.patches {
+            fs::copy(&patch.path, staged.join(&patch.name)).map_err(|e| {
+                PatchError::PatchFailed(format!("Cannot stage {}: {}", patch.name, e))
+            })?;
+            series.push_str(&patch.name);
+            series.push('\n');
+        }
+        fs::write(staged.join(SERIES_FILE), series)
+            .map_err(|e| PatchError::PatchFailed(format!("Cannot write series file: {}", e)))?;
+
+        Ok(staged)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::process::Command;
+    use tempfile::TempDir;
+
+    const ORIGINAL: &str = "int sched_latency = 6;\nint sched_granularity = 3;\n";
+
+    fn git_available() -> bool {
+        Command::new("git")
+            .arg("--version")
+            .output()
+            .map(|o| o.status.success())
+            .unwrap_or(false)
+    }
+
+    /// Unified diff against `kernel/sched.c` changing one line.
+    fn make_patch(from: &str, to: &str) -> String {
+        format!(
+            "--- a/kernel/sched.c\n+++ b/kernel/sched.c\n@@ -1,2 +1,2 @@\n{}\n int sched_granularity = 3;\n",
+            [format!("-{}", from), format!("+{}", to)].join("\n")
+        )
+    }
+
+    fn source_tree() -> TempDir {
+        let tree = TempDir::new().unwrap();
+        fs::create_dir_all(tree.path().join("kernel")).unwrap();
+        fs::write(tree.path().join("kernel/sched.c"), ORIGINAL).unwrap();
+        tree
+    }
+
+    fn queue_dir(patches: &[(&str, String)]) -> TempDir {
+        let dir = TempDir::new().unwrap();
+        for (name, content) in patches {
+            fs::write(dir.path().join(name), content).unwrap();
+        }
+        dir
+    }
+
+    #[test]
+    fn test_missing_dir_is_empty_queue() {
+        let workspace = TempDir::new().unwrap();
+        let queue = PatchQueue::for_profile(workspace.path(), "Gaming").unwrap();
+        assert!(queue.is_empty());
+        assert_eq!(
+            queue.dir(),
+            workspace.path().join("patches").join("gaming").as_path()
+        );
+    }
+
+    #[test]
+    fn test_order_by_file_name() {
+        let dir = queue_dir(&[
+            ("0002-second.patch", "b".to_string()),
+            ("0001-first.patch", "a".to_string()),
+            ("0003-third.diff", "c".to_string()),
+            ("README.md", "not a patch".to_string()),
+        ]);
+        let queue = PatchQueue::load(dir.path()).unwrap();
+        let names: Vec<&str> = queue.patches().iter().map(|p| p.name.as_str()).collect();
+        assert_eq!(
+            names,
+            vec!["0001-first.patch", "0002-second.patch", "0003-third.diff"]
+        );
+    }
+
+    #[test]
+    fn test_series_file_controls_order() {
+        let dir = queue_dir(&[
+            ("a.patch", "a".to_string()),
+            ("b.patch", "b".to_string()),
+            ("unused.patch", "u".to_string()),
+            (
+                SERIES_FILE,
+                "# backports first\nb.patch\n\na.patch # then ours\n".to_string(),
+            ),
+        ]);
+        let queue = PatchQueue::load(dir.path()).unwrap();
+        let names: Vec<&str> = queue.patches().iter().map(|p| p.name.as_str()).collect();
+        assert_eq!(names, vec!["b.patch", "a.patch"]);
+    }
+
+    #[test]
+    fn test_series_with_missing_patch_fails() {
+        let dir = queue_dir(&[(SERIES_FILE, "missing.patch\n".to_string())]);
+        assert!(matches!(
+            PatchQueue::load(dir.path()),
+            Err(PatchError::FileNotFound(_))
+        ));
+    }
+
+    #[test]
+    fn test_rejects_unsafe_names() {
+        let dir = queue_dir(&[("bad name.patch", "x".to_string())]);
+        assert!(matches!(
+            PatchQueue::load(dir.path()),
+            Err(PatchError::ValidationFailed(_))
+        ));
+    }
+
+    #[test]
+    fn test_mpl_entries_carry_hash() {
+        let dir = queue_dir(&[("0001-fix.patch", "content".to_string())]);
+        let queue = PatchQueue::load(dir.path()).unwrap();
+        let entries = queue.mpl_entries();
+        assert_eq!(entries.len(), 1);
+        assert!(entries[0].starts_with("0001-fix.patch@"));
+        assert_eq!(entries[0].len(), "0001-fix.patch@".len() + 12);
+    }
+
+    #[test]
+    fn test_stage_writes_series() {
+        let dir = queue_dir(&[
+            ("0001-a.patch", "a".to_string()),
+            ("0002-b.patch", "b".to_string()),
+        ]);
+        let kernel = TempDir::new().unwrap();
+        let queue = PatchQueue::load(dir.path()).unwrap();
+
+        let staged = queue.stage(kernel.path()).unwrap();
+        assert_eq!(staged, kernel.path().join(STAGED_QUEUE_DIR));
+        assert_eq!(
+            fs::read_to_string(staged.join(SERIES_FILE)).unwrap(),
+            "0001-a.patch\n0002-b.patch\n"
+        );
+        assert!(staged.join("0002-b.patch").is_file());
+
+        // Restaging an empty queue clears the old patches
+        PatchQueue::default().stage(kernel.path()).unwrap();
+        assert!(!staged.join("0001-a.patch").exists());
+    }
+
+    #[test]
+    fn test_apply_stacked_series() {
+        if !git_available() {
+            eprintln!("git unavailable, skipping");
+            return;
+        }
+        let tree = source_tree();
+        let dir = queue_dir(&[
+            (
+                "0001-latency.patch",
+                make_patch("int sched_latency = 6;", "int sched_latency = 4;"),
+            ),
+            (
+                "0002-latency-again.patch",
+                make_patch("int sched_latency = 4;", "int sched_latency = 3;"),
+            ),
+        ]);
+        let queue = PatchQueue::load(dir.path()).unwrap();
+        let git = GitManager::for_source_tree(tree.path());
+
+        let applied = queue.apply(&git).unwrap();
+        assert_eq!(applied.len(), 2);
+        let content = fs::read_to_string(tree.path().join("kernel/sched.c")).unwrap();
+        assert!(content.contains("int sched_latency = 3;"));
+
+        // Re-running on the patched tree is a no-op, not a conflict
+        assert_eq!(queue.apply(&git).unwrap(), applied);
+        assert!(queue.check(&git).is_err());
+        queue.check_extracted(&git).unwrap();
+    }
+
+    #[test]
+    fn test_conflict_names_failing_patch() {
+        if !git_available() {
+            eprintln!("git unavailable, skipping");
+            return;
+        }
+        let tree = source_tree();
+        let dir = queue_dir(&[
+            (
+                "0001-ok.patch",
+                make_patch("int sched_latency = 6;", "int sched_latency = 4;"),
+            ),
+            (
+                "0002-stale.patch",
+                make_patch("int sched_latency = 8;", "int sched_latency = 2;"),
+            ),
+        ]);
+        let queue = PatchQueue::load(dir.path()).unwrap();
+        let git = GitManager::for_source_tree(tree.path());
+
+        match queue.apply(&git) {
+            Err(PatchError::Conflict { patch, .. }) => assert_eq!(patch, "0002-stale.patch"),
+            other => panic!("expected conflict, got {:?}", other),
+        }
+        assert!(matches!(
+            queue.check_extracted(&git),
+            Err(PatchError::Conflict { .. })
+        ));
+        // Dry run failed, so nothing was applied
+        assert_eq!(
+            fs::read_to_string(tree.path().join("kernel/sched.c")).unwrap(),
+            ORIGINAL
+        );
+    }
+}
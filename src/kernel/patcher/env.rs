@@ -154,6 +154,17 @@ pub fn sanitize_build_environment(env_vars: &mut HashMap<String, String>) {
     eprintln!("[Patcher] [SANITIZE] Environment sanitization complete");
 }
 
+/// makepkg's download cache (`SRCDEST`), shared by every build
+///
+/// In offline source mode this is the mirror's `sources/` directory, filled
+/// by `goatd mirror sync`.
+pub fn source_cache_dir() -> String {
+    if let Some(mirror) = crate::kernel::mirror::SourceMirror::offline() {
+        return mirror.sources_dir().to_string_lossy().to_string();
+    }
+    std::env::var("SRCDEST").unwrap_or_else(|_| "/tmp/kernel-sources".to_string())
+}
+
 /// Prepares purified build environment variables for toolchain enforcement.
 ///
 /// Centralizes environment variable setup to ensure:
@@ -403,7 +414,7 @@ pub fn prepare_build_environment(
     // ============================================================================
     // CRITICAL: SRCDEST is now handled by the patcher, not the executor.
     // This enables smart caching of source artifacts across builds.
-    let srcdest = std::env::var("SRCDEST").unwrap_or_else(|_| "/tmp/kernel-sources".to_string());
+    let srcdest = source_cache_dir();
     env_vars.insert("SRCDEST".to_string(), srcdest.clone());
     eprintln!(
         "[Patcher] [ENV] [CACHE] Set SRCDEST={} for smart asset reuse",
@@ -3,6 +3,8 @@
 //! This module handles the generation and application of kernel configuration
 //! options through both native KConfig injection and direct .config manipulation.
 
+use crate::config::cmdline::CMDLINE_KEY;
+use crate::kernel::toolchain::CLANG_VERSION_KEY;
 use crate::error::PatchError;
 use crate::models::{HardeningLevel, LtoType, HardwareContext};
 use once_cell::sync::Lazy;
@@ -86,7 +88,10 @@ impl crate::kernel::patcher::KernelPatcher {
 
         content.push_str("\n# Clang/LLVM toolchain enforcement\n");
         content.push_str("CONFIG_CC_IS_CLANG=y\n");
-        content.push_str("CONFIG_CLANG_VERSION=190106\n");
+        // Probed by kernel::toolchain; without it Kconfig derives the value from $(CC)
+        if let Some(clang_version) = options.get(CLANG_VERSION_KEY) {
+            content.push_str(&format!("CONFIG_CLANG_VERSION={}\n", clang_version));
+        }
 
         // STEP 2: Inject user-provided options (skip special prefix keys)
         content.push_str("\n# User-provided configuration options\n");
@@ -122,6 +127,14 @@ impl crate::kernel::patcher::KernelPatcher {
             );
         }
 
+        // STEP 4: Baked-in kernel command line resolved by the finalizer
+        if let Some(cmdline) = options.get(CMDLINE_KEY) {
+            content.push_str("\n# Baked-in kernel command line\n");
+            content.push_str("CONFIG_CMDLINE_BOOL=y\n");
+            content.push_str(&format!("CONFIG_CMDLINE=\"{}\"\n", cmdline));
+            eprintln!("[Patcher] [CONFIG-OVERRIDE] Injected CONFIG_CMDLINE=\"{}\"", cmdline);
+        }
+
         // Write .config.override file
         fs::write(&override_path, &content).map_err(|e| {
             PatchError::PatchFailed(format!("Failed to write .config.override: {}", e))
@@ -156,13 +169,16 @@ impl crate::kernel::patcher::KernelPatcher {
     /// via CONFIG_CMDLINE, which overrides any runtime kernel parameters. This is critical
     /// for features like MGLRU that report 0x0000 at runtime despite being enabled via CONFIG.
     ///
-    /// Parameters baked in:
+    /// When the finalizer resolved a command line (`_CMDLINE` in the options, see
+    /// `config::cmdline`), it replaces CONFIG_CMDLINE verbatim. Otherwise the legacy
+    /// parameters are appended to the existing CONFIG_CMDLINE:
     /// - `lru_gen.enabled=7` if `use_mglru` is true (enables all MGLRU subsystems)
     /// - `mitigations=off` if `hardening_level` is Minimal (performance optimization)
     /// - `nowatchdog` (always) - disable watchdog timer for performance
     /// - `preempt=full` (always) - ensure full preemption is enforced at runtime
     ///
     /// # Arguments
+    /// * `resolved` - Command line resolved by the finalizer, if any
     /// * `use_mglru` - Whether MGLRU is enabled
     /// * `hardening_level` - Hardening level for mitigations decision
     ///
@@ -170,6 +186,7 @@ impl crate::kernel::patcher::KernelPatcher {
     /// Result indicating success or error
     fn inject_baked_in_cmdline(
         &self,
+        resolved: Option<&str>,
         use_mglru: bool,
         hardening_level: HardeningLevel,
     ) -> PatchResult<()> {
@@ -204,47 +221,24 @@ impl crate::kernel::patcher::KernelPatcher {
             }
         }
 
-        // STEP 2: Build the new command line parameters to append
-        let mut new_params = Vec::new();
-
-        // Always add performance parameters
-        new_params.push("nowatchdog");
-        new_params.push("preempt=full");
-
-        // Add MGLRU parameter if enabled
-        if use_mglru {
-            new_params.push("lru_gen.enabled=7");
-            eprintln!("[Patcher] [CMDLINE] MGLRU enabled: adding lru_gen.enabled=7");
-        }
-
-        // Add mitigations=off if hardening is Minimal
-        if hardening_level == HardeningLevel::Minimal {
-            new_params.push("mitigations=off");
-            eprintln!("[Patcher] [CMDLINE] Hardening is Minimal: adding mitigations=off");
-        }
-
-        // STEP 3: Construct the final command line
-        let mut final_cmdline = existing_cmdline.clone();
-        if !final_cmdline.is_empty() && !final_cmdline.ends_with(' ') {
-            final_cmdline.push(' ');
-        }
-
-        for param in &new_params {
-            if !final_cmdline.contains(param) {
-                final_cmdline.push_str(param);
-                final_cmdline.push(' ');
+        // STEP 2: Use the resolved command line, or append the legacy parameters
+        let final_cmdline = match resolved {
+            Some(resolved) => {
+                eprintln!(
+                    "[Patcher] [CMDLINE] Using resolved command line (replaces '{}')",
+                    existing_cmdline
+                );
+                resolved.trim().to_string()
             }
-        }
-
-        // Remove trailing space
-        let final_cmdline = final_cmdline.trim_end().to_string();
+            None => Self::legacy_cmdline(&existing_cmdline, use_mglru, hardening_level),
+        };
 
         eprintln!(
             "[Patcher] [CMDLINE] FINAL BAKED-IN CMDLINE: '{}'",
             final_cmdline
         );
 
-        // STEP 4: Remove existing CONFIG_CMDLINE* entries from .config (exact and prefix matches)
+        // STEP 3: Remove existing CONFIG_CMDLINE* entries from .config (exact and prefix matches)
         // Exact matches: CONFIG_CMDLINE_BOOL, CONFIG_CMDLINE_OVERRIDE
         // Prefix matches: CONFIG_CMDLINE=
         let lines: Vec<&str> = content
@@ -264,18 +258,18 @@ impl crate::kernel::patcher::KernelPatcher {
             }
         }
 
-        // STEP 5: Inject CONFIG_CMDLINE with the final command line
+        // STEP 4: Inject CONFIG_CMDLINE with the final command line
         if !content.is_empty() && !content.ends_with('\n') {
             content.push('\n');
         }
         content.push_str(&format!("CONFIG_CMDLINE=\"{}\"", final_cmdline));
         content.push('\n');
 
-        // STEP 6: Inject CONFIG_CMDLINE_BOOL=y to enable command line override
+        // STEP 5: Inject CONFIG_CMDLINE_BOOL=y to enable command line override
         content.push_str("CONFIG_CMDLINE_BOOL=y");
         content.push('\n');
 
-        // STEP 7: Explicitly inject CONFIG_CMDLINE_OVERRIDE=n to prevent override conflicts
+        // STEP 6: Explicitly inject CONFIG_CMDLINE_OVERRIDE=n to prevent override conflicts
         // This ensures the baked-in CMDLINE takes precedence at runtime
         content.push_str("CONFIG_CMDLINE_OVERRIDE=n");
         content.push('\n');
@@ -289,6 +283,46 @@ impl crate::kernel::patcher::KernelPatcher {
         Ok(())
     }
 
+    /// Legacy baked-in command line: `existing` plus the fixed performance parameters.
+    fn legacy_cmdline(
+        existing_cmdline: &str,
+        use_mglru: bool,
+        hardening_level: HardeningLevel,
+    ) -> String {
+        let mut new_params = Vec::new();
+
+        // Always add performance parameters
+        new_params.push("nowatchdog");
+        new_params.push("preempt=full");
+
+        // Add MGLRU parameter if enabled
+        if use_mglru {
+            new_params.push("lru_gen.enabled=7");
+            eprintln!("[Patcher] [CMDLINE] MGLRU enabled: adding lru_gen.enabled=7");
+        }
+
+        // Add mitigations=off if hardening is Minimal
+        if hardening_level == HardeningLevel::Minimal {
+            new_params.push("mitigations=off");
+            eprintln!("[Patcher] [CMDLINE] Hardening is Minimal: adding mitigations=off");
+        }
+
+        let mut final_cmdline = existing_cmdline.to_string();
+        if !final_cmdline.is_empty() && !final_cmdline.ends_with(' ') {
+            final_cmdline.push(' ');
+        }
+
+        for param in &new_params {
+            if !final_cmdline.contains(param) {
+                final_cmdline.push_str(param);
+                final_cmdline.push(' ');
+            }
+        }
+
+        // Remove trailing space
+        final_cmdline.trim_end().to_string()
+    }
+
     /// Applies Kconfig with Clang/LTO/BORE enforcement (Phase 5).
     ///
     /// Now also supports baking in command line parameters via CONFIG_CMDLINE for
@@ -332,6 +366,8 @@ impl crate::kernel::patcher::KernelPatcher {
         } else {
             HardeningLevel::Standard
         };
+        let resolved_cmdline = options.get(CMDLINE_KEY).cloned();
+        let clang_version = options.get(CLANG_VERSION_KEY).cloned();
 
         // Extract MGLRU flags.
         let mut mglru_options = HashMap::new();
@@ -443,11 +479,11 @@ impl crate::kernel::patcher::KernelPatcher {
         // CRITICAL: Respect the target lto_type when setting LTO configs
         // NOTE: CONFIG_LOCALVERSION is set dynamically based on variant + profile
         // to ensure collision-free kernel version strings across all builds
-        let mut clang_configs = vec![
-            ("CONFIG_CC_IS_CLANG", "y"),
-            ("CONFIG_CLANG_VERSION", "190106"), // LLVM 19.0.1 release
-            ("CONFIG_CC_IS_GCC", "n"),
-        ];
+        let mut clang_configs = vec![("CONFIG_CC_IS_CLANG", "y"), ("CONFIG_CC_IS_GCC", "n")];
+        // Real version of the probed Clang (kernel::toolchain), never a guess
+        if let Some(clang_version) = clang_version.as_deref() {
+            clang_configs.push(("CONFIG_CLANG_VERSION", clang_version));
+        }
 
         // Add LTO configs based on lto_type parameter
         match lto_type {
@@ -735,7 +771,11 @@ impl crate::kernel::patcher::KernelPatcher {
         // enable features like MGLRU that require both CONFIG options AND runtime
         // kernel parameters to function correctly.
         eprintln!("[Patcher] [CMDLINE] STARTING BAKED-IN CMDLINE INJECTION");
-        self.inject_baked_in_cmdline(use_mglru, hardening_level)?;
+        self.inject_baked_in_cmdline(
+            resolved_cmdline.as_deref(),
+            use_mglru,
+            hardening_level,
+        )?;
         eprintln!("[Patcher] [CMDLINE] SUCCESS: Baked-in CMDLINE injection complete");
 
         Ok(())
@@ -826,6 +866,98 @@ impl crate::kernel::patcher::KernelPatcher {
         Ok(())
     }
 
+    /// Enforce module signing with the user's MOK for Secure Boot builds
+    ///
+    /// Sets the signing options in `.config`, `.config.override` and the parent
+    /// `config` (whichever exist) so that whichever file the PKGBUILD ends up
+    /// using, every module is signed with the MOK and unsigned modules are refused:
+    /// - CONFIG_MODULE_SIG=y / CONFIG_MODULE_SIG_ALL=y
+    /// - CONFIG_MODULE_SIG_FORCE=y
+    /// - CONFIG_MODULE_SIG_SHA512=y / CONFIG_MODULE_SIG_HASH="sha512"
+    /// - CONFIG_MODULE_SIG_KEY="<key_pem>"
+    ///
+    /// # Arguments
+    /// * `key_pem` - Combined private key + certificate PEM (see `kernel::signing`)
+    ///
+    /// # Returns
+    /// Result indicating success or error
+    pub fn apply_module_signing(&self, key_pem: &std::path::Path) -> PatchResult<()> {
+        let key_value = key_pem.to_str().ok_or_else(|| {
+            PatchError::ValidationFailed(format!(
+                "Module signing key path is not valid UTF-8: {}",
+                key_pem.display()
+            ))
+        })?;
+        if key_value.contains('"') {
+            return Err(PatchError::ValidationFailed(format!(
+                "Module signing key path contains a quote: {}",
+                key_value
+            )));
+        }
+
+        let signing_key = format!("\"{}\"", key_value);
+        let signing_configs = [
+            ("CONFIG_MODULE_SIG", "y"),
+            ("CONFIG_MODULE_SIG_ALL", "y"),
+            ("CONFIG_MODULE_SIG_FORCE", "y"),
+            ("CONFIG_MODULE_SIG_SHA512", "y"),
+            ("CONFIG_MODULE_SIG_HASH", "\"sha512\""),
+            ("CONFIG_MODULE_SIG_KEY", signing_key.as_str()),
+        ];
+
+        let mut targets = vec![
+            self.src_dir().join(".config"),
+            self.src_dir().join(".config.override"),
+        ];
+        if let Some(parent) = self.src_dir().parent() {
+            targets.push(parent.join("config"));
+        }
+
+        let mut patched = 0;
+        for (index, path) in targets.iter().enumerate() {
+            // .config is always written; the other files only when they already exist
+            if index > 0 && !path.exists() {
+                continue;
+            }
+
+            let content = if path.exists() {
+                fs::read_to_string(path).map_err(|e| {
+                    PatchError::PatchFailed(format!("Failed to read {}: {}", path.display(), e))
+                })?
+            } else {
+                String::new()
+            };
+
+            let mut lines: Vec<String> = content
+                .lines()
+                .filter(|line| {
+                    !signing_configs.iter().any(|(key, _)| {
+                        line.starts_with(&format!("{}=", key))
+                            || *line == format!("# {} is not set", key)
+                    })
+                })
+                .map(|line| line.to_string())
+                .collect();
+
+            lines.push("# GOATd Secure Boot: module signing with MOK".to_string());
+            for (key, value) in &signing_configs {
+                lines.push(format!("{}={}", key, value));
+            }
+
+            fs::write(path, lines.join("\n") + "\n").map_err(|e| {
+                PatchError::PatchFailed(format!("Failed to write {}: {}", path.display(), e))
+            })?;
+            patched += 1;
+        }
+
+        eprintln!(
+            "[Patcher] [MODULE-SIGNING] SUCCESS: Module signing enforced in {} config file(s) with key {}",
+            patched, key_value
+        );
+
+        Ok(())
+    }
+
     /// Apply Intel safety cluster configuration
     ///
     /// Enforces GPU virtualization, IOMMU, and Arc GPU support for Intel platforms.
@@ -94,13 +94,14 @@ pub mod templates;
 
 use crate::error::PatchError;
 use crate::hardware::gpu;
+use crate::kernel::compiler_cache::CompilerCacheSession;
 use crate::kernel::lto;
-use crate::models::{GpuVendor, HardwareContext};
+use crate::models::{CompilerCacheConfig, GpuVendor, HardwareContext};
 use once_cell::sync::Lazy;
 use regex::Regex;
 use std::collections::HashMap;
 use std::fs;
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
 use std::process::Command;
 
 /// Phase-aware patch hook system
@@ -535,16 +536,22 @@ impl KernelPatcher {
     /// 3. HOST COMPILER enforcement (HOSTCC=clang, HOSTCXX=clang++)
     /// 4. PATH purification to prevent GCC/legacy compiler interference
     /// 5. Dynamic toolchain discovery (strip, llvm-strip, etc.)
+    /// 6. Optional ccache/sccache compiler wrappers at the front of PATH
     ///
     /// This method encapsulates all environment setup that previously lived in the Executor,
     /// ensuring the Executor only receives and applies pre-configured environment variables.
     ///
     /// # Arguments
     /// * `native_optimizations` - Whether to enable -march=native in KCFLAGS
+    /// * `compiler_cache` - ccache/sccache settings (no-op when disabled)
     ///
     /// # Returns
     /// HashMap of environment variable names to values
-    pub fn prepare_build_environment(&self, native_optimizations: bool) -> HashMap<String, String> {
+    pub fn prepare_build_environment(
+        &self,
+        native_optimizations: bool,
+        compiler_cache: &CompilerCacheConfig,
+    ) -> HashMap<String, String> {
         let mut env_vars = HashMap::new();
 
         // CRITICAL: Sanitize environment FIRST to remove leaked paths and GCC contamination
@@ -640,6 +647,34 @@ impl KernelPatcher {
         env_vars.insert("PATH".to_string(), new_path.clone());
         eprintln!("[Patcher] [ENV] Purified PATH: {} and /usr/bin:/bin (removed gcc/llvm/clang installations)", llvm_bin_path);
 
+        // ============================================================================
+        // COMPILER CACHE: ccache/sccache wrappers ahead of the purified PATH
+        // ============================================================================
+        if let Some(session) = CompilerCacheSession::from_config(compiler_cache, &self.src_dir) {
+            match session.install_wrappers(&new_path) {
+                Ok(count) if count > 0 => {
+                    env_vars.insert(
+                        "PATH".to_string(),
+                        format!("{}:{}", session.wrapper_dir.display(), new_path),
+                    );
+                    env_vars.extend(session.env_vars());
+                    eprintln!(
+                        "[Patcher] [ENV] {} enabled: {} compiler wrappers in {}, cache at {}",
+                        session.binary(),
+                        count,
+                        session.wrapper_dir.display(),
+                        session.cache_dir.display()
+                    );
+                }
+                Ok(_) => {
+                    eprintln!("[Patcher] [ENV] WARNING: {} enabled but no compilers found to wrap - building uncached", session.binary());
+                }
+                Err(e) => {
+                    eprintln!("[Patcher] [ENV] WARNING: {} setup failed: {} - building uncached", session.binary(), e);
+                }
+            }
+        }
+
         // Verify make is available
         if let Ok(make_cmd) = std::process::Command::new("make").arg("--version").output() {
             if make_cmd.status.success() {
@@ -1994,6 +2029,19 @@ export READELF=llvm-readelf
             }
         }
 
+        // PHASE 1.G: Secure Boot kernel image signing (only when a MOK is configured)
+        if let (Some(key), Some(cert)) = (
+            build_env_vars.get("GOATD_SB_KEY"),
+            build_env_vars.get("GOATD_SB_CERT"),
+        ) {
+            eprintln!("[Patcher] [ORCHESTRATION] PHASE 1.G: Injecting Secure Boot kernel image signing");
+            pkgbuild::inject_kernel_image_signing(&self.src_dir, Path::new(key), Path::new(cert))?;
+        }
+
+        // PHASE 1.H: Consume the merged PGO/AutoFDO profile (optimize builds only)
+        eprintln!("[Patcher] [ORCHESTRATION] PHASE 1.H: Checking for a PGO profile");
+        pkgbuild::inject_pgo_profile(&self.src_dir, &config_options)?;
+
         eprintln!("[Patcher] [ORCHESTRATION] PHASE 1: PKGBUILD surgical injections complete");
 
         // ====================================================================
@@ -2030,6 +2078,12 @@ export READELF=llvm-readelf
             self.inject_modular_localversion(&variant, profile)?;
         }
 
+        // PHASE 2.D: Enforce module signing with the MOK (Secure Boot builds)
+        if let Some(key_pem) = build_env_vars.get("GOATD_MODULE_SIG_KEY") {
+            eprintln!("[Patcher] [ORCHESTRATION] PHASE 2.D: Enforcing module signing (CONFIG_MODULE_SIG_FORCE)");
+            self.apply_module_signing(Path::new(key_pem))?;
+        }
+
         eprintln!("[Patcher] [ORCHESTRATION] PHASE 2: Kernel configuration complete");
 
         // ====================================================================
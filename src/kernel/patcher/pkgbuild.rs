@@ -46,6 +46,11 @@ static PKGBASE_REGEX: Lazy<Regex> = Lazy::new(|| {
         .expect("Invalid pkgbase regex")
 });
 
+/// Block injected by `inject_pgo_profile`, removed before re-injection
+static PGO_BLOCK_REGEX: Lazy<Regex> = Lazy::new(|| {
+    Regex::new(r"(?s)\n# GOATD_PGO_START\n.*?# GOATD_PGO_END\n").expect("Invalid PGO block regex")
+});
+
 /// Helper to read PKGBUILD from source directory
 fn read_pkgbuild(src_dir: &Path) -> PatchResult<(std::path::PathBuf, String)> {
     let path = src_dir.join("PKGBUILD");
@@ -157,6 +162,29 @@ fn extract_pkgver(content: &str) -> Option<String> {
     None
 }
 
+/// Expand `$name` / `${name}` references to top-level PKGBUILD assignments
+/// Unknown variables are left as-is; expansion is single-pass (no recursion)
+fn expand_pkgbuild_variables(value: &str, content: &str) -> String {
+    if !value.contains('$') {
+        return value.to_string();
+    }
+    static VAR_REF_REGEX: Lazy<Regex> = Lazy::new(|| {
+        Regex::new(r"\$(?:\{(\w+)\}|(\w+))").expect("Invalid variable reference regex")
+    });
+    VAR_REF_REGEX
+        .replace_all(value, |caps: &regex::Captures| {
+            let name = caps.get(1).or_else(|| caps.get(2)).map_or("", |m| m.as_str());
+            let assignment = format!(r#"(?m)^{}=['"]?([^'"\s]*)['"]?\s*$"#, regex::escape(name));
+            Regex::new(&assignment)
+                .ok()
+                .and_then(|re| re.captures(content))
+                .and_then(|c| c.get(1))
+                .map(|m| m.as_str().to_string())
+                .unwrap_or_else(|| caps[0].to_string())
+        })
+        .to_string()
+}
+
 /// Detect the kernel variant from PKGBUILD's pkgbase variable
 /// Returns the cleaned variant like "linux", "linux-zen", "linux-hardened", etc.
 ///
@@ -181,11 +209,14 @@ fn detect_kernel_variant(content: &str) -> PatchResult<String> {
     // Group 1: Base variant with GOATd suffix stripped (linux-zen from linux-zen-goatd-gaming)
     // Group 2: Direct variant without GOATd suffix (fallback if group 1 doesn't match)
     if let Some(caps) = PKGBASE_REGEX.captures(content) {
-        let variant = caps
+        let raw_variant = caps
             .get(1)
             .or_else(|| caps.get(2))
             .map(|m| m.as_str().trim())
             .unwrap_or("");
+        // Third-party PKGBUILDs build pkgbase from variables (linux-cachyos: pkgbase="linux-$_pkgsuffix")
+        let expanded = expand_pkgbuild_variables(raw_variant, content);
+        let variant = expanded.as_str();
         if variant.starts_with("linux") {
             // IDEMPOTENCY CHECK: If variant contains "-goatd-" already, treat it as immutable
             // Extract the base name before the first "-goatd-" occurrence
@@ -323,6 +354,39 @@ export KCFLAGS="${{KCFLAGS}} {} {} {}"
     Ok(())
 }
 
+/// Inject the merged PGO/AutoFDO profile of an optimize build into PKGBUILD
+/// Exports CLANG_AUTOFDO_PROFILE (AutoFDO) or -fprofile-use in KCFLAGS (instrumented)
+pub fn inject_pgo_profile(src_dir: &Path, options: &HashMap<String, String>) -> PatchResult<()> {
+    let exports = crate::kernel::pgo::build_exports(options);
+    if exports.is_none() && !src_dir.join("PKGBUILD").exists() {
+        return Ok(());
+    }
+
+    let (path, content) = read_pkgbuild(src_dir)?;
+
+    // Idempotent: drop the block of an earlier optimize build before (re)injecting
+    let mut patched = PGO_BLOCK_REGEX.replace_all(&content, "").to_string();
+
+    if let Some(exports) = exports {
+        let pgo_block = format!(
+            "\n# GOATD_PGO_START\n# PROFILE-GUIDED OPTIMIZATION (merged profile)\n{}\n# GOATD_PGO_END\n",
+            exports
+        );
+        let pos = find_function_body_start(&patched, "build").ok_or_else(|| {
+            PatchError::PatchFailed(
+                "PKGBUILD has no build() function to consume the PGO profile".to_string(),
+            )
+        })?;
+        patched.insert_str(pos, &pgo_block);
+        eprintln!("[Patcher] [PGO] Injected profile into build(): {}", exports);
+    }
+
+    if patched != content {
+        fs::write(&path, &patched).map_err(|e| PatchError::PatchFailed(e.to_string()))?;
+    }
+    Ok(())
+}
+
 /// Inject Clang/LLVM toolchain exports into PKGBUILD
 /// Uses regex to surgically replace GCC variables and inject export blocks
 pub fn inject_clang_into_pkgbuild(src_dir: &Path) -> PatchResult<()> {
@@ -1535,6 +1599,104 @@ fn inject_prebuild_lto_hard_enforcer(
     Ok(())
 }
 
+/// Inject the user patch queue into prepare(), ahead of "Setting config..."
+///
+/// Falls back to the start of prepare() when the PKGBUILD has no such step; the
+/// snippet changes into the kernel tree itself, so its position only matters
+/// relative to configuration.
+pub fn inject_patch_queue(src_dir: &Path, staged_dir: &Path) -> PatchResult<()> {
+    let staged = match staged_dir.to_str() {
+        Some(dir) if !dir.contains('\'') => dir,
+        _ => {
+            return Err(PatchError::ValidationFailed(format!(
+                "Patch queue path must be valid UTF-8 without quotes: {}",
+                staged_dir.display()
+            )))
+        }
+    };
+
+    let (path, mut content) = read_pkgbuild(src_dir)?;
+
+    if content.contains("PHASE PQ: GOATD USER PATCH QUEUE") {
+        return Ok(());
+    }
+
+    let prepare_start = find_function_body_start(&content, "prepare").ok_or_else(|| {
+        PatchError::PatchFailed("prepare() function not found in PKGBUILD".to_string())
+    })?;
+    let prepare_end = find_function_body_end(&content, prepare_start).unwrap_or(content.len());
+
+    let injection_point = content[prepare_start..prepare_end]
+        .find("echo \"Setting config...\"")
+        .and_then(|pos| content[..prepare_start + pos].rfind('\n'))
+        .map(|newline| newline + 1)
+        .unwrap_or(prepare_start);
+
+    let snippet = templates::get_patch_queue_injection(staged);
+    content.insert_str(injection_point, &format!("{}\n", snippet));
+    fs::write(path, content).map_err(|e| PatchError::PatchFailed(e.to_string()))?;
+    eprintln!("[Patcher] [PKGBUILD] [PATCH-QUEUE] Injected user patch queue into prepare()");
+
+    Ok(())
+}
+
+/// Inject Secure Boot kernel image signing at the end of build()
+///
+/// The image is signed in place after compilation, so package() picks up the
+/// signed vmlinuz without further PKGBUILD changes. An existing PHASE SB block
+/// is replaced, so a changed MOK key or certificate path takes effect.
+pub fn inject_kernel_image_signing(src_dir: &Path, key: &Path, cert: &Path) -> PatchResult<()> {
+    let (key, cert) = match (key.to_str(), cert.to_str()) {
+        (Some(key), Some(cert)) if !key.contains('\'') && !cert.contains('\'') => (key, cert),
+        _ => {
+            return Err(PatchError::ValidationFailed(format!(
+                "MOK key/cert paths must be valid UTF-8 without quotes: {} / {}",
+                key.display(),
+                cert.display()
+            )))
+        }
+    };
+
+    let (path, mut content) = read_pkgbuild(src_dir)?;
+    let snippet = templates::get_kernel_image_signing(key, cert);
+
+    if let Some(marker) = content.find("PHASE SB: SECURE BOOT KERNEL IMAGE SIGNING") {
+        // The block starts with the blank line above its banner and ends with
+        // the success message
+        const BLOCK_END: &str = "echo \"[SECURE-BOOT] ✓ Kernel image signed\" >&2\n";
+        let block_start = content[..marker]
+            .rfind('\n')
+            .and_then(|banner_end| content[..banner_end].rfind('\n'))
+            .unwrap_or(marker);
+        let block_end = content[marker..]
+            .find(BLOCK_END)
+            .map(|pos| marker + pos + BLOCK_END.len())
+            .ok_or_else(|| {
+                PatchError::PatchFailed("Unterminated PHASE SB block in PKGBUILD".to_string())
+            })?;
+
+        if content[block_start..block_end] != snippet {
+            content.replace_range(block_start..block_end, &snippet);
+            fs::write(path, content).map_err(|e| PatchError::PatchFailed(e.to_string()))?;
+            eprintln!("[Patcher] [PKGBUILD] [SECURE-BOOT] Updated kernel image signing in build()");
+        }
+        return Ok(());
+    }
+
+    let body_start = find_function_body_start(&content, "build").ok_or_else(|| {
+        PatchError::PatchFailed("build() function not found in PKGBUILD".to_string())
+    })?;
+    let body_end = find_function_body_end(&content, body_start).ok_or_else(|| {
+        PatchError::PatchFailed("End of build() function not found in PKGBUILD".to_string())
+    })?;
+
+    content.insert_str(body_end, &snippet);
+    fs::write(path, content).map_err(|e| PatchError::PatchFailed(e.to_string()))?;
+    eprintln!("[Patcher] [PKGBUILD] [SECURE-BOOT] Injected kernel image signing at end of build()");
+
+    Ok(())
+}
+
 // ============================================================================
 // Extension Methods on KernelPatcher (as required by the facade)
 // ============================================================================
@@ -1545,6 +1707,11 @@ impl super::KernelPatcher {
         inject_polly_flags(self.src_dir(), options)
     }
 
+    /// Inject the merged PGO/AutoFDO profile of an optimize build into PKGBUILD
+    pub fn inject_pgo_profile(&self, options: &HashMap<String, String>) -> PatchResult<()> {
+        inject_pgo_profile(self.src_dir(), options)
+    }
+
     /// Inject Clang/LLVM toolchain exports into PKGBUILD
     pub fn inject_clang_into_pkgbuild(&self) -> PatchResult<()> {
         inject_clang_into_pkgbuild(self.src_dir())
@@ -1739,7 +1906,7 @@ impl super::KernelPatcher {
 
         // Get the expected source URL from KernelSourceDB
         use crate::kernel::sources::KernelSourceDB;
-        let source_db = KernelSourceDB::new();
+        let source_db = KernelSourceDB::load();
         let expected_source_url = source_db.get_source_url(kernel_variant).ok_or_else(|| {
             PatchError::PatchFailed(format!("Unknown kernel variant: {}", kernel_variant))
         })?;
@@ -1752,8 +1919,10 @@ impl super::KernelPatcher {
         // STEP 1.5: Detect AUR/External variants based on source URL
         // AUR variants: contain aur.archlinux.org
         // External variants: contain github.com (or other git hosting)
+        // Custom variants: user-registered sources always manage their own sources
         let is_aur_or_external = expected_source_url.contains("aur.archlinux.org")
-            || expected_source_url.contains("github.com");
+            || expected_source_url.contains("github.com")
+            || source_db.is_custom(kernel_variant);
 
         if is_aur_or_external {
             eprintln!(
@@ -2024,6 +2193,16 @@ impl super::KernelPatcher {
         inject_prebuild_lto_hard_enforcer(self.src_dir(), lto_type)
     }
 
+    /// Inject the staged user patch queue into prepare()
+    pub fn inject_patch_queue(&self, staged_dir: &Path) -> PatchResult<()> {
+        inject_patch_queue(self.src_dir(), staged_dir)
+    }
+
+    /// Inject Secure Boot kernel image signing (sbsign with the MOK) into build()
+    pub fn inject_kernel_image_signing(&self, key: &Path, cert: &Path) -> PatchResult<()> {
+        inject_kernel_image_signing(self.src_dir(), key, cert)
+    }
+
     /// Inject global enforcement scope (ENFORCER_SAFE_LIST export and restore helper)
     /// MUST be called before inject_post_modprobed_hard_enforcer
     pub fn inject_global_enforcement_scope(&self) -> PatchResult<()> {
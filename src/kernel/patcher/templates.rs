@@ -1268,6 +1268,68 @@ pub fn get_polly_block(cflags: &str, cxxflags: &str, ldflags: &str) -> String {
     )
 }
 
+/// Generate user patch queue application snippet for prepare()
+///
+/// Applies the staged series to the extracted kernel tree before configuration.
+/// Each patch is dry-run with `git apply --check` first; a conflict aborts the
+/// build with a `[PATCH-QUEUE] CONFLICT` line naming the patch.
+pub fn get_patch_queue_injection(staged_dir: &str) -> String {
+    format!(
+        r#"
+    # =====================================================================
+    # PHASE PQ: GOATD USER PATCH QUEUE
+    # =====================================================================
+    # Patches are applied in 'series' order inside the kernel tree. Repository
+    # discovery stops at $srcdir so git apply patches the tree, not the
+    # surrounding PKGBUILD checkout.
+    (
+        cd "${{srcdir}}/${{_srcname}}" || exit 1
+        export GIT_CEILING_DIRECTORIES="${{srcdir}}"
+        while IFS= read -r _goatd_patch; do
+            [ -n "$_goatd_patch" ] || continue
+            if ! git apply --check '{dir}'/"$_goatd_patch"; then
+                echo "[PATCH-QUEUE] CONFLICT: $_goatd_patch does not apply" >&2
+                exit 1
+            fi
+            git apply '{dir}'/"$_goatd_patch" || exit 1
+            echo "[PATCH-QUEUE] ✓ Applied $_goatd_patch" >&2
+        done < '{dir}/series'
+    ) || return 1
+    # END PATCH QUEUE
+"#,
+        dir = staged_dir
+    )
+}
+
+/// Generate Secure Boot kernel image signing snippet
+///
+/// Appended to the end of build(): signs the freshly built image in place with
+/// the user's MOK so that package() installs an already-signed vmlinuz.
+pub fn get_kernel_image_signing(key: &str, cert: &str) -> String {
+    format!(
+        r#"
+    # =====================================================================
+    # PHASE SB: SECURE BOOT KERNEL IMAGE SIGNING (MOK)
+    # =====================================================================
+    # Signs the kernel image with the Machine Owner Key so the package
+    # installs a vmlinuz that boots with Secure Boot enforced.
+    if ! command -v sbsign >/dev/null 2>&1; then
+        echo "[SECURE-BOOT] ERROR: sbsign not found (install sbsigntools)" >&2
+        return 1
+    fi
+    _goatd_image="$(make -s image_name)"
+    echo "[SECURE-BOOT] Signing $_goatd_image with MOK certificate '{cert}'" >&2
+    sbsign --key '{key}' --cert '{cert}' --output "$_goatd_image" "$_goatd_image" || {{
+        echo "[SECURE-BOOT] ERROR: Failed to sign $_goatd_image" >&2
+        return 1
+    }}
+    echo "[SECURE-BOOT] ✓ Kernel image signed" >&2
+"#,
+        key = key,
+        cert = cert
+    )
+}
+
 /// Bash function snippet for resolving GOATD workspace root via .goatd_anchor
 ///
 /// Generates a Bash function that walks up the directory tree to locate .goatd_anchor
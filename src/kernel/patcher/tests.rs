@@ -1492,3 +1492,330 @@ prepare() {
     
     eprintln!("[TEST] global_enforcement_idempotency: ✓ PASSED - Injection is idempotent");
 }
+
+/// Test: Secure Boot module signing options land in .config and .config.override
+#[test]
+fn test_apply_module_signing_enforces_sig_force() {
+    use crate::kernel::patcher::KernelPatcher;
+    use std::fs;
+
+    let temp_dir = tempfile::tempdir().expect("Failed to create temp directory");
+    let src_dir = temp_dir.path().join("linux");
+    fs::create_dir_all(&src_dir).expect("Failed to create src dir");
+    fs::write(
+        src_dir.join(".config"),
+        "CONFIG_MODULES=y\n# CONFIG_MODULE_SIG_FORCE is not set\nCONFIG_MODULE_SIG_KEY=\"certs/signing_key.pem\"\n",
+    )
+    .expect("Failed to write .config");
+    fs::write(src_dir.join(".config.override"), "CONFIG_LTO_CLANG=y\n")
+        .expect("Failed to write .config.override");
+
+    let key_pem = temp_dir.path().join("goatd_module_signing.pem");
+    let patcher = KernelPatcher::new(src_dir.clone());
+    patcher
+        .apply_module_signing(&key_pem)
+        .expect("Module signing failed");
+    // Running twice must not duplicate entries
+    patcher
+        .apply_module_signing(&key_pem)
+        .expect("Second module signing failed");
+
+    let config = fs::read_to_string(src_dir.join(".config")).unwrap();
+    assert!(config.contains("CONFIG_MODULES=y"));
+    assert!(!config.contains("# CONFIG_MODULE_SIG_FORCE is not set"));
+    assert!(!config.contains("certs/signing_key.pem"));
+    assert_eq!(config.matches("CONFIG_MODULE_SIG_FORCE=y").count(), 1);
+    assert!(config.contains(&format!("CONFIG_MODULE_SIG_KEY=\"{}\"", key_pem.display())));
+
+    let override_content = fs::read_to_string(src_dir.join(".config.override")).unwrap();
+    assert!(override_content.contains("CONFIG_LTO_CLANG=y"));
+    assert!(override_content.contains("CONFIG_MODULE_SIG_ALL=y"));
+}
+
+/// Test: Secure Boot image signing is appended to build() exactly once, and
+/// re-injecting with another MOK replaces the block
+#[test]
+fn test_inject_kernel_image_signing_idempotent() {
+    use crate::kernel::patcher::KernelPatcher;
+    use std::fs;
+    use std::path::Path;
+
+    let temp_dir = tempfile::tempdir().expect("Failed to create temp directory");
+    let src_dir = temp_dir.path();
+    let pkgbuild_path = src_dir.join("PKGBUILD");
+    fs::write(
+        &pkgbuild_path,
+        r#"pkgbase=linux
+build() {
+  cd $_srcname
+  make all
+}
+
+package_linux() {
+  install -Dm644 "$(make -s image_name)" "$modulesdir/vmlinuz"
+}
+"#,
+    )
+    .expect("Failed to write PKGBUILD");
+
+    let patcher = KernelPatcher::new(src_dir.to_path_buf());
+    let key = Path::new("/etc/mok/MOK.key");
+    let cert = Path::new("/etc/mok/MOK.crt");
+    patcher
+        .inject_kernel_image_signing(key, cert)
+        .expect("First injection failed");
+    patcher
+        .inject_kernel_image_signing(key, cert)
+        .expect("Second injection failed");
+
+    let content = fs::read_to_string(&pkgbuild_path).unwrap();
+    assert_eq!(content.matches("sbsign --key").count(), 1);
+
+    // The signing step runs after make and before build() closes
+    let make_pos = content.find("make all").unwrap();
+    let sign_pos = content.find("sbsign --key '/etc/mok/MOK.key'").unwrap();
+    let package_pos = content.find("package_linux()").unwrap();
+    assert!(make_pos < sign_pos && sign_pos < package_pos);
+
+    // A new key pair replaces the stale paths instead of being skipped
+    patcher
+        .inject_kernel_image_signing(
+            Path::new("/etc/mok/new/MOK.key"),
+            Path::new("/etc/mok/new/MOK.crt"),
+        )
+        .expect("Re-injection with a new MOK failed");
+    let updated = fs::read_to_string(&pkgbuild_path).unwrap();
+    assert_eq!(updated.matches("PHASE SB:").count(), 1);
+    assert!(updated.contains("sbsign --key '/etc/mok/new/MOK.key' --cert '/etc/mok/new/MOK.crt'"));
+    assert!(!updated.contains("'/etc/mok/MOK.key'"));
+    assert!(!updated.contains("'/etc/mok/MOK.crt'"));
+    assert!(updated.find("make all").unwrap() < updated.find("PHASE SB:").unwrap());
+    assert!(updated.find("PHASE SB:").unwrap() < updated.find("package_linux()").unwrap());
+    // Switching back yields exactly the original injection
+    patcher
+        .inject_kernel_image_signing(key, cert)
+        .expect("Re-injection with the original MOK failed");
+    assert_eq!(fs::read_to_string(&pkgbuild_path).unwrap(), content);
+
+    let syntax = Command::new("bash").arg("-n").arg(&pkgbuild_path).status();
+    if let Ok(status) = syntax {
+        assert!(status.success(), "Injected PKGBUILD must be valid bash");
+    }
+
+    // Quotes in key paths would break the single-quoted snippet
+    assert!(patcher
+        .inject_kernel_image_signing(Path::new("/tmp/it's.key"), cert)
+        .is_err());
+}
+
+/// Test: User patch queue runs inside prepare() before "Setting config..."
+#[test]
+fn test_inject_patch_queue_before_setting_config() {
+    use crate::kernel::patcher::KernelPatcher;
+    use std::fs;
+    use std::path::Path;
+
+    let temp_dir = tempfile::tempdir().expect("Failed to create temp directory");
+    let src_dir = temp_dir.path();
+    let pkgbuild_path = src_dir.join("PKGBUILD");
+    fs::write(
+        &pkgbuild_path,
+        r#"pkgbase=linux
+_srcname=linux-6.12.1
+prepare() {
+  cd $_srcname
+
+  echo "Setting version..."
+  echo "-$pkgrel" > localversion.10-pkgrel
+
+  echo "Setting config..."
+  cp ../config .config
+  make olddefconfig
+}
+
+build() {
+  cd $_srcname
+  make all
+}
+"#,
+    )
+    .expect("Failed to write PKGBUILD");
+
+    let patcher = KernelPatcher::new(src_dir.to_path_buf());
+    let staged = Path::new("/workspace/linux/goatd-patches");
+    patcher
+        .inject_patch_queue(staged)
+        .expect("First injection failed");
+    patcher
+        .inject_patch_queue(staged)
+        .expect("Second injection failed");
+
+    let content = fs::read_to_string(&pkgbuild_path).unwrap();
+    assert_eq!(content.matches("PHASE PQ: GOATD USER PATCH QUEUE").count(), 1);
+
+    let version_pos = content.find("echo \"Setting version...\"").unwrap();
+    let queue_pos = content.find("git apply --check").unwrap();
+    let config_pos = content.find("echo \"Setting config...\"").unwrap();
+    assert!(version_pos < queue_pos && queue_pos < config_pos);
+    assert!(content.contains("done < '/workspace/linux/goatd-patches/series'"));
+
+    let syntax = Command::new("bash").arg("-n").arg(&pkgbuild_path).status();
+    if let Ok(status) = syntax {
+        assert!(status.success(), "Injected PKGBUILD must be valid bash");
+    }
+}
+
+/// Test: A resolved `_CMDLINE` replaces CONFIG_CMDLINE verbatim in .config and the override
+#[test]
+fn test_apply_kconfig_uses_resolved_cmdline() {
+    use crate::config::cmdline::CMDLINE_KEY;
+    use crate::kernel::patcher::KernelPatcher;
+    use crate::models::{HardwareContext, LtoType};
+    use std::collections::HashMap;
+    use std::fs;
+
+    let temp_dir = tempfile::tempdir().expect("Failed to create temp directory");
+    let src_dir = temp_dir.path().join("linux");
+    fs::create_dir_all(&src_dir).expect("Failed to create src dir");
+    fs::write(
+        src_dir.join(".config"),
+        "CONFIG_MODULES=y\nCONFIG_CMDLINE=\"quiet nowatchdog\"\n",
+    )
+    .expect("Failed to write .config");
+
+    let mut options = HashMap::new();
+    options.insert(
+        CMDLINE_KEY.to_string(),
+        "preempt=none split_lock_detect=off".to_string(),
+    );
+    options.insert("_MGLRU_CONFIG_LRU_GEN".to_string(), "CONFIG_LRU_GEN=y".to_string());
+
+    let patcher = KernelPatcher::new(src_dir.clone());
+    patcher
+        .apply_kconfig(options.clone(), LtoType::None, HardwareContext::default())
+        .expect("apply_kconfig failed");
+    patcher
+        .generate_config_override(options, LtoType::None)
+        .expect("generate_config_override failed");
+
+    let config = fs::read_to_string(src_dir.join(".config")).unwrap();
+    assert_eq!(config.matches("CONFIG_CMDLINE=").count(), 1);
+    assert!(config.contains("CONFIG_CMDLINE=\"preempt=none split_lock_detect=off\"\n"));
+    assert!(config.contains("CONFIG_CMDLINE_BOOL=y"));
+    // Legacy parameters are not appended once a command line was resolved
+    assert!(!config.contains("lru_gen.enabled=7"));
+
+    let override_content = fs::read_to_string(src_dir.join(".config.override")).unwrap();
+    assert!(override_content.contains("CONFIG_CMDLINE=\"preempt=none split_lock_detect=off\""));
+}
+
+/// Test: CONFIG_CLANG_VERSION is the probed value (`_CLANG_VERSION`), never hardcoded
+#[test]
+fn test_clang_version_comes_from_toolchain_probe() {
+    use crate::kernel::patcher::KernelPatcher;
+    use crate::kernel::toolchain::CLANG_VERSION_KEY;
+    use crate::models::{HardwareContext, LtoType};
+    use std::collections::HashMap;
+
+    let temp_dir = tempfile::tempdir().expect("Failed to create temp directory");
+    let src_dir = temp_dir.path().join("linux");
+    fs::create_dir_all(&src_dir).expect("Failed to create src dir");
+    fs::write(src_dir.join(".config"), "CONFIG_MODULES=y\nCONFIG_CLANG_VERSION=0\n")
+        .expect("Failed to write .config");
+
+    let mut options = HashMap::new();
+    options.insert(CLANG_VERSION_KEY.to_string(), "180108".to_string());
+
+    let patcher = KernelPatcher::new(src_dir.clone());
+    patcher
+        .apply_kconfig(options.clone(), LtoType::None, HardwareContext::default())
+        .expect("apply_kconfig failed");
+    patcher
+        .generate_config_override(options, LtoType::None)
+        .expect("generate_config_override failed");
+
+    let config = fs::read_to_string(src_dir.join(".config")).unwrap();
+    assert_eq!(config.matches("CONFIG_CLANG_VERSION=").count(), 1);
+    assert!(config.contains("CONFIG_CLANG_VERSION=180108\n"));
+    assert!(!config.lines().any(|line| line.starts_with(CLANG_VERSION_KEY)));
+    let override_content = fs::read_to_string(src_dir.join(".config.override")).unwrap();
+    assert!(override_content.contains("CONFIG_CLANG_VERSION=180108\n"));
+
+    // Without a probed version nothing is guessed
+    patcher
+        .generate_config_override(HashMap::new(), LtoType::None)
+        .expect("generate_config_override failed");
+    let override_content = fs::read_to_string(src_dir.join(".config.override")).unwrap();
+    assert!(!override_content.contains("CONFIG_CLANG_VERSION"));
+}
+
+/// Test: the merged AutoFDO profile is exported in build() exactly once and removed again
+#[test]
+fn test_inject_pgo_profile_idempotent() {
+    use crate::kernel::pgo::{PGO_METHOD_KEY, PGO_PROFILE_KEY};
+    use std::collections::HashMap;
+
+    let temp_dir = tempfile::tempdir().expect("Failed to create temp directory");
+    let src_dir = temp_dir.path();
+    fs::write(
+        src_dir.join("PKGBUILD"),
+        "pkgname=linux-goatd\nbuild() {\n  cd linux\n  make all\n}\n",
+    )
+    .expect("Failed to write PKGBUILD");
+
+    let mut options = HashMap::new();
+    options.insert(PGO_METHOD_KEY.to_string(), "autofdo".to_string());
+    options.insert(
+        PGO_PROFILE_KEY.to_string(),
+        "/tmp/pgo/6.19.0/kernel.afdo".to_string(),
+    );
+
+    crate::kernel::patcher::pkgbuild::inject_pgo_profile(src_dir, &options)
+        .expect("first injection failed");
+    crate::kernel::patcher::pkgbuild::inject_pgo_profile(src_dir, &options)
+        .expect("second injection failed");
+
+    let content = fs::read_to_string(src_dir.join("PKGBUILD")).unwrap();
+    assert_eq!(content.matches("# GOATD_PGO_START").count(), 1);
+    assert!(content.contains("export CLANG_AUTOFDO_PROFILE=\"/tmp/pgo/6.19.0/kernel.afdo\""));
+    let build_pos = content.find("build() {").unwrap();
+    assert!(content.find("# GOATD_PGO_START").unwrap() > build_pos);
+    assert!(content.find("# GOATD_PGO_END").unwrap() < content.find("make all").unwrap());
+
+    // A non-optimize build drops the stale profile
+    crate::kernel::patcher::pkgbuild::inject_pgo_profile(src_dir, &HashMap::new())
+        .expect("removal failed");
+    let content = fs::read_to_string(src_dir.join("PKGBUILD")).unwrap();
+    assert!(!content.contains("GOATD_PGO"));
+    assert!(content.contains("build() {\n  cd linux\n  make all\n}"));
+}
+
+/// Test: pkgbase built from variables (CachyOS style) is expanded before rebranding
+#[test]
+fn test_rebranding_expands_pkgbase_variables() {
+    use crate::kernel::patcher::pkgbuild::patch_pkgbuild_for_rebranding;
+
+    let temp_dir = tempfile::tempdir().expect("Failed to create temp directory");
+    let src_dir = temp_dir.path();
+    let pkgbuild_path = src_dir.join("PKGBUILD");
+    fs::write(
+        &pkgbuild_path,
+        r#"_pkgsuffix=cachyos
+pkgbase="linux-$_pkgsuffix"
+pkgname=("$pkgbase" "$pkgbase-headers")
+pkgver=6.18.3
+pkgrel=1
+"#,
+    )
+    .expect("Failed to write mock PKGBUILD");
+
+    patch_pkgbuild_for_rebranding(src_dir, "gaming").expect("Rebranding failed");
+    let rebranded = fs::read_to_string(&pkgbuild_path).unwrap();
+    assert!(rebranded.contains("pkgbase='linux-cachyos-goatd-gaming'"));
+    // Entries derived from $pkgbase follow the new pkgbase on their own
+    assert!(rebranded.contains(r#"pkgname=("$pkgbase" "$pkgbase-headers")"#));
+    assert!(!rebranded.contains("linux-linux"));
+
+    patch_pkgbuild_for_rebranding(src_dir, "gaming").expect("Second rebranding failed");
+    assert_eq!(fs::read_to_string(&pkgbuild_path).unwrap(), rebranded);
+}
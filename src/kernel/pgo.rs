@@ -0,0 +1,563 @@
+//! Profile-guided optimization (PGO / AutoFDO) for kernel builds.
+//!
+//! Two-stage workflow:
+//! 1. A [`PgoStage::Instrument`] build produces a kernel that can be profiled
+//!    (`CONFIG_AUTOFDO_CLANG` for AutoFDO, `CONFIG_PGO_CLANG` for instrumented
+//!    PGO, which needs the Clang PGO patch in the user patch queue).
+//! 2. On that kernel, [`collect`] runs a workload (the built-in benchmark
+//!    phases or a user command) and stores the raw profile under
+//!    `<workspace>/pgo/<version>/raw/`; [`ProfileStore::merge`] merges every
+//!    raw profile of the version with `llvm-profdata`.
+//! 3. A [`PgoStage::Optimize`] build of the same version consumes the merged
+//!    profile. The profile used is recorded in `MPLMetadata::pgo_profile`.
+
+use crate::error::AppError;
+use crate::models::{KernelConfig, PgoConfig, PgoMethod, PgoStage};
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::process::Command;
+use std::time::Duration;
+
+/// Directory (relative to the workspace) holding per-version profiles.
+pub const PGO_DIR_NAME: &str = "pgo";
+
+/// Derived config option: PGO stage of the build (`PgoStage::as_str`).
+pub const PGO_STAGE_KEY: &str = "_PGO_STAGE";
+
+/// Derived config option: merged profile consumed by an optimize build.
+pub const PGO_PROFILE_KEY: &str = "_PGO_PROFILE";
+
+/// Derived config option: `autofdo` or `instrumented`.
+pub const PGO_METHOD_KEY: &str = "_PGO_METHOD";
+
+/// debugfs interface of an instrumented (CONFIG_PGO_CLANG) kernel.
+pub const INSTRUMENTED_PROFRAW: &str = "/sys/kernel/debug/pgo/vmlinux.profraw";
+pub const INSTRUMENTED_RESET: &str = "/sys/kernel/debug/pgo/reset";
+
+/// perf sampling period for AutoFDO (a prime, as recommended upstream).
+pub const AUTOFDO_SAMPLE_PERIOD: u32 = 500009;
+
+/// Compiler flags added to KCFLAGS when consuming an instrumented profile.
+const PROFILE_USE_WARNINGS: &str = "-Wno-profile-instr-unprofiled -Wno-profile-instr-out-of-date";
+
+/// File name of the merged profile in the version directory.
+pub fn merged_file_name(method: PgoMethod) -> &'static str {
+    match method {
+        PgoMethod::AutoFdo => "kernel.afdo",
+        PgoMethod::Instrumented => "vmlinux.profdata",
+    }
+}
+
+/// Extension of the raw profiles merged into the merged profile.
+pub fn raw_extension(method: PgoMethod) -> &'static str {
+    match method {
+        PgoMethod::AutoFdo => "afdo",
+        PgoMethod::Instrumented => "profraw",
+    }
+}
+
+/// Kconfig options for a `method` build at `stage`.
+pub fn kconfig_options(
+    method: PgoMethod,
+    stage: PgoStage,
+) -> &'static [(&'static str, &'static str)] {
+    match (method, stage) {
+        (_, PgoStage::Off) => &[],
+        (PgoMethod::AutoFdo, _) => &[("CONFIG_AUTOFDO_CLANG", "y")],
+        (PgoMethod::Instrumented, PgoStage::Instrument) => {
+            &[("CONFIG_PGO_CLANG", "y"), ("CONFIG_DEBUG_FS", "y")]
+        }
+        (PgoMethod::Instrumented, PgoStage::Optimize) => &[("CONFIG_PGO_CLANG", "n")],
+    }
+}
+
+/// Directory key for a kernel version: "6.12.1" for "6.12.1-goatd-gaming".
+pub fn version_key(kernel_version: &str) -> String {
+    use crate::kernel::toolchain::Version;
+    Version::parse(kernel_version)
+        .map(|v| v.to_string())
+        .unwrap_or_else(|| kernel_version.replace('/', "_"))
+}
+
+/// Command collecting a profile into the store of `kernel_version`.
+///
+/// Without `--version`, `goatd pgo collect` keys the store on the running
+/// kernel's `uname -r`, which need not match the build's version.
+pub fn collect_command(kernel_version: &str) -> String {
+    format!("goatd pgo collect --version {}", kernel_version)
+}
+
+/// Profiles of one kernel version: `<workspace>/pgo/<version>/`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ProfileStore {
+    dir: PathBuf,
+}
+
+impl ProfileStore {
+    pub fn new(workspace: &Path, kernel_version: &str) -> Self {
+        ProfileStore {
+            dir: workspace
+                .join(PGO_DIR_NAME)
+                .join(version_key(kernel_version)),
+        }
+    }
+
+    pub fn dir(&self) -> &Path {
+        &self.dir
+    }
+
+    /// Raw profiles waiting to be merged.
+    pub fn raw_dir(&self) -> PathBuf {
+        self.dir.join("raw")
+    }
+
+    pub fn merged_path(&self, method: PgoMethod) -> PathBuf {
+        self.dir.join(merged_file_name(method))
+    }
+
+    /// Raw profiles of `method`, oldest first.
+    pub fn raw_profiles(&self, method: PgoMethod) -> Vec<PathBuf> {
+        let mut profiles: Vec<PathBuf> = fs::read_dir(self.raw_dir())
+            .map(|entries| {
+                entries
+                    .filter_map(|entry| entry.ok())
+                    .map(|entry| entry.path())
+                    .filter(|path| {
+                        path.extension().and_then(|e| e.to_str()) == Some(raw_extension(method))
+                    })
+                    .collect()
+            })
+            .unwrap_or_default();
+        profiles.sort();
+        profiles
+    }
+
+    /// Path for a new raw profile, named after the current time.
+    pub fn next_raw_path(&self, extension: &str) -> Result<PathBuf, AppError> {
+        fs::create_dir_all(self.raw_dir())?;
+        let stamp = chrono::Utc::now().format("%Y%m%dT%H%M%S%.3fZ");
+        Ok(self.raw_dir().join(format!("{}.{}", stamp, extension)))
+    }
+
+    /// Merge every raw profile of `method` into the merged profile.
+    pub fn merge(&self, method: PgoMethod) -> Result<PathBuf, AppError> {
+        let raw = self.raw_profiles(method);
+        if raw.is_empty() {
+            return Err(AppError::InvalidInput(format!(
+                "No raw {} profiles in {}",
+                method.as_str(),
+                self.raw_dir().display()
+            )));
+        }
+        let merged = self.merged_path(method);
+        let args = merge_args(method, &merged, &raw);
+        run_tool("llvm-profdata", &args)?;
+        eprintln!(
+            "[PGO] Merged {} profile(s) into {}",
+            raw.len(),
+            merged.display()
+        );
+        Ok(merged)
+    }
+}
+
+/// `llvm-profdata` arguments merging `raw` into `merged`.
+pub fn merge_args(method: PgoMethod, merged: &Path, raw: &[PathBuf]) -> Vec<String> {
+    let mut args = vec!["merge".to_string()];
+    if method == PgoMethod::AutoFdo {
+        args.push("--sample".to_string());
+    }
+    args.push(format!("--output={}", merged.display()));
+    args.extend(raw.iter().map(|p| p.to_string_lossy().to_string()));
+    args
+}
+
+/// Apply the PGO stage to a configured build: Kconfig options plus the
+/// derived `_PGO_*` keys read by the patcher.
+///
+/// An optimize build resolves its profile from `pgo.profile` or the
+/// workspace profile of the build's kernel version, and fails if there is
+/// none. Returns the profile consumed (optimize) or the profile directory
+/// to collect into (instrument).
+pub fn apply(config: &mut KernelConfig, workspace: &Path) -> Result<Option<PathBuf>, String> {
+    let pgo: PgoConfig = config.pgo.clone();
+    for key in [PGO_STAGE_KEY, PGO_PROFILE_KEY, PGO_METHOD_KEY] {
+        config.config_options.remove(key);
+    }
+    if pgo.stage == PgoStage::Off {
+        return Ok(None);
+    }
+
+    let store = ProfileStore::new(workspace, &config.version);
+    let target = match pgo.stage {
+        PgoStage::Optimize => {
+            let profile = pgo
+                .profile
+                .clone()
+                .unwrap_or_else(|| store.merged_path(pgo.method));
+            if !profile.is_file() {
+                return Err(format!(
+                    "No merged {} profile at {}: build and boot the instrumented kernel, then run `{}` (writes to {})",
+                    pgo.method.as_str(),
+                    profile.display(),
+                    collect_command(&config.version),
+                    store.dir().display()
+                ));
+            }
+            let profile = profile.canonicalize().unwrap_or(profile);
+            config.config_options.insert(
+                PGO_PROFILE_KEY.to_string(),
+                profile.to_string_lossy().to_string(),
+            );
+            profile
+        }
+        _ => store.dir().to_path_buf(),
+    };
+
+    for (key, value) in kconfig_options(pgo.method, pgo.stage) {
+        config
+            .config_options
+            .insert(key.to_string(), value.to_string());
+    }
+    config
+        .config_options
+        .insert(PGO_STAGE_KEY.to_string(), pgo.stage.as_str().to_string());
+    config
+        .config_options
+        .insert(PGO_METHOD_KEY.to_string(), pgo.method.as_str().to_string());
+    Ok(Some(target))
+}
+
+/// Shell exports consuming the profile in the PKGBUILD build(), if the
+/// options describe an optimize build.
+pub fn build_exports(options: &std::collections::HashMap<String, String>) -> Option<String> {
+    let profile = options.get(PGO_PROFILE_KEY)?;
+    match options.get(PGO_METHOD_KEY).map(|s| s.as_str()) {
+        Some("instrumented") => Some(format!(
+            "export KCFLAGS=\"${{KCFLAGS}} -fprofile-use={} {}\"",
+            profile, PROFILE_USE_WARNINGS
+        )),
+        _ => Some(format!("export CLANG_AUTOFDO_PROFILE=\"{}\"", profile)),
+    }
+}
+
+/// Workload exercised while the profile is recorded.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Workload {
+    /// The benchmark stressor phases, `phase_secs` seconds each
+    Benchmark { phase_secs: u64 },
+    /// A user command, run through `sh -c`
+    Command(String),
+}
+
+/// Profiling method of the running kernel: instrumented if its debugfs
+/// profile exists, AutoFDO otherwise.
+pub fn detect_method() -> PgoMethod {
+    if Path::new(INSTRUMENTED_PROFRAW).exists() {
+        PgoMethod::Instrumented
+    } else {
+        PgoMethod::AutoFdo
+    }
+}
+
+/// vmlinux with debug info for the running kernel, as installed by the
+/// headers package.
+pub fn default_vmlinux(kernel_release: &str) -> PathBuf {
+    PathBuf::from("/usr/lib/modules")
+        .join(kernel_release)
+        .join("build/vmlinux")
+}
+
+/// Record one raw profile of `workload` into `store`.
+///
+/// Must run on the booted stage-1 kernel, with the privileges needed for
+/// debugfs (instrumented) or system-wide `perf record` (AutoFDO).
+pub fn collect(
+    store: &ProfileStore,
+    method: PgoMethod,
+    workload: &Workload,
+    vmlinux: &Path,
+) -> Result<PathBuf, AppError> {
+    let raw = store.next_raw_path(raw_extension(method))?;
+    match method {
+        PgoMethod::Instrumented => {
+            fs::write(INSTRUMENTED_RESET, "1")
+                .map_err(|e| AppError::Io(format!("{}: {}", INSTRUMENTED_RESET, e)))?;
+            run_workload(workload)?;
+            fs::copy(INSTRUMENTED_PROFRAW, &raw)
+                .map_err(|e| AppError::Io(format!("{}: {}", INSTRUMENTED_PROFRAW, e)))?;
+        }
+        PgoMethod::AutoFdo => {
+            if !vmlinux.is_file() {
+                return Err(AppError::InvalidInput(format!(
+                    "vmlinux with debug info not found at {} (pass --vmlinux)",
+                    vmlinux.display()
+                )));
+            }
+            let perf_data = raw.with_extension("perf.data");
+            record_with_perf(workload, &perf_data)?;
+            let converted = run_tool(
+                "llvm-profgen",
+                &[
+                    "--kernel".to_string(),
+                    format!("--binary={}", vmlinux.display()),
+                    format!("--perfdata={}", perf_data.display()),
+                    format!("--output={}", raw.display()),
+                ],
+            );
+            let _ = fs::remove_file(&perf_data);
+            converted?;
+        }
+    }
+    eprintln!("[PGO] Recorded raw profile {}", raw.display());
+    Ok(raw)
+}
+
+/// `perf record` event recording taken branches with LBR/BRS on this CPU.
+fn autofdo_perf_event() -> Vec<String> {
+    let vendor = crate::hardware::detect_cpu_vendor().unwrap_or_default();
+    if vendor.contains("AMD") {
+        vec![
+            "--pfm-events".to_string(),
+            "RETIRED_TAKEN_BRANCH_INSTRUCTIONS:k".to_string(),
+        ]
+    } else {
+        vec!["-e".to_string(), "BR_INST_RETIRED.NEAR_TAKEN:k".to_string()]
+    }
+}
+
+/// Sample the whole system with `perf record -b` while the workload runs.
+fn record_with_perf(workload: &Workload, output: &Path) -> Result<(), AppError> {
+    let mut args = vec!["record".to_string()];
+    args.extend(autofdo_perf_event());
+    args.extend(["-a", "-N", "-b", "-c"].map(String::from));
+    args.push(AUTOFDO_SAMPLE_PERIOD.to_string());
+    args.push("-o".to_string());
+    args.push(output.to_string_lossy().to_string());
+    args.push("--".to_string());
+
+    match workload {
+        Workload::Command(cmd) => {
+            args.extend(["sh".to_string(), "-c".to_string(), cmd.clone()]);
+            run_tool("perf", &args)
+        }
+        Workload::Benchmark { phase_secs } => {
+            // perf samples system-wide for the duration of the in-process workload
+            let total = phase_secs * benchmark_phase_count() as u64;
+            args.extend(["sleep".to_string(), total.to_string()]);
+            let mut perf = Command::new("perf")
+                .args(&args)
+                .spawn()
+                .map_err(|e| os_error("perf", e.to_string()))?;
+            let workload_result = run_benchmark_workload(*phase_secs);
+            let status = perf.wait().map_err(|e| os_error("perf", e.to_string()))?;
+            workload_result?;
+            if !status.success() {
+                return Err(os_error("perf record", format!("exited with {}", status)));
+            }
+            Ok(())
+        }
+    }
+}
+
+fn run_workload(workload: &Workload) -> Result<(), AppError> {
+    match workload {
+        Workload::Benchmark { phase_secs } => run_benchmark_workload(*phase_secs),
+        Workload::Command(cmd) => run_tool("sh", &["-c".to_string(), cmd.clone()]),
+    }
+}
+
+fn benchmark_phase_count() -> usize {
+    crate::system::performance::BenchmarkScenario::goatd_full()
+        .phases
+        .len()
+}
+
+/// Walk the `BenchmarkOrchestrator` phases, running each phase's stressors
+/// for `phase_secs` seconds.
+pub fn run_benchmark_workload(phase_secs: u64) -> Result<(), AppError> {
+    use crate::system::performance::{BenchmarkOrchestrator, StressorManager};
+
+    let mut orchestrator = BenchmarkOrchestrator::new();
+    loop {
+        eprintln!(
+            "[PGO] Workload phase: {}",
+            orchestrator.current_phase_name()
+        );
+        let mut stressors =
+            StressorManager::new(0).map_err(|e| AppError::ModuleInit(e.to_string()))?;
+        for (stressor, intensity) in orchestrator.get_phase_stressors() {
+            stressors
+                .start_stressor(stressor, intensity)
+                .map_err(|e| AppError::ModuleInit(e.to_string()))?;
+        }
+        std::thread::sleep(Duration::from_secs(phase_secs));
+        stressors
+            .stop_all_stressors()
+            .map_err(|e| AppError::ModuleInit(e.to_string()))?;
+
+        if orchestrator.advance_phase().is_none() {
+            return Ok(());
+        }
+    }
+}
+
+fn run_tool(tool: &str, args: &[String]) -> Result<(), AppError> {
+    let output = Command::new(tool)
+        .args(args)
+        .output()
+        .map_err(|e| os_error(tool, e.to_string()))?;
+    if output.status.success() {
+        Ok(())
+    } else {
+        Err(os_error(
+            tool,
+            String::from_utf8_lossy(&output.stderr).trim().to_string(),
+        ))
+    }
+}
+
+fn os_error(cmd: &str, reason: String) -> AppError {
+    AppError::OsCommand {
+        cmd: cmd.to_string(),
+        reason,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::collections::HashMap;
+
+    fn config(stage: PgoStage, method: PgoMethod, profile: Option<PathBuf>) -> KernelConfig {
+        KernelConfig {
+            version: "6.14.2".to_string(),
+            pgo: PgoConfig {
+                stage,
+                method,
+                profile,
+            },
+            ..KernelConfig::default()
+        }
+    }
+
+    #[test]
+    fn test_version_key() {
+        assert_eq!(version_key("6.12.1-goatd-gaming"), "6.12.1");
+        assert_eq!(version_key("6.14"), "6.14.0");
+        assert_eq!(version_key("latest"), "latest");
+    }
+
+    #[test]
+    fn test_apply_instrument_stage() {
+        let workspace = tempfile::tempdir().unwrap();
+        let mut config = config(PgoStage::Instrument, PgoMethod::AutoFdo, None);
+
+        let target = apply(&mut config, workspace.path()).unwrap();
+        assert_eq!(target, Some(workspace.path().join("pgo/6.14.2")));
+        assert_eq!(
+            config.config_options.get("CONFIG_AUTOFDO_CLANG"),
+            Some(&"y".to_string())
+        );
+        assert_eq!(
+            config.config_options.get(PGO_STAGE_KEY),
+            Some(&"instrument".to_string())
+        );
+        assert!(!config.config_options.contains_key(PGO_PROFILE_KEY));
+        assert_eq!(build_exports(&config.config_options), None);
+    }
+
+    #[test]
+    fn test_apply_optimize_requires_profile() {
+        let workspace = tempfile::tempdir().unwrap();
+        let mut config = config(PgoStage::Optimize, PgoMethod::Instrumented, None);
+        let err = apply(&mut config, workspace.path()).unwrap_err();
+        assert!(err.contains("vmlinux.profdata"), "{}", err);
+        assert!(
+            err.contains("`goatd pgo collect --version 6.14.2` (writes to"),
+            "{}",
+            err
+        );
+
+        let store = ProfileStore::new(workspace.path(), "6.14.2");
+        fs::create_dir_all(store.dir()).unwrap();
+        fs::write(store.merged_path(PgoMethod::Instrumented), b"profdata").unwrap();
+
+        let profile = apply(&mut config, workspace.path()).unwrap().unwrap();
+        assert!(profile.ends_with("pgo/6.14.2/vmlinux.profdata"));
+        assert_eq!(
+            config.config_options.get("CONFIG_PGO_CLANG"),
+            Some(&"n".to_string())
+        );
+        let exports = build_exports(&config.config_options).unwrap();
+        assert!(exports.starts_with("export KCFLAGS=\"${KCFLAGS} -fprofile-use="));
+        assert!(exports.contains("vmlinux.profdata"));
+    }
+
+    #[test]
+    fn test_apply_off_clears_derived_keys() {
+        let workspace = tempfile::tempdir().unwrap();
+        let mut config = config(PgoStage::Off, PgoMethod::AutoFdo, None);
+        config
+            .config_options
+            .insert(PGO_STAGE_KEY.to_string(), "optimize".to_string());
+        assert_eq!(apply(&mut config, workspace.path()), Ok(None));
+        assert!(!config.config_options.contains_key(PGO_STAGE_KEY));
+    }
+
+    #[test]
+    fn test_autofdo_exports_and_merge_args() {
+        let mut options = HashMap::new();
+        options.insert(
+            PGO_PROFILE_KEY.to_string(),
+            "/ws/pgo/6.14.2/kernel.afdo".to_string(),
+        );
+        options.insert(PGO_METHOD_KEY.to_string(), "autofdo".to_string());
+        assert_eq!(
+            build_exports(&options).as_deref(),
+            Some("export CLANG_AUTOFDO_PROFILE=\"/ws/pgo/6.14.2/kernel.afdo\"")
+        );
+
+        let args = merge_args(
+            PgoMethod::AutoFdo,
+            Path::new("/ws/kernel.afdo"),
+            &[
+                PathBuf::from("/ws/raw/a.afdo"),
+                PathBuf::from("/ws/raw/b.afdo"),
+            ],
+        );
+        assert_eq!(
+            args,
+            vec![
+                "merge",
+                "--sample",
+                "--output=/ws/kernel.afdo",
+                "/ws/raw/a.afdo",
+                "/ws/raw/b.afdo"
+            ]
+        );
+    }
+
+    #[test]
+    fn test_raw_profiles_filtered_by_method() {
+        let workspace = tempfile::tempdir().unwrap();
+        let store = ProfileStore::new(workspace.path(), "6.14.2");
+        fs::create_dir_all(store.raw_dir()).unwrap();
+        fs::write(store.raw_dir().join("2.afdo"), b"").unwrap();
+        fs::write(store.raw_dir().join("1.afdo"), b"").unwrap();
+        fs::write(store.raw_dir().join("1.profraw"), b"").unwrap();
+
+        let afdo = store.raw_profiles(PgoMethod::AutoFdo);
+        assert_eq!(
+            afdo,
+            vec![
+                store.raw_dir().join("1.afdo"),
+                store.raw_dir().join("2.afdo")
+            ]
+        );
+        assert_eq!(store.raw_profiles(PgoMethod::Instrumented).len(), 1);
+        assert!(ProfileStore::new(workspace.path(), "6.1.0")
+            .merge(PgoMethod::AutoFdo)
+            .is_err());
+    }
+}
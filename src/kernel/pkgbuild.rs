@@ -4,16 +4,35 @@
 //! and extracting version information using regex patterns.
 //!
 //! Uses the KernelSourceDB to locate PKGBUILD URLs based on canonical variant names.
+//! In offline mode versions come from the local source mirror instead.
 
-use crate::kernel::sources::KernelSourceDB;
+use crate::kernel::mirror::SourceMirror;
+use crate::kernel::sources::{KernelSourceDB, PKGBUILD_REF_PLACEHOLDER};
 use log;
 use regex::Regex;
 
-/// Maps kernel variant names (canonical form) to their raw PKGBUILD URLs
-/// Uses the centralized KernelSourceDB for consistency
-fn get_pkgbuild_url_for_variant(variant: &str) -> Option<String> {
-    let db = KernelSourceDB::new();
-    db.get_pkgbuild_url(variant).map(|url| url.to_string())
+/// Resolve the raw PKGBUILD URL of a variant at the ref its branch/tag policy selects
+/// Uses the centralized KernelSourceDB for consistency (including custom sources)
+///
+/// Only URLs with a `{ref}` placeholder need the ref; `LatestTag` policies list
+/// the remote tags for it, off the async runtime.
+async fn resolve_pkgbuild_url(variant: &str) -> Result<String, String> {
+    let source = KernelSourceDB::load()
+        .get_source(variant)
+        .cloned()
+        .ok_or_else(|| format!("Unknown kernel variant: {}", variant))?;
+    if !source.pkgbuild_url.contains(PKGBUILD_REF_PLACEHOLDER) {
+        return Ok(source.pkgbuild_url);
+    }
+
+    let resolved = tokio::task::spawn_blocking({
+        let source = source.clone();
+        move || source.resolve_ref()
+    })
+    .await
+    .map_err(|e| format!("Ref resolution task failed: {}", e))?
+    .map_err(|e| format!("Failed to resolve ref for {}: {}", variant, e))?;
+    Ok(source.pkgbuild_url_for(resolved.as_deref()))
 }
 
 /// Fetch and parse the latest kernel version from a PKGBUILD file
@@ -33,11 +52,25 @@ fn get_pkgbuild_url_for_variant(variant: &str) -> Option<String> {
 /// assert!(version.contains("-")); // Should be in format X.Y.Z-N
 /// ```
 pub async fn get_latest_version_from_pkgbuild(url: &str) -> Result<String, String> {
+    let content = fetch_pkgbuild(url).await?;
+
+    // Parse pkgver and pkgrel using regex
+    let pkgver = extract_pkgver(&content)?;
+    let pkgrel = extract_pkgrel(&content)?;
+
+    let version = format!("{}-{}", pkgver, pkgrel);
+    log::debug!("[PKGBUILD] Extracted version: {}", version);
+
+    Ok(version)
+}
+
+/// Fetch the raw content of a PKGBUILD
+pub async fn fetch_pkgbuild(url: &str) -> Result<String, String> {
     log::debug!("[PKGBUILD] Fetching: {}", url);
 
-    // Fetch the raw PKGBUILD content
     let response = reqwest::get(url)
         .await
+        .and_then(|response| response.error_for_status())
         .map_err(|e| format!("Failed to fetch PKGBUILD: {}", e))?;
 
     let content = response
@@ -46,15 +79,7 @@ pub async fn get_latest_version_from_pkgbuild(url: &str) -> Result<String, Strin
         .map_err(|e| format!("Failed to read response body: {}", e))?;
 
     log::debug!("[PKGBUILD] Successfully fetched {} bytes", content.len());
-
-    // Parse pkgver and pkgrel using regex
-    let pkgver = extract_pkgver(&content)?;
-    let pkgrel = extract_pkgrel(&content)?;
-
-    let version = format!("{}-{}", pkgver, pkgrel);
-    log::debug!("[PKGBUILD] Extracted version: {}", version);
-
-    Ok(version)
+    Ok(content)
 }
 
 /// Extract pkgver from PKGBUILD content
@@ -110,7 +135,7 @@ pub fn extract_pkgrel(content: &str) -> Result<String, String> {
 /// Convenience function: fetch PKGBUILD version by kernel variant name (canonical names)
 ///
 /// # Arguments
-/// * `variant` - The kernel variant name (canonical): "linux", "linux-lts", "linux-hardened", "linux-mainline", "linux-zen", "linux-tkg"
+/// * `variant` - The kernel variant name (canonical): "linux", "linux-lts", "linux-hardened", "linux-mainline", "linux-zen", "linux-tkg", "linux-cachyos", "linux-rt", or a custom source
 ///
 /// # Returns
 /// * `Ok(String)` - Version string in format `{pkgver}-{pkgrel}`
@@ -130,8 +155,28 @@ pub async fn get_latest_version_by_variant(variant: &str) -> Result<String, Stri
         variant
     );
 
-    let url = get_pkgbuild_url_for_variant(variant).ok_or_else(|| {
-        let err_msg = format!("Unknown kernel variant: {}", variant);
+    // Offline mode: resolve from the local mirror's tags, never the network
+    if let Some(mirror) = SourceMirror::offline() {
+        eprintln!(
+            "[PKGBUILD] [GET_VERSION] Offline mode: resolving '{}' from mirror {}",
+            variant,
+            mirror.root().display()
+        );
+        let source = KernelSourceDB::load()
+            .get_source(variant)
+            .cloned()
+            .ok_or_else(|| format!("Unknown kernel variant: {}", variant))?;
+        let variant_owned = variant.to_string();
+        return tokio::task::spawn_blocking(move || mirror.latest_version(&variant_owned, &source))
+            .await
+            .map_err(|e| format!("Mirror lookup task failed: {}", e))?
+            .map_err(|err_msg| {
+                eprintln!("[PKGBUILD] [GET_VERSION] [ERROR] {}", err_msg);
+                err_msg
+            });
+    }
+
+    let url = resolve_pkgbuild_url(variant).await.map_err(|err_msg| {
         eprintln!("[PKGBUILD] [GET_VERSION] [ERROR] {}", err_msg);
         err_msg
     })?;
@@ -225,36 +270,39 @@ mod tests {
     #[test]
     fn test_get_pkgbuild_url_canonical_variants() {
         // Test canonical variant names (from KernelSourceDB)
-        assert!(get_pkgbuild_url_for_variant("linux").is_some());
-        assert!(get_pkgbuild_url_for_variant("linux-lts").is_some());
-        assert!(get_pkgbuild_url_for_variant("linux-hardened").is_some());
-        assert!(get_pkgbuild_url_for_variant("linux-mainline").is_some());
-        assert!(get_pkgbuild_url_for_variant("linux-zen").is_some());
-        assert!(get_pkgbuild_url_for_variant("linux-tkg").is_some());
+        let db = KernelSourceDB::new();
+        assert!(db.get_pkgbuild_url("linux").is_some());
+        assert!(db.get_pkgbuild_url("linux-lts").is_some());
+        assert!(db.get_pkgbuild_url("linux-hardened").is_some());
+        assert!(db.get_pkgbuild_url("linux-mainline").is_some());
+        assert!(db.get_pkgbuild_url("linux-zen").is_some());
+        assert!(db.get_pkgbuild_url("linux-tkg").is_some());
     }
 
     #[test]
     fn test_get_pkgbuild_url_canonical_urls() {
         // Verify URL structure matches KernelSourceDB
-        let url_linux = get_pkgbuild_url_for_variant("linux").unwrap();
+        let db = KernelSourceDB::new();
+        let url_linux = db.get_pkgbuild_url("linux").unwrap();
         assert!(url_linux.contains("linux/-/raw"));
 
-        let url_lts = get_pkgbuild_url_for_variant("linux-lts").unwrap();
+        let url_lts = db.get_pkgbuild_url("linux-lts").unwrap();
         assert!(url_lts.contains("linux-lts"));
 
-        let url_zen = get_pkgbuild_url_for_variant("linux-zen").unwrap();
+        let url_zen = db.get_pkgbuild_url("linux-zen").unwrap();
         assert!(url_zen.contains("linux-zen"));
 
-        let url_mainline = get_pkgbuild_url_for_variant("linux-mainline").unwrap();
+        let url_mainline = db.get_pkgbuild_url("linux-mainline").unwrap();
         assert!(url_mainline.contains("aur.archlinux.org"));
 
-        let url_tkg = get_pkgbuild_url_for_variant("linux-tkg").unwrap();
+        let url_tkg = db.get_pkgbuild_url("linux-tkg").unwrap();
         assert!(url_tkg.contains("github.com") || url_tkg.contains("raw.githubusercontent.com"));
     }
 
     #[test]
     fn test_get_pkgbuild_url_unknown() {
-        assert!(get_pkgbuild_url_for_variant("unknown").is_none());
-        assert!(get_pkgbuild_url_for_variant("linux-invalid").is_none());
+        let db = KernelSourceDB::new();
+        assert!(db.get_pkgbuild_url("unknown").is_none());
+        assert!(db.get_pkgbuild_url("linux-invalid").is_none());
     }
 }
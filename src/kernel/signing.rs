@@ -0,0 +1,590 @@
+//! Secure Boot signing with a Machine Owner Key (MOK).
+//!
+//! Provides the pieces needed to produce a kernel that boots with Secure Boot
+//! enforced:
+//! - Validation of a user-supplied MOK private key / certificate pair
+//! - The combined PEM file consumed by `CONFIG_MODULE_SIG_KEY`
+//! - Signing and verifying the kernel image (`sbsign` / `sbverify`)
+//! - Detection of appended module signatures on `.ko`, `.ko.gz`, `.ko.zst`
+//!   and `.ko.xz` files, and their verification against the MOK certificate
+
+use crate::error::AppError;
+use crate::models::KernelConfig;
+use std::fs;
+use std::io::{Read, Write};
+use std::path::{Path, PathBuf};
+use std::process::Command;
+
+/// Trailer the kernel appends after a module signature (`scripts/sign-file`).
+pub const MODULE_SIG_MAGIC: &[u8] = b"~Module signature appended~\n";
+
+/// File name of the combined key + certificate PEM staged for the build.
+pub const MODULE_SIGNING_PEM_NAME: &str = "goatd_module_signing.pem";
+
+/// Size of `struct module_signature` that precedes the magic trailer.
+const MODULE_SIGNATURE_STRUCT_LEN: usize = 12;
+
+/// `id_type` value for PKCS#7 signatures (the only type the kernel accepts).
+const PKEY_ID_PKCS7: u8 = 2;
+
+/// A Machine Owner Key: private key and matching X.509 certificate (PEM).
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct MokKeyPair {
+    /// Path to the PEM-encoded private key
+    pub key: PathBuf,
+    /// Path to the PEM-encoded certificate enrolled with `mokutil --import`
+    pub cert: PathBuf,
+}
+
+impl MokKeyPair {
+    /// Create a key pair from explicit paths.
+    pub fn new(key: impl Into<PathBuf>, cert: impl Into<PathBuf>) -> Self {
+        MokKeyPair {
+            key: key.into(),
+            cert: cert.into(),
+        }
+    }
+
+    /// Build the key pair configured on a `KernelConfig`, if both paths are set.
+    pub fn from_config(config: &KernelConfig) -> Option<Self> {
+        match (&config.mok_key_path, &config.mok_cert_path) {
+            (Some(key), Some(cert)) => Some(MokKeyPair::new(key.clone(), cert.clone())),
+            _ => None,
+        }
+    }
+
+    /// Check that both files exist and that the certificate belongs to the key.
+    ///
+    /// The public key extracted from the certificate is compared with the one
+    /// derived from the private key via `openssl`.
+    pub fn validate(&self) -> Result<(), AppError> {
+        for (label, path) in [("MOK key", &self.key), ("MOK certificate", &self.cert)] {
+            if !path.is_file() {
+                return Err(AppError::InvalidPath(format!(
+                    "{} not found: {}",
+                    label,
+                    path.display()
+                )));
+            }
+        }
+
+        let cert_pubkey = run_openssl(&[
+            "x509",
+            "-noout",
+            "-pubkey",
+            "-in",
+            &self.cert.to_string_lossy(),
+        ])?;
+        let key_pubkey = run_openssl(&["pkey", "-pubout", "-in", &self.key.to_string_lossy()])?;
+
+        if cert_pubkey.trim() != key_pubkey.trim() {
+            return Err(AppError::InvalidInput(format!(
+                "MOK certificate {} does not match private key {}",
+                self.cert.display(),
+                self.key.display()
+            )));
+        }
+
+        Ok(())
+    }
+
+    /// Write the key and certificate into one PEM file for `CONFIG_MODULE_SIG_KEY`.
+    ///
+    /// The file holds the private key, so it is staged outside the kernel tree
+    /// in a private (`0700`) temporary directory and created with mode `0600`
+    /// from the start. Both are removed when the returned guard is dropped.
+    pub fn write_module_signing_pem(&self) -> Result<ModuleSigningPem, AppError> {
+        let key = fs::read_to_string(&self.key)
+            .map_err(|e| AppError::Io(format!("Failed to read {}: {}", self.key.display(), e)))?;
+        let cert = fs::read_to_string(&self.cert)
+            .map_err(|e| AppError::Io(format!("Failed to read {}: {}", self.cert.display(), e)))?;
+
+        let mut combined = key.trim_end().to_string();
+        combined.push('\n');
+        combined.push_str(cert.trim_end());
+        combined.push('\n');
+
+        let mut dir_builder = tempfile::Builder::new();
+        dir_builder.prefix("goatd-signing-");
+        let mut options = fs::OpenOptions::new();
+        options.write(true).create_new(true);
+        #[cfg(unix)]
+        {
+            use std::os::unix::fs::{OpenOptionsExt, PermissionsExt};
+            dir_builder.permissions(fs::Permissions::from_mode(0o700));
+            options.mode(0o600);
+        }
+
+        let dir = dir_builder
+            .tempdir()
+            .map_err(|e| AppError::Io(format!("Failed to create signing key directory: {}", e)))?;
+        let path = dir.path().join(MODULE_SIGNING_PEM_NAME);
+        options
+            .open(&path)
+            .and_then(|mut file| file.write_all(combined.as_bytes()))
+            .map_err(|e| AppError::Io(format!("Failed to write {}: {}", path.display(), e)))?;
+
+        Ok(ModuleSigningPem { dir, path })
+    }
+}
+
+/// Combined MOK key + certificate PEM staged for a single build.
+///
+/// Dropping it deletes the file and its private directory.
+#[derive(Debug)]
+pub struct ModuleSigningPem {
+    dir: tempfile::TempDir,
+    path: PathBuf,
+}
+
+impl ModuleSigningPem {
+    /// Path to the PEM file (the value of `CONFIG_MODULE_SIG_KEY`).
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+
+    /// Directory holding the PEM file.
+    pub fn dir(&self) -> &Path {
+        self.dir.path()
+    }
+}
+
+fn run_openssl(args: &[&str]) -> Result<String, AppError> {
+    let output = Command::new("openssl")
+        .args(args)
+        .output()
+        .map_err(|e| AppError::OsCommand {
+            cmd: format!("openssl {}", args.join(" ")),
+            reason: e.to_string(),
+        })?;
+
+    if !output.status.success() {
+        return Err(AppError::OsCommand {
+            cmd: format!("openssl {}", args.join(" ")),
+            reason: String::from_utf8_lossy(&output.stderr).trim().to_string(),
+        });
+    }
+
+    Ok(String::from_utf8_lossy(&output.stdout).to_string())
+}
+
+/// Whether `tool` can be found in `PATH`.
+pub fn tool_available(tool: &str) -> bool {
+    Command::new("which")
+        .arg(tool)
+        .output()
+        .map(|o| o.status.success())
+        .unwrap_or(false)
+}
+
+/// Sign a kernel image in place with `sbsign`.
+pub fn sign_kernel_image(image: &Path, keys: &MokKeyPair) -> Result<(), AppError> {
+    let output = Command::new("sbsign")
+        .arg("--key")
+        .arg(&keys.key)
+        .arg("--cert")
+        .arg(&keys.cert)
+        .arg("--output")
+        .arg(image)
+        .arg(image)
+        .output()
+        .map_err(|e| AppError::OsCommand {
+            cmd: "sbsign".to_string(),
+            reason: e.to_string(),
+        })?;
+
+    if !output.status.success() {
+        return Err(AppError::OsCommand {
+            cmd: "sbsign".to_string(),
+            reason: String::from_utf8_lossy(&output.stderr).trim().to_string(),
+        });
+    }
+
+    Ok(())
+}
+
+/// Check a kernel image's Authenticode signature against `cert` with `sbverify`.
+///
+/// Returns `Ok(false)` when the image is unsigned or signed by another key, and
+/// an error only when `sbverify` cannot be run.
+pub fn verify_kernel_image(image: &Path, cert: &Path) -> Result<bool, AppError> {
+    let output = Command::new("sbverify")
+        .arg("--cert")
+        .arg(cert)
+        .arg(image)
+        .output()
+        .map_err(|e| AppError::OsCommand {
+            cmd: "sbverify".to_string(),
+            reason: e.to_string(),
+        })?;
+
+    Ok(output.status.success())
+}
+
+/// Appended signature found at the end of a kernel module.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ModuleSignature {
+    /// Signature type (`2` = PKCS#7)
+    pub id_type: u8,
+    /// Length of the signature blob in bytes
+    pub sig_len: u32,
+}
+
+impl ModuleSignature {
+    /// Whether the signature is in the PKCS#7 format the kernel verifies.
+    pub fn is_pkcs7(&self) -> bool {
+        self.id_type == PKEY_ID_PKCS7
+    }
+}
+
+/// Parse the appended signature trailer of an uncompressed module image.
+///
+/// Layout: `[module][signature][struct module_signature][magic]`, with
+/// `sig_len` stored big-endian in the last four bytes of the struct.
+pub fn parse_module_signature(data: &[u8]) -> Option<ModuleSignature> {
+    let trailer_len = MODULE_SIG_MAGIC.len() + MODULE_SIGNATURE_STRUCT_LEN;
+    if data.len() < trailer_len || !data.ends_with(MODULE_SIG_MAGIC) {
+        return None;
+    }
+
+    let info_start = data.len() - trailer_len;
+    let info = &data[info_start..info_start + MODULE_SIGNATURE_STRUCT_LEN];
+    let sig_len = u32::from_be_bytes([info[8], info[9], info[10], info[11]]);
+
+    if sig_len == 0 || sig_len as usize > info_start {
+        return None;
+    }
+
+    Some(ModuleSignature {
+        id_type: info[2],
+        sig_len,
+    })
+}
+
+/// Whether `path` names a kernel module, compressed or not.
+pub fn is_module_file(path: &Path) -> bool {
+    let name = path
+        .file_name()
+        .map(|n| n.to_string_lossy().to_string())
+        .unwrap_or_default();
+    [".ko", ".ko.gz", ".ko.zst", ".ko.xz"]
+        .iter()
+        .any(|ext| name.ends_with(ext))
+}
+
+/// Read a module file, decompressing `.gz`, `.zst` and `.xz` modules.
+pub fn read_module_bytes(path: &Path) -> Result<Vec<u8>, AppError> {
+    let name = path.to_string_lossy();
+
+    if name.ends_with(".gz") {
+        let file = fs::File::open(path)?;
+        let mut decoder = flate2::read::GzDecoder::new(file);
+        let mut data = Vec::new();
+        decoder
+            .read_to_end(&mut data)
+            .map_err(|e| AppError::Io(format!("Failed to decompress {}: {}", name, e)))?;
+        return Ok(data);
+    }
+
+    let decompressor = if name.ends_with(".zst") {
+        Some("zstd")
+    } else if name.ends_with(".xz") {
+        Some("xz")
+    } else {
+        None
+    };
+
+    match decompressor {
+        Some(tool) => {
+            let output = Command::new(tool)
+                .arg("-dcq")
+                .arg(path)
+                .output()
+                .map_err(|e| AppError::OsCommand {
+                    cmd: format!("{} -dcq {}", tool, name),
+                    reason: e.to_string(),
+                })?;
+            if !output.status.success() {
+                return Err(AppError::OsCommand {
+                    cmd: format!("{} -dcq {}", tool, name),
+                    reason: String::from_utf8_lossy(&output.stderr).trim().to_string(),
+                });
+            }
+            Ok(output.stdout)
+        }
+        None => fs::read(path).map_err(|e| AppError::Io(format!("Failed to read {}: {}", name, e))),
+    }
+}
+
+/// Whether the module at `path` carries a PKCS#7 appended signature.
+pub fn is_module_signed(path: &Path) -> Result<bool, AppError> {
+    let data = read_module_bytes(path)?;
+    Ok(parse_module_signature(&data).is_some_and(|sig| sig.is_pkcs7()))
+}
+
+/// Whether the module at `path` is signed by the key behind `cert`.
+///
+/// Module signatures carry no certificate, so the signer is looked up in
+/// `cert` alone (`openssl cms -verify -nointern`) and the signature is checked
+/// over the module body. Unsigned modules and modules signed by any other key
+/// return `Ok(false)`.
+pub fn is_module_signed_by(path: &Path, cert: &Path) -> Result<bool, AppError> {
+    if !cert.is_file() {
+        return Err(AppError::InvalidPath(format!(
+            "MOK certificate not found: {}",
+            cert.display()
+        )));
+    }
+
+    let data = read_module_bytes(path)?;
+    let Some(sig) = parse_module_signature(&data).filter(|sig| sig.is_pkcs7()) else {
+        return Ok(false);
+    };
+
+    // [module][signature][struct module_signature][magic]
+    let sig_end = data.len() - MODULE_SIG_MAGIC.len() - MODULE_SIGNATURE_STRUCT_LEN;
+    let body_end = sig_end - sig.sig_len as usize;
+
+    let scratch = tempfile::TempDir::new()?;
+    let body = scratch.path().join("module");
+    let signature = scratch.path().join("module.p7s");
+    fs::write(&body, &data[..body_end])?;
+    fs::write(&signature, &data[body_end..sig_end])?;
+
+    let output = Command::new("openssl")
+        .args(["cms", "-verify", "-binary", "-inform", "DER"])
+        .args(["-nointern", "-noverify", "-out", "/dev/null"])
+        .arg("-in")
+        .arg(&signature)
+        .arg("-content")
+        .arg(&body)
+        .arg("-certfile")
+        .arg(cert)
+        .output()
+        .map_err(|e| AppError::OsCommand {
+            cmd: "openssl cms -verify".to_string(),
+            reason: e.to_string(),
+        })?;
+
+    Ok(output.status.success())
+}
+
+#[cfg(test)]
+pub(crate) mod tests {
+    use super::*;
+    use std::io::Write;
+    use tempfile::TempDir;
+
+    /// Generate a throwaway self-signed key pair, or `None` without openssl.
+    pub(crate) fn generate_test_keypair(dir: &Path, name: &str) -> Option<MokKeyPair> {
+        let key = dir.join(format!("{}.key", name));
+        let cert = dir.join(format!("{}.crt", name));
+        let status = Command::new("openssl")
+            .args([
+                "req", "-x509", "-newkey", "rsa:2048", "-nodes", "-days", "1",
+            ])
+            .args(["-subj", "/CN=GOATd test MOK"])
+            .arg("-keyout")
+            .arg(&key)
+            .arg("-out")
+            .arg(&cert)
+            .output()
+            .ok()?;
+        status.status.success().then(|| MokKeyPair::new(key, cert))
+    }
+
+    /// Append a real PKCS#7 module signature made with `keys` to `body`, the
+    /// way `scripts/sign-file` does, or `None` without openssl.
+    pub(crate) fn sign_module_bytes(body: &[u8], keys: &MokKeyPair) -> Option<Vec<u8>> {
+        let scratch = TempDir::new().ok()?;
+        let module = scratch.path().join("module");
+        fs::write(&module, body).ok()?;
+        let output = Command::new("openssl")
+            .args(["cms", "-sign", "-binary", "-noattr", "-nocerts"])
+            .args(["-outform", "DER", "-md", "sha256", "-signer"])
+            .arg(&keys.cert)
+            .arg("-inkey")
+            .arg(&keys.key)
+            .arg("-in")
+            .arg(&module)
+            .output()
+            .ok()?;
+        if !output.status.success() {
+            return None;
+        }
+
+        let mut data = body.to_vec();
+        data.extend_from_slice(&output.stdout);
+        data.extend_from_slice(&[0, 0, PKEY_ID_PKCS7, 0, 0, 0, 0, 0]);
+        data.extend_from_slice(&(output.stdout.len() as u32).to_be_bytes());
+        data.extend_from_slice(MODULE_SIG_MAGIC);
+        Some(data)
+    }
+
+    fn signed_module_bytes() -> Vec<u8> {
+        let mut data = b"\x7fELF fake module body".to_vec();
+        let signature = vec![0x30u8; 64];
+        data.extend_from_slice(&signature);
+        data.extend_from_slice(&[0, 0, PKEY_ID_PKCS7, 0, 0, 0, 0, 0]);
+        data.extend_from_slice(&(signature.len() as u32).to_be_bytes());
+        data.extend_from_slice(MODULE_SIG_MAGIC);
+        data
+    }
+
+    #[test]
+    fn test_keypair_validate_accepts_matching_pair() {
+        let dir = TempDir::new().unwrap();
+        let Some(keys) = generate_test_keypair(dir.path(), "mok") else {
+            eprintln!("openssl unavailable, skipping");
+            return;
+        };
+        assert!(keys.validate().is_ok());
+    }
+
+    #[test]
+    fn test_keypair_validate_rejects_mismatched_pair() {
+        let dir = TempDir::new().unwrap();
+        let (Some(first), Some(second)) = (
+            generate_test_keypair(dir.path(), "first"),
+            generate_test_keypair(dir.path(), "second"),
+        ) else {
+            eprintln!("openssl unavailable, skipping");
+            return;
+        };
+        let mixed = MokKeyPair::new(first.key, second.cert);
+        assert!(matches!(mixed.validate(), Err(AppError::InvalidInput(_))));
+    }
+
+    #[test]
+    fn test_keypair_validate_missing_files() {
+        let keys = MokKeyPair::new("/nonexistent/mok.key", "/nonexistent/mok.crt");
+        assert!(matches!(keys.validate(), Err(AppError::InvalidPath(_))));
+    }
+
+    #[test]
+    fn test_write_module_signing_pem() {
+        let dir = TempDir::new().unwrap();
+        let Some(keys) = generate_test_keypair(dir.path(), "mok") else {
+            eprintln!("openssl unavailable, skipping");
+            return;
+        };
+        let pem = keys.write_module_signing_pem().unwrap();
+        assert!(!pem.path().starts_with(dir.path()));
+        let content = fs::read_to_string(pem.path()).unwrap();
+        assert!(content.contains("PRIVATE KEY-----"));
+        assert!(content.contains("-----BEGIN CERTIFICATE-----"));
+
+        #[cfg(unix)]
+        {
+            use std::os::unix::fs::PermissionsExt;
+            let mode = fs::metadata(pem.path()).unwrap().permissions().mode();
+            assert_eq!(mode & 0o777, 0o600);
+            let dir_mode = fs::metadata(pem.dir()).unwrap().permissions().mode();
+            assert_eq!(dir_mode & 0o777, 0o700);
+        }
+
+        let staged_dir = pem.dir().to_path_buf();
+        drop(pem);
+        assert!(!staged_dir.exists());
+    }
+
+    #[test]
+    fn test_parse_module_signature() {
+        let sig = parse_module_signature(&signed_module_bytes()).unwrap();
+        assert!(sig.is_pkcs7());
+        assert_eq!(sig.sig_len, 64);
+
+        assert!(parse_module_signature(b"\x7fELF unsigned module").is_none());
+        // Magic without a plausible signature length
+        let mut bogus = vec![0u8; MODULE_SIGNATURE_STRUCT_LEN];
+        bogus.extend_from_slice(MODULE_SIG_MAGIC);
+        assert!(parse_module_signature(&bogus).is_none());
+    }
+
+    #[test]
+    fn test_is_module_signed_plain_and_gzip() {
+        let dir = TempDir::new().unwrap();
+
+        let plain = dir.path().join("signed.ko");
+        fs::write(&plain, signed_module_bytes()).unwrap();
+        assert!(is_module_signed(&plain).unwrap());
+
+        let unsigned = dir.path().join("unsigned.ko");
+        fs::write(&unsigned, b"\x7fELF unsigned module").unwrap();
+        assert!(!is_module_signed(&unsigned).unwrap());
+
+        let gz = dir.path().join("signed.ko.gz");
+        let mut encoder = flate2::write::GzEncoder::new(
+            fs::File::create(&gz).unwrap(),
+            flate2::Compression::default(),
+        );
+        encoder.write_all(&signed_module_bytes()).unwrap();
+        encoder.finish().unwrap();
+        assert!(is_module_signed(&gz).unwrap());
+    }
+
+    #[test]
+    fn test_is_module_signed_by_checks_signer() {
+        let dir = TempDir::new().unwrap();
+        let (Some(mok), Some(other)) = (
+            generate_test_keypair(dir.path(), "mok"),
+            generate_test_keypair(dir.path(), "other"),
+        ) else {
+            eprintln!("openssl unavailable, skipping");
+            return;
+        };
+        let body = b"\x7fELF fake module body";
+
+        let ours = dir.path().join("ours.ko");
+        fs::write(&ours, sign_module_bytes(body, &mok).unwrap()).unwrap();
+        assert!(is_module_signed_by(&ours, &mok.cert).unwrap());
+
+        // A valid signature from a second throwaway key is not the MOK's
+        let theirs = dir.path().join("theirs.ko");
+        fs::write(&theirs, sign_module_bytes(body, &other).unwrap()).unwrap();
+        assert!(is_module_signed(&theirs).unwrap());
+        assert!(!is_module_signed_by(&theirs, &mok.cert).unwrap());
+
+        // Tampering with the body after signing breaks the signature
+        let mut tampered = sign_module_bytes(body, &mok).unwrap();
+        tampered[0] ^= 0xff;
+        let tampered_path = dir.path().join("tampered.ko");
+        fs::write(&tampered_path, tampered).unwrap();
+        assert!(!is_module_signed_by(&tampered_path, &mok.cert).unwrap());
+
+        // The fake trailer from signed_module_bytes() is not a valid signature
+        let fake = dir.path().join("fake.ko");
+        fs::write(&fake, signed_module_bytes()).unwrap();
+        assert!(!is_module_signed_by(&fake, &mok.cert).unwrap());
+
+        assert!(matches!(
+            is_module_signed_by(&ours, Path::new("/nonexistent/mok.crt")),
+            Err(AppError::InvalidPath(_))
+        ));
+    }
+
+    #[test]
+    fn test_is_module_file() {
+        assert!(is_module_file(Path::new("drivers/nvme.ko")));
+        assert!(is_module_file(Path::new("nvme.ko.zst")));
+        assert!(is_module_file(Path::new("nvme.ko.xz")));
+        assert!(!is_module_file(Path::new("modules.dep")));
+    }
+
+    #[test]
+    fn test_sign_and_verify_kernel_image() {
+        if !tool_available("sbsign") || !tool_available("sbverify") {
+            eprintln!("sbsign/sbverify unavailable, skipping");
+            return;
+        }
+        let dir = TempDir::new().unwrap();
+        let Some(keys) = generate_test_keypair(dir.path(), "mok") else {
+            return;
+        };
+        // sbsign only accepts PE images; without one the signing must fail cleanly
+        let image = dir.path().join("vmlinuz");
+        fs::write(&image, b"not a PE image").unwrap();
+        assert!(sign_kernel_image(&image, &keys).is_err());
+        assert!(!verify_kernel_image(&image, &keys.cert).unwrap());
+    }
+}
@@ -3,8 +3,28 @@
 //! Maps kernel variant names to their corresponding git repository URLs and raw PKGBUILD URLs.
 //! This module provides a centralized place to manage which remote sources
 //! are used when cloning kernel repositories and polling version information.
+//!
+//! Besides the built-in variants, users can register their own sources
+//! (`AppState::custom_sources`) with a branch/tag policy; these are merged in by
+//! [`KernelSourceDB::load`] and override built-ins of the same name.
 
+use crate::kernel::git::{self, GitError, GitManager, GitResult};
+use crate::models::{CustomKernelSource, SourceRef};
 use std::collections::HashMap;
+use std::path::Path;
+
+/// Placeholder in a PKGBUILD URL replaced by the resolved branch or tag
+pub const PKGBUILD_REF_PLACEHOLDER: &str = "{ref}";
+
+/// Variants offered in the Build tab, in display order (custom sources follow)
+pub const UI_VARIANTS: &[&str] = &[
+    "linux",
+    "linux-lts",
+    "linux-hardened",
+    "linux-mainline",
+    "linux-cachyos",
+    "linux-rt",
+];
 
 /// Kernel variant enumeration matching UI options
 #[derive(Debug, Clone, PartialEq, Eq)]
@@ -21,6 +41,12 @@ pub enum KernelVariant {
     Zen,
     /// TKG kernel variant (https://github.com/Frogging-Family/linux-tkg.git)
     Tkg,
+    /// CachyOS kernel variant (https://aur.archlinux.org/linux-cachyos.git)
+    CachyOs,
+    /// PREEMPT_RT kernel variant (https://aur.archlinux.org/linux-rt.git)
+    Rt,
+    /// User-registered source (see `KernelSourceDB::variant`)
+    Custom(String),
 }
 
 impl KernelVariant {
@@ -33,6 +59,8 @@ impl KernelVariant {
             "linux-hardened" => Some(KernelVariant::Hardened),
             "linux-zen" => Some(KernelVariant::Zen),
             "linux-tkg" => Some(KernelVariant::Tkg),
+            "linux-cachyos" => Some(KernelVariant::CachyOs),
+            "linux-rt" => Some(KernelVariant::Rt),
             _ => None,
         }
     }
@@ -46,6 +74,9 @@ impl KernelVariant {
             KernelVariant::Hardened => "linux-hardened",
             KernelVariant::Zen => "linux-zen",
             KernelVariant::Tkg => "linux-tkg",
+            KernelVariant::CachyOs => "linux-cachyos",
+            KernelVariant::Rt => "linux-rt",
+            KernelVariant::Custom(name) => name,
         }
     }
 }
@@ -55,13 +86,124 @@ impl KernelVariant {
 pub struct KernelSource {
     /// Git repository URL (for cloning)
     pub git_url: String,
-    /// Raw PKGBUILD URL (for version polling via reqwest)
+    /// Raw PKGBUILD URL (for version polling via reqwest), may contain `{ref}`
     pub pkgbuild_url: String,
+    /// Branch/tag policy applied when cloning and polling
+    pub reference: SourceRef,
+}
+
+impl KernelSource {
+    fn builtin(git_url: &str, pkgbuild_url: &str) -> Self {
+        KernelSource {
+            git_url: git_url.to_string(),
+            pkgbuild_url: pkgbuild_url.to_string(),
+            reference: SourceRef::DefaultBranch,
+        }
+    }
+
+    /// Resolve the ref policy to a branch or tag name (`None` = remote default branch)
+    ///
+    /// `LatestTag` lists the remote's tags, so this may touch the network.
+    pub fn resolve_ref(&self) -> GitResult<Option<String>> {
+        match &self.reference {
+            SourceRef::DefaultBranch => Ok(None),
+            SourceRef::Branch(name) | SourceRef::Tag(name) => Ok(Some(name.clone())),
+            SourceRef::LatestTag(prefix) => {
+                let tags = git::list_remote_tags(&self.git_url)?;
+                latest_tag(&tags, prefix).map(Some).ok_or_else(|| {
+                    GitError::RefNotFound(format!(
+                        "No tag starting with '{}' in {}",
+                        prefix, self.git_url
+                    ))
+                })
+            }
+        }
+    }
+
+    /// PKGBUILD URL with `{ref}` replaced by the resolved ref (`HEAD` for the default branch)
+    pub fn pkgbuild_url_for(&self, resolved_ref: Option<&str>) -> String {
+        self.pkgbuild_url
+            .replace(PKGBUILD_REF_PLACEHOLDER, resolved_ref.unwrap_or("HEAD"))
+    }
+}
+
+/// Newest tag starting with `prefix`, compared by the version number after the prefix
+pub fn latest_tag(tags: &[String], prefix: &str) -> Option<String> {
+    tags.iter()
+        .filter_map(|tag| tag.strip_prefix(prefix).map(|rest| (tag, rest)))
+        .max_by(|(a, a_rest), (b, b_rest)| {
+            git::extract_version_number(a_rest)
+                .cmp(&git::extract_version_number(b_rest))
+                .then_with(|| a.cmp(b))
+        })
+        .map(|(tag, _)| tag.clone())
+}
+
+/// Clone a kernel source into `target_path` and check out the ref its policy selects
+///
+/// The clone is shallow; a branch or tag other than the remote HEAD is fetched
+/// on top of it before checkout.
+pub fn clone_source(source: &KernelSource, target_path: &Path) -> GitResult<GitManager> {
+    let resolved = source.resolve_ref()?;
+    let manager = GitManager::clone(&source.git_url, target_path)?;
+
+    match (&source.reference, resolved) {
+        (SourceRef::Branch(branch), _) => {
+            manager.fetch_ref(&format!("+refs/heads/{0}:refs/remotes/origin/{0}", branch))?;
+            manager.checkout(&format!("origin/{}", branch))?;
+        }
+        (_, Some(tag)) => {
+            manager.fetch_ref(&format!("+refs/tags/{0}:refs/tags/{0}", tag))?;
+            manager.checkout(&tag)?;
+        }
+        (_, None) => {}
+    }
+
+    Ok(manager)
+}
+
+/// Check a user-registered source before it is saved or used
+///
+/// Names follow the Arch kernel package convention (`linux-<suffix>`, lowercase),
+/// since the name becomes the workspace directory and the rebranded package prefix.
+pub fn validate_custom_source(source: &CustomKernelSource) -> Result<(), String> {
+    let name = source.name.as_str();
+    let suffix = name.strip_prefix("linux-").unwrap_or("");
+    if suffix.is_empty()
+        || !name
+            .chars()
+            .all(|c| c.is_ascii_lowercase() || c.is_ascii_digit() || c == '-')
+    {
+        return Err(format!(
+            "Invalid variant name '{}': expected linux-<suffix> using a-z, 0-9 and '-'",
+            name
+        ));
+    }
+    if name.contains("-goatd-") || name.ends_with('-') {
+        return Err(format!("Invalid variant name '{}'", name));
+    }
+    if source.git_url.trim().is_empty() || source.git_url.contains(char::is_whitespace) {
+        return Err(format!("Source '{}' needs a git URL", name));
+    }
+    if !(source.pkgbuild_url.starts_with("https://") || source.pkgbuild_url.starts_with("http://"))
+    {
+        return Err(format!(
+            "Source '{}' needs an http(s) raw PKGBUILD URL for version polling",
+            name
+        ));
+    }
+    match &source.reference {
+        SourceRef::Branch(r) | SourceRef::Tag(r) if r.trim().is_empty() => {
+            Err(format!("Source '{}' has an empty branch/tag", name))
+        }
+        _ => Ok(()),
+    }
 }
 
 /// Kernel source URL database
 pub struct KernelSourceDB {
     sources: HashMap<String, KernelSource>,
+    custom: Vec<String>,
 }
 
 impl KernelSourceDB {
@@ -73,67 +215,148 @@ impl KernelSourceDB {
         // Points to the official Arch Linux packaging repository for the stable linux kernel
         sources.insert(
             "linux".to_string(),
-            KernelSource {
-                git_url: "https://gitlab.archlinux.org/archlinux/packaging/packages/linux.git".to_string(),
-                pkgbuild_url: "https://gitlab.archlinux.org/archlinux/packaging/packages/linux/-/raw/main/PKGBUILD".to_string(),
-            },
+            KernelSource::builtin(
+                "https://gitlab.archlinux.org/archlinux/packaging/packages/linux.git",
+                "https://gitlab.archlinux.org/archlinux/packaging/packages/linux/-/raw/main/PKGBUILD",
+            ),
         );
 
         // Long-term support kernel
         // Points to the official Arch Linux packaging repository for LTS kernel
         sources.insert(
             "linux-lts".to_string(),
-            KernelSource {
-                git_url: "https://gitlab.archlinux.org/archlinux/packaging/packages/linux-lts.git".to_string(),
-                pkgbuild_url: "https://gitlab.archlinux.org/archlinux/packaging/packages/linux-lts/-/raw/main/PKGBUILD".to_string(),
-            },
+            KernelSource::builtin(
+                "https://gitlab.archlinux.org/archlinux/packaging/packages/linux-lts.git",
+                "https://gitlab.archlinux.org/archlinux/packaging/packages/linux-lts/-/raw/main/PKGBUILD",
+            ),
         );
 
         // Hardened kernel variant
         // Points to the Arch Linux hardened kernel packaging
         sources.insert(
             "linux-hardened".to_string(),
-            KernelSource {
-                git_url: "https://gitlab.archlinux.org/archlinux/packaging/packages/linux-hardened.git".to_string(),
-                pkgbuild_url: "https://gitlab.archlinux.org/archlinux/packaging/packages/linux-hardened/-/raw/main/PKGBUILD".to_string(),
-            },
+            KernelSource::builtin(
+                "https://gitlab.archlinux.org/archlinux/packaging/packages/linux-hardened.git",
+                "https://gitlab.archlinux.org/archlinux/packaging/packages/linux-hardened/-/raw/main/PKGBUILD",
+            ),
         );
 
         // Zen kernel variant
         // Points to the official Arch Linux packaging repository for Zen kernel
         sources.insert(
             "linux-zen".to_string(),
-            KernelSource {
-                git_url: "https://gitlab.archlinux.org/archlinux/packaging/packages/linux-zen.git".to_string(),
-                pkgbuild_url: "https://gitlab.archlinux.org/archlinux/packaging/packages/linux-zen/-/raw/main/PKGBUILD".to_string(),
-            },
+            KernelSource::builtin(
+                "https://gitlab.archlinux.org/archlinux/packaging/packages/linux-zen.git",
+                "https://gitlab.archlinux.org/archlinux/packaging/packages/linux-zen/-/raw/main/PKGBUILD",
+            ),
         );
 
         // Mainline kernel
         // Points to the AUR maintainer's linux-mainline package
         sources.insert(
             "linux-mainline".to_string(),
-            KernelSource {
-                git_url: "https://aur.archlinux.org/linux-mainline.git".to_string(),
-                pkgbuild_url:
-                    "https://aur.archlinux.org/cgit/aur.git/plain/PKGBUILD?h=linux-mainline"
-                        .to_string(),
-            },
+            KernelSource::builtin(
+                "https://aur.archlinux.org/linux-mainline.git",
+                "https://aur.archlinux.org/cgit/aur.git/plain/PKGBUILD?h=linux-mainline",
+            ),
         );
 
         // TKG kernel variant (community-maintained build script repo)
         // Points to the Frogging-Family linux-tkg project on GitHub
         sources.insert(
             "linux-tkg".to_string(),
-            KernelSource {
-                git_url: "https://github.com/Frogging-Family/linux-tkg.git".to_string(),
-                pkgbuild_url:
-                    "https://raw.githubusercontent.com/Frogging-Family/linux-tkg/master/PKGBUILD"
-                        .to_string(),
-            },
+            KernelSource::builtin(
+                "https://github.com/Frogging-Family/linux-tkg.git",
+                "https://raw.githubusercontent.com/Frogging-Family/linux-tkg/master/PKGBUILD",
+            ),
         );
 
-        KernelSourceDB { sources }
+        // CachyOS kernel variant
+        // Points to the AUR package of the CachyOS kernel
+        sources.insert(
+            "linux-cachyos".to_string(),
+            KernelSource::builtin(
+                "https://aur.archlinux.org/linux-cachyos.git",
+                "https://aur.archlinux.org/cgit/aur.git/plain/PKGBUILD?h=linux-cachyos",
+            ),
+        );
+
+        // PREEMPT_RT kernel variant
+        // Points to the AUR package of the realtime kernel
+        sources.insert(
+            "linux-rt".to_string(),
+            KernelSource::builtin(
+                "https://aur.archlinux.org/linux-rt.git",
+                "https://aur.archlinux.org/cgit/aur.git/plain/PKGBUILD?h=linux-rt",
+            ),
+        );
+
+        KernelSourceDB {
+            sources,
+            custom: Vec::new(),
+        }
+    }
+
+    /// Built-in sources plus user-registered ones
+    ///
+    /// A custom source replaces a built-in of the same name; invalid entries are skipped.
+    pub fn with_custom_sources(custom: &[CustomKernelSource]) -> Self {
+        let mut db = Self::new();
+        for source in custom {
+            if let Err(e) = validate_custom_source(source) {
+                eprintln!("[Sources] [CUSTOM] Skipping source: {}", e);
+                continue;
+            }
+            db.sources.insert(
+                source.name.clone(),
+                KernelSource {
+                    git_url: source.git_url.trim().to_string(),
+                    pkgbuild_url: source.pkgbuild_url.trim().to_string(),
+                    reference: source.reference.clone(),
+                },
+            );
+            if !db.custom.contains(&source.name) {
+                db.custom.push(source.name.clone());
+            }
+        }
+        db
+    }
+
+    /// Built-in sources plus the custom sources persisted in settings
+    pub fn load() -> Self {
+        match crate::config::SettingsManager::load() {
+            Ok(state) => Self::with_custom_sources(&state.custom_sources),
+            Err(_) => Self::new(),
+        }
+    }
+
+    /// Get the full source entry for a variant
+    pub fn get_source(&self, variant: &str) -> Option<&KernelSource> {
+        self.sources.get(variant)
+    }
+
+    /// Resolve a variant name, including user-registered sources
+    pub fn variant(&self, name: &str) -> Option<KernelVariant> {
+        if self.is_custom(name) {
+            return Some(KernelVariant::Custom(name.to_string()));
+        }
+        KernelVariant::from_str(name).filter(|v| self.sources.contains_key(v.canonical_name()))
+    }
+
+    /// True if the variant comes from (or is overridden by) a user-registered source
+    pub fn is_custom(&self, variant: &str) -> bool {
+        self.custom.iter().any(|name| name == variant)
+    }
+
+    /// Variants for the Build tab: `UI_VARIANTS`, then custom sources in registration order
+    pub fn ui_variants(&self) -> Vec<String> {
+        let mut variants: Vec<String> = UI_VARIANTS.iter().map(|v| v.to_string()).collect();
+        for name in &self.custom {
+            if !variants.contains(name) {
+                variants.push(name.clone());
+            }
+        }
+        variants
     }
 
     /// Get the Git source URL for a given kernel variant
@@ -199,6 +422,11 @@ mod tests {
             KernelVariant::from_str("linux-tkg"),
             Some(KernelVariant::Tkg)
         );
+        assert_eq!(
+            KernelVariant::from_str("linux-cachyos"),
+            Some(KernelVariant::CachyOs)
+        );
+        assert_eq!(KernelVariant::from_str("linux-rt"), Some(KernelVariant::Rt));
         assert_eq!(KernelVariant::from_str("invalid"), None);
     }
 
@@ -293,4 +521,148 @@ mod tests {
         assert_eq!(db.get_source_url("linux-unknown"), None);
         assert_eq!(db.get_pkgbuild_url("linux-unknown"), None);
     }
+
+    fn custom(name: &str, reference: SourceRef) -> CustomKernelSource {
+        CustomKernelSource {
+            name: name.to_string(),
+            git_url: format!("https://git.example.com/{}.git", name),
+            pkgbuild_url: format!("https://git.example.com/{}/raw/{{ref}}/PKGBUILD", name),
+            reference,
+        }
+    }
+
+    #[test]
+    fn test_custom_sources_extend_and_override() {
+        let db = KernelSourceDB::with_custom_sources(&[
+            custom("linux-acme", SourceRef::Branch("lts-6.12".to_string())),
+            custom("linux-rt", SourceRef::LatestTag("v".to_string())),
+            custom("acme", SourceRef::DefaultBranch),
+        ]);
+
+        assert_eq!(
+            db.get_source_url("linux-acme"),
+            Some("https://git.example.com/linux-acme.git")
+        );
+        assert_eq!(
+            db.variant("linux-acme"),
+            Some(KernelVariant::Custom("linux-acme".to_string()))
+        );
+        // Overrides the built-in entry
+        assert_eq!(
+            db.get_source_url("linux-rt"),
+            Some("https://git.example.com/linux-rt.git")
+        );
+        assert!(db.is_custom("linux-rt") && !db.is_custom("linux"));
+        // Invalid names are skipped
+        assert_eq!(db.get_source_url("acme"), None);
+        assert_eq!(db.variant("linux-zen"), Some(KernelVariant::Zen));
+
+        let variants = db.ui_variants();
+        assert_eq!(&variants[..UI_VARIANTS.len()], UI_VARIANTS);
+        assert_eq!(variants.last().map(|v| v.as_str()), Some("linux-acme"));
+        assert_eq!(variants.iter().filter(|v| *v == "linux-rt").count(), 1);
+    }
+
+    #[test]
+    fn test_validate_custom_source() {
+        assert!(validate_custom_source(&custom("linux-acme", SourceRef::DefaultBranch)).is_ok());
+        assert!(validate_custom_source(&custom("linux-", SourceRef::DefaultBranch)).is_err());
+        assert!(validate_custom_source(&custom("linux-Acme", SourceRef::DefaultBranch)).is_err());
+        assert!(
+            validate_custom_source(&custom("linux-acme-goatd-x", SourceRef::DefaultBranch))
+                .is_err()
+        );
+        assert!(
+            validate_custom_source(&custom("linux-acme", SourceRef::Tag(String::new()))).is_err()
+        );
+
+        let mut no_pkgbuild = custom("linux-acme", SourceRef::DefaultBranch);
+        no_pkgbuild.pkgbuild_url.clear();
+        assert!(validate_custom_source(&no_pkgbuild).is_err());
+    }
+
+    #[test]
+    fn test_latest_tag_and_pkgbuild_url() {
+        let tags: Vec<String> = ["v6.6.50-rt42", "v6.12.3-rt5", "v6.12.10-rt9", "other-7.0"]
+            .iter()
+            .map(|t| t.to_string())
+            .collect();
+        assert_eq!(latest_tag(&tags, "v"), Some("v6.12.10-rt9".to_string()));
+        assert_eq!(latest_tag(&tags, "v6.6"), Some("v6.6.50-rt42".to_string()));
+        assert_eq!(latest_tag(&tags, "nope"), None);
+
+        let db = KernelSourceDB::with_custom_sources(&[custom(
+            "linux-acme",
+            SourceRef::Tag("v1".to_string()),
+        )]);
+        let source = db.get_source("linux-acme").unwrap();
+        assert_eq!(
+            source.pkgbuild_url_for(Some("v1")),
+            "https://git.example.com/linux-acme/raw/v1/PKGBUILD"
+        );
+        assert_eq!(
+            source.pkgbuild_url_for(None),
+            "https://git.example.com/linux-acme/raw/HEAD/PKGBUILD"
+        );
+    }
+
+    #[test]
+    fn test_clone_source_checks_out_policy_ref() {
+        use git2::{Repository, Signature};
+
+        let temp = tempfile::tempdir().unwrap();
+        let origin_path = temp.path().join("origin");
+        let origin = Repository::init(&origin_path).unwrap();
+        let sig = Signature::now("GOATd", "goatd@example.com").unwrap();
+        let commit = |content: &str, parent: Option<git2::Oid>| {
+            std::fs::write(origin_path.join("PKGBUILD"), content).unwrap();
+            let mut index = origin.index().unwrap();
+            index.add_path(Path::new("PKGBUILD")).unwrap();
+            let tree = origin.find_tree(index.write_tree().unwrap()).unwrap();
+            let parents: Vec<git2::Commit> = parent
+                .map(|oid| vec![origin.find_commit(oid).unwrap()])
+                .unwrap_or_default();
+            let parent_refs: Vec<&git2::Commit> = parents.iter().collect();
+            origin
+                .commit(Some("HEAD"), &sig, &sig, content, &tree, &parent_refs)
+                .unwrap()
+        };
+        let v1 = commit("pkgver=6.12.1\n", None);
+        origin
+            .tag_lightweight("v6.12.1", &origin.find_object(v1, None).unwrap(), false)
+            .unwrap();
+        let v2 = commit("pkgver=6.12.2\n", Some(v1));
+        origin
+            .tag_lightweight("v6.12.2", &origin.find_object(v2, None).unwrap(), false)
+            .unwrap();
+        commit("pkgver=6.13.0\n", Some(v2));
+
+        let url = origin_path.to_string_lossy().to_string();
+        let source = |reference| KernelSource {
+            git_url: url.clone(),
+            pkgbuild_url: String::new(),
+            reference,
+        };
+
+        let target = temp.path().join("tag");
+        clone_source(&source(SourceRef::Tag("v6.12.1".to_string())), &target).unwrap();
+        assert_eq!(
+            std::fs::read_to_string(target.join("PKGBUILD")).unwrap(),
+            "pkgver=6.12.1\n"
+        );
+
+        let target = temp.path().join("latest");
+        clone_source(&source(SourceRef::LatestTag("v6.12".to_string())), &target).unwrap();
+        assert_eq!(
+            std::fs::read_to_string(target.join("PKGBUILD")).unwrap(),
+            "pkgver=6.12.2\n"
+        );
+
+        let target = temp.path().join("head");
+        clone_source(&source(SourceRef::DefaultBranch), &target).unwrap();
+        assert_eq!(
+            std::fs::read_to_string(target.join("PKGBUILD")).unwrap(),
+            "pkgver=6.13.0\n"
+        );
+    }
 }
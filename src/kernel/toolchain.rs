@@ -0,0 +1,395 @@
+//! Toolchain probe: versions of clang, ld.lld, llvm-ar, rustc and gcc.
+//!
+//! Binaries are resolved in the same purified `PATH` the build uses
+//! ([`crate::system::purify_path`]), so an LLVM override directory
+//! (`<src>/.llvm_bin`) wins over the system toolchain. The probe produces a
+//! [`ToolchainFingerprint`] that is checked against the minimum versions of
+//! the kernel release being built, recorded in the `BuildResult`, and whose
+//! Clang version is fed to the patcher as `CONFIG_CLANG_VERSION`.
+
+use crate::kernel::compiler_cache::find_in_path;
+use crate::models::{ToolInfo, ToolchainFingerprint};
+use once_cell::sync::Lazy;
+use regex::Regex;
+use std::fmt;
+use std::path::Path;
+use std::process::Command;
+
+/// Derived config option carrying the probed `CONFIG_CLANG_VERSION` value.
+pub const CLANG_VERSION_KEY: &str = "_CLANG_VERSION";
+
+/// Name of the LLVM override directory inside the kernel source root.
+pub const LLVM_BIN_DIR_NAME: &str = ".llvm_bin";
+
+static VERSION_AFTER_KEYWORD: Lazy<Regex> =
+    Lazy::new(|| Regex::new(r"version\s+(\d+)\.(\d+)(?:\.(\d+))?").unwrap());
+static ANY_VERSION: Lazy<Regex> =
+    Lazy::new(|| Regex::new(r"\b(\d+)\.(\d+)(?:\.(\d+))?\b").unwrap());
+
+/// A `major.minor.patch` version.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub struct Version {
+    pub major: u32,
+    pub minor: u32,
+    pub patch: u32,
+}
+
+impl Version {
+    pub const fn new(major: u32, minor: u32, patch: u32) -> Self {
+        Version {
+            major,
+            minor,
+            patch,
+        }
+    }
+
+    /// Parse the first `X.Y[.Z]` in `text` (e.g. "19.1.7", "6.12-rc3").
+    pub fn parse(text: &str) -> Option<Self> {
+        Self::from_captures(ANY_VERSION.captures(text)?)
+    }
+
+    /// Parse a `--version` banner, preferring the number after "version".
+    ///
+    /// Handles `clang version 19.1.7`, `LLD 19.1.7 (compatible ...)`,
+    /// `LLVM version 19.1.7`, `rustc 1.82.0 (...)` and `gcc (GCC) 14.2.1 ...`.
+    pub fn parse_banner(output: &str) -> Option<Self> {
+        VERSION_AFTER_KEYWORD
+            .captures(output)
+            .and_then(Self::from_captures)
+            .or_else(|| Self::parse(output))
+    }
+
+    fn from_captures(caps: regex::Captures<'_>) -> Option<Self> {
+        let part = |i: usize| caps.get(i).map_or(Some(0), |m| m.as_str().parse().ok());
+        Some(Version::new(part(1)?, part(2)?, part(3)?))
+    }
+
+    /// Kconfig encoding used by `CONFIG_CLANG_VERSION` (19.1.7 -> 190107).
+    pub fn kconfig_number(&self) -> u32 {
+        self.major * 10000 + self.minor * 100 + self.patch
+    }
+}
+
+impl fmt::Display for Version {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}.{}.{}", self.major, self.minor, self.patch)
+    }
+}
+
+/// Tools in the fingerprint.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Tool {
+    Clang,
+    LdLld,
+    LlvmAr,
+    Rustc,
+    Gcc,
+}
+
+impl Tool {
+    pub const ALL: [Tool; 5] = [
+        Tool::Clang,
+        Tool::LdLld,
+        Tool::LlvmAr,
+        Tool::Rustc,
+        Tool::Gcc,
+    ];
+
+    /// Binary name looked up in the purified PATH.
+    pub fn binary(&self) -> &'static str {
+        match self {
+            Tool::Clang => "clang",
+            Tool::LdLld => "ld.lld",
+            Tool::LlvmAr => "llvm-ar",
+            Tool::Rustc => "rustc",
+            Tool::Gcc => "gcc",
+        }
+    }
+
+    /// Whether a failed check stops the build. The kernel is always built
+    /// with LLVM; rustc only matters for CONFIG_RUST and gcc is not used.
+    pub fn is_required(&self) -> bool {
+        matches!(self, Tool::Clang | Tool::LdLld | Tool::LlvmAr)
+    }
+
+    /// This tool's entry in `fingerprint`.
+    pub fn slot<'a>(&self, fingerprint: &'a ToolchainFingerprint) -> &'a Option<ToolInfo> {
+        match self {
+            Tool::Clang => &fingerprint.clang,
+            Tool::LdLld => &fingerprint.ld_lld,
+            Tool::LlvmAr => &fingerprint.llvm_ar,
+            Tool::Rustc => &fingerprint.rustc,
+            Tool::Gcc => &fingerprint.gcc,
+        }
+    }
+
+    fn slot_mut<'a>(&self, fingerprint: &'a mut ToolchainFingerprint) -> &'a mut Option<ToolInfo> {
+        match self {
+            Tool::Clang => &mut fingerprint.clang,
+            Tool::LdLld => &mut fingerprint.ld_lld,
+            Tool::LlvmAr => &mut fingerprint.llvm_ar,
+            Tool::Rustc => &mut fingerprint.rustc,
+            Tool::Gcc => &mut fingerprint.gcc,
+        }
+    }
+}
+
+/// Minimum tool versions by kernel release (Documentation/process/changes.rst).
+///
+/// Each row applies from `since` (major, minor) until a later row supersedes
+/// it. ld.lld and llvm-ar follow the LLVM minimum.
+const MINIMUM_VERSIONS: &[((u32, u32), Tool, Version)] = &[
+    ((5, 15), Tool::Clang, Version::new(10, 0, 1)),
+    ((5, 18), Tool::Clang, Version::new(11, 0, 0)),
+    ((6, 9), Tool::Clang, Version::new(13, 0, 1)),
+    ((6, 15), Tool::Clang, Version::new(15, 0, 0)),
+    ((5, 15), Tool::Gcc, Version::new(5, 1, 0)),
+    ((6, 15), Tool::Gcc, Version::new(8, 1, 0)),
+    ((6, 1), Tool::Rustc, Version::new(1, 62, 0)),
+    ((6, 11), Tool::Rustc, Version::new(1, 78, 0)),
+];
+
+/// Minimum version of `tool` for kernel `major.minor`, if one is known.
+pub fn minimum_version(tool: Tool, kernel: (u32, u32)) -> Option<Version> {
+    let table_tool = match tool {
+        Tool::LdLld | Tool::LlvmAr => Tool::Clang,
+        other => other,
+    };
+    MINIMUM_VERSIONS
+        .iter()
+        .filter(|(since, row_tool, _)| *row_tool == table_tool && *since <= kernel)
+        .max_by_key(|(since, _, _)| *since)
+        .map(|(_, _, min)| *min)
+}
+
+/// A toolchain problem found by [`check_minimums`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ToolchainIssue {
+    pub tool: Tool,
+    /// Whether this issue stops the build
+    pub fatal: bool,
+    pub message: String,
+}
+
+/// Probe every tool in the purified PATH built around `llvm_bin_override`.
+pub fn probe(llvm_bin_override: Option<&Path>) -> ToolchainFingerprint {
+    let search_path = crate::system::purify_path(llvm_bin_override);
+    let mut fingerprint = ToolchainFingerprint::default();
+    for tool in Tool::ALL {
+        *tool.slot_mut(&mut fingerprint) = probe_tool(tool.binary(), &search_path);
+    }
+    fingerprint
+}
+
+/// Probe the toolchain for a kernel source tree, honouring `<src>/.llvm_bin`.
+pub fn probe_for_source(src_dir: &Path) -> ToolchainFingerprint {
+    let llvm_bin = src_dir.join(LLVM_BIN_DIR_NAME);
+    probe(llvm_bin.is_dir().then_some(llvm_bin.as_path()))
+}
+
+fn probe_tool(name: &str, search_path: &str) -> Option<ToolInfo> {
+    let path = find_in_path(name, search_path)?;
+    let output = Command::new(&path)
+        .arg("--version")
+        .env("PATH", search_path)
+        .output()
+        .ok()?;
+    if !output.status.success() {
+        return None;
+    }
+    tool_info(
+        &path.to_string_lossy(),
+        &String::from_utf8_lossy(&output.stdout),
+    )
+}
+
+/// Build a [`ToolInfo`] from a binary path and its `--version` output.
+pub fn tool_info(path: &str, output: &str) -> Option<ToolInfo> {
+    let version = Version::parse_banner(output)?;
+    let banner = output.lines().find(|l| !l.trim().is_empty())?.trim();
+    Some(ToolInfo {
+        path: path.to_string(),
+        version: version.to_string(),
+        banner: banner.to_string(),
+    })
+}
+
+/// `CONFIG_CLANG_VERSION` value for the probed Clang, if any.
+pub fn clang_kconfig_version(fingerprint: &ToolchainFingerprint) -> Option<String> {
+    let clang = fingerprint.clang.as_ref()?;
+    Version::parse(&clang.version).map(|v| v.kconfig_number().to_string())
+}
+
+/// Check the fingerprint against the minimums of `kernel_version`.
+///
+/// Missing or too-old LLVM tools are fatal; rustc and gcc only warn. An
+/// unparseable kernel version (e.g. "latest") skips the minimum checks.
+pub fn check_minimums(
+    fingerprint: &ToolchainFingerprint,
+    kernel_version: &str,
+) -> Vec<ToolchainIssue> {
+    let kernel = Version::parse(kernel_version).map(|v| (v.major, v.minor));
+    let mut issues = Vec::new();
+
+    for tool in Tool::ALL {
+        let info = match tool.slot(fingerprint) {
+            Some(info) => info,
+            None => {
+                if tool.is_required() {
+                    issues.push(ToolchainIssue {
+                        tool,
+                        fatal: true,
+                        message: format!("{} not found in the build PATH", tool.binary()),
+                    });
+                }
+                continue;
+            }
+        };
+        let (Some(kernel), Some(found)) = (kernel, Version::parse(&info.version)) else {
+            continue;
+        };
+        if let Some(min) = minimum_version(tool, kernel).filter(|min| found < *min) {
+            issues.push(ToolchainIssue {
+                tool,
+                fatal: tool.is_required(),
+                message: format!(
+                    "{} {} is older than the minimum {} for Linux {}.{} ({})",
+                    tool.binary(),
+                    found,
+                    min,
+                    kernel.0,
+                    kernel.1,
+                    info.path
+                ),
+            });
+        }
+    }
+    issues
+}
+
+/// One-line summary of the fingerprint for the build log.
+pub fn summary(fingerprint: &ToolchainFingerprint) -> String {
+    Tool::ALL
+        .iter()
+        .map(|tool| match tool.slot(fingerprint) {
+            Some(info) => format!("{} {}", tool.binary(), info.version),
+            None => format!("{} -", tool.binary()),
+        })
+        .collect::<Vec<_>>()
+        .join(", ")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn info(version: &str) -> Option<ToolInfo> {
+        Some(ToolInfo {
+            path: "/usr/bin/tool".to_string(),
+            version: version.to_string(),
+            banner: String::new(),
+        })
+    }
+
+    #[test]
+    fn test_parse_banners() {
+        let cases = [
+            (
+                "clang version 19.1.7\nTarget: x86_64-pc-linux-gnu",
+                "19.1.7",
+            ),
+            ("Ubuntu clang version 18.1.3 (1ubuntu1)", "18.1.3"),
+            ("LLD 19.1.7 (compatible with GNU linkers)", "19.1.7"),
+            (
+                "LLVM (http://llvm.org/):\n  LLVM version 20.1.0\n",
+                "20.1.0",
+            ),
+            ("rustc 1.82.0 (f6e511eec 2024-10-15)", "1.82.0"),
+            ("gcc (GCC) 14.2.1 20240910", "14.2.1"),
+        ];
+        for (banner, expected) in cases {
+            let version = Version::parse_banner(banner).unwrap();
+            assert_eq!(version.to_string(), expected, "banner: {}", banner);
+        }
+        assert_eq!(Version::parse("6.12-rc3"), Some(Version::new(6, 12, 0)));
+        assert_eq!(Version::parse_banner("no digits here"), None);
+    }
+
+    #[test]
+    fn test_clang_kconfig_version() {
+        assert_eq!(Version::new(19, 1, 7).kconfig_number(), 190107);
+        let fingerprint = ToolchainFingerprint {
+            clang: tool_info("/opt/llvm/bin/clang", "clang version 18.1.8\n"),
+            ..Default::default()
+        };
+        assert_eq!(
+            clang_kconfig_version(&fingerprint).as_deref(),
+            Some("180108")
+        );
+        assert_eq!(
+            clang_kconfig_version(&ToolchainFingerprint::default()),
+            None
+        );
+    }
+
+    #[test]
+    fn test_minimum_version_by_release() {
+        assert_eq!(
+            minimum_version(Tool::Clang, (6, 6)),
+            Some(Version::new(11, 0, 0))
+        );
+        assert_eq!(
+            minimum_version(Tool::LdLld, (6, 15)),
+            Some(Version::new(15, 0, 0))
+        );
+        assert_eq!(
+            minimum_version(Tool::Rustc, (6, 12)),
+            Some(Version::new(1, 78, 0))
+        );
+        assert_eq!(minimum_version(Tool::Clang, (4, 19)), None);
+    }
+
+    #[test]
+    fn test_check_minimums() {
+        let fingerprint = ToolchainFingerprint {
+            clang: info("14.0.6"),
+            ld_lld: info("19.1.7"),
+            llvm_ar: None,
+            rustc: info("1.75.0"),
+            gcc: info("14.2.1"),
+        };
+
+        let issues = check_minimums(&fingerprint, "6.16.1");
+        let fatal: Vec<Tool> = issues.iter().filter(|i| i.fatal).map(|i| i.tool).collect();
+        assert_eq!(fatal, vec![Tool::Clang, Tool::LlvmAr]);
+        assert!(issues.iter().any(|i| i.tool == Tool::Rustc && !i.fatal));
+
+        // Clang 14 is fine for 6.6; the missing llvm-ar is still fatal
+        let issues = check_minimums(&fingerprint, "6.6.50");
+        assert!(!issues.iter().any(|i| i.tool == Tool::Clang));
+        assert!(issues.iter().any(|i| i.tool == Tool::LlvmAr && i.fatal));
+    }
+
+    #[test]
+    fn test_probe_prefers_llvm_override() {
+        use std::os::unix::fs::PermissionsExt;
+
+        let dir = tempfile::tempdir().unwrap();
+        let clang = dir.path().join("clang");
+        std::fs::write(&clang, "#!/bin/sh\necho 'clang version 42.0.1'\n").unwrap();
+        std::fs::set_permissions(&clang, std::fs::Permissions::from_mode(0o755)).unwrap();
+
+        let fingerprint = probe(Some(dir.path()));
+        let clang_info = fingerprint.clang.expect("override clang probed");
+        assert_eq!(clang_info.version, "42.0.1");
+        assert_eq!(clang_info.path, clang.to_string_lossy());
+        assert_eq!(
+            clang_kconfig_version(&ToolchainFingerprint {
+                clang: Some(clang_info),
+                ..Default::default()
+            })
+            .as_deref(),
+            Some("420001")
+        );
+    }
+}
@@ -5,12 +5,14 @@
 //! - Makefiles have correct syntax
 //! - Configuration options are properly set
 //! - LTO shielding is correctly applied
+//! - Secure Boot signatures are present in the produced package
 
 use crate::error::AppError;
 use crate::error::PatchError;
+use crate::kernel::signing;
 use regex::Regex;
 use std::fs;
-use std::path::Path;
+use std::path::{Path, PathBuf};
 
 /// Result type for validation operations
 pub type ValidationResult<T> = std::result::Result<T, PatchError>;
@@ -339,6 +341,133 @@ pub fn validate_pkgbuild_syntax(pkgbuild_path: &Path) -> ValidationResult<()> {
     }
 }
 
+/// Secure Boot signature status of a built kernel package.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct SignatureReport {
+    /// Kernel image found in the package (`usr/lib/modules/<release>/vmlinuz`)
+    pub image: Option<PathBuf>,
+    /// Whether the image verifies against the MOK certificate
+    pub image_signed: bool,
+    /// Number of modules whose signature verifies against the MOK certificate
+    pub modules_signed: usize,
+    /// Modules unsigned or signed by another key (paths relative to the package root)
+    pub unsigned_modules: Vec<PathBuf>,
+}
+
+impl SignatureReport {
+    /// Whether the image and every module are signed.
+    pub fn is_fully_signed(&self) -> bool {
+        self.image.is_some() && self.image_signed && self.unsigned_modules.is_empty()
+    }
+}
+
+/// Verify Secure Boot signatures inside a built kernel package.
+///
+/// The package's `usr/lib/modules` tree is extracted with `bsdtar` into a
+/// temporary directory, then checked with [`verify_tree_signatures`].
+///
+/// # Arguments
+///
+/// * `package_path` - Path to the `.pkg.tar.zst` kernel package
+/// * `cert` - MOK certificate the image must verify against
+pub fn verify_package_signatures(
+    package_path: &Path,
+    cert: &Path,
+) -> std::result::Result<SignatureReport, AppError> {
+    use std::process::Command;
+
+    if !package_path.is_file() {
+        return Err(AppError::InvalidPath(format!(
+            "Package not found: {}",
+            package_path.display()
+        )));
+    }
+
+    let extract_dir = tempfile::TempDir::new()?;
+    let output = Command::new("bsdtar")
+        .arg("-xf")
+        .arg(package_path)
+        .arg("-C")
+        .arg(extract_dir.path())
+        .arg("usr/lib/modules")
+        .output()
+        .map_err(|e| AppError::OsCommand {
+            cmd: "bsdtar -xf".to_string(),
+            reason: e.to_string(),
+        })?;
+
+    if !output.status.success() {
+        return Err(AppError::OsCommand {
+            cmd: format!("bsdtar -xf {}", package_path.display()),
+            reason: String::from_utf8_lossy(&output.stderr).trim().to_string(),
+        });
+    }
+
+    eprintln!(
+        "[Validator] [SECURE-BOOT] Checking signatures in {}",
+        package_path.display()
+    );
+    verify_tree_signatures(extract_dir.path(), cert)
+}
+
+/// Verify Secure Boot signatures in an installed or extracted kernel tree.
+///
+/// Every `vmlinuz` below `root` is checked with `sbverify` and every module
+/// (`.ko`, `.ko.gz`, `.ko.zst`, `.ko.xz`) for an appended PKCS#7 signature
+/// made with the MOK.
+pub fn verify_tree_signatures(
+    root: &Path,
+    cert: &Path,
+) -> std::result::Result<SignatureReport, AppError> {
+    let mut files = Vec::new();
+    collect_files(root, &mut files)?;
+    files.sort();
+
+    let mut report = SignatureReport::default();
+    for file in files {
+        let relative = file.strip_prefix(root).unwrap_or(&file).to_path_buf();
+
+        if file.file_name().is_some_and(|name| name == "vmlinuz") {
+            let signed = signing::verify_kernel_image(&file, cert)?;
+            eprintln!(
+                "[Validator] [SECURE-BOOT] {} kernel image {}",
+                if signed { "✓" } else { "✗" },
+                relative.display()
+            );
+            // With several images in the tree, all of them must be signed
+            report.image_signed = signed && (report.image.is_none() || report.image_signed);
+            report.image = Some(relative);
+        } else if signing::is_module_file(&file) {
+            if signing::is_module_signed_by(&file, cert)? {
+                report.modules_signed += 1;
+            } else {
+                report.unsigned_modules.push(relative);
+            }
+        }
+    }
+
+    eprintln!(
+        "[Validator] [SECURE-BOOT] Modules: {} signed, {} unsigned",
+        report.modules_signed,
+        report.unsigned_modules.len()
+    );
+
+    Ok(report)
+}
+
+fn collect_files(dir: &Path, files: &mut Vec<PathBuf>) -> std::result::Result<(), AppError> {
+    for entry in fs::read_dir(dir)? {
+        let entry = entry?;
+        let file_type = entry.file_type()?;
+        if file_type.is_dir() {
+            collect_files(&entry.path(), files)?;
+        } else if file_type.is_file() {
+            files.push(entry.path());
+        }
+    }
+    Ok(())
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -623,4 +752,93 @@ mod tests {
             assert!(err.to_string().contains("colons"));
         }
     }
+
+    // ======= SECURE BOOT SIGNATURE TESTS
+
+    fn write_module(path: &Path, signer: Option<&signing::MokKeyPair>) {
+        fs::create_dir_all(path.parent().unwrap()).unwrap();
+        let body = b"\x7fELF module";
+        let data = match signer {
+            Some(keys) => signing::tests::sign_module_bytes(body, keys).unwrap(),
+            None => body.to_vec(),
+        };
+        fs::write(path, data).unwrap();
+    }
+
+    /// Throwaway MOK plus a second, unenrolled key, or `None` without openssl.
+    fn test_keys(dir: &Path) -> Option<(signing::MokKeyPair, signing::MokKeyPair)> {
+        Some((
+            signing::tests::generate_test_keypair(dir, "mok")?,
+            signing::tests::generate_test_keypair(dir, "other")?,
+        ))
+    }
+
+    #[test]
+    fn test_verify_tree_signatures_reports_unsigned_modules() {
+        let temp_dir = TempDir::new().unwrap();
+        let Some((mok, other)) = test_keys(temp_dir.path()) else {
+            eprintln!("openssl unavailable, skipping");
+            return;
+        };
+        let root = temp_dir.path().join("pkg");
+        let modules = root.join("usr/lib/modules/6.12.1-goatd/kernel");
+        write_module(&modules.join("drivers/nvme.ko"), Some(&mok));
+        write_module(&modules.join("fs/btrfs.ko"), Some(&mok));
+        write_module(&modules.join("net/wireguard.ko"), None);
+        write_module(&modules.join("net/tun.ko"), Some(&other));
+
+        let report = verify_tree_signatures(&root, &mok.cert).unwrap();
+        assert_eq!(report.modules_signed, 2);
+        assert_eq!(
+            report.unsigned_modules,
+            vec![
+                PathBuf::from("usr/lib/modules/6.12.1-goatd/kernel/net/tun.ko"),
+                PathBuf::from("usr/lib/modules/6.12.1-goatd/kernel/net/wireguard.ko"),
+            ]
+        );
+        // No image in the tree: never reported as fully signed
+        assert!(report.image.is_none());
+        assert!(!report.is_fully_signed());
+    }
+
+    #[test]
+    fn test_verify_package_signatures_extracts_modules() {
+        use std::process::Command;
+
+        let temp_dir = TempDir::new().unwrap();
+        let Some((mok, _)) = test_keys(temp_dir.path()) else {
+            eprintln!("openssl unavailable, skipping");
+            return;
+        };
+        let root = temp_dir.path().join("pkg");
+        let modules = root.join("usr/lib/modules/6.12.1-goatd/kernel");
+        write_module(&modules.join("drivers/nvme.ko"), Some(&mok));
+        write_module(&modules.join("drivers/e1000e.ko"), None);
+
+        let package = temp_dir.path().join("linux-goatd-6.12.1-1-x86_64.pkg.tar");
+        let packed = Command::new("bsdtar")
+            .arg("-cf")
+            .arg(&package)
+            .arg("-C")
+            .arg(&root)
+            .arg("usr")
+            .status();
+        if !packed.map(|s| s.success()).unwrap_or(false) {
+            eprintln!("bsdtar unavailable, skipping");
+            return;
+        }
+
+        let report = verify_package_signatures(&package, &mok.cert).unwrap();
+        assert_eq!(report.modules_signed, 1);
+        assert_eq!(report.unsigned_modules.len(), 1);
+    }
+
+    #[test]
+    fn test_verify_package_signatures_missing_package() {
+        let result = verify_package_signatures(
+            Path::new("/nonexistent/linux.pkg.tar.zst"),
+            Path::new("/nonexistent/mok.crt"),
+        );
+        assert!(matches!(result, Err(AppError::InvalidPath(_))));
+    }
 }
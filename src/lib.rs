@@ -18,6 +18,7 @@
 //! - **orchestrator**: Async build coordination and state management (Phases 1-5)
 //! - **kernel**: Kernel management (package management, audit) (Phases 1-3)
 //! - **validator**: Build validation utilities (Phase 5)
+//! - **cli**: Headless `goatd` command-line front-end
 
 #![allow(dead_code)]
 
@@ -58,6 +59,9 @@ pub use log_collector::{LogCollector, LogLine};
 // Phase 1-3: Kernel management module (package management, audit)
 pub mod kernel;
 
+// Headless command-line front-end (`goatd` binary)
+pub mod cli;
+
 // ============================================================================
 // PUBLIC RE-EXPORTS FOR CONVENIENCE
 // ============================================================================
@@ -95,6 +95,9 @@ async fn main() -> goatd_kernel::Result<()> {
     .await;
     let controller = Arc::new(RwLock::new(controller));
 
+    // Process queued builds (restored from the previous session) in the background
+    goatd_kernel::ui::build::spawn_build_queue_runner(controller.clone());
+
     // =========================================================================
     // HARDWARE DETECTION - BACKGROUND TASK FOR STARTUP OPTIMIZATION
     // =========================================================================
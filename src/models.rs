@@ -139,6 +139,65 @@ impl Default for HardwareContext {
     }
 }
 
+/// Core type of a logical CPU on hybrid processors.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum CoreType {
+    /// Performance core (Intel `cpu_core` PMU)
+    Performance,
+    /// Efficiency core (Intel `cpu_atom` PMU)
+    Efficiency,
+    /// Core of a non-hybrid processor
+    Standard,
+}
+
+impl CoreType {
+    /// Short cell label prefix, e.g. "P" for "P3".
+    pub fn short_label(&self) -> &'static str {
+        match self {
+            CoreType::Performance => "P",
+            CoreType::Efficiency => "E",
+            CoreType::Standard => "CPU ",
+        }
+    }
+
+    /// Display label.
+    pub fn label(&self) -> &'static str {
+        match self {
+            CoreType::Performance => "P-cores",
+            CoreType::Efficiency => "E-cores",
+            CoreType::Standard => "Cores",
+        }
+    }
+}
+
+/// Which CPUs a latency monitoring session measures.
+#[derive(Clone, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum CpuSelection {
+    /// Only `PerformanceConfig::core_id`
+    #[default]
+    Single,
+    /// An explicit list of CPUs
+    Cpus(Vec<usize>),
+    /// Every online CPU of one core type
+    CoreType(CoreType),
+    /// Every online CPU
+    All,
+}
+
+impl CpuSelection {
+    /// Display label.
+    pub fn label(&self) -> String {
+        match self {
+            CpuSelection::Single => "Single core".to_string(),
+            CpuSelection::Cpus(cpus) => format!("{} CPUs", cpus.len()),
+            CpuSelection::CoreType(core_type) => core_type.label().to_string(),
+            CpuSelection::All => "All cores".to_string(),
+        }
+    }
+}
+
 /// Storage type.
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
 pub enum StorageType {
@@ -163,6 +222,202 @@ pub enum LtoType {
     Full,
 }
 
+/// Compiler cache backend.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum CompilerCache {
+    #[default]
+    None,
+    Ccache,
+    Sccache,
+}
+
+impl CompilerCache {
+    /// Executable name of the cache wrapper (None for no cache).
+    pub fn binary(&self) -> Option<&'static str> {
+        match self {
+            CompilerCache::None => None,
+            CompilerCache::Ccache => Some("ccache"),
+            CompilerCache::Sccache => Some("sccache"),
+        }
+    }
+}
+
+/// Compiler cache settings.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct CompilerCacheConfig {
+    #[serde(default)]
+    pub backend: CompilerCache, // Backend
+    #[serde(default)]
+    pub dir: Option<PathBuf>, // Cache dir (backend default if None)
+    #[serde(default)]
+    pub max_size: Option<String>, // Size limit, e.g. "20G"
+}
+
+impl CompilerCacheConfig {
+    /// True if a cache backend is selected.
+    pub fn is_enabled(&self) -> bool {
+        self.backend != CompilerCache::None
+    }
+}
+
+/// Stage of the two-stage profile-guided optimization workflow.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum PgoStage {
+    #[default]
+    Off,
+    Instrument, // Stage 1: build a kernel that can be profiled
+    Optimize,   // Stage 2: rebuild consuming the merged profile
+}
+
+impl PgoStage {
+    /// Lowercase name, as used in MPL metadata and on the command line.
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            PgoStage::Off => "off",
+            PgoStage::Instrument => "instrument",
+            PgoStage::Optimize => "optimize",
+        }
+    }
+}
+
+/// How the kernel profile is collected.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum PgoMethod {
+    #[default]
+    AutoFdo, // perf branch sampling (CONFIG_AUTOFDO_CLANG, Linux 6.13+)
+    Instrumented, // Clang instrumentation (CONFIG_PGO_CLANG, needs the PGO patch)
+}
+
+impl PgoMethod {
+    /// Lowercase name, as used in MPL metadata and on the command line.
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            PgoMethod::AutoFdo => "autofdo",
+            PgoMethod::Instrumented => "instrumented",
+        }
+    }
+}
+
+/// Profile-guided optimization settings.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct PgoConfig {
+    #[serde(default)]
+    pub stage: PgoStage, // Off / instrument / optimize
+    #[serde(default)]
+    pub method: PgoMethod, // AutoFDO or instrumented
+    #[serde(default)]
+    pub profile: Option<PathBuf>, // Merged profile (workspace profile for the version if None)
+}
+
+/// Resource limits applied to the build process (see `orchestrator::governor`).
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct BuildResourceLimits {
+    #[serde(default)]
+    pub nice: Option<i32>, // nice level for make and its children (None = unchanged)
+    #[serde(default)]
+    pub ionice_idle: bool, // Run with the idle I/O scheduling class
+    #[serde(default)]
+    pub cgroup: bool, // Run inside a transient cgroup v2 scope
+    #[serde(default)]
+    pub cpu_quota_percent: Option<u32>, // cgroup CPUQuota (100 = one CPU)
+    #[serde(default)]
+    pub memory_max_mb: Option<u64>, // cgroup MemoryMax in MiB
+    #[serde(default)]
+    pub thermal_limit_c: Option<f32>, // Throttle the build above this package temperature
+}
+
+/// Which ref of a kernel source repository is built.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum SourceRef {
+    #[default]
+    DefaultBranch, // Remote HEAD
+    Branch(String),    // Named branch
+    Tag(String),       // Fixed tag
+    LatestTag(String), // Newest tag starting with this prefix ("" = any tag)
+}
+
+/// User-registered kernel source, persisted in settings.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct CustomKernelSource {
+    pub name: String, // Variant name, also the workspace directory (e.g. "linux-acme")
+    pub git_url: String, // Repository holding the PKGBUILD
+    #[serde(default)]
+    pub pkgbuild_url: String, // Raw PKGBUILD for version polling; "{ref}" expands to the resolved ref
+    #[serde(default)]
+    pub reference: SourceRef, // Branch/tag policy
+}
+
+/// Compiler cache statistics for one build.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct CompilerCacheStats {
+    pub backend: CompilerCache, // Backend
+    pub hits: u64,              // Cache hits
+    pub misses: u64,            // Cache misses
+    pub size_bytes: u64,        // Cache size after build
+}
+
+impl CompilerCacheStats {
+    /// Hit rate in percent (0.0 if nothing was compiled).
+    pub fn hit_rate(&self) -> f64 {
+        let total = self.hits + self.misses;
+        if total == 0 {
+            0.0
+        } else {
+            self.hits as f64 * 100.0 / total as f64
+        }
+    }
+
+    /// One-line summary for the build log.
+    pub fn summary(&self) -> String {
+        format!(
+            "{}: {} hits / {} misses ({:.1}% hit rate), cache size {:.1} MiB",
+            self.backend.binary().unwrap_or("none"),
+            self.hits,
+            self.misses,
+            self.hit_rate(),
+            self.size_bytes as f64 / (1024.0 * 1024.0)
+        )
+    }
+}
+
+/// One probed toolchain binary.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ToolInfo {
+    pub path: String,    // Resolved binary
+    pub version: String, // Parsed version (e.g. 19.1.7)
+    pub banner: String,  // First line of --version
+}
+
+/// Versions of the toolchain a kernel was built with.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ToolchainFingerprint {
+    #[serde(default)]
+    pub clang: Option<ToolInfo>, // C compiler
+    #[serde(default)]
+    pub ld_lld: Option<ToolInfo>, // Linker
+    #[serde(default)]
+    pub llvm_ar: Option<ToolInfo>, // Archiver
+    #[serde(default)]
+    pub rustc: Option<ToolInfo>, // Rust compiler (CONFIG_RUST)
+    #[serde(default)]
+    pub gcc: Option<ToolInfo>, // GCC (not used for the kernel itself)
+}
+
+/// Kernel command line customization on top of the profile defaults.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct KernelCmdlineConfig {
+    #[serde(default)]
+    pub additions: Vec<String>, // Parameters to add (override profile values with the same key)
+    #[serde(default)]
+    pub removals: Vec<String>, // Keys (`preempt`) or exact parameters (`preempt=full`) to drop
+    #[serde(default)]
+    pub boot_entry: bool, // Also write the parameters into the bootloader entry
+}
+
 /// Build phase.
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
 pub enum BuildPhase {
@@ -290,6 +545,22 @@ pub struct KernelConfig {
     pub native_optimizations: bool,              // Enable -march=native
     pub user_toggled_native_optimizations: bool, // User manually toggled native optimizations
     pub kernel_variant: String,                  // Kernel variant
+    #[serde(default)]
+    pub mok_key_path: Option<PathBuf>, // MOK private key used for Secure Boot signing
+    #[serde(default)]
+    pub mok_cert_path: Option<PathBuf>, // MOK certificate (PEM) used for Secure Boot signing
+    #[serde(default)]
+    pub compiler_cache: CompilerCacheConfig, // ccache/sccache settings
+    #[serde(default)]
+    pub cmdline: KernelCmdlineConfig, // Kernel command line additions/removals
+    #[serde(default)]
+    pub pgo: PgoConfig, // Profile-guided optimization stage and profile
+    #[serde(default)]
+    pub build_jobs: Option<usize>, // make -j for this build (None = all CPUs)
+    #[serde(default)]
+    pub resources: BuildResourceLimits, // nice/ionice, cgroup limits and thermal throttling
+    #[serde(default)]
+    pub strict_kconfig: bool, // Fail the build when Kconfig would drop a requested option
 }
 
 impl Default for KernelConfig {
@@ -323,6 +594,14 @@ impl Default for KernelConfig {
             native_optimizations: true,               // Default: native optimizations enabled
             user_toggled_native_optimizations: false, // Not manually toggled by default
             kernel_variant: String::new(),            // Default: empty kernel variant
+            mok_key_path: None,                       // No MOK key configured by default
+            mok_cert_path: None,                      // No MOK certificate configured by default
+            compiler_cache: CompilerCacheConfig::default(), // No compiler cache by default
+            cmdline: KernelCmdlineConfig::default(),        // Profile command line only
+            pgo: PgoConfig::default(),                      // No PGO by default
+            build_jobs: None,                               // Use every CPU
+            resources: BuildResourceLimits::default(),      // No resource limits by default
+            strict_kconfig: false,                          // Report dropped options only
         }
     }
 }
@@ -390,6 +669,10 @@ pub struct BuildResult {
     pub lto_enabled: bool,         // LTO?
     pub patches_applied: u32,      // # patches
     pub error_msg: Option<String>, // Error
+    #[serde(default)]
+    pub compiler_cache: Option<CompilerCacheStats>, // ccache/sccache stats
+    #[serde(default)]
+    pub toolchain: Option<ToolchainFingerprint>, // Probed toolchain versions
 }
 
 /// Kernel info.
@@ -517,6 +800,19 @@ pub struct MPLMetadata {
 
     /// Profile suffix for LOCALVERSION (e.g., "-goatd-gaming")
     pub profile_suffix: String,
+
+    /// User patch queue applied to the source, in order (`<name>@<sha256 prefix>`)
+    #[serde(default)]
+    pub applied_patches: Vec<String>,
+
+    /// PGO stage of this build ("off", "instrument" or "optimize")
+    #[serde(default)]
+    pub pgo_stage: String,
+
+    /// Merged PGO profile consumed by an "optimize" build, or the workspace
+    /// directory an "instrument" build's profiles are collected into
+    #[serde(default)]
+    pub pgo_profile: String,
 }
 
 impl MPLMetadata {
@@ -550,9 +846,17 @@ impl MPLMetadata {
             pkgver: kernel_version,
             pkgrel: "1".to_string(),
             profile_suffix,
+            applied_patches: Vec::new(),
+            pgo_stage: String::new(),
+            pgo_profile: String::new(),
         }
     }
 
+    /// Read MPL metadata from a shell-format file
+    pub fn read_from_file(path: &std::path::Path) -> std::io::Result<Self> {
+        Self::from_shell_format(&std::fs::read_to_string(path)?)
+    }
+
     /// Write MPL metadata to file as shell-sourceable format
     pub fn write_to_file(&self, path: &std::path::Path) -> std::io::Result<()> {
         let content = self.to_shell_format();
@@ -578,6 +882,9 @@ GOATD_SOURCE_DIR="{}"
 GOATD_PKGVER="{}"
 GOATD_PKGREL="{}"
 GOATD_PROFILE_SUFFIX="{}"
+GOATD_APPLIED_PATCHES="{}"
+GOATD_PGO_STAGE="{}"
+GOATD_PGO_PROFILE="{}"
 "#,
             self.build_timestamp,
             self.build_id,
@@ -593,6 +900,9 @@ GOATD_PROFILE_SUFFIX="{}"
             self.pkgver,
             self.pkgrel,
             self.profile_suffix,
+            self.applied_patches.join(" "),
+            self.pgo_stage,
+            self.pgo_profile,
         )
     }
 
@@ -625,6 +935,15 @@ GOATD_PROFILE_SUFFIX="{}"
                 metadata.pkgrel = extract_value(line);
             } else if line.starts_with("GOATD_PROFILE_SUFFIX=") {
                 metadata.profile_suffix = extract_value(line);
+            } else if line.starts_with("GOATD_APPLIED_PATCHES=") {
+                metadata.applied_patches = extract_value(line)
+                    .split_whitespace()
+                    .map(|s| s.to_string())
+                    .collect();
+            } else if line.starts_with("GOATD_PGO_STAGE=") {
+                metadata.pgo_stage = extract_value(line);
+            } else if line.starts_with("GOATD_PGO_PROFILE=") {
+                metadata.pgo_profile = extract_value(line);
             }
         }
 
@@ -647,6 +966,9 @@ impl Default for MPLMetadata {
             pkgver: String::new(),
             pkgrel: "1".to_string(),
             profile_suffix: String::new(),
+            applied_patches: Vec::new(),
+            pgo_stage: String::new(),
+            pgo_profile: String::new(),
         }
     }
 }
@@ -726,6 +1048,14 @@ mod tests {
             native_optimizations: true,
             user_toggled_native_optimizations: false,
             kernel_variant: String::new(),
+            mok_key_path: None,
+            mok_cert_path: None,
+            compiler_cache: Default::default(),
+            cmdline: Default::default(),
+            pgo: Default::default(),
+            build_jobs: None,
+            resources: Default::default(),
+            strict_kconfig: false,
         };
         assert_eq!(config.lto_type, LtoType::Thin);
         assert_eq!(config.hardening, HardeningLevel::Standard);
@@ -1,21 +1,437 @@
-//! Checkpoint data structures for build state serialization.
+//! Build checkpoint persistence and resume support.
 //!
-//! **IMPORTANT**: This module contains ONLY DATA STRUCTURES.
+//! After every successful phase transition the orchestrator writes a
+//! [`BuildCheckpoint`] to `<checkpoint_dir>/build_checkpoint.json`. A checkpoint
+//! holds the full [`OrchestrationState`] (including the finalized, version-resolved
+//! `KernelConfig`) together with a fingerprint of the files the Patching phase
+//! writes (PKGBUILD, kernel config files, patches) and of the kernel tree makepkg
+//! extracted and prepared below `src/`. A build that fails refreshes the
+//! fingerprint so it covers the tree the build left behind.
 //!
-//! CheckpointManager and checkpoint persistence logic is now in Python
-//! (src/core/build_orchestrator.py). This module remains for potential
-//! serialization support if needed.
-//!
-//! Python handles:
-//! - Saving checkpoints to JSON
-//! - Loading checkpoints from storage
-//! - Managing checkpoint directory
-//! - State recovery on resume
+//! `AsyncOrchestrator::resume()` loads a checkpoint, verifies that the source tree
+//! still matches the recorded fingerprint and then continues from the phase that
+//! was about to run, so a build that dies in `Building` does not have to re-clone
+//! and re-patch the sources, and continues compiling in the prepared tree.
+
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::time::SystemTime;
+
+use super::state::{BuildPhaseState, OrchestrationState};
+use crate::error::Result;
+
+/// File name of the checkpoint inside the checkpoint directory.
+pub const CHECKPOINT_FILE_NAME: &str = "build_checkpoint.json";
+
+/// Checkpoint format version, bumped on incompatible layout changes.
+pub const CHECKPOINT_FORMAT_VERSION: u32 = 1;
+
+/// Files above this size are fingerprinted by size and mtime instead of content.
+const CONTENT_HASH_LIMIT: u64 = 1024 * 1024;
+
+/// Directory makepkg extracts and prepares the sources in.
+const EXTRACTED_SOURCE_DIR: &str = "src";
+
+/// File name suffixes of kbuild outputs inside the extracted tree.
+const BUILD_OUTPUT_SUFFIXES: &[&str] = &[
+    ".o", ".ko", ".a", ".mod", ".mod.c", ".cmd", ".d", ".order", ".symvers", ".tmp", ".lds",
+];
+
+/// Directories inside the extracted tree that only hold generated files.
+const BUILD_OUTPUT_DIRS: &[&str] = &[".git", ".tmp_versions", "generated"];
 
-// Note: This module is kept minimal since Python now handles checkpointing.
-// BuildState serialization is handled via serde in models.rs
+/// A persisted snapshot of an in-flight build.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct BuildCheckpoint {
+    /// Checkpoint format version
+    pub format_version: u32,
+
+    /// Orchestration state at the time of the checkpoint.
+    /// `state.phase` is the next phase to run.
+    pub state: OrchestrationState,
+
+    /// Kernel source directory the checkpoint belongs to
+    pub kernel_path: PathBuf,
+
+    /// SHA-256 fingerprint of the patched source tree (see [`fingerprint_source_tree`])
+    pub source_fingerprint: String,
+
+    /// When the checkpoint was written
+    pub saved_at: SystemTime,
+}
+
+impl BuildCheckpoint {
+    /// The phase a resumed build continues from.
+    pub fn resume_phase(&self) -> BuildPhaseState {
+        self.state.phase
+    }
+
+    /// Whether this checkpoint can be resumed at all.
+    pub fn is_resumable(&self) -> bool {
+        !matches!(
+            self.state.phase,
+            BuildPhaseState::Completed | BuildPhaseState::Failed
+        )
+    }
+
+    /// Check that the source tree on disk still matches the recorded fingerprint.
+    pub fn verify_source_tree(&self) -> Result<()> {
+        let current = fingerprint_source_tree(&self.kernel_path)?;
+        if current != self.source_fingerprint {
+            return Err(format!(
+                "Source tree at {} changed since checkpoint (expected {}, found {})",
+                self.kernel_path.display(),
+                self.source_fingerprint,
+                current
+            )
+            .into());
+        }
+        Ok(())
+    }
+}
+
+/// Reads and writes build checkpoints in a checkpoint directory.
+#[derive(Debug, Clone)]
+pub struct CheckpointManager {
+    checkpoint_dir: PathBuf,
+}
+
+impl CheckpointManager {
+    /// Create a manager for the given checkpoint directory (created lazily on save).
+    pub fn new(checkpoint_dir: PathBuf) -> Self {
+        CheckpointManager { checkpoint_dir }
+    }
+
+    /// Path of the checkpoint file managed by this instance.
+    pub fn checkpoint_path(&self) -> PathBuf {
+        self.checkpoint_dir.join(CHECKPOINT_FILE_NAME)
+    }
+
+    /// Whether a checkpoint file currently exists.
+    pub fn has_checkpoint(&self) -> bool {
+        self.checkpoint_path().exists()
+    }
+
+    /// Persist `state` and the current fingerprint of `kernel_path`.
+    ///
+    /// The file is written to a temporary name and renamed into place so an
+    /// interrupted write never leaves a truncated checkpoint behind.
+    pub fn save(&self, state: &OrchestrationState, kernel_path: &Path) -> Result<PathBuf> {
+        fs::create_dir_all(&self.checkpoint_dir)
+            .map_err(|e| format!("Failed to create checkpoint directory: {}", e))?;
+
+        let path = self.checkpoint_path();
+        let mut state = state.clone();
+        state.checkpoint_path = Some(path.clone());
+
+        let checkpoint = BuildCheckpoint {
+            format_version: CHECKPOINT_FORMAT_VERSION,
+            state,
+            kernel_path: kernel_path.to_path_buf(),
+            source_fingerprint: fingerprint_source_tree(kernel_path)?,
+            saved_at: SystemTime::now(),
+        };
+
+        let json = serde_json::to_string_pretty(&checkpoint)?;
+        let tmp_path = self.checkpoint_dir.join(format!(
+            ".{}.{}.tmp",
+            CHECKPOINT_FILE_NAME,
+            std::process::id()
+        ));
+        fs::write(&tmp_path, json).map_err(|e| format!("Failed to write checkpoint: {}", e))?;
+        fs::rename(&tmp_path, &path)
+            .map_err(|e| format!("Failed to move checkpoint into place: {}", e))?;
+
+        Ok(path)
+    }
+
+    /// Load the checkpoint managed by this instance.
+    pub fn load(&self) -> Result<BuildCheckpoint> {
+        Self::load_from(&self.checkpoint_path())
+    }
+
+    /// Load a checkpoint from an explicit file path.
+    pub fn load_from(path: &Path) -> Result<BuildCheckpoint> {
+        let content = fs::read_to_string(path)
+            .map_err(|e| format!("Failed to read checkpoint {}: {}", path.display(), e))?;
+        let checkpoint: BuildCheckpoint = serde_json::from_str(&content)?;
+
+        if checkpoint.format_version != CHECKPOINT_FORMAT_VERSION {
+            return Err(format!(
+                "Unsupported checkpoint format version {} (expected {})",
+                checkpoint.format_version, CHECKPOINT_FORMAT_VERSION
+            )
+            .into());
+        }
+
+        Ok(checkpoint)
+    }
+
+    /// Remove the checkpoint file, if any.
+    pub fn clear(&self) -> Result<()> {
+        match fs::remove_file(self.checkpoint_path()) {
+            Ok(()) => Ok(()),
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => Ok(()),
+            Err(e) => Err(format!("Failed to remove checkpoint: {}", e).into()),
+        }
+    }
+}
+
+/// Whether a top-level file in the kernel directory is part of the patched tree.
+///
+/// Covers what the Patching phase writes: PKGBUILD, `config*` / `.config*`
+/// files and `*.patch` files. Build outputs (`.kernelrelease`, packages, logs)
+/// are excluded so an aborted build does not invalidate its own checkpoint.
+fn is_fingerprinted_file(name: &str) -> bool {
+    name == "PKGBUILD"
+        || name.starts_with("config")
+        || name.starts_with(".config")
+        || name.ends_with(".patch")
+}
+
+/// Whether a file below `src/` is written by the compile rather than by
+/// extraction or prepare().
+fn is_build_output(name: &str) -> bool {
+    BUILD_OUTPUT_SUFFIXES
+        .iter()
+        .any(|suffix| name.ends_with(suffix))
+        || name.starts_with("vmlinux")
+        || name.starts_with(".tmp")
+        || matches!(name, "System.map" | ".version" | ".config.old" | "bzImage")
+}
+
+/// Whether a directory below `src/` only holds build outputs.
+fn is_build_output_dir(path: &Path) -> bool {
+    let name = path
+        .file_name()
+        .and_then(|n| n.to_str())
+        .unwrap_or_default();
+    BUILD_OUTPUT_DIRS.contains(&name) || path.ends_with("include/config")
+}
+
+/// Feed every extracted source file below `dir` into `hasher`, in sorted order.
+///
+/// The tree holds tens of thousands of files, so each contributes its relative
+/// path, size and modification time rather than its contents. Symlinks (makepkg
+/// links the downloaded archives into `src/`) and build outputs are skipped.
+fn hash_extracted_tree(hasher: &mut Sha256, root: &Path, dir: &Path) -> std::io::Result<()> {
+    let mut entries: Vec<fs::DirEntry> = fs::read_dir(dir)?.filter_map(|e| e.ok()).collect();
+    entries.sort_by_key(|entry| entry.file_name());
+
+    for entry in entries {
+        let path = entry.path();
+        let file_type = entry.file_type()?;
+        if file_type.is_dir() {
+            if !is_build_output_dir(&path) {
+                hash_extracted_tree(hasher, root, &path)?;
+            }
+            continue;
+        }
+        if !file_type.is_file() || is_build_output(&entry.file_name().to_string_lossy()) {
+            continue;
+        }
+
+        let metadata = entry.metadata()?;
+        let mtime = metadata
+            .modified()?
+            .duration_since(SystemTime::UNIX_EPOCH)
+            .map(|d| d.as_nanos())
+            .unwrap_or(0);
+        let relative = path.strip_prefix(root).unwrap_or(&path);
+        hasher.update(relative.to_string_lossy().as_bytes());
+        hasher.update([0u8]);
+        hasher.update(metadata.len().to_le_bytes());
+        hasher.update(mtime.to_le_bytes());
+    }
+    Ok(())
+}
+
+/// Compute a SHA-256 fingerprint of the patched source tree in `kernel_path`.
+///
+/// Top-level files are visited in sorted order. Small files contribute their
+/// contents; files larger than 1 MiB contribute their size and modification
+/// time. The extracted tree below `src/` follows (see [`hash_extracted_tree`]).
+pub fn fingerprint_source_tree(kernel_path: &Path) -> Result<String> {
+    let entries = fs::read_dir(kernel_path).map_err(|e| {
+        format!(
+            "Failed to read kernel source directory {}: {}",
+            kernel_path.display(),
+            e
+        )
+    })?;
+
+    let mut files: Vec<(String, PathBuf)> = entries
+        .filter_map(|entry| entry.ok())
+        .filter(|entry| entry.file_type().map(|t| t.is_file()).unwrap_or(false))
+        .filter_map(|entry| {
+            let name = entry.file_name().to_string_lossy().to_string();
+            is_fingerprinted_file(&name).then(|| (name, entry.path()))
+        })
+        .collect();
+    files.sort();
+
+    let mut hasher = Sha256::new();
+    for (name, path) in files {
+        let metadata = fs::metadata(&path)?;
+        hasher.update(name.as_bytes());
+        hasher.update([0u8]);
+
+        if metadata.len() <= CONTENT_HASH_LIMIT {
+            hasher.update(fs::read(&path)?);
+        } else {
+            let mtime = metadata
+                .modified()?
+                .duration_since(SystemTime::UNIX_EPOCH)
+                .map(|d| d.as_nanos())
+                .unwrap_or(0);
+            hasher.update(metadata.len().to_le_bytes());
+            hasher.update(mtime.to_le_bytes());
+        }
+        hasher.update([0u8]);
+    }
+
+    let extracted = kernel_path.join(EXTRACTED_SOURCE_DIR);
+    if extracted.is_dir() {
+        hash_extracted_tree(&mut hasher, &extracted, &extracted).map_err(|e| {
+            format!(
+                "Failed to fingerprint extracted sources in {}: {}",
+                extracted.display(),
+                e
+            )
+        })?;
+    }
+
+    Ok(format!("{:x}", hasher.finalize()))
+}
 
 #[cfg(test)]
 mod tests {
-    // Checkpoint tests are now in Python (tests/test_layer_integration.py)
+    use super::*;
+    use tempfile::TempDir;
+
+    fn test_state() -> OrchestrationState {
+        let hw = crate::models::HardwareInfo {
+            cpu_model: "Test CPU".to_string(),
+            cpu_cores: 8,
+            cpu_threads: 16,
+            ram_gb: 16,
+            disk_free_gb: 100,
+            gpu_vendor: crate::models::GpuVendor::Amd,
+            gpu_model: "Test GPU".to_string(),
+            gpu_active_driver: true,
+            storage_type: crate::models::StorageType::Nvme,
+            storage_model: "Test Storage".to_string(),
+            boot_type: crate::models::BootType::Efi,
+            boot_manager: crate::models::BootManager {
+                detector: "systemd-boot".to_string(),
+                is_efi: true,
+            },
+            init_system: crate::models::InitSystem {
+                name: "systemd".to_string(),
+            },
+            all_drives: Vec::new(),
+        };
+        let mut config = crate::models::KernelConfig::default();
+        config.kernel_variant = "linux".to_string();
+        config.version = "6.12.1".to_string();
+        OrchestrationState::new(hw, config)
+    }
+
+    fn kernel_tree() -> TempDir {
+        let dir = TempDir::new().unwrap();
+        fs::write(dir.path().join("PKGBUILD"), "pkgbase=linux-goatd\n").unwrap();
+        fs::write(dir.path().join("config"), "CONFIG_LTO_CLANG_THIN=y\n").unwrap();
+        fs::write(dir.path().join(".kernelrelease"), "6.12.1-goatd\n").unwrap();
+        dir
+    }
+
+    #[test]
+    fn test_save_and_load_roundtrip() {
+        let tree = kernel_tree();
+        let checkpoints = TempDir::new().unwrap();
+        let manager = CheckpointManager::new(checkpoints.path().to_path_buf());
+
+        let mut state = test_state();
+        state.transition_to(BuildPhaseState::Configuration).unwrap();
+        state.transition_to(BuildPhaseState::Patching).unwrap();
+        state.transition_to(BuildPhaseState::Building).unwrap();
+
+        let path = manager.save(&state, tree.path()).unwrap();
+        assert_eq!(path, manager.checkpoint_path());
+
+        let loaded = CheckpointManager::load_from(&path).unwrap();
+        assert_eq!(loaded.resume_phase(), BuildPhaseState::Building);
+        assert!(loaded.is_resumable());
+        assert_eq!(loaded.state.config.version, "6.12.1");
+        assert_eq!(loaded.state.checkpoint_path, Some(path));
+        assert!(loaded.verify_source_tree().is_ok());
+    }
+
+    #[test]
+    fn test_verify_detects_modified_tree() {
+        let tree = kernel_tree();
+        let checkpoints = TempDir::new().unwrap();
+        let manager = CheckpointManager::new(checkpoints.path().to_path_buf());
+        manager.save(&test_state(), tree.path()).unwrap();
+
+        fs::write(tree.path().join("config"), "CONFIG_LTO_NONE=y\n").unwrap();
+
+        let loaded = manager.load().unwrap();
+        assert!(loaded.verify_source_tree().is_err());
+    }
+
+    #[test]
+    fn test_fingerprint_ignores_build_outputs() {
+        let tree = kernel_tree();
+        let before = fingerprint_source_tree(tree.path()).unwrap();
+
+        fs::write(tree.path().join(".kernelrelease"), "6.12.2-goatd\n").unwrap();
+        fs::write(tree.path().join("linux-goatd.pkg.tar.zst"), "pkg").unwrap();
+        fs::create_dir_all(tree.path().join("src")).unwrap();
+
+        assert_eq!(before, fingerprint_source_tree(tree.path()).unwrap());
+
+        fs::write(tree.path().join("0001-fix.patch"), "diff").unwrap();
+        assert_ne!(before, fingerprint_source_tree(tree.path()).unwrap());
+    }
+
+    #[test]
+    fn test_fingerprint_covers_extracted_tree() {
+        let tree = kernel_tree();
+        let source = tree.path().join("src/linux-6.12.1");
+        fs::create_dir_all(source.join("kernel/sched")).unwrap();
+        fs::write(source.join("kernel/sched/fair.c"), "int x;\n").unwrap();
+        fs::write(source.join(".config"), "CONFIG_LTO_CLANG_THIN=y\n").unwrap();
+        let before = fingerprint_source_tree(tree.path()).unwrap();
+
+        // Compile outputs do not invalidate the checkpoint
+        fs::write(source.join("kernel/sched/fair.o"), "elf").unwrap();
+        fs::write(source.join("kernel/sched/.fair.o.cmd"), "cmd").unwrap();
+        fs::create_dir_all(source.join("include/generated")).unwrap();
+        fs::write(source.join("include/generated/autoconf.h"), "#define X").unwrap();
+        fs::create_dir_all(source.join("include/config")).unwrap();
+        fs::write(source.join("include/config/auto.conf"), "X=y").unwrap();
+        fs::write(source.join("vmlinux.o"), "elf").unwrap();
+        assert_eq!(before, fingerprint_source_tree(tree.path()).unwrap());
+
+        // A change to the patched tree does
+        fs::write(source.join("kernel/sched/fair.c"), "int x = 1;\n").unwrap();
+        assert_ne!(before, fingerprint_source_tree(tree.path()).unwrap());
+    }
+
+    #[test]
+    fn test_clear_removes_checkpoint() {
+        let tree = kernel_tree();
+        let checkpoints = TempDir::new().unwrap();
+        let manager = CheckpointManager::new(checkpoints.path().to_path_buf());
+
+        manager.save(&test_state(), tree.path()).unwrap();
+        assert!(manager.has_checkpoint());
+        manager.clear().unwrap();
+        assert!(!manager.has_checkpoint());
+        // Clearing twice is not an error
+        manager.clear().unwrap();
+    }
 }
@@ -40,9 +40,11 @@
 //! - Add metrics for path resolution success/failure rates across mount points
 //! - Implement workspace migration helpers for users changing storage configurations
 
+use super::{failure, governor};
 use crate::error::BuildError;
 use crate::kernel::pkgbuild::get_latest_version_by_variant;
 use crate::models::{HardwareInfo, KernelConfig};
+use crate::system::performance::thermal::read_thermal_data;
 use regex::Regex;
 use std::path::Path;
 use std::sync::Arc;
@@ -570,6 +572,7 @@ fn parse_build_progress(line: &str) -> Option<u32> {
 /// # Arguments
 /// * `kernel_path` - Path to the kernel source directory
 /// * `config` - Kernel configuration (used for build options)
+/// * `continue_build` - Build on the already extracted and prepared tree (`makepkg -e`)
 /// * `output_callback` - Callback function to receive output lines and progress updates
 /// * `cancel_rx` - Watch channel receiver for cancellation signals
 /// * `log_collector` - Optional log collector for dual-writing build output
@@ -589,6 +592,7 @@ fn parse_build_progress(line: &str) -> Option<u32> {
 pub async fn run_kernel_build<F>(
     kernel_path: &Path,
     config: &KernelConfig,
+    continue_build: bool,
     mut output_callback: F,
     mut cancel_rx: watch::Receiver<bool>,
     log_collector: Option<std::sync::Arc<crate::LogCollector>>,
@@ -696,11 +700,22 @@ where
     let child_pid: Arc<std::sync::Mutex<Option<u32>>> = Arc::new(std::sync::Mutex::new(None));
     let child_pid_clone = Arc::clone(&child_pid);
 
+    // Keep the tail of the build output for failure analysis
+    let log_tail = Arc::new(std::sync::Mutex::new(std::collections::VecDeque::new()));
+    let log_tail_writer = Arc::clone(&log_tail);
+    let mut output_callback = move |line: String, progress: Option<u32>| {
+        if let Ok(mut tail) = log_tail_writer.lock() {
+            failure::push_tail(&mut tail, &line);
+        }
+        output_callback(line, progress);
+    };
+
     let build_future = async {
         run_kernel_build_inner(
             kernel_path,
             &canonical_kernel_path,
             config,
+            continue_build,
             &mut output_callback,
             &mut cancel_rx,
             &log_collector,
@@ -718,7 +733,9 @@ where
             timeout_duration
         );
         match tokio::time::timeout(timeout_duration, build_future).await {
-            Ok(result) => result?,
+            Ok(result) => result.map_err(|e| {
+                diagnose_build_failure(e, &log_tail, &mut output_callback, &log_collector)
+            })?,
             Err(_timeout_err) => {
                 // TIMEOUT TRIGGERED: Perform explicit process cleanup (mirror cancellation logic)
                 let timeout_msg = format!("Build timeout exceeded: {:?}", timeout_duration);
@@ -771,22 +788,69 @@ where
                     }
                 }
 
-                return Err(BuildError::BuildFailed(timeout_msg));
+                return Err(diagnose_build_failure(
+                    BuildError::BuildFailed(timeout_msg),
+                    &log_tail,
+                    &mut output_callback,
+                    &log_collector,
+                ));
             }
         }
     } else {
-        build_future.await?;
+        build_future.await.map_err(|e| {
+            diagnose_build_failure(e, &log_tail, &mut output_callback, &log_collector)
+        })?;
     }
 
     Ok(())
 }
 
+/// Run the build-log analyzer over the captured output tail of a failed build.
+///
+/// A recognised failure turns `BuildFailed` into `BuildError::Diagnosed` and is
+/// written to the build log; every other error is returned unchanged.
+fn diagnose_build_failure<F>(
+    err: BuildError,
+    log_tail: &std::sync::Mutex<std::collections::VecDeque<String>>,
+    output_callback: &mut F,
+    log_collector: &Option<std::sync::Arc<crate::LogCollector>>,
+) -> BuildError
+where
+    F: FnMut(String, Option<u32>),
+{
+    let BuildError::BuildFailed(message) = err else {
+        return err;
+    };
+    let lines: Vec<String> = log_tail
+        .lock()
+        .map(|tail| tail.iter().cloned().collect())
+        .unwrap_or_default();
+    let Some(diagnosis) = failure::analyze_build_log(&lines) else {
+        eprintln!("[Build] [DIAGNOSIS] No known failure pattern in the build output");
+        return BuildError::BuildFailed(message);
+    };
+
+    eprintln!("[Build] [DIAGNOSIS] {}", diagnosis.title());
+    for line in diagnosis.to_string().lines() {
+        let msg = format!("[DIAGNOSIS] {}", line);
+        output_callback(msg.clone(), None);
+        if let Some(ref collector) = log_collector {
+            collector.log_str(&msg);
+        }
+    }
+    BuildError::Diagnosed {
+        message,
+        diagnosis: Box::new(diagnosis),
+    }
+}
+
 /// Inner async function that implements the build loop.
 /// Extracted to allow timeout wrapping without code duplication.
 async fn run_kernel_build_inner<F>(
     kernel_path: &Path,
     canonical_kernel_path: &std::path::Path,
     config: &KernelConfig,
+    continue_build: bool,
     output_callback: &mut F,
     cancel_rx: &mut watch::Receiver<bool>,
     log_collector: &Option<std::sync::Arc<crate::LogCollector>>,
@@ -1004,9 +1068,11 @@ where
     }
 
     // Determine build command based on what's available
-    let num_jobs = num_cpus::get();
+    // A build matrix hands each build its share of the global job budget
+    let num_jobs = config.build_jobs.unwrap_or_else(num_cpus::get).max(1);
+    eprintln!("[Build] [JOBS] Using {} parallel make jobs", num_jobs);
 
-    let mut command = if pkgbuild_path.exists() {
+    let (program, args): (&str, Vec<String>) = if pkgbuild_path.exists() {
         eprintln!("[Build] [DEBUG] PKGBUILD found, using 'makepkg'");
 
         eprintln!(
@@ -1032,24 +1098,68 @@ where
             );
         }
 
-        let mut cmd = Command::new("makepkg");
-        cmd.arg("-s");
-        cmd.arg("-f");
-        cmd.arg("--noconfirm");
-        cmd
+        let mut args = vec![
+            "-s".to_string(),
+            "-f".to_string(),
+            "--noconfirm".to_string(),
+        ];
+        if continue_build {
+            // Keep the extracted, prepared tree and its objects (skips prepare())
+            eprintln!("[Build] [RESUME] Continuing in the existing source tree (makepkg -e)");
+            args.push("-e".to_string());
+        }
+        ("makepkg", args)
     } else if kernel_path.join("scripts/build.sh").exists() {
         eprintln!("[Build] [DEBUG] Found scripts/build.sh, using it");
-        let mut cmd = Command::new("bash");
-        cmd.arg("scripts/build.sh");
-        cmd
+        ("bash", vec!["scripts/build.sh".to_string()])
     } else {
         eprintln!("[Build] [DEBUG] Falling back to standard 'make'");
-        let mut cmd = Command::new("make");
-        cmd.arg(format!("-j{}", num_jobs));
-        cmd.arg("bzImage");
-        cmd
+        (
+            "make",
+            vec![format!("-j{}", num_jobs), "bzImage".to_string()],
+        )
     };
 
+    // ============================================================================
+    // RESOURCE GOVERNOR: nice/ionice, cgroup scope and thermal throttling
+    // ============================================================================
+    let limits = &config.resources;
+    let cgroup_ok = governor::needs_cgroup(limits) && governor::cgroup_available();
+    let governed = governor::wrap_command(
+        limits,
+        num_jobs,
+        program,
+        &args,
+        &governor::scope_unit_name(),
+        cgroup_ok,
+    )
+    .map_err(|e| {
+        eprintln!("[Build] [GOVERNOR] ERROR: {}", e);
+        BuildError::BuildFailed(e)
+    })?;
+    for line in governor::describe(limits, num_jobs)
+        .into_iter()
+        .chain(governed.warnings.iter().map(|w| format!("WARNING: {}", w)))
+    {
+        eprintln!("[Build] [GOVERNOR] {}", line);
+        let msg = format!("[GOVERNOR] {}", line);
+        output_callback(msg.clone(), None);
+        if let Some(ref collector) = log_collector {
+            collector.log_str(&msg);
+        }
+    }
+    let mut thermal_throttle = match (&governed.scope_unit, limits.thermal_limit_c) {
+        (Some(_), Some(limit)) => Some(governor::ThermalThrottle::new(
+            limit,
+            governor::initial_cpu_quota(limits, num_jobs),
+        )),
+        _ => None,
+    };
+    let mut thermal_tick = tokio::time::interval(Duration::from_secs(governor::THERMAL_POLL_SECS));
+
+    let mut command = Command::new(&governed.program);
+    command.args(&governed.args);
+
     // Set working directory
     command.current_dir(&canonical_kernel_path);
 
@@ -1222,7 +1332,8 @@ where
     eprintln!("[Build] [ENV-CONFIG] Exported GOATD_KERNEL_VARIANT={}", config.kernel_variant);
 
     let patcher = crate::kernel::patcher::KernelPatcher::new(canonical_kernel_path.to_path_buf());
-    let hardened_env = patcher.prepare_build_environment(config.native_optimizations);
+    let hardened_env =
+        patcher.prepare_build_environment(config.native_optimizations, &config.compiler_cache);
 
     eprintln!("[Build] [ENV-UNIFY] ========== SURGICAL INJECTION LOOP START ==========");
     eprintln!("[Build] [ENV-UNIFY] Obtained hardened environment from patcher");
@@ -1419,6 +1530,21 @@ where
                     }
                 }
             }
+            _ = thermal_tick.tick(), if thermal_throttle.is_some() => {
+                let package_temp = read_thermal_data().package_temperature;
+                let throttle = thermal_throttle.as_mut().expect("guarded by select condition");
+                if let (Some(quota), Some(unit)) = (throttle.update(package_temp), &governed.scope_unit) {
+                    let msg = match governor::set_scope_cpu_quota(unit, quota) {
+                        Ok(()) => format!("[GOVERNOR] Package at {:.0}°C: CPU quota set to {}%", package_temp, quota),
+                        Err(e) => format!("[GOVERNOR] WARNING: Failed to set CPU quota to {}%: {}", quota, e),
+                    };
+                    eprintln!("[Build] {}", msg);
+                    output_callback(msg.clone(), None);
+                    if let Some(ref collector) = log_collector {
+                        collector.log_str(&msg);
+                    }
+                }
+            }
             _ = cancel_rx.changed() => {
                 if *cancel_rx.borrow() {
                     output_callback("Build cancelled by user".to_string(), None);
@@ -2029,6 +2155,14 @@ mod tests {
             native_optimizations: true,
             user_toggled_native_optimizations: false,
             kernel_variant: String::new(),
+            mok_key_path: None,
+            mok_cert_path: None,
+            compiler_cache: Default::default(),
+            cmdline: Default::default(),
+            pgo: Default::default(),
+            build_jobs: None,
+            resources: Default::default(),
+            strict_kconfig: false,
         }
     }
 
@@ -0,0 +1,279 @@
+//! Build failure analysis.
+//!
+//! When a kernel build fails, the tail of its output is matched against a
+//! small database of known failures ([`FAILURE_PATTERNS`]). The latest line
+//! that matches becomes a [`BuildFailureDiagnosis`]: what went wrong, the log
+//! lines around the offending line, and a suggested fix. The executor attaches
+//! it to the returned [`crate::error::BuildError`] so the CLI and the Build tab
+//! can show something more useful than "exit code 2".
+
+use std::collections::VecDeque;
+
+/// Output lines kept by the executor for failure analysis.
+pub const FAILURE_LOG_TAIL: usize = 400;
+
+/// Lines of context shown before and after the matching line.
+const EXCERPT_BEFORE: usize = 6;
+const EXCERPT_AFTER: usize = 2;
+
+/// Class of a recognised build failure.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum FailureKind {
+    LtoOutOfMemory,
+    MissingToolchain,
+    KconfigPrompt,
+    BindgenMismatch,
+    DiskFull,
+    PgpKeyMissing,
+}
+
+impl FailureKind {
+    /// Short title for the Build tab and the CLI.
+    pub fn title(&self) -> &'static str {
+        match self {
+            FailureKind::LtoOutOfMemory => "Linker ran out of memory (LTO)",
+            FailureKind::MissingToolchain => "Missing build toolchain",
+            FailureKind::KconfigPrompt => "Kconfig waited for an answer",
+            FailureKind::BindgenMismatch => "Rust bindgen/libclang version mismatch",
+            FailureKind::DiskFull => "Out of disk space",
+            FailureKind::PgpKeyMissing => "PGP key missing for source signature",
+        }
+    }
+}
+
+/// One entry of the known-failure database.
+#[derive(Debug, Clone, Copy)]
+pub struct FailurePattern {
+    pub kind: FailureKind,
+    /// Lowercase substrings; a line matches if it contains any of them
+    pub needles: &'static [&'static str],
+    pub suggested_fix: &'static str,
+}
+
+/// Known build failures, most specific first (the order only breaks ties
+/// between patterns matching the same line).
+pub const FAILURE_PATTERNS: &[FailurePattern] = &[
+    FailurePattern {
+        kind: FailureKind::DiskFull,
+        needles: &["no space left on device", "disk quota exceeded"],
+        suggested_fix: "Free space on the workspace disk (an LTO build needs 30+ GB), \
+            clear old builds from the Workspace tab or move the workspace to a bigger disk.",
+    },
+    FailurePattern {
+        kind: FailureKind::PgpKeyMissing,
+        needles: &[
+            "unknown public key",
+            "one or more pgp signatures could not be verified",
+            "can't check signature: no public key",
+        ],
+        suggested_fix: "Import the signing key shown in the excerpt with \
+            `gpg --recv-keys <KEYID>` (the kernel is signed by Linus Torvalds and \
+            Greg Kroah-Hartman), then restart the build.",
+    },
+    FailurePattern {
+        kind: FailureKind::LtoOutOfMemory,
+        needles: &[
+            "llvm error: out of memory",
+            "ld.lld: error: out of memory",
+            "unable to execute command: killed",
+            "linker command failed due to signal",
+            "killed signal terminated program",
+            "cannot allocate memory",
+        ],
+        suggested_fix: "The LTO link needs a lot of RAM. Switch from Full to Thin LTO, \
+            lower the make jobs (--jobs) or set a memory limit so other builds are not \
+            killed, and add swap or zram before retrying.",
+    },
+    FailurePattern {
+        kind: FailureKind::BindgenMismatch,
+        needles: &[
+            "rust bindings generator 'bindgen'",
+            "libclang (used by the rust bindings generator",
+            "bindgen: error",
+            "rust is not available",
+        ],
+        suggested_fix: "The installed bindgen/libclang does not match what this kernel \
+            expects. Update rust-bindgen and clang together (`pacman -Syu rust-bindgen \
+            clang`), or disable CONFIG_RUST for this build.",
+    },
+    FailurePattern {
+        kind: FailureKind::KconfigPrompt,
+        needles: &["error in reading or end of file"],
+        suggested_fix: "Kconfig asked about a new option and got no answer. Rebuild so \
+            the configuration is refreshed with `make olddefconfig`, or set the option \
+            explicitly in the profile's config options.",
+    },
+    FailurePattern {
+        kind: FailureKind::MissingToolchain,
+        needles: &[
+            "clang: command not found",
+            "ld.lld: command not found",
+            "llvm-ar: command not found",
+            "bc: command not found",
+            "cpio: command not found",
+            "pahole: command not found",
+            "could not resolve all dependencies",
+            "missing dependencies:",
+            "cannot find a working compiler",
+        ],
+        suggested_fix: "Install the LLVM toolchain and kernel build dependencies: \
+            `sudo pacman -S --needed base-devel clang llvm lld bc cpio pahole`.",
+    },
+];
+
+/// A recognised build failure with the log lines that identify it.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct BuildFailureDiagnosis {
+    pub kind: FailureKind,
+    /// The line that matched the pattern
+    pub matched_line: String,
+    /// Lines around the match, in log order
+    pub excerpt: Vec<String>,
+    pub suggested_fix: String,
+}
+
+impl BuildFailureDiagnosis {
+    pub fn title(&self) -> &'static str {
+        self.kind.title()
+    }
+}
+
+impl std::fmt::Display for BuildFailureDiagnosis {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        writeln!(f, "Diagnosis: {}", self.title())?;
+        writeln!(f, "Suggested fix: {}", self.suggested_fix)?;
+        writeln!(f, "Log excerpt:")?;
+        for line in &self.excerpt {
+            writeln!(f, "  {}", line)?;
+        }
+        Ok(())
+    }
+}
+
+/// Append a line to the failure-analysis tail, dropping the oldest one.
+pub fn push_tail(tail: &mut VecDeque<String>, line: &str) {
+    if tail.len() >= FAILURE_LOG_TAIL {
+        tail.pop_front();
+    }
+    tail.push_back(line.to_string());
+}
+
+/// Match the end of a build log against [`FAILURE_PATTERNS`].
+///
+/// The log is scanned from the end and the latest matching line wins, so a
+/// benign earlier match cannot hide the error that actually stopped the build.
+/// When one line matches several patterns, database order decides.
+pub fn analyze_build_log<S: AsRef<str>>(lines: &[S]) -> Option<BuildFailureDiagnosis> {
+    let (idx, pattern) = lines.iter().enumerate().rev().find_map(|(idx, line)| {
+        let line = line.as_ref().to_lowercase();
+        FAILURE_PATTERNS
+            .iter()
+            .find(|pattern| pattern.needles.iter().any(|n| line.contains(n)))
+            .map(|pattern| (idx, pattern))
+    })?;
+
+    let start = idx.saturating_sub(EXCERPT_BEFORE);
+    let end = (idx + EXCERPT_AFTER + 1).min(lines.len());
+    Some(BuildFailureDiagnosis {
+        kind: pattern.kind,
+        matched_line: lines[idx].as_ref().to_string(),
+        excerpt: lines[start..end]
+            .iter()
+            .map(|l| l.as_ref().to_string())
+            .collect(),
+        suggested_fix: pattern.suggested_fix.to_string(),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn diagnose(log: &str) -> Option<FailureKind> {
+        let lines: Vec<&str> = log.lines().collect();
+        analyze_build_log(&lines).map(|d| d.kind)
+    }
+
+    #[test]
+    fn test_analyze_recognises_each_failure_class() {
+        assert_eq!(
+            diagnose("  LD      vmlinux.o\nLLVM ERROR: out of memory\nmake[2]: *** Error 1"),
+            Some(FailureKind::LtoOutOfMemory)
+        );
+        assert_eq!(
+            diagnose("  CC      init/main.o\n/bin/sh: line 1: clang: command not found"),
+            Some(FailureKind::MissingToolchain)
+        );
+        assert_eq!(
+            diagnose("Enable foo (FOO) [N/y/?] (NEW) \nError in reading or end of file."),
+            Some(FailureKind::KconfigPrompt)
+        );
+        assert_eq!(
+            diagnose("***\n*** Rust bindings generator 'bindgen' is too new.\n***"),
+            Some(FailureKind::BindgenMismatch)
+        );
+        assert_eq!(
+            diagnose("  AR      built-in.a\nfatal error: write error: No space left on device"),
+            Some(FailureKind::DiskFull)
+        );
+        assert_eq!(
+            diagnose(
+                "==> Verifying source file signatures with gpg...\n    linux-6.12.tar ... FAILED (unknown public key 38DBBDC86092693E)"
+            ),
+            Some(FailureKind::PgpKeyMissing)
+        );
+        assert_eq!(diagnose("make: *** [Makefile:1] Error 2"), None);
+    }
+
+    #[test]
+    fn test_analyze_prefers_latest_failure_over_earlier_benign_match() {
+        // An allocation warning early in the build must not mask the real error
+        let log = "ccache: warning: cannot allocate memory for the cache, continuing\n\
+                   Enable foo (FOO) [N/y/?] (NEW) y\n\
+                   scripts/setlocalversion: line 3: git: command not found\n\
+                   \x20 CC      init/main.o\n\
+                   /bin/sh: line 1: clang: command not found\n\
+                   make[2]: *** [scripts/Makefile.build:243: init/main.o] Error 127";
+        assert_eq!(diagnose(log), Some(FailureKind::MissingToolchain));
+
+        // Generic lines alone are not a diagnosis
+        assert_eq!(
+            diagnose(
+                "Enable foo (FOO) [N/y/?] (NEW) y\n\
+                 scripts/setlocalversion: line 3: git: command not found\n\
+                 make: *** [Makefile:1] Error 2"
+            ),
+            None
+        );
+    }
+
+    #[test]
+    fn test_analyze_prefers_last_failure_and_builds_excerpt() {
+        let mut lines: Vec<String> = (0..20).map(|i| format!("  CC      file{}.o", i)).collect();
+        lines.push("ld.lld: error: out of memory".to_string());
+        lines.push("make[1]: *** [Makefile:1234: vmlinux] Error 1".to_string());
+        // The later disk-full error wins over the OOM symptom before it
+        lines.push("write error: No space left on device".to_string());
+        lines.push("==> ERROR: A failure occurred in build().".to_string());
+
+        let diagnosis = analyze_build_log(&lines).unwrap();
+        assert_eq!(diagnosis.kind, FailureKind::DiskFull);
+        assert_eq!(
+            diagnosis.matched_line,
+            "write error: No space left on device"
+        );
+        assert_eq!(diagnosis.excerpt.len(), EXCERPT_BEFORE + 2);
+        assert_eq!(diagnosis.excerpt.last().unwrap(), &lines[23]);
+        assert!(diagnosis.to_string().contains("Out of disk space"));
+    }
+
+    #[test]
+    fn test_push_tail_is_bounded() {
+        let mut tail = VecDeque::new();
+        for i in 0..FAILURE_LOG_TAIL + 5 {
+            push_tail(&mut tail, &i.to_string());
+        }
+        assert_eq!(tail.len(), FAILURE_LOG_TAIL);
+        assert_eq!(tail.front().unwrap(), "5");
+    }
+}
@@ -0,0 +1,318 @@
+//! Build resource governor.
+//!
+//! Keeps a kernel build from taking the whole machine: the build command is
+//! prefixed with `nice`/`ionice` and, when cgroup limits are requested, started
+//! in a transient cgroup v2 scope (`systemd-run --user --scope`) with `CPUQuota`
+//! and `MemoryMax`. While the build runs, [`ThermalThrottle`] lowers the scope's
+//! CPU quota whenever the CPU package gets hotter than the configured limit and
+//! raises it again once the package has cooled down. The quota is the only
+//! lever on a running build, so a thermal limit without a scope is an error.
+
+use std::path::Path;
+
+use crate::kernel::compiler_cache::find_in_path;
+use crate::models::BuildResourceLimits;
+
+/// Present when the unified (v2) cgroup hierarchy is mounted.
+const CGROUP_CONTROLLERS: &str = "/sys/fs/cgroup/cgroup.controllers";
+
+/// Degrees below the limit the package must reach before the quota is raised.
+pub const THERMAL_HYSTERESIS_C: f32 = 5.0;
+
+/// The throttle never goes below one CPU worth of quota.
+pub const MIN_CPU_QUOTA_PERCENT: u32 = 100;
+
+/// How often the package temperature is sampled while throttling is active.
+pub const THERMAL_POLL_SECS: u64 = 5;
+
+/// A build command after the resource limits were applied.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct GovernedCommand {
+    pub program: String,
+    pub args: Vec<String>,
+    /// Name of the transient scope unit (with `.scope`), if one is used
+    pub scope_unit: Option<String>,
+    /// Limits that were requested but could not be applied
+    pub warnings: Vec<String>,
+}
+
+/// Whether the limits need the build to run in its own cgroup.
+pub fn needs_cgroup(limits: &BuildResourceLimits) -> bool {
+    limits.cgroup
+        || limits.cpu_quota_percent.is_some()
+        || limits.memory_max_mb.is_some()
+        || limits.thermal_limit_c.is_some()
+}
+
+/// Whether transient cgroup v2 scopes can be created on this system.
+pub fn cgroup_available() -> bool {
+    let path = std::env::var("PATH").unwrap_or_default();
+    Path::new(CGROUP_CONTROLLERS).exists() && find_in_path("systemd-run", &path).is_some()
+}
+
+/// A scope unit name unique to this process and moment (without `.scope`).
+pub fn scope_unit_name() -> String {
+    let stamp = std::time::SystemTime::now()
+        .duration_since(std::time::UNIX_EPOCH)
+        .map(|d| d.as_millis())
+        .unwrap_or_default();
+    format!("goatd-build-{}-{}", std::process::id(), stamp)
+}
+
+/// The CPU quota the build starts with (`jobs` CPUs if no quota is set).
+pub fn initial_cpu_quota(limits: &BuildResourceLimits, jobs: usize) -> u32 {
+    limits
+        .cpu_quota_percent
+        .unwrap_or((jobs.max(1) as u32).saturating_mul(100))
+        .max(MIN_CPU_QUOTA_PERCENT)
+}
+
+/// Prefix `program args` with the configured resource limits.
+///
+/// `unit` is the scope name to use; it is ignored when the limits do not need
+/// a cgroup or `cgroup_ok` is false (the cgroup limits are then dropped with a
+/// warning and only nice/ionice apply). A thermal limit cannot be honoured
+/// without the scope's CPU quota, so it fails instead.
+pub fn wrap_command(
+    limits: &BuildResourceLimits,
+    jobs: usize,
+    program: &str,
+    args: &[String],
+    unit: &str,
+    cgroup_ok: bool,
+) -> Result<GovernedCommand, String> {
+    let mut argv: Vec<String> = Vec::new();
+    let mut warnings = Vec::new();
+    let mut scope_unit = None;
+
+    if needs_cgroup(limits) {
+        if cgroup_ok {
+            argv.extend(
+                ["systemd-run", "--user", "--scope", "--quiet"]
+                    .iter()
+                    .map(|s| s.to_string()),
+            );
+            argv.push(format!("--unit={}", unit));
+            if limits.cpu_quota_percent.is_some() || limits.thermal_limit_c.is_some() {
+                argv.push("-p".to_string());
+                argv.push(format!("CPUQuota={}%", initial_cpu_quota(limits, jobs)));
+            }
+            if let Some(mb) = limits.memory_max_mb {
+                argv.push("-p".to_string());
+                argv.push(format!("MemoryMax={}M", mb));
+            }
+            argv.push("--".to_string());
+            scope_unit = Some(format!("{}.scope", unit));
+        } else if let Some(limit) = limits.thermal_limit_c {
+            return Err(format!(
+                "Thermal limit of {:.0}°C needs a cgroup v2 scope (systemd-run --user --scope), \
+                 which is not available on this system",
+                limit
+            ));
+        } else {
+            warnings.push(
+                "cgroup v2 or systemd-run not available: CPU/memory limits disabled".to_string(),
+            );
+        }
+    }
+
+    if let Some(level) = limits.nice {
+        argv.extend(["nice".to_string(), "-n".to_string(), level.to_string()]);
+    }
+    if limits.ionice_idle {
+        argv.extend(["ionice".to_string(), "-c".to_string(), "3".to_string()]);
+    }
+
+    argv.push(program.to_string());
+    argv.extend(args.iter().cloned());
+
+    let program = argv.remove(0);
+    Ok(GovernedCommand {
+        program,
+        args: argv,
+        scope_unit,
+        warnings,
+    })
+}
+
+/// Human-readable summary of the limits, one line each, for the build log.
+pub fn describe(limits: &BuildResourceLimits, jobs: usize) -> Vec<String> {
+    let mut lines = vec![format!("make jobs: {}", jobs)];
+    if let Some(level) = limits.nice {
+        lines.push(format!("nice: {}", level));
+    }
+    if limits.ionice_idle {
+        lines.push("ionice: idle class".to_string());
+    }
+    if needs_cgroup(limits) {
+        lines.push("cgroup: transient systemd scope".to_string());
+    }
+    if let Some(quota) = limits.cpu_quota_percent {
+        lines.push(format!("CPU quota: {}%", quota));
+    }
+    if let Some(mb) = limits.memory_max_mb {
+        lines.push(format!("memory max: {} MiB", mb));
+    }
+    if let Some(limit) = limits.thermal_limit_c {
+        lines.push(format!(
+            "thermal limit: {:.0}°C (CPU quota {}% - {}%)",
+            limit,
+            MIN_CPU_QUOTA_PERCENT,
+            initial_cpu_quota(limits, jobs)
+        ));
+    }
+    lines
+}
+
+/// Lowers the build's CPU quota while the package runs hot.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ThermalThrottle {
+    pub limit_c: f32,
+    pub max_quota: u32,
+    pub current_quota: u32,
+}
+
+impl ThermalThrottle {
+    pub fn new(limit_c: f32, max_quota: u32) -> Self {
+        let max_quota = max_quota.max(MIN_CPU_QUOTA_PERCENT);
+        ThermalThrottle {
+            limit_c,
+            max_quota,
+            current_quota: max_quota,
+        }
+    }
+
+    /// Feed one package temperature; returns the new quota if it changed.
+    ///
+    /// Above the limit the quota is halved, below `limit - hysteresis` it is
+    /// doubled back towards the maximum. A reading of 0 (no sensor) is ignored.
+    pub fn update(&mut self, package_temp_c: f32) -> Option<u32> {
+        if package_temp_c <= 0.0 {
+            return None;
+        }
+        let next = if package_temp_c > self.limit_c {
+            (self.current_quota / 2).max(MIN_CPU_QUOTA_PERCENT)
+        } else if package_temp_c < self.limit_c - THERMAL_HYSTERESIS_C {
+            self.current_quota.saturating_mul(2).min(self.max_quota)
+        } else {
+            self.current_quota
+        };
+        if next == self.current_quota {
+            return None;
+        }
+        self.current_quota = next;
+        Some(next)
+    }
+}
+
+/// Change the CPU quota of a running build scope.
+pub fn set_scope_cpu_quota(unit: &str, quota_percent: u32) -> Result<(), String> {
+    let output = std::process::Command::new("systemctl")
+        .args(["--user", "set-property", "--runtime", unit])
+        .arg(format!("CPUQuota={}%", quota_percent))
+        .output()
+        .map_err(|e| format!("failed to run systemctl: {}", e))?;
+    if output.status.success() {
+        Ok(())
+    } else {
+        Err(String::from_utf8_lossy(&output.stderr).trim().to_string())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn args(list: &[&str]) -> Vec<String> {
+        list.iter().map(|s| s.to_string()).collect()
+    }
+
+    #[test]
+    fn test_wrap_command_without_limits_is_unchanged() {
+        let limits = BuildResourceLimits::default();
+        let cmd = wrap_command(&limits, 8, "makepkg", &args(&["-s"]), "u", true).unwrap();
+        assert_eq!(cmd.program, "makepkg");
+        assert_eq!(cmd.args, args(&["-s"]));
+        assert_eq!(cmd.scope_unit, None);
+        assert!(cmd.warnings.is_empty());
+    }
+
+    #[test]
+    fn test_wrap_command_with_scope_nice_and_ionice() {
+        let limits = BuildResourceLimits {
+            nice: Some(10),
+            ionice_idle: true,
+            memory_max_mb: Some(8192),
+            thermal_limit_c: Some(85.0),
+            ..Default::default()
+        };
+        let cmd =
+            wrap_command(&limits, 4, "makepkg", &args(&["-f"]), "goatd-build-1", true).unwrap();
+        assert_eq!(cmd.program, "systemd-run");
+        assert_eq!(
+            cmd.args,
+            args(&[
+                "--user",
+                "--scope",
+                "--quiet",
+                "--unit=goatd-build-1",
+                "-p",
+                "CPUQuota=400%",
+                "-p",
+                "MemoryMax=8192M",
+                "--",
+                "nice",
+                "-n",
+                "10",
+                "ionice",
+                "-c",
+                "3",
+                "makepkg",
+                "-f",
+            ])
+        );
+        assert_eq!(cmd.scope_unit.as_deref(), Some("goatd-build-1.scope"));
+    }
+
+    #[test]
+    fn test_wrap_command_falls_back_without_cgroup() {
+        let limits = BuildResourceLimits {
+            nice: Some(5),
+            cpu_quota_percent: Some(200),
+            ..Default::default()
+        };
+        let cmd = wrap_command(&limits, 4, "make", &args(&["-j4"]), "u", false).unwrap();
+        assert_eq!(cmd.program, "nice");
+        assert_eq!(cmd.args, args(&["-n", "5", "make", "-j4"]));
+        assert_eq!(cmd.scope_unit, None);
+        assert_eq!(cmd.warnings.len(), 1);
+    }
+
+    #[test]
+    fn test_wrap_command_thermal_limit_requires_scope() {
+        let limits = BuildResourceLimits {
+            thermal_limit_c: Some(85.0),
+            ..Default::default()
+        };
+        let err = wrap_command(&limits, 4, "make", &args(&["-j4"]), "u", false).unwrap_err();
+        assert!(err.contains("85°C"));
+        assert!(wrap_command(&limits, 4, "make", &args(&["-j4"]), "u", true).is_ok());
+    }
+
+    #[test]
+    fn test_thermal_throttle_halves_and_recovers() {
+        let mut throttle = ThermalThrottle::new(80.0, 800);
+        assert_eq!(throttle.update(0.0), None);
+        assert_eq!(throttle.update(78.0), None);
+        assert_eq!(throttle.update(86.0), Some(400));
+        assert_eq!(throttle.update(90.0), Some(200));
+        assert_eq!(throttle.update(91.0), Some(100));
+        assert_eq!(throttle.update(92.0), None);
+        // Inside the hysteresis band nothing changes
+        assert_eq!(throttle.update(77.0), None);
+        assert_eq!(throttle.update(70.0), Some(200));
+        assert_eq!(throttle.update(70.0), Some(400));
+        assert_eq!(throttle.update(70.0), Some(800));
+        assert_eq!(throttle.update(60.0), None);
+    }
+}
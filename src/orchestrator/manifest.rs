@@ -0,0 +1,413 @@
+//! Reproducible build manifests.
+//!
+//! At the end of the Validation phase a [`BuildManifest`] is written next to
+//! every produced package as `<package>.manifest.json`. It records everything
+//! needed to reproduce the build: the finalized `KernelConfig`, the commit of
+//! the kernel source repository, the toolchain fingerprint, the MPL metadata
+//! (including the applied user patch queue) and the SHA-256 of the final
+//! `.config`.
+//!
+//! A manifest can be fed back into the orchestrator
+//! (`AsyncOrchestrator::set_reference_manifest`): the sources are pinned to the
+//! recorded commit, the recorded config is used as-is, and the rebuilt kernel's
+//! manifest is compared against the reference to flag drift.
+
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use crate::error::Result;
+use crate::kernel::config_diff::parse_config;
+use crate::kernel::git::GitManager;
+use crate::kernel::toolchain::{self, Tool};
+use crate::models::{KernelConfig, MPLMetadata, ToolchainFingerprint};
+
+/// Suffix appended to a package file name to form its manifest path.
+pub const MANIFEST_SUFFIX: &str = ".manifest.json";
+
+/// Manifest format version, bumped on incompatible layout changes.
+///
+/// Version 2 records the probed [`ToolchainFingerprint`] instead of the
+/// toolchain version strings of version 1.
+pub const MANIFEST_FORMAT_VERSION: u32 = 2;
+
+/// A package produced by the build and its checksum.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ManifestArtifact {
+    pub file_name: String,
+    pub sha256: String,
+}
+
+/// Complete record of a build's inputs and its resulting configuration.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct BuildManifest {
+    /// Manifest format version
+    pub format_version: u32,
+
+    /// When the manifest was written (RFC 3339)
+    pub created_at: String,
+
+    /// Finalized kernel configuration the build ran with
+    pub config: KernelConfig,
+
+    /// HEAD commit of the kernel source repository (`None` for non-git trees)
+    pub source_commit: Option<String>,
+
+    /// Toolchain in the build PATH (purified, honouring `.llvm_bin`)
+    pub toolchain: ToolchainFingerprint,
+
+    /// MPL metadata of the build (build id, kernel release, applied patches)
+    pub mpl: Option<MPLMetadata>,
+
+    /// SHA-256 of the normalized final `.config` (see [`hash_kernel_config`])
+    pub config_sha256: Option<String>,
+
+    /// Packages produced by the build
+    pub artifacts: Vec<ManifestArtifact>,
+}
+
+impl BuildManifest {
+    /// Capture the manifest of a finished build in `kernel_path`.
+    pub fn capture(
+        config: &KernelConfig,
+        kernel_path: &Path,
+        artifacts: &[PathBuf],
+    ) -> Result<Self> {
+        let source_commit = GitManager::new(kernel_path)
+            .and_then(|git| git.get_head_commit())
+            .ok();
+
+        let mpl = [
+            kernel_path.join(".goatd_metadata"),
+            kernel_path
+                .parent()
+                .map(|p| p.join(".goatd_metadata"))
+                .unwrap_or_default(),
+        ]
+        .iter()
+        .find_map(|path| MPLMetadata::read_from_file(path).ok());
+
+        let config_sha256 = match find_final_config(kernel_path) {
+            Some(path) => Some(hash_kernel_config(&fs::read_to_string(&path)?)),
+            None => None,
+        };
+
+        let artifacts = artifacts
+            .iter()
+            .filter(|path| path.is_file())
+            .map(|path| {
+                Ok(ManifestArtifact {
+                    file_name: path
+                        .file_name()
+                        .map(|n| n.to_string_lossy().to_string())
+                        .unwrap_or_default(),
+                    sha256: sha256_file(path)?,
+                })
+            })
+            .collect::<Result<Vec<_>>>()?;
+
+        Ok(BuildManifest {
+            format_version: MANIFEST_FORMAT_VERSION,
+            created_at: chrono::Local::now().to_rfc3339(),
+            config: config.clone(),
+            source_commit,
+            toolchain: toolchain::probe_for_source(kernel_path),
+            mpl,
+            config_sha256,
+            artifacts,
+        })
+    }
+
+    /// Manifest path for a package: `<package>.manifest.json`.
+    pub fn path_for_package(package: &Path) -> PathBuf {
+        let mut name = package.as_os_str().to_os_string();
+        name.push(MANIFEST_SUFFIX);
+        PathBuf::from(name)
+    }
+
+    /// Write the manifest next to `package` and return the manifest path.
+    pub fn write_for_package(&self, package: &Path) -> Result<PathBuf> {
+        let path = Self::path_for_package(package);
+        self.write_to(&path)?;
+        Ok(path)
+    }
+
+    /// Write the manifest as pretty-printed JSON.
+    pub fn write_to(&self, path: &Path) -> Result<()> {
+        let json = serde_json::to_string_pretty(self)?;
+        fs::write(path, json)
+            .map_err(|e| format!("Failed to write manifest {}: {}", path.display(), e))?;
+        Ok(())
+    }
+
+    /// Load a manifest from disk.
+    pub fn load(path: &Path) -> Result<Self> {
+        let content = fs::read_to_string(path)
+            .map_err(|e| format!("Failed to read manifest {}: {}", path.display(), e))?;
+        let value: serde_json::Value = serde_json::from_str(&content)?;
+
+        // Check the version before the layout: older manifests don't deserialize
+        let format_version = value.get("format_version").and_then(|v| v.as_u64());
+        if format_version != Some(MANIFEST_FORMAT_VERSION as u64) {
+            return Err(format!(
+                "Unsupported manifest format version {} (expected {})",
+                format_version.map_or_else(|| "<missing>".to_string(), |v| v.to_string()),
+                MANIFEST_FORMAT_VERSION
+            )
+            .into());
+        }
+
+        Ok(serde_json::from_value(value)?)
+    }
+
+    /// The configuration to rebuild this manifest with.
+    ///
+    /// The recorded config is already finalized with a resolved version, so it
+    /// is returned unchanged; the version is never re-resolved to "latest".
+    pub fn rebuild_config(&self) -> KernelConfig {
+        self.config.clone()
+    }
+
+    /// Compare a rebuilt manifest (`actual`) against this reference.
+    pub fn compare(&self, actual: &BuildManifest) -> ManifestDrift {
+        let banner = |fingerprint: &ToolchainFingerprint, tool: Tool| {
+            tool.slot(fingerprint)
+                .as_ref()
+                .map(|info| info.banner.clone())
+        };
+        let toolchain = Tool::ALL
+            .into_iter()
+            .filter_map(|tool| {
+                let expected = banner(&self.toolchain, tool);
+                let found = banner(&actual.toolchain, tool);
+                (expected != found).then(|| ToolchainChange {
+                    tool: tool.binary().to_string(),
+                    expected,
+                    actual: found,
+                })
+            })
+            .collect();
+
+        ManifestDrift {
+            config_hash: (self.config_sha256 != actual.config_sha256).then(|| ValueDrift {
+                expected: self.config_sha256.clone(),
+                actual: actual.config_sha256.clone(),
+            }),
+            source_commit: (self.source_commit != actual.source_commit).then(|| ValueDrift {
+                expected: self.source_commit.clone(),
+                actual: actual.source_commit.clone(),
+            }),
+            toolchain,
+        }
+    }
+}
+
+/// An expected vs. actual value that differs.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct ValueDrift {
+    pub expected: Option<String>,
+    pub actual: Option<String>,
+}
+
+/// A tool whose version differs between two manifests.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct ToolchainChange {
+    pub tool: String,
+    pub expected: Option<String>,
+    pub actual: Option<String>,
+}
+
+/// Differences between a reference manifest and a rebuild.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize)]
+pub struct ManifestDrift {
+    /// Final `.config` hash mismatch: the rebuild is not the same kernel
+    pub config_hash: Option<ValueDrift>,
+    /// Source commit mismatch
+    pub source_commit: Option<ValueDrift>,
+    /// Toolchain version differences (explain, but do not by themselves constitute, drift)
+    pub toolchain: Vec<ToolchainChange>,
+}
+
+impl ManifestDrift {
+    /// Whether the rebuilt kernel's configuration or sources differ from the reference.
+    pub fn is_drifted(&self) -> bool {
+        self.config_hash.is_some() || self.source_commit.is_some()
+    }
+
+    /// Human-readable report, one finding per line.
+    pub fn report(&self) -> Vec<String> {
+        let show = |v: &Option<String>| v.clone().unwrap_or_else(|| "<none>".to_string());
+        let mut lines = Vec::new();
+        if let Some(drift) = &self.config_hash {
+            lines.push(format!(
+                ".config hash: expected {}, got {}",
+                show(&drift.expected),
+                show(&drift.actual)
+            ));
+        }
+        if let Some(drift) = &self.source_commit {
+            lines.push(format!(
+                "source commit: expected {}, got {}",
+                show(&drift.expected),
+                show(&drift.actual)
+            ));
+        }
+        for change in &self.toolchain {
+            lines.push(format!(
+                "{}: expected '{}', got '{}'",
+                change.tool,
+                show(&change.expected),
+                show(&change.actual)
+            ));
+        }
+        lines
+    }
+}
+
+/// Bootloader entry options for `package`: `base` merged with the kernel command
+/// line recorded in its manifest when the build opted into writing it to the
+/// boot entry. Returns `base` unchanged when there is no manifest.
+pub fn boot_entry_options_for_package(package: &Path, base: &str) -> String {
+    match BuildManifest::load(&BuildManifest::path_for_package(package)) {
+        Ok(manifest) => crate::config::cmdline::boot_entry_options(base, &manifest.config),
+        Err(_) => base.to_string(),
+    }
+}
+
+/// Locate the final `.config`: `<kernel_path>/.config`, then `<kernel_path>/src/*/.config`.
+pub fn find_final_config(kernel_path: &Path) -> Option<PathBuf> {
+    let direct = kernel_path.join(".config");
+    if direct.is_file() {
+        return Some(direct);
+    }
+
+    let mut candidates: Vec<PathBuf> = fs::read_dir(kernel_path.join("src"))
+        .ok()?
+        .filter_map(|entry| entry.ok())
+        .map(|entry| entry.path().join(".config"))
+        .filter(|path| path.is_file())
+        .collect();
+    candidates.sort();
+    candidates.into_iter().next()
+}
+
+/// SHA-256 of a `.config` normalized to its sorted `SYMBOL=value` assignments.
+///
+/// Comments (including the generated header with the toolchain banner) and
+/// ordering do not affect the hash; `# CONFIG_X is not set` counts as `X=n`.
+pub fn hash_kernel_config(content: &str) -> String {
+    let mut hasher = Sha256::new();
+    for (symbol, value) in parse_config(content) {
+        hasher.update(symbol.as_bytes());
+        hasher.update(b"=");
+        hasher.update(value.as_bytes());
+        hasher.update(b"\n");
+    }
+    format!("{:x}", hasher.finalize())
+}
+
+/// SHA-256 of a file's contents.
+fn sha256_file(path: &Path) -> Result<String> {
+    let mut file =
+        fs::File::open(path).map_err(|e| format!("Failed to open {}: {}", path.display(), e))?;
+    let mut hasher = Sha256::new();
+    std::io::copy(&mut file, &mut hasher)?;
+    Ok(format!("{:x}", hasher.finalize()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    fn build_tree() -> TempDir {
+        let dir = TempDir::new().unwrap();
+        let src = dir.path().join("src/linux-6.12.1");
+        fs::create_dir_all(&src).unwrap();
+        fs::write(
+            src.join(".config"),
+            "# Linux/x86 6.12.1 Kernel Configuration\nCONFIG_HZ=1000\nCONFIG_LTO_CLANG_THIN=y\n",
+        )
+        .unwrap();
+        fs::write(
+            dir.path().join("linux-goatd-6.12.1-1-x86_64.pkg.tar.zst"),
+            b"package",
+        )
+        .unwrap();
+        dir
+    }
+
+    fn capture(tree: &TempDir) -> BuildManifest {
+        let mut config = KernelConfig::default();
+        config.version = "6.12.1".to_string();
+        let package = tree.path().join("linux-goatd-6.12.1-1-x86_64.pkg.tar.zst");
+        BuildManifest::capture(&config, tree.path(), &[package]).unwrap()
+    }
+
+    #[test]
+    fn test_hash_ignores_comments_and_order() {
+        let a = "# header v1\nCONFIG_A=y\n# CONFIG_B is not set\n";
+        let b = "CONFIG_B=n\n# header v2\nCONFIG_A=y\n";
+        assert_eq!(hash_kernel_config(a), hash_kernel_config(b));
+        assert_ne!(hash_kernel_config(a), hash_kernel_config("CONFIG_A=m\n"));
+    }
+
+    #[test]
+    fn test_capture_write_and_load() {
+        let tree = build_tree();
+        let manifest = capture(&tree);
+
+        assert_eq!(manifest.artifacts.len(), 1);
+        assert_eq!(manifest.artifacts[0].sha256.len(), 64);
+        assert!(manifest.config_sha256.is_some());
+        // Not a git repository
+        assert_eq!(manifest.source_commit, None);
+
+        let package = tree.path().join("linux-goatd-6.12.1-1-x86_64.pkg.tar.zst");
+        let path = manifest.write_for_package(&package).unwrap();
+        assert!(path
+            .to_string_lossy()
+            .ends_with(".pkg.tar.zst.manifest.json"));
+
+        let loaded = BuildManifest::load(&path).unwrap();
+        assert_eq!(loaded.config_sha256, manifest.config_sha256);
+        assert_eq!(loaded.rebuild_config().version, "6.12.1");
+    }
+
+    #[test]
+    fn test_load_rejects_version_1_manifest() {
+        let tree = build_tree();
+        let mut value = serde_json::to_value(capture(&tree)).unwrap();
+        value["format_version"] = 1.into();
+        // Version 1 recorded plain version strings for the toolchain
+        value["toolchain"] = serde_json::json!({ "clang": "clang version 19.1.0" });
+        let path = tree.path().join("old.manifest.json");
+        fs::write(&path, value.to_string()).unwrap();
+
+        let err = BuildManifest::load(&path).unwrap_err().to_string();
+        assert!(err.contains("Unsupported manifest format version 1 (expected 2)"));
+    }
+
+    #[test]
+    fn test_compare_flags_config_drift() {
+        let tree = build_tree();
+        let reference = capture(&tree);
+        assert!(!reference.compare(&capture(&tree)).is_drifted());
+
+        fs::write(
+            tree.path().join("src/linux-6.12.1/.config"),
+            "CONFIG_HZ=300\nCONFIG_LTO_CLANG_THIN=y\n",
+        )
+        .unwrap();
+        let mut rebuilt = capture(&tree);
+        rebuilt.toolchain.clang = toolchain::tool_info("/usr/bin/clang", "clang version 99.0.0\n");
+
+        let drift = reference.compare(&rebuilt);
+        assert!(drift.is_drifted());
+        assert!(drift.config_hash.is_some());
+        assert!(drift.source_commit.is_none());
+        assert!(drift.toolchain.iter().any(|c| c.tool == "clang"));
+        assert!(drift.report()[0].starts_with(".config hash"));
+    }
+}
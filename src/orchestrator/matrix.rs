@@ -0,0 +1,652 @@
+//! Build matrix: several (variant, profile, LTO) builds from one source checkout.
+//!
+//! Each variant is acquired once into its pristine checkout
+//! (`<workspace>/<variant>`); every matrix entry then builds in its own git
+//! worktree of that checkout (`<workspace>/matrix-<label>`). The variant's
+//! sources are downloaded once into the shared SRCDEST before any entry is
+//! configured, so the extraction in configure() and the concurrent makepkg runs
+//! find them there instead of downloading them again. Preparation,
+//! configuration and patching run one entry at a time, the compile phases run
+//! concurrently with the global make job budget split between them, and the
+//! outcome of every build is collected into a [`MatrixSummary`].
+
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+
+use serde::{Deserialize, Serialize};
+use tokio::sync::Semaphore;
+
+use super::{AsyncOrchestrator, BuildPhaseState};
+use crate::error::Result;
+use crate::kernel::git::GitManager;
+use crate::kernel::patcher::KernelPatcher;
+use crate::models::{BuildResult, HardwareInfo, KernelConfig, LtoType};
+use crate::ui::controller::BuildEvent;
+use crate::LogCollector;
+
+/// Summary file written into the workspace after a matrix run
+pub const SUMMARY_FILE: &str = "matrix-summary.json";
+
+/// One build of the matrix
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct MatrixEntry {
+    pub variant: String,
+    pub profile: String,
+    pub lto: LtoType,
+}
+
+impl MatrixEntry {
+    /// Parse `variant:profile[:lto]`; a missing LTO mode takes `default_lto`
+    pub fn parse(spec: &str, default_lto: LtoType) -> std::result::Result<Self, String> {
+        let parts: Vec<&str> = spec.split(':').map(str::trim).collect();
+        let (variant, profile, lto) = match parts.as_slice() {
+            [variant, profile] => (*variant, *profile, default_lto),
+            [variant, profile, lto] => {
+                let lto = match lto.to_ascii_lowercase().as_str() {
+                    "none" => LtoType::None,
+                    "thin" => LtoType::Thin,
+                    "full" => LtoType::Full,
+                    other => return Err(format!("Unknown LTO mode '{}' in '{}'", other, spec)),
+                };
+                (*variant, *profile, lto)
+            }
+            _ => {
+                return Err(format!(
+                    "Matrix entry '{}' must be variant:profile[:lto]",
+                    spec
+                ))
+            }
+        };
+        if variant.is_empty() || profile.is_empty() {
+            return Err(format!(
+                "Matrix entry '{}' needs a variant and a profile",
+                spec
+            ));
+        }
+        Ok(MatrixEntry {
+            variant: variant.to_string(),
+            profile: profile.to_string(),
+            lto,
+        })
+    }
+
+    /// Path-safe name of the build, e.g. `linux-gaming-thin`
+    pub fn label(&self) -> String {
+        let lto = match self.lto {
+            LtoType::None => "none",
+            LtoType::Thin => "thin",
+            LtoType::Full => "full",
+        };
+        format!("{}-{}-{}", self.variant, self.profile, lto)
+            .to_ascii_lowercase()
+            .chars()
+            .map(|c| if c.is_ascii_alphanumeric() { c } else { '-' })
+            .collect()
+    }
+
+    /// The base config with this entry's variant, profile, LTO and job share applied
+    fn config(&self, base: &KernelConfig, jobs: usize) -> KernelConfig {
+        let mut config = base.clone();
+        config.kernel_variant = self.variant.clone();
+        config.profile = self.profile.clone();
+        config.lto_type = self.lto;
+        config.user_toggled_lto = true;
+        config.build_jobs = Some(jobs);
+        config
+    }
+}
+
+/// make jobs per build when `concurrent` builds share a budget of `budget` jobs
+pub fn split_job_budget(budget: usize, concurrent: usize) -> usize {
+    (budget / concurrent.max(1)).max(1)
+}
+
+/// Result of one matrix build
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct MatrixBuildOutcome {
+    pub entry: MatrixEntry,
+    pub kernel_path: PathBuf,
+    pub success: bool,
+    pub error: Option<String>,
+    pub artifacts: Vec<PathBuf>,
+    pub result: Option<BuildResult>,
+    pub duration_secs: u64,
+}
+
+impl MatrixBuildOutcome {
+    fn failed(entry: &MatrixEntry, kernel_path: &Path, error: String, started: Instant) -> Self {
+        MatrixBuildOutcome {
+            entry: entry.clone(),
+            kernel_path: kernel_path.to_path_buf(),
+            success: false,
+            error: Some(error),
+            artifacts: Vec::new(),
+            result: None,
+            duration_secs: started.elapsed().as_secs(),
+        }
+    }
+}
+
+/// Combined outcome of a matrix run, in entry order
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct MatrixSummary {
+    pub outcomes: Vec<MatrixBuildOutcome>,
+}
+
+impl MatrixSummary {
+    pub fn all_succeeded(&self) -> bool {
+        self.outcomes.iter().all(|outcome| outcome.success)
+    }
+
+    /// Human-readable report: one line per build, then its artifacts
+    pub fn report(&self) -> Vec<String> {
+        let succeeded = self.outcomes.iter().filter(|o| o.success).count();
+        let mut lines = vec![format!(
+            "Build matrix: {}/{} builds succeeded",
+            succeeded,
+            self.outcomes.len()
+        )];
+        for outcome in &self.outcomes {
+            let elapsed = Duration::from_secs(outcome.duration_secs);
+            let minutes = elapsed.as_secs() / 60;
+            let seconds = elapsed.as_secs() % 60;
+            if outcome.success {
+                let version = outcome
+                    .result
+                    .as_ref()
+                    .map(|r| r.kernel_version.as_str())
+                    .unwrap_or("?");
+                lines.push(format!(
+                    "  ✓ {} ({}) in {}m{:02}s",
+                    outcome.entry.label(),
+                    version,
+                    minutes,
+                    seconds
+                ));
+                for artifact in &outcome.artifacts {
+                    lines.push(format!("      {}", artifact.display()));
+                }
+            } else {
+                lines.push(format!(
+                    "  ✗ {} after {}m{:02}s: {}",
+                    outcome.entry.label(),
+                    minutes,
+                    seconds,
+                    outcome.error.as_deref().unwrap_or("unknown error")
+                ));
+            }
+        }
+        lines
+    }
+
+    /// Write the summary as JSON
+    pub fn save(&self, path: &Path) -> std::io::Result<()> {
+        let json = serde_json::to_string_pretty(self)
+            .map_err(|e| std::io::Error::new(std::io::ErrorKind::InvalidData, e))?;
+        std::fs::write(path, json)
+    }
+}
+
+/// Several builds of shared source checkouts with a global job budget
+#[derive(Debug, Clone)]
+pub struct BuildMatrix {
+    pub base: KernelConfig,
+    pub entries: Vec<MatrixEntry>,
+    pub workspace: PathBuf,
+    /// Total make jobs across all concurrently running builds
+    pub job_budget: usize,
+    /// Builds compiling at the same time
+    pub max_parallel: usize,
+}
+
+impl BuildMatrix {
+    /// A matrix running every entry at once on all CPUs
+    pub fn new(base: KernelConfig, entries: Vec<MatrixEntry>, workspace: PathBuf) -> Self {
+        let max_parallel = entries.len().max(1);
+        BuildMatrix {
+            base,
+            entries,
+            workspace,
+            job_budget: num_cpus::get(),
+            max_parallel,
+        }
+    }
+
+    /// Pristine checkout shared by all entries of a variant
+    pub fn pristine_path(&self, variant: &str) -> PathBuf {
+        self.workspace.join(variant)
+    }
+
+    /// Worktree an entry builds in
+    pub fn worktree_path(&self, entry: &MatrixEntry) -> PathBuf {
+        self.workspace.join(format!("matrix-{}", entry.label()))
+    }
+
+    /// make jobs each build gets
+    pub fn jobs_per_build(&self) -> usize {
+        split_job_budget(self.job_budget, self.max_parallel.min(self.entries.len()))
+    }
+
+    /// Run every build and return the combined summary
+    ///
+    /// A failing build does not stop the others; the summary is also written
+    /// to `<workspace>/matrix-summary.json`.
+    pub async fn run(
+        &self,
+        hardware: HardwareInfo,
+        build_tx: Option<tokio::sync::mpsc::Sender<BuildEvent>>,
+        cancel_rx: tokio::sync::watch::Receiver<bool>,
+        log_collector: Option<Arc<LogCollector>>,
+    ) -> Result<MatrixSummary> {
+        if self.entries.is_empty() {
+            return Err("Build matrix has no entries".into());
+        }
+        let jobs = self.jobs_per_build();
+        eprintln!(
+            "[Build] [MATRIX] {} builds, {} at a time, {} make jobs each (budget {})",
+            self.entries.len(),
+            self.max_parallel,
+            jobs,
+            self.job_budget
+        );
+        let checkpoint_root = self.workspace.join(".checkpoints").join("matrix");
+
+        // STAGE 1: one pristine source checkout per variant, with its sources
+        // downloaded into the shared SRCDEST
+        let mut source_errors: HashMap<String, String> = HashMap::new();
+        let mut acquired_variants: Vec<&str> = Vec::new();
+        for entry in &self.entries {
+            if acquired_variants.contains(&entry.variant.as_str()) {
+                continue;
+            }
+            acquired_variants.push(&entry.variant);
+            let pristine = self.pristine_path(&entry.variant);
+            eprintln!(
+                "[Build] [MATRIX] Acquiring {} sources into {}",
+                entry.variant,
+                pristine.display()
+            );
+            let acquired = async {
+                let orch = AsyncOrchestrator::new(
+                    hardware.clone(),
+                    entry.config(&self.base, jobs),
+                    checkpoint_root.join(format!("source-{}", entry.variant)),
+                    pristine.clone(),
+                    build_tx.clone(),
+                    cancel_rx.clone(),
+                    log_collector.clone(),
+                    None,
+                    None,
+                )
+                .await?;
+                orch.acquire_sources(&entry.variant).await
+            }
+            .await;
+            match acquired {
+                Ok(()) => prefetch_sources(&pristine).await,
+                Err(e) => {
+                    eprintln!(
+                        "[Build] [MATRIX] ✗ Source acquisition failed for {}: {}",
+                        entry.variant, e
+                    );
+                    source_errors.insert(entry.variant.clone(), e.to_string());
+                }
+            }
+        }
+
+        // STAGE 2: worktree + preparation/configuration/patching, one entry at a time
+        let mut outcomes: Vec<Option<MatrixBuildOutcome>> = vec![None; self.entries.len()];
+        let mut ready: Vec<(usize, AsyncOrchestrator, Instant)> = Vec::new();
+        for (index, entry) in self.entries.iter().enumerate() {
+            let started = Instant::now();
+            let worktree = self.worktree_path(entry);
+            if *cancel_rx.borrow() {
+                outcomes[index] = Some(MatrixBuildOutcome::failed(
+                    entry,
+                    &worktree,
+                    "Cancelled".to_string(),
+                    started,
+                ));
+                continue;
+            }
+            if let Some(error) = source_errors.get(&entry.variant) {
+                outcomes[index] = Some(MatrixBuildOutcome::failed(
+                    entry,
+                    &worktree,
+                    format!("Source acquisition failed: {}", error),
+                    started,
+                ));
+                continue;
+            }
+
+            eprintln!(
+                "[Build] [MATRIX] Preparing {} in {}",
+                entry.label(),
+                worktree.display()
+            );
+            match self
+                .prepare_entry(
+                    entry,
+                    &worktree,
+                    jobs,
+                    &hardware,
+                    &build_tx,
+                    &cancel_rx,
+                    &log_collector,
+                    &checkpoint_root,
+                )
+                .await
+            {
+                Ok(orch) => ready.push((index, orch, started)),
+                Err(e) => {
+                    eprintln!(
+                        "[Build] [MATRIX] ✗ {} failed before building: {}",
+                        entry.label(),
+                        e
+                    );
+                    outcomes[index] = Some(MatrixBuildOutcome::failed(
+                        entry,
+                        &worktree,
+                        e.to_string(),
+                        started,
+                    ));
+                }
+            }
+        }
+
+        // STAGE 3: compile concurrently, at most `max_parallel` at a time. The
+        // builds are driven together on this task: their work happens in the
+        // makepkg child processes, so polling them side by side is enough.
+        let permits = Arc::new(Semaphore::new(self.max_parallel.max(1)));
+        let builds = ready.into_iter().map(|(index, orch, started)| {
+            let permits = permits.clone();
+            let entry = self.entries[index].clone();
+            let worktree = self.worktree_path(&entry);
+            async move {
+                let _permit = permits.acquire_owned().await;
+                eprintln!("[Build] [MATRIX] Building {}", entry.label());
+                let finished = async {
+                    orch.build().await?;
+                    orch.validate().await
+                }
+                .await;
+                let outcome = match finished {
+                    Ok(()) => MatrixBuildOutcome {
+                        artifacts: KernelPatcher::new(worktree.clone())
+                            .find_build_artifacts()
+                            .unwrap_or_default(),
+                        kernel_path: worktree,
+                        success: true,
+                        error: None,
+                        result: Some(orch.build_result().await),
+                        duration_secs: started.elapsed().as_secs(),
+                        entry,
+                    },
+                    Err(e) => {
+                        let mut outcome =
+                            MatrixBuildOutcome::failed(&entry, &worktree, e.to_string(), started);
+                        outcome.result = Some(orch.build_result().await);
+                        outcome
+                    }
+                };
+                eprintln!(
+                    "[Build] [MATRIX] {} {}",
+                    if outcome.success { "✓" } else { "✗" },
+                    outcome.entry.label()
+                );
+                (index, outcome)
+            }
+        });
+        for (index, outcome) in futures::future::join_all(builds).await {
+            outcomes[index] = Some(outcome);
+        }
+
+        let summary = MatrixSummary {
+            outcomes: outcomes.into_iter().flatten().collect(),
+        };
+        if let Err(e) = summary.save(&self.workspace.join(SUMMARY_FILE)) {
+            eprintln!("[Build] [MATRIX] ⚠ Failed to write matrix summary: {}", e);
+        }
+        Ok(summary)
+    }
+
+    /// Check an entry's worktree out of the pristine checkout and run it up to the Building phase
+    #[allow(clippy::too_many_arguments)]
+    async fn prepare_entry(
+        &self,
+        entry: &MatrixEntry,
+        worktree: &Path,
+        jobs: usize,
+        hardware: &HardwareInfo,
+        build_tx: &Option<tokio::sync::mpsc::Sender<BuildEvent>>,
+        cancel_rx: &tokio::sync::watch::Receiver<bool>,
+        log_collector: &Option<Arc<LogCollector>>,
+        checkpoint_root: &Path,
+    ) -> Result<AsyncOrchestrator> {
+        GitManager::new(self.pristine_path(&entry.variant))
+            .and_then(|pristine| {
+                pristine.add_worktree(&format!("matrix-{}", entry.label()), worktree)
+            })
+            .map_err(|e| format!("Failed to create worktree: {}", e))?;
+
+        let orch = AsyncOrchestrator::new(
+            hardware.clone(),
+            entry.config(&self.base, jobs),
+            checkpoint_root.join(entry.label()),
+            worktree.to_path_buf(),
+            build_tx.clone(),
+            cancel_rx.clone(),
+            log_collector.clone(),
+            None,
+            None,
+        )
+        .await?;
+
+        loop {
+            match orch.current_phase().await {
+                BuildPhaseState::Preparation => orch.prepare().await?,
+                BuildPhaseState::Configuration => orch.configure().await?,
+                BuildPhaseState::Patching => orch.patch().await?,
+                BuildPhaseState::Building => return Ok(orch),
+                other => return Err(format!("Unexpected phase {:?}", other).into()),
+            }
+        }
+    }
+}
+
+/// Download (and verify) a PKGBUILD's sources into the shared SRCDEST
+///
+/// Non-fatal: on failure each build downloads its sources itself.
+async fn prefetch_sources(kernel_path: &Path) {
+    if !kernel_path.join("PKGBUILD").exists() {
+        return;
+    }
+    let srcdest = crate::kernel::patcher::env::source_cache_dir();
+    let _ = std::fs::create_dir_all(&srcdest);
+    eprintln!(
+        "[Build] [MATRIX] Prefetching sources of {} into {}",
+        kernel_path.display(),
+        srcdest
+    );
+    match tokio::process::Command::new("makepkg")
+        .args(["--verifysource", "--noconfirm"])
+        .current_dir(kernel_path)
+        .env("SRCDEST", &srcdest)
+        .status()
+        .await
+    {
+        Ok(status) if status.success() => {
+            eprintln!("[Build] [MATRIX] ✓ Sources prefetched");
+        }
+        Ok(status) => {
+            eprintln!("[Build] [MATRIX] ⚠ Source prefetch exited with {}", status);
+        }
+        Err(e) => {
+            eprintln!(
+                "[Build] [MATRIX] ⚠ Failed to run makepkg --verifysource: {}",
+                e
+            );
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_matrix_entry() {
+        assert_eq!(
+            MatrixEntry::parse("linux:gaming:full", LtoType::Thin).unwrap(),
+            MatrixEntry {
+                variant: "linux".to_string(),
+                profile: "gaming".to_string(),
+                lto: LtoType::Full,
+            }
+        );
+        assert_eq!(
+            MatrixEntry::parse("linux-lts:Server", LtoType::None)
+                .unwrap()
+                .lto,
+            LtoType::None
+        );
+        assert!(MatrixEntry::parse("linux", LtoType::Thin).is_err());
+        assert!(MatrixEntry::parse("linux::thin", LtoType::Thin).is_err());
+        assert!(MatrixEntry::parse("linux:gaming:fat", LtoType::Thin).is_err());
+        assert!(MatrixEntry::parse("linux:gaming:thin:x", LtoType::Thin).is_err());
+    }
+
+    #[test]
+    fn test_labels_and_paths() {
+        let entry = MatrixEntry::parse("linux-zen:Workstation", LtoType::Thin).unwrap();
+        assert_eq!(entry.label(), "linux-zen-workstation-thin");
+
+        let matrix = BuildMatrix::new(
+            KernelConfig::default(),
+            vec![entry.clone()],
+            PathBuf::from("/ws"),
+        );
+        assert_eq!(
+            matrix.pristine_path("linux-zen"),
+            PathBuf::from("/ws/linux-zen")
+        );
+        assert_eq!(
+            matrix.worktree_path(&entry),
+            PathBuf::from("/ws/matrix-linux-zen-workstation-thin")
+        );
+
+        let config = entry.config(&KernelConfig::default(), 6);
+        assert_eq!(config.kernel_variant, "linux-zen");
+        assert_eq!(config.profile, "Workstation");
+        assert_eq!(config.build_jobs, Some(6));
+        assert!(config.user_toggled_lto);
+    }
+
+    #[test]
+    fn test_job_budget_split() {
+        assert_eq!(split_job_budget(24, 3), 8);
+        assert_eq!(split_job_budget(16, 3), 5);
+        assert_eq!(split_job_budget(2, 4), 1);
+        assert_eq!(split_job_budget(8, 0), 8);
+
+        let entries = vec![
+            MatrixEntry::parse("linux:gaming", LtoType::Thin).unwrap(),
+            MatrixEntry::parse("linux:server", LtoType::Thin).unwrap(),
+            MatrixEntry::parse("linux:workstation", LtoType::Full).unwrap(),
+        ];
+        let mut matrix = BuildMatrix::new(KernelConfig::default(), entries, PathBuf::from("/ws"));
+        matrix.job_budget = 24;
+        assert_eq!(matrix.jobs_per_build(), 8);
+        matrix.max_parallel = 2;
+        assert_eq!(matrix.jobs_per_build(), 12);
+    }
+
+    #[test]
+    fn test_worktrees_share_the_pristine_checkout() {
+        use git2::{Repository, Signature};
+
+        let temp = tempfile::tempdir().unwrap();
+        let pristine = temp.path().join("linux");
+        let repo = Repository::init(&pristine).unwrap();
+        std::fs::write(pristine.join("PKGBUILD"), "pkgver=6.12.1\n").unwrap();
+        let mut index = repo.index().unwrap();
+        index.add_path(Path::new("PKGBUILD")).unwrap();
+        let tree = repo.find_tree(index.write_tree().unwrap()).unwrap();
+        let sig = Signature::now("GOATd", "goatd@example.com").unwrap();
+        repo.commit(Some("HEAD"), &sig, &sig, "init", &tree, &[])
+            .unwrap();
+        // Local edits of the pristine checkout must not leak into worktrees
+        std::fs::write(pristine.join("PKGBUILD"), "pkgver=patched\n").unwrap();
+
+        let matrix = BuildMatrix::new(
+            KernelConfig::default(),
+            vec![
+                MatrixEntry::parse("linux:gaming", LtoType::Thin).unwrap(),
+                MatrixEntry::parse("linux:server", LtoType::Full).unwrap(),
+            ],
+            temp.path().to_path_buf(),
+        );
+        let git = GitManager::new(&pristine).unwrap();
+        for entry in &matrix.entries {
+            let path = matrix.worktree_path(entry);
+            git.add_worktree(&format!("matrix-{}", entry.label()), &path)
+                .unwrap();
+            assert_eq!(
+                std::fs::read_to_string(path.join("PKGBUILD")).unwrap(),
+                "pkgver=6.12.1\n"
+            );
+            // Re-running replaces the stale worktree
+            std::fs::write(path.join("PKGBUILD"), "pkgver=dirty\n").unwrap();
+            git.add_worktree(&format!("matrix-{}", entry.label()), &path)
+                .unwrap();
+            assert_eq!(
+                std::fs::read_to_string(path.join("PKGBUILD")).unwrap(),
+                "pkgver=6.12.1\n"
+            );
+        }
+        assert_eq!(repo.worktrees().unwrap().len(), 2);
+    }
+
+    #[test]
+    fn test_summary_report() {
+        let entry = MatrixEntry::parse("linux:gaming", LtoType::Thin).unwrap();
+        let failed = MatrixEntry::parse("linux:server", LtoType::Full).unwrap();
+        let summary = MatrixSummary {
+            outcomes: vec![
+                MatrixBuildOutcome {
+                    entry: entry.clone(),
+                    kernel_path: PathBuf::from("/ws/matrix-linux-gaming-thin"),
+                    success: true,
+                    error: None,
+                    artifacts: vec![PathBuf::from(
+                        "/ws/matrix-linux-gaming-thin/linux.pkg.tar.zst",
+                    )],
+                    result: None,
+                    duration_secs: 725,
+                },
+                MatrixBuildOutcome::failed(
+                    &failed,
+                    Path::new("/ws/matrix-linux-server-full"),
+                    "Build failed".to_string(),
+                    Instant::now(),
+                ),
+            ],
+        };
+        assert!(!summary.all_succeeded());
+        let report = summary.report();
+        assert_eq!(report[0], "Build matrix: 1/2 builds succeeded");
+        assert_eq!(report[1], "  ✓ linux-gaming-thin (?) in 12m05s");
+        assert!(report[2].ends_with("linux.pkg.tar.zst"));
+        assert_eq!(report[3], "  ✗ linux-server-full after 0m00s: Build failed");
+
+        let temp = tempfile::tempdir().unwrap();
+        let path = temp.path().join(SUMMARY_FILE);
+        summary.save(&path).unwrap();
+        let loaded: MatrixSummary =
+            serde_json::from_str(&std::fs::read_to_string(path).unwrap()).unwrap();
+        assert_eq!(loaded.outcomes.len(), 2);
+        assert_eq!(loaded.outcomes[1].error.as_deref(), Some("Build failed"));
+    }
+}
@@ -2,10 +2,15 @@
 
 pub mod checkpoint;
 pub mod executor;
+pub mod failure;
+pub mod governor;
+pub mod manifest;
+pub mod matrix;
 pub mod phases;
+pub mod queue;
 pub mod state;
 
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
 use std::sync::Arc;
 use std::time::Duration;
 use tokio::sync::RwLock;
@@ -17,10 +22,22 @@ pub use executor::{
 
 pub use phases::prepare_build_environment;
 
+pub use checkpoint::{BuildCheckpoint, CheckpointManager};
+
+pub use manifest::{BuildManifest, ManifestDrift};
+
+pub use matrix::{BuildMatrix, MatrixEntry, MatrixSummary};
+
+pub use queue::{BuildQueue, BuildSchedule, QueueStatus, QueuedBuild};
+
 pub use state::{BuildPhaseState, OrchestrationState};
 
 use crate::error::Result;
-use crate::models::{HardwareInfo, KernelConfig};
+use crate::kernel::compiler_cache::{self, CompilerCacheSession};
+use crate::kernel::signing::ModuleSigningPem;
+use crate::models::{
+    BuildResult, CompilerCacheStats, HardwareInfo, KernelConfig, LtoType, ToolchainFingerprint,
+};
 use crate::LogCollector;
 use eframe::egui;
 
@@ -39,6 +56,10 @@ pub struct AsyncOrchestrator {
     /// Whether recovery from previous checkpoint is enabled
     recovery_enabled: bool,
 
+    /// Created by resume(): a tree that was already compiling is built on
+    /// without re-extracting it
+    resumed: bool,
+
     /// Channel for sending build events to UI
     build_tx: Option<tokio::sync::mpsc::Sender<crate::ui::controller::BuildEvent>>,
 
@@ -53,6 +74,21 @@ pub struct AsyncOrchestrator {
 
     /// Optional egui context handle for requesting repaints from background threads
     ctx_handle: Option<egui::Context>,
+
+    /// Manifest being rebuilt: pins the source commit and is compared after validation
+    reference_manifest: Option<Arc<BuildManifest>>,
+
+    /// Drift of the rebuilt kernel against `reference_manifest` (set by validate())
+    manifest_drift: Arc<RwLock<Option<ManifestDrift>>>,
+
+    /// ccache/sccache statistics of the last build() (None if the cache is disabled)
+    compiler_cache_stats: Arc<RwLock<Option<CompilerCacheStats>>>,
+
+    /// Toolchain versions probed during configure()
+    toolchain: Arc<RwLock<Option<ToolchainFingerprint>>>,
+
+    /// Private copy of the MOK key for CONFIG_MODULE_SIG_KEY (deleted when build() ends)
+    module_signing_pem: Arc<RwLock<Option<ModuleSigningPem>>>,
 }
 
 impl AsyncOrchestrator {
@@ -127,11 +163,17 @@ impl AsyncOrchestrator {
             checkpoint_dir,
             kernel_path,
             recovery_enabled: true,
+            resumed: false,
             build_tx,
             cancel_rx,
             log_collector,
             test_timeout,
             ctx_handle,
+            reference_manifest: None,
+            manifest_drift: Arc::new(RwLock::new(None)),
+            compiler_cache_stats: Arc::new(RwLock::new(None)),
+            toolchain: Arc::new(RwLock::new(None)),
+            module_signing_pem: Arc::new(RwLock::new(None)),
         })
     }
 
@@ -176,6 +218,13 @@ impl AsyncOrchestrator {
     pub async fn transition_phase(&self, next_phase: BuildPhaseState) -> Result<()> {
         let mut state = self.state.write().await;
         state.transition_to(next_phase)?;
+        let snapshot = state.clone();
+        drop(state);
+
+        // Persist a checkpoint so an interrupted build can resume from this phase
+        if self.recovery_enabled {
+            self.persist_checkpoint(snapshot).await;
+        }
 
         // Emit PhaseChanged event to UI channel
         if let Some(ref tx) = self.build_tx {
@@ -196,6 +245,159 @@ impl AsyncOrchestrator {
         Ok(())
     }
 
+    /// Write (or, once the build completes, remove) the build checkpoint.
+    ///
+    /// Fingerprinting walks the extracted tree, so it runs on a blocking thread
+    /// and without the state lock held. Checkpoint failures are logged but never
+    /// fail the build.
+    async fn persist_checkpoint(&self, state: OrchestrationState) {
+        let manager = CheckpointManager::new(self.checkpoint_dir.clone());
+        let kernel_path = self.kernel_path.clone();
+        let result = tokio::task::spawn_blocking(move || {
+            match state.phase {
+                BuildPhaseState::Completed => manager.clear(),
+                BuildPhaseState::Failed => Ok(()),
+                _ => manager.save(&state, &kernel_path).map(|_| ()),
+            }
+            .map_err(|e| e.to_string())
+        })
+        .await
+        .unwrap_or_else(|e| Err(format!("checkpoint task failed: {}", e)));
+
+        if let Err(e) = result {
+            eprintln!("[Build] [CHECKPOINT] ⚠ Failed to persist checkpoint: {}", e);
+        }
+    }
+
+    /// Re-fingerprint the Building checkpoint after a failed compile, so a
+    /// resume accepts the tree prepare() and the compile left behind.
+    async fn refresh_building_checkpoint(&self) {
+        let snapshot = self.state.read().await.clone();
+        if self.recovery_enabled && snapshot.phase == BuildPhaseState::Building {
+            self.persist_checkpoint(snapshot).await;
+        }
+    }
+
+    /// Extracted kernel tree a resumed build can keep compiling in: one whose
+    /// compile already started (kbuild wrote `include/config/auto.conf`, which
+    /// happens only after prepare() finished).
+    fn compiling_source_root(&self) -> Option<PathBuf> {
+        if !self.resumed || !self.kernel_path.join("PKGBUILD").exists() {
+            return None;
+        }
+        crate::kernel::kconfig_tree::locate_source_root(&self.kernel_path)
+            .filter(|root| root.join("include/config/auto.conf").is_file())
+    }
+
+    /// Apply the profile's user patch queue.
+    ///
+    /// A bare kernel tree is dry-run and patched right here, and the queue is
+    /// recorded in the MPL metadata. For PKGBUILD builds the queue is staged for
+    /// prepare() to apply before "Setting config...", after a dry run against
+    /// the tree `makepkg --nobuild` extracted; it is recorded once the build has
+    /// applied it. Conflicts fail the Patching phase.
+    async fn apply_user_patch_queue(&self, config: &KernelConfig) -> Result<()> {
+        use crate::kernel::git::GitManager;
+        use crate::kernel::patch_queue::{PatchQueue, STAGED_QUEUE_DIR};
+        use crate::kernel::patcher::KernelPatcher;
+
+        let workspace = self.kernel_path.parent().unwrap_or(&self.kernel_path);
+        let queue = PatchQueue::for_profile(workspace, &config.profile)?;
+        let is_pkgbuild_build = self.kernel_path.join("PKGBUILD").exists();
+        let previously_staged = self.kernel_path.join(STAGED_QUEUE_DIR).exists();
+
+        let applied = if queue.is_empty() && !previously_staged {
+            eprintln!(
+                "[Build] [PATCH-QUEUE] No user patches in {}",
+                queue.dir().display()
+            );
+            Vec::new()
+        } else if is_pkgbuild_build {
+            if !queue.is_empty() {
+                let tree = self.extracted_source_root().await?;
+                queue.check_extracted(&GitManager::for_source_tree(&tree))?;
+                eprintln!(
+                    "[Build] [PATCH-QUEUE] ✓ {} patch(es) apply cleanly to {}",
+                    queue.len(),
+                    tree.display()
+                );
+            }
+            // Restaging an empty queue also neutralizes a snippet left by an earlier build
+            let staged = queue.stage(&self.kernel_path)?;
+            KernelPatcher::new(self.kernel_path.clone()).inject_patch_queue(&staged)?;
+            eprintln!(
+                "[Build] [PATCH-QUEUE] Staged {} patch(es) for prepare(): {}",
+                queue.len(),
+                staged.display()
+            );
+            // Nothing is applied until prepare() runs; see record_staged_patches()
+            Vec::new()
+        } else {
+            queue.apply(&GitManager::for_source_tree(&self.kernel_path))?
+        };
+        self.record_applied_patches(applied)?;
+
+        if !queue.is_empty() {
+            self.send_log_event(format!(
+                "User patch queue ({}): {}",
+                config.profile,
+                queue
+                    .patches()
+                    .iter()
+                    .map(|p| p.name.as_str())
+                    .collect::<Vec<_>>()
+                    .join(", ")
+            ))
+            .await;
+        }
+
+        Ok(())
+    }
+
+    /// Kernel tree extracted from the PKGBUILD's sources, extracting it first
+    /// if no earlier phase has.
+    async fn extracted_source_root(&self) -> Result<PathBuf> {
+        use crate::kernel::kconfig_tree::locate_source_root;
+
+        if let Some(root) = locate_source_root(&self.kernel_path) {
+            return Ok(root);
+        }
+        self.extract_sources().await?;
+        locate_source_root(&self.kernel_path).ok_or_else(|| {
+            format!(
+                "No kernel tree found in {} after extracting sources",
+                self.kernel_path.join("src").display()
+            )
+            .into()
+        })
+    }
+
+    /// Record the staged user patch queue once a build has applied it in prepare().
+    fn record_staged_patches(&self) -> Result<()> {
+        use crate::kernel::patch_queue::{PatchQueue, STAGED_QUEUE_DIR};
+
+        let staged = self.kernel_path.join(STAGED_QUEUE_DIR);
+        if !staged.exists() {
+            return Ok(());
+        }
+        self.record_applied_patches(PatchQueue::load(&staged)?.mpl_entries())
+    }
+
+    /// Write the user patches present in the built tree to the MPL metadata.
+    fn record_applied_patches(&self, applied: Vec<String>) -> Result<()> {
+        use crate::models::MPLMetadata;
+
+        let mpl_path = self.kernel_path.join(".goatd_metadata");
+        let mut mpl = MPLMetadata::read_from_file(&mpl_path).unwrap_or_default();
+        if mpl.applied_patches != applied {
+            mpl.applied_patches = applied;
+            let temp_path = mpl_path.with_extension("tmp");
+            mpl.write_to_file(&temp_path)?;
+            std::fs::rename(&temp_path, &mpl_path)?;
+        }
+        Ok(())
+    }
+
     /// Record a patch application result.
     pub async fn record_patch_result(&self, success: bool) {
         self.state.write().await.record_patch_applied(success);
@@ -294,6 +496,106 @@ impl AsyncOrchestrator {
             state_mut.config.kernel_variant = kernel_variant.clone();
         }
 
+        self.acquire_sources(&kernel_variant).await?;
+
+        self.pin_source_commit().await?;
+
+        // =========================================================================
+        // CLEANUP OLD ARTIFACTS - Delegate to KernelPatcher
+        // =========================================================================
+        // Use Patcher's cleanup method for old .pkg.tar.zst files
+        use crate::kernel::patcher::KernelPatcher;
+        let patcher = KernelPatcher::new(self.kernel_path.clone());
+        let _ = patcher.cleanup_previous_artifacts();
+
+        // =========================================================================
+        // INITIALIZE MODPROBED-DB - Database for module detection
+        // =========================================================================
+        {
+            match tokio::process::Command::new("which")
+                .arg("modprobed-db")
+                .status()
+                .await
+            {
+                Ok(status) if status.success() => {
+                    // modprobed-db is available, initialize the database
+                    self.send_log_event("Initializing modprobed-db database...".to_string())
+                        .await;
+                    eprintln!("[Build] [MODPROBED] Starting modprobed-db initialization");
+
+                    // First execution: create/initialize the database
+                    match tokio::process::Command::new("modprobed-db")
+                        .arg("store")
+                        .status()
+                        .await
+                    {
+                        Ok(status) if status.success() => {
+                            eprintln!("[Build] [MODPROBED] ✓ First modprobed-db store completed");
+
+                            // Wait 500ms before second run
+                            tokio::time::sleep(tokio::time::Duration::from_millis(500)).await;
+
+                            // Second execution: populate/update the database
+                            match tokio::process::Command::new("modprobed-db")
+                                .arg("store")
+                                .status()
+                                .await
+                            {
+                                Ok(status) if status.success() => {
+                                    eprintln!(
+                                        "[Build] [MODPROBED] ✓ Second modprobed-db store completed"
+                                    );
+                                    self.send_status(
+                                        "modprobed-db database initialized successfully"
+                                            .to_string(),
+                                    )
+                                    .await;
+                                }
+                                _ => {
+                                    eprintln!("[Build] [MODPROBED] ⚠ Second modprobed-db store failed, continuing anyway");
+                                    // Non-fatal: continue with build
+                                }
+                            }
+                        }
+                        _ => {
+                            eprintln!("[Build] [MODPROBED] ⚠ First modprobed-db store failed, continuing anyway");
+                            // Non-fatal: continue with build
+                        }
+                    }
+                }
+                _ => {
+                    eprintln!(
+                        "[Build] [MODPROBED] ⚠ modprobed-db not found, skipping initialization"
+                    );
+                    self.send_log_event(
+                        "modprobed-db not installed - skipping database initialization".to_string(),
+                    )
+                    .await;
+                    // Non-fatal: continue with build
+                }
+            }
+        }
+
+        // =========================================================================
+        // PHASE 1b: HARDWARE VALIDATION - After source acquisition, validate hardware
+        // =========================================================================
+        // Validate hardware meets minimum requirements and kernel source exists
+        phases::prepare_build_environment(&hardware, &self.kernel_path)?;
+
+        // Update progress: Preparation phase is 0-5%
+        let progress = 5;
+        self.set_progress(progress).await;
+        eprintln!("[Build] [PROGRESS] Preparation complete: {}%", progress);
+
+        // Transition to Configuration phase
+        self.transition_phase(BuildPhaseState::Configuration).await
+    }
+
+    /// Ensures the kernel sources of `kernel_variant` are checked out at the kernel path.
+    ///
+    /// Clones missing sources (from the offline mirror if enabled) and purges and
+    /// re-clones a checkout whose version no longer matches the variant's latest.
+    pub async fn acquire_sources(&self, kernel_variant: &str) -> Result<()> {
         // =========================================================================
         // PHASE 1a: SOURCE AUTO-ACQUISITION - Check and fetch missing sources
         // =========================================================================
@@ -310,25 +612,30 @@ impl AsyncOrchestrator {
                 pkgbuild_path
             );
 
-            // Get source URL from the kernel sources database
-            use crate::kernel::sources::KernelSourceDB;
-            let source_db = KernelSourceDB::new();
+            // Get source (URL and branch/tag policy) from the kernel sources database
+            use crate::kernel::mirror::SourceMirror;
+            use crate::kernel::sources::{self, KernelSourceDB};
+            let source_db = KernelSourceDB::load();
 
-            let source_url = source_db
-                .get_source_url(&kernel_variant)
+            let source = source_db
+                .get_source(kernel_variant)
                 .ok_or_else(|| format!("Unknown kernel variant: {}", kernel_variant))?;
+            // Offline mode: clone from the local mirror instead of the remote
+            let source = match SourceMirror::offline() {
+                Some(mirror) => mirror
+                    .localize(kernel_variant, source)
+                    .map_err(|e| format!("Offline source mode: {}", e))?,
+                None => source.clone(),
+            };
 
             eprintln!(
-                "[Build] [PREPARATION] Cloning kernel source from: {}",
-                source_url
+                "[Build] [PREPARATION] Cloning kernel source from: {} ({:?})",
+                source.git_url, source.reference
             );
-            self.send_log_event(format!("Cloning from: {}", source_url))
+            self.send_log_event(format!("Cloning from: {}", source.git_url))
                 .await;
 
-            // Perform async git clone operation
-            use crate::kernel::git::GitManager;
-
-            match GitManager::clone(source_url, &self.kernel_path) {
+            match sources::clone_source(&source, &self.kernel_path) {
                 Ok(_) => {
                     self.send_log_event("Sources successfully acquired.".to_string())
                         .await;
@@ -362,7 +669,7 @@ impl AsyncOrchestrator {
 
             // Fetch expected version from remote (latest version for the variant)
             let expected_version =
-                match crate::kernel::pkgbuild::get_latest_version_by_variant(&kernel_variant).await
+                match crate::kernel::pkgbuild::get_latest_version_by_variant(kernel_variant).await
                 {
                     Ok(version) => {
                         eprintln!(
@@ -386,8 +693,13 @@ impl AsyncOrchestrator {
                     }
                 };
 
-            // Validate source version against expected
-            if !expected_version.is_empty() {
+            // Validate source version against expected (a pinned manifest rebuild
+            // deliberately builds an older version)
+            let is_pinned = self
+                .reference_manifest
+                .as_ref()
+                .is_some_and(|m| m.source_commit.is_some());
+            if !expected_version.is_empty() && !is_pinned {
                 use crate::kernel::git::validate_source_version;
 
                 match validate_source_version(&self.kernel_path, &expected_version) {
@@ -431,21 +743,28 @@ impl AsyncOrchestrator {
                             .map_err(|e| format!("Failed to recreate kernel directory: {}", e))?;
 
                         // Perform fresh clone
-                        use crate::kernel::sources::KernelSourceDB;
-                        let source_db = KernelSourceDB::new();
-                        let source_url = source_db
-                            .get_source_url(&kernel_variant)
+                        use crate::kernel::mirror::SourceMirror;
+                        use crate::kernel::sources::{self, KernelSourceDB};
+                        let source_db = KernelSourceDB::load();
+                        let source = source_db
+                            .get_source(kernel_variant)
                             .ok_or_else(|| format!("Unknown kernel variant: {}", kernel_variant))?;
+                        // Offline mode: clone from the local mirror instead of the remote
+                        let source = match SourceMirror::offline() {
+                            Some(mirror) => mirror
+                                .localize(kernel_variant, source)
+                                .map_err(|e| format!("Offline source mode: {}", e))?,
+                            None => source.clone(),
+                        };
 
                         eprintln!(
-                            "[Build] [PREPARATION] Re-cloning kernel source from: {}",
-                            source_url
+                            "[Build] [PREPARATION] Re-cloning kernel source from: {} ({:?})",
+                            source.git_url, source.reference
                         );
-                        self.send_log_event(format!("Re-cloning from: {}", source_url))
+                        self.send_log_event(format!("Re-cloning from: {}", source.git_url))
                             .await;
 
-                        use crate::kernel::git::GitManager;
-                        match GitManager::clone(source_url, &self.kernel_path) {
+                        match sources::clone_source(&source, &self.kernel_path) {
                             Ok(_) => {
                                 self.send_log_event("Fresh kernel sources acquired.".to_string())
                                     .await;
@@ -470,95 +789,26 @@ impl AsyncOrchestrator {
             }
         }
 
-        // =========================================================================
-        // CLEANUP OLD ARTIFACTS - Delegate to KernelPatcher
-        // =========================================================================
-        // Use Patcher's cleanup method for old .pkg.tar.zst files
-        use crate::kernel::patcher::KernelPatcher;
-        let patcher = KernelPatcher::new(self.kernel_path.clone());
-        let _ = patcher.cleanup_previous_artifacts();
-
-        // =========================================================================
-        // INITIALIZE MODPROBED-DB - Database for module detection
-        // =========================================================================
-        {
-            match tokio::process::Command::new("which")
-                .arg("modprobed-db")
-                .status()
-                .await
-            {
-                Ok(status) if status.success() => {
-                    // modprobed-db is available, initialize the database
-                    self.send_log_event("Initializing modprobed-db database...".to_string())
-                        .await;
-                    eprintln!("[Build] [MODPROBED] Starting modprobed-db initialization");
-
-                    // First execution: create/initialize the database
-                    match tokio::process::Command::new("modprobed-db")
-                        .arg("store")
-                        .status()
-                        .await
-                    {
-                        Ok(status) if status.success() => {
-                            eprintln!("[Build] [MODPROBED] ✓ First modprobed-db store completed");
-
-                            // Wait 500ms before second run
-                            tokio::time::sleep(tokio::time::Duration::from_millis(500)).await;
-
-                            // Second execution: populate/update the database
-                            match tokio::process::Command::new("modprobed-db")
-                                .arg("store")
-                                .status()
-                                .await
-                            {
-                                Ok(status) if status.success() => {
-                                    eprintln!(
-                                        "[Build] [MODPROBED] ✓ Second modprobed-db store completed"
-                                    );
-                                    self.send_status(
-                                        "modprobed-db database initialized successfully"
-                                            .to_string(),
-                                    )
-                                    .await;
-                                }
-                                _ => {
-                                    eprintln!("[Build] [MODPROBED] ⚠ Second modprobed-db store failed, continuing anyway");
-                                    // Non-fatal: continue with build
-                                }
-                            }
-                        }
-                        _ => {
-                            eprintln!("[Build] [MODPROBED] ⚠ First modprobed-db store failed, continuing anyway");
-                            // Non-fatal: continue with build
-                        }
-                    }
-                }
-                _ => {
-                    eprintln!(
-                        "[Build] [MODPROBED] ⚠ modprobed-db not found, skipping initialization"
-                    );
-                    self.send_log_event(
-                        "modprobed-db not installed - skipping database initialization".to_string(),
-                    )
-                    .await;
-                    // Non-fatal: continue with build
-                }
+        // Offline mode: makepkg must find every download in the mirror
+        let offline_mirror = crate::kernel::mirror::SourceMirror::offline();
+        if let Some(mirror) = offline_mirror.filter(|_| pkgbuild_path.exists()) {
+            let missing = mirror
+                .missing_sources(&self.kernel_path)
+                .map_err(|e| format!("Offline source mode: {}", e))?;
+            if !missing.is_empty() {
+                return Err(format!(
+                    "Offline source mode: not in the mirror: {} - run `goatd mirror sync` while online",
+                    missing.join(", ")
+                )
+                .into());
             }
+            eprintln!(
+                "[Build] [PREPARATION] ✓ All sources available in {}",
+                mirror.sources_dir().display()
+            );
         }
 
-        // =========================================================================
-        // PHASE 1b: HARDWARE VALIDATION - After source acquisition, validate hardware
-        // =========================================================================
-        // Validate hardware meets minimum requirements and kernel source exists
-        phases::prepare_build_environment(&hardware, &self.kernel_path)?;
-
-        // Update progress: Preparation phase is 0-5%
-        let progress = 5;
-        self.set_progress(progress).await;
-        eprintln!("[Build] [PROGRESS] Preparation complete: {}%", progress);
-
-        // Transition to Configuration phase
-        self.transition_phase(BuildPhaseState::Configuration).await
+        Ok(())
     }
 
     /// Finalizes config via Rule Engine, applies GPU/driver policies.
@@ -621,10 +871,19 @@ impl AsyncOrchestrator {
         );
 
         // Apply GPU policy and resolve dynamic version (STEP 2: Dynamic Versioning)
-        let configured =
+        let mut configured =
             executor::configure_build(&mut finalized_config, &hardware, self.build_tx.as_ref())
                 .await?;
 
+        // Probe the real toolchain: minimum versions and CONFIG_CLANG_VERSION
+        self.probe_toolchain(&mut configured).await?;
+
+        // PGO/AutoFDO: instrument for profiling, or consume the merged profile
+        self.apply_pgo_stage(&mut configured).await?;
+
+        // Report options that olddefconfig would drop, before anything is compiled
+        self.check_kconfig_tree(&configured).await?;
+
         // Update internal state with configured kernel config
         {
             let mut state = self.state.write().await;
@@ -657,6 +916,11 @@ impl AsyncOrchestrator {
         let config = state.config.clone();
         drop(state);
 
+        // =========================================================================
+        // USER PATCH QUEUE: applied to the source before configuration
+        // =========================================================================
+        self.apply_user_patch_queue(&config).await?;
+
         // =========================================================================
         // UNIFIED SURGICAL ENGINE: Delegate all patching to KernelPatcher
         // =========================================================================
@@ -733,11 +997,49 @@ impl AsyncOrchestrator {
             if config.secure_boot { "1" } else { "0" }.to_string(),
         );
 
-        // Hardening flag: only set if profile specifically requests "Hardened" status
-        let hardening_enabled = config.hardening == crate::models::HardeningLevel::Hardened;
-        build_env_vars.insert(
-            "GOATD_ENABLE_HARDENING".to_string(),
-            if hardening_enabled { "1" } else { "0" }.to_string(),
+        // Secure Boot: sign the kernel image and every module with the user's MOK.
+        // An invalid key pair fails the phase here, before anything is compiled.
+        let signing_keys = if config.secure_boot {
+            match crate::kernel::signing::MokKeyPair::from_config(&config) {
+                Some(keys) => {
+                    keys.validate()
+                        .map_err(|e| format!("Secure Boot key check failed: {}", e))?;
+                    let key = keys.key.canonicalize().unwrap_or_else(|_| keys.key.clone());
+                    let cert = keys.cert.canonicalize().unwrap_or_else(|_| keys.cert.clone());
+                    let pem = keys.write_module_signing_pem()?;
+                    build_env_vars
+                        .insert("GOATD_SB_KEY".to_string(), key.to_string_lossy().to_string());
+                    build_env_vars
+                        .insert("GOATD_SB_CERT".to_string(), cert.to_string_lossy().to_string());
+                    build_env_vars.insert(
+                        "GOATD_MODULE_SIG_KEY".to_string(),
+                        pem.path().to_string_lossy().to_string(),
+                    );
+                    *self.module_signing_pem.write().await = Some(pem);
+                    eprintln!(
+                        "[Build] [SECURE-BOOT] Signing with MOK certificate {}",
+                        cert.display()
+                    );
+                    Some(keys)
+                }
+                None => {
+                    let warning = "⚠  Secure Boot is enabled but no MOK key/certificate is configured: \
+                        the kernel image and modules will NOT be signed."
+                        .to_string();
+                    eprintln!("[Build] [SECURE-BOOT] {}", warning);
+                    self.send_log_event(warning).await;
+                    None
+                }
+            }
+        } else {
+            None
+        };
+
+        // Hardening flag: only set if profile specifically requests "Hardened" status
+        let hardening_enabled = config.hardening == crate::models::HardeningLevel::Hardened;
+        build_env_vars.insert(
+            "GOATD_ENABLE_HARDENING".to_string(),
+            if hardening_enabled { "1" } else { "0" }.to_string(),
         );
 
         build_env_vars.insert(
@@ -888,6 +1190,21 @@ impl AsyncOrchestrator {
             eprintln!("[Build] [VERIFICATION] ⚠ Could not read PKGBUILD for verification");
         }
 
+        // Secure Boot signing is not optional once keys are configured: unlike the
+        // other injections, a missing signing step fails the build here.
+        if signing_keys.is_some() {
+            let pkgbuild = std::fs::read_to_string(&pkgbuild_path).unwrap_or_default();
+            let dot_config =
+                std::fs::read_to_string(self.kernel_path.join(".config")).unwrap_or_default();
+            if !pkgbuild.contains("PHASE SB: SECURE BOOT KERNEL IMAGE SIGNING") {
+                return Err("Secure Boot: kernel image signing was not injected into PKGBUILD".into());
+            }
+            if !dot_config.lines().any(|l| l == "CONFIG_MODULE_SIG_FORCE=y") {
+                return Err("Secure Boot: CONFIG_MODULE_SIG_FORCE=y missing from .config".into());
+            }
+            eprintln!("[Build] [VERIFICATION]   ✓ Secure Boot image and module signing configured");
+        }
+
         // Update progress: Patching phase is 8-10%
         let progress = 10;
         self.set_progress(progress).await;
@@ -916,6 +1233,17 @@ impl AsyncOrchestrator {
         let config = state.config.clone();
         drop(state);
 
+        let continue_in = self.compiling_source_root();
+        self.stage_module_signing_key(&config, continue_in.as_deref())
+            .await?;
+        if let Some(ref root) = continue_in {
+            self.send_log_event(format!(
+                "Continuing the interrupted compile in {} (makepkg -e)",
+                root.display()
+            ))
+            .await;
+        }
+
         eprintln!("[Build] [ORCHESTRATOR] Starting build phase with logging enabled");
 
         // Clone self for use in callback and pass the actual cancellation receiver
@@ -984,18 +1312,37 @@ impl AsyncOrchestrator {
             }
         };
 
+        // Snapshot compiler cache counters so the summary covers this build only
+        let cache_session =
+            CompilerCacheSession::from_config(&config.compiler_cache, &self.kernel_path);
+        let cache_before = cache_session.as_ref().and_then(|s| s.snapshot());
+
         // CRITICAL: Call the real build executor with logging callback and timeout
         eprintln!("[Build] [EXECUTOR] Launching kernel build process");
-        executor::run_kernel_build(
+        let outcome = executor::run_kernel_build(
             &self.kernel_path,
             &config,
+            continue_in.is_some(),
             callback_fn,
             cancel_rx,
             self.log_collector.clone(),
             self.test_timeout,
         )
-        .await?;
+        .await;
+        self.release_module_signing_key().await;
+        if outcome.is_err() {
+            self.refresh_building_checkpoint().await;
+        }
+        outcome?;
         eprintln!("[Build] [EXECUTOR] Kernel build process completed");
+        self.record_staged_patches()?;
+
+        if let Some(after) = cache_session.as_ref().and_then(|s| s.snapshot()) {
+            let stats = compiler_cache::stats_delta(cache_before, after);
+            self.send_log_event(format!("[Build] [CACHE] {}", stats.summary()))
+                .await;
+            *self.compiler_cache_stats.write().await = Some(stats);
+        }
 
         // Transition to Validation phase
         self.transition_phase(BuildPhaseState::Validation).await
@@ -1020,6 +1367,10 @@ impl AsyncOrchestrator {
         let config = state.config.clone();
         drop(state);
 
+        let continue_in = self.compiling_source_root();
+        self.stage_module_signing_key(&config, continue_in.as_deref())
+            .await?;
+
         eprintln!("[Build] [ORCHESTRATOR] Starting build_with_output phase with logging enabled");
 
         // Use the actual cancellation receiver from UI
@@ -1047,9 +1398,10 @@ impl AsyncOrchestrator {
         };
 
         // Call the real build executor with output streaming and timeout
-        executor::run_kernel_build(
+        let outcome = executor::run_kernel_build(
             &self.kernel_path,
             &config,
+            continue_in.is_some(),
             move |output, progress| {
                 // CRITICAL FIX: Build/Log callbacks are EXEMPT from INITIALIZING gate
                 // Pass output to provided handler WITHOUT diagnostic prefixes that confuse the log pipe
@@ -1091,10 +1443,16 @@ impl AsyncOrchestrator {
             self.log_collector.clone(),
             self.test_timeout,
         )
-        .await?;
+        .await;
 
         // Cancel the timer task when build completes
         timer_handle.abort();
+        self.release_module_signing_key().await;
+        if outcome.is_err() {
+            self.refresh_building_checkpoint().await;
+        }
+        outcome?;
+        self.record_staged_patches()?;
 
         eprintln!("[Build] [ORCHESTRATOR] build_with_output phase completed");
         // Transition to Validation phase
@@ -1135,6 +1493,8 @@ impl AsyncOrchestrator {
             artifacts.len()
         );
 
+        self.emit_build_manifests(&config, &artifacts).await;
+
         // =========================================================================
         // PHASE 5 AUDIT GATE: Post-Configuration Audit
         // =========================================================================
@@ -1169,6 +1529,54 @@ impl AsyncOrchestrator {
             }
         }
 
+        // =========================================================================
+        // SECURE BOOT GATE: the produced package must carry signed image + modules
+        // =========================================================================
+        if config.secure_boot {
+            if let Some(keys) = crate::kernel::signing::MokKeyPair::from_config(&config) {
+                self.send_status("Validation: Verifying Secure Boot signatures...".to_string())
+                    .await;
+                let kernel_packages = artifacts.iter().filter(|path| {
+                    let name = path
+                        .file_name()
+                        .map(|n| n.to_string_lossy().to_string())
+                        .unwrap_or_default();
+                    name.ends_with(".pkg.tar.zst")
+                        && !name.contains("-headers-")
+                        && !name.contains("-docs-")
+                });
+
+                for package in kernel_packages {
+                    let report =
+                        crate::kernel::validator::verify_package_signatures(package, &keys.cert)?;
+                    if !report.is_fully_signed() {
+                        return Err(format!(
+                            "Secure Boot verification failed for {}: image {}, {} unsigned module(s){}",
+                            package.display(),
+                            match (&report.image, report.image_signed) {
+                                (None, _) => "missing",
+                                (Some(_), true) => "signed",
+                                (Some(_), false) => "NOT signed",
+                            },
+                            report.unsigned_modules.len(),
+                            report
+                                .unsigned_modules
+                                .first()
+                                .map(|m| format!(" (e.g. {})", m.display()))
+                                .unwrap_or_default()
+                        )
+                        .into());
+                    }
+                    self.send_log_event(format!(
+                        "✓ Secure Boot: {} signed ({} modules)",
+                        package.display(),
+                        report.modules_signed
+                    ))
+                    .await;
+                }
+            }
+        }
+
         // Verify LTO was applied if requested
         if config.lto_type != crate::models::LtoType::None {
             // In a real implementation, would check ELF sections or symbols
@@ -1265,13 +1673,87 @@ impl AsyncOrchestrator {
         tuning
     }
 
-    /// Executes all 5 phases sequentially.
+    /// Recreate an orchestrator from a checkpoint written by a previous build.
+    ///
+    /// Verifies that the patched source tree still matches the checkpoint
+    /// fingerprint and restores the saved state, so `run()` continues from the
+    /// phase that was interrupted instead of re-cloning and re-patching.
+    ///
+    /// # Errors
+    /// Returns an error if the checkpoint cannot be read, belongs to a finished
+    /// build, or the source tree has changed since it was written.
+    pub async fn resume(
+        checkpoint_path: &Path,
+        build_tx: Option<tokio::sync::mpsc::Sender<crate::ui::controller::BuildEvent>>,
+        cancel_rx: tokio::sync::watch::Receiver<bool>,
+        log_collector: Option<Arc<LogCollector>>,
+        ctx_handle: Option<egui::Context>,
+    ) -> Result<Self> {
+        let checkpoint = CheckpointManager::load_from(checkpoint_path)?;
+        if !checkpoint.is_resumable() {
+            return Err(format!(
+                "Checkpoint is in terminal phase '{}' and cannot be resumed",
+                checkpoint.resume_phase().as_str()
+            )
+            .into());
+        }
+        checkpoint.verify_source_tree()?;
+
+        let checkpoint_dir = checkpoint_path
+            .parent()
+            .map(|p| p.to_path_buf())
+            .unwrap_or_else(|| PathBuf::from("."));
+
+        let mut orch = Self::new(
+            checkpoint.state.hardware.clone(),
+            checkpoint.state.config.clone(),
+            checkpoint_dir,
+            checkpoint.kernel_path.clone(),
+            build_tx,
+            cancel_rx,
+            log_collector,
+            None,
+            ctx_handle,
+        )
+        .await?;
+
+        let resume_phase = checkpoint.resume_phase();
+        *orch.state.write().await = checkpoint.state;
+        orch.resumed = true;
+
+        eprintln!(
+            "[Build] [CHECKPOINT] ✓ Resuming build at phase '{}'",
+            resume_phase.as_str()
+        );
+        orch.send_log_event(format!(
+            "Resuming build from checkpoint at phase: {}",
+            resume_phase.as_str()
+        ))
+        .await;
+
+        Ok(orch)
+    }
+
+    /// Executes the remaining phases sequentially, starting from the current phase.
+    ///
+    /// A fresh orchestrator runs all 5 phases; one created by `resume()` skips
+    /// the phases its checkpoint already completed.
     pub async fn run(&self) -> Result<()> {
-        self.prepare().await?;
-        self.configure().await?;
-        self.patch().await?;
-        self.build().await?;
-        self.validate().await?;
+        if self.current_phase().await == BuildPhaseState::Preparation {
+            self.prepare().await?;
+        }
+        if self.current_phase().await == BuildPhaseState::Configuration {
+            self.configure().await?;
+        }
+        if self.current_phase().await == BuildPhaseState::Patching {
+            self.patch().await?;
+        }
+        if self.current_phase().await == BuildPhaseState::Building {
+            self.build().await?;
+        }
+        if self.current_phase().await == BuildPhaseState::Validation {
+            self.validate().await?;
+        }
         // CRITICAL FIX: Installation removed - deferred to Kernel Manager
         Ok(())
     }
@@ -1290,6 +1772,342 @@ impl AsyncOrchestrator {
     pub fn is_recovery_enabled(&self) -> bool {
         self.recovery_enabled
     }
+
+    /// Rebuild from a manifest: pin the sources to its commit and compare the
+    /// result against it once the build validates.
+    ///
+    /// The orchestrator should be created with `manifest.rebuild_config()`.
+    pub fn set_reference_manifest(&mut self, manifest: BuildManifest) {
+        self.reference_manifest = Some(Arc::new(manifest));
+    }
+
+    /// Drift against the reference manifest, available after validate().
+    pub async fn manifest_drift(&self) -> Option<ManifestDrift> {
+        self.manifest_drift.read().await.clone()
+    }
+
+    /// Compiler cache statistics of the last build, if a cache was enabled.
+    pub async fn compiler_cache_stats(&self) -> Option<CompilerCacheStats> {
+        *self.compiler_cache_stats.read().await
+    }
+
+    /// Summary of the current build as a `BuildResult`.
+    pub async fn build_result(&self) -> BuildResult {
+        let state = self.state.read().await;
+        BuildResult {
+            success: state.phase == BuildPhaseState::Completed,
+            kernel_version: state.config.version.clone(),
+            lto_enabled: state.config.lto_type != LtoType::None,
+            patches_applied: state.patches_applied,
+            error_msg: state.error.clone(),
+            compiler_cache: *self.compiler_cache_stats.read().await,
+            toolchain: self.toolchain.read().await.clone(),
+        }
+    }
+
+    /// Toolchain fingerprint of the current build, available after configure().
+    pub async fn toolchain(&self) -> Option<ToolchainFingerprint> {
+        self.toolchain.read().await.clone()
+    }
+
+    /// Probe clang/ld.lld/llvm-ar/rustc/gcc in the build PATH (honouring the
+    /// `.llvm_bin` override), check them against the kernel release's minimums
+    /// and pass the Clang version to the patcher as `_CLANG_VERSION`.
+    ///
+    /// Fails the build when a required LLVM tool is missing or too old.
+    async fn probe_toolchain(&self, config: &mut KernelConfig) -> Result<()> {
+        use crate::kernel::toolchain;
+
+        let fingerprint = toolchain::probe_for_source(&self.kernel_path);
+        self.send_log_event(format!(
+            "[Build] [TOOLCHAIN] {}",
+            toolchain::summary(&fingerprint)
+        ))
+        .await;
+
+        let issues = toolchain::check_minimums(&fingerprint, &config.version);
+        for issue in issues.iter().filter(|issue| !issue.fatal) {
+            self.send_log_event(format!("⚠  Toolchain: {}", issue.message))
+                .await;
+        }
+        let fatal: Vec<&str> = issues
+            .iter()
+            .filter(|issue| issue.fatal)
+            .map(|issue| issue.message.as_str())
+            .collect();
+        if !fatal.is_empty() {
+            return Err(format!("Unsupported toolchain: {}", fatal.join("; ")).into());
+        }
+
+        match toolchain::clang_kconfig_version(&fingerprint) {
+            Some(version) => {
+                eprintln!("[Build] [TOOLCHAIN] CONFIG_CLANG_VERSION={}", version);
+                config
+                    .config_options
+                    .insert(toolchain::CLANG_VERSION_KEY.to_string(), version);
+            }
+            None => {
+                config.config_options.remove(toolchain::CLANG_VERSION_KEY);
+            }
+        }
+
+        *self.toolchain.write().await = Some(fingerprint);
+        Ok(())
+    }
+
+    /// Apply the configured PGO stage (see `kernel::pgo::apply`) and record
+    /// the stage and profile in the MPL metadata.
+    ///
+    /// Fails the build when an optimize stage has no merged profile.
+    async fn apply_pgo_stage(&self, config: &mut KernelConfig) -> Result<()> {
+        use crate::kernel::pgo;
+        use crate::models::{MPLMetadata, PgoStage};
+
+        let workspace = self.kernel_path.parent().unwrap_or(&self.kernel_path);
+        let target = pgo::apply(config, workspace).map_err(|e| format!("PGO: {}", e))?;
+
+        let stage = config.pgo.stage;
+        let profile = target
+            .as_ref()
+            .map(|p| p.to_string_lossy().to_string())
+            .unwrap_or_default();
+        match stage {
+            PgoStage::Off => {}
+            PgoStage::Instrument => {
+                self.send_log_event(format!(
+                    "PGO stage 1 ({}): instrumented build, boot it and run `{}` to collect profiles into {}",
+                    config.pgo.method.as_str(),
+                    pgo::collect_command(&config.version),
+                    profile
+                ))
+                .await
+            }
+            PgoStage::Optimize => {
+                self.send_log_event(format!(
+                    "PGO stage 2 ({}): optimizing with {}",
+                    config.pgo.method.as_str(),
+                    profile
+                ))
+                .await
+            }
+        }
+
+        let mpl_path = self.kernel_path.join(".goatd_metadata");
+        if stage == PgoStage::Off && !mpl_path.exists() {
+            return Ok(());
+        }
+        let mut mpl = MPLMetadata::read_from_file(&mpl_path).unwrap_or_default();
+        if mpl.pgo_stage != stage.as_str() || mpl.pgo_profile != profile {
+            mpl.pgo_stage = stage.as_str().to_string();
+            mpl.pgo_profile = profile;
+            let temp_path = mpl_path.with_extension("tmp");
+            mpl.write_to_file(&temp_path)?;
+            std::fs::rename(&temp_path, &mpl_path)?;
+        }
+        Ok(())
+    }
+
+    /// Check the requested config options against the source's Kconfig tree
+    /// and log every option `olddefconfig` would drop, with the reason.
+    ///
+    /// The PKGBUILD's sources are extracted first (`makepkg --nobuild`), since
+    /// configure() runs before the build has unpacked them. With
+    /// `strict_kconfig` set, any dropped option, or a check that cannot run,
+    /// fails the phase; otherwise the check is reported as skipped.
+    async fn check_kconfig_tree(&self, config: &KernelConfig) -> Result<()> {
+        use crate::kernel::kconfig_tree;
+
+        if kconfig_tree::locate_source_root(&self.kernel_path).is_none() {
+            if let Err(e) = self.extract_sources().await {
+                if config.strict_kconfig {
+                    return Err(format!("Kconfig check failed: {}", e).into());
+                }
+                self.send_log_event(format!("⚠  Kconfig check skipped: {}", e))
+                    .await;
+                return Ok(());
+            }
+        }
+
+        match kconfig_tree::check_kernel_config(&self.kernel_path, config) {
+            Ok(Some(report)) if report.is_clean() => {
+                eprintln!(
+                    "[Build] [KCONFIG] ✓ All {} requested options resolve in the Kconfig tree",
+                    report.checked
+                );
+                Ok(())
+            }
+            Ok(Some(report)) => {
+                self.send_log_event(format!(
+                    "⚠  Kconfig check: {} of {} requested options will not apply as set",
+                    report.issues.len(),
+                    report.checked
+                ))
+                .await;
+                for line in report.lines() {
+                    self.send_log_event(format!("[Build] [KCONFIG] {}", line))
+                        .await;
+                }
+                if config.strict_kconfig {
+                    return Err(format!(
+                        "Kconfig check failed: {} requested options would be dropped or overridden",
+                        report.issues.len()
+                    )
+                    .into());
+                }
+                Ok(())
+            }
+            Ok(None) if config.strict_kconfig => {
+                Err("Kconfig check failed: kernel sources could not be extracted".into())
+            }
+            Ok(None) => {
+                self.send_log_event(
+                    "⚠  Kconfig check skipped: no extracted kernel sources".to_string(),
+                )
+                .await;
+                Ok(())
+            }
+            Err(e) if config.strict_kconfig => Err(format!("Kconfig check failed: {}", e).into()),
+            Err(e) => {
+                self.send_log_event(format!("⚠  Kconfig check skipped: {}", e))
+                    .await;
+                Ok(())
+            }
+        }
+    }
+
+    /// Download and unpack the PKGBUILD's sources into `src/` without running
+    /// prepare(), so the Kconfig tree can be read before anything is patched.
+    ///
+    /// A tree without a PKGBUILD has nothing to extract and succeeds as is.
+    async fn extract_sources(&self) -> Result<()> {
+        if !self.kernel_path.join("PKGBUILD").exists() {
+            return Ok(());
+        }
+        let srcdest = crate::kernel::patcher::env::source_cache_dir();
+        let _ = std::fs::create_dir_all(&srcdest);
+        eprintln!(
+            "[Build] [KCONFIG] Extracting sources of {} for the Kconfig check",
+            self.kernel_path.display()
+        );
+        match tokio::process::Command::new("makepkg")
+            .args(["--nobuild", "--noprepare", "--nodeps", "--noconfirm"])
+            .current_dir(&self.kernel_path)
+            .env("SRCDEST", &srcdest)
+            .status()
+            .await
+        {
+            Ok(status) if status.success() => {
+                eprintln!("[Build] [KCONFIG] ✓ Sources extracted");
+                Ok(())
+            }
+            Ok(status) => Err(format!("makepkg --nobuild exited with {}", status).into()),
+            Err(e) => Err(format!("failed to run makepkg --nobuild: {}", e).into()),
+        }
+    }
+
+    /// Make sure the module signing key exists for the compile step.
+    ///
+    /// patch() stages it; a build resumed at the Building phase has lost that
+    /// copy, so a fresh one is written and CONFIG_MODULE_SIG_KEY repointed to it,
+    /// including in `continue_in`, the prepared tree a resumed compile builds on.
+    async fn stage_module_signing_key(
+        &self,
+        config: &KernelConfig,
+        continue_in: Option<&Path>,
+    ) -> Result<()> {
+        if !config.secure_boot || self.module_signing_pem.read().await.is_some() {
+            return Ok(());
+        }
+        let Some(keys) = crate::kernel::signing::MokKeyPair::from_config(config) else {
+            return Ok(());
+        };
+        let pem = keys.write_module_signing_pem()?;
+        crate::kernel::patcher::KernelPatcher::new(self.kernel_path.clone())
+            .apply_module_signing(pem.path())?;
+        if let Some(root) = continue_in {
+            crate::kernel::patcher::KernelPatcher::new(root.to_path_buf())
+                .apply_module_signing(pem.path())?;
+        }
+        eprintln!(
+            "[Build] [SECURE-BOOT] Staged module signing key at {}",
+            pem.path().display()
+        );
+        *self.module_signing_pem.write().await = Some(pem);
+        Ok(())
+    }
+
+    /// Delete the staged module signing key once compilation has ended.
+    async fn release_module_signing_key(&self) {
+        if let Some(pem) = self.module_signing_pem.write().await.take() {
+            eprintln!(
+                "[Build] [SECURE-BOOT] Removed module signing key {}",
+                pem.path().display()
+            );
+        }
+    }
+
+    /// Pin the kernel sources to the reference manifest's commit, if any.
+    async fn pin_source_commit(&self) -> Result<()> {
+        let Some(commit) = self
+            .reference_manifest
+            .as_ref()
+            .and_then(|m| m.source_commit.clone())
+        else {
+            return Ok(());
+        };
+
+        use crate::kernel::git::GitManager;
+        GitManager::new(&self.kernel_path)
+            .and_then(|git| git.checkout_commit(&commit))
+            .map_err(|e| format!("Failed to pin sources to manifest commit {}: {:?}", commit, e))?;
+
+        eprintln!("[Build] [MANIFEST] ✓ Sources pinned to commit {}", commit);
+        self.send_log_event(format!("Sources pinned to manifest commit {}", commit))
+            .await;
+        Ok(())
+    }
+
+    /// Write a build manifest next to every artifact and compare against the
+    /// reference manifest when rebuilding.
+    async fn emit_build_manifests(&self, config: &KernelConfig, artifacts: &[PathBuf]) {
+        let manifest = match BuildManifest::capture(config, &self.kernel_path, artifacts) {
+            Ok(manifest) => manifest,
+            Err(e) => {
+                eprintln!("[Build] [MANIFEST] ⚠ Could not capture build manifest: {}", e);
+                self.send_log_event(format!("Warning: build manifest not written: {}", e))
+                    .await;
+                return;
+            }
+        };
+
+        for artifact in artifacts.iter().filter(|path| path.is_file()) {
+            match manifest.write_for_package(artifact) {
+                Ok(path) => eprintln!("[Build] [MANIFEST] ✓ Wrote {}", path.display()),
+                Err(e) => eprintln!(
+                    "[Build] [MANIFEST] ⚠ Failed to write manifest for {}: {}",
+                    artifact.display(),
+                    e
+                ),
+            }
+        }
+
+        let Some(reference) = &self.reference_manifest else {
+            return;
+        };
+        let drift = reference.compare(&manifest);
+        if drift.is_drifted() {
+            self.send_log_event("⚠ Rebuild DRIFTED from the reference manifest:".to_string())
+                .await;
+        } else {
+            self.send_log_event("✓ Rebuild matches the reference manifest".to_string())
+                .await;
+        }
+        for line in drift.report() {
+            eprintln!("[Build] [MANIFEST] [DRIFT] {}", line);
+            self.send_log_event(format!("  {}", line)).await;
+        }
+        *self.manifest_drift.write().await = Some(drift);
+    }
 }
 
 #[cfg(test)]
@@ -1348,6 +2166,14 @@ mod tests {
             native_optimizations: true,
             user_toggled_native_optimizations: false,
             kernel_variant: String::new(),
+            mok_key_path: None,
+            mok_cert_path: None,
+            compiler_cache: Default::default(),
+            cmdline: Default::default(),
+            pgo: Default::default(),
+            build_jobs: None,
+            resources: Default::default(),
+            strict_kconfig: false,
         };
 
         let (_, cancel_rx) = tokio::sync::watch::channel(false);
@@ -1422,6 +2248,14 @@ mod tests {
             native_optimizations: true,
             user_toggled_native_optimizations: false,
             kernel_variant: String::new(),
+            mok_key_path: None,
+            mok_cert_path: None,
+            compiler_cache: Default::default(),
+            cmdline: Default::default(),
+            pgo: Default::default(),
+            build_jobs: None,
+            resources: Default::default(),
+            strict_kconfig: false,
         };
 
         let (_, cancel_rx) = tokio::sync::watch::channel(false);
@@ -1498,6 +2332,14 @@ mod tests {
             native_optimizations: true,
             user_toggled_native_optimizations: false,
             kernel_variant: String::new(),
+            mok_key_path: None,
+            mok_cert_path: None,
+            compiler_cache: Default::default(),
+            cmdline: Default::default(),
+            pgo: Default::default(),
+            build_jobs: None,
+            resources: Default::default(),
+            strict_kconfig: false,
         };
 
         let (_, cancel_rx) = tokio::sync::watch::channel(false);
@@ -1527,4 +2369,153 @@ mod tests {
             .await
             .is_err());
     }
+
+    #[tokio::test]
+    async fn test_resume_from_checkpoint() {
+        let workspace = tempfile::TempDir::new().unwrap();
+        let kernel_path = workspace.path().join("linux");
+        let checkpoint_dir = workspace.path().join(".checkpoints");
+        std::fs::create_dir_all(&kernel_path).unwrap();
+        std::fs::write(kernel_path.join("PKGBUILD"), "pkgbase=linux\n").unwrap();
+
+        let hw = crate::models::HardwareInfo {
+            cpu_model: "Test CPU".to_string(),
+            cpu_cores: 8,
+            cpu_threads: 16,
+            ram_gb: 16,
+            disk_free_gb: 100,
+            gpu_vendor: crate::models::GpuVendor::Intel,
+            gpu_model: "Test GPU".to_string(),
+            gpu_active_driver: true,
+            storage_type: crate::models::StorageType::Nvme,
+            storage_model: "Test Storage".to_string(),
+            boot_type: crate::models::BootType::Efi,
+            boot_manager: crate::models::BootManager {
+                detector: "systemd-boot".to_string(),
+                is_efi: true,
+            },
+            init_system: crate::models::InitSystem {
+                name: "systemd".to_string(),
+            },
+            all_drives: Vec::new(),
+        };
+        let mut config = KernelConfig::default();
+        config.kernel_variant = "linux".to_string();
+
+        let (_, cancel_rx) = tokio::sync::watch::channel(false);
+        let orch = AsyncOrchestrator::new(
+            hw,
+            config,
+            checkpoint_dir.clone(),
+            kernel_path.clone(),
+            None,
+            cancel_rx.clone(),
+            None,
+            None,
+            None,
+        )
+        .await
+        .unwrap();
+
+        // Simulate a build that got as far as Building before dying
+        orch.transition_phase(BuildPhaseState::Configuration)
+            .await
+            .unwrap();
+        orch.state.write().await.config.version = "6.12.1".to_string();
+        orch.transition_phase(BuildPhaseState::Patching).await.unwrap();
+        orch.transition_phase(BuildPhaseState::Building).await.unwrap();
+
+        let checkpoint_path = CheckpointManager::new(checkpoint_dir).checkpoint_path();
+        assert!(checkpoint_path.exists());
+
+        let resumed =
+            AsyncOrchestrator::resume(&checkpoint_path, None, cancel_rx.clone(), None, None)
+                .await
+                .unwrap();
+        assert_eq!(resumed.current_phase().await, BuildPhaseState::Building);
+        assert_eq!(resumed.state_snapshot().await.config.version, "6.12.1");
+
+        // Only a resumed build whose compile had started continues with makepkg -e
+        assert!(resumed.compiling_source_root().is_none());
+        let source_root = kernel_path.join("src/linux-6.12.1");
+        std::fs::create_dir_all(source_root.join("include/config")).unwrap();
+        std::fs::write(source_root.join("Kconfig"), "").unwrap();
+        std::fs::write(source_root.join("Makefile"), "").unwrap();
+        std::fs::write(source_root.join("include/config/auto.conf"), "").unwrap();
+        assert_eq!(resumed.compiling_source_root(), Some(source_root));
+        assert!(orch.compiling_source_root().is_none());
+
+        // A modified source tree must refuse to resume
+        std::fs::write(kernel_path.join("PKGBUILD"), "pkgbase=tampered\n").unwrap();
+        assert!(
+            AsyncOrchestrator::resume(&checkpoint_path, None, cancel_rx, None, None)
+                .await
+                .is_err()
+        );
+    }
+
+    #[tokio::test]
+    async fn test_strict_kconfig_check_fails_build() {
+        let workspace = tempfile::TempDir::new().unwrap();
+        let kernel_path = workspace.path().join("linux");
+        let source_root = kernel_path.join("src/linux-6.18.3");
+        std::fs::create_dir_all(&source_root).unwrap();
+        std::fs::write(source_root.join("Makefile"), "").unwrap();
+        std::fs::write(
+            source_root.join("Kconfig"),
+            "config NET\n\tbool \"Networking\"\n",
+        )
+        .unwrap();
+
+        let hw = crate::models::HardwareInfo {
+            cpu_model: "Test CPU".to_string(),
+            cpu_cores: 8,
+            cpu_threads: 16,
+            ram_gb: 16,
+            disk_free_gb: 100,
+            gpu_vendor: crate::models::GpuVendor::Intel,
+            gpu_model: "Test GPU".to_string(),
+            gpu_active_driver: true,
+            storage_type: crate::models::StorageType::Nvme,
+            storage_model: "Test Storage".to_string(),
+            boot_type: crate::models::BootType::Efi,
+            boot_manager: crate::models::BootManager {
+                detector: "systemd-boot".to_string(),
+                is_efi: true,
+            },
+            init_system: crate::models::InitSystem {
+                name: "systemd".to_string(),
+            },
+            all_drives: Vec::new(),
+        };
+        let (_, cancel_rx) = tokio::sync::watch::channel(false);
+        let orch = AsyncOrchestrator::new(
+            hw,
+            KernelConfig::default(),
+            workspace.path().join(".checkpoints"),
+            kernel_path,
+            None,
+            cancel_rx,
+            None,
+            None,
+            None,
+        )
+        .await
+        .unwrap();
+
+        let mut config = KernelConfig::default();
+        config
+            .config_options
+            .insert("CONFIG_NO_SUCH_OPTION".to_string(), "y".to_string());
+        assert!(orch.check_kconfig_tree(&config).await.is_ok());
+
+        config.strict_kconfig = true;
+        assert!(orch.check_kconfig_tree(&config).await.is_err());
+
+        config.config_options.clear();
+        config
+            .config_options
+            .insert("CONFIG_NET".to_string(), "y".to_string());
+        assert!(orch.check_kconfig_tree(&config).await.is_ok());
+    }
 }
@@ -0,0 +1,461 @@
+//! Persistent build queue.
+//!
+//! Queued builds carry a full [`KernelConfig`] snapshot taken when they were
+//! enqueued, so several differently configured builds can wait in line while
+//! the Build tab is changed for the next one. The queue is stored as JSON
+//! (`~/.config/goatdkernel/build_queue.json`) after every change and reloaded at
+//! startup; a build that was running when the app exited is queued again.
+//!
+//! The queue only decides *what* runs next ([`BuildQueue::next_due`]); the
+//! controller runs entries one at a time through the regular orchestrator.
+
+use chrono::{DateTime, Duration, Local, NaiveTime, TimeZone};
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use crate::error::Result;
+use crate::models::KernelConfig;
+
+/// File name of the queue inside the GOATd config directory.
+pub const QUEUE_FILE_NAME: &str = "build_queue.json";
+
+/// When a queued build may start.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+pub enum BuildSchedule {
+    /// As soon as no other build is running
+    #[default]
+    Now,
+    /// Once, not before the given time
+    At { time: DateTime<Local> },
+    /// Every day at `hour:minute` local time (e.g. a nightly build)
+    Daily { hour: u32, minute: u32 },
+}
+
+impl BuildSchedule {
+    /// The first start time strictly after `after` (`None` = immediately).
+    pub fn next_run(&self, after: DateTime<Local>) -> Option<DateTime<Local>> {
+        match *self {
+            BuildSchedule::Now => None,
+            BuildSchedule::At { time } => Some(time),
+            BuildSchedule::Daily { hour, minute } => {
+                let at = NaiveTime::from_hms_opt(hour.min(23), minute.min(59), 0)?;
+                let mut day = after.date_naive();
+                loop {
+                    // `earliest()` is None only inside a DST gap; try the next day then
+                    if let Some(candidate) = Local.from_local_datetime(&day.and_time(at)).earliest()
+                    {
+                        if candidate > after {
+                            return Some(candidate);
+                        }
+                    }
+                    day += Duration::days(1);
+                }
+            }
+        }
+    }
+
+    /// Short description for the Build tab
+    pub fn describe(&self) -> String {
+        match self {
+            BuildSchedule::Now => "next".to_string(),
+            BuildSchedule::At { time } => format!("at {}", time.format("%Y-%m-%d %H:%M")),
+            BuildSchedule::Daily { hour, minute } => format!("daily at {:02}:{:02}", hour, minute),
+        }
+    }
+}
+
+/// Parse a `HH:MM` time of day.
+pub fn parse_time_of_day(text: &str) -> Option<(u32, u32)> {
+    let (hour, minute) = text.trim().split_once(':')?;
+    let hour = hour.parse::<u32>().ok().filter(|h| *h < 24)?;
+    let minute = minute.parse::<u32>().ok().filter(|m| *m < 60)?;
+    Some((hour, minute))
+}
+
+/// Lifecycle of a queued build.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+pub enum QueueStatus {
+    #[default]
+    Pending,
+    Running,
+    Succeeded,
+    Failed,
+    Cancelled,
+}
+
+impl QueueStatus {
+    /// Finished entries are kept for reference until cleared.
+    pub fn is_finished(&self) -> bool {
+        matches!(
+            self,
+            QueueStatus::Succeeded | QueueStatus::Failed | QueueStatus::Cancelled
+        )
+    }
+
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            QueueStatus::Pending => "pending",
+            QueueStatus::Running => "running",
+            QueueStatus::Succeeded => "succeeded",
+            QueueStatus::Failed => "failed",
+            QueueStatus::Cancelled => "cancelled",
+        }
+    }
+}
+
+/// One entry of the build queue.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct QueuedBuild {
+    /// Stable identifier (unique within the queue)
+    pub id: u64,
+
+    /// Display name, e.g. "linux-zen Gaming"
+    pub label: String,
+
+    /// Build configuration captured at enqueue time
+    pub config: KernelConfig,
+
+    #[serde(default)]
+    pub schedule: BuildSchedule,
+
+    #[serde(default)]
+    pub status: QueueStatus,
+
+    /// Earliest start (`None` = as soon as possible)
+    #[serde(default)]
+    pub next_run: Option<DateTime<Local>>,
+
+    pub enqueued_at: DateTime<Local>,
+
+    /// When the last run of this entry finished
+    #[serde(default)]
+    pub last_finished: Option<DateTime<Local>>,
+
+    /// Outcome of the last run (recurring entries stay pending between runs)
+    #[serde(default)]
+    pub last_success: Option<bool>,
+}
+
+impl QueuedBuild {
+    /// Whether the entry may start at `now`.
+    pub fn is_due(&self, now: DateTime<Local>) -> bool {
+        self.status == QueueStatus::Pending && self.next_run.is_none_or(|at| at <= now)
+    }
+}
+
+/// Ordered, persisted list of queued builds.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct BuildQueue {
+    next_id: u64,
+    entries: Vec<QueuedBuild>,
+
+    /// Where the queue is saved (`None` = in memory only)
+    #[serde(skip)]
+    path: Option<PathBuf>,
+}
+
+impl BuildQueue {
+    /// `~/.config/goatdkernel/build_queue.json` (see [`crate::config::app_config_dir`]).
+    pub fn default_path() -> Result<PathBuf> {
+        Ok(crate::config::app_config_dir()?.join(QUEUE_FILE_NAME))
+    }
+
+    /// Load the queue from `path`; a missing file is an empty queue.
+    ///
+    /// Entries left `Running` by a previous session are queued again.
+    pub fn load(path: &Path) -> Result<Self> {
+        let mut queue = if path.exists() {
+            let json = fs::read_to_string(path)?;
+            serde_json::from_str::<BuildQueue>(&json)
+                .map_err(|e| format!("Invalid build queue {}: {}", path.display(), e))?
+        } else {
+            BuildQueue::default()
+        };
+        for entry in &mut queue.entries {
+            if entry.status == QueueStatus::Running {
+                eprintln!(
+                    "[Build] [QUEUE] Re-queueing '{}' (interrupted by restart)",
+                    entry.label
+                );
+                entry.status = QueueStatus::Pending;
+            }
+        }
+        queue.path = Some(path.to_path_buf());
+        Ok(queue)
+    }
+
+    /// Write the queue to its file (no-op for in-memory queues).
+    pub fn save(&self) -> Result<()> {
+        let Some(path) = &self.path else {
+            return Ok(());
+        };
+        if let Some(parent) = path.parent() {
+            fs::create_dir_all(parent)?;
+        }
+        let json = serde_json::to_string_pretty(self)
+            .map_err(|e| format!("Failed to serialize build queue: {}", e))?;
+        fs::write(path, json)?;
+        Ok(())
+    }
+
+    pub fn entries(&self) -> &[QueuedBuild] {
+        &self.entries
+    }
+
+    pub fn get(&self, id: u64) -> Option<&QueuedBuild> {
+        self.entries.iter().find(|e| e.id == id)
+    }
+
+    /// Append a build and return its id.
+    pub fn enqueue(
+        &mut self,
+        label: String,
+        config: KernelConfig,
+        schedule: BuildSchedule,
+        now: DateTime<Local>,
+    ) -> u64 {
+        self.next_id += 1;
+        let id = self.next_id;
+        self.entries.push(QueuedBuild {
+            id,
+            label,
+            config,
+            schedule,
+            status: QueueStatus::Pending,
+            next_run: schedule.next_run(now),
+            enqueued_at: now,
+            last_finished: None,
+            last_success: None,
+        });
+        id
+    }
+
+    /// Move an entry `offset` places (negative = towards the front).
+    pub fn move_entry(&mut self, id: u64, offset: isize) -> bool {
+        let Some(from) = self.position(id) else {
+            return false;
+        };
+        let to = from
+            .saturating_add_signed(offset)
+            .min(self.entries.len() - 1);
+        if to == from {
+            return false;
+        }
+        let entry = self.entries.remove(from);
+        self.entries.insert(to, entry);
+        true
+    }
+
+    /// Cancel a pending or running entry; returns its previous status.
+    ///
+    /// Stopping the running build itself is up to the caller.
+    pub fn cancel(&mut self, id: u64) -> Option<QueueStatus> {
+        let entry = self.entries.iter_mut().find(|e| e.id == id)?;
+        let previous = entry.status;
+        if previous.is_finished() {
+            return None;
+        }
+        entry.status = QueueStatus::Cancelled;
+        Some(previous)
+    }
+
+    /// Drop succeeded, failed and cancelled entries.
+    pub fn clear_finished(&mut self) -> usize {
+        let before = self.entries.len();
+        self.entries.retain(|e| !e.status.is_finished());
+        before - self.entries.len()
+    }
+
+    /// The first due entry in queue order, unless a build is already running.
+    pub fn next_due(&self, now: DateTime<Local>) -> Option<u64> {
+        if self
+            .entries
+            .iter()
+            .any(|e| e.status == QueueStatus::Running)
+        {
+            return None;
+        }
+        self.entries.iter().find(|e| e.is_due(now)).map(|e| e.id)
+    }
+
+    /// Earliest scheduled start among pending entries.
+    pub fn next_wakeup(&self) -> Option<DateTime<Local>> {
+        self.entries
+            .iter()
+            .filter(|e| e.status == QueueStatus::Pending)
+            .filter_map(|e| e.next_run)
+            .min()
+    }
+
+    pub fn mark_running(&mut self, id: u64) -> Option<&QueuedBuild> {
+        let entry = self.entries.iter_mut().find(|e| e.id == id)?;
+        entry.status = QueueStatus::Running;
+        Some(entry)
+    }
+
+    /// Record the outcome of a run. Daily entries are re-armed for their next
+    /// slot; entries cancelled while running stay cancelled.
+    pub fn finish(&mut self, id: u64, success: bool, now: DateTime<Local>) {
+        let Some(entry) = self.entries.iter_mut().find(|e| e.id == id) else {
+            return;
+        };
+        entry.last_finished = Some(now);
+        entry.last_success = Some(success);
+        if entry.status == QueueStatus::Cancelled {
+            return;
+        }
+        entry.status = match entry.schedule {
+            BuildSchedule::Daily { .. } => {
+                entry.next_run = entry.schedule.next_run(now);
+                QueueStatus::Pending
+            }
+            _ if success => QueueStatus::Succeeded,
+            _ => QueueStatus::Failed,
+        };
+    }
+
+    fn position(&self, id: u64) -> Option<usize> {
+        self.entries.iter().position(|e| e.id == id)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn at(day: u32, hour: u32, minute: u32) -> DateTime<Local> {
+        Local
+            .with_ymd_and_hms(2025, 3, day, hour, minute, 0)
+            .single()
+            .unwrap()
+    }
+
+    fn ids(queue: &BuildQueue) -> Vec<u64> {
+        queue.entries().iter().map(|e| e.id).collect()
+    }
+
+    #[test]
+    fn test_schedule_next_run() {
+        let now = at(10, 12, 0);
+        assert_eq!(BuildSchedule::Now.next_run(now), None);
+        assert_eq!(
+            BuildSchedule::At { time: at(11, 1, 0) }.next_run(now),
+            Some(at(11, 1, 0))
+        );
+        let nightly = BuildSchedule::Daily {
+            hour: 2,
+            minute: 30,
+        };
+        assert_eq!(nightly.next_run(now), Some(at(11, 2, 30)));
+        assert_eq!(nightly.next_run(at(11, 1, 0)), Some(at(11, 2, 30)));
+        assert_eq!(nightly.next_run(at(11, 2, 30)), Some(at(12, 2, 30)));
+    }
+
+    #[test]
+    fn test_parse_time_of_day() {
+        assert_eq!(parse_time_of_day("03:00"), Some((3, 0)));
+        assert_eq!(parse_time_of_day(" 23:59 "), Some((23, 59)));
+        assert_eq!(parse_time_of_day("24:00"), None);
+        assert_eq!(parse_time_of_day("3:60"), None);
+        assert_eq!(parse_time_of_day("nightly"), None);
+    }
+
+    #[test]
+    fn test_order_reorder_and_cancel() {
+        let now = at(10, 12, 0);
+        let mut queue = BuildQueue::default();
+        let a = queue.enqueue("a".into(), KernelConfig::default(), BuildSchedule::Now, now);
+        let b = queue.enqueue("b".into(), KernelConfig::default(), BuildSchedule::Now, now);
+        let c = queue.enqueue("c".into(), KernelConfig::default(), BuildSchedule::Now, now);
+        assert_eq!(queue.next_due(now), Some(a));
+
+        assert!(queue.move_entry(c, -2));
+        assert_eq!(ids(&queue), vec![c, a, b]);
+        assert!(queue.move_entry(c, 10));
+        assert_eq!(ids(&queue), vec![a, b, c]);
+        assert!(!queue.move_entry(a, -1));
+
+        assert_eq!(queue.cancel(a), Some(QueueStatus::Pending));
+        assert_eq!(queue.cancel(a), None);
+        assert_eq!(queue.next_due(now), Some(b));
+
+        // One build at a time
+        queue.mark_running(b);
+        assert_eq!(queue.next_due(now), None);
+        queue.finish(b, true, now);
+        assert_eq!(queue.get(b).unwrap().status, QueueStatus::Succeeded);
+        assert_eq!(queue.next_due(now), Some(c));
+
+        assert_eq!(queue.clear_finished(), 2);
+        assert_eq!(ids(&queue), vec![c]);
+    }
+
+    #[test]
+    fn test_scheduled_entries_wait_and_recur() {
+        let now = at(10, 12, 0);
+        let mut queue = BuildQueue::default();
+        let nightly = queue.enqueue(
+            "nightly".into(),
+            KernelConfig::default(),
+            BuildSchedule::Daily { hour: 3, minute: 0 },
+            now,
+        );
+        assert_eq!(queue.next_due(now), None);
+        assert_eq!(queue.next_wakeup(), Some(at(11, 3, 0)));
+        assert_eq!(queue.next_due(at(11, 3, 0)), Some(nightly));
+
+        queue.mark_running(nightly);
+        queue.finish(nightly, false, at(11, 4, 10));
+        let entry = queue.get(nightly).unwrap();
+        assert_eq!(entry.status, QueueStatus::Pending);
+        assert_eq!(entry.last_success, Some(false));
+        assert_eq!(entry.next_run, Some(at(12, 3, 0)));
+
+        // Cancelling a running entry wins over its outcome
+        queue.mark_running(nightly);
+        assert_eq!(queue.cancel(nightly), Some(QueueStatus::Running));
+        queue.finish(nightly, false, at(12, 3, 5));
+        assert_eq!(queue.get(nightly).unwrap().status, QueueStatus::Cancelled);
+    }
+
+    #[test]
+    fn test_default_path_is_in_user_config_dir() {
+        let path = BuildQueue::default_path().unwrap();
+        assert!(path.starts_with(dirs::config_dir().unwrap()));
+        assert!(path.ends_with("goatdkernel/build_queue.json"));
+    }
+
+    #[test]
+    fn test_queue_survives_restart() {
+        let temp = tempfile::tempdir().unwrap();
+        let path = temp.path().join("goatdkernel").join(QUEUE_FILE_NAME);
+        let now = at(10, 12, 0);
+
+        let mut queue = BuildQueue::load(&path).unwrap();
+        assert!(queue.entries().is_empty());
+        let mut config = KernelConfig::default();
+        config.kernel_variant = "linux-zen".to_string();
+        let first = queue.enqueue("zen".into(), config, BuildSchedule::Now, now);
+        let second = queue.enqueue(
+            "nightly".into(),
+            KernelConfig::default(),
+            BuildSchedule::Daily { hour: 2, minute: 0 },
+            now,
+        );
+        queue.mark_running(first);
+        queue.save().unwrap();
+
+        let mut reloaded = BuildQueue::load(&path).unwrap();
+        assert_eq!(ids(&reloaded), vec![first, second]);
+        let entry = reloaded.get(first).unwrap();
+        assert_eq!(entry.status, QueueStatus::Pending);
+        assert_eq!(entry.config.kernel_variant, "linux-zen");
+        assert_eq!(reloaded.get(second).unwrap().next_run, Some(at(11, 2, 0)));
+
+        // Ids keep counting after a reload
+        let third = reloaded.enqueue("x".into(), KernelConfig::default(), BuildSchedule::Now, now);
+        assert!(third > second);
+    }
+}
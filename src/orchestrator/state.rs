@@ -275,6 +275,14 @@ mod tests {
             native_optimizations: true,
             user_toggled_native_optimizations: false,
             kernel_variant: String::new(),
+            mok_key_path: None,
+            mok_cert_path: None,
+            compiler_cache: Default::default(),
+            cmdline: Default::default(),
+            pgo: Default::default(),
+            build_jobs: None,
+            resources: Default::default(),
+            strict_kconfig: false,
         };
 
         let state = OrchestrationState::new(hw.clone(), config.clone());
@@ -334,6 +342,14 @@ mod tests {
             native_optimizations: true,
             user_toggled_native_optimizations: false,
             kernel_variant: String::new(),
+            mok_key_path: None,
+            mok_cert_path: None,
+            compiler_cache: Default::default(),
+            cmdline: Default::default(),
+            pgo: Default::default(),
+            build_jobs: None,
+            resources: Default::default(),
+            strict_kconfig: false,
         };
 
         let mut state = OrchestrationState::new(hw, config);
@@ -393,6 +409,14 @@ mod tests {
             native_optimizations: true,
             user_toggled_native_optimizations: false,
             kernel_variant: String::new(),
+            mok_key_path: None,
+            mok_cert_path: None,
+            compiler_cache: Default::default(),
+            cmdline: Default::default(),
+            pgo: Default::default(),
+            build_jobs: None,
+            resources: Default::default(),
+            strict_kconfig: false,
         };
 
         let mut state = OrchestrationState::new(hw, config);
@@ -451,6 +475,14 @@ mod tests {
             native_optimizations: true,
             user_toggled_native_optimizations: false,
             kernel_variant: String::new(),
+            mok_key_path: None,
+            mok_cert_path: None,
+            compiler_cache: Default::default(),
+            cmdline: Default::default(),
+            pgo: Default::default(),
+            build_jobs: None,
+            resources: Default::default(),
+            strict_kconfig: false,
         };
 
         let mut state = OrchestrationState::new(hw, config);
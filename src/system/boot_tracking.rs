@@ -0,0 +1,556 @@
+//! Boot-success tracking for newly installed kernels.
+//!
+//! Installing a kernel marks it as a **trial**: its boot entry is selected
+//! for the next boot only (one-shot), so the persistent default still points
+//! at the previous kernel. After the next boot a post-boot check decides:
+//! - the trial kernel is running and healthy (`verify_kernel_installation`
+//!   finds its modules and `SystemAudit::get_summary` succeeds) → **good**,
+//!   and it becomes the persistent default
+//! - another kernel is running (the trial never came up) or the trial kernel
+//!   is unhealthy → **failed**, and the boot entry of the last known-good
+//!   kernel is restored as the default. That kernel need not be tracked: a
+//!   failed trial usually falls back to a stock `linux` / `linux-lts` entry,
+//!   which is recorded from the bootloader when it boots healthy
+//!
+//! Boots are identified by `/proc/sys/kernel/random/boot_id`, so running the
+//! check several times during one boot is harmless.
+
+use crate::error::AppError;
+use crate::kernel::audit::SystemAudit;
+use crate::system::bootloader::{self, BootloaderManager};
+use crate::ui::SystemWrapper;
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, HashMap};
+use std::fs;
+use std::path::{Path, PathBuf};
+
+/// File name of the boot tracking state in the user config dir.
+pub const BOOT_STATE_FILE_NAME: &str = "boot_state.json";
+
+/// Kernel module tree, whose `<release>/pkgbase` files map releases to packages.
+pub const MODULES_ROOT: &str = "/usr/lib/modules";
+
+/// Boots without the trial kernel before it is marked as failed.
+///
+/// The trial is a one-shot entry, so a single boot that did not end up in it
+/// means the kernel failed to come up.
+pub const MAX_TRIAL_BOOTS: u32 = 1;
+
+/// Boot state of an installed kernel.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum BootStatus {
+    /// Installed, not yet confirmed by a successful boot
+    Trial,
+    /// Booted and passed the post-boot check
+    Good,
+    /// Never came up, or came up unhealthy
+    Failed,
+}
+
+impl BootStatus {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            BootStatus::Trial => "TRIAL",
+            BootStatus::Good => "GOOD",
+            BootStatus::Failed => "FAILED",
+        }
+    }
+}
+
+/// Tracking record of one kernel package.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct TrackedKernel {
+    pub pkgbase: String,
+    pub kernel_release: Option<String>,
+    pub status: BootStatus,
+    /// Boot entry managed by `system::bootloader`, if one was written
+    pub entry_id: Option<String>,
+    pub installed_at: String,
+    /// Boot during which the kernel was installed (not a boot of the kernel)
+    pub installed_boot_id: String,
+    /// Boots since installation that did not run this kernel
+    pub missed_boots: u32,
+    pub confirmed_at: Option<String>,
+    /// Why the kernel was marked as failed
+    #[serde(default)]
+    pub problems: Vec<String>,
+}
+
+/// What the current boot looks like.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct BootObservation {
+    pub boot_id: String,
+    pub kernel_release: String,
+    /// Package that installed the running kernel
+    pub running_pkgbase: Option<String>,
+    /// Boot entry the bootloader reports for this boot, if it exports one
+    pub running_entry: Option<String>,
+    /// Problems found by the post-boot check (empty when healthy)
+    pub problems: Vec<String>,
+}
+
+impl BootObservation {
+    /// Run the post-boot check against the running kernel.
+    pub fn collect() -> Result<Self, AppError> {
+        let boot_id = current_boot_id()?;
+        let summary = SystemAudit::get_summary().map_err(AppError::Audit)?;
+        let kernel_release = summary.kernel_version;
+
+        let mut problems = Vec::new();
+        match crate::system::verification::verify_kernel_installation(&kernel_release) {
+            Ok(status) if status.module_dir_exists => {}
+            Ok(_) => problems.push(format!("no module directory for {}", kernel_release)),
+            Err(e) => problems.push(format!("installation check failed: {:?}", e)),
+        }
+
+        Ok(BootObservation {
+            boot_id,
+            running_pkgbase: pkgbase_for_release(Path::new(MODULES_ROOT), &kernel_release),
+            running_entry: bootloader::booted_entry_id(Path::new(bootloader::EFIVARS_DIR)),
+            kernel_release,
+            problems,
+        })
+    }
+
+    pub fn is_healthy(&self) -> bool {
+        self.problems.is_empty()
+    }
+}
+
+/// Result of processing one boot.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct BootCheckOutcome {
+    /// This boot was already processed
+    pub already_checked: bool,
+    /// Trial kernel confirmed by this boot
+    pub promoted: Option<String>,
+    /// Kernels marked as failed by this boot
+    pub failed: Vec<String>,
+    /// Entry to make the persistent default (promoted or restored kernel)
+    pub default_entry: Option<String>,
+}
+
+impl BootCheckOutcome {
+    /// Human-readable lines for the log.
+    pub fn messages(&self) -> Vec<String> {
+        let mut lines = Vec::new();
+        if let Some(pkgbase) = &self.promoted {
+            lines.push(format!("✓ {} booted successfully and is now GOOD", pkgbase));
+        }
+        for pkgbase in &self.failed {
+            lines.push(format!(
+                "✗ {} did not boot successfully and is marked FAILED",
+                pkgbase
+            ));
+        }
+        if let Some(entry) = &self.default_entry {
+            lines.push(format!("Default boot entry set to '{}'", entry));
+        }
+        lines
+    }
+}
+
+/// Persisted boot tracking state.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct BootTracker {
+    pub kernels: BTreeMap<String, TrackedKernel>,
+    /// Package of the last kernel that booted and passed the check
+    pub last_good: Option<String>,
+    /// Boot entry of `last_good`, also known when that kernel is not tracked
+    #[serde(default)]
+    pub last_good_entry: Option<String>,
+    /// Boot id of the last processed boot
+    pub last_checked_boot: Option<String>,
+}
+
+impl BootTracker {
+    /// Load the state, or an empty tracker if the file does not exist.
+    pub fn load(path: &Path) -> Result<Self, AppError> {
+        match fs::read_to_string(path) {
+            Ok(content) => serde_json::from_str(&content).map_err(|e| {
+                AppError::Settings(format!("Invalid boot state {}: {}", path.display(), e))
+            }),
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => Ok(BootTracker::default()),
+            Err(e) => Err(AppError::Io(format!(
+                "Failed to read {}: {}",
+                path.display(),
+                e
+            ))),
+        }
+    }
+
+    pub fn save(&self, path: &Path) -> Result<(), AppError> {
+        if let Some(parent) = path.parent() {
+            fs::create_dir_all(parent)?;
+        }
+        let json = serde_json::to_string_pretty(self)
+            .map_err(|e| AppError::Settings(format!("Failed to serialize boot state: {}", e)))?;
+        fs::write(path, json)
+            .map_err(|e| AppError::Io(format!("Failed to write {}: {}", path.display(), e)))
+    }
+
+    /// Record a freshly installed kernel as a trial.
+    pub fn mark_trial(
+        &mut self,
+        pkgbase: &str,
+        kernel_release: Option<String>,
+        entry_id: Option<String>,
+        boot_id: &str,
+    ) {
+        self.kernels.insert(
+            pkgbase.to_string(),
+            TrackedKernel {
+                pkgbase: pkgbase.to_string(),
+                kernel_release,
+                status: BootStatus::Trial,
+                entry_id,
+                installed_at: chrono::Local::now().to_rfc3339(),
+                installed_boot_id: boot_id.to_string(),
+                missed_boots: 0,
+                confirmed_at: None,
+                problems: Vec::new(),
+            },
+        );
+    }
+
+    /// Stop tracking an uninstalled kernel.
+    pub fn forget(&mut self, pkgbase: &str) {
+        self.kernels.remove(pkgbase);
+        if self.last_good.as_deref() == Some(pkgbase) {
+            self.last_good = None;
+            self.last_good_entry = None;
+        }
+    }
+
+    pub fn status(&self, pkgbase: &str) -> Option<BootStatus> {
+        self.kernels.get(pkgbase).map(|kernel| kernel.status)
+    }
+
+    /// Status of every tracked kernel, keyed by package base.
+    pub fn statuses(&self) -> HashMap<String, BootStatus> {
+        self.kernels
+            .iter()
+            .map(|(pkgbase, kernel)| (pkgbase.clone(), kernel.status))
+            .collect()
+    }
+
+    /// Apply the post-boot check of `observation` to all tracked kernels.
+    pub fn record_boot(&mut self, observation: &BootObservation) -> BootCheckOutcome {
+        if self.last_checked_boot.as_deref() == Some(observation.boot_id.as_str()) {
+            return BootCheckOutcome {
+                already_checked: true,
+                ..Default::default()
+            };
+        }
+        self.last_checked_boot = Some(observation.boot_id.clone());
+
+        let running = observation.running_pkgbase.as_deref();
+        let mut outcome = BootCheckOutcome::default();
+
+        for (pkgbase, kernel) in self.kernels.iter_mut() {
+            // Installed during this boot: it has not had its chance yet
+            if kernel.installed_boot_id == observation.boot_id {
+                continue;
+            }
+
+            if running == Some(pkgbase.as_str()) {
+                if observation.is_healthy() {
+                    if kernel.status != BootStatus::Good {
+                        kernel.status = BootStatus::Good;
+                        kernel.confirmed_at = Some(chrono::Local::now().to_rfc3339());
+                        kernel.problems.clear();
+                        outcome.promoted = Some(pkgbase.clone());
+                    }
+                } else if kernel.status == BootStatus::Trial {
+                    kernel.status = BootStatus::Failed;
+                    kernel.problems = observation.problems.clone();
+                    outcome.failed.push(pkgbase.clone());
+                }
+            } else if kernel.status == BootStatus::Trial {
+                kernel.missed_boots += 1;
+                if kernel.missed_boots >= MAX_TRIAL_BOOTS {
+                    kernel.status = BootStatus::Failed;
+                    kernel.problems = vec![format!(
+                        "system booted {} instead",
+                        observation.kernel_release
+                    )];
+                    outcome.failed.push(pkgbase.clone());
+                }
+            }
+        }
+
+        if observation.is_healthy() {
+            if let Some(running) = running {
+                self.last_good = Some(running.to_string());
+            }
+            // Tracked kernels use their managed entry; untracked ones (stock
+            // kernels) the entry the bootloader reports
+            let entry = running
+                .and_then(|pkgbase| self.entry_of(pkgbase))
+                .or_else(|| observation.running_entry.clone());
+            if entry.is_some() {
+                self.last_good_entry = entry;
+            }
+        }
+
+        outcome.default_entry = match &outcome.promoted {
+            Some(pkgbase) => self.entry_of(pkgbase),
+            None if !outcome.failed.is_empty() => self.last_good_entry.clone(),
+            None => None,
+        };
+
+        outcome
+    }
+
+    fn entry_of(&self, pkgbase: &str) -> Option<String> {
+        self.kernels
+            .get(pkgbase)
+            .and_then(|kernel| kernel.entry_id.clone())
+    }
+}
+
+/// `~/.config/goatdkernel/boot_state.json`, next to the build queue.
+pub fn default_state_path() -> Result<PathBuf, AppError> {
+    Ok(crate::config::app_config_dir()?.join(BOOT_STATE_FILE_NAME))
+}
+
+/// Mark a kernel installed during this boot as a trial in the default state file.
+pub fn record_install(pkgbase: &str, entry_id: Option<String>) -> Result<(), AppError> {
+    let path = &default_state_path()?;
+    let mut tracker = BootTracker::load(path)?;
+    tracker.mark_trial(
+        pkgbase,
+        release_for_pkgbase(Path::new(MODULES_ROOT), pkgbase),
+        entry_id,
+        &current_boot_id()?,
+    );
+    tracker.save(path)
+}
+
+/// Stop tracking an uninstalled kernel in the default state file.
+pub fn forget_kernel(pkgbase: &str) -> Result<(), AppError> {
+    let path = &default_state_path()?;
+    if !path.exists() {
+        return Ok(());
+    }
+    let mut tracker = BootTracker::load(path)?;
+    tracker.forget(pkgbase);
+    tracker.save(path)
+}
+
+/// Run the post-boot check and apply the resulting default boot entry.
+///
+/// Returns `None` when no kernel has ever been tracked.
+pub fn check_current_boot(
+    state_path: &Path,
+    system: &dyn SystemWrapper,
+) -> Result<Option<BootCheckOutcome>, AppError> {
+    if !state_path.exists() {
+        return Ok(None);
+    }
+
+    let mut tracker = BootTracker::load(state_path)?;
+    let observation = BootObservation::collect()?;
+    let outcome = tracker.record_boot(&observation);
+    if outcome.already_checked {
+        return Ok(Some(outcome));
+    }
+    tracker.save(state_path)?;
+
+    if let Some(entry_id) = &outcome.default_entry {
+        let manager = BootloaderManager::detect()?;
+        let staging = std::env::temp_dir().join("goatd-boot-entries");
+        let commands = manager.default_commands(entry_id, &staging)?;
+        system
+            .batch_privileged_commands(commands.iter().map(String::as_str).collect())
+            .map_err(|reason| AppError::OsCommand {
+                cmd: "set default boot entry".to_string(),
+                reason,
+            })?;
+    }
+
+    Ok(Some(outcome))
+}
+
+/// Identifier of the current boot.
+pub fn current_boot_id() -> Result<String, AppError> {
+    fs::read_to_string("/proc/sys/kernel/random/boot_id")
+        .map(|id| id.trim().to_string())
+        .map_err(|e| AppError::Io(format!("Failed to read boot id: {}", e)))
+}
+
+/// Package base recorded by the package in `<modules_root>/<release>/pkgbase`.
+pub fn pkgbase_for_release(modules_root: &Path, release: &str) -> Option<String> {
+    fs::read_to_string(modules_root.join(release).join("pkgbase"))
+        .ok()
+        .map(|pkgbase| pkgbase.trim().to_string())
+        .filter(|pkgbase| !pkgbase.is_empty())
+}
+
+/// Release installed by `pkgbase`, found through the `pkgbase` files.
+pub fn release_for_pkgbase(modules_root: &Path, pkgbase: &str) -> Option<String> {
+    let mut releases: Vec<PathBuf> = fs::read_dir(modules_root)
+        .ok()?
+        .filter_map(|entry| entry.ok().map(|e| e.path()))
+        .collect();
+    releases.sort();
+    releases.into_iter().rev().find_map(|dir| {
+        let release = dir.file_name()?.to_string_lossy().to_string();
+        (pkgbase_for_release(modules_root, &release).as_deref() == Some(pkgbase)).then_some(release)
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    fn observation(boot_id: &str, running: &str, healthy: bool) -> BootObservation {
+        BootObservation {
+            boot_id: boot_id.to_string(),
+            kernel_release: format!("6.18.3-{}", running),
+            running_pkgbase: Some(running.to_string()),
+            running_entry: None,
+            problems: if healthy {
+                Vec::new()
+            } else {
+                vec!["no module directory".to_string()]
+            },
+        }
+    }
+
+    fn tracker_with_good_and_trial() -> BootTracker {
+        let mut tracker = BootTracker::default();
+        tracker.mark_trial(
+            "linux-goatd-server",
+            None,
+            Some("goatd-linux-goatd-server".into()),
+            "b0",
+        );
+        tracker.record_boot(&observation("b1", "linux-goatd-server", true));
+        tracker.mark_trial(
+            "linux-goatd-gaming",
+            None,
+            Some("goatd-linux-goatd-gaming".into()),
+            "b1",
+        );
+        tracker
+    }
+
+    #[test]
+    fn test_trial_promoted_after_healthy_boot() {
+        let mut tracker = tracker_with_good_and_trial();
+        assert_eq!(tracker.status("linux-goatd-server"), Some(BootStatus::Good));
+
+        // Same boot as the installation: nothing happens yet
+        let outcome = tracker.record_boot(&observation("b1", "linux-goatd-server", true));
+        assert!(outcome.already_checked);
+
+        let outcome = tracker.record_boot(&observation("b2", "linux-goatd-gaming", true));
+        assert_eq!(outcome.promoted.as_deref(), Some("linux-goatd-gaming"));
+        assert_eq!(
+            outcome.default_entry.as_deref(),
+            Some("goatd-linux-goatd-gaming")
+        );
+        assert_eq!(tracker.status("linux-goatd-gaming"), Some(BootStatus::Good));
+        assert_eq!(tracker.last_good.as_deref(), Some("linux-goatd-gaming"));
+    }
+
+    #[test]
+    fn test_trial_failed_restores_last_good() {
+        let mut tracker = tracker_with_good_and_trial();
+
+        // The one-shot boot fell back to the previous kernel
+        let outcome = tracker.record_boot(&observation("b2", "linux-goatd-server", true));
+        assert_eq!(outcome.failed, vec!["linux-goatd-gaming".to_string()]);
+        assert_eq!(
+            outcome.default_entry.as_deref(),
+            Some("goatd-linux-goatd-server")
+        );
+        assert_eq!(
+            tracker.status("linux-goatd-gaming"),
+            Some(BootStatus::Failed)
+        );
+        assert!(tracker.kernels["linux-goatd-gaming"].problems[0].contains("instead"));
+    }
+
+    #[test]
+    fn test_trial_failed_restores_untracked_stock_kernel() {
+        let mut tracker = BootTracker::default();
+        tracker.mark_trial(
+            "linux-goatd-gaming",
+            None,
+            Some("goatd-linux-goatd-gaming".into()),
+            "b0",
+        );
+
+        // The trial never came up; the machine is back on the stock kernel
+        let mut stock = observation("b1", "linux", true);
+        stock.running_entry = Some("arch".to_string());
+        let outcome = tracker.record_boot(&stock);
+        assert_eq!(outcome.failed, vec!["linux-goatd-gaming".to_string()]);
+        assert_eq!(tracker.status("linux"), None);
+        assert_eq!(outcome.default_entry.as_deref(), Some("arch"));
+        assert_eq!(tracker.last_good_entry.as_deref(), Some("arch"));
+    }
+
+    #[test]
+    fn test_unhealthy_trial_boot_fails() {
+        let mut tracker = tracker_with_good_and_trial();
+        let outcome = tracker.record_boot(&observation("b2", "linux-goatd-gaming", false));
+        assert_eq!(outcome.failed, vec!["linux-goatd-gaming".to_string()]);
+        assert_eq!(
+            outcome.default_entry.as_deref(),
+            Some("goatd-linux-goatd-server")
+        );
+        assert_eq!(tracker.last_good.as_deref(), Some("linux-goatd-server"));
+    }
+
+    #[test]
+    fn test_default_state_path_is_in_user_config_dir() {
+        let path = default_state_path().unwrap();
+        assert!(path.is_absolute());
+        assert!(path.ends_with("goatdkernel/boot_state.json"));
+    }
+
+    #[test]
+    fn test_state_roundtrip_and_forget() {
+        let dir = TempDir::new().unwrap();
+        let path = dir.path().join("goatdkernel").join(BOOT_STATE_FILE_NAME);
+        assert_eq!(BootTracker::load(&path).unwrap(), BootTracker::default());
+
+        let mut tracker = tracker_with_good_and_trial();
+        tracker.save(&path).unwrap();
+        let loaded = BootTracker::load(&path).unwrap();
+        assert_eq!(loaded, tracker);
+
+        tracker.forget("linux-goatd-server");
+        assert_eq!(tracker.last_good, None);
+        assert_eq!(tracker.statuses().len(), 1);
+    }
+
+    #[test]
+    fn test_pkgbase_release_mapping() {
+        let dir = TempDir::new().unwrap();
+        for (release, pkgbase) in [
+            ("6.18.3-arch1-1", "linux"),
+            ("6.18.3-arch1-1-goatd-gaming", "linux-goatd-gaming"),
+        ] {
+            fs::create_dir_all(dir.path().join(release)).unwrap();
+            fs::write(
+                dir.path().join(release).join("pkgbase"),
+                format!("{}\n", pkgbase),
+            )
+            .unwrap();
+        }
+        assert_eq!(
+            pkgbase_for_release(dir.path(), "6.18.3-arch1-1-goatd-gaming").as_deref(),
+            Some("linux-goatd-gaming")
+        );
+        assert_eq!(
+            release_for_pkgbase(dir.path(), "linux").as_deref(),
+            Some("6.18.3-arch1-1")
+        );
+        assert_eq!(release_for_pkgbase(dir.path(), "linux-lts"), None);
+    }
+}
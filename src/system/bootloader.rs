@@ -0,0 +1,1026 @@
+//! Boot entry management for installed kernels.
+//!
+//! `hardware::boot::detect_boot_manager` only identifies the bootloader; this
+//! module creates and updates the entries that make an installed GOATd kernel
+//! bootable:
+//! - systemd-boot: one `<ESP>/loader/entries/goatd-<pkgbase>.conf` per kernel
+//! - GRUB: a managed `menuentry` block in `<boot>/grub/custom.cfg`, which the
+//!   stock `41_custom` script sources at boot without regenerating `grub.cfg`
+//! - Limine: a managed block in `limine.conf`
+//!
+//! Entries can be marked as the one-shot default for the next boot, and every
+//! entry can be verified to reference a kernel and initramfs that exist.
+//!
+//! Writes to the ESP usually need root, so each change is planned first
+//! ([`EntryPlan`]) and then either applied directly or staged into a
+//! privileged `install` command for `SystemWrapper::batch_privileged_commands`.
+
+use crate::error::AppError;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::process::Command;
+
+/// Prefix of every entry id managed by GOATd.
+pub const ENTRY_PREFIX: &str = "goatd-";
+
+/// Size of a GRUB environment block (`grubenv`), which is always padded to 1 KiB.
+const GRUBENV_SIZE: usize = 1024;
+
+const GRUBENV_HEADER: &str = "# GRUB Environment Block\n";
+
+/// Where the kernel exposes EFI variables.
+pub const EFIVARS_DIR: &str = "/sys/firmware/efi/efivars";
+
+/// EFI variable in which systemd-boot records the entry it booted.
+const LOADER_ENTRY_SELECTED: &str = "LoaderEntrySelected-4a67b082-0a4c-41cf-b6c7-440b29bb8c4f";
+
+/// Microcode images loaded as the first initrd when present.
+const MICROCODE_IMAGES: &[&str] = &["intel-ucode.img", "amd-ucode.img"];
+
+/// Supported boot managers.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum BootloaderKind {
+    SystemdBoot,
+    Grub,
+    Limine,
+}
+
+impl BootloaderKind {
+    /// Map a name reported by `hardware::detect_boot_manager`.
+    pub fn from_name(name: &str) -> Option<Self> {
+        match name.trim().to_lowercase().as_str() {
+            "systemd-boot" | "systemd" => Some(BootloaderKind::SystemdBoot),
+            "grub" | "grub2" => Some(BootloaderKind::Grub),
+            "limine" => Some(BootloaderKind::Limine),
+            _ => None,
+        }
+    }
+
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            BootloaderKind::SystemdBoot => "systemd-boot",
+            BootloaderKind::Grub => "grub",
+            BootloaderKind::Limine => "limine",
+        }
+    }
+
+    /// Whether the boot manager can boot an entry once without changing the default.
+    pub fn supports_oneshot(&self) -> bool {
+        !matches!(self, BootloaderKind::Limine)
+    }
+
+    /// Identify the boot manager from the files present in `layout`.
+    pub fn detect_in(layout: &BootLayout) -> Option<Self> {
+        if layout.limine_config().is_some() {
+            Some(BootloaderKind::Limine)
+        } else if layout.esp.join("loader/loader.conf").is_file()
+            || layout.esp.join("loader/entries").is_dir()
+        {
+            Some(BootloaderKind::SystemdBoot)
+        } else if layout.grub_dir().join("grub.cfg").is_file() {
+            Some(BootloaderKind::Grub)
+        } else {
+            None
+        }
+    }
+}
+
+/// Where the bootloader and the kernel images live.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct BootLayout {
+    /// EFI system partition mount (holds `loader/` or `limine.conf`)
+    pub esp: PathBuf,
+    /// Directory mkinitcpio installs `vmlinuz-*` and `initramfs-*.img` into
+    pub boot: PathBuf,
+    /// Path of `boot` as seen by GRUB on its partition: `/boot`, or empty
+    /// when `/boot` is a separate partition
+    pub grub_prefix: String,
+}
+
+impl BootLayout {
+    pub fn new(esp: impl Into<PathBuf>, boot: impl Into<PathBuf>) -> Self {
+        BootLayout {
+            esp: esp.into(),
+            boot: boot.into(),
+            grub_prefix: "/boot".to_string(),
+        }
+    }
+
+    /// Probe the standard mount points (`/efi`, `/boot/efi`, `/boot`).
+    pub fn detect() -> Self {
+        let esp = ["/efi", "/boot/efi", "/boot"]
+            .iter()
+            .map(PathBuf::from)
+            .find(|dir| {
+                dir.join("EFI").is_dir()
+                    || dir.join("loader").is_dir()
+                    || dir.join("limine.conf").is_file()
+            })
+            .unwrap_or_else(|| PathBuf::from("/boot"));
+
+        let mut layout = BootLayout::new(esp, "/boot");
+        if is_mountpoint(Path::new("/boot")) {
+            layout.grub_prefix = String::new();
+        }
+        layout
+    }
+
+    /// GRUB directory (`grub2` on distributions that rename it).
+    pub fn grub_dir(&self) -> PathBuf {
+        let grub2 = self.boot.join("grub2");
+        if grub2.is_dir() && !self.boot.join("grub").is_dir() {
+            grub2
+        } else {
+            self.boot.join("grub")
+        }
+    }
+
+    /// First existing `limine.conf` in the locations Limine searches.
+    pub fn limine_config(&self) -> Option<PathBuf> {
+        [
+            self.esp.join("limine.conf"),
+            self.esp.join("limine/limine.conf"),
+            self.esp.join("EFI/limine/limine.conf"),
+            self.esp.join("EFI/BOOT/limine.conf"),
+            self.boot.join("limine.conf"),
+            self.boot.join("limine/limine.conf"),
+        ]
+        .into_iter()
+        .find(|path| path.is_file())
+    }
+}
+
+/// Whether `path` is listed as a mount point in `/proc/self/mountinfo`.
+fn is_mountpoint(path: &Path) -> bool {
+    let target = path.to_string_lossy();
+    fs::read_to_string("/proc/self/mountinfo")
+        .map(|info| {
+            info.lines()
+                .any(|line| line.split_whitespace().nth(4) == Some(target.as_ref()))
+        })
+        .unwrap_or(false)
+}
+
+/// Kernel image and initrds installed for one package base.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct KernelImages {
+    pub pkgbase: String,
+    pub kernel: PathBuf,
+    /// Microcode images first, then the initramfs
+    pub initrds: Vec<PathBuf>,
+}
+
+impl KernelImages {
+    /// The images mkinitcpio installs for `pkgbase` (`vmlinuz-<pkgbase>`,
+    /// `initramfs-<pkgbase>.img`), plus any microcode found in `boot`.
+    pub fn for_pkgbase(boot: &Path, pkgbase: &str) -> Self {
+        let mut initrds: Vec<PathBuf> = MICROCODE_IMAGES
+            .iter()
+            .map(|name| boot.join(name))
+            .filter(|path| path.is_file())
+            .collect();
+        initrds.push(boot.join(format!("initramfs-{}.img", pkgbase)));
+
+        KernelImages {
+            pkgbase: pkgbase.to_string(),
+            kernel: boot.join(format!("vmlinuz-{}", pkgbase)),
+            initrds,
+        }
+    }
+}
+
+/// A boot entry to create or update.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct BootEntry {
+    pub id: String,
+    pub title: String,
+    pub images: KernelImages,
+    /// Kernel command line
+    pub options: String,
+}
+
+impl BootEntry {
+    pub fn new(images: KernelImages, options: impl Into<String>) -> Self {
+        BootEntry {
+            id: entry_id(&images.pkgbase),
+            title: format!("GOATd Kernel ({})", images.pkgbase),
+            images,
+            options: options.into(),
+        }
+    }
+}
+
+/// Entry id GOATd uses for a package base.
+pub fn entry_id(pkgbase: &str) -> String {
+    format!("{}{}", ENTRY_PREFIX, pkgbase)
+}
+
+/// Package base of a kernel package file
+/// (`linux-goatd-gaming-6.18.3-1-x86_64.pkg.tar.zst` → `linux-goatd-gaming`).
+pub fn pkgbase_from_package(package: &Path) -> Option<String> {
+    let file_name = package.file_name()?.to_string_lossy();
+    crate::kernel::config_diff::split_package_name(&file_name).map(|(name, _)| name.to_string())
+}
+
+/// New contents of a bootloader file, ready to be written.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct EntryPlan {
+    pub kind: BootloaderKind,
+    pub entry_id: String,
+    pub target: PathBuf,
+    pub content: String,
+}
+
+impl EntryPlan {
+    /// Write the planned content (needs write access to `target`).
+    pub fn apply(&self) -> Result<(), AppError> {
+        if let Some(parent) = self.target.parent() {
+            fs::create_dir_all(parent).map_err(|e| {
+                AppError::Io(format!("Failed to create {}: {}", parent.display(), e))
+            })?;
+        }
+        fs::write(&self.target, &self.content)
+            .map_err(|e| AppError::Io(format!("Failed to write {}: {}", self.target.display(), e)))
+    }
+
+    /// Stage the content under `staging_dir` and return the shell command that
+    /// installs it in place when run as root.
+    pub fn privileged_command(&self, staging_dir: &Path) -> Result<String, AppError> {
+        fs::create_dir_all(staging_dir)?;
+        let staged = staging_dir.join(format!("{}.{}", self.entry_id, self.kind.as_str()));
+        fs::write(&staged, &self.content)
+            .map_err(|e| AppError::Io(format!("Failed to stage {}: {}", staged.display(), e)))?;
+        Ok(format!(
+            "install -Dm644 {} {}",
+            shell_quote(&staged.to_string_lossy()),
+            shell_quote(&self.target.to_string_lossy())
+        ))
+    }
+}
+
+/// Result of checking that an entry points at existing files.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct EntryVerification {
+    pub entry_id: String,
+    /// File the entry was read from
+    pub config: PathBuf,
+    pub kernel: Option<PathBuf>,
+    pub initrds: Vec<PathBuf>,
+    /// Referenced files that do not exist (or a missing kernel line)
+    pub problems: Vec<String>,
+}
+
+impl EntryVerification {
+    pub fn is_bootable(&self) -> bool {
+        self.problems.is_empty()
+    }
+}
+
+/// Creates, verifies and selects boot entries for one boot manager.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct BootloaderManager {
+    pub kind: BootloaderKind,
+    pub layout: BootLayout,
+}
+
+impl BootloaderManager {
+    pub fn new(kind: BootloaderKind, layout: BootLayout) -> Self {
+        BootloaderManager { kind, layout }
+    }
+
+    /// Detect the layout and boot manager of the running system.
+    ///
+    /// Limine is probed first since `detect_boot_manager` does not know it.
+    pub fn detect() -> Result<Self, AppError> {
+        let layout = BootLayout::detect();
+        let detected = crate::hardware::detect_boot_manager()
+            .map_err(|e| AppError::HardwareDetection(e.to_string()))?;
+
+        let kind = layout
+            .limine_config()
+            .map(|_| BootloaderKind::Limine)
+            .or_else(|| BootloaderKind::from_name(&detected))
+            .or_else(|| BootloaderKind::detect_in(&layout))
+            .ok_or_else(|| {
+                AppError::InvalidInput(format!(
+                    "Unsupported boot manager '{}' (expected systemd-boot, GRUB or Limine)",
+                    detected
+                ))
+            })?;
+
+        Ok(BootloaderManager::new(kind, layout))
+    }
+
+    /// File holding the entry `entry_id`.
+    pub fn entry_config_path(&self, entry_id: &str) -> Result<PathBuf, AppError> {
+        match self.kind {
+            BootloaderKind::SystemdBoot => Ok(self
+                .layout
+                .esp
+                .join("loader/entries")
+                .join(format!("{}.conf", entry_id))),
+            BootloaderKind::Grub => Ok(self.layout.grub_dir().join("custom.cfg")),
+            BootloaderKind::Limine => self.layout.limine_config().ok_or_else(|| {
+                AppError::InvalidPath(format!(
+                    "No limine.conf found under {} or {}",
+                    self.layout.esp.display(),
+                    self.layout.boot.display()
+                ))
+            }),
+        }
+    }
+
+    /// Plan the file contents that create or update `entry`.
+    ///
+    /// GRUB and Limine keep one managed block per entry inside a shared file;
+    /// any block for the same id is replaced, everything else is preserved.
+    pub fn plan_entry(&self, entry: &BootEntry) -> Result<EntryPlan, AppError> {
+        let target = self.entry_config_path(&entry.id)?;
+
+        let content = match self.kind {
+            BootloaderKind::SystemdBoot => self.render_systemd_boot(entry)?,
+            BootloaderKind::Grub | BootloaderKind::Limine => {
+                let block = if self.kind == BootloaderKind::Grub {
+                    self.render_grub(entry)?
+                } else {
+                    self.render_limine(entry)?
+                };
+                let existing = match fs::read_to_string(&target) {
+                    Ok(existing) => existing,
+                    Err(e) if e.kind() == std::io::ErrorKind::NotFound => String::new(),
+                    Err(e) => {
+                        return Err(AppError::Io(format!(
+                            "Failed to read {}: {}",
+                            target.display(),
+                            e
+                        )))
+                    }
+                };
+                replace_block(&existing, &entry.id, &block)
+            }
+        };
+
+        Ok(EntryPlan {
+            kind: self.kind,
+            entry_id: entry.id.clone(),
+            target,
+            content,
+        })
+    }
+
+    /// Privileged shell commands that install `entry` and, with `next_boot`,
+    /// make it the one-shot default. Entry contents are staged in `staging_dir`.
+    pub fn install_commands(
+        &self,
+        entry: &BootEntry,
+        staging_dir: &Path,
+        next_boot: bool,
+    ) -> Result<Vec<String>, AppError> {
+        let mut commands = vec![self.plan_entry(entry)?.privileged_command(staging_dir)?];
+        if next_boot {
+            commands.push(self.oneshot_command(&entry.id)?);
+        }
+        Ok(commands)
+    }
+
+    /// Plan and write `entry` directly (needs write access to the ESP).
+    pub fn install_entry(&self, entry: &BootEntry) -> Result<PathBuf, AppError> {
+        let plan = self.plan_entry(entry)?;
+        plan.apply()?;
+        Ok(plan.target)
+    }
+
+    fn render_systemd_boot(&self, entry: &BootEntry) -> Result<String, AppError> {
+        let mut out = String::from("# Managed by GOATd Kernel\n");
+        out.push_str(&format!("title   {}\n", entry.title));
+        out.push_str(&format!(
+            "linux   {}\n",
+            path_on_partition(&entry.images.kernel, &self.layout.esp)?
+        ));
+        for initrd in &entry.images.initrds {
+            out.push_str(&format!(
+                "initrd  {}\n",
+                path_on_partition(initrd, &self.layout.esp)?
+            ));
+        }
+        out.push_str(&format!("options {}\n", entry.options));
+        Ok(out)
+    }
+
+    fn render_grub(&self, entry: &BootEntry) -> Result<String, AppError> {
+        let kernel = self.grub_path(&entry.images.kernel)?;
+        let initrds = entry
+            .images
+            .initrds
+            .iter()
+            .map(|path| self.grub_path(path))
+            .collect::<Result<Vec<_>, _>>()?;
+
+        Ok(format!(
+            "menuentry '{title}' --id '{id}' {{\n\
+             \tsearch --no-floppy --file --set=root {kernel}\n\
+             \tlinux {kernel} {options}\n\
+             \tinitrd {initrds}\n\
+             }}\n",
+            title = entry.title.replace('\'', ""),
+            id = entry.id,
+            kernel = kernel,
+            options = entry.options,
+            initrds = initrds.join(" "),
+        ))
+    }
+
+    fn render_limine(&self, entry: &BootEntry) -> Result<String, AppError> {
+        let mut out = format!("/{}\n", entry.title);
+        out.push_str("    protocol: linux\n");
+        out.push_str(&format!(
+            "    path: boot():{}\n",
+            path_on_partition(&entry.images.kernel, &self.layout.esp)?
+        ));
+        out.push_str(&format!("    cmdline: {}\n", entry.options));
+        for initrd in &entry.images.initrds {
+            out.push_str(&format!(
+                "    module_path: boot():{}\n",
+                path_on_partition(initrd, &self.layout.esp)?
+            ));
+        }
+        Ok(out)
+    }
+
+    /// Path of a file under `boot` as GRUB sees it.
+    fn grub_path(&self, path: &Path) -> Result<String, AppError> {
+        let relative = path_on_partition(path, &self.layout.boot)?;
+        Ok(format!("{}{}", self.layout.grub_prefix, relative))
+    }
+
+    /// Map a path written in an entry back to the host filesystem.
+    fn host_path(&self, entry_path: &str) -> PathBuf {
+        let (root, path) = match self.kind {
+            BootloaderKind::SystemdBoot => (&self.layout.esp, entry_path),
+            BootloaderKind::Limine => (
+                &self.layout.esp,
+                entry_path.strip_prefix("boot():").unwrap_or(entry_path),
+            ),
+            BootloaderKind::Grub => (
+                &self.layout.boot,
+                entry_path
+                    .strip_prefix(self.layout.grub_prefix.as_str())
+                    .unwrap_or(entry_path),
+            ),
+        };
+        root.join(path.trim_start_matches('/'))
+    }
+
+    /// Check that the entry exists and references an existing kernel and initramfs.
+    pub fn verify_entry(&self, entry_id: &str) -> Result<EntryVerification, AppError> {
+        let config = self.entry_config_path(entry_id)?;
+        let content = fs::read_to_string(&config).map_err(|e| {
+            AppError::InvalidPath(format!(
+                "Boot entry '{}' not readable at {}: {}",
+                entry_id,
+                config.display(),
+                e
+            ))
+        })?;
+
+        let body = match self.kind {
+            BootloaderKind::SystemdBoot => content,
+            BootloaderKind::Grub | BootloaderKind::Limine => find_block(&content, entry_id)
+                .ok_or_else(|| {
+                    AppError::InvalidPath(format!(
+                        "Boot entry '{}' not found in {}",
+                        entry_id,
+                        config.display()
+                    ))
+                })?,
+        };
+
+        let mut kernel = None;
+        let mut initrds = Vec::new();
+        for line in body.lines() {
+            let line = line.trim();
+            let (key, value) = match self.kind {
+                BootloaderKind::Limine => match line.split_once(':') {
+                    Some((key, value)) => (key.trim(), value.trim()),
+                    None => continue,
+                },
+                _ => match line.split_once(char::is_whitespace) {
+                    Some((key, value)) => (key, value.trim()),
+                    None => continue,
+                },
+            };
+            match (self.kind, key) {
+                (BootloaderKind::SystemdBoot, "linux") | (BootloaderKind::Limine, "path") => {
+                    kernel = Some(self.host_path(value));
+                }
+                (BootloaderKind::Grub, "linux") => {
+                    kernel = value.split_whitespace().next().map(|p| self.host_path(p));
+                }
+                (BootloaderKind::SystemdBoot, "initrd")
+                | (BootloaderKind::Limine, "module_path") => {
+                    initrds.push(self.host_path(value));
+                }
+                (BootloaderKind::Grub, "initrd") => {
+                    initrds.extend(value.split_whitespace().map(|p| self.host_path(p)));
+                }
+                _ => {}
+            }
+        }
+
+        let mut problems = Vec::new();
+        match &kernel {
+            Some(path) if !path.is_file() => {
+                problems.push(format!("kernel image missing: {}", path.display()))
+            }
+            Some(_) => {}
+            None => problems.push("entry has no kernel line".to_string()),
+        }
+        if initrds.is_empty() {
+            problems.push("entry has no initramfs".to_string());
+        }
+        for initrd in initrds.iter().filter(|path| !path.is_file()) {
+            problems.push(format!("initramfs missing: {}", initrd.display()));
+        }
+
+        Ok(EntryVerification {
+            entry_id: entry_id.to_string(),
+            config,
+            kernel,
+            initrds,
+            problems,
+        })
+    }
+
+    /// Privileged shell commands that make `entry_id` the persistent default.
+    ///
+    /// GRUB reads `saved_entry` only with `GRUB_DEFAULT=saved`. Limine selects
+    /// its default by position, so `limine.conf` is rewritten (staged in
+    /// `staging_dir`) with `default_entry` pointing at the entry.
+    pub fn default_commands(
+        &self,
+        entry_id: &str,
+        staging_dir: &Path,
+    ) -> Result<Vec<String>, AppError> {
+        match self.kind {
+            BootloaderKind::SystemdBoot => Ok(vec![format!(
+                "bootctl --esp-path={} set-default {}",
+                shell_quote(&self.layout.esp.to_string_lossy()),
+                shell_quote(&format!("{}.conf", entry_id))
+            )]),
+            BootloaderKind::Grub => Ok(vec![format!(
+                "grub-editenv {} set {}",
+                shell_quote(&self.layout.grub_dir().join("grubenv").to_string_lossy()),
+                shell_quote(&format!("saved_entry={}", entry_id))
+            )]),
+            BootloaderKind::Limine => Ok(vec![self
+                .plan_limine_default(entry_id)?
+                .privileged_command(staging_dir)?]),
+        }
+    }
+
+    /// Plan `limine.conf` with `default_entry` set to the position of `entry_id`.
+    pub fn plan_limine_default(&self, entry_id: &str) -> Result<EntryPlan, AppError> {
+        let target = self.entry_config_path(entry_id)?;
+        let content = fs::read_to_string(&target)
+            .map_err(|e| AppError::Io(format!("Failed to read {}: {}", target.display(), e)))?;
+
+        let title = find_block(&content, entry_id)
+            .and_then(|block| block.lines().next().map(|line| line.trim().to_string()))
+            .ok_or_else(|| {
+                AppError::InvalidPath(format!(
+                    "Boot entry '{}' not found in {}",
+                    entry_id,
+                    target.display()
+                ))
+            })?;
+
+        // Top-level entries start with a single '/'; sub-entries use '//'
+        let index = content
+            .lines()
+            .map(str::trim)
+            .filter(|line| line.starts_with('/') && !line.starts_with("//"))
+            .position(|line| line == title)
+            .map(|i| i + 1)
+            .unwrap_or(1);
+
+        let mut out = String::new();
+        let mut replaced = false;
+        for line in content.lines() {
+            if line.trim_start().starts_with("default_entry:") {
+                out.push_str(&format!("default_entry: {}\n", index));
+                replaced = true;
+            } else {
+                out.push_str(line);
+                out.push('\n');
+            }
+        }
+        if !replaced {
+            out = format!("default_entry: {}\n{}", index, out);
+        }
+
+        Ok(EntryPlan {
+            kind: self.kind,
+            entry_id: entry_id.to_string(),
+            target,
+            content: out,
+        })
+    }
+
+    /// Shell command that boots `entry_id` once on the next boot (run as root).
+    ///
+    /// GRUB honours `next_entry` from `grubenv` through its stock `00_header`.
+    /// Limine has no one-shot mechanism.
+    pub fn oneshot_command(&self, entry_id: &str) -> Result<String, AppError> {
+        match self.kind {
+            BootloaderKind::SystemdBoot => Ok(format!(
+                "bootctl --esp-path={} set-oneshot {}",
+                shell_quote(&self.layout.esp.to_string_lossy()),
+                shell_quote(&format!("{}.conf", entry_id))
+            )),
+            BootloaderKind::Grub => Ok(format!(
+                "grub-editenv {} set {}",
+                shell_quote(&self.layout.grub_dir().join("grubenv").to_string_lossy()),
+                shell_quote(&format!("next_entry={}", entry_id))
+            )),
+            BootloaderKind::Limine => Err(limine_oneshot_error()),
+        }
+    }
+
+    /// Make `entry_id` the default for the next boot only.
+    ///
+    /// systemd-boot stores the choice in an EFI variable via `bootctl`; GRUB
+    /// gets `next_entry` written into `grubenv` directly.
+    pub fn set_oneshot(&self, entry_id: &str) -> Result<(), AppError> {
+        match self.kind {
+            BootloaderKind::SystemdBoot => {
+                let output = Command::new("bootctl")
+                    .arg(format!("--esp-path={}", self.layout.esp.display()))
+                    .arg("set-oneshot")
+                    .arg(format!("{}.conf", entry_id))
+                    .output()
+                    .map_err(|e| AppError::OsCommand {
+                        cmd: "bootctl set-oneshot".to_string(),
+                        reason: e.to_string(),
+                    })?;
+                if !output.status.success() {
+                    return Err(AppError::OsCommand {
+                        cmd: "bootctl set-oneshot".to_string(),
+                        reason: String::from_utf8_lossy(&output.stderr).trim().to_string(),
+                    });
+                }
+                Ok(())
+            }
+            BootloaderKind::Grub => set_grubenv_var(
+                &self.layout.grub_dir().join("grubenv"),
+                "next_entry",
+                entry_id,
+            ),
+            BootloaderKind::Limine => Err(limine_oneshot_error()),
+        }
+    }
+}
+
+fn limine_oneshot_error() -> AppError {
+    AppError::InvalidInput(
+        "Limine does not support a one-shot default entry; select the entry at the boot menu"
+            .to_string(),
+    )
+}
+
+/// Absolute path of `path` on the partition mounted at `root`.
+fn path_on_partition(path: &Path, root: &Path) -> Result<String, AppError> {
+    let relative = path.strip_prefix(root).map_err(|_| {
+        AppError::InvalidPath(format!(
+            "{} is not on the boot partition {} and cannot be read by the bootloader",
+            path.display(),
+            root.display()
+        ))
+    })?;
+    Ok(format!("/{}", relative.to_string_lossy()))
+}
+
+fn block_markers(entry_id: &str) -> (String, String) {
+    (
+        format!("# >>> GOATd {} >>>", entry_id),
+        format!("# <<< GOATd {} <<<", entry_id),
+    )
+}
+
+/// Text between the managed markers of `entry_id`.
+fn find_block(content: &str, entry_id: &str) -> Option<String> {
+    let (begin, end) = block_markers(entry_id);
+    let start = content.find(&begin)? + begin.len();
+    let stop = start + content[start..].find(&end)?;
+    Some(content[start..stop].trim_matches('\n').to_string())
+}
+
+/// Replace (or append) the managed block of `entry_id` in `content`.
+fn replace_block(content: &str, entry_id: &str, block: &str) -> String {
+    let (begin, end) = block_markers(entry_id);
+    let managed = format!("{}\n{}\n{}\n", begin, block.trim_end(), end);
+
+    if let Some(start) = content.find(&begin) {
+        if let Some(offset) = content[start..].find(&end) {
+            let mut stop = start + offset + end.len();
+            if content[stop..].starts_with('\n') {
+                stop += 1;
+            }
+            return format!("{}{}{}", &content[..start], managed, &content[stop..]);
+        }
+    }
+
+    let mut out = content.to_string();
+    if !out.is_empty() {
+        if !out.ends_with('\n') {
+            out.push('\n');
+        }
+        out.push('\n');
+    }
+    out.push_str(&managed);
+    out
+}
+
+/// Set `key=value` in a GRUB environment block, keeping its fixed 1 KiB size.
+pub fn set_grubenv_var(path: &Path, key: &str, value: &str) -> Result<(), AppError> {
+    let existing = match fs::read_to_string(path) {
+        Ok(content) => content,
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => String::new(),
+        Err(e) => {
+            return Err(AppError::Io(format!(
+                "Failed to read {}: {}",
+                path.display(),
+                e
+            )))
+        }
+    };
+
+    let mut out = String::from(GRUBENV_HEADER);
+    for line in existing.lines() {
+        if line.starts_with('#') || line.is_empty() {
+            continue;
+        }
+        if line.split_once('=').is_some_and(|(k, _)| k == key) {
+            continue;
+        }
+        out.push_str(line);
+        out.push('\n');
+    }
+    out.push_str(&format!("{}={}\n", key, value));
+
+    if out.len() > GRUBENV_SIZE {
+        return Err(AppError::InvalidInput(format!(
+            "{} would exceed the {}-byte GRUB environment block",
+            path.display(),
+            GRUBENV_SIZE
+        )));
+    }
+    out.push_str(&"#".repeat(GRUBENV_SIZE - out.len()));
+
+    fs::write(path, out)
+        .map_err(|e| AppError::Io(format!("Failed to write {}: {}", path.display(), e)))
+}
+
+/// Entry systemd-boot booted, as an id accepted by `default_commands`.
+///
+/// Read from the `LoaderEntrySelected` EFI variable: a 4-byte attribute
+/// header followed by a NUL-terminated UTF-16LE string (`arch.conf`).
+/// Unified kernel images (`*.efi`) have no `.conf` entry and yield `None`,
+/// as do GRUB and Limine, which do not export the booted entry.
+pub fn booted_entry_id(efivars_dir: &Path) -> Option<String> {
+    let raw = fs::read(efivars_dir.join(LOADER_ENTRY_SELECTED)).ok()?;
+    let units: Vec<u16> = raw
+        .get(4..)?
+        .chunks_exact(2)
+        .map(|pair| u16::from_le_bytes([pair[0], pair[1]]))
+        .take_while(|&unit| unit != 0)
+        .collect();
+    let selected = String::from_utf16(&units).ok()?;
+    let id = selected.strip_suffix(".conf").unwrap_or(&selected);
+    (!id.is_empty() && !id.ends_with(".efi")).then(|| id.to_string())
+}
+
+/// Command line of the running kernel without bootloader-injected arguments.
+pub fn current_cmdline() -> Result<String, AppError> {
+    let cmdline = fs::read_to_string("/proc/cmdline")
+        .map_err(|e| AppError::Io(format!("Failed to read /proc/cmdline: {}", e)))?;
+    Ok(sanitize_cmdline(&cmdline))
+}
+
+/// Drop `BOOT_IMAGE=` and `initrd=` arguments, which the bootloader adds itself.
+pub fn sanitize_cmdline(cmdline: &str) -> String {
+    cmdline
+        .split_whitespace()
+        .filter(|arg| !arg.starts_with("BOOT_IMAGE=") && !arg.starts_with("initrd="))
+        .collect::<Vec<_>>()
+        .join(" ")
+}
+
+fn shell_quote(value: &str) -> String {
+    format!("'{}'", value.replace('\'', "'\\''"))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    /// A fake ESP mounted at `/boot` with one installed kernel.
+    fn fake_boot(pkgbase: &str) -> (TempDir, BootLayout) {
+        let dir = TempDir::new().unwrap();
+        let boot = dir.path().join("boot");
+        fs::create_dir_all(boot.join("loader/entries")).unwrap();
+        fs::write(boot.join("loader/loader.conf"), "timeout 3\n").unwrap();
+        fs::write(boot.join(format!("vmlinuz-{}", pkgbase)), b"kernel").unwrap();
+        fs::write(boot.join(format!("initramfs-{}.img", pkgbase)), b"initrd").unwrap();
+        fs::write(boot.join("amd-ucode.img"), b"ucode").unwrap();
+        let layout = BootLayout::new(&boot, &boot);
+        (dir, layout)
+    }
+
+    fn entry_for(layout: &BootLayout, pkgbase: &str) -> BootEntry {
+        BootEntry::new(
+            KernelImages::for_pkgbase(&layout.boot, pkgbase),
+            "root=UUID=1234 rw quiet",
+        )
+    }
+
+    #[test]
+    fn test_systemd_boot_entry_roundtrip() {
+        let (_dir, layout) = fake_boot("linux-goatd-gaming");
+        assert_eq!(
+            BootloaderKind::detect_in(&layout),
+            Some(BootloaderKind::SystemdBoot)
+        );
+        let manager = BootloaderManager::new(BootloaderKind::SystemdBoot, layout.clone());
+        let entry = entry_for(&layout, "linux-goatd-gaming");
+
+        let path = manager.install_entry(&entry).unwrap();
+        assert_eq!(
+            path,
+            layout
+                .esp
+                .join("loader/entries/goatd-linux-goatd-gaming.conf")
+        );
+        let content = fs::read_to_string(&path).unwrap();
+        assert!(content.contains("linux   /vmlinuz-linux-goatd-gaming\n"));
+        assert!(
+            content.contains("initrd  /amd-ucode.img\ninitrd  /initramfs-linux-goatd-gaming.img\n")
+        );
+        assert!(content.contains("options root=UUID=1234 rw quiet\n"));
+
+        let verification = manager.verify_entry(&entry.id).unwrap();
+        assert!(verification.is_bootable(), "{:?}", verification.problems);
+        assert_eq!(verification.initrds.len(), 2);
+
+        fs::remove_file(layout.boot.join("initramfs-linux-goatd-gaming.img")).unwrap();
+        let verification = manager.verify_entry(&entry.id).unwrap();
+        assert!(!verification.is_bootable());
+        assert!(verification.problems[0].contains("initramfs missing"));
+    }
+
+    #[test]
+    fn test_kernel_outside_esp_is_rejected() {
+        let (dir, layout) = fake_boot("linux-goatd");
+        let esp_layout = BootLayout::new(dir.path().join("efi"), &layout.boot);
+        let manager = BootloaderManager::new(BootloaderKind::SystemdBoot, esp_layout.clone());
+        let err = manager
+            .plan_entry(&entry_for(&esp_layout, "linux-goatd"))
+            .unwrap_err();
+        assert!(matches!(err, AppError::InvalidPath(_)));
+    }
+
+    #[test]
+    fn test_grub_block_is_updated_in_place() {
+        let (_dir, mut layout) = fake_boot("linux-goatd");
+        layout.grub_prefix = String::new();
+        let grub_dir = layout.grub_dir();
+        fs::create_dir_all(&grub_dir).unwrap();
+        fs::write(grub_dir.join("grub.cfg"), "# generated\n").unwrap();
+        fs::write(grub_dir.join("custom.cfg"), "menuentry 'Mine' {\n}\n").unwrap();
+
+        let manager = BootloaderManager::new(BootloaderKind::Grub, layout.clone());
+        let mut entry = entry_for(&layout, "linux-goatd");
+        manager.install_entry(&entry).unwrap();
+        entry.options = "root=UUID=5678 rw".to_string();
+        manager.install_entry(&entry).unwrap();
+
+        let content = fs::read_to_string(grub_dir.join("custom.cfg")).unwrap();
+        assert!(content.starts_with("menuentry 'Mine' {\n}\n"));
+        assert_eq!(
+            content.matches("# >>> GOATd goatd-linux-goatd >>>").count(),
+            1
+        );
+        assert!(content.contains("\tlinux /vmlinuz-linux-goatd root=UUID=5678 rw\n"));
+        assert!(content.contains("\tinitrd /amd-ucode.img /initramfs-linux-goatd.img\n"));
+        assert!(manager.verify_entry(&entry.id).unwrap().is_bootable());
+    }
+
+    #[test]
+    fn test_grub_oneshot_writes_grubenv() {
+        let (_dir, layout) = fake_boot("linux-goatd");
+        let grubenv = layout.grub_dir().join("grubenv");
+        fs::create_dir_all(layout.grub_dir()).unwrap();
+        set_grubenv_var(&grubenv, "saved_entry", "Arch Linux").unwrap();
+
+        let manager = BootloaderManager::new(BootloaderKind::Grub, layout);
+        manager.set_oneshot("goatd-linux-goatd").unwrap();
+
+        let content = fs::read_to_string(&grubenv).unwrap();
+        assert_eq!(content.len(), GRUBENV_SIZE);
+        assert!(content.starts_with(GRUBENV_HEADER));
+        assert!(content.contains("saved_entry=Arch Linux\nnext_entry=goatd-linux-goatd\n#"));
+    }
+
+    #[test]
+    fn test_limine_entry_and_oneshot() {
+        let (_dir, layout) = fake_boot("linux-goatd");
+        fs::write(layout.esp.join("limine.conf"), "timeout: 5\n").unwrap();
+        assert_eq!(
+            BootloaderKind::detect_in(&layout),
+            Some(BootloaderKind::Limine)
+        );
+
+        let manager = BootloaderManager::new(BootloaderKind::Limine, layout.clone());
+        let entry = entry_for(&layout, "linux-goatd");
+        manager.install_entry(&entry).unwrap();
+
+        let content = fs::read_to_string(layout.esp.join("limine.conf")).unwrap();
+        assert!(content.starts_with("timeout: 5\n\n# >>> GOATd"));
+        assert!(content.contains("    path: boot():/vmlinuz-linux-goatd\n"));
+        assert!(content.contains("    module_path: boot():/initramfs-linux-goatd.img\n"));
+        assert!(manager.verify_entry(&entry.id).unwrap().is_bootable());
+        assert!(manager.set_oneshot(&entry.id).is_err());
+        assert!(manager.oneshot_command(&entry.id).is_err());
+
+        let plan = manager.plan_limine_default(&entry.id).unwrap();
+        assert!(plan.content.starts_with("default_entry: 1\ntimeout: 5\n"));
+    }
+
+    #[test]
+    fn test_privileged_command_stages_content() {
+        let (dir, layout) = fake_boot("linux-goatd");
+        let manager = BootloaderManager::new(BootloaderKind::SystemdBoot, layout.clone());
+        let plan = manager
+            .plan_entry(&entry_for(&layout, "linux-goatd"))
+            .unwrap();
+        let cmd = plan
+            .privileged_command(&dir.path().join("staging"))
+            .unwrap();
+        assert!(cmd.starts_with("install -Dm644 '"));
+        assert!(cmd.ends_with("loader/entries/goatd-linux-goatd.conf'"));
+        let staged = dir.path().join("staging/goatd-linux-goatd.systemd-boot");
+        assert_eq!(fs::read_to_string(staged).unwrap(), plan.content);
+    }
+
+    #[test]
+    fn test_install_commands_and_pkgbase() {
+        let (dir, layout) = fake_boot("linux-goatd");
+        let manager = BootloaderManager::new(BootloaderKind::SystemdBoot, layout.clone());
+        let commands = manager
+            .install_commands(&entry_for(&layout, "linux-goatd"), dir.path(), true)
+            .unwrap();
+        assert_eq!(commands.len(), 2);
+        assert!(commands[1].starts_with("bootctl --esp-path="));
+        assert!(commands[1].ends_with("set-oneshot 'goatd-linux-goatd.conf'"));
+
+        assert_eq!(
+            pkgbase_from_package(Path::new(
+                "/ws/linux-goatd-gaming-6.18.3.arch1-1-x86_64.pkg.tar.zst"
+            )),
+            Some("linux-goatd-gaming".to_string())
+        );
+    }
+
+    #[test]
+    fn test_booted_entry_id() {
+        let dir = TempDir::new().unwrap();
+        assert_eq!(booted_entry_id(dir.path()), None);
+
+        let write_selected = |entry: &str| {
+            let mut raw = vec![0x06, 0x00, 0x00, 0x00];
+            for unit in entry.encode_utf16().chain([0]) {
+                raw.extend_from_slice(&unit.to_le_bytes());
+            }
+            fs::write(dir.path().join(LOADER_ENTRY_SELECTED), raw).unwrap();
+        };
+        write_selected("arch.conf");
+        assert_eq!(booted_entry_id(dir.path()).as_deref(), Some("arch"));
+        write_selected("arch-linux.efi");
+        assert_eq!(booted_entry_id(dir.path()), None);
+    }
+
+    #[test]
+    fn test_sanitize_cmdline() {
+        assert_eq!(
+            sanitize_cmdline(
+                "BOOT_IMAGE=/vmlinuz-linux root=UUID=1 rw initrd=\\initramfs.img quiet\n"
+            ),
+            "root=UUID=1 rw quiet"
+        );
+        assert_eq!(
+            BootloaderKind::from_name("GRUB"),
+            Some(BootloaderKind::Grub)
+        );
+        assert_eq!(BootloaderKind::from_name("refind"), None);
+    }
+}
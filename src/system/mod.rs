@@ -1,3 +1,5 @@
+pub mod boot_tracking;
+pub mod bootloader;
 pub mod health;
 pub mod performance;
 /// System module: security-validated command execution, input validation
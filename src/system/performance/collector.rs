@@ -814,6 +814,30 @@ impl LatencyProcessor {
         self.rolling_samples.clear();
     }
 
+    /// Merge another processor's samples into this one (multi-core merged view)
+    /// Combines histograms, buckets and maxima; the rolling window keeps the most
+    /// recent samples of both, capped at the rolling window size
+    pub fn merge(&mut self, other: &LatencyProcessor) -> Result<(), Box<dyn std::error::Error>> {
+        self.histogram.add(&other.histogram)?;
+        self.max_latency_ns = self.max_latency_ns.max(other.max_latency_ns);
+        self.sample_count += other.sample_count;
+        for (bucket, count) in self.buckets.iter_mut().zip(other.buckets.iter()) {
+            *bucket += count;
+        }
+        self.cycle_max_ns = self.cycle_max_ns.max(other.cycle_max_ns);
+        if other.sample_count > 0 {
+            self.last_sample_ns = other.last_sample_ns;
+        }
+
+        self.rolling_samples
+            .extend(other.rolling_samples.iter().copied());
+        while self.rolling_samples.len() > self.rolling_max_size {
+            self.rolling_samples.pop_front();
+        }
+
+        Ok(())
+    }
+
     /// Set core temperatures directly (for seamless restart transition)
     /// This prevents heatmap blackout when monitoring is restarted
     pub fn set_core_temperatures(&mut self, temps: Vec<f32>) {
@@ -1066,6 +1090,35 @@ mod tests {
         );
     }
 
+    #[test]
+    fn test_processor_merge() {
+        let mut p_core = LatencyProcessor::default();
+        let mut e_core = LatencyProcessor::default();
+        for _ in 0..99 {
+            p_core
+                .record_sample(2_000)
+                .expect("Failed to record sample");
+            e_core
+                .record_sample(8_000)
+                .expect("Failed to record sample");
+        }
+        e_core
+            .record_sample(90_000)
+            .expect("Failed to record sample");
+
+        let mut merged = LatencyProcessor::default();
+        merged.merge(&p_core).expect("Failed to merge");
+        merged.merge(&e_core).expect("Failed to merge");
+
+        assert_eq!(merged.sample_count(), 199);
+        assert!((merged.max() - 90.0).abs() < 0.1);
+        // 99 of 199 samples are 2 µs: P49 falls on the P-core side, P51 on the E-core side
+        assert!(merged.histogram.value_at_percentile(49.0) <= 2_100);
+        assert!(merged.histogram.value_at_percentile(51.0) >= 7_900);
+        assert!(merged.p99() >= 7.9);
+        assert!((merged.average() - (99.0 * 2.0 + 99.0 * 8.0 + 90.0) / 199.0).abs() < 0.01);
+    }
+
     #[test]
     fn test_processor_millisecond_accuracy() {
         let mut processor = LatencyProcessor::default();
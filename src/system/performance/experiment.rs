@@ -0,0 +1,406 @@
+//! A/B kernel experiments
+//!
+//! A single benchmark run is too noisy to tell two kernels apart on a desktop,
+//! so an experiment repeats the same benchmark N times per kernel and stores
+//! every run as its own `PerformanceRecord` tagged with the experiment name.
+//! Runs are grouped by experiment and `KernelContext`, and each metric is
+//! compared on the medians of the two groups:
+//!
+//! - 95% confidence interval of each median (distribution-free, order statistics)
+//! - Two-sided Mann-Whitney U test (exact for small tie-free samples, normal
+//!   approximation with tie correction otherwise)
+//! - Verdict: improved / regressed only when p < [`SIGNIFICANCE_LEVEL`]
+
+use super::PerformanceMetrics;
+use serde::{Deserialize, Serialize};
+
+/// p-value below which a difference is reported as significant
+pub const SIGNIFICANCE_LEVEL: f64 = 0.05;
+
+/// Fewest runs per kernel for a verdict (3 vs 3 cannot reach p < 0.05)
+pub const MIN_RUNS: usize = 4;
+
+/// Default number of repetitions per kernel in the Performance tab
+pub const DEFAULT_REPETITIONS: usize = 8;
+
+/// z for a two-sided 95% interval
+const Z_95: f64 = 1.959964;
+
+/// Largest n_a * n_b for which the exact U distribution is computed
+const EXACT_U_LIMIT: usize = 400;
+
+/// Metric compared between the two groups of runs (all lower-is-better)
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub enum ComparedMetric {
+    MinLatency,
+    MaxLatency,
+    AvgLatency,
+    P99Latency,
+    P999Latency,
+    SmiCount,
+    StallCount,
+}
+
+impl ComparedMetric {
+    pub const ALL: [ComparedMetric; 7] = [
+        ComparedMetric::MinLatency,
+        ComparedMetric::MaxLatency,
+        ComparedMetric::AvgLatency,
+        ComparedMetric::P99Latency,
+        ComparedMetric::P999Latency,
+        ComparedMetric::SmiCount,
+        ComparedMetric::StallCount,
+    ];
+
+    pub fn label(&self) -> &'static str {
+        match self {
+            ComparedMetric::MinLatency => "Min Latency (µs)",
+            ComparedMetric::MaxLatency => "Max Latency (µs)",
+            ComparedMetric::AvgLatency => "Avg Latency (µs)",
+            ComparedMetric::P99Latency => "P99 (µs)",
+            ComparedMetric::P999Latency => "P99.9 (µs)",
+            ComparedMetric::SmiCount => "SMI Count",
+            ComparedMetric::StallCount => "Stall Correlated",
+        }
+    }
+
+    /// Value of this metric in one run
+    pub fn value(&self, metrics: &PerformanceMetrics) -> f64 {
+        match self {
+            // The stored session metrics keep the last sample, used as "min" by the UI
+            ComparedMetric::MinLatency => metrics.current_us as f64,
+            ComparedMetric::MaxLatency => metrics.max_us as f64,
+            ComparedMetric::AvgLatency => metrics.avg_us as f64,
+            ComparedMetric::P99Latency => metrics.p99_us as f64,
+            ComparedMetric::P999Latency => metrics.p99_9_us as f64,
+            ComparedMetric::SmiCount => metrics.total_smis as f64,
+            ComparedMetric::StallCount => metrics.spikes_correlated_to_smi as f64,
+        }
+    }
+}
+
+/// Outcome of comparing one metric between kernel A and kernel B
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub enum Verdict {
+    /// B is significantly lower (better) than A
+    Improved,
+    /// B is significantly higher (worse) than A
+    Regressed,
+    /// The runs do not show a difference at the significance level
+    NoSignificantDifference,
+    /// Fewer than [`MIN_RUNS`] runs on either side
+    InsufficientData,
+}
+
+impl Verdict {
+    pub fn label(&self) -> &'static str {
+        match self {
+            Verdict::Improved => "improved",
+            Verdict::Regressed => "regressed",
+            Verdict::NoSignificantDifference => "no significant difference",
+            Verdict::InsufficientData => "not enough runs",
+        }
+    }
+
+    pub fn is_significant(&self) -> bool {
+        matches!(self, Verdict::Improved | Verdict::Regressed)
+    }
+}
+
+/// Statistics for one metric across both groups of runs
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct MetricComparison {
+    pub metric: ComparedMetric,
+    pub median_a: f64,
+    pub median_b: f64,
+    /// 95% confidence interval of the median of A
+    pub ci_a: (f64, f64),
+    /// 95% confidence interval of the median of B
+    pub ci_b: (f64, f64),
+    /// (median_b - median_a) / median_a * 100 (0 when median_a is 0)
+    pub delta_percent: f64,
+    /// Two-sided Mann-Whitney p-value (None without enough runs)
+    pub p_value: Option<f64>,
+    pub verdict: Verdict,
+}
+
+/// Result of an A/B experiment comparison
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct ExperimentComparison {
+    pub runs_a: usize,
+    pub runs_b: usize,
+    pub metrics: Vec<MetricComparison>,
+}
+
+impl ExperimentComparison {
+    /// Comparison row for one metric
+    pub fn metric(&self, metric: ComparedMetric) -> Option<&MetricComparison> {
+        self.metrics.iter().find(|m| m.metric == metric)
+    }
+}
+
+/// Compare every [`ComparedMetric`] between two groups of runs
+pub fn compare_runs(
+    runs_a: &[PerformanceMetrics],
+    runs_b: &[PerformanceMetrics],
+) -> ExperimentComparison {
+    let metrics = ComparedMetric::ALL
+        .iter()
+        .map(|&metric| {
+            let a: Vec<f64> = runs_a.iter().map(|m| metric.value(m)).collect();
+            let b: Vec<f64> = runs_b.iter().map(|m| metric.value(m)).collect();
+            compare_metric(metric, &a, &b)
+        })
+        .collect();
+
+    ExperimentComparison {
+        runs_a: runs_a.len(),
+        runs_b: runs_b.len(),
+        metrics,
+    }
+}
+
+fn compare_metric(metric: ComparedMetric, a: &[f64], b: &[f64]) -> MetricComparison {
+    let median_a = median(a);
+    let median_b = median(b);
+    let delta_percent = if median_a != 0.0 {
+        (median_b - median_a) / median_a * 100.0
+    } else {
+        0.0
+    };
+
+    let (p_value, verdict) = if a.len() < MIN_RUNS || b.len() < MIN_RUNS {
+        (None, Verdict::InsufficientData)
+    } else {
+        let p = mann_whitney_p(a, b);
+        let verdict = if p >= SIGNIFICANCE_LEVEL || median_a == median_b {
+            Verdict::NoSignificantDifference
+        } else if median_b < median_a {
+            Verdict::Improved
+        } else {
+            Verdict::Regressed
+        };
+        (Some(p), verdict)
+    };
+
+    MetricComparison {
+        metric,
+        median_a,
+        median_b,
+        ci_a: median_ci(a),
+        ci_b: median_ci(b),
+        delta_percent,
+        p_value,
+        verdict,
+    }
+}
+
+fn sorted(values: &[f64]) -> Vec<f64> {
+    let mut sorted = values.to_vec();
+    sorted.sort_by(|x, y| x.total_cmp(y));
+    sorted
+}
+
+/// Median (0 for no values)
+pub fn median(values: &[f64]) -> f64 {
+    let sorted = sorted(values);
+    let n = sorted.len();
+    match n {
+        0 => 0.0,
+        _ if n % 2 == 1 => sorted[n / 2],
+        _ => (sorted[n / 2 - 1] + sorted[n / 2]) / 2.0,
+    }
+}
+
+/// 95% confidence interval of the median from order statistics
+///
+/// Uses ranks `n/2 ∓ z·√n/2` of the sorted sample; with few runs this widens
+/// to the full range of the sample.
+pub fn median_ci(values: &[f64]) -> (f64, f64) {
+    let sorted = sorted(values);
+    let n = sorted.len();
+    if n == 0 {
+        return (0.0, 0.0);
+    }
+    let half_width = Z_95 * (n as f64).sqrt() / 2.0;
+    let lower = ((n as f64 / 2.0 - half_width).floor() as isize).clamp(1, n as isize) as usize;
+    let upper = ((1.0 + n as f64 / 2.0 + half_width).ceil() as isize).clamp(1, n as isize) as usize;
+    (sorted[lower - 1], sorted[upper - 1])
+}
+
+/// Mann-Whitney U statistic of `a` against `b` (ties count one half)
+pub fn mann_whitney_u(a: &[f64], b: &[f64]) -> f64 {
+    a.iter()
+        .map(|&x| {
+            b.iter()
+                .map(|&y| {
+                    if x > y {
+                        1.0
+                    } else if x == y {
+                        0.5
+                    } else {
+                        0.0
+                    }
+                })
+                .sum::<f64>()
+        })
+        .sum()
+}
+
+/// Two-sided p-value of the Mann-Whitney U test
+pub fn mann_whitney_p(a: &[f64], b: &[f64]) -> f64 {
+    let (n_a, n_b) = (a.len(), b.len());
+    if n_a == 0 || n_b == 0 {
+        return 1.0;
+    }
+    let u = mann_whitney_u(a, b);
+
+    let pooled = sorted(&[a, b].concat());
+    let has_ties = pooled.windows(2).any(|w| w[0] == w[1]);
+    if !has_ties && n_a * n_b <= EXACT_U_LIMIT {
+        return exact_u_p(n_a, n_b, u);
+    }
+
+    // Normal approximation with tie and continuity correction
+    let n = (n_a + n_b) as f64;
+    let mut tie_term = 0.0;
+    let mut i = 0;
+    while i < pooled.len() {
+        let mut j = i;
+        while j + 1 < pooled.len() && pooled[j + 1] == pooled[i] {
+            j += 1;
+        }
+        let t = (j - i + 1) as f64;
+        tie_term += t * t * t - t;
+        i = j + 1;
+    }
+    let mean = (n_a * n_b) as f64 / 2.0;
+    let variance = (n_a * n_b) as f64 / 12.0 * ((n + 1.0) - tie_term / (n * (n - 1.0)));
+    if variance <= 0.0 {
+        return 1.0;
+    }
+    let z = ((u - mean).abs() - 0.5).max(0.0) / variance.sqrt();
+    erfc(z / std::f64::consts::SQRT_2).min(1.0)
+}
+
+/// Exact two-sided p-value for U from the null distribution (no ties)
+fn exact_u_p(n_a: usize, n_b: usize, u: f64) -> f64 {
+    // counts[i][j][k]: arrangements of i values of A and j of B with U = k
+    let mut counts: Vec<Vec<Vec<f64>>> = vec![vec![Vec::new(); n_b + 1]; n_a + 1];
+    for i in 0..=n_a {
+        for j in 0..=n_b {
+            counts[i][j] = if i == 0 || j == 0 {
+                vec![1.0]
+            } else {
+                // The largest value is either from A (beats all j of B) or from B
+                let mut dist = vec![0.0; i * j + 1];
+                for (k, c) in counts[i - 1][j].iter().enumerate() {
+                    dist[k + j] += c;
+                }
+                for (k, c) in counts[i][j - 1].iter().enumerate() {
+                    dist[k] += c;
+                }
+                dist
+            };
+        }
+    }
+
+    let dist = &counts[n_a][n_b];
+    let total: f64 = dist.iter().sum();
+    let u = u.round() as usize;
+    let lower: f64 = dist[..=u].iter().sum::<f64>() / total;
+    let upper: f64 = dist[u..].iter().sum::<f64>() / total;
+    (2.0 * lower.min(upper)).min(1.0)
+}
+
+/// Complementary error function (Abramowitz & Stegun 7.1.26, |ε| < 1.5e-7)
+fn erfc(x: f64) -> f64 {
+    if x < 0.0 {
+        return 2.0 - erfc(-x);
+    }
+    let t = 1.0 / (1.0 + 0.3275911 * x);
+    let poly = t
+        * (0.254829592
+            + t * (-0.284496736 + t * (1.421413741 + t * (-1.453152027 + t * 1.061405429))));
+    poly * (-x * x).exp()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn runs(values: &[f32]) -> Vec<PerformanceMetrics> {
+        values
+            .iter()
+            .map(|&v| PerformanceMetrics {
+                max_us: v,
+                avg_us: v / 10.0,
+                ..Default::default()
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_median_and_ci() {
+        assert_eq!(median(&[3.0, 1.0, 2.0]), 2.0);
+        assert_eq!(median(&[4.0, 1.0, 2.0, 3.0]), 2.5);
+        assert_eq!(median(&[]), 0.0);
+
+        let values: Vec<f64> = (1..=20).map(f64::from).collect();
+        let (lo, hi) = median_ci(&values);
+        assert!(lo < 10.5 && hi > 10.5);
+        assert_eq!((lo, hi), (5.0, 16.0));
+        // Few runs: the interval is the sample range
+        assert_eq!(median_ci(&[5.0, 1.0, 3.0]), (1.0, 5.0));
+    }
+
+    #[test]
+    fn test_mann_whitney_exact_and_normal() {
+        // Complete separation of 5 vs 5: p = 2 / C(10, 5)
+        let a = [10.0, 11.0, 12.0, 13.0, 14.0];
+        let b = [1.0, 2.0, 3.0, 4.0, 5.0];
+        assert_eq!(mann_whitney_u(&a, &b), 25.0);
+        assert!((mann_whitney_p(&a, &b) - 2.0 / 252.0).abs() < 1e-12);
+
+        // Interleaved samples are not significant
+        let c = [1.0, 3.0, 5.0, 7.0, 9.0];
+        let d = [2.0, 4.0, 6.0, 8.0, 10.0];
+        assert!(mann_whitney_p(&c, &d) > 0.5);
+
+        // Ties switch to the normal approximation
+        let e = [1.0, 1.0, 2.0, 2.0, 3.0, 3.0];
+        assert!((mann_whitney_p(&e, &e) - 1.0).abs() < 1e-6);
+        assert!((erfc(0.0) - 1.0).abs() < 1e-7);
+        assert!((erfc(1.959964 / std::f64::consts::SQRT_2) - 0.05).abs() < 1e-4);
+    }
+
+    #[test]
+    fn test_compare_runs_verdicts() {
+        // Noise around the same value: no significant difference
+        let a = runs(&[100.0, 104.0, 98.0, 101.0, 103.0, 97.0]);
+        let b = runs(&[99.0, 105.0, 100.0, 96.0, 102.0, 104.0]);
+        let cmp = compare_runs(&a, &b);
+        let max = cmp.metric(ComparedMetric::MaxLatency).unwrap();
+        assert_eq!(max.verdict, Verdict::NoSignificantDifference);
+        assert!(max.p_value.unwrap() > SIGNIFICANCE_LEVEL);
+
+        // Consistently lower max latency on B
+        let b = runs(&[80.0, 82.0, 79.0, 81.0, 83.0, 78.0]);
+        let cmp = compare_runs(&a, &b);
+        let max = cmp.metric(ComparedMetric::MaxLatency).unwrap();
+        assert_eq!(max.verdict, Verdict::Improved);
+        assert!(max.delta_percent < -15.0);
+        assert_eq!(cmp.runs_a, 6);
+
+        // All-zero SMI counts never differ
+        let smi = cmp.metric(ComparedMetric::SmiCount).unwrap();
+        assert_eq!(smi.verdict, Verdict::NoSignificantDifference);
+
+        // Single records cannot be judged
+        let cmp = compare_runs(&a[..1], &b[..1]);
+        assert_eq!(
+            cmp.metric(ComparedMetric::MaxLatency).unwrap().verdict,
+            Verdict::InsufficientData
+        );
+    }
+}
@@ -0,0 +1,847 @@
+//! Spike Forensics: Root-cause capture around latency spikes
+//!
+//! `LatencyCollector` counts spikes and correlates SMIs, but a spike that is not
+//! an SMI says nothing about its cause. This module runs on its own thread, off
+//! the hot path, and samples the measured core every [`FORENSICS_WINDOW`]:
+//! - `/proc/interrupts` and `/proc/softirqs` counters for that CPU
+//! - cpuidle state entry counts (`/sys/devices/system/cpu/cpuN/cpuidle`)
+//! - the tasks on the core's run queue (only when a spike occurred)
+//!
+//! A window without spikes becomes the baseline. When the collector's spike
+//! counter moves, each source's delta is compared against the baseline rate and
+//! the spike is attributed to the source with the largest excess. Runnable tasks
+//! are only blamed when no interrupt, softirq or idle source stands out, since
+//! that leaves preemption as the likely cause. Results are tallied into a
+//! [`ForensicsReport`] whose top offenders end up in the `SessionSummary`.
+
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicBool, AtomicU64, Ordering};
+use std::sync::{Arc, RwLock};
+use std::time::{Duration, Instant};
+
+/// Sampling interval of the forensics thread
+pub const FORENSICS_WINDOW: Duration = Duration::from_millis(100);
+
+/// Number of offenders attached to a session summary
+pub const TOP_OFFENDERS: usize = 5;
+
+/// Minimum excess events over the baseline for a source to be a candidate
+/// (periodic sources like the local timer drift by one event per window)
+const MIN_EXCESS_EVENTS: f64 = 2.0;
+
+/// Selects one counter group (interrupts, softirqs, idle states) of a snapshot
+type CounterGroup = fn(&CpuSnapshot) -> &BTreeMap<String, u64>;
+
+/// Category of a spike source
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum OffenderKind {
+    /// Hardware or architectural interrupt (row of /proc/interrupts)
+    Irq,
+    /// Softirq vector (row of /proc/softirqs)
+    Softirq,
+    /// Idle state exit (cpuidle)
+    IdleState,
+    /// Task on the measured core's run queue
+    Task,
+}
+
+impl OffenderKind {
+    /// Short display label
+    pub fn label(&self) -> &'static str {
+        match self {
+            OffenderKind::Irq => "IRQ",
+            OffenderKind::Softirq => "softirq",
+            OffenderKind::IdleState => "C-state",
+            OffenderKind::Task => "task",
+        }
+    }
+}
+
+/// A ranked spike source, as reported in the session summary
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct SpikeOffender {
+    pub kind: OffenderKind,
+    /// IRQ number/name, softirq vector, idle state name or task command
+    pub name: String,
+    /// Device/description for IRQs, exit latency for idle states, pid for tasks
+    #[serde(default)]
+    pub detail: String,
+    /// Spikes for which this source was the most likely cause
+    #[serde(default)]
+    pub attributed_spikes: u64,
+    /// Spikes during which this source was active above its baseline
+    #[serde(default)]
+    pub seen_in_spikes: u64,
+    /// Events above the baseline rate, summed across spike windows
+    /// (run-queue appearances for tasks)
+    #[serde(default)]
+    pub excess_events: f64,
+}
+
+/// Tally of all analyzed spikes in a session
+#[derive(Clone, Debug, Default, PartialEq, Serialize, Deserialize)]
+pub struct ForensicsReport {
+    /// Spikes that fell into an analyzed window
+    pub spikes_analyzed: u64,
+    /// Spikes for which no source stood out
+    pub unattributed_spikes: u64,
+    /// All offenders, most attributed spikes first
+    pub offenders: Vec<SpikeOffender>,
+}
+
+impl ForensicsReport {
+    /// The `n` highest ranked offenders
+    pub fn top_offenders(&self, n: usize) -> Vec<SpikeOffender> {
+        self.offenders.iter().take(n).cloned().collect()
+    }
+}
+
+/// One row of /proc/interrupts or /proc/softirqs
+#[derive(Clone, Debug, PartialEq)]
+pub struct CounterRow {
+    /// Row label without the colon ("142", "LOC", "NET_RX")
+    pub id: String,
+    /// Count per CPU column
+    pub per_cpu: Vec<u64>,
+    /// Trailing description (chip, hwirq and device for IRQs)
+    pub description: String,
+}
+
+/// Parse /proc/interrupts or /proc/softirqs
+///
+/// Returns the CPU ids of the header columns (offline CPUs have no column) and
+/// the counter rows. Rows with fewer counters than CPUs (ERR, MIS) keep the
+/// counters they have.
+pub fn parse_proc_counters(content: &str) -> (Vec<usize>, Vec<CounterRow>) {
+    let mut lines = content.lines();
+    let cpus: Vec<usize> = lines
+        .next()
+        .unwrap_or_default()
+        .split_whitespace()
+        .filter_map(|col| col.strip_prefix("CPU")?.parse().ok())
+        .collect();
+
+    let rows = lines
+        .filter_map(|line| {
+            let (id, rest) = line.split_once(':')?;
+            let mut tokens = rest.split_whitespace().peekable();
+            let mut per_cpu = Vec::with_capacity(cpus.len());
+            while per_cpu.len() < cpus.len() {
+                match tokens.peek().and_then(|t| t.parse::<u64>().ok()) {
+                    Some(count) => {
+                        per_cpu.push(count);
+                        tokens.next();
+                    }
+                    None => break,
+                }
+            }
+            Some(CounterRow {
+                id: id.trim().to_string(),
+                per_cpu,
+                description: tokens.collect::<Vec<_>>().join(" "),
+            })
+        })
+        .collect();
+
+    (cpus, rows)
+}
+
+/// Counters of one cpuidle state
+#[derive(Clone, Debug, PartialEq)]
+pub struct IdleStateCounters {
+    pub name: String,
+    /// Times the state was entered
+    pub usage: u64,
+    /// Exit latency (µs)
+    pub exit_latency_us: u64,
+}
+
+/// Read the cpuidle states of `cpu` below `sys_root`
+///
+/// Returns an empty list when cpuidle is not available (VMs, idle=poll).
+pub fn read_idle_states(sys_root: &Path, cpu: usize) -> Vec<IdleStateCounters> {
+    let dir = sys_root
+        .join("devices/system/cpu")
+        .join(format!("cpu{}", cpu))
+        .join("cpuidle");
+    let mut states: Vec<(usize, IdleStateCounters)> = match fs::read_dir(&dir) {
+        Ok(entries) => entries
+            .filter_map(|entry| entry.ok())
+            .map(|entry| entry.path())
+            .filter_map(|path| {
+                let index = path
+                    .file_name()?
+                    .to_string_lossy()
+                    .strip_prefix("state")?
+                    .parse()
+                    .ok()?;
+                let read = |file: &str| fs::read_to_string(path.join(file)).ok();
+                let state = IdleStateCounters {
+                    name: read("name")?.trim().to_string(),
+                    usage: read("usage")?.trim().parse().ok()?,
+                    exit_latency_us: read("latency")
+                        .and_then(|l| l.trim().parse().ok())
+                        .unwrap_or(0),
+                };
+                Some((index, state))
+            })
+            .collect(),
+        Err(_) => Vec::new(),
+    };
+    states.sort_by_key(|(index, _)| *index);
+    states.into_iter().map(|(_, state)| state).collect()
+}
+
+/// The fields of /proc/<pid>/task/<tid>/stat used for run-queue attribution
+#[derive(Clone, Debug, PartialEq)]
+pub struct TaskStat {
+    pub tid: u32,
+    pub comm: String,
+    pub state: char,
+    /// CPU the task last ran on (field 39)
+    pub processor: usize,
+}
+
+/// Parse a /proc stat line
+///
+/// The command is taken up to the last ')', since it may contain spaces and
+/// parentheses itself.
+pub fn parse_task_stat(line: &str) -> Option<TaskStat> {
+    let open = line.find('(')?;
+    let close = line.rfind(')')?;
+    let tid = line[..open].trim().parse().ok()?;
+    let comm = line.get(open + 1..close)?.to_string();
+    let fields: Vec<&str> = line[close + 1..].split_whitespace().collect();
+    // fields[0] is field 3 (state), so field 39 (processor) is fields[36]
+    Some(TaskStat {
+        tid,
+        comm,
+        state: fields.first()?.chars().next()?,
+        processor: fields.get(36)?.parse().ok()?,
+    })
+}
+
+/// Runnable tasks that last ran on `cpu`, excluding process `exclude_pid`
+pub fn read_runqueue(proc_root: &Path, cpu: usize, exclude_pid: u32) -> Vec<TaskStat> {
+    let mut tasks = Vec::new();
+    let Ok(processes) = fs::read_dir(proc_root) else {
+        return tasks;
+    };
+    for process in processes.filter_map(|entry| entry.ok()) {
+        let pid: u32 = match process.file_name().to_string_lossy().parse() {
+            Ok(pid) => pid,
+            Err(_) => continue,
+        };
+        if pid == exclude_pid {
+            continue;
+        }
+        let Ok(threads) = fs::read_dir(process.path().join("task")) else {
+            continue;
+        };
+        for thread in threads.filter_map(|entry| entry.ok()) {
+            let Ok(line) = fs::read_to_string(thread.path().join("stat")) else {
+                continue;
+            };
+            if let Some(task) = parse_task_stat(&line) {
+                if task.state == 'R' && task.processor == cpu {
+                    tasks.push(task);
+                }
+            }
+        }
+    }
+    tasks.sort_by_key(|task| task.tid);
+    tasks
+}
+
+/// Counters of the measured CPU at one point in time
+#[derive(Clone, Debug, PartialEq)]
+pub struct CpuSnapshot {
+    /// Time since the forensics session started
+    pub at: Duration,
+    pub interrupts: BTreeMap<String, u64>,
+    pub softirqs: BTreeMap<String, u64>,
+    pub idle_states: BTreeMap<String, u64>,
+}
+
+impl CpuSnapshot {
+    /// Build a snapshot of `cpu` from /proc file contents and cpuidle counters
+    ///
+    /// Also returns the IRQ descriptions for offender details.
+    pub fn from_proc(
+        cpu: usize,
+        interrupts: &str,
+        softirqs: &str,
+        idle_states: &[IdleStateCounters],
+        at: Duration,
+    ) -> (Self, BTreeMap<String, String>) {
+        let column = |content: &str| {
+            let (cpus, rows) = parse_proc_counters(content);
+            let index = cpus.iter().position(|&c| c == cpu);
+            rows.into_iter()
+                .filter_map(|row| {
+                    let count = *row.per_cpu.get(index?)?;
+                    Some((row.id, count, row.description))
+                })
+                .collect::<Vec<_>>()
+        };
+
+        let irq_rows = column(interrupts);
+        let descriptions = irq_rows
+            .iter()
+            .map(|(id, _, description)| (id.clone(), description.clone()))
+            .collect();
+        let snapshot = CpuSnapshot {
+            at,
+            interrupts: irq_rows
+                .into_iter()
+                .map(|(id, count, _)| (id, count))
+                .collect(),
+            softirqs: column(softirqs)
+                .into_iter()
+                .map(|(id, count, _)| (id, count))
+                .collect(),
+            idle_states: idle_states
+                .iter()
+                .map(|state| (state.name.clone(), state.usage))
+                .collect(),
+        };
+        (snapshot, descriptions)
+    }
+}
+
+/// Where the forensics module reads kernel counters from
+#[derive(Clone, Debug)]
+pub struct ForensicsSources {
+    pub proc_root: PathBuf,
+    pub sys_root: PathBuf,
+}
+
+impl Default for ForensicsSources {
+    fn default() -> Self {
+        ForensicsSources {
+            proc_root: PathBuf::from("/proc"),
+            sys_root: PathBuf::from("/sys"),
+        }
+    }
+}
+
+/// Outcome of analyzing one spike window
+#[derive(Clone, Debug, PartialEq)]
+pub struct SpikeAttribution {
+    /// Spikes in the window
+    pub spikes: u64,
+    /// Most likely cause, if any source stood out
+    pub cause: Option<(OffenderKind, String)>,
+    /// Sources above baseline, highest excess first
+    pub candidates: Vec<(OffenderKind, String, f64)>,
+}
+
+/// Per-session spike forensics state for one measured CPU
+pub struct SpikeForensics {
+    cpu: usize,
+    sources: ForensicsSources,
+    started: Instant,
+    own_pid: u32,
+    previous: Option<CpuSnapshot>,
+    /// Deltas and length of the last window without spikes
+    baseline: Option<(CpuSnapshot, f64)>,
+    irq_descriptions: BTreeMap<String, String>,
+    idle_latencies: BTreeMap<String, u64>,
+    tally: BTreeMap<(OffenderKind, String), SpikeOffender>,
+    spikes_analyzed: u64,
+    unattributed_spikes: u64,
+}
+
+impl SpikeForensics {
+    /// Create forensics for `cpu` reading the live /proc and /sys
+    pub fn new(cpu: usize) -> Self {
+        Self::with_sources(cpu, ForensicsSources::default())
+    }
+
+    /// Create forensics for `cpu` reading from the given roots
+    pub fn with_sources(cpu: usize, sources: ForensicsSources) -> Self {
+        SpikeForensics {
+            cpu,
+            sources,
+            started: Instant::now(),
+            own_pid: std::process::id(),
+            previous: None,
+            baseline: None,
+            irq_descriptions: BTreeMap::new(),
+            idle_latencies: BTreeMap::new(),
+            tally: BTreeMap::new(),
+            spikes_analyzed: 0,
+            unattributed_spikes: 0,
+        }
+    }
+
+    /// Read the current counters of the measured CPU
+    pub fn snapshot(&mut self) -> io::Result<CpuSnapshot> {
+        let interrupts = fs::read_to_string(self.sources.proc_root.join("interrupts"))?;
+        let softirqs = fs::read_to_string(self.sources.proc_root.join("softirqs"))?;
+        let idle_states = read_idle_states(&self.sources.sys_root, self.cpu);
+        Ok(self.build_snapshot(&interrupts, &softirqs, &idle_states, self.started.elapsed()))
+    }
+
+    /// Build a snapshot from already-read counters, remembering offender details
+    pub fn build_snapshot(
+        &mut self,
+        interrupts: &str,
+        softirqs: &str,
+        idle_states: &[IdleStateCounters],
+        at: Duration,
+    ) -> CpuSnapshot {
+        let (snapshot, descriptions) =
+            CpuSnapshot::from_proc(self.cpu, interrupts, softirqs, idle_states, at);
+        self.irq_descriptions.extend(descriptions);
+        for state in idle_states {
+            self.idle_latencies
+                .insert(state.name.clone(), state.exit_latency_us);
+        }
+        snapshot
+    }
+
+    /// Runnable tasks on the measured CPU, excluding this process
+    pub fn runqueue(&self) -> Vec<TaskStat> {
+        read_runqueue(&self.sources.proc_root, self.cpu, self.own_pid)
+    }
+
+    /// Feed the next snapshot and the spikes seen since the previous one
+    ///
+    /// Quiet windows update the baseline; a window with spikes is attributed
+    /// and tallied. `runqueue` is the run-queue capture taken for that window.
+    pub fn observe(
+        &mut self,
+        snapshot: CpuSnapshot,
+        spikes: u64,
+        runqueue: &[TaskStat],
+    ) -> Option<SpikeAttribution> {
+        let previous = self.previous.replace(snapshot.clone())?;
+        let window = delta(&previous, &snapshot);
+        let window_secs = snapshot.at.saturating_sub(previous.at).as_secs_f64();
+
+        if spikes == 0 {
+            self.baseline = Some((window, window_secs));
+            return None;
+        }
+
+        let attribution = self.attribute(&window, window_secs, spikes, runqueue);
+        self.tally(&attribution, runqueue);
+        Some(attribution)
+    }
+
+    fn attribute(
+        &self,
+        window: &CpuSnapshot,
+        window_secs: f64,
+        spikes: u64,
+        runqueue: &[TaskStat],
+    ) -> SpikeAttribution {
+        let scale = match self.baseline {
+            Some((_, baseline_secs)) if baseline_secs > 0.0 => window_secs / baseline_secs,
+            _ => 1.0,
+        };
+        let expected = |counters: CounterGroup, id: &str| {
+            self.baseline
+                .as_ref()
+                .and_then(|(baseline, _)| counters(baseline).get(id))
+                .map(|&count| count as f64 * scale)
+                .unwrap_or(0.0)
+        };
+
+        let mut candidates = Vec::new();
+        let groups: [(OffenderKind, CounterGroup); 3] = [
+            (OffenderKind::Irq, |s| &s.interrupts),
+            (OffenderKind::Softirq, |s| &s.softirqs),
+            (OffenderKind::IdleState, |s| &s.idle_states),
+        ];
+        for (kind, counters) in groups {
+            for (id, &count) in counters(window) {
+                let excess = count as f64 - expected(counters, id);
+                if excess >= MIN_EXCESS_EVENTS {
+                    candidates.push((kind, id.clone(), excess));
+                }
+            }
+        }
+        candidates.sort_by(|a, b| b.2.total_cmp(&a.2).then_with(|| a.0.cmp(&b.0)));
+
+        let cause = candidates
+            .first()
+            .map(|(kind, id, _)| (*kind, id.clone()))
+            .or_else(|| {
+                runqueue
+                    .first()
+                    .map(|task| (OffenderKind::Task, task.comm.clone()))
+            });
+
+        SpikeAttribution {
+            spikes,
+            cause,
+            candidates,
+        }
+    }
+
+    fn tally(&mut self, attribution: &SpikeAttribution, runqueue: &[TaskStat]) {
+        self.spikes_analyzed += attribution.spikes;
+        if attribution.cause.is_none() {
+            self.unattributed_spikes += attribution.spikes;
+        }
+
+        let mut seen: Vec<(OffenderKind, String, String, f64)> = attribution
+            .candidates
+            .iter()
+            .map(|(kind, id, excess)| (*kind, id.clone(), self.detail(*kind, id), *excess))
+            .collect();
+        for task in runqueue {
+            seen.push((
+                OffenderKind::Task,
+                task.comm.clone(),
+                format!("pid {}", task.tid),
+                1.0,
+            ));
+        }
+
+        for (kind, name, detail, excess) in seen {
+            let entry = self
+                .tally
+                .entry((kind, name.clone()))
+                .or_insert_with(|| SpikeOffender {
+                    kind,
+                    name,
+                    detail,
+                    attributed_spikes: 0,
+                    seen_in_spikes: 0,
+                    excess_events: 0.0,
+                });
+            entry.seen_in_spikes += attribution.spikes;
+            entry.excess_events += excess;
+        }
+
+        if let Some(cause) = &attribution.cause {
+            if let Some(entry) = self.tally.get_mut(cause) {
+                entry.attributed_spikes += attribution.spikes;
+            }
+        }
+    }
+
+    fn detail(&self, kind: OffenderKind, id: &str) -> String {
+        match kind {
+            OffenderKind::Irq => self.irq_descriptions.get(id).cloned().unwrap_or_default(),
+            OffenderKind::IdleState => self
+                .idle_latencies
+                .get(id)
+                .map(|latency| format!("exit latency {}µs", latency))
+                .unwrap_or_default(),
+            OffenderKind::Softirq | OffenderKind::Task => String::new(),
+        }
+    }
+
+    /// Ranked report of all spikes analyzed so far
+    pub fn report(&self) -> ForensicsReport {
+        let mut offenders: Vec<SpikeOffender> = self.tally.values().cloned().collect();
+        offenders.sort_by(|a, b| {
+            b.attributed_spikes
+                .cmp(&a.attributed_spikes)
+                .then(b.seen_in_spikes.cmp(&a.seen_in_spikes))
+                .then(b.excess_events.total_cmp(&a.excess_events))
+        });
+        ForensicsReport {
+            spikes_analyzed: self.spikes_analyzed,
+            unattributed_spikes: self.unattributed_spikes,
+            offenders,
+        }
+    }
+}
+
+/// Per-counter increase from `before` to `after`
+fn delta(before: &CpuSnapshot, after: &CpuSnapshot) -> CpuSnapshot {
+    let diff = |a: &BTreeMap<String, u64>, b: &BTreeMap<String, u64>| {
+        b.iter()
+            .map(|(id, &count)| {
+                let prev = a.get(id).copied().unwrap_or(count);
+                (id.clone(), count.saturating_sub(prev))
+            })
+            .collect()
+    };
+    CpuSnapshot {
+        at: after.at.saturating_sub(before.at),
+        interrupts: diff(&before.interrupts, &after.interrupts),
+        softirqs: diff(&before.softirqs, &after.softirqs),
+        idle_states: diff(&before.idle_states, &after.idle_states),
+    }
+}
+
+/// Spawn the forensics thread for the measured `cpu`
+///
+/// Samples every [`FORENSICS_WINDOW`] until `stop_flag` is set, watching
+/// `spike_count` for new spikes, and publishes the tally into `report`.
+pub fn spawn_spike_forensics(
+    cpu: usize,
+    stop_flag: Arc<AtomicBool>,
+    spike_count: Arc<AtomicU64>,
+    report: Arc<RwLock<ForensicsReport>>,
+) -> std::thread::JoinHandle<()> {
+    std::thread::spawn(move || {
+        let mut forensics = SpikeForensics::new(cpu);
+        let mut last_spikes = spike_count.load(Ordering::Relaxed);
+        eprintln!("[FORENSICS] Spike forensics started (cpu={})", cpu);
+
+        while !stop_flag.load(Ordering::Acquire) {
+            std::thread::sleep(FORENSICS_WINDOW);
+
+            let snapshot = match forensics.snapshot() {
+                Ok(snapshot) => snapshot,
+                Err(e) => {
+                    eprintln!("[FORENSICS] Warning: Cannot read interrupt counters: {}", e);
+                    break;
+                }
+            };
+            let spikes_now = spike_count.load(Ordering::Relaxed);
+            let spikes = spikes_now.saturating_sub(last_spikes);
+            last_spikes = spikes_now;
+
+            let runqueue = if spikes > 0 {
+                forensics.runqueue()
+            } else {
+                Vec::new()
+            };
+            if let Some(attribution) = forensics.observe(snapshot, spikes, &runqueue) {
+                match &attribution.cause {
+                    Some((kind, name)) => eprintln!(
+                        "[FORENSICS] {} spike(s) attributed to {} {} ({} candidates, {} runnable tasks)",
+                        attribution.spikes,
+                        kind.label(),
+                        name,
+                        attribution.candidates.len(),
+                        runqueue.len()
+                    ),
+                    None => eprintln!(
+                        "[FORENSICS] {} spike(s) without a standout source",
+                        attribution.spikes
+                    ),
+                }
+                if let Ok(mut shared) = report.write() {
+                    *shared = forensics.report();
+                }
+            }
+        }
+
+        eprintln!("[FORENSICS] Spike forensics stopped (cpu={})", cpu);
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const INTERRUPTS: [&str; 3] = [
+        include_str!("../../../tests/fixtures/forensics/interrupts_t0"),
+        include_str!("../../../tests/fixtures/forensics/interrupts_t1"),
+        include_str!("../../../tests/fixtures/forensics/interrupts_t2"),
+    ];
+    const SOFTIRQS: [&str; 3] = [
+        include_str!("../../../tests/fixtures/forensics/softirqs_t0"),
+        include_str!("../../../tests/fixtures/forensics/softirqs_t1"),
+        include_str!("../../../tests/fixtures/forensics/softirqs_t2"),
+    ];
+    const TASK_STAT: &str = include_str!("../../../tests/fixtures/forensics/task_stat");
+
+    fn write_idle_states(sys_root: &Path, cpu: usize, states: &[(&str, u64, u64)]) {
+        for (i, (name, usage, latency)) in states.iter().enumerate() {
+            let dir = sys_root.join(format!("devices/system/cpu/cpu{}/cpuidle/state{}", cpu, i));
+            fs::create_dir_all(&dir).unwrap();
+            fs::write(dir.join("name"), format!("{}\n", name)).unwrap();
+            fs::write(dir.join("usage"), format!("{}\n", usage)).unwrap();
+            fs::write(dir.join("latency"), format!("{}\n", latency)).unwrap();
+        }
+    }
+
+    #[test]
+    fn test_parse_proc_counters_fixtures() {
+        let (cpus, rows) = parse_proc_counters(INTERRUPTS[0]);
+        assert_eq!(cpus, vec![0, 1, 2, 3]);
+        let wifi = rows.iter().find(|row| row.id == "142").unwrap();
+        assert_eq!(wifi.per_cpu, vec![0, 0, 201377, 0]);
+        assert_eq!(wifi.description, "PCI-MSI 333824-edge iwlwifi");
+        let loc = rows.iter().find(|row| row.id == "LOC").unwrap();
+        assert_eq!(loc.description, "Local timer interrupts");
+        // ERR has a single global counter
+        let err = rows.iter().find(|row| row.id == "ERR").unwrap();
+        assert_eq!(err.per_cpu, vec![0]);
+
+        let (cpus, rows) = parse_proc_counters(SOFTIRQS[0]);
+        assert_eq!(cpus.len(), 4);
+        assert_eq!(rows.len(), 10);
+        let net_rx = rows.iter().find(|row| row.id == "NET_RX").unwrap();
+        assert_eq!(net_rx.per_cpu[2], 412871);
+
+        // Offline CPUs have no column, so ids follow the header
+        let (cpus, rows) = parse_proc_counters("      CPU0  CPU2\n LOC:  5  7  Local timer\n");
+        assert_eq!(cpus, vec![0, 2]);
+        let (snapshot, _) = CpuSnapshot::from_proc(
+            2,
+            "      CPU0  CPU2\n LOC:  5  7  Local timer\n",
+            "",
+            &[],
+            Duration::ZERO,
+        );
+        assert_eq!(rows[0].per_cpu, vec![5, 7]);
+        assert_eq!(snapshot.interrupts["LOC"], 7);
+    }
+
+    #[test]
+    fn test_parse_task_stat_and_runqueue() {
+        let tasks: Vec<TaskStat> = TASK_STAT.lines().filter_map(parse_task_stat).collect();
+        assert_eq!(tasks.len(), 7);
+        assert_eq!(tasks[2].comm, "Web Content");
+        assert_eq!(tasks[2].state, 'R');
+        assert_eq!(tasks[2].processor, 2);
+        assert_eq!(tasks[3].comm, "(sd-pam)");
+        assert_eq!(tasks[6].comm, "cc1plus) R (x");
+        assert_eq!(tasks[6].processor, 2);
+
+        let proc_root = tempfile::tempdir().unwrap();
+        for (line, task) in TASK_STAT.lines().zip(&tasks) {
+            let dir = proc_root
+                .path()
+                .join(task.tid.to_string())
+                .join("task")
+                .join(task.tid.to_string());
+            fs::create_dir_all(&dir).unwrap();
+            fs::write(dir.join("stat"), line).unwrap();
+        }
+        // Non-process entries are skipped
+        fs::create_dir_all(proc_root.path().join("sys")).unwrap();
+
+        // Runnable on cpu2, without our own process (3051)
+        let runqueue = read_runqueue(proc_root.path(), 2, 3051);
+        let names: Vec<&str> = runqueue.iter().map(|task| task.comm.as_str()).collect();
+        assert_eq!(names, vec!["Web Content", "cc1plus) R (x"]);
+    }
+
+    #[test]
+    fn test_read_idle_states_fake_sysfs() {
+        let sys_root = tempfile::tempdir().unwrap();
+        assert!(read_idle_states(sys_root.path(), 2).is_empty());
+
+        write_idle_states(
+            sys_root.path(),
+            2,
+            &[("POLL", 10, 0), ("C1", 400, 1), ("C6", 90, 133)],
+        );
+        let states = read_idle_states(sys_root.path(), 2);
+        let names: Vec<&str> = states.iter().map(|s| s.name.as_str()).collect();
+        assert_eq!(names, vec!["POLL", "C1", "C6"]);
+        assert_eq!(states[2].usage, 90);
+        assert_eq!(states[2].exit_latency_us, 133);
+    }
+
+    #[test]
+    fn test_spike_attributed_against_quiet_baseline() {
+        let mut forensics = SpikeForensics::new(2);
+        let idle = [
+            vec![IdleStateCounters {
+                name: "C6".into(),
+                usage: 100,
+                exit_latency_us: 133,
+            }],
+            vec![IdleStateCounters {
+                name: "C6".into(),
+                usage: 102,
+                exit_latency_us: 133,
+            }],
+            vec![IdleStateCounters {
+                name: "C6".into(),
+                usage: 112,
+                exit_latency_us: 133,
+            }],
+        ];
+        let runqueue: Vec<TaskStat> = TASK_STAT
+            .lines()
+            .filter_map(parse_task_stat)
+            .filter(|task| task.comm == "Web Content")
+            .collect();
+
+        let mut results = Vec::new();
+        for t in 0..3 {
+            let at = Duration::from_millis(100 * t as u64);
+            let snapshot = forensics.build_snapshot(INTERRUPTS[t], SOFTIRQS[t], &idle[t], at);
+            let spikes = if t == 2 { 2 } else { 0 };
+            results.push(forensics.observe(snapshot, spikes, &runqueue));
+        }
+        // First snapshot primes, second is the quiet baseline
+        assert!(results[0].is_none());
+        assert!(results[1].is_none());
+
+        let attribution = results[2].clone().unwrap();
+        assert_eq!(
+            attribution.cause,
+            Some((OffenderKind::Irq, "142".to_string()))
+        );
+        let excess: BTreeMap<_, _> = attribution
+            .candidates
+            .iter()
+            .map(|(kind, id, excess)| ((*kind, id.as_str()), *excess))
+            .collect();
+        assert_eq!(excess[&(OffenderKind::Irq, "142")], 186.0);
+        assert_eq!(excess[&(OffenderKind::Softirq, "NET_RX")], 167.0);
+        assert_eq!(excess[&(OffenderKind::IdleState, "C6")], 8.0);
+        // Local timer ticks at its usual rate
+        assert!(!excess.contains_key(&(OffenderKind::Irq, "LOC")));
+
+        let report = forensics.report();
+        assert_eq!(report.spikes_analyzed, 2);
+        assert_eq!(report.unattributed_spikes, 0);
+        let top = report.top_offenders(TOP_OFFENDERS);
+        assert_eq!(top.len(), TOP_OFFENDERS);
+        assert_eq!(top[0].name, "142");
+        assert_eq!(top[0].detail, "PCI-MSI 333824-edge iwlwifi");
+        assert_eq!(top[0].attributed_spikes, 2);
+        let web = report
+            .offenders
+            .iter()
+            .find(|o| o.kind == OffenderKind::Task)
+            .unwrap();
+        assert_eq!(web.detail, "pid 2210");
+        assert_eq!(web.attributed_spikes, 0);
+        let c6 = report
+            .offenders
+            .iter()
+            .find(|o| o.kind == OffenderKind::IdleState)
+            .unwrap();
+        assert_eq!(c6.detail, "exit latency 133µs");
+    }
+
+    #[test]
+    fn test_runqueue_blamed_without_interrupt_excess() {
+        let mut forensics = SpikeForensics::new(2);
+        let runqueue: Vec<TaskStat> = TASK_STAT.lines().filter_map(parse_task_stat).collect();
+        for t in 0..3 {
+            let at = Duration::from_millis(100 * t as u64);
+            // Same counters every time: no source above baseline
+            let snapshot = forensics.build_snapshot(INTERRUPTS[0], SOFTIRQS[0], &[], at);
+            let spikes = if t == 2 { 1 } else { 0 };
+            if let Some(attribution) = forensics.observe(snapshot, spikes, &runqueue[2..3]) {
+                assert!(attribution.candidates.is_empty());
+                assert_eq!(
+                    attribution.cause,
+                    Some((OffenderKind::Task, "Web Content".to_string()))
+                );
+            }
+        }
+        assert_eq!(forensics.report().offenders[0].attributed_spikes, 1);
+
+        // Nothing stands out and nothing is runnable
+        let snapshot =
+            forensics.build_snapshot(INTERRUPTS[0], SOFTIRQS[0], &[], Duration::from_millis(300));
+        let attribution = forensics.observe(snapshot, 3, &[]).unwrap();
+        assert_eq!(attribution.cause, None);
+        assert_eq!(forensics.report().unattributed_spikes, 3);
+        assert_eq!(forensics.report().spikes_analyzed, 4);
+    }
+}
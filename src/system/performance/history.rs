@@ -592,6 +592,7 @@ impl PerformanceHistory {
             active_stressors,
             histogram_buckets,
             label: None,
+            experiment: None,
         })
     }
 }
@@ -664,6 +665,30 @@ impl HistoryManager {
         &self,
         summary: SessionSummary,
         histogram_buckets: Vec<HistogramBucket>,
+    ) -> Result<String, Box<dyn std::error::Error>> {
+        self.write_record(summary, histogram_buckets, None)
+    }
+
+    /// Save one repetition of an A/B experiment
+    ///
+    /// Same as [`save_record`](Self::save_record) but tags the record with the
+    /// experiment name so [`load_experiment_group`](Self::load_experiment_group)
+    /// can find all runs of the same kernel.
+    pub fn save_experiment_run(
+        &self,
+        summary: SessionSummary,
+        histogram_buckets: Vec<HistogramBucket>,
+        experiment: &str,
+    ) -> Result<String, Box<dyn std::error::Error>> {
+        eprintln!("[HISTORY_MANAGER] Experiment run for: {}", experiment);
+        self.write_record(summary, histogram_buckets, Some(experiment.to_string()))
+    }
+
+    fn write_record(
+        &self,
+        summary: SessionSummary,
+        histogram_buckets: Vec<HistogramBucket>,
+        experiment: Option<String>,
     ) -> Result<String, Box<dyn std::error::Error>> {
         // Build the complete performance record, preserving the label
         let record = PerformanceRecord {
@@ -673,6 +698,7 @@ impl HistoryManager {
             active_stressors: summary.active_stressors.clone(),
             histogram_buckets,
             label: summary.label.clone(),
+            experiment,
         };
 
         // Generate unique filename
@@ -833,6 +859,39 @@ impl HistoryManager {
         Ok(record)
     }
 
+    /// Load every run that belongs to the same experiment group as a record
+    ///
+    /// A group is all records with the same experiment name and kernel context.
+    /// A record outside any experiment is returned on its own.
+    pub fn load_experiment_group(
+        &self,
+        id: &str,
+    ) -> Result<Vec<PerformanceRecord>, Box<dyn std::error::Error>> {
+        let anchor = self.load_record(id)?;
+        if anchor.experiment.is_none() {
+            return Ok(vec![anchor]);
+        }
+
+        let mut records = Vec::new();
+        for record_id in self.list_records()? {
+            match self.load_record(&record_id) {
+                Ok(record) => records.push(record),
+                Err(e) => eprintln!(
+                    "[HISTORY_MANAGER] Warning: Skipping {} in experiment group: {}",
+                    record_id, e
+                ),
+            }
+        }
+
+        let group = experiment_group(records, &anchor);
+        eprintln!(
+            "[HISTORY_MANAGER] Experiment group for {}: {} runs",
+            id,
+            group.len()
+        );
+        Ok(group)
+    }
+
     /// Delete a performance record by ID
     pub fn delete_record(&self, id: &str) -> Result<(), Box<dyn std::error::Error>> {
         let filepath = self.records_dir.join(id);
@@ -854,6 +913,25 @@ impl HistoryManager {
     }
 }
 
+/// Records of the same experiment and kernel as `anchor` (always including it)
+pub fn experiment_group(
+    records: Vec<PerformanceRecord>,
+    anchor: &PerformanceRecord,
+) -> Vec<PerformanceRecord> {
+    if anchor.experiment.is_none() {
+        return vec![anchor.clone()];
+    }
+    let group: Vec<PerformanceRecord> = records
+        .into_iter()
+        .filter(|r| r.experiment == anchor.experiment && r.kernel_context == anchor.kernel_context)
+        .collect();
+    if group.is_empty() {
+        vec![anchor.clone()]
+    } else {
+        group
+    }
+}
+
 impl Default for HistoryManager {
     fn default() -> Self {
         Self::new().expect("Failed to initialize HistoryManager")
@@ -1199,6 +1277,33 @@ mod tests {
         assert_eq!(record.unwrap().active_stressors, stressors);
     }
 
+    #[test]
+    fn test_experiment_group_matches_name_and_kernel() {
+        let mut history = PerformanceHistory::new(5);
+        history.add_snapshot(create_test_snapshot());
+        let base = history.export_record(vec![], vec![]).unwrap();
+
+        let tagged = |experiment: Option<&str>, version: &str| {
+            let mut record = base.clone();
+            record.experiment = experiment.map(str::to_string);
+            record.kernel_context.version = version.to_string();
+            record
+        };
+        let records = vec![
+            tagged(Some("lto"), "6.7.0"),
+            tagged(Some("lto"), "6.7.0"),
+            tagged(Some("lto"), "6.8.0"),
+            tagged(Some("other"), "6.7.0"),
+            tagged(None, "6.7.0"),
+        ];
+
+        let group = experiment_group(records.clone(), &records[0]);
+        assert_eq!(group.len(), 2);
+        assert_eq!(experiment_group(records.clone(), &records[2]).len(), 1);
+        // Untagged records are compared on their own
+        assert_eq!(experiment_group(records.clone(), &records[4]).len(), 1);
+    }
+
     #[test]
     fn test_benchmark_run_creation() {
         use crate::system::performance::scoring::PersonalityType;
@@ -1247,6 +1352,8 @@ mod tests {
                 brief: "Balanced personality profile (⚖️) delivers very good performance overall. Strongest in Latency.".to_string(),
                 is_balanced_override: false,
                 specialization_index: 5.0,
+                perf_per_watt: None,
+                power_efficiency_score: None,
             },
             vec!["stress-ng".to_string()],
             Some(60.0),
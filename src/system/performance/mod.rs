@@ -7,16 +7,26 @@
 //! - **Tuner**: Prepares system environment (mlockall, SCHED_FIFO, CPU affinity)
 //! - **Collector**: Measures latency with nanosecond precision using lock-free rtrb ring buffer
 //! - **Diagnostic**: Detects SMI (System Management Interrupt) correlations via MSR
+//! - **Multicore**: One pinned collector per selected CPU with per-CPU and merged results
+//! - **Forensics**: Attributes latency spikes to IRQs, softirqs, idle states and run-queue tasks
+//! - **Power**: RAPL energy counters for joules and average watts per benchmark phase
 //! - **History**: Persists performance snapshots for trend analysis
+//! - **Experiment**: Repeated-run A/B comparison with significance testing
 //! - **Stressor**: Orchestrates background workers (CPU, Memory, Scheduler) for load testing
+//! - **Scenario**: Declarative TOML benchmark scenarios (phases, stressors, collectors, weights)
 
 pub mod collector;
 pub mod context_switch;
 pub mod diagnostic;
 pub mod diagnostic_buffer;
+pub mod experiment;
+pub mod forensics;
 pub mod freezer;
 pub mod history;
 pub mod jitter;
+pub mod multicore;
+pub mod power;
+pub mod scenario;
 pub mod scoring;
 pub mod stressor;
 pub mod syscall;
@@ -25,6 +35,7 @@ pub mod thermal;
 pub mod tuner;
 pub mod watchdog;
 
+use crate::models::CpuSelection;
 use serde::{Deserialize, Serialize};
 use std::fmt;
 use std::sync::atomic::{AtomicBool, AtomicU64, Ordering};
@@ -248,12 +259,21 @@ pub use diagnostic::{SmiCorrelation, SmiDetector};
 pub use diagnostic_buffer::{
     get_global_buffer, init_global_buffer, DiagnosticBuffer, DiagnosticMessage,
 };
+pub use experiment::{
+    compare_runs, ComparedMetric, ExperimentComparison, MetricComparison, Verdict,
+};
+pub use forensics::{ForensicsReport, OffenderKind, SpikeForensics, SpikeOffender};
 pub use freezer::{BenchmarkFreezer, FreezerConfig};
 pub use history::{
     BenchmarkRun, BenchmarkRunManager, HistoryManager, PerformanceHistory, PerformanceSnapshot,
 };
 pub use jitter::{MicroJitterCollector, MicroJitterConfig, MicroJitterMetrics};
-pub use scoring::{PerformanceScorer, PersonalityType, ReferenceBenchmarks, ScoringResult};
+pub use multicore::{CpuLatency, MultiCoreMetrics, MultiCoreSession};
+pub use power::{PhaseEnergy, PhaseEnergyTracker, PowerMeter, PowerMetrics};
+pub use scenario::{BenchmarkScenario, PhaseStressor, ScenarioCollectors, ScenarioPhase};
+pub use scoring::{
+    PerformanceScorer, PersonalityType, ReferenceBenchmarks, ScoringResult, ScoringWeights,
+};
 pub use stressor::{Intensity, StressorManager, StressorType};
 pub use syscall::{SyscallSaturationCollector, SyscallSaturationConfig, SyscallSaturationMetrics};
 pub use task_wakeup::{TaskWakeupCollector, TaskWakeupConfig, TaskWakeupMetrics};
@@ -270,6 +290,8 @@ pub struct PerformanceConfig {
     pub core_id: usize,
     /// Latency threshold for spike detection (microseconds)
     pub spike_threshold_us: u64,
+    /// CPUs to measure; anything but a single CPU runs one pinned collector per CPU
+    pub cpus: CpuSelection,
 }
 
 impl Default for PerformanceConfig {
@@ -278,6 +300,7 @@ impl Default for PerformanceConfig {
             interval_us: 1000,       // 1 ms default
             core_id: 0,              // CPU 0 default
             spike_threshold_us: 100, // 100 µs spike threshold
+            cpus: CpuSelection::Single,
         }
     }
 }
@@ -464,7 +487,7 @@ impl BenchmarkMetrics {
 }
 
 /// Kernel context information for performance records
-#[derive(Clone, Debug, Serialize, Deserialize)]
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
 #[serde(rename_all = "snake_case")]
 pub struct KernelContext {
     /// Kernel version string
@@ -497,6 +520,9 @@ pub struct PerformanceRecord {
     /// If None, display will use timestamp as fallback
     #[serde(default)]
     pub label: Option<String>,
+    /// Name of the A/B experiment this run belongs to (None for single runs)
+    #[serde(default)]
+    pub experiment: Option<String>,
 }
 
 /// Histogram bucket for latency distribution
@@ -528,6 +554,12 @@ pub struct MonitoringState {
     pub final_sample_count: Arc<AtomicU64>,
     /// Total dropped samples at session end (set when monitoring stops)
     pub final_dropped_count: Arc<AtomicU64>,
+    /// Spike root-cause tally (published by the forensics thread)
+    pub spike_forensics: Arc<std::sync::RwLock<ForensicsReport>>,
+    /// Per-CPU results of a multi-core session (published by its processing thread)
+    pub per_cpu: Arc<std::sync::RwLock<Option<MultiCoreMetrics>>>,
+    /// Energy per phase of a system benchmark (published when the benchmark ends)
+    pub power: Arc<std::sync::RwLock<Option<PowerMetrics>>>,
 }
 
 impl Default for MonitoringState {
@@ -540,6 +572,9 @@ impl Default for MonitoringState {
             total_smi_count: Arc::new(AtomicU64::new(0)),
             final_sample_count: Arc::new(AtomicU64::new(0)),
             final_dropped_count: Arc::new(AtomicU64::new(0)),
+            spike_forensics: Arc::new(std::sync::RwLock::new(ForensicsReport::default())),
+            per_cpu: Arc::new(std::sync::RwLock::new(None)),
+            power: Arc::new(std::sync::RwLock::new(None)),
         }
     }
 }
@@ -574,83 +609,18 @@ impl MonitoringState {
     pub fn total_smi_count(&self) -> u64 {
         self.total_smi_count.load(Ordering::Relaxed)
     }
-}
-
-// BenchmarkPhase is defined inline above
-
-/// Benchmark phase: 6 phases of the GOATd Full Benchmark
-/// NOTE: Timing starts at 10s (after 10s calibration period)
-#[derive(Clone, Copy, Debug, PartialEq, Eq)]
-pub enum BenchmarkPhase {
-    /// Phase 1: Baseline (no stressors, 10-20s)
-    Baseline,
-    /// Phase 2: Computational Heat (CPU 100%, 20-30s)
-    ComputationalHeat,
-    /// Phase 3: Memory Saturation (Memory 100%, 30-40s)
-    MemorySaturation,
-    /// Phase 4: Scheduler Flood (Scheduler 100%, 40-50s)
-    SchedulerFlood,
-    /// Phase 5: Gaming Simulator (CPU 50% + Scheduler 50%, 50-60s)
-    GamingSimulator,
-    /// Phase 6: The Gauntlet (CPU 100% + Memory 100% + Scheduler 100%, 60-70s)
-    TheGauntlet,
-}
-
-impl fmt::Display for BenchmarkPhase {
-    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
-        match self {
-            BenchmarkPhase::Baseline => write!(f, "Baseline"),
-            BenchmarkPhase::ComputationalHeat => write!(f, "Computational Heat"),
-            BenchmarkPhase::MemorySaturation => write!(f, "Memory Saturation"),
-            BenchmarkPhase::SchedulerFlood => write!(f, "Scheduler Flood"),
-            BenchmarkPhase::GamingSimulator => write!(f, "Gaming Simulator"),
-            BenchmarkPhase::TheGauntlet => write!(f, "The Gauntlet"),
-        }
-    }
-}
-
-impl BenchmarkPhase {
-    /// Get the start time in seconds for this phase
-    /// NOTE: All phases are offset by 10 seconds to account for the CALIBRATION period
-    /// - 0-10s: CALIBRATION (hardware noise floor establishment)
-    /// - 10-20s: Phase 1 (Baseline)
-    /// - 20-30s: Phase 2 (CPU Heat)
-    /// - 30-40s: Phase 3 (Memory Saturation)
-    /// - 40-50s: Phase 4 (Scheduler Flood)
-    /// - 50-60s: Phase 5 (Gaming Simulator)
-    /// - 60-70s: Phase 6 (The Gauntlet)
-    pub fn start_time(&self) -> u64 {
-        const CALIBRATION_OFFSET_SECS: u64 = 10;
-        match self {
-            BenchmarkPhase::Baseline => 0 + CALIBRATION_OFFSET_SECS,
-            BenchmarkPhase::ComputationalHeat => 10 + CALIBRATION_OFFSET_SECS,
-            BenchmarkPhase::MemorySaturation => 20 + CALIBRATION_OFFSET_SECS,
-            BenchmarkPhase::SchedulerFlood => 30 + CALIBRATION_OFFSET_SECS,
-            BenchmarkPhase::GamingSimulator => 40 + CALIBRATION_OFFSET_SECS,
-            BenchmarkPhase::TheGauntlet => 50 + CALIBRATION_OFFSET_SECS,
-        }
-    }
-
-    /// Get the duration of this phase in seconds
-    pub fn duration_secs(&self) -> u64 {
-        10 // All phases are 10 seconds
-    }
 
-    /// Get the end time in seconds for this phase
-    pub fn end_time(&self) -> u64 {
-        self.start_time() + self.duration_secs()
+    /// Get the highest ranked spike offenders found so far
+    pub fn top_spike_offenders(&self) -> Vec<SpikeOffender> {
+        self.spike_forensics
+            .read()
+            .map(|report| report.top_offenders(forensics::TOP_OFFENDERS))
+            .unwrap_or_default()
     }
 
-    /// Get the next phase, or None if this is the last phase
-    pub fn next_phase(&self) -> Option<BenchmarkPhase> {
-        match self {
-            BenchmarkPhase::Baseline => Some(BenchmarkPhase::ComputationalHeat),
-            BenchmarkPhase::ComputationalHeat => Some(BenchmarkPhase::MemorySaturation),
-            BenchmarkPhase::MemorySaturation => Some(BenchmarkPhase::SchedulerFlood),
-            BenchmarkPhase::SchedulerFlood => Some(BenchmarkPhase::GamingSimulator),
-            BenchmarkPhase::GamingSimulator => Some(BenchmarkPhase::TheGauntlet),
-            BenchmarkPhase::TheGauntlet => None,
-        }
+    /// Get the latest per-CPU results (multi-core sessions only)
+    pub fn per_cpu_metrics(&self) -> Option<MultiCoreMetrics> {
+        self.per_cpu.read().ok().and_then(|per_cpu| per_cpu.clone())
     }
 }
 
@@ -659,19 +629,22 @@ impl BenchmarkPhase {
 pub enum MonitoringMode {
     /// Benchmark mode: runs for a specified duration then auto-stops
     Benchmark(Duration),
-    /// System benchmark: GOATd Full Benchmark with 6 phases
-    SystemBenchmark,
+    /// System benchmark: runs the phases of a benchmark scenario (GOATd Full Benchmark by default)
+    SystemBenchmark(BenchmarkScenario),
     /// Continuous mode: runs until manually stopped with periodic diagnostics
     Continuous,
 }
 
 impl MonitoringMode {
     /// Get the duration if this is a benchmark mode
-    /// NOTE: SystemBenchmark now takes 70 seconds (10s calibration + 60s benchmarking)
+    /// NOTE: SystemBenchmark takes the scenario's calibration plus all phases
+    /// (70 seconds for the GOATd Full Benchmark)
     pub fn duration(&self) -> Option<Duration> {
         match self {
             MonitoringMode::Benchmark(d) => Some(*d),
-            MonitoringMode::SystemBenchmark => Some(Duration::from_secs(70)),
+            MonitoringMode::SystemBenchmark(scenario) => {
+                Some(Duration::from_secs(scenario.total_duration_secs()))
+            }
             MonitoringMode::Continuous => None,
         }
     }
@@ -683,7 +656,7 @@ impl MonitoringMode {
 
     /// Check if this is system benchmark mode
     pub fn is_system_benchmark(&self) -> bool {
-        matches!(self, MonitoringMode::SystemBenchmark)
+        matches!(self, MonitoringMode::SystemBenchmark(_))
     }
 }
 
@@ -734,6 +707,15 @@ pub struct SessionSummary {
     /// Custom label/name for this benchmark result
     #[serde(default)]
     pub label: Option<String>,
+    /// Most likely spike sources, highest ranked first
+    #[serde(default)]
+    pub top_offenders: Vec<SpikeOffender>,
+    /// Per-CPU, per-core-type and merged latency of a multi-core session
+    #[serde(default)]
+    pub per_cpu: Option<MultiCoreMetrics>,
+    /// Energy per phase of a system benchmark (RAPL)
+    #[serde(default)]
+    pub power: Option<PowerMetrics>,
 }
 
 impl SessionSummary {
@@ -758,6 +740,9 @@ impl SessionSummary {
             total_samples,
             total_dropped_samples,
             label: None,
+            top_offenders: Vec::new(),
+            per_cpu: None,
+            power: None,
         }
     }
 
@@ -783,6 +768,9 @@ impl SessionSummary {
             total_samples,
             total_dropped_samples,
             label,
+            top_offenders: Vec::new(),
+            per_cpu: None,
+            power: None,
         }
     }
 
@@ -793,11 +781,19 @@ impl SessionSummary {
         let elapsed = start_instant.elapsed();
         self.duration_secs = Some(elapsed.as_secs_f64());
     }
+
+    /// Attach spike offenders, per-CPU results and energy from the session's monitoring state
+    pub fn attach_monitoring_state(&mut self, state: &MonitoringState) {
+        self.top_offenders = state.top_spike_offenders();
+        self.per_cpu = state.per_cpu_metrics();
+        self.power = state.power.read().ok().and_then(|power| power.clone());
+    }
 }
 
-/// Benchmark Orchestrator: Manages the 70-second GOATd Full Benchmark sequence
+/// Benchmark Orchestrator: Runs the phases of a benchmark scenario
 ///
-/// Coordinates calibration followed by 6 consecutive 10-second phases with phase-specific stressor configurations:
+/// Coordinates a calibration period followed by the scenario's phases, each with
+/// its own stressor configuration. The default GOATd Full Benchmark is 70 seconds:
 /// - Calibration (0-10s): Established hardware noise floor, no active stressors
 /// - Phase 1 (10-20s): Baseline (no stressors)
 /// - Phase 2 (20-30s): CPU 100%
@@ -807,27 +803,38 @@ impl SessionSummary {
 /// - Phase 6 (60-70s): CPU 100% + Memory 100% + Scheduler 100%
 ///
 /// The orchestrator handles stressor transitions and collects phase-specific metrics.
-/// Phase times are internally offset by 10 seconds to account for the calibration period.
+/// Phase times are offset by the scenario's calibration period.
 #[derive(Clone, Debug)]
 pub struct BenchmarkOrchestrator {
-    /// Current phase
-    pub current_phase: BenchmarkPhase,
+    /// Scenario being run
+    pub scenario: BenchmarkScenario,
+    /// Index of the current phase in `scenario.phases`
+    pub current_phase: usize,
     /// Session start time
     pub session_start: Instant,
     /// Phase metrics collection: phase name -> metrics snapshot
     pub phase_metrics: Vec<(String, PerformanceMetrics)>,
     /// Aggregated benchmark metrics from specialized collectors (Phase 2.1)
     pub collector_metrics: BenchmarkMetrics,
+    /// Energy per phase (None without readable RAPL counters)
+    pub energy: Option<PhaseEnergyTracker>,
 }
 
 impl BenchmarkOrchestrator {
-    /// Create a new benchmark orchestrator
+    /// Create a new benchmark orchestrator for the GOATd Full Benchmark
     pub fn new() -> Self {
+        Self::with_scenario(BenchmarkScenario::goatd_full())
+    }
+
+    /// Create a new benchmark orchestrator for a scenario
+    pub fn with_scenario(scenario: BenchmarkScenario) -> Self {
         BenchmarkOrchestrator {
-            current_phase: BenchmarkPhase::Baseline,
+            phase_metrics: Vec::with_capacity(scenario.phases.len()),
+            scenario,
+            current_phase: 0,
             session_start: Instant::now(),
-            phase_metrics: Vec::with_capacity(6),
             collector_metrics: BenchmarkMetrics::new(),
+            energy: PowerMeter::discover().map(PhaseEnergyTracker::new),
         }
     }
 
@@ -836,74 +843,101 @@ impl BenchmarkOrchestrator {
         self.session_start.elapsed().as_secs()
     }
 
-    /// Check if the benchmark is complete (70 seconds elapsed: 10s calibration + 60s benchmarking)
+    /// Check if the benchmark is complete (calibration and all phases elapsed)
     pub fn is_complete(&self) -> bool {
-        self.elapsed_secs() >= 70
+        self.elapsed_secs() >= self.scenario.total_duration_secs()
     }
 
-    /// Transition to the next phase and return the new phase or None if complete
-    pub fn advance_phase(&mut self) -> Option<BenchmarkPhase> {
-        if let Some(next) = self.current_phase.next_phase() {
-            self.current_phase = next;
-            Some(next)
+    /// Number of phases in the scenario
+    pub fn phase_count(&self) -> usize {
+        self.scenario.phases.len()
+    }
+
+    /// Name of the current phase
+    pub fn current_phase_name(&self) -> &str {
+        self.scenario
+            .phases
+            .get(self.current_phase)
+            .map_or("", |p| p.name.as_str())
+    }
+
+    /// Spectrum strip highlighted by the current phase
+    pub fn current_phase_focus(&self) -> Option<&str> {
+        self.scenario
+            .phases
+            .get(self.current_phase)
+            .and_then(|p| p.focus.as_deref())
+    }
+
+    /// Start time of the current phase in seconds since the session start
+    pub fn current_phase_start(&self) -> u64 {
+        self.scenario.phase_start_secs(self.current_phase)
+    }
+
+    /// End time of the current phase in seconds since the session start
+    pub fn current_phase_end(&self) -> u64 {
+        self.scenario.phase_end_secs(self.current_phase)
+    }
+
+    /// Transition to the next phase and return its index or None if complete
+    pub fn advance_phase(&mut self) -> Option<usize> {
+        if self.current_phase + 1 < self.phase_count() {
+            self.current_phase += 1;
+            Some(self.current_phase)
         } else {
             None
         }
     }
 
+    /// Attribute energy to the running phase (calibration counts as its own phase)
+    ///
+    /// Called on every phase tick so phase boundaries are accurate to the tick interval.
+    pub fn update_energy(&mut self) {
+        let calibrating =
+            self.session_start.elapsed() < Duration::from_secs(self.scenario.calibration_secs);
+        let phase = if calibrating {
+            "Calibration".to_string()
+        } else {
+            self.current_phase_name().to_string()
+        };
+        if let Some(energy) = self.energy.as_mut() {
+            energy.enter_phase(&phase);
+        }
+    }
+
+    /// Close the energy measurement of the last phase
+    pub fn finish_energy(&mut self) {
+        if let Some(energy) = self.energy.as_mut() {
+            energy.finish();
+        }
+    }
+
+    /// Energy per phase measured so far
+    pub fn power_metrics(&self) -> Option<PowerMetrics> {
+        self.energy.as_ref().and_then(|energy| energy.metrics())
+    }
+
     /// Get stressors for the current phase
     pub fn get_phase_stressors(&self) -> Vec<(StressorType, Intensity)> {
-        match self.current_phase {
-            BenchmarkPhase::Baseline => {
-                // No stressors
-                vec![]
-            }
-            BenchmarkPhase::ComputationalHeat => {
-                // CPU 100%
-                vec![(StressorType::Cpu, Intensity::new(100))]
-            }
-            BenchmarkPhase::MemorySaturation => {
-                // Memory 100%
-                vec![(StressorType::Memory, Intensity::new(100))]
-            }
-            BenchmarkPhase::SchedulerFlood => {
-                // Scheduler 100%
-                vec![(StressorType::Scheduler, Intensity::new(100))]
-            }
-            BenchmarkPhase::GamingSimulator => {
-                // CPU 50% + Scheduler 50%
-                vec![
-                    (StressorType::Cpu, Intensity::new(50)),
-                    (StressorType::Scheduler, Intensity::new(50)),
-                ]
-            }
-            BenchmarkPhase::TheGauntlet => {
-                // CPU 100% + Memory 100% + Scheduler 100%
-                vec![
-                    (StressorType::Cpu, Intensity::new(100)),
-                    (StressorType::Memory, Intensity::new(100)),
-                    (StressorType::Scheduler, Intensity::new(100)),
-                ]
-            }
-        }
+        self.scenario.phase_stressors(self.current_phase)
     }
 
     /// Record metrics for the current phase
     pub fn record_phase_metrics(&mut self, metrics: PerformanceMetrics) {
         self.phase_metrics
-            .push((self.current_phase.to_string(), metrics));
+            .push((self.current_phase_name().to_string(), metrics));
     }
 
     /// Calculate the final GOAT Score from aggregated phase metrics
     ///
-    /// Aggregates all 6 phase metrics by mathematically averaging them:
+    /// Aggregates all phase metrics by mathematically averaging them:
     /// - Averages max, p99, p99.9, avg latencies across all phases
     /// - Averages spike counts and SMI correlations
     /// - Uses rolling metrics when available (p99, p99.9, consistency) for accuracy
     /// - Preserves benchmark_metrics from last phase for detailed scoring
     ///
     /// This ensures a cumulative/standardized score that represents the kernel's
-    /// overall performance across all 6 stress phases, not just a snapshot.
+    /// overall performance across all stress phases, not just a snapshot.
     pub fn calculate_final_score(&self) -> Option<u16> {
         if self.phase_metrics.is_empty() {
             return None;
@@ -952,8 +986,11 @@ impl BenchmarkOrchestrator {
         }
 
         // Score the aggregated metrics with PerformanceScorer
-        // This applies the standard 7-metric weighting (27% Latency, 18% Consistency, etc.)
-        let scorer = PerformanceScorer::new();
+        // This applies the scenario's 7-metric weighting (default 27% Latency, 18% Consistency, etc.)
+        // Energy adds the perf-per-watt dimension when RAPL counters are readable
+        let scorer = PerformanceScorer::new()
+            .with_weights(self.scenario.weights.clone())
+            .with_power(self.power_metrics());
         let result = scorer.score_metrics(&aggregated);
 
         eprintln!("[BENCHMARK_ORCHESTRATOR] Final GOAT Score from cumulative metrics: {} ({} phases averaged)",
@@ -1027,4 +1064,22 @@ mod tests {
 
         assert!(metrics.is_complete());
     }
+
+    #[test]
+    fn test_orchestrator_walks_scenario_phases() {
+        let mut orchestrator = BenchmarkOrchestrator::new();
+        assert_eq!(orchestrator.phase_count(), 6);
+        assert_eq!(orchestrator.current_phase_name(), "Baseline");
+        assert_eq!(orchestrator.current_phase_end(), 20);
+        assert!(orchestrator.get_phase_stressors().is_empty());
+
+        while orchestrator.advance_phase().is_some() {}
+        assert_eq!(orchestrator.current_phase_name(), "The Gauntlet");
+        assert_eq!(orchestrator.current_phase_focus(), Some("Consistency"));
+        assert_eq!(orchestrator.get_phase_stressors().len(), 3);
+        assert_eq!(
+            MonitoringMode::SystemBenchmark(orchestrator.scenario.clone()).duration(),
+            Some(Duration::from_secs(70))
+        );
+    }
 }
@@ -0,0 +1,352 @@
+//! Multi-Core Latency Collection
+//!
+//! `PerformanceConfig::core_id` measures a single CPU, but on hybrid parts the
+//! P-cores and E-cores behave very differently. A multi-core session runs one
+//! pinned `LatencyCollector` per selected CPU, each with its own ring buffer,
+//! and a processing thread that drains them into per-CPU `LatencyProcessor`s.
+//!
+//! The primary CPU keeps feeding the regular single-core pipeline: its samples
+//! are forwarded to the dashboard's ring buffer and its collector uses the
+//! shared `MonitoringState` counters, so scoring and spike forensics are
+//! unchanged. Per-CPU, per-core-type and merged metrics are published into
+//! `MonitoringState::per_cpu` for the latency heatmap and the session summary.
+
+use super::collector::{CollectorEvent, LatencyCollector, LatencyProcessor};
+use super::{MonitoringState, PerformanceMetrics};
+use crate::hardware::cpu::CpuTopology;
+use crate::models::CoreType;
+use serde::{Deserialize, Serialize};
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::{Arc, RwLock};
+use std::thread::JoinHandle;
+use std::time::{Duration, Instant};
+
+/// How often the processing thread drains the per-CPU rings
+const DRAIN_INTERVAL: Duration = Duration::from_millis(10);
+
+/// How often per-CPU metrics are published
+const PUBLISH_INTERVAL: Duration = Duration::from_millis(250);
+
+/// Latency results of one CPU
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct CpuLatency {
+    pub cpu: usize,
+    pub core_type: CoreType,
+    pub metrics: PerformanceMetrics,
+}
+
+/// Per-CPU results of a multi-core session with merged views
+#[derive(Clone, Debug, Default, Serialize, Deserialize)]
+pub struct MultiCoreMetrics {
+    /// One entry per measured CPU, ascending
+    pub per_cpu: Vec<CpuLatency>,
+    /// All samples of all CPUs combined
+    pub merged: PerformanceMetrics,
+    /// Samples combined per core type (P-cores, E-cores)
+    #[serde(default)]
+    pub by_core_type: Vec<(CoreType, PerformanceMetrics)>,
+}
+
+impl MultiCoreMetrics {
+    /// Aggregate per-CPU processors into per-CPU, per-core-type and merged metrics
+    ///
+    /// `cpus` holds (cpu, core type, processor, spike count).
+    pub fn from_processors(cpus: &[(usize, CoreType, &LatencyProcessor, u64)]) -> Self {
+        let per_cpu = cpus
+            .iter()
+            .map(|&(cpu, core_type, processor, spikes)| CpuLatency {
+                cpu,
+                core_type,
+                metrics: processor_metrics(processor, spikes),
+            })
+            .collect();
+
+        let merge = |filter: &dyn Fn(CoreType) -> bool| {
+            let mut merged = LatencyProcessor::default();
+            let mut spikes = 0;
+            for &(_, core_type, processor, cpu_spikes) in cpus {
+                if filter(core_type) {
+                    if let Err(e) = merged.merge(processor) {
+                        eprintln!("[MULTICORE] Warning: Failed to merge histograms: {}", e);
+                    }
+                    spikes += cpu_spikes;
+                }
+            }
+            processor_metrics(&merged, spikes)
+        };
+
+        let mut core_types: Vec<CoreType> = cpus.iter().map(|&(_, ct, _, _)| ct).collect();
+        core_types.sort_unstable();
+        core_types.dedup();
+
+        MultiCoreMetrics {
+            per_cpu,
+            merged: merge(&|_| true),
+            by_core_type: core_types
+                .into_iter()
+                .map(|core_type| (core_type, merge(&|ct| ct == core_type)))
+                .collect(),
+        }
+    }
+
+    /// The CPU with the highest P99 latency
+    pub fn worst_cpu(&self) -> Option<&CpuLatency> {
+        self.per_cpu
+            .iter()
+            .max_by(|a, b| a.metrics.p99_us.total_cmp(&b.metrics.p99_us))
+    }
+}
+
+/// Latency fields of `PerformanceMetrics` from a processor
+fn processor_metrics(processor: &LatencyProcessor, spikes: u64) -> PerformanceMetrics {
+    PerformanceMetrics {
+        current_us: processor.last_sample(),
+        max_us: processor.max(),
+        p99_us: processor.p99(),
+        p99_9_us: processor.p99_9(),
+        avg_us: processor.average(),
+        total_spikes: spikes,
+        histogram_buckets: processor.get_histogram_buckets(),
+        ..PerformanceMetrics::default()
+    }
+}
+
+/// Wiring of the session's primary CPU into the single-core pipeline
+pub struct PrimaryCollector {
+    pub cpu: usize,
+    /// Ring consumed by the dashboard's background processor
+    pub forward: rtrb::Producer<u64>,
+    /// Event ring consumed by the diagnostic event consumer
+    pub event_producer: rtrb::Producer<CollectorEvent>,
+    /// Shared counters and stop flag of the session
+    pub state: MonitoringState,
+    /// Receives the real-time scheduling status of the primary thread
+    pub metrics: Arc<RwLock<PerformanceMetrics>>,
+}
+
+/// A running multi-core session
+pub struct MultiCoreSession {
+    handles: Vec<JoinHandle<()>>,
+}
+
+/// Per-CPU state owned by the processing thread
+struct CpuChannel {
+    cpu: usize,
+    core_type: CoreType,
+    samples: rtrb::Consumer<u64>,
+    events: Option<rtrb::Consumer<CollectorEvent>>,
+    spike_count: Arc<AtomicU64>,
+    processor: LatencyProcessor,
+}
+
+impl MultiCoreSession {
+    /// Start one pinned collector per CPU plus the processing thread
+    ///
+    /// The session stops when `primary.state.stop_flag` is set.
+    pub fn start(
+        cpus: &[usize],
+        topology: &CpuTopology,
+        interval: Duration,
+        spike_threshold_ns: u64,
+        primary: PrimaryCollector,
+    ) -> Self {
+        let PrimaryCollector {
+            cpu: primary_cpu,
+            mut forward,
+            event_producer: primary_events,
+            state,
+            metrics,
+        } = primary;
+        let stop_flag = state.stop_flag.clone();
+        let mut primary_events = Some(primary_events);
+        let mut handles = Vec::with_capacity(cpus.len() + 1);
+        let mut channels = Vec::with_capacity(cpus.len());
+
+        for &cpu in cpus {
+            let is_primary = cpu == primary_cpu;
+            let (producer, samples) = rtrb::RingBuffer::new(65536);
+            let (event_producer, events) = match primary_events.take().filter(|_| is_primary) {
+                Some(events) => (events, None),
+                None => {
+                    let (producer, consumer) = rtrb::RingBuffer::new(256);
+                    (producer, Some(consumer))
+                }
+            };
+            let (dropped, spike_count, smi_spikes, total_smi) = if is_primary {
+                (
+                    state.dropped_samples.clone(),
+                    state.spike_count.clone(),
+                    state.smi_correlated_spikes.clone(),
+                    state.total_smi_count.clone(),
+                )
+            } else {
+                (
+                    Arc::new(AtomicU64::new(0)),
+                    Arc::new(AtomicU64::new(0)),
+                    Arc::new(AtomicU64::new(0)),
+                    Arc::new(AtomicU64::new(0)),
+                )
+            };
+            channels.push(CpuChannel {
+                cpu,
+                core_type: topology.core_type(cpu),
+                samples,
+                events,
+                spike_count: spike_count.clone(),
+                processor: LatencyProcessor::default(),
+            });
+
+            let stop = stop_flag.clone();
+            let rt_metrics = metrics.clone();
+            handles.push(std::thread::spawn(move || {
+                let mut tuner = crate::system::performance::Tuner::new();
+                let rt_result = tuner.apply_realtime_settings(cpu);
+                if let Err(ref e) = rt_result {
+                    eprintln!(
+                        "[MULTICORE] Warning: Real-time settings failed on cpu {}: {}",
+                        cpu, e
+                    );
+                }
+                if is_primary {
+                    if let Ok(mut m) = rt_metrics.write() {
+                        m.rt_active = rt_result.is_ok();
+                        m.rt_error = rt_result.err().map(|e| e.to_string());
+                    }
+                }
+
+                LatencyCollector::new(
+                    interval,
+                    producer,
+                    event_producer,
+                    stop,
+                    dropped,
+                    spike_threshold_ns,
+                    spike_count,
+                    smi_spikes,
+                    total_smi,
+                    None,
+                )
+                .run();
+            }));
+        }
+        // The primary CPU was not selected: release its event ring
+        drop(primary_events);
+
+        eprintln!(
+            "[MULTICORE] Started {} pinned collectors (primary cpu {})",
+            channels.len(),
+            primary_cpu
+        );
+
+        let per_cpu = state.per_cpu.clone();
+        handles.push(std::thread::spawn(move || {
+            let mut last_publish = Instant::now();
+            loop {
+                let stopping = stop_flag.load(Ordering::Acquire);
+                for channel in channels.iter_mut() {
+                    while let Ok(sample_ns) = channel.samples.pop() {
+                        let _ = channel.processor.record_sample(sample_ns);
+                        if channel.cpu == primary_cpu {
+                            let _ = forward.push(sample_ns);
+                        }
+                    }
+                    if let Some(events) = channel.events.as_mut() {
+                        while events.pop().is_ok() {}
+                    }
+                }
+
+                if stopping || last_publish.elapsed() >= PUBLISH_INTERVAL {
+                    let snapshot: Vec<(usize, CoreType, &LatencyProcessor, u64)> = channels
+                        .iter()
+                        .map(|c| {
+                            (
+                                c.cpu,
+                                c.core_type,
+                                &c.processor,
+                                c.spike_count.load(Ordering::Relaxed),
+                            )
+                        })
+                        .collect();
+                    if let Ok(mut shared) = per_cpu.write() {
+                        *shared = Some(MultiCoreMetrics::from_processors(&snapshot));
+                    }
+                    last_publish = Instant::now();
+                }
+
+                if stopping {
+                    break;
+                }
+                std::thread::sleep(DRAIN_INTERVAL);
+            }
+            eprintln!("[MULTICORE] Processing thread stopped");
+        }));
+
+        MultiCoreSession { handles }
+    }
+
+    /// Wait for all collector and processing threads to exit
+    pub fn join(self) {
+        for handle in self.handles {
+            let _ = handle.join();
+        }
+    }
+}
+
+/// Whether a session with these CPUs needs the multi-core path
+pub fn is_multi_core(cpus: &[usize]) -> bool {
+    cpus.len() > 1
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::CpuSelection;
+
+    #[test]
+    fn test_single_selection_is_not_multi_core() {
+        let flat = CpuTopology {
+            online: (0..4).collect(),
+            ..CpuTopology::default()
+        };
+        let single = flat.resolve_selection(&CpuSelection::Single, 1);
+        assert!(!is_multi_core(&single));
+        let all = flat.resolve_selection(&CpuSelection::All, 1);
+        assert!(is_multi_core(&all));
+    }
+
+    #[test]
+    fn test_multi_core_metrics_aggregation() {
+        let mut p_core = LatencyProcessor::default();
+        let mut e_core_a = LatencyProcessor::default();
+        let mut e_core_b = LatencyProcessor::default();
+        for _ in 0..100 {
+            p_core.record_sample(3_000).unwrap();
+            e_core_a.record_sample(12_000).unwrap();
+            e_core_b.record_sample(14_000).unwrap();
+        }
+        e_core_b.record_sample(250_000).unwrap();
+
+        let metrics = MultiCoreMetrics::from_processors(&[
+            (0, CoreType::Performance, &p_core, 0),
+            (4, CoreType::Efficiency, &e_core_a, 1),
+            (5, CoreType::Efficiency, &e_core_b, 3),
+        ]);
+
+        assert_eq!(metrics.per_cpu.len(), 3);
+        assert!((metrics.per_cpu[0].metrics.max_us - 3.0).abs() < 0.01);
+        assert_eq!(metrics.per_cpu[2].metrics.total_spikes, 3);
+        assert_eq!(metrics.worst_cpu().unwrap().cpu, 5);
+
+        assert!((metrics.merged.max_us - 250.0).abs() < 0.5);
+        assert_eq!(metrics.merged.total_spikes, 4);
+
+        assert_eq!(metrics.by_core_type.len(), 2);
+        let (p_type, p_metrics) = &metrics.by_core_type[0];
+        assert_eq!(*p_type, CoreType::Performance);
+        assert!(p_metrics.p99_us < 3.1);
+        let (e_type, e_metrics) = &metrics.by_core_type[1];
+        assert_eq!(*e_type, CoreType::Efficiency);
+        assert_eq!(e_metrics.total_spikes, 4);
+        assert!(e_metrics.p99_us >= 12.0);
+    }
+}
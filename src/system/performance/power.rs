@@ -0,0 +1,384 @@
+//! RAPL Energy Measurement
+//!
+//! Reads the cumulative energy counters the powercap framework exposes under
+//! `/sys/class/powercap` (`intel-rapl:N` zones, also used by AMD Zen CPUs, and
+//! `amd-rapl:N` where present) to attribute joules and average watts to each
+//! benchmark phase.
+//!
+//! Only top-level zones are summed: sub-zones (`intel-rapl:0:0` core, uncore,
+//! dram) are already part of their package. When package zones exist, other
+//! top-level zones such as `psys` (whole platform, includes the package) are
+//! skipped to avoid double counting.
+//!
+//! Since Linux 5.10 `energy_uj` is readable by root only; without access the
+//! meter is simply unavailable and benchmarks run without energy figures.
+
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::time::Instant;
+
+/// Default powercap sysfs root
+pub const POWERCAP_ROOT: &str = "/sys/class/powercap";
+
+/// Zone name prefixes of RAPL control types
+const RAPL_PREFIXES: [&str; 2] = ["intel-rapl:", "amd-rapl:"];
+
+/// A top-level RAPL energy zone
+#[derive(Clone, Debug, PartialEq)]
+pub struct RaplZone {
+    /// Zone name from sysfs (e.g. "package-0", "psys")
+    pub name: String,
+    /// Zone directory (e.g. /sys/class/powercap/intel-rapl:0)
+    pub path: PathBuf,
+    /// Counter range in microjoules; `energy_uj` wraps to 0 past this value
+    pub max_energy_range_uj: u64,
+}
+
+impl RaplZone {
+    /// Read the cumulative energy counter in microjoules
+    pub fn read_energy_uj(&self) -> Option<u64> {
+        read_u64(&self.path.join("energy_uj"))
+    }
+
+    /// Energy between two counter readings, accounting for one wraparound
+    fn delta_uj(&self, start: u64, end: u64) -> u64 {
+        if end >= start {
+            end - start
+        } else {
+            self.max_energy_range_uj.saturating_sub(start) + end
+        }
+    }
+}
+
+fn read_u64(path: &Path) -> Option<u64> {
+    fs::read_to_string(path).ok()?.trim().parse().ok()
+}
+
+/// Whether a powercap entry is a top-level RAPL zone ("intel-rapl:0", not "intel-rapl:0:0")
+fn is_top_level_zone(entry: &str) -> bool {
+    RAPL_PREFIXES.iter().any(|prefix| {
+        entry
+            .strip_prefix(prefix)
+            .is_some_and(|index| !index.is_empty() && index.chars().all(|c| c.is_ascii_digit()))
+    })
+}
+
+/// Cumulative energy counters of all zones at one instant
+#[derive(Clone, Debug)]
+pub struct EnergySample {
+    pub taken_at: Instant,
+    energy_uj: Vec<u64>,
+}
+
+/// Reads the package energy counters of the running system
+#[derive(Clone, Debug)]
+pub struct PowerMeter {
+    zones: Vec<RaplZone>,
+}
+
+impl PowerMeter {
+    /// Discover readable RAPL zones under `/sys/class/powercap`
+    pub fn discover() -> Option<Self> {
+        Self::discover_at(Path::new(POWERCAP_ROOT))
+    }
+
+    /// Discover readable RAPL zones under a powercap root
+    ///
+    /// Returns None when no zone has a readable energy counter.
+    pub fn discover_at(powercap_root: &Path) -> Option<Self> {
+        let mut zones: Vec<RaplZone> = fs::read_dir(powercap_root)
+            .ok()?
+            .flatten()
+            .filter(|entry| is_top_level_zone(&entry.file_name().to_string_lossy()))
+            .filter_map(|entry| {
+                let path = entry.path();
+                let name = fs::read_to_string(path.join("name"))
+                    .map(|name| name.trim().to_string())
+                    .unwrap_or_else(|_| entry.file_name().to_string_lossy().into_owned());
+                let zone = RaplZone {
+                    name,
+                    max_energy_range_uj: read_u64(&path.join("max_energy_range_uj"))
+                        .unwrap_or(u64::MAX),
+                    path,
+                };
+                zone.read_energy_uj().map(|_| zone)
+            })
+            .collect();
+
+        if zones.iter().any(|zone| zone.name.starts_with("package")) {
+            zones.retain(|zone| zone.name.starts_with("package"));
+        }
+        zones.sort_by(|a, b| a.path.cmp(&b.path));
+
+        if zones.is_empty() {
+            eprintln!(
+                "[POWER] No readable RAPL energy counters under {} (root required)",
+                powercap_root.display()
+            );
+            return None;
+        }
+        eprintln!(
+            "[POWER] Measuring energy of {} RAPL zone(s): {}",
+            zones.len(),
+            zones
+                .iter()
+                .map(|z| z.name.as_str())
+                .collect::<Vec<_>>()
+                .join(", ")
+        );
+        Some(PowerMeter { zones })
+    }
+
+    /// The measured zones
+    pub fn zones(&self) -> &[RaplZone] {
+        &self.zones
+    }
+
+    /// Read all energy counters (None if any read fails)
+    pub fn sample(&self) -> Option<EnergySample> {
+        let energy_uj = self
+            .zones
+            .iter()
+            .map(RaplZone::read_energy_uj)
+            .collect::<Option<Vec<u64>>>()?;
+        Some(EnergySample {
+            taken_at: Instant::now(),
+            energy_uj,
+        })
+    }
+
+    /// Energy in joules consumed by all zones between two samples
+    pub fn joules_between(&self, start: &EnergySample, end: &EnergySample) -> f64 {
+        self.zones
+            .iter()
+            .zip(start.energy_uj.iter().zip(end.energy_uj.iter()))
+            .map(|(zone, (&s, &e))| zone.delta_uj(s, e))
+            .sum::<u64>() as f64
+            / 1_000_000.0
+    }
+}
+
+/// Energy consumed during one benchmark phase
+#[derive(Clone, Debug, Default, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub struct PhaseEnergy {
+    /// Phase name (or "Calibration")
+    pub phase: String,
+    /// Measured duration in seconds
+    pub duration_secs: f64,
+    /// Energy consumed in joules
+    pub joules: f64,
+    /// Average power in watts
+    pub avg_watts: f64,
+}
+
+impl PhaseEnergy {
+    /// Energy of a phase between two samples of a meter
+    pub fn between(
+        phase: &str,
+        meter: &PowerMeter,
+        start: &EnergySample,
+        end: &EnergySample,
+    ) -> Self {
+        let duration_secs = end
+            .taken_at
+            .saturating_duration_since(start.taken_at)
+            .as_secs_f64();
+        let joules = meter.joules_between(start, end);
+        PhaseEnergy {
+            phase: phase.to_string(),
+            duration_secs,
+            joules,
+            avg_watts: if duration_secs > 0.0 {
+                joules / duration_secs
+            } else {
+                0.0
+            },
+        }
+    }
+}
+
+/// Energy results of a benchmark session
+#[derive(Clone, Debug, Default, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub struct PowerMetrics {
+    /// Measured RAPL zones (e.g. "package-0")
+    pub zones: Vec<String>,
+    /// Energy per phase in run order
+    pub phases: Vec<PhaseEnergy>,
+    /// Energy of all phases in joules
+    pub total_joules: f64,
+    /// Duration of all phases in seconds
+    pub total_secs: f64,
+    /// Average power over all phases in watts
+    pub avg_watts: f64,
+}
+
+impl PowerMetrics {
+    /// Aggregate per-phase energy
+    pub fn from_phases(zones: Vec<String>, phases: Vec<PhaseEnergy>) -> Self {
+        let total_joules: f64 = phases.iter().map(|p| p.joules).sum();
+        let total_secs: f64 = phases.iter().map(|p| p.duration_secs).sum();
+        PowerMetrics {
+            zones,
+            phases,
+            total_joules,
+            total_secs,
+            avg_watts: if total_secs > 0.0 {
+                total_joules / total_secs
+            } else {
+                0.0
+            },
+        }
+    }
+}
+
+/// Attributes energy to consecutive benchmark phases
+#[derive(Clone, Debug)]
+pub struct PhaseEnergyTracker {
+    meter: PowerMeter,
+    current: Option<(String, EnergySample)>,
+    phases: Vec<PhaseEnergy>,
+}
+
+impl PhaseEnergyTracker {
+    pub fn new(meter: PowerMeter) -> Self {
+        PhaseEnergyTracker {
+            meter,
+            current: None,
+            phases: Vec::new(),
+        }
+    }
+
+    /// Note the phase running now; closes the previous phase when it changes
+    pub fn enter_phase(&mut self, phase: &str) {
+        if self.current.as_ref().is_some_and(|(name, _)| name == phase) {
+            return;
+        }
+        self.finish();
+        self.current = self
+            .meter
+            .sample()
+            .map(|sample| (phase.to_string(), sample));
+    }
+
+    /// Close the running phase
+    pub fn finish(&mut self) {
+        let Some((phase, start)) = self.current.take() else {
+            return;
+        };
+        match self.meter.sample() {
+            Some(end) => {
+                let energy = PhaseEnergy::between(&phase, &self.meter, &start, &end);
+                eprintln!(
+                    "[POWER] {}: {:.1} J over {:.1}s ({:.1} W)",
+                    energy.phase, energy.joules, energy.duration_secs, energy.avg_watts
+                );
+                self.phases.push(energy);
+            }
+            None => eprintln!(
+                "[POWER] Warning: Failed to read energy counters for {}",
+                phase
+            ),
+        }
+    }
+
+    /// Energy results of the closed phases (None before the first phase closes)
+    pub fn metrics(&self) -> Option<PowerMetrics> {
+        if self.phases.is_empty() {
+            return None;
+        }
+        Some(PowerMetrics::from_phases(
+            self.meter.zones.iter().map(|z| z.name.clone()).collect(),
+            self.phases.clone(),
+        ))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::time::Duration;
+
+    fn write_zone(root: &Path, dir: &str, name: &str, energy_uj: u64) {
+        let zone = root.join(dir);
+        fs::create_dir_all(&zone).unwrap();
+        fs::write(zone.join("name"), format!("{}\n", name)).unwrap();
+        fs::write(zone.join("energy_uj"), format!("{}\n", energy_uj)).unwrap();
+        fs::write(zone.join("max_energy_range_uj"), "262143328850\n").unwrap();
+    }
+
+    #[test]
+    fn test_discover_fake_powercap() {
+        let root = tempfile::tempdir().unwrap();
+        assert!(PowerMeter::discover_at(root.path()).is_none());
+
+        // Two sockets, a core sub-zone and a platform zone
+        write_zone(root.path(), "intel-rapl:0", "package-0", 1_000_000);
+        write_zone(root.path(), "intel-rapl:0:0", "core", 500_000);
+        write_zone(root.path(), "intel-rapl:1", "package-1", 2_000_000);
+        write_zone(root.path(), "intel-rapl:2", "psys", 9_000_000);
+
+        let meter = PowerMeter::discover_at(root.path()).unwrap();
+        let names: Vec<&str> = meter.zones().iter().map(|z| z.name.as_str()).collect();
+        assert_eq!(names, vec!["package-0", "package-1"]);
+        assert_eq!(meter.zones()[0].max_energy_range_uj, 262_143_328_850);
+
+        // Without package zones, other top-level zones are used
+        let amd = tempfile::tempdir().unwrap();
+        write_zone(amd.path(), "amd-rapl:0", "amd-zone", 42);
+        let meter = PowerMeter::discover_at(amd.path()).unwrap();
+        assert_eq!(meter.zones()[0].name, "amd-zone");
+    }
+
+    #[test]
+    fn test_phase_energy_with_wraparound() {
+        let root = tempfile::tempdir().unwrap();
+        write_zone(root.path(), "intel-rapl:0", "package-0", 262_140_000_000);
+        write_zone(root.path(), "intel-rapl:1", "package-1", 10_000_000);
+        let meter = PowerMeter::discover_at(root.path()).unwrap();
+        let mut start = meter.sample().unwrap();
+        start.taken_at = Instant::now();
+
+        // package-0 wraps: 3_328_850 µJ to the range limit plus 96_671_150 µJ after it
+        write_zone(root.path(), "intel-rapl:0", "package-0", 96_671_150);
+        write_zone(root.path(), "intel-rapl:1", "package-1", 160_000_000);
+        let mut end = meter.sample().unwrap();
+        end.taken_at = start.taken_at + Duration::from_secs(10);
+
+        let energy = PhaseEnergy::between("Baseline", &meter, &start, &end);
+        assert!((energy.joules - 250.0).abs() < 1e-6);
+        assert!((energy.duration_secs - 10.0).abs() < 1e-9);
+        assert!((energy.avg_watts - 25.0).abs() < 1e-6);
+    }
+
+    #[test]
+    fn test_phase_energy_tracker() {
+        let root = tempfile::tempdir().unwrap();
+        write_zone(root.path(), "intel-rapl:0", "package-0", 0);
+        let mut tracker = PhaseEnergyTracker::new(PowerMeter::discover_at(root.path()).unwrap());
+        assert!(tracker.metrics().is_none());
+
+        tracker.enter_phase("Calibration");
+        write_zone(root.path(), "intel-rapl:0", "package-0", 40_000_000);
+        tracker.enter_phase("Calibration");
+        tracker.enter_phase("Computational Heat");
+        write_zone(root.path(), "intel-rapl:0", "package-0", 190_000_000);
+        tracker.finish();
+        tracker.finish();
+
+        let metrics = tracker.metrics().unwrap();
+        assert_eq!(metrics.zones, vec!["package-0".to_string()]);
+        let phases: Vec<(&str, f64)> = metrics
+            .phases
+            .iter()
+            .map(|p| (p.phase.as_str(), p.joules))
+            .collect();
+        assert_eq!(
+            phases,
+            vec![("Calibration", 40.0), ("Computational Heat", 150.0)]
+        );
+        assert!((metrics.total_joules - 190.0).abs() < 1e-9);
+    }
+}
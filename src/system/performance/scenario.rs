@@ -0,0 +1,413 @@
+//! Declarative benchmark scenarios
+//!
+//! A scenario describes a system benchmark in TOML: a calibration period
+//! followed by named phases, each with a duration and the stressors (with
+//! intensity) that run during it, the specialized collectors to run for the
+//! whole benchmark and the GOAT Score weights. The GOATd Full Benchmark is the
+//! built-in default ([`BenchmarkScenario::goatd_full`]); user scenarios live as
+//! `*.toml` files in `~/.config/goatdkernel/performance/scenarios/`.
+
+use super::scoring::ScoringWeights;
+use super::stressor::{Intensity, StressorType};
+use crate::error::ConfigError;
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::path::{Path, PathBuf};
+
+/// TOML source of the built-in GOATd Full Benchmark
+pub const DEFAULT_SCENARIO_TOML: &str = include_str!("../../../assets/scenarios/goatd-full.toml");
+
+/// Identifier of the built-in scenario (user files may not use it)
+pub const DEFAULT_SCENARIO_ID: &str = "goatd-full";
+
+/// Spectrum strips a phase can highlight with `focus`
+pub const FOCUS_METRICS: &[&str] = &[
+    "Latency",
+    "Throughput",
+    "Jitter",
+    "Efficiency",
+    "Thermal",
+    "Consistency",
+    "SMI Res.",
+];
+
+fn default_calibration_secs() -> u64 {
+    10
+}
+
+fn default_intensity() -> u8 {
+    100
+}
+
+fn default_true() -> bool {
+    true
+}
+
+/// A stressor running during a phase
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct PhaseStressor {
+    #[serde(rename = "type")]
+    pub kind: StressorType,
+    /// Load level 0-100
+    #[serde(default = "default_intensity")]
+    pub intensity: u8,
+}
+
+/// One phase of a scenario
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct ScenarioPhase {
+    pub name: String,
+    pub duration_secs: u64,
+    #[serde(default)]
+    pub stressors: Vec<PhaseStressor>,
+    /// Spectrum strip highlighted while the phase runs (one of [`FOCUS_METRICS`])
+    #[serde(default)]
+    pub focus: Option<String>,
+}
+
+/// Specialized collectors that run in loops for the whole benchmark
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct ScenarioCollectors {
+    #[serde(default = "default_true")]
+    pub micro_jitter: bool,
+    #[serde(default = "default_true")]
+    pub context_switch: bool,
+    #[serde(default = "default_true")]
+    pub syscall: bool,
+    #[serde(default)]
+    pub task_wakeup: bool,
+}
+
+impl Default for ScenarioCollectors {
+    fn default() -> Self {
+        ScenarioCollectors {
+            micro_jitter: true,
+            context_switch: true,
+            syscall: true,
+            task_wakeup: false,
+        }
+    }
+}
+
+/// A system benchmark definition
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct BenchmarkScenario {
+    pub name: String,
+    #[serde(default)]
+    pub description: String,
+    /// Seconds of hardware noise-floor calibration before the first phase
+    #[serde(default = "default_calibration_secs")]
+    pub calibration_secs: u64,
+    #[serde(default)]
+    pub collectors: ScenarioCollectors,
+    #[serde(default)]
+    pub weights: ScoringWeights,
+    pub phases: Vec<ScenarioPhase>,
+}
+
+impl BenchmarkScenario {
+    /// The built-in GOATd Full Benchmark (six 10-second phases)
+    pub fn goatd_full() -> Self {
+        Self::from_toml_str(DEFAULT_SCENARIO_TOML).expect("built-in benchmark scenario is valid")
+    }
+
+    /// Parse and validate a scenario
+    pub fn from_toml_str(content: &str) -> Result<Self, ConfigError> {
+        let scenario: BenchmarkScenario =
+            toml::from_str(content).map_err(|e| ConfigError::InvalidToml(e.to_string()))?;
+        scenario.validate()?;
+        Ok(scenario)
+    }
+
+    /// Load and validate a scenario file
+    pub fn load(path: &Path) -> Result<Self, ConfigError> {
+        let content = fs::read_to_string(path)?;
+        Self::from_toml_str(&content)
+    }
+
+    /// Check phases, intensities, focus names and weights
+    pub fn validate(&self) -> Result<(), ConfigError> {
+        let invalid = |msg: String| {
+            Err(ConfigError::ValidationFailed(format!(
+                "Scenario '{}': {}",
+                self.name, msg
+            )))
+        };
+
+        if self.name.trim().is_empty() {
+            return invalid("name must not be empty".to_string());
+        }
+        if self.phases.is_empty() {
+            return invalid("at least one phase is required".to_string());
+        }
+        for phase in &self.phases {
+            if phase.duration_secs == 0 {
+                return invalid(format!("phase '{}' has a zero duration", phase.name));
+            }
+            if let Some(stressor) = phase.stressors.iter().find(|s| s.intensity > 100) {
+                return invalid(format!(
+                    "phase '{}': {} intensity {} is above 100",
+                    phase.name, stressor.kind, stressor.intensity
+                ));
+            }
+            if let Some(focus) = &phase.focus {
+                if !FOCUS_METRICS.contains(&focus.as_str()) {
+                    return invalid(format!(
+                        "phase '{}': unknown focus '{}' (expected one of {})",
+                        phase.name,
+                        focus,
+                        FOCUS_METRICS.join(", ")
+                    ));
+                }
+            }
+        }
+        if let Err(e) = self.weights.validate() {
+            return invalid(e);
+        }
+        Ok(())
+    }
+
+    /// Calibration plus all phases, in seconds
+    pub fn total_duration_secs(&self) -> u64 {
+        self.calibration_secs + self.phases.iter().map(|p| p.duration_secs).sum::<u64>()
+    }
+
+    /// Seconds since the session start at which phase `index` begins
+    pub fn phase_start_secs(&self, index: usize) -> u64 {
+        self.calibration_secs
+            + self
+                .phases
+                .iter()
+                .take(index)
+                .map(|p| p.duration_secs)
+                .sum::<u64>()
+    }
+
+    /// Seconds since the session start at which phase `index` ends
+    pub fn phase_end_secs(&self, index: usize) -> u64 {
+        self.phase_start_secs(index) + self.phases.get(index).map_or(0, |p| p.duration_secs)
+    }
+
+    /// Stressors of phase `index` with their intensities
+    pub fn phase_stressors(&self, index: usize) -> Vec<(StressorType, Intensity)> {
+        self.phases
+            .get(index)
+            .map(|phase| {
+                phase
+                    .stressors
+                    .iter()
+                    .map(|s| (s.kind, Intensity::new(s.intensity)))
+                    .collect()
+            })
+            .unwrap_or_default()
+    }
+}
+
+impl Default for BenchmarkScenario {
+    fn default() -> Self {
+        Self::goatd_full()
+    }
+}
+
+/// Directory of user scenario files
+pub fn scenarios_dir() -> PathBuf {
+    std::env::var("XDG_CONFIG_HOME")
+        .ok()
+        .map(PathBuf::from)
+        .or_else(|| {
+            std::env::var("HOME")
+                .ok()
+                .map(|h| PathBuf::from(h).join(".config"))
+        })
+        .unwrap_or_else(|| PathBuf::from("/tmp/.config"))
+        .join("goatdkernel")
+        .join("performance")
+        .join("scenarios")
+}
+
+/// Result of loading the scenario directory
+#[derive(Debug, Default)]
+pub struct ScenarioLoad {
+    /// (id, scenario), the built-in scenario first, then user files by name
+    pub scenarios: Vec<(String, BenchmarkScenario)>,
+    /// Files that failed to load and why
+    pub errors: Vec<(PathBuf, ConfigError)>,
+}
+
+/// Load the built-in scenario and every `*.toml` scenario in `dir`
+///
+/// The id of a user scenario is its file stem. A missing directory yields only
+/// the built-in scenario; broken files are reported in [`ScenarioLoad::errors`].
+pub fn load_scenarios(dir: &Path) -> ScenarioLoad {
+    let mut load = ScenarioLoad {
+        scenarios: vec![(
+            DEFAULT_SCENARIO_ID.to_string(),
+            BenchmarkScenario::goatd_full(),
+        )],
+        errors: Vec::new(),
+    };
+
+    let mut files: Vec<PathBuf> = match fs::read_dir(dir) {
+        Ok(entries) => entries
+            .filter_map(|entry| entry.ok())
+            .map(|entry| entry.path())
+            .filter(|path| path.is_file() && path.extension().is_some_and(|ext| ext == "toml"))
+            .collect(),
+        Err(_) => Vec::new(),
+    };
+    files.sort();
+
+    for path in files {
+        let id = path
+            .file_stem()
+            .map(|stem| stem.to_string_lossy().to_string())
+            .unwrap_or_default();
+        if id == DEFAULT_SCENARIO_ID {
+            load.errors.push((
+                path,
+                ConfigError::ConflictDetected(format!(
+                    "Scenario file '{}' shadows the built-in scenario",
+                    id
+                )),
+            ));
+            continue;
+        }
+        match BenchmarkScenario::load(&path) {
+            Ok(scenario) => load.scenarios.push((id, scenario)),
+            Err(e) => {
+                eprintln!("[SCENARIO] Warning: Skipping {}: {}", path.display(), e);
+                load.errors.push((path, e));
+            }
+        }
+    }
+
+    load
+}
+
+/// Validate a scenario file and copy it into `dir`
+///
+/// Returns the id of the imported scenario. An existing user scenario with
+/// the same file name is replaced.
+pub fn import_scenario(source: &Path, dir: &Path) -> Result<String, ConfigError> {
+    let scenario = BenchmarkScenario::load(source)?;
+    let id = source
+        .file_stem()
+        .map(|stem| stem.to_string_lossy().to_string())
+        .filter(|stem| !stem.is_empty())
+        .ok_or_else(|| ConfigError::FileNotFound(source.display().to_string()))?;
+    if id == DEFAULT_SCENARIO_ID {
+        return Err(ConfigError::ConflictDetected(format!(
+            "Scenario file '{}' shadows the built-in scenario; rename it",
+            id
+        )));
+    }
+
+    fs::create_dir_all(dir)?;
+    fs::copy(source, dir.join(format!("{}.toml", id)))?;
+    eprintln!(
+        "[SCENARIO] Imported '{}' ({} phases, {}s) as {}",
+        scenario.name,
+        scenario.phases.len(),
+        scenario.total_duration_secs(),
+        id
+    );
+    Ok(id)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const SHORT: &str = r#"
+name = "Short"
+
+[weights]
+latency = 1.0
+consistency = 0.0
+jitter = 0.0
+throughput = 0.0
+efficiency = 0.0
+thermal = 0.0
+smi_resilience = 0.0
+
+[[phases]]
+name = "Idle"
+duration_secs = 5
+
+[[phases]]
+name = "Load"
+duration_secs = 20
+focus = "Jitter"
+stressors = [{ type = "cpu", intensity = 75 }, { type = "scheduler" }]
+"#;
+
+    #[test]
+    fn test_default_scenario_matches_full_benchmark() {
+        let scenario = BenchmarkScenario::goatd_full();
+        assert_eq!(scenario.phases.len(), 6);
+        assert_eq!(scenario.total_duration_secs(), 70);
+        assert_eq!(scenario.phase_start_secs(0), 10);
+        assert_eq!(scenario.phase_end_secs(5), 70);
+        assert_eq!(scenario.phases[5].name, "The Gauntlet");
+        assert_eq!(scenario.phase_stressors(0).len(), 0);
+        let gaming = scenario.phase_stressors(4);
+        assert_eq!(gaming[0].0, StressorType::Cpu);
+        assert_eq!(gaming[0].1.value(), 50);
+        assert_eq!(gaming[1].0, StressorType::Scheduler);
+        assert_eq!(scenario.weights, ScoringWeights::default());
+        assert!(!scenario.collectors.task_wakeup);
+    }
+
+    #[test]
+    fn test_parse_and_validate_scenario() {
+        let scenario = BenchmarkScenario::from_toml_str(SHORT).unwrap();
+        assert_eq!(scenario.calibration_secs, 10);
+        assert_eq!(scenario.total_duration_secs(), 35);
+        assert_eq!(scenario.phase_end_secs(1), 35);
+        assert_eq!(scenario.phase_stressors(1)[1].1.value(), 100);
+        assert!(scenario.collectors.syscall);
+        assert_eq!(scenario.weights.normalized().latency, 1.0);
+
+        let bad_focus = SHORT.replace("\"Jitter\"", "\"Speed\"");
+        assert!(matches!(
+            BenchmarkScenario::from_toml_str(&bad_focus),
+            Err(ConfigError::ValidationFailed(_))
+        ));
+        let bad_intensity = SHORT.replace("intensity = 75", "intensity = 150");
+        assert!(BenchmarkScenario::from_toml_str(&bad_intensity).is_err());
+        let no_phases = "name = \"Empty\"\nphases = []\n";
+        assert!(BenchmarkScenario::from_toml_str(no_phases).is_err());
+        assert!(matches!(
+            BenchmarkScenario::from_toml_str("name = "),
+            Err(ConfigError::InvalidToml(_))
+        ));
+    }
+
+    #[test]
+    fn test_import_and_load_scenarios() {
+        let source = tempfile::tempdir().unwrap();
+        let dir = tempfile::tempdir().unwrap();
+        let short = source.path().join("short.toml");
+        fs::write(&short, SHORT).unwrap();
+        let broken = source.path().join("broken.toml");
+        fs::write(&broken, "name = \"Broken\"\n").unwrap();
+
+        assert_eq!(import_scenario(&short, dir.path()).unwrap(), "short");
+        assert!(import_scenario(&broken, dir.path()).is_err());
+        let shadow = source.path().join("goatd-full.toml");
+        fs::write(&shadow, SHORT).unwrap();
+        assert!(matches!(
+            import_scenario(&shadow, dir.path()),
+            Err(ConfigError::ConflictDetected(_))
+        ));
+
+        fs::write(dir.path().join("broken.toml"), "name = \"Broken\"\n").unwrap();
+        let load = load_scenarios(dir.path());
+        let ids: Vec<&str> = load.scenarios.iter().map(|(id, _)| id.as_str()).collect();
+        assert_eq!(ids, vec![DEFAULT_SCENARIO_ID, "short"]);
+        assert_eq!(load.errors.len(), 1);
+
+        let missing = load_scenarios(Path::new("/nonexistent/goatd/scenarios"));
+        assert_eq!(missing.scenarios.len(), 1);
+    }
+}
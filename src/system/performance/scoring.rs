@@ -7,10 +7,12 @@
 //! - **7-Metric Spectrum**: Latency (27%), Consistency (18%), Jitter (15%), Throughput (10%),
 //!   CPU Efficiency (10%), Thermal (10%), SMI Resilience (10%)
 //! - **GOAT Score**: Weighted aggregate (0-1000) from normalized metrics
+//! - **Perf-per-Watt**: GOAT points per package watt when RAPL energy was measured;
+//!   a personality dimension only, it does not change the GOAT Score
 //! - **Personality Analysis**: Derives personality type based on metric strengths
 //! - **Balanced Override**: Detects versatile kernels with no dominant weakness
 
-use crate::system::performance::{BenchmarkMetrics, PerformanceMetrics};
+use crate::system::performance::{BenchmarkMetrics, PerformanceMetrics, PowerMetrics};
 use serde::{Deserialize, Serialize};
 use std::fmt;
 
@@ -30,6 +32,8 @@ pub enum PersonalityType {
     Balanced,
     /// Server: Optimized for stability, task agility, and efficiency
     Server,
+    /// Power-efficient: Most performance per watt (laptops, small form factor)
+    PowerEfficient,
 }
 
 impl fmt::Display for PersonalityType {
@@ -41,6 +45,7 @@ impl fmt::Display for PersonalityType {
             PersonalityType::Throughput => write!(f, "Throughput"),
             PersonalityType::Balanced => write!(f, "Balanced"),
             PersonalityType::Server => write!(f, "Server"),
+            PersonalityType::PowerEfficient => write!(f, "Power-Efficient"),
         }
     }
 }
@@ -67,6 +72,9 @@ impl PersonalityType {
             PersonalityType::Server => {
                 "Optimized for stable task scheduling and long-term efficiency"
             }
+            PersonalityType::PowerEfficient => {
+                "Delivers the most performance per watt for battery and thermally limited systems"
+            }
         }
     }
 
@@ -79,6 +87,7 @@ impl PersonalityType {
             PersonalityType::Throughput => "🚀",
             PersonalityType::Balanced => "⚖️",
             PersonalityType::Server => "🖥️",
+            PersonalityType::PowerEfficient => "🔋",
         }
     }
 }
@@ -103,12 +112,95 @@ pub struct ScoringResult {
     pub is_balanced_override: bool,
     /// Percentage above average (0-100 range)
     pub specialization_index: f32,
+    /// GOAT points per package watt (None without energy measurement)
+    #[serde(default)]
+    pub perf_per_watt: Option<f32>,
+    /// Perf-per-watt normalized to 0-100 (None without energy measurement)
+    #[serde(default)]
+    pub power_efficiency_score: Option<f32>,
 }
 
 /// Performance Scorer: Transforms raw metrics into GOAT Score and Personality
 pub struct PerformanceScorer {
     /// Reference benchmarks for normalization (best-case values)
     pub reference_benchmarks: ReferenceBenchmarks,
+    /// Weights of the 7 spectrum metrics in the GOAT Score
+    pub weights: ScoringWeights,
+    /// Energy of the scored session for the perf-per-watt dimension
+    pub power: Option<PowerMetrics>,
+}
+
+/// Weights of the 7 spectrum metrics in the GOAT Score
+///
+/// Benchmark scenarios can override them; they are normalized to sum to 1.0
+/// before scoring, so only their ratios matter.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct ScoringWeights {
+    pub latency: f32,
+    pub consistency: f32,
+    pub jitter: f32,
+    pub throughput: f32,
+    pub efficiency: f32,
+    pub thermal: f32,
+    pub smi_resilience: f32,
+}
+
+impl Default for ScoringWeights {
+    fn default() -> Self {
+        ScoringWeights {
+            latency: 0.27,
+            consistency: 0.18,
+            jitter: 0.15,
+            throughput: 0.10,
+            efficiency: 0.10,
+            thermal: 0.10,
+            smi_resilience: 0.10,
+        }
+    }
+}
+
+impl ScoringWeights {
+    fn as_array(&self) -> [f32; 7] {
+        [
+            self.latency,
+            self.consistency,
+            self.jitter,
+            self.throughput,
+            self.efficiency,
+            self.thermal,
+            self.smi_resilience,
+        ]
+    }
+
+    /// Check that no weight is negative and at least one is positive
+    pub fn validate(&self) -> Result<(), String> {
+        let weights = self.as_array();
+        if weights.iter().any(|w| !w.is_finite() || *w < 0.0) {
+            return Err("scoring weights must be finite and not negative".to_string());
+        }
+        if weights.iter().sum::<f32>() <= 0.0 {
+            return Err("at least one scoring weight must be positive".to_string());
+        }
+        Ok(())
+    }
+
+    /// The weights scaled to sum to 1.0 (the defaults if they cannot be)
+    pub fn normalized(&self) -> ScoringWeights {
+        if self.validate().is_err() {
+            return ScoringWeights::default();
+        }
+        let sum: f32 = self.as_array().iter().sum();
+        ScoringWeights {
+            latency: self.latency / sum,
+            consistency: self.consistency / sum,
+            jitter: self.jitter / sum,
+            throughput: self.throughput / sum,
+            efficiency: self.efficiency / sum,
+            thermal: self.thermal / sum,
+            smi_resilience: self.smi_resilience / sum,
+        }
+    }
 }
 
 /// Reference benchmarks for metric normalization
@@ -130,6 +222,10 @@ pub struct ReferenceBenchmarks {
     pub max_core_temp_c: f32,
     /// Cold temperature baseline in Celsius (for efficiency scaling)
     pub cold_temp_c: f32,
+    /// Best (highest) GOAT points per package watt
+    pub perf_per_watt: f32,
+    /// Worst (lowest) GOAT points per package watt
+    pub min_perf_per_watt: f32,
 }
 
 impl Default for ReferenceBenchmarks {
@@ -143,6 +239,8 @@ impl Default for ReferenceBenchmarks {
             task_wakeup_latency_us: 100.0,  // 100µs task wakeup baseline
             max_core_temp_c: 80.0,          // 80°C as max acceptable
             cold_temp_c: 40.0,              // 40°C as thermal baseline
+            perf_per_watt: 50.0,            // e.g. 750 points at 15 W
+            min_perf_per_watt: 2.0,         // e.g. 500 points at 250 W
         }
     }
 }
@@ -152,6 +250,8 @@ impl PerformanceScorer {
     pub fn new() -> Self {
         PerformanceScorer {
             reference_benchmarks: ReferenceBenchmarks::default(),
+            weights: ScoringWeights::default(),
+            power: None,
         }
     }
 
@@ -159,9 +259,23 @@ impl PerformanceScorer {
     pub fn with_references(references: ReferenceBenchmarks) -> Self {
         PerformanceScorer {
             reference_benchmarks: references,
+            weights: ScoringWeights::default(),
+            power: None,
         }
     }
 
+    /// Use custom metric weights (e.g. from a benchmark scenario)
+    pub fn with_weights(mut self, weights: ScoringWeights) -> Self {
+        self.weights = weights;
+        self
+    }
+
+    /// Add the perf-per-watt dimension from measured session energy
+    pub fn with_power(mut self, power: Option<PowerMetrics>) -> Self {
+        self.power = power;
+        self
+    }
+
     /// Score a PerformanceMetrics instance (Phase 2 collector output)
     ///
     /// TRUSTWORTHY CALIBRATION: Applies noise floor fairness offset
@@ -205,21 +319,30 @@ impl PerformanceScorer {
         );
 
         // Calculate GOAT Score using 7-metric weights
-        // Latency (27%), Consistency (18%), Jitter (15%), Throughput (10%),
+        // Default: Latency (27%), Consistency (18%), Jitter (15%), Throughput (10%),
         // Efficiency (10%), Thermal (10%), SMI Res (10%)
-        let weighted_score = (latency_score * 0.27)
-            + (consistency_score * 0.18)
-            + (jitter_score * 0.15)
-            + (throughput_score * 0.10)
-            + (efficiency_score * 0.10)
-            + (thermal_score * 0.10)
-            + (smi_score * 0.10);
+        let weights = self.weights.normalized();
+        let weighted_score = (latency_score * weights.latency)
+            + (consistency_score * weights.consistency)
+            + (jitter_score * weights.jitter)
+            + (throughput_score * weights.throughput)
+            + (efficiency_score * weights.efficiency)
+            + (thermal_score * weights.thermal)
+            + (smi_score * weights.smi_resilience);
 
         // weighted_score is already on 0-100 scale, multiply by 10.0 to get 0-1000
         let goat_score = ((weighted_score * 10.0).min(1000.0)) as u16;
 
+        // Perf-per-watt: only when energy was measured, and not part of the GOAT Score
+        let perf_per_watt = self
+            .power
+            .as_ref()
+            .filter(|power| power.avg_watts > 0.0)
+            .map(|power| goat_score as f32 / power.avg_watts as f32);
+        let power_efficiency_score = perf_per_watt.map(|ppw| self.normalize_perf_per_watt(ppw));
+
         // Determine personality based on dominant metrics
-        let metrics_vec = vec![
+        let mut metrics_vec = vec![
             ("Latency", latency_score),
             ("Consistency", consistency_score),
             ("Jitter", jitter_score),
@@ -228,6 +351,9 @@ impl PerformanceScorer {
             ("Thermal", thermal_score),
             ("SMI-Resilience", smi_score),
         ];
+        if let Some(power_score) = power_efficiency_score {
+            metrics_vec.push(("Power-Efficiency", power_score));
+        }
 
         let (primary_name, primary_score) = metrics_vec
             .iter()
@@ -278,6 +404,8 @@ impl PerformanceScorer {
             brief,
             is_balanced_override: is_balanced,
             specialization_index,
+            perf_per_watt,
+            power_efficiency_score,
         }
     }
 
@@ -493,6 +621,15 @@ impl PerformanceScorer {
         }
     }
 
+    /// Normalize GOAT points per package watt to a 0-100 power efficiency score
+    pub fn normalize_perf_per_watt(&self, perf_per_watt: f32) -> f32 {
+        self.normalize_higher_is_better(
+            perf_per_watt,
+            self.reference_benchmarks.perf_per_watt,
+            self.reference_benchmarks.min_perf_per_watt,
+        )
+    }
+
     /// Normalize SMI spike correlation to 0-100 resistance score
     pub fn normalize_smi_resistance(&self, total_spikes: u64, smi_correlated: u64) -> f32 {
         if total_spikes == 0 {
@@ -560,6 +697,7 @@ impl PerformanceScorer {
             "Throughput" => PersonalityType::Throughput,
             "Thermal" => PersonalityType::Workstation,
             "Efficiency" => PersonalityType::Server,
+            "Power-Efficiency" => PersonalityType::PowerEfficient,
             _ => PersonalityType::Balanced,
         }
     }
@@ -614,12 +752,37 @@ mod tests {
         assert_eq!(PersonalityType::Gaming.symbol(), "🎮");
         assert_eq!(PersonalityType::RealTime.symbol(), "⚡");
         assert_eq!(PersonalityType::Balanced.symbol(), "⚖️");
+        assert_eq!(PersonalityType::PowerEfficient.symbol(), "🔋");
     }
 
     #[test]
     fn test_scorer_creation() {
         let scorer = PerformanceScorer::new();
         assert_eq!(scorer.reference_benchmarks.p99_latency_us, 50.0);
+        assert_eq!(scorer.weights, ScoringWeights::default());
+    }
+
+    #[test]
+    fn test_scoring_weights_normalized() {
+        let weights = ScoringWeights {
+            latency: 2.0,
+            consistency: 2.0,
+            jitter: 0.0,
+            throughput: 0.0,
+            efficiency: 0.0,
+            thermal: 0.0,
+            smi_resilience: 0.0,
+        };
+        let normalized = weights.normalized();
+        assert_eq!(normalized.latency, 0.5);
+        assert_eq!(normalized.consistency, 0.5);
+
+        let negative = ScoringWeights {
+            thermal: -1.0,
+            ..Default::default()
+        };
+        assert!(negative.validate().is_err());
+        assert_eq!(negative.normalized(), ScoringWeights::default());
     }
 
     #[test]
@@ -682,6 +845,46 @@ mod tests {
         assert!(score < 20.0);
     }
 
+    #[test]
+    fn test_perf_per_watt_dimension() {
+        let scorer = PerformanceScorer::new();
+        assert_eq!(scorer.normalize_perf_per_watt(60.0), 100.0);
+        assert_eq!(scorer.normalize_perf_per_watt(26.0), 50.0);
+        assert_eq!(scorer.normalize_perf_per_watt(1.0), 0.0);
+
+        let metrics = PerformanceMetrics {
+            p99_us: 40.0,
+            core_temperatures: vec![55.0],
+            ..Default::default()
+        };
+        let without_power = scorer.score_metrics(&metrics);
+        assert_eq!(without_power.perf_per_watt, None);
+        assert_eq!(without_power.power_efficiency_score, None);
+
+        let power = PowerMetrics::from_phases(
+            vec!["package-0".to_string()],
+            vec![crate::system::performance::PhaseEnergy {
+                phase: "Baseline".to_string(),
+                duration_secs: 10.0,
+                joules: 100.0,
+                avg_watts: 10.0,
+            }],
+        );
+        let with_power = PerformanceScorer::new()
+            .with_power(Some(power))
+            .score_metrics(&metrics);
+        // Perf-per-watt is a personality dimension only
+        assert_eq!(with_power.goat_score, without_power.goat_score);
+        let ppw = with_power.perf_per_watt.unwrap();
+        assert!((ppw - with_power.goat_score as f32 / 10.0).abs() < 1e-3);
+        assert!(with_power.power_efficiency_score.unwrap() > 0.0);
+
+        assert!(matches!(
+            scorer.classify_personality_from_metrics("Power-Efficiency"),
+            PersonalityType::PowerEfficient
+        ));
+    }
+
     #[test]
     fn test_smi_resistance() {
         let scorer = PerformanceScorer::new();
@@ -56,9 +56,27 @@ pub struct UIState {
     /// Build error messages (separate from general error_message for isolated display)
     pub build_errors: Vec<String>,
 
+    /// Known failure recognised in the last build's log (excerpt + suggested fix)
+    pub build_failure: Option<crate::orchestrator::failure::BuildFailureDiagnosis>,
+
     /// Path to the current build's full log file on disk
     pub build_log_file_path: Option<String>,
 
+    /// Build queue snapshot (live from controller)
+    pub build_queue: Vec<crate::orchestrator::QueuedBuild>,
+
+    /// Label for the next queued build (empty = "<variant> <profile>")
+    pub queue_label: String,
+
+    /// Schedule of the next queued build (0 = next, 1 = once at, 2 = daily at)
+    pub queue_schedule_idx: usize,
+
+    /// Start time ("HH:MM") for scheduled queue entries
+    pub queue_time: String,
+
+    /// Last queue error (invalid time, persistence failure)
+    pub queue_error: Option<String>,
+
     /// Whether to show the comparison modal
     pub show_compare_popup: bool,
 
@@ -98,6 +116,15 @@ pub struct UIState {
     /// Use native optimizations (-march=native)
     pub native_optimizations: bool,
 
+    /// Kernel command line additions being edited (space-separated)
+    pub cmdline_additions: String,
+
+    /// Kernel command line removals being edited (space-separated)
+    pub cmdline_removals: String,
+
+    /// Also write the baked-in command line to the boot entry
+    pub cmdline_boot_entry: bool,
+
     /// Selected SCX profile index
     pub selected_scx_profile: Option<usize>,
 
@@ -143,6 +170,12 @@ pub struct UIState {
     /// Flag to track if kernel list has been initialized (prevents re-scanning on every frame)
     pub ui_state_initialized: bool,
 
+    /// Whether the Kconfig diff window is open
+    pub show_config_diff: bool,
+
+    /// Artifact being diffed against the running kernel (for the window title)
+    pub config_diff_artifact: String,
+
     /// Map of kernel variant name to its latest fetched version string
     pub latest_versions: HashMap<String, String>,
 
@@ -231,7 +264,13 @@ impl Default for UIState {
             is_building: false,
             build_elapsed_seconds: 0,
             build_errors: Vec::new(),
+            build_failure: None,
             build_log_file_path: None,
+            build_queue: Vec::new(),
+            queue_label: String::new(),
+            queue_schedule_idx: 0,
+            queue_time: "03:00".to_string(),
+            queue_error: None,
             show_compare_popup: false,
             selected_kernel_name: String::new(),
             error_message: None,
@@ -245,6 +284,9 @@ impl Default for UIState {
             use_polly: false,
             use_mglru: false,
             native_optimizations: true,
+            cmdline_additions: String::new(),
+            cmdline_removals: String::new(),
+            cmdline_boot_entry: false,
             selected_scx_profile: None,
             scx_enabled: false,
             active_scx_binary: String::new(),
@@ -260,6 +302,8 @@ impl Default for UIState {
             installed_kernels: Vec::new(),
             built_artifacts: Vec::new(),
             ui_state_initialized: false,
+            show_config_diff: false,
+            config_diff_artifact: String::new(),
             latest_versions: HashMap::new(),
             version_poll_active: HashSet::new(),
             last_version_poll: HashMap::new(),
@@ -485,7 +529,7 @@ impl AppUI {
                     }
                     crate::ui::controller::BuildEvent::JitterAuditComplete(summary) => {
                         // Update UI state with the completed jitter audit summary
-                        self.ui_state.jitter_audit_summary = Some(summary.clone());
+                        self.ui_state.jitter_audit_summary = Some(*summary.clone());
                         self.ui_state.is_auditing_jitter = false;
                         log::debug!(
                             "[UI] Jitter audit completed: samples={}, duration={:.2}s",
@@ -513,6 +557,26 @@ impl AppUI {
                         eprintln!("[UI] WorkspaceChanged event received, resetting UI state for re-scan");
                         self.ui_state.needs_repaint = true;
                     }
+                    crate::ui::controller::BuildEvent::QueueChanged(entries) => {
+                        self.ui_state.build_queue = entries;
+                        self.ui_state.needs_repaint = true;
+                    }
+                    crate::ui::controller::BuildEvent::QueuedBuildStarted(label) => {
+                        // Same clean slate as the START BUILD button
+                        self.ui_state.is_building = true;
+                        self.ui_state.build_log.clear();
+                        self.ui_state.build_progress = 0;
+                        self.ui_state.build_status = format!("Queued build: {}", label);
+                        self.ui_state.current_build_phase = String::new();
+                        self.ui_state.build_elapsed_seconds = 0;
+                        self.ui_state.build_errors.clear();
+                        self.ui_state.build_failure = None;
+                        self.ui_state.needs_repaint = true;
+                    }
+                    crate::ui::controller::BuildEvent::FailureDiagnosed(diagnosis) => {
+                        self.ui_state.build_failure = Some(diagnosis);
+                        self.ui_state.needs_repaint = true;
+                    }
                 }
             }
         }
@@ -635,11 +699,10 @@ impl AppUI {
         if let Ok(guard) = self.controller.try_read() {
             if let Ok(state) = guard.get_state() {
                 // Sync variant: AppState string -> UIState index
-                let variant_options =
-                    vec!["linux", "linux-lts", "linux-hardened", "linux-mainline"];
+                let variant_options = guard.variant_options();
                 if let Some(variant_idx) = variant_options
                     .iter()
-                    .position(|&v| v == state.selected_variant)
+                    .position(|v| *v == state.selected_variant)
                 {
                     if self.ui_state.selected_variant != variant_idx {
                         log::debug!("[SYNC] Variant mismatch: UI index={}, Backend='{}'. Updating UI state.",
@@ -649,10 +712,10 @@ impl AppUI {
                 }
 
                 // Sync profile: AppState string -> UIState index
-                let profile_options = vec!["Gaming", "Workstation", "Laptop", "Server"];
+                let profile_options = crate::config::profiles::ui_profile_keys();
                 if let Some(profile_idx) = profile_options
                     .iter()
-                    .position(|&p| p == state.selected_profile)
+                    .position(|p| p.eq_ignore_ascii_case(&state.selected_profile))
                 {
                     if self.ui_state.selected_profile != profile_idx {
                         log::debug!("[SYNC] Profile mismatch: UI index={}, Backend='{}'. Updating UI state.",
@@ -724,6 +787,20 @@ impl AppUI {
                     );
                     self.ui_state.use_whitelist = state.use_whitelist;
                 }
+
+                // Sync command line editor: only when the parsed text differs, so
+                // the user's in-progress spacing is kept
+                if super::build::split_params(&self.ui_state.cmdline_additions)
+                    != state.cmdline_additions
+                {
+                    self.ui_state.cmdline_additions = state.cmdline_additions.join(" ");
+                }
+                if super::build::split_params(&self.ui_state.cmdline_removals)
+                    != state.cmdline_removals
+                {
+                    self.ui_state.cmdline_removals = state.cmdline_removals.join(" ");
+                }
+                self.ui_state.cmdline_boot_entry = state.cmdline_boot_entry;
             }
         }
     }
@@ -929,17 +1006,23 @@ impl eframe::App for AppUI {
         self.sync_build_settings();
 
         // Trigger version polling when Build tab is focused with debouncing (30-second throttle per variant)
-        // Polls all canonical kernel variants: linux, linux-lts, linux-hardened, linux-mainline, linux-zen, linux-tkg
+        // Polls all canonical kernel variants (linux, linux-lts, linux-hardened, linux-mainline,
+        // linux-cachyos, linux-rt, linux-zen, linux-tkg) plus any user-registered sources
         let current_tab = self.ui_state.active_tab;
         if current_tab == Tab::Build {
-            let variants = vec![
-                "linux",
-                "linux-lts",
-                "linux-hardened",
-                "linux-mainline",
-                "linux-zen",
-                "linux-tkg",
-            ];
+            let mut variants: Vec<String> = ["linux-zen", "linux-tkg"]
+                .iter()
+                .map(|v| v.to_string())
+                .collect();
+            match self.controller.try_read() {
+                Ok(guard) => variants.extend(guard.variant_options()),
+                Err(_) => variants.extend(
+                    crate::kernel::sources::UI_VARIANTS
+                        .iter()
+                        .map(|v| v.to_string()),
+                ),
+            }
+            let variants: Vec<&str> = variants.iter().map(String::as_str).collect();
             let now = Instant::now();
             let poll_interval = std::time::Duration::from_secs(1800);
 
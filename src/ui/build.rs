@@ -17,7 +17,7 @@ pub struct BuildUIState {
     /// Selected variant (linux, linux-lts, etc.)
     pub selected_variant: usize,
 
-    /// Selected profile (Gaming, Workstation, Server, Laptop)
+    /// Selected profile (index into `profiles::ui_profile_keys()`)
     pub selected_profile: usize,
 
     /// Selected LTO level (None, Thin, Full)
@@ -65,6 +65,11 @@ pub fn render_build(ui: &mut egui::Ui, app: &mut AppUI, controller: &Arc<RwLock<
                         ui.group(|ui| {
                             render_build_parameters(ui, app, controller);
                         });
+
+                        ui.add_space(8.0);
+                        ui.group(|ui| {
+                            render_build_queue(ui, app, controller);
+                        });
                         
                         if app.ui_state.is_building {
                             ui.add_space(8.0);
@@ -73,7 +78,9 @@ pub fn render_build(ui: &mut egui::Ui, app: &mut AppUI, controller: &Arc<RwLock<
                             });
                         }
                         
-                        if !app.ui_state.build_errors.is_empty() {
+                        if !app.ui_state.build_errors.is_empty()
+                            || app.ui_state.build_failure.is_some()
+                        {
                             ui.add_space(8.0);
                             ui.group(|ui| {
                                 render_build_errors(ui, app);
@@ -95,13 +102,19 @@ fn render_build_parameters(ui: &mut egui::Ui, app: &mut AppUI, controller: &Arc<
 
     ui.horizontal(|ui| {
         ui.label("Variant:");
-        let variants = vec!["linux", "linux-lts", "linux-hardened", "linux-mainline"];
-        let selected_variant_name = variants.get(app.ui_state.selected_variant).copied().unwrap_or("linux");
+        // Built-in variants plus user-registered sources (Settings → Kernel Sources)
+        let variants = controller
+            .try_read()
+            .map(|guard| guard.variant_options())
+            .unwrap_or_else(|_| {
+                crate::kernel::sources::UI_VARIANTS.iter().map(|v| v.to_string()).collect()
+            });
+        let selected_variant_name = variants.get(app.ui_state.selected_variant).map(String::as_str).unwrap_or("linux");
         egui::ComboBox::from_id_source("build_variant_combo")
             .selected_text(selected_variant_name)
             .show_ui(ui, |ui| {
                 for (i, variant) in variants.iter().enumerate() {
-                    if ui.selectable_value(&mut app.ui_state.selected_variant, i, *variant).changed() {
+                    if ui.selectable_value(&mut app.ui_state.selected_variant, i, variant.as_str()).changed() {
                         let variant_str = variant.to_string();
                         let controller_clone = Arc::clone(controller);
 
@@ -154,7 +167,7 @@ fn render_build_parameters(ui: &mut egui::Ui, app: &mut AppUI, controller: &Arc<
             });
 
         // Display latest version or polling indicator
-        let selected_variant_name = variants.get(app.ui_state.selected_variant).copied().unwrap_or("linux");
+        let selected_variant_name = variants.get(app.ui_state.selected_variant).map(String::as_str).unwrap_or("linux");
         if app.ui_state.version_poll_active.contains(selected_variant_name) {
             ui.add(egui::Spinner::new().size(12.0));
             ui.label("Checking version...");
@@ -169,14 +182,24 @@ fn render_build_parameters(ui: &mut egui::Ui, app: &mut AppUI, controller: &Arc<
 
     ui.horizontal(|ui| {
         ui.label("Profile:");
-        let profiles = vec!["Gaming", "Workstation", "Laptop", "Server"];
-        let profiles_lower = vec!["gaming", "workstation", "laptop", "server"];
-        let selected_profile_name = profiles.get(app.ui_state.selected_profile).copied().unwrap_or("Gaming");
+        // Built-in profiles plus user profiles from ~/.config/goatdkernel/profiles/*.toml
+        let available_profiles = crate::config::profiles::get_available_profiles();
+        let profiles_lower = crate::config::profiles::ui_profile_keys();
+        let profile_label = |key: &str| -> String {
+            available_profiles
+                .get(key)
+                .map(|def| if def.is_user_defined() { format!("{} (custom)", def.name) } else { def.name.clone() })
+                .unwrap_or_else(|| key.to_string())
+        };
+        let selected_profile_name = profiles_lower
+            .get(app.ui_state.selected_profile)
+            .map(|key| profile_label(key))
+            .unwrap_or_else(|| "Gaming".to_string());
         egui::ComboBox::from_id_source("build_profile_combo")
             .selected_text(selected_profile_name)
             .show_ui(ui, |ui| {
-                for (i, profile) in profiles.iter().enumerate() {
-                    if ui.selectable_value(&mut app.ui_state.selected_profile, i, *profile).changed() {
+                for (i, profile) in profiles_lower.iter().enumerate() {
+                    if ui.selectable_value(&mut app.ui_state.selected_profile, i, profile_label(profile)).changed() {
                         let profile_str = profile.to_string();
                         let profile_str_clone = profile_str.clone();  // Clone before async move
                         let controller_clone = Arc::clone(controller);
@@ -214,8 +237,8 @@ fn render_build_parameters(ui: &mut egui::Ui, app: &mut AppUI, controller: &Arc<
 
         // Display profile explanation to the right of the ComboBox
         if let Some(profile_key) = profiles_lower.get(app.ui_state.selected_profile) {
-            if let Some(profile_def) = crate::config::profiles::get_profile(profile_key) {
-                ui.label(profile_def.explanation);
+            if let Some(profile_def) = available_profiles.get(profile_key) {
+                ui.label(&profile_def.explanation);
             }
         }
     });
@@ -375,6 +398,288 @@ fn render_build_parameters(ui: &mut egui::Ui, app: &mut AppUI, controller: &Arc<
             ui.label("(requires modprobed-db to be enabled)");
         }
     });
+
+    ui.separator();
+    render_cmdline_editor(ui, app, controller);
+}
+
+/// Render the kernel command line editor with a live preview of `CONFIG_CMDLINE`
+fn render_cmdline_editor(ui: &mut egui::Ui, app: &mut AppUI, controller: &Arc<RwLock<AppController>>) {
+    use crate::config::cmdline;
+
+    ui.heading("Kernel Command Line");
+
+    let mut changed = false;
+    ui.horizontal(|ui| {
+        ui.label("Add:");
+        changed |= ui
+            .add(
+                egui::TextEdit::singleline(&mut app.ui_state.cmdline_additions)
+                    .hint_text("threadirqs split_lock_detect=off")
+                    .desired_width(320.0),
+            )
+            .on_hover_text("Parameters to add; a key the profile already sets is overridden")
+            .changed();
+    });
+    ui.horizontal(|ui| {
+        ui.label("Remove:");
+        changed |= ui
+            .add(
+                egui::TextEdit::singleline(&mut app.ui_state.cmdline_removals)
+                    .hint_text("nowatchdog preempt")
+                    .desired_width(320.0),
+            )
+            .on_hover_text("Keys (preempt) or exact parameters (preempt=full) to drop from the profile defaults")
+            .changed();
+    });
+    changed |= ui
+        .checkbox(
+            &mut app.ui_state.cmdline_boot_entry,
+            "Also write these parameters to the boot entry",
+        )
+        .changed();
+
+    let user_cmdline = crate::models::KernelCmdlineConfig {
+        additions: split_params(&app.ui_state.cmdline_additions),
+        removals: split_params(&app.ui_state.cmdline_removals),
+        boot_entry: app.ui_state.cmdline_boot_entry,
+    };
+
+    if changed {
+        // Persist synchronously so the per-frame sync never sees stale text
+        let persist = |controller: &AppController, user_cmdline: &crate::models::KernelCmdlineConfig| {
+            let _ = controller.update_state(|state| {
+                state.cmdline_additions = user_cmdline.additions.clone();
+                state.cmdline_removals = user_cmdline.removals.clone();
+                state.cmdline_boot_entry = user_cmdline.boot_entry;
+            });
+        };
+        if let Ok(controller_guard) = controller.try_read() {
+            persist(&controller_guard, &user_cmdline);
+        } else {
+            let controller_clone = Arc::clone(controller);
+            let user_cmdline = user_cmdline.clone();
+            tokio::spawn(async move {
+                persist(&*controller_clone.read().await, &user_cmdline);
+            });
+        }
+    }
+
+    let profile = crate::config::profiles::ui_profile_keys()
+        .get(app.ui_state.selected_profile)
+        .cloned()
+        .unwrap_or_else(|| "gaming".to_string());
+    let preview_config = crate::models::KernelConfig {
+        profile,
+        hardening: crate::models::HardeningLevel::from_index(app.ui_state.selected_hardening),
+        use_mglru: app.ui_state.use_mglru,
+        cmdline: user_cmdline,
+        ..crate::models::KernelConfig::default()
+    };
+    match cmdline::preview(&preview_config) {
+        Ok(resolved) => {
+            ui.horizontal(|ui| {
+                ui.label("CONFIG_CMDLINE:");
+                ui.monospace(resolved.render())
+                    .on_hover_text(resolved.preview_lines().join("\n"));
+            });
+        }
+        Err(e) => {
+            ui.colored_label(egui::Color32::from_rgb(255, 100, 100), format!("⚠ {}", e));
+        }
+    }
+}
+
+/// Queue schedule choices, indexed by `UIState::queue_schedule_idx`
+const QUEUE_SCHEDULES: [&str; 3] = ["Next", "Once at", "Daily at"];
+
+/// A queue edit requested from the Build tab
+enum QueueAction {
+    Move(u64, isize),
+    Cancel(u64),
+    ClearFinished,
+}
+
+/// Render the build queue: queue the current configuration, reorder and cancel entries
+fn render_build_queue(ui: &mut egui::Ui, app: &mut AppUI, controller: &Arc<RwLock<AppController>>) {
+    use crate::orchestrator::queue::{parse_time_of_day, BuildSchedule, QueueStatus};
+
+    ui.heading("Build Queue");
+
+    ui.horizontal(|ui| {
+        ui.label("Label:");
+        ui.add(
+            egui::TextEdit::singleline(&mut app.ui_state.queue_label)
+                .hint_text("variant + profile")
+                .desired_width(180.0),
+        );
+        egui::ComboBox::from_id_source("queue_schedule")
+            .selected_text(QUEUE_SCHEDULES[app.ui_state.queue_schedule_idx])
+            .show_ui(ui, |ui| {
+                for (idx, name) in QUEUE_SCHEDULES.iter().enumerate() {
+                    ui.selectable_value(&mut app.ui_state.queue_schedule_idx, idx, *name);
+                }
+            });
+        if app.ui_state.queue_schedule_idx != 0 {
+            ui.add(
+                egui::TextEdit::singleline(&mut app.ui_state.queue_time)
+                    .hint_text("HH:MM")
+                    .desired_width(50.0),
+            );
+        }
+
+        if ui
+            .button("➕ Add to Queue")
+            .on_hover_text(
+                "Queue the current build configuration; queued builds run one after another",
+            )
+            .clicked()
+        {
+            let schedule = match (
+                app.ui_state.queue_schedule_idx,
+                parse_time_of_day(&app.ui_state.queue_time),
+            ) {
+                (0, _) => Ok(BuildSchedule::Now),
+                (1, Some((hour, minute))) => BuildSchedule::Daily { hour, minute }
+                    .next_run(chrono::Local::now())
+                    .map(|time| BuildSchedule::At { time })
+                    .ok_or_else(|| "Invalid start time".to_string()),
+                (_, Some((hour, minute))) => Ok(BuildSchedule::Daily { hour, minute }),
+                (_, None) => Err(format!(
+                    "Invalid time '{}' (expected HH:MM)",
+                    app.ui_state.queue_time
+                )),
+            };
+            let result = schedule.and_then(|schedule| match controller.try_read() {
+                Ok(controller_guard) => controller_guard
+                    .enqueue_build(&app.ui_state.queue_label, schedule)
+                    .map(|_| ()),
+                Err(_) => Err("Controller busy, try again".to_string()),
+            });
+            match result {
+                Ok(()) => {
+                    app.ui_state.queue_label.clear();
+                    app.ui_state.queue_error = None;
+                }
+                Err(e) => app.ui_state.queue_error = Some(e),
+            }
+        }
+    });
+
+    if let Some(error) = &app.ui_state.queue_error {
+        ui.colored_label(
+            egui::Color32::from_rgb(255, 100, 100),
+            format!("⚠ {}", error),
+        );
+    }
+
+    if app.ui_state.build_queue.is_empty() {
+        ui.label("No queued builds.");
+        return;
+    }
+
+    let mut action = None;
+    let last = app.ui_state.build_queue.len() - 1;
+    egui::Grid::new("build_queue_grid")
+        .striped(true)
+        .spacing([12.0, 4.0])
+        .show(ui, |ui| {
+            for (idx, entry) in app.ui_state.build_queue.iter().enumerate() {
+                let color = match entry.status {
+                    QueueStatus::Pending => egui::Color32::from_rgb(180, 180, 180),
+                    QueueStatus::Running => egui::Color32::from_rgb(100, 180, 255),
+                    QueueStatus::Succeeded => egui::Color32::from_rgb(76, 175, 80),
+                    QueueStatus::Failed => egui::Color32::from_rgb(244, 67, 54),
+                    QueueStatus::Cancelled => egui::Color32::from_rgb(140, 140, 140),
+                };
+                ui.colored_label(color, entry.status.as_str());
+                ui.label(&entry.label);
+                ui.label(format!(
+                    "{} / {} / {:?} LTO",
+                    entry.config.kernel_variant, entry.config.profile, entry.config.lto_type
+                ));
+                ui.label(match (entry.status, entry.next_run) {
+                    (QueueStatus::Pending, Some(at)) => format!(
+                        "{} (next {})",
+                        entry.schedule.describe(),
+                        at.format("%a %H:%M")
+                    ),
+                    _ => entry.schedule.describe(),
+                });
+                ui.label(match (entry.last_success, entry.last_finished) {
+                    (Some(success), Some(at)) => format!(
+                        "last run {} {}",
+                        if success { "✓" } else { "✗" },
+                        at.format("%Y-%m-%d %H:%M")
+                    ),
+                    _ => String::new(),
+                });
+
+                ui.horizontal(|ui| {
+                    if !entry.status.is_finished() {
+                        if ui
+                            .add_enabled(idx > 0, egui::Button::new("⬆"))
+                            .on_hover_text("Run earlier")
+                            .clicked()
+                        {
+                            action = Some(QueueAction::Move(entry.id, -1));
+                        }
+                        if ui
+                            .add_enabled(idx < last, egui::Button::new("⬇"))
+                            .on_hover_text("Run later")
+                            .clicked()
+                        {
+                            action = Some(QueueAction::Move(entry.id, 1));
+                        }
+                        let cancel_hint = if entry.status == QueueStatus::Running {
+                            "Cancel the running build"
+                        } else {
+                            "Remove from the queue"
+                        };
+                        if ui.button("✖").on_hover_text(cancel_hint).clicked() {
+                            action = Some(QueueAction::Cancel(entry.id));
+                        }
+                    }
+                });
+                ui.end_row();
+            }
+        });
+
+    if app
+        .ui_state
+        .build_queue
+        .iter()
+        .any(|e| e.status.is_finished())
+        && ui.button("🧹 Clear Finished").clicked()
+    {
+        action = Some(QueueAction::ClearFinished);
+    }
+
+    if let Some(action) = action {
+        let apply = move |controller: &AppController| {
+            let result = match action {
+                QueueAction::Move(id, offset) => controller.move_queued_build(id, offset),
+                QueueAction::Cancel(id) => controller.cancel_queued_build(id),
+                QueueAction::ClearFinished => controller.clear_finished_builds(),
+            };
+            if let Err(e) = result {
+                log_info!("[BUILD] [QUEUE] {}", e);
+            }
+        };
+        if let Ok(controller_guard) = controller.try_read() {
+            apply(&controller_guard);
+        } else {
+            let controller_clone = Arc::clone(controller);
+            tokio::spawn(async move {
+                apply(&*controller_clone.read().await);
+            });
+        }
+    }
+}
+
+/// Split a space-separated parameter list as typed in the command line editor
+pub fn split_params(text: &str) -> Vec<String> {
+    text.split_whitespace().map(str::to_string).collect()
 }
 
 /// Render build progress section
@@ -418,6 +723,43 @@ fn render_build_errors(ui: &mut egui::Ui, app: &mut AppUI) {
     for idx in errors_to_remove.iter().rev() {
         app.ui_state.build_errors.remove(*idx);
     }
+
+    // Known failure recognised by the build-log analyzer
+    let mut dismiss_diagnosis = false;
+    if let Some(diagnosis) = &app.ui_state.build_failure {
+        ui.add_space(4.0);
+        ui.horizontal(|ui| {
+            ui.colored_label(
+                egui::Color32::from_rgb(255, 100, 100),
+                format!("Diagnosis: {}", diagnosis.title()),
+            );
+            if ui.button("✕").clicked() {
+                dismiss_diagnosis = true;
+            }
+        });
+        ui.horizontal_wrapped(|ui| {
+            ui.colored_label(egui::Color32::from_rgb(200, 150, 0), "Suggested fix:");
+            ui.label(&diagnosis.suggested_fix);
+        });
+        egui::CollapsingHeader::new("Log excerpt")
+            .id_source("build_failure_excerpt")
+            .default_open(true)
+            .show(ui, |ui| {
+                for line in &diagnosis.excerpt {
+                    if *line == diagnosis.matched_line {
+                        ui.colored_label(
+                            egui::Color32::from_rgb(255, 100, 100),
+                            egui::RichText::new(line).monospace(),
+                        );
+                    } else {
+                        ui.monospace(line);
+                    }
+                }
+            });
+    }
+    if dismiss_diagnosis {
+        app.ui_state.build_failure = None;
+    }
 }
 
 /// Render build log viewer with dynamic height
@@ -503,6 +845,7 @@ pub fn render_build_controls(
                         app.ui_state.current_build_phase = String::new();
                         app.ui_state.build_elapsed_seconds = 0;
                         app.ui_state.build_errors.clear();
+                        app.ui_state.build_failure = None;
 
                         // Spawn async build task via tokio with context for repaints
                         let controller_clone = Arc::clone(controller);
@@ -592,33 +935,6 @@ fn generate_build_log_filename() -> String {
 /// Result indicating whether build initialization succeeded
 pub async fn start_build(controller: &AppController, ctx_handle: Option<egui::Context>) -> Result<(), String> {
     eprintln!("[BUILD] [START_BUILD] ⭐ START_BUILD CALLED");
-    controller.log_event("BUILD", "Starting kernel build orchestration");
-
-    // =========================================================================
-    // START NEW LOG SESSION - Ensure full and dedicated log file for this build
-    // =========================================================================
-    let _session_log_path = if let Some(ref log_collector) = controller.log_collector {
-        let filename = generate_build_log_filename();
-        match log_collector.start_new_session(&filename).await {
-            Ok(path) => {
-                controller.log_event("BUILD", &format!("Full build log: {}", path.display()));
-                Some(path)
-            }
-            Err(e) => {
-                log_info!("[BUILD] Warning: Failed to start log session: {}", e);
-                None
-            }
-        }
-    } else {
-        log_info!("[BUILD] Warning: LogCollector not available");
-        None
-    };
-
-    // Send initial status to UI
-    let _ = controller
-        .build_tx
-        .send(BuildEvent::Status("preparation".into()))
-        .await;
 
     eprintln!("[BUILD] [START_BUILD] ⚠ About to call get_state() to read AppState");
     let state = controller.get_state()?;
@@ -666,6 +982,143 @@ pub async fn start_build(controller: &AppController, ctx_handle: Option<egui::Co
         state.kernel_hardening
     );
 
+    // =========================================================================
+    // ROBUST KERNEL CONFIG POPULATION
+    // =========================================================================
+    eprintln!("[BUILD] [CONFIG_POPULATION] ⭐ STARTING CONFIG POPULATION");
+    let config = config_from_state(&state);
+
+    // DIAGNOSTIC: Validate variant assignment
+    eprintln!("[BUILD] [CONFIG_POPULATION] ⭐ kernel_variant assignment:");
+    eprintln!(
+        "[BUILD] [CONFIG_POPULATION]   Source (state.selected_variant): '{}'",
+        state.selected_variant
+    );
+    eprintln!(
+        "[BUILD] [CONFIG_POPULATION]   Target (config.kernel_variant): '{}'",
+        config.kernel_variant
+    );
+    eprintln!(
+        "[BUILD] [CONFIG_POPULATION]   Match: {}",
+        state.selected_variant == config.kernel_variant
+    );
+
+    if config.kernel_variant.is_empty() {
+        eprintln!("[BUILD] [CONFIG_POPULATION] ❌ CRITICAL ERROR: kernel_variant is EMPTY!");
+        return Err("kernel_variant is empty - cannot proceed with build".to_string());
+    } else {
+        eprintln!(
+            "[BUILD] [CONFIG_POPULATION] ✓ kernel_variant is non-empty: '{}'",
+            config.kernel_variant
+        );
+    }
+
+    // DIAGNOSTIC: Validate config population
+    log_info!("[BUILD] [CONFIG_VALIDATION] version field: '{}' (should be 'latest' for dynamic resolution)", config.version);
+    log_info!("[BUILD] [CONFIG_VALIDATION] kernel_variant field: '{}' (identifies which variant to fetch)", config.kernel_variant);
+    log_info!(
+        "[BUILD] Config: variant={}, lto={:?}, profile={}",
+        config.version,
+        config.lto_type,
+        config.profile
+    );
+
+    launch_build(controller, config, ctx_handle)
+        .await
+        .map(|_| ())
+}
+
+/// Snapshot the Build tab selections in `state` as a [`crate::models::KernelConfig`]
+///
+/// Used for immediate builds and for builds added to the queue.
+pub fn config_from_state(state: &crate::config::AppState) -> crate::models::KernelConfig {
+    let mut config = crate::models::KernelConfig::default();
+
+    // CRITICAL: Keep version as "latest" for dynamic orchestrator resolution
+    config.version = "latest".to_string();
+    config.kernel_variant = state.selected_variant.clone();
+
+    // LTO: String from AppState -> LtoType enum with validation
+    config.lto_type = match state.selected_lto.as_str() {
+        "full" => crate::models::LtoType::Full,
+        "none" => crate::models::LtoType::None,
+        _ => crate::models::LtoType::Thin,
+    };
+
+    // Module stripping flags: Boolean from AppState
+    config.use_modprobed = state.use_modprobed;
+    config.use_whitelist = state.use_whitelist;
+
+    // Hardening: HardeningLevel from AppState
+    config.hardening = state.kernel_hardening;
+
+    // Security boot
+    config.secure_boot = state.secure_boot;
+    config.mok_key_path = (!state.mok_key_path.is_empty())
+        .then(|| std::path::PathBuf::from(&state.mok_key_path));
+    config.mok_cert_path = (!state.mok_cert_path.is_empty())
+        .then(|| std::path::PathBuf::from(&state.mok_cert_path));
+
+    // Profile: String from AppState
+    config.profile = state.selected_profile.clone();
+
+    // Optimization flags: Boolean from AppState with user override tracking
+    config.use_polly = state.use_polly;
+    config.use_mglru = state.use_mglru;
+    config.user_toggled_polly = state.user_toggled_polly;
+    config.user_toggled_mglru = state.user_toggled_mglru;
+    config.user_toggled_lto = state.user_toggled_lto;
+    config.user_toggled_hardening = state.user_toggled_hardening;
+    config.user_toggled_bore = state.user_toggled_bore;
+
+    // Kernel command line: user layer on top of the profile defaults
+    config.cmdline = crate::models::KernelCmdlineConfig {
+        additions: state.cmdline_additions.clone(),
+        removals: state.cmdline_removals.clone(),
+        boot_entry: state.cmdline_boot_entry,
+    };
+
+    config
+}
+
+/// Launch an orchestrated build of `config` in the configured workspace
+///
+/// Returns once the build is running; the handle resolves to whether it succeeded.
+pub async fn launch_build(
+    controller: &AppController,
+    config: crate::models::KernelConfig,
+    ctx_handle: Option<egui::Context>,
+) -> Result<tokio::task::JoinHandle<bool>, String> {
+    controller.log_event("BUILD", "Starting kernel build orchestration");
+
+    // =========================================================================
+    // START NEW LOG SESSION - Ensure full and dedicated log file for this build
+    // =========================================================================
+    let _session_log_path = if let Some(ref log_collector) = controller.log_collector {
+        let filename = generate_build_log_filename();
+        match log_collector.start_new_session(&filename).await {
+            Ok(path) => {
+                controller.log_event("BUILD", &format!("Full build log: {}", path.display()));
+                Some(path)
+            }
+            Err(e) => {
+                log_info!("[BUILD] Warning: Failed to start log session: {}", e);
+                None
+            }
+        }
+    } else {
+        log_info!("[BUILD] Warning: LogCollector not available");
+        None
+    };
+
+    // Send initial status to UI
+    let _ = controller
+        .build_tx
+        .send(BuildEvent::Status("preparation".into()))
+        .await;
+
+    let state = controller.get_state()?;
+
     let mut detector = crate::hardware::HardwareDetector::new();
     let hw_info = detector.detect_all().map_err(|e| {
         let msg = format!("Hardware detection failed: {}", e);
@@ -740,86 +1193,12 @@ pub async fn start_build(controller: &AppController, ctx_handle: Option<egui::Co
         return Err(error_msg);
     }
 
-    let kernel_path = workspace_path.join(&state.selected_variant);
+    let kernel_path = workspace_path.join(&config.kernel_variant);
     let _ = controller.build_tx.try_send(BuildEvent::Log(format!(
         "[Controller] [PRE-FLIGHT] Using authorized workspace: {}",
         workspace_path.display()
     )));
 
-    // =========================================================================
-    // ROBUST KERNEL CONFIG POPULATION
-    // =========================================================================
-    eprintln!("[BUILD] [CONFIG_POPULATION] ⭐ STARTING CONFIG POPULATION");
-    let mut config = crate::models::KernelConfig::default();
-
-    // CRITICAL: Keep version as "latest" for dynamic orchestrator resolution
-    config.version = "latest".to_string();
-    config.kernel_variant = state.selected_variant.clone();
-
-    // DIAGNOSTIC: Validate variant assignment
-    eprintln!("[BUILD] [CONFIG_POPULATION] ⭐ kernel_variant assignment:");
-    eprintln!(
-        "[BUILD] [CONFIG_POPULATION]   Source (state.selected_variant): '{}'",
-        state.selected_variant
-    );
-    eprintln!(
-        "[BUILD] [CONFIG_POPULATION]   Target (config.kernel_variant): '{}'",
-        config.kernel_variant
-    );
-    eprintln!(
-        "[BUILD] [CONFIG_POPULATION]   Match: {}",
-        state.selected_variant == config.kernel_variant
-    );
-
-    if config.kernel_variant.is_empty() {
-        eprintln!("[BUILD] [CONFIG_POPULATION] ❌ CRITICAL ERROR: kernel_variant is EMPTY!");
-        return Err("kernel_variant is empty - cannot proceed with build".to_string());
-    } else {
-        eprintln!(
-            "[BUILD] [CONFIG_POPULATION] ✓ kernel_variant is non-empty: '{}'",
-            config.kernel_variant
-        );
-    }
-
-    // LTO: String from AppState -> LtoType enum with validation
-    config.lto_type = match state.selected_lto.as_str() {
-        "full" => crate::models::LtoType::Full,
-        "none" => crate::models::LtoType::None,
-        _ => crate::models::LtoType::Thin,
-    };
-
-    // Module stripping flags: Boolean from AppState
-    config.use_modprobed = state.use_modprobed;
-    config.use_whitelist = state.use_whitelist;
-
-    // Hardening: HardeningLevel from AppState
-    config.hardening = state.kernel_hardening;
-
-    // Security boot
-    config.secure_boot = state.secure_boot;
-
-    // Profile: String from AppState
-    config.profile = state.selected_profile.clone();
-
-    // Optimization flags: Boolean from AppState with user override tracking
-    config.use_polly = state.use_polly;
-    config.use_mglru = state.use_mglru;
-    config.user_toggled_polly = state.user_toggled_polly;
-    config.user_toggled_mglru = state.user_toggled_mglru;
-    config.user_toggled_lto = state.user_toggled_lto;
-    config.user_toggled_hardening = state.user_toggled_hardening;
-    config.user_toggled_bore = state.user_toggled_bore;
-
-    // DIAGNOSTIC: Validate config population
-    log_info!("[BUILD] [CONFIG_VALIDATION] version field: '{}' (should be 'latest' for dynamic resolution)", config.version);
-    log_info!("[BUILD] [CONFIG_VALIDATION] kernel_variant field: '{}' (identifies which variant to fetch)", config.kernel_variant);
-    log_info!(
-        "[BUILD] Config: variant={}, lto={:?}, profile={}",
-        config.version,
-        config.lto_type,
-        config.profile
-    );
-
     // Create async orchestrator
     let checkpoint_dir = workspace_path.join(".checkpoints");
 
@@ -864,10 +1243,13 @@ pub async fn start_build(controller: &AppController, ctx_handle: Option<egui::Co
 
     // Spawn background build task
     let log_collector_for_flush = controller.log_collector.clone();
-    tokio::task::spawn_blocking(move || {
+    let build_active = controller.build_active.clone();
+    let queue_notify = controller.queue_notify.clone();
+    build_active.store(true, std::sync::atomic::Ordering::SeqCst);
+    let handle = tokio::task::spawn_blocking(move || {
         let rt = tokio::runtime::Runtime::new().unwrap();
 
-        rt.block_on(async {
+        let success = rt.block_on(async {
             let _ = tx
                 .send(BuildEvent::Status("Starting kernel build...".to_string()))
                 .await;
@@ -902,11 +1284,20 @@ pub async fn start_build(controller: &AppController, ctx_handle: Option<egui::Co
                     }
 
                     let _ = tx.send(BuildEvent::Finished(true)).await;
+                    true
                 }
                 Err(e) => {
                     let err_msg = format!("Build orchestration failed: {}", e);
                     log::error!("[Build] {}", err_msg);
                     let _ = tx.send(BuildEvent::Log(err_msg.clone())).await;
+                    if let Some(diagnosis) = e
+                        .downcast_ref::<crate::error::BuildError>()
+                        .and_then(|build_err| build_err.diagnosis())
+                    {
+                        let _ = tx
+                            .send(BuildEvent::FailureDiagnosed(diagnosis.clone()))
+                            .await;
+                    }
 
                     // CRITICAL: Flush logs even on error
                     if let Some(ref log_collector) = log_collector_for_flush {
@@ -921,15 +1312,99 @@ pub async fn start_build(controller: &AppController, ctx_handle: Option<egui::Co
                     }
 
                     let _ = tx.send(BuildEvent::Finished(false)).await;
+                    false
                 }
             }
         });
+
+        timer_handle.abort();
+        build_active.store(false, std::sync::atomic::Ordering::SeqCst);
+        // Let the queue runner pick up the next build
+        queue_notify.notify_one();
+        success
     });
 
     log::debug!("[Build] Build timer task spawned");
-    let _ = timer_handle;
 
-    Ok(())
+    Ok(handle)
+}
+
+/// Longest the queue runner sleeps between checks for due builds
+const QUEUE_POLL_INTERVAL: std::time::Duration = std::time::Duration::from_secs(60);
+
+/// Run queued builds one at a time for the lifetime of the app
+///
+/// The runner wakes when the queue changes, when a build finishes and at the
+/// next scheduled start, and launches due entries through [`launch_build`].
+pub fn spawn_build_queue_runner(
+    controller: Arc<RwLock<AppController>>,
+) -> tokio::task::JoinHandle<()> {
+    tokio::spawn(async move {
+        let notify = {
+            let controller = controller.read().await;
+            let _ = controller
+                .build_tx
+                .send(BuildEvent::QueueChanged(controller.queued_builds()))
+                .await;
+            controller.queue_notify.clone()
+        };
+
+        loop {
+            let started = {
+                let controller = controller.read().await;
+                match controller.start_next_queued_build() {
+                    Some(entry) => {
+                        eprintln!("[Build] [QUEUE] Starting queued build '{}'", entry.label);
+                        let _ = controller
+                            .build_tx
+                            .send(BuildEvent::QueuedBuildStarted(entry.label.clone()))
+                            .await;
+                        match launch_build(&controller, entry.config, controller.get_ui_context())
+                            .await
+                        {
+                            Ok(handle) => Some((entry.id, handle)),
+                            Err(e) => {
+                                eprintln!(
+                                    "[Build] [QUEUE] ✗ Queued build '{}' failed to start: {}",
+                                    entry.label, e
+                                );
+                                controller.finish_queued_build(entry.id, false);
+                                None
+                            }
+                        }
+                    }
+                    None => None,
+                }
+            };
+
+            if let Some((id, handle)) = started {
+                let success = handle.await.unwrap_or(false);
+                eprintln!(
+                    "[Build] [QUEUE] Queued build finished (success={})",
+                    success
+                );
+                controller.read().await.finish_queued_build(id, success);
+                continue;
+            }
+
+            // Sleep until the next scheduled start (or a queue change)
+            let wait = {
+                let controller = controller.read().await;
+                let next_wakeup = controller
+                    .build_queue
+                    .read()
+                    .ok()
+                    .and_then(|queue| queue.next_wakeup());
+                next_wakeup
+                    .and_then(|at| (at - chrono::Local::now()).to_std().ok())
+                    .map_or(QUEUE_POLL_INTERVAL, |until| until.min(QUEUE_POLL_INTERVAL))
+            };
+            tokio::select! {
+                _ = notify.notified() => {}
+                _ = tokio::time::sleep(wait) => {}
+            }
+        }
+    })
 }
 
 /// Cancel active build with timeout-aware UI state reset
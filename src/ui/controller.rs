@@ -6,16 +6,21 @@ use super::{AuditTrait, KernelManagerTrait, SystemWrapper};
 use crate::config::{AppState, SettingsManager};
 use crate::kernel::manager::KernelManagerImpl;
 use crate::log_info;
+use crate::models::CustomKernelSource;
+use crate::orchestrator::failure::BuildFailureDiagnosis;
+use crate::orchestrator::queue::{BuildQueue, BuildSchedule, QueuedBuild};
 use crate::system::performance::collector::LatencyProcessor;
+use crate::system::performance::multicore;
 use crate::system::performance::{
-    BenchmarkOrchestrator, ContextSwitchCollector, ContextSwitchConfig, HistogramBucket,
-    HistoryManager, Intensity, KernelContext, LatencyCollector, LifecycleState,
-    MicroJitterCollector, MicroJitterConfig, MonitoringMode, MonitoringState, PerformanceConfig,
-    PerformanceHistory, PerformanceMetrics, PerformanceRecord, SessionSummary, StressorManager,
-    StressorType, SyscallSaturationCollector, SyscallSaturationConfig,
+    compare_runs, BenchmarkOrchestrator, ComparedMetric, ContextSwitchCollector,
+    ContextSwitchConfig, ExperimentComparison, HistogramBucket, HistoryManager, Intensity,
+    KernelContext, LatencyCollector, LifecycleState, MicroJitterCollector, MicroJitterConfig,
+    MonitoringMode, MonitoringState, MultiCoreSession, PerformanceConfig, PerformanceHistory,
+    PerformanceMetrics, PerformanceRecord, SessionSummary, StressorManager, StressorType,
+    SyscallSaturationCollector, SyscallSaturationConfig, TaskWakeupCollector, TaskWakeupConfig,
 };
 use crate::system::SystemImpl;
-use std::collections::VecDeque;
+use std::collections::{HashMap, VecDeque};
 use std::path::PathBuf;
 use std::sync::atomic::{AtomicBool, AtomicU64, Ordering};
 use std::sync::Arc;
@@ -36,10 +41,14 @@ pub enum BuildEvent {
     InstallationComplete(bool),          // Installation finished (success/failure)
     KernelUninstalled,                   // Kernel package was successfully uninstalled
     LatestVersionUpdate(String, String), // (variant_name, version_string)
-    JitterAuditComplete(SessionSummary), // Jitter audit session completed
+    JitterAuditComplete(Box<SessionSummary>), // Jitter audit session completed
     ArtifactDeleted,                     // Built artifact was successfully deleted
     VersionResolved(String), // Dynamic version successfully resolved to concrete version
-    WorkspaceChanged,                    // Workspace path changed, forces UI refresh
+    WorkspaceChanged,        // Workspace path changed, forces UI refresh
+    QueueChanged(Vec<QueuedBuild>), // Build queue snapshot after any change
+    QueuedBuildStarted(String), // A queued build (label) is starting
+    /// Known failure recognised in the build log (excerpt + suggested fix)
+    FailureDiagnosed(BuildFailureDiagnosis),
 }
 
 /// Central state manager for AppController
@@ -98,6 +107,13 @@ pub struct AppController {
     pub active_kernel_audit: Arc<RwLock<Option<crate::kernel::audit::KernelAuditData>>>,
     /// Cached kernel audit data for selected kernel (Kernel Manager)
     pub selected_kernel_audit: Arc<RwLock<Option<crate::kernel::audit::KernelAuditData>>>,
+    /// Kconfig diff of the selected artifact against the running kernel (Kernel Manager)
+    pub artifact_config_diff:
+        Arc<RwLock<Option<Result<crate::kernel::config_diff::ConfigDiff, String>>>>,
+    /// Boot-success state (trial/good/failed) of tracked kernels, keyed by package base
+    pub kernel_boot_states: Arc<
+        RwLock<HashMap<String, crate::system::boot_tracking::BootStatus>>,
+    >,
     /// Cached jitter audit summary (populated when jitter audit completes)
     pub cached_jitter_summary: Arc<RwLock<Option<SessionSummary>>>,
     /// Benchmark orchestrator for SystemBenchmark mode
@@ -118,6 +134,12 @@ pub struct AppController {
     pub collector_syscall: Arc<RwLock<Option<SyscallSaturationCollector>>>,
     /// Shared container for benchmark metrics from specialized collectors
     pub benchmark_metrics_container: Arc<RwLock<crate::system::performance::BenchmarkMetrics>>,
+    /// Whether an orchestrated build is running (GUI or queued)
+    pub build_active: Arc<AtomicBool>,
+    /// Persistent queue of builds waiting to run
+    pub build_queue: Arc<RwLock<BuildQueue>>,
+    /// Wakes the queue runner when the queue changes
+    pub queue_notify: Arc<tokio::sync::Notify>,
 }
 
 impl AppController {
@@ -159,6 +181,14 @@ impl AppController {
             })
             .ok();
 
+        // Restore queued builds from the previous session
+        let build_queue = BuildQueue::default_path()
+            .and_then(|path| BuildQueue::load(&path))
+            .unwrap_or_else(|e| {
+                log_info!("[AppController] WARNING: Failed to load build queue: {}", e);
+                BuildQueue::default()
+            });
+
         // Clone settings before moving it into the controller
         let settings_clone = settings.clone();
 
@@ -190,6 +220,8 @@ impl AppController {
             atomic_ui_dirty: Arc::new(AtomicBool::new(false)),
             active_kernel_audit: Arc::new(RwLock::new(None)),
             selected_kernel_audit: Arc::new(RwLock::new(None)),
+            artifact_config_diff: Arc::new(RwLock::new(None)),
+            kernel_boot_states: Arc::new(RwLock::new(HashMap::new())),
             cached_jitter_summary: Arc::new(RwLock::new(None)),
             benchmark_orchestrator: Arc::new(RwLock::new(None)),
             cached_health_report: Arc::new(RwLock::new(None)),
@@ -202,6 +234,9 @@ impl AppController {
             benchmark_metrics_container: Arc::new(RwLock::new(
                 crate::system::performance::BenchmarkMetrics::new(),
             )),
+            build_active: Arc::new(AtomicBool::new(false)),
+            build_queue: Arc::new(RwLock::new(build_queue)),
+            queue_notify: Arc::new(tokio::sync::Notify::new()),
         };
 
         // TRIGGER INITIAL DEEP AUDIT - CONSOLIDATED FROM MAIN.RS
@@ -245,6 +280,9 @@ impl AppController {
             }
         });
 
+        // Post-boot check: confirm or fail kernels installed as trials
+        controller.check_boot_success_async();
+
         Ok(controller)
     }
 
@@ -307,6 +345,35 @@ impl AppController {
         })
     }
 
+    /// Kernel variants offered in the Build tab, including user-registered sources
+    pub fn variant_options(&self) -> Vec<String> {
+        let custom = self
+            .settings
+            .read()
+            .map(|state| state.custom_sources.clone())
+            .unwrap_or_default();
+        crate::kernel::sources::KernelSourceDB::with_custom_sources(&custom).ui_variants()
+    }
+
+    /// Register a custom kernel source (replacing one of the same name) and persist it
+    pub fn add_custom_source(&self, source: CustomKernelSource) -> Result<(), String> {
+        crate::kernel::sources::validate_custom_source(&source)?;
+        self.update_state(|state| {
+            state.custom_sources.retain(|s| s.name != source.name);
+            state.custom_sources.push(source.clone());
+        })
+    }
+
+    /// Remove a custom kernel source; a build selection of it falls back to `linux`
+    pub fn remove_custom_source(&self, name: &str) -> Result<(), String> {
+        self.update_state(|state| {
+            state.custom_sources.retain(|s| s.name != name);
+            if state.selected_variant == name {
+                state.selected_variant = "linux".to_string();
+            }
+        })
+    }
+
     /// Reset all application state to defaults
     /// Used for Settings → Reset to Defaults button
     pub fn reset_to_defaults(&self) -> Result<(), String> {
@@ -401,6 +468,15 @@ impl AppController {
                 "WORKSPACE",
                 "Workspace path changed, UI state reset to re-scan kernels",
             ),
+            BuildEvent::QueueChanged(entries) => {
+                self.log_event("QUEUE", &format!("{} queued builds", entries.len()))
+            }
+            BuildEvent::QueuedBuildStarted(label) => {
+                self.log_event("QUEUE", &format!("Starting queued build: {}", label))
+            }
+            BuildEvent::FailureDiagnosed(diagnosis) => {
+                self.log_event("DIAGNOSIS", diagnosis.title())
+            }
         }
     }
 
@@ -462,7 +538,7 @@ impl AppController {
                     );
 
                     // Get the git URL from KernelSourceDB
-                    let db = KernelSourceDB::new();
+                    let db = KernelSourceDB::load();
                     if let Some(git_url) = db.get_source_url(&variant) {
                         match get_latest_remote_version(git_url) {
                             Ok(v) => {
@@ -706,6 +782,91 @@ impl AppController {
         super::build::cancel_build(self)
     }
 
+    /// Queue the current Build tab configuration under `label`
+    pub fn enqueue_build(&self, label: &str, schedule: BuildSchedule) -> Result<u64, String> {
+        let config = super::build::config_from_state(&self.get_state()?);
+        if config.kernel_variant.is_empty() {
+            return Err("No kernel variant selected".to_string());
+        }
+        let label = if label.trim().is_empty() {
+            format!("{} {}", config.kernel_variant, config.profile)
+        } else {
+            label.trim().to_string()
+        };
+        self.log_event(
+            "QUEUE",
+            &format!("Queued '{}' ({})", label, schedule.describe()),
+        );
+        self.update_queue(|queue| queue.enqueue(label, config, schedule, chrono::Local::now()))
+    }
+
+    /// Snapshot of the build queue in run order
+    pub fn queued_builds(&self) -> Vec<QueuedBuild> {
+        self.build_queue
+            .read()
+            .map(|queue| queue.entries().to_vec())
+            .unwrap_or_default()
+    }
+
+    /// Move a queued build `offset` places (negative = earlier)
+    pub fn move_queued_build(&self, id: u64, offset: isize) -> Result<(), String> {
+        self.update_queue(|queue| queue.move_entry(id, offset))
+            .map(|_| ())
+    }
+
+    /// Cancel a queued build; cancelling the running entry stops its build
+    pub fn cancel_queued_build(&self, id: u64) -> Result<(), String> {
+        let previous = self.update_queue(|queue| queue.cancel(id))?;
+        if previous == Some(crate::orchestrator::QueueStatus::Running) {
+            self.cancel_build();
+        }
+        Ok(())
+    }
+
+    /// Remove succeeded, failed and cancelled entries from the queue
+    pub fn clear_finished_builds(&self) -> Result<(), String> {
+        self.update_queue(|queue| queue.clear_finished())
+            .map(|_| ())
+    }
+
+    /// Mark the next due queued build as running, unless a build is already active
+    pub fn start_next_queued_build(&self) -> Option<QueuedBuild> {
+        if self.build_active.load(Ordering::SeqCst) {
+            return None;
+        }
+        self.update_queue(|queue| {
+            let id = queue.next_due(chrono::Local::now())?;
+            queue.mark_running(id).cloned()
+        })
+        .ok()
+        .flatten()
+    }
+
+    /// Record the outcome of a queued build
+    pub fn finish_queued_build(&self, id: u64, success: bool) {
+        if let Err(e) = self.update_queue(|queue| queue.finish(id, success, chrono::Local::now())) {
+            log_info!("[AppController] WARNING: {}", e);
+        }
+    }
+
+    /// Apply `f` to the queue, persist it and publish the new snapshot
+    fn update_queue<R>(&self, f: impl FnOnce(&mut BuildQueue) -> R) -> Result<R, String> {
+        let (result, snapshot) = {
+            let mut queue = self
+                .build_queue
+                .write()
+                .map_err(|e| format!("Failed to lock build queue: {}", e))?;
+            let result = f(&mut queue);
+            queue
+                .save()
+                .map_err(|e| format!("Failed to save build queue: {}", e))?;
+            (result, queue.entries().to_vec())
+        };
+        let _ = self.build_tx.try_send(BuildEvent::QueueChanged(snapshot));
+        self.queue_notify.notify_one();
+        Ok(result)
+    }
+
     /// Uninstall a kernel package - delegates to kernels module
     pub fn uninstall_kernel(&self, pkg_name: &str) -> Result<(), String> {
         self.log_event("KERNEL", &format!("Uninstalling kernel: {}", pkg_name));
@@ -1124,6 +1285,7 @@ impl AppController {
 
         let system = self.system.clone();
         let build_tx = self.build_tx.clone();
+        let boot_states = Arc::clone(&self.kernel_boot_states);
 
         // Get the workspace path from settings for kernelrelease lookup
         // CRITICAL (Chunk 1): RESOLVE WORKSPACE PATH TO ABSOLUTE
@@ -1485,6 +1647,54 @@ fi
             let dkms_soft_failure = format!("({}) || echo \"DKMS_FAILED_MODULES\"", dkms_cmd);
             commands.push(dkms_soft_failure);
 
+            // 3e. Boot entry for the detected boot manager (Soft Failure)
+            // Planned now as the user (contents staged in a temp dir), installed by root in the batch
+            eprintln!("[KERNEL] [UNIFIED] Step 5: Adding boot entry creation to unified batch (soft failure)");
+            let boot_manager = match crate::system::bootloader::BootloaderManager::detect() {
+                Ok(manager) => Some(manager),
+                Err(e) => {
+                    eprintln!("[KERNEL] [BOOT] ⚠ Skipping boot entry: {}", e);
+                    let _ = build_tx.try_send(BuildEvent::Log(format!(
+                        "⚠ Boot entry not created: {}",
+                        e
+                    )));
+                    None
+                }
+            };
+            let boot_entry = boot_manager.as_ref().and_then(|manager| {
+                // Recorded build command line (boot-entry mode) on top of the running one
+                let cmdline = crate::orchestrator::manifest::boot_entry_options_for_package(
+                    &path,
+                    &crate::system::bootloader::current_cmdline().unwrap_or_default(),
+                );
+                let entry = crate::system::bootloader::BootEntry::new(
+                    crate::system::bootloader::KernelImages::for_pkgbase(
+                        &manager.layout.boot,
+                        &kernel_variant,
+                    ),
+                    cmdline,
+                );
+                let staging = std::env::temp_dir().join("goatd-boot-entries");
+                // Trial boot: the new kernel is the default for the next boot only
+                match manager.install_commands(&entry, &staging, manager.kind.supports_oneshot()) {
+                    Ok(entry_cmds) => {
+                        commands.push(format!(
+                            "({}) || echo \"BOOT_ENTRY_FAILED\"",
+                            entry_cmds.join(" && ")
+                        ));
+                        Some(entry)
+                    }
+                    Err(e) => {
+                        eprintln!("[KERNEL] [BOOT] ⚠ Could not plan boot entry: {}", e);
+                        let _ = build_tx.try_send(BuildEvent::Log(format!(
+                            "⚠ Boot entry not created: {}",
+                            e
+                        )));
+                        None
+                    }
+                }
+            });
+
             if commands.is_empty() {
                 eprintln!("[KERNEL] No installation commands generated");
                 let _ = build_tx.try_send(BuildEvent::Error(
@@ -1591,6 +1801,54 @@ fi
                         if dkms_soft_failure_detected { "⚠ FAILED/INCOMPATIBLE (soft)" } else { "✓ SUCCESS" });
                     eprintln!("[KERNEL] [UNIFIED BATCH] ═══════════════════════════════════════════════════");
 
+                    // === BOOT ENTRY VERIFICATION ===
+                    if let (Some(manager), Some(entry)) = (&boot_manager, &boot_entry) {
+                        if stdout.contains("BOOT_ENTRY_FAILED") {
+                            eprintln!("[KERNEL] [BOOT] ⚠ Sentinel found: BOOT_ENTRY_FAILED");
+                            let _ = build_tx.try_send(BuildEvent::Log(format!(
+                                "⚠ Failed to write {} boot entry '{}'",
+                                manager.kind.as_str(),
+                                entry.id
+                            )));
+                        } else {
+                            let msg = match manager.verify_entry(&entry.id) {
+                                Ok(verification) if verification.is_bootable() => {
+                                    match crate::system::boot_tracking::record_install(
+                                        &entry.images.pkgbase,
+                                        Some(entry.id.clone()),
+                                    ) {
+                                        Ok(()) => {
+                                            if let Ok(mut states) = boot_states.write() {
+                                                states.insert(
+                                                    entry.images.pkgbase.clone(),
+                                                    crate::system::boot_tracking::BootStatus::Trial,
+                                                );
+                                            }
+                                        }
+                                        Err(e) => eprintln!("[KERNEL] [BOOT] ⚠ Failed to record trial boot: {}", e),
+                                    }
+                                    format!(
+                                        "✓ {} boot entry '{}' verified; {} is on TRIAL until it boots successfully",
+                                        manager.kind.as_str(),
+                                        entry.id,
+                                        entry.images.pkgbase
+                                    )
+                                }
+                                Ok(verification) => format!(
+                                    "⚠ Boot entry '{}' is NOT bootable: {}",
+                                    entry.id,
+                                    verification.problems.join("; ")
+                                ),
+                                Err(e) => format!(
+                                    "ℹ️ Boot entry '{}' written but could not be verified: {}",
+                                    entry.id, e
+                                ),
+                            };
+                            eprintln!("[KERNEL] [BOOT] {}", msg);
+                            let _ = build_tx.try_send(BuildEvent::Log(msg));
+                        }
+                    }
+
                     // === INSTALLATION RESULT REPORTING ===
                     if dkms_soft_failure_detected {
                         // --- PARTIAL SUCCESS PATH ---
@@ -2096,7 +2354,10 @@ fi
     /// - Clears PerformanceMetrics (current, max, p99, avg, rolling windows)
     /// - Clears PerformanceHistory (snapshots and rolling window buffers)
     /// - Creates fresh LatencyProcessor in background tasks
-    pub fn start_performance_monitoring(&self, config: PerformanceConfig) -> Result<(), String> {
+    pub fn start_performance_monitoring(
+        &self,
+        mut config: PerformanceConfig,
+    ) -> Result<(), String> {
         // Store the UI context if available (will be populated by app.rs when called)
         // For now, leave None - will be set by app.rs via set_perf_ui_context()
         // Check if already monitoring
@@ -2153,6 +2414,13 @@ fi
             *state = Some(monitoring_state.clone());
         }
 
+        // Resolve the CPUs to measure; the primary core keeps feeding the single-core pipeline
+        let topology = crate::hardware::detect_cpu_topology();
+        let cpus = topology.resolve_selection(&config.cpus, config.core_id);
+        if !cpus.contains(&config.core_id) {
+            config.core_id = cpus[0];
+        }
+
         // Capture core_id from config for use in background processor
         let core_id = config.core_id;
 
@@ -2181,43 +2449,65 @@ fi
         // Clone metrics for use in the collector thread
         let metrics_for_thread = self.perf_metrics.clone();
 
-        std::thread::spawn(move || {
-            // CRITICAL: Apply real-time settings before starting measurement loop
-            let mut tuner = crate::system::performance::Tuner::new();
-            let rt_result = tuner.apply_realtime_settings(core_id);
-            match rt_result {
-                Ok(()) => {
-                    eprintln!("[PERF] [THREAD] Done: Real-time settings applied (SCHED_FIFO priority 80, CPU affinity to core {}, mlockall)", core_id);
-                }
-                Err(ref e) => {
-                    eprintln!("[PERF] [THREAD] Warning: Real-time settings failed (non-root?): {}. Proceeding with standard scheduling.", e);
-                    eprintln!("[PERF] [THREAD] Warning: Latency measurements will be less accurate without SCHED_FIFO priority.");
-                }
-            }
-
-            // Update shared metrics state with RT status
-            if let Ok(mut metrics) = metrics_for_thread.write() {
-                metrics.rt_active = rt_result.is_ok();
-                metrics.rt_error = rt_result.err().map(|e| e.to_string());
-            }
-
-            let collector = LatencyCollector::new(
+        if multicore::is_multi_core(&cpus) {
+            eprintln!(
+                "[PERF] [START] Multi-core collection on {} CPUs ({}), primary core {}",
+                cpus.len(),
+                config.cpus.label(),
+                core_id
+            );
+            MultiCoreSession::start(
+                &cpus,
+                &topology,
                 interval,
-                producer,
-                event_producer,
-                stop_flag,
-                dropped_count,
                 spike_threshold,
-                spike_count,
-                smi_spikes_for_collector,
-                total_smi_for_collector,
-                smi_correlation,
+                multicore::PrimaryCollector {
+                    cpu: core_id,
+                    forward: producer,
+                    event_producer,
+                    state: monitoring_state.clone(),
+                    metrics: metrics_for_thread,
+                },
             );
-            eprintln!("[PERF] [THREAD] Starting latency collection loop (interval_us={}, spike_threshold_ns={})", config.interval_us, spike_threshold);
-            eprintln!("[PERF] [THREAD] SMI correlation initialized asynchronously (will be populated when MSR driver is ready)");
-            collector.run();
-            eprintln!("[PERF] [THREAD] Latency collection loop stopped");
-        });
+        } else {
+            std::thread::spawn(move || {
+                // CRITICAL: Apply real-time settings before starting measurement loop
+                let mut tuner = crate::system::performance::Tuner::new();
+                let rt_result = tuner.apply_realtime_settings(core_id);
+                match rt_result {
+                    Ok(()) => {
+                        eprintln!("[PERF] [THREAD] Done: Real-time settings applied (SCHED_FIFO priority 80, CPU affinity to core {}, mlockall)", core_id);
+                    }
+                    Err(ref e) => {
+                        eprintln!("[PERF] [THREAD] Warning: Real-time settings failed (non-root?): {}. Proceeding with standard scheduling.", e);
+                        eprintln!("[PERF] [THREAD] Warning: Latency measurements will be less accurate without SCHED_FIFO priority.");
+                    }
+                }
+
+                // Update shared metrics state with RT status
+                if let Ok(mut metrics) = metrics_for_thread.write() {
+                    metrics.rt_active = rt_result.is_ok();
+                    metrics.rt_error = rt_result.err().map(|e| e.to_string());
+                }
+
+                let collector = LatencyCollector::new(
+                    interval,
+                    producer,
+                    event_producer,
+                    stop_flag,
+                    dropped_count,
+                    spike_threshold,
+                    spike_count,
+                    smi_spikes_for_collector,
+                    total_smi_for_collector,
+                    smi_correlation,
+                );
+                eprintln!("[PERF] [THREAD] Starting latency collection loop (interval_us={}, spike_threshold_ns={})", config.interval_us, spike_threshold);
+                eprintln!("[PERF] [THREAD] SMI correlation initialized asynchronously (will be populated when MSR driver is ready)");
+                collector.run();
+                eprintln!("[PERF] [THREAD] Latency collection loop stopped");
+            });
+        }
 
         // Spawn the event consumer thread for diagnostic event processing
         // Pass smi_correlated_spikes for asynchronous SMI correlation
@@ -2232,6 +2522,15 @@ fi
             // The handle is dropped here, allowing the consumer to run in the background
         });
 
+        // Spawn spike forensics off the hot path: attributes non-SMI spikes to
+        // IRQs, softirqs, idle states or run-queue tasks on the measured core
+        let _forensics_handle = crate::system::performance::forensics::spawn_spike_forensics(
+            core_id,
+            monitoring_state.stop_flag.clone(),
+            monitoring_state.spike_count.clone(),
+            monitoring_state.spike_forensics.clone(),
+        );
+
         // Spawn background task to initialize SMI correlation asynchronously
         // This prevents the native collector thread from hanging on MSR driver access
 
@@ -2505,7 +2804,7 @@ fi
                     }
                 });
             }
-            Some(MonitoringMode::Benchmark(_)) | Some(MonitoringMode::SystemBenchmark) => {
+            Some(MonitoringMode::Benchmark(_)) | Some(MonitoringMode::SystemBenchmark(_)) => {
                 eprintln!("[PERF] [BIFURCATION] Spawning system_monitor_task and benchmark_runner_task for Benchmark/SystemBenchmark mode");
 
                 // Clone for system_monitor_task before moving into spawn (for benchmark path)
@@ -3914,26 +4213,19 @@ fi
         0.0 // Default if unable to read or parse
     }
 
-    /// Compare two performance records and calculate deltas
+    /// Compare two groups of experiment runs metric by metric
     ///
-    /// Returns a tuple of (P99 delta, P99.9 delta, Max delta, improvement/degradation indicator)
+    /// Each side is every repetition of the benchmark on one kernel. The result
+    /// carries medians, 95% confidence intervals and a Mann-Whitney verdict per
+    /// metric, so noise between single runs is reported as "no significant
+    /// difference" instead of an improvement or regression.
     pub fn compare_performance_records(
-        record_a: &PerformanceRecord,
-        record_b: &PerformanceRecord,
-    ) -> (f32, f32, f32, String) {
-        let p99_delta = record_b.metrics.p99_us - record_a.metrics.p99_us;
-        let p99_9_delta = record_b.metrics.p99_9_us - record_a.metrics.p99_9_us;
-        let max_delta = record_b.metrics.max_us - record_a.metrics.max_us;
-
-        let trend = if max_delta < 0.0 {
-            "Improved".to_string()
-        } else if max_delta > 0.0 {
-            "Degraded".to_string()
-        } else {
-            "Unchanged".to_string()
-        };
-
-        (p99_delta, p99_9_delta, max_delta, trend)
+        runs_a: &[PerformanceRecord],
+        runs_b: &[PerformanceRecord],
+    ) -> ExperimentComparison {
+        let metrics_a: Vec<PerformanceMetrics> = runs_a.iter().map(|r| r.metrics.clone()).collect();
+        let metrics_b: Vec<PerformanceMetrics> = runs_b.iter().map(|r| r.metrics.clone()).collect();
+        compare_runs(&metrics_a, &metrics_b)
     }
 
     /// Handler for Quick Jitter Audit (bounded 5-second benchmark session)
@@ -4048,8 +4340,14 @@ fi
             );
         }
 
-        // Start the performance monitoring with default config
-        let config = PerformanceConfig::default();
+        // Start the performance monitoring with the configured CPU selection
+        let config = PerformanceConfig {
+            cpus: self
+                .get_state()
+                .map(|state| state.perf_cpu_selection)
+                .unwrap_or_default(),
+            ..PerformanceConfig::default()
+        };
         self.start_performance_monitoring(config)?;
 
         // Spawn a task to handle auto-termination for Benchmark mode
@@ -4083,10 +4381,10 @@ fi
         //   CPU Impact: Minimal to moderate with optional stressors
         //   Use Case: Quick 5-10 second baseline jitter audits
         //
-        // MonitoringMode::SystemBenchmark
-        //   Purpose: Heavy, comprehensive 60-second benchmark with CPU-intensive collectors
-        //   Behavior: Runs 6-phase orchestrated benchmark with serial CPU-bound collectors
-        //   Collectors: MicroJitter P99.99, ContextSwitch RTT, Syscall Saturation
+        // MonitoringMode::SystemBenchmark(scenario)
+        //   Purpose: Heavy, comprehensive benchmark with CPU-intensive collectors
+        //   Behavior: Runs the scenario's phases (GOATd Full Benchmark: 6 phases, 70s) with serial CPU-bound collectors
+        //   Collectors: chosen by the scenario - MicroJitter P99.99, ContextSwitch RTT, Syscall Saturation, Task Wakeup
         //   UI Impact: UI may briefly freeze during Phase 3 collector execution (intended)
         //   CPU Impact: HEAVY - uses spawn_blocking for CPU-bound benchmarks
         //   Use Case: Full system performance assessment, comprehensive scoring, detailed analysis
@@ -4243,6 +4541,11 @@ fi
                                 final_dropped,
                             );
                             summary.mark_completed(start_instant);
+                            if let Some(state) =
+                                monitoring_state_arc.read().ok().and_then(|s| s.clone())
+                            {
+                                summary.attach_monitoring_state(&state);
+                            }
 
                             eprintln!("[PERF] [LIFECYCLE] ⚠ Session summary created: samples={}, dropped={}, duration={:.2}s, completed={}",
                                 summary.total_samples,
@@ -4261,8 +4564,8 @@ fi
                             }
 
                             // CRITICAL: Emit JitterAuditComplete event to notify UI
-                            let _ =
-                                build_tx.try_send(BuildEvent::JitterAuditComplete(summary.clone()));
+                            let event = BuildEvent::JitterAuditComplete(Box::new(summary.clone()));
+                            let _ = build_tx.try_send(event);
                             eprintln!(
                                 "[PERF] [LIFECYCLE] Done: JitterAuditComplete event emitted to UI"
                             );
@@ -4286,8 +4589,15 @@ fi
                     eprintln!("[PERF] [LIFECYCLE] Done: Benchmark session finalized");
                 });
             }
-            MonitoringMode::SystemBenchmark => {
-                eprintln!("[PERF] [LIFECYCLE] SystemBenchmark mode: 60-second GOATd Full Benchmark with 6 phases");
+            MonitoringMode::SystemBenchmark(ref scenario) => {
+                eprintln!(
+                    "[PERF] [LIFECYCLE] SystemBenchmark mode: {} ({}s, {} phases)",
+                    scenario.name,
+                    scenario.total_duration_secs(),
+                    scenario.phases.len()
+                );
+                let scenario = scenario.clone();
+                let collectors = scenario.collectors.clone();
 
                 let benchmark_orch = self.benchmark_orchestrator.clone();
                 let lifecycle_state = self.perf_lifecycle_state.clone();
@@ -4297,147 +4607,192 @@ fi
                 let benchmark_metrics_container = self.benchmark_metrics_container.clone();
 
                 tokio::spawn(async move {
-                    eprintln!("[PERF] [BENCHMARK] Starting GOATd Full Benchmark orchestration");
+                    eprintln!(
+                        "[PERF] [BENCHMARK] Starting benchmark orchestration: {}",
+                        scenario.name
+                    );
 
                     // Initialize orchestrator
                     {
                         if let Ok(mut orch) = benchmark_orch.write() {
-                            *orch = Some(BenchmarkOrchestrator::new());
+                            *orch = Some(BenchmarkOrchestrator::with_scenario(scenario));
                         }
                     }
 
                     // Spawn specialized collectors as looping blocking tasks with result wiring
-                    eprintln!("[PERF] [BENCHMARK] Spawning specialized collectors in LOOP mode (MicroJitter, ContextSwitch, Syscall)");
+                    eprintln!(
+                        "[PERF] [BENCHMARK] Spawning specialized collectors in LOOP mode: {:?}",
+                        collectors
+                    );
 
                     // Micro-Jitter Collector - runs in loop with 3-second intervals
-                    let jitter_metrics_arc = benchmark_metrics_container.clone();
-                    let active_for_jitter = active.clone();
-                    tokio::task::spawn_blocking(move || {
-                        loop {
-                            if !active_for_jitter.load(Ordering::Acquire) {
-                                eprintln!("[PERF] [BENCHMARK] MicroJitter collector stopping (active_flag is false)");
-                                break;
-                            }
+                    if collectors.micro_jitter {
+                        let jitter_metrics_arc = benchmark_metrics_container.clone();
+                        let active_for_jitter = active.clone();
+                        tokio::task::spawn_blocking(move || {
+                            loop {
+                                if !active_for_jitter.load(Ordering::Acquire) {
+                                    eprintln!("[PERF] [BENCHMARK] MicroJitter collector stopping (active_flag is false)");
+                                    break;
+                                }
 
-                            let jitter_collector =
-                                MicroJitterCollector::new(MicroJitterConfig::default());
-                            match jitter_collector.run() {
-                                Ok(jitter_metrics) => {
-                                    eprintln!("[PERF] [BENCHMARK] ✓ MicroJitter measurement: P99.99={:.2}µs, Max={:.2}µs, Spikes={}",
-                                        jitter_metrics.p99_99_us, jitter_metrics.max_us, jitter_metrics.spike_count);
+                                let jitter_collector =
+                                    MicroJitterCollector::new(MicroJitterConfig::default());
+                                match jitter_collector.run() {
+                                    Ok(jitter_metrics) => {
+                                        eprintln!("[PERF] [BENCHMARK] ✓ MicroJitter measurement: P99.99={:.2}µs, Max={:.2}µs, Spikes={}",
+                                            jitter_metrics.p99_99_us, jitter_metrics.max_us, jitter_metrics.spike_count);
 
-                                    // WIRING: Pipe results into BenchmarkMetrics
-                                    if let Ok(mut benchmark) = jitter_metrics_arc.write() {
-                                        benchmark.micro_jitter = Some(jitter_metrics.clone());
+                                        // WIRING: Pipe results into BenchmarkMetrics
+                                        if let Ok(mut benchmark) = jitter_metrics_arc.write() {
+                                            benchmark.micro_jitter = Some(jitter_metrics.clone());
+                                        }
+                                    }
+                                    Err(e) => {
+                                        eprintln!(
+                                            "[PERF] [BENCHMARK] ✗ MicroJitter collector error: {}",
+                                            e
+                                        );
                                     }
                                 }
-                                Err(e) => {
-                                    eprintln!(
-                                        "[PERF] [BENCHMARK] ✗ MicroJitter collector error: {}",
-                                        e
-                                    );
-                                }
-                            }
 
-                            // Sleep 3 seconds before next measurement
-                            eprintln!("[PERF] [BENCHMARK] MicroJitter collector sleeping 3 seconds before next measurement");
-                            std::thread::sleep(Duration::from_secs(3));
-                        }
-                    });
+                                // Sleep 3 seconds before next measurement
+                                eprintln!("[PERF] [BENCHMARK] MicroJitter collector sleeping 3 seconds before next measurement");
+                                std::thread::sleep(Duration::from_secs(3));
+                            }
+                        });
+                    }
 
                     // Context-Switch RTT Collector - runs in loop with 1-second intervals
-                    // LABORATORY-GRADE: Uses Median RTT (representative) instead of P99 (biased by outliers)
-                    let cs_metrics_arc = benchmark_metrics_container.clone();
-                    let history_for_cs = history.clone();
-                    let active_for_cs = active.clone();
-                    tokio::task::spawn_blocking(move || {
-                        loop {
-                            if !active_for_cs.load(Ordering::Acquire) {
-                                eprintln!("[PERF] [BENCHMARK] ContextSwitch collector stopping (active_flag is false)");
-                                break;
-                            }
+                    if collectors.context_switch {
+                        // LABORATORY-GRADE: Uses Median RTT (representative) instead of P99 (biased by outliers)
+                        let cs_metrics_arc = benchmark_metrics_container.clone();
+                        let history_for_cs = history.clone();
+                        let active_for_cs = active.clone();
+                        tokio::task::spawn_blocking(move || {
+                            loop {
+                                if !active_for_cs.load(Ordering::Acquire) {
+                                    eprintln!("[PERF] [BENCHMARK] ContextSwitch collector stopping (active_flag is false)");
+                                    break;
+                                }
 
-                            let cs_collector =
-                                ContextSwitchCollector::new(ContextSwitchConfig::default());
-                            match cs_collector.run() {
-                                Ok(cs_summary) => {
-                                    eprintln!("[PERF] [BENCHMARK] ✓ ContextSwitch measurement: Mean={:.3}µs, Median={:.3}µs, P95={:.3}µs",
-                                        cs_summary.mean, cs_summary.median, cs_summary.p95);
-
-                                    // CRITICAL: Pipe MEDIAN RTT to rolling window for real-time efficiency updates
-                                    // Median is representative (50th percentile) and avoids P99 bias
-                                    if let Ok(mut h) = history_for_cs.write() {
-                                        h.rolling_window.add_efficiency(cs_summary.median);
-                                        eprintln!("[PERF] [BENCHMARK] ✓ Efficiency Median wired to rolling window: {:.3}µs (HIGH-PRECISION)", cs_summary.median);
-                                    }
+                                let cs_collector =
+                                    ContextSwitchCollector::new(ContextSwitchConfig::default());
+                                match cs_collector.run() {
+                                    Ok(cs_summary) => {
+                                        eprintln!("[PERF] [BENCHMARK] ✓ ContextSwitch measurement: Mean={:.3}µs, Median={:.3}µs, P95={:.3}µs",
+                                            cs_summary.mean, cs_summary.median, cs_summary.p95);
+
+                                        // CRITICAL: Pipe MEDIAN RTT to rolling window for real-time efficiency updates
+                                        // Median is representative (50th percentile) and avoids P99 bias
+                                        if let Ok(mut h) = history_for_cs.write() {
+                                            h.rolling_window.add_efficiency(cs_summary.median);
+                                            eprintln!("[PERF] [BENCHMARK] ✓ Efficiency Median wired to rolling window: {:.3}µs (HIGH-PRECISION)", cs_summary.median);
+                                        }
 
-                                    // WIRING: Pipe results into BenchmarkMetrics (convert Summary to Metrics)
-                                    if let Ok(mut benchmark) = cs_metrics_arc.write() {
-                                        let metrics = cs_summary.clone().into();
-                                        benchmark.context_switch_rtt = Some(metrics);
+                                        // WIRING: Pipe results into BenchmarkMetrics (convert Summary to Metrics)
+                                        if let Ok(mut benchmark) = cs_metrics_arc.write() {
+                                            let metrics = cs_summary.clone().into();
+                                            benchmark.context_switch_rtt = Some(metrics);
+                                        }
+                                    }
+                                    Err(e) => {
+                                        eprintln!(
+                                            "[PERF] [BENCHMARK] ✗ ContextSwitch collector error: {}",
+                                            e
+                                        );
                                     }
                                 }
-                                Err(e) => {
-                                    eprintln!(
-                                        "[PERF] [BENCHMARK] ✗ ContextSwitch collector error: {}",
-                                        e
-                                    );
-                                }
-                            }
 
-                            // Sleep 1 second before next measurement (faster fill of 20-sample rolling window)
-                            eprintln!("[PERF] [BENCHMARK] ContextSwitch collector sleeping 1 second before next measurement");
-                            std::thread::sleep(Duration::from_secs(1));
-                        }
-                    });
+                                // Sleep 1 second before next measurement (faster fill of 20-sample rolling window)
+                                eprintln!("[PERF] [BENCHMARK] ContextSwitch collector sleeping 1 second before next measurement");
+                                std::thread::sleep(Duration::from_secs(1));
+                            }
+                        });
+                    }
 
                     // Syscall Saturation Collector - runs in loop with 3-second intervals
-                    let syscall_metrics_arc = benchmark_metrics_container.clone();
-                    let history_for_syscall = history.clone();
-                    let active_for_syscall = active.clone();
-                    tokio::task::spawn_blocking(move || {
-                        loop {
-                            if !active_for_syscall.load(Ordering::Acquire) {
-                                eprintln!("[PERF] [BENCHMARK] Syscall collector stopping (active_flag is false)");
-                                break;
-                            }
+                    if collectors.syscall {
+                        let syscall_metrics_arc = benchmark_metrics_container.clone();
+                        let history_for_syscall = history.clone();
+                        let active_for_syscall = active.clone();
+                        tokio::task::spawn_blocking(move || {
+                            loop {
+                                if !active_for_syscall.load(Ordering::Acquire) {
+                                    eprintln!("[PERF] [BENCHMARK] Syscall collector stopping (active_flag is false)");
+                                    break;
+                                }
+
+                                let syscall_collector = SyscallSaturationCollector::new(
+                                    SyscallSaturationConfig::default(),
+                                );
+                                match syscall_collector.run() {
+                                    Ok(syscall_metrics) => {
+                                        eprintln!("[PERF] [BENCHMARK] ✓ Syscall measurement: Avg={:.2}ns/call, Throughput={:.0}k/sec",
+                                            syscall_metrics.avg_ns_per_call, syscall_metrics.calls_per_second as f32 / 1000.0);
+
+                                        // CRITICAL: Pipe throughput to rolling window for real-time history
+                                        if let Ok(mut h) = history_for_syscall.write() {
+                                            h.rolling_window.add_throughput(
+                                                syscall_metrics.calls_per_second as f32,
+                                            );
+                                            eprintln!("[PERF] [BENCHMARK] ✓ Throughput wired to rolling window: {:.0}k/sec", syscall_metrics.calls_per_second as f32 / 1000.0);
+                                        }
 
-                            let syscall_collector =
-                                SyscallSaturationCollector::new(SyscallSaturationConfig::default());
-                            match syscall_collector.run() {
-                                Ok(syscall_metrics) => {
-                                    eprintln!("[PERF] [BENCHMARK] ✓ Syscall measurement: Avg={:.2}ns/call, Throughput={:.0}k/sec",
-                                        syscall_metrics.avg_ns_per_call, syscall_metrics.calls_per_second as f32 / 1000.0);
-
-                                    // CRITICAL: Pipe throughput to rolling window for real-time history
-                                    if let Ok(mut h) = history_for_syscall.write() {
-                                        h.rolling_window.add_throughput(
-                                            syscall_metrics.calls_per_second as f32,
+                                        // WIRING: Pipe results into BenchmarkMetrics
+                                        if let Ok(mut benchmark) = syscall_metrics_arc.write() {
+                                            benchmark.syscall_saturation =
+                                                Some(syscall_metrics.clone());
+                                        }
+                                    }
+                                    Err(e) => {
+                                        eprintln!(
+                                            "[PERF] [BENCHMARK] ✗ Syscall collector error: {}",
+                                            e
                                         );
-                                        eprintln!("[PERF] [BENCHMARK] ✓ Throughput wired to rolling window: {:.0}k/sec", syscall_metrics.calls_per_second as f32 / 1000.0);
                                     }
+                                }
+
+                                // Sleep 3 seconds before next measurement
+                                eprintln!("[PERF] [BENCHMARK] Syscall collector sleeping 3 seconds before next measurement");
+                                std::thread::sleep(Duration::from_secs(3));
+                            }
+                        });
+                    }
+
+                    // Task Wakeup Collector - runs in loop with 3-second intervals
+                    if collectors.task_wakeup {
+                        let wakeup_metrics_arc = benchmark_metrics_container.clone();
+                        let active_for_wakeup = active.clone();
+                        tokio::task::spawn_blocking(move || loop {
+                            if !active_for_wakeup.load(Ordering::Acquire) {
+                                eprintln!("[PERF] [BENCHMARK] TaskWakeup collector stopping (active_flag is false)");
+                                break;
+                            }
 
-                                    // WIRING: Pipe results into BenchmarkMetrics
-                                    if let Ok(mut benchmark) = syscall_metrics_arc.write() {
-                                        benchmark.syscall_saturation =
-                                            Some(syscall_metrics.clone());
+                            match TaskWakeupCollector::new(TaskWakeupConfig::default()).run() {
+                                Ok(wakeup_metrics) => {
+                                    eprintln!(
+                                        "[PERF] [BENCHMARK] ✓ TaskWakeup measurement: Avg={:.2}µs, P99={:.2}µs",
+                                        wakeup_metrics.avg_latency_us, wakeup_metrics.p99_latency_us
+                                    );
+                                    if let Ok(mut benchmark) = wakeup_metrics_arc.write() {
+                                        benchmark.task_wakeup = Some(wakeup_metrics);
                                     }
                                 }
                                 Err(e) => {
                                     eprintln!(
-                                        "[PERF] [BENCHMARK] ✗ Syscall collector error: {}",
+                                        "[PERF] [BENCHMARK] ✗ TaskWakeup collector error: {}",
                                         e
                                     );
                                 }
                             }
 
-                            // Sleep 3 seconds before next measurement
-                            eprintln!("[PERF] [BENCHMARK] Syscall collector sleeping 3 seconds before next measurement");
                             std::thread::sleep(Duration::from_secs(3));
-                        }
-                    });
+                        });
+                    }
 
-                    // Run the 6-phase benchmark sequence
+                    // Run the scenario's phase sequence
                     let mut phase_tick = tokio::time::interval(Duration::from_millis(100));
                     let mut last_phase_transition_elapsed = 0u64; // Track last transition time
 
@@ -4466,8 +4821,9 @@ fi
                             if let Ok(mut orch) = benchmark_orch.write() {
                                 if let Some(ref mut orchestrator) = *orch {
                                     let elapsed = orchestrator.elapsed_secs();
-                                    let current_phase = orchestrator.current_phase;
-                                    let phase_end = current_phase.end_time();
+                                    let current_phase =
+                                        orchestrator.current_phase_name().to_string();
+                                    let phase_end = orchestrator.current_phase_end();
 
                                     // Explicit phase transition: check if we've crossed phase boundary
                                     eprintln!("[PERF] [BENCHMARK] [PHASE_CHECK] elapsed={}s, phase_end={}s, last_transition={}s", elapsed, phase_end, last_phase_transition_elapsed);
@@ -4476,7 +4832,7 @@ fi
                                     {
                                         if let Some(next_phase) = orchestrator.advance_phase() {
                                             last_phase_transition_elapsed = elapsed;
-                                            eprintln!("[PERF] [BENCHMARK] ✓ Phase transition at {}s: {} -> {}", elapsed, current_phase, next_phase);
+                                            eprintln!("[PERF] [BENCHMARK] ✓ Phase transition at {}s: {} -> {} ({}/{})", elapsed, current_phase, orchestrator.current_phase_name(), next_phase + 1, orchestrator.phase_count());
                                             should_advance = true;
                                             new_stressors = orchestrator.get_phase_stressors();
 
@@ -4488,11 +4844,14 @@ fi
                                         }
                                     }
 
+                                    // Attribute energy to the phase running now
+                                    orchestrator.update_energy();
+
                                     // Check if benchmark is complete
                                     let is_done = orchestrator.is_complete();
                                     eprintln!("[PERF] [BENCHMARK] [COMPLETION_CHECK] elapsed={}s, is_complete={}", elapsed, is_done);
                                     if is_done {
-                                        eprintln!("[PERF] [BENCHMARK] ✅ GOATd Full Benchmark complete (60s elapsed) - BREAKING LOOP");
+                                        eprintln!("[PERF] [BENCHMARK] ✅ Benchmark scenario complete ({}s elapsed) - BREAKING LOOP", elapsed);
                                         break;
                                     }
                                 }
@@ -4510,7 +4869,7 @@ fi
                     }
 
                     // Auto-stop the benchmark - CRITICAL: This signals both loop AND processor
-                    eprintln!("[PERF] [BENCHMARK] ✅ INITIATING BENCHMARK AUTO-STOP at 60s");
+                    eprintln!("[PERF] [BENCHMARK] ✅ INITIATING BENCHMARK AUTO-STOP");
                     eprintln!("[PERF] [BENCHMARK] Setting active=false to trigger loop exit");
                     active.store(false, Ordering::Release);
 
@@ -4550,6 +4909,26 @@ fi
                         }
                     }
 
+                    // Close the energy measurement of the last phase and publish it
+                    if let Ok(mut orch_lock) = benchmark_orch.write() {
+                        if let Some(ref mut orchestrator) = *orch_lock {
+                            orchestrator.finish_energy();
+                            if let Some(power) = orchestrator.power_metrics() {
+                                eprintln!(
+                                    "[PERF] [BENCHMARK] Energy: {:.1} J over {:.1}s ({:.1} W average)",
+                                    power.total_joules, power.total_secs, power.avg_watts
+                                );
+                                if let Some(state) =
+                                    monitoring_state_arc.read().ok().and_then(|s| s.clone())
+                                {
+                                    if let Ok(mut shared) = state.power.write() {
+                                        *shared = Some(power);
+                                    }
+                                }
+                            }
+                        }
+                    }
+
                     // Finalize results - calculate final GOAT Score from aggregated metrics
                     let mut final_goat_score = 0u16;
                     if let Ok(orch_lock) = benchmark_orch.read() {
@@ -4661,6 +5040,11 @@ fi
                                 final_dropped,
                             );
                             summary.mark_completed(start_instant);
+                            if let Some(state) =
+                                monitoring_state_arc.read().ok().and_then(|s| s.clone())
+                            {
+                                summary.attach_monitoring_state(&state);
+                            }
 
                             if let Ok(mut ss) = session_summary.write() {
                                 *ss = Some(summary.clone());
@@ -4670,8 +5054,8 @@ fi
                                 *cached = Some(summary.clone());
                             }
 
-                            let _ =
-                                build_tx.try_send(BuildEvent::JitterAuditComplete(summary.clone()));
+                            let event = BuildEvent::JitterAuditComplete(Box::new(summary.clone()));
+                            let _ = build_tx.try_send(event);
 
                             if let Ok(mut h) = history.write() {
                                 let snapshot = crate::system::performance::PerformanceSnapshot::new(
@@ -4836,6 +5220,14 @@ fi
                             final_dropped,
                         );
                         summary.mark_completed(start_instant);
+                        if let Some(state) = self
+                            .perf_monitoring_state
+                            .read()
+                            .ok()
+                            .and_then(|s| s.clone())
+                        {
+                            summary.attach_monitoring_state(&state);
+                        }
 
                         eprintln!("[PERF] [STOP] ⚠ Session summary created: samples={}, dropped={}, duration={:.2}s, metrics.max={:.2}µs, metrics.p99.9={:.2}µs",
                             summary.total_samples,
@@ -4971,6 +5363,14 @@ fi
                 final_dropped,
             );
             summary.mark_completed(start_time);
+            if let Some(state) = self
+                .perf_monitoring_state
+                .read()
+                .ok()
+                .and_then(|s| s.clone())
+            {
+                summary.attach_monitoring_state(&state);
+            }
 
             eprintln!("[PERF] [FINALIZE] Session summary created: samples={}, dropped={}, duration={:.2}s",
                 summary.total_samples,
@@ -5243,19 +5643,17 @@ fi
 
     /// Handle compare tests popup: load and compare two performance records
     ///
-    /// Loads both records from HistoryManager using their IDs, calculates % deltas
-    /// for all 6 core metrics:
-    /// - Min Latency: Delta = (B_min - A_min) / A_min * 100
-    /// - Max Latency: Delta = (B_max - A_max) / A_max * 100
-    /// - Avg Latency: Delta = (B_avg - A_avg) / A_avg * 100
-    /// - P99.9 Latency: Delta = (B_p99.9 - A_p99.9) / A_p99.9 * 100
-    /// - SMI Count: Delta = (B_smi - A_smi) / A_smi * 100
-    /// - Stall Count: Delta = (B_stall - A_stall) / A_stall * 100
+    /// Each record is expanded to its experiment group (all runs with the same
+    /// experiment name and kernel context; a plain record is a group of one)
+    /// and the groups are compared with [`Self::compare_performance_records`].
+    /// The returned values are the per-group medians of the 6 core metrics:
+    /// - Min, Max, Avg, P99.9 Latency, SMI Count, Stall Count
+    /// - Delta = (median_B - median_A) / median_A * 100
     ///
-    /// Color Logic:
-    /// - Negative delta (lower value in Test B) = Green (improvement)
-    /// - Positive delta (higher value in Test B) = Red (regression)
-    /// - Zero delta = gray (neutral)
+    /// Color Logic (applied by the UI from the verdicts):
+    /// - Significantly lower in Test B = Green (improvement)
+    /// - Significantly higher in Test B = Red (regression)
+    /// - No significant difference or too few runs = gray (neutral)
     pub fn handle_compare_tests_request(
         &self,
         test_a_id: &str,
@@ -5268,6 +5666,8 @@ fi
             (String, String, String, f32, f32, f32, f32, i32, i32),
             // Deltas: (min_delta%, max_delta%, avg_delta%, p99.9_delta%, smi_delta%, stall_delta%)
             (f32, f32, f32, f32, f32, f32),
+            // Per-metric statistics and verdicts
+            ExperimentComparison,
         ),
         String,
     > {
@@ -5281,112 +5681,77 @@ fi
             .as_ref()
             .ok_or_else(|| "HistoryManager not initialized".to_string())?;
 
-        // Fetch both records
-        let record_a = mgr
-            .load_record(test_a_id)
+        // Fetch both experiment groups
+        let runs_a = mgr
+            .load_experiment_group(test_a_id)
             .map_err(|e| format!("Failed to load test A ({}): {}", test_a_id, e))?;
 
-        let record_b = mgr
-            .load_record(test_b_id)
+        let runs_b = mgr
+            .load_experiment_group(test_b_id)
             .map_err(|e| format!("Failed to load test B ({}): {}", test_b_id, e))?;
 
-        log::debug!("[PERF] [COMPARE] Comparing {} vs {}", test_a_id, test_b_id);
-
-        // Extract values from Test A
-        let a_kernel = record_a.kernel_context.version.clone();
-        let a_scx = record_a.kernel_context.scx_profile.clone();
-        let a_lto = record_a.kernel_context.lto_config.clone();
-        let a_min = record_a.metrics.current_us; // Note: using min from metrics storage
-        let a_max = record_a.metrics.max_us;
-        let a_avg = record_a.metrics.avg_us;
-        let a_p99_9 = record_a.metrics.p99_9_us;
-        let a_smi_count = record_a.metrics.total_smis as i32;
-        let a_stall_count = record_a.metrics.spikes_correlated_to_smi as i32;
-
-        // Extract values from Test B
-        let b_kernel = record_b.kernel_context.version.clone();
-        let b_scx = record_b.kernel_context.scx_profile.clone();
-        let b_lto = record_b.kernel_context.lto_config.clone();
-        let b_min = record_b.metrics.current_us;
-        let b_max = record_b.metrics.max_us;
-        let b_avg = record_b.metrics.avg_us;
-        let b_p99_9 = record_b.metrics.p99_9_us;
-        let b_smi_count = record_b.metrics.total_smis as i32;
-        let b_stall_count = record_b.metrics.spikes_correlated_to_smi as i32;
-
-        // Calculate % deltas: (ValB - ValA) / ValA * 100.0
-        // Avoid division by zero
-        let delta_min = if a_min != 0.0 {
-            (b_min - a_min) / a_min * 100.0
-        } else {
-            0.0
-        };
-
-        let delta_max = if a_max != 0.0 {
-            (b_max - a_max) / a_max * 100.0
-        } else {
-            0.0
-        };
+        log::debug!(
+            "[PERF] [COMPARE] Comparing {} ({} runs) vs {} ({} runs)",
+            test_a_id,
+            runs_a.len(),
+            test_b_id,
+            runs_b.len()
+        );
 
-        let delta_avg = if a_avg != 0.0 {
-            (b_avg - a_avg) / a_avg * 100.0
-        } else {
-            0.0
-        };
+        let comparison = Self::compare_performance_records(&runs_a, &runs_b);
 
-        let delta_p99_9 = if a_p99_9 != 0.0 {
-            (b_p99_9 - a_p99_9) / a_p99_9 * 100.0
-        } else {
-            0.0
+        let values = |runs: &[PerformanceRecord], side_a: bool| {
+            let median = |metric: ComparedMetric| {
+                comparison
+                    .metric(metric)
+                    .map(|m| if side_a { m.median_a } else { m.median_b })
+                    .unwrap_or(0.0)
+            };
+            let context = &runs[0].kernel_context;
+            (
+                context.version.clone(),
+                context.scx_profile.clone(),
+                context.lto_config.clone(),
+                // Note: using min from metrics storage
+                median(ComparedMetric::MinLatency) as f32,
+                median(ComparedMetric::MaxLatency) as f32,
+                median(ComparedMetric::AvgLatency) as f32,
+                median(ComparedMetric::P999Latency) as f32,
+                median(ComparedMetric::SmiCount).round() as i32,
+                median(ComparedMetric::StallCount).round() as i32,
+            )
         };
-
-        let delta_smi = if a_smi_count != 0 {
-            ((b_smi_count - a_smi_count) as f32 / a_smi_count as f32) * 100.0
-        } else {
-            0.0
+        let test_a = values(&runs_a, true);
+        let test_b = values(&runs_b, false);
+
+        let delta = |metric: ComparedMetric| {
+            comparison
+                .metric(metric)
+                .map(|m| m.delta_percent as f32)
+                .unwrap_or(0.0)
         };
-
-        let delta_stall = if a_stall_count != 0 {
-            ((b_stall_count - a_stall_count) as f32 / a_stall_count as f32) * 100.0
-        } else {
-            0.0
-        };
-
-        log::debug!("[PERF] [COMPARE] Deltas - Min: {:.1}%, Max: {:.1}%, Avg: {:.1}%, P99.9: {:.1}%, SMI: {:.1}%, Stall: {:.1}%",
-            delta_min, delta_max, delta_avg, delta_p99_9, delta_smi, delta_stall);
-
-        let test_a = (
-            a_kernel,
-            a_scx,
-            a_lto,
-            a_min,
-            a_max,
-            a_avg,
-            a_p99_9,
-            a_smi_count,
-            a_stall_count,
-        );
-        let test_b = (
-            b_kernel,
-            b_scx,
-            b_lto,
-            b_min,
-            b_max,
-            b_avg,
-            b_p99_9,
-            b_smi_count,
-            b_stall_count,
-        );
         let deltas = (
-            delta_min,
-            delta_max,
-            delta_avg,
-            delta_p99_9,
-            delta_smi,
-            delta_stall,
+            delta(ComparedMetric::MinLatency),
+            delta(ComparedMetric::MaxLatency),
+            delta(ComparedMetric::AvgLatency),
+            delta(ComparedMetric::P999Latency),
+            delta(ComparedMetric::SmiCount),
+            delta(ComparedMetric::StallCount),
         );
 
-        Ok((test_a, test_b, deltas))
+        for m in &comparison.metrics {
+            log::debug!(
+                "[PERF] [COMPARE] {}: {:.2} vs {:.2} ({:+.1}%), p={:?} -> {}",
+                m.metric.label(),
+                m.median_a,
+                m.median_b,
+                m.delta_percent,
+                m.p_value,
+                m.verdict.label()
+            );
+        }
+
+        Ok((test_a, test_b, deltas, comparison))
     }
 
     /// Save current performance record to persistent history with custom label
@@ -5414,6 +5779,14 @@ fi
         // Set the custom label provided by the user
         summary.label = Some(label.to_string());
         summary.completed_successfully = true;
+        if let Some(state) = self
+            .perf_monitoring_state
+            .read()
+            .ok()
+            .and_then(|s| s.clone())
+        {
+            summary.attach_monitoring_state(&state);
+        }
 
         // Persist via HistoryManager (which now preserves the label)
         if let Ok(mgr_lock) = self.perf_history_manager.read() {
@@ -5447,6 +5820,66 @@ fi
         Err("HistoryManager not initialized".to_string())
     }
 
+    /// Persist the last finished benchmark session as one experiment repetition
+    ///
+    /// Takes the cached session summary (written when a Benchmark session
+    /// auto-stops), labels it "<experiment> #<repetition>" and stores it tagged
+    /// with the experiment name so the runs can be compared as a group.
+    pub fn handle_save_experiment_run(
+        &self,
+        experiment: &str,
+        repetition: usize,
+    ) -> Result<String, String> {
+        let mut summary = self
+            .perf_session_summary
+            .write()
+            .map_err(|e| format!("Failed to read session summary: {}", e))?
+            .take()
+            .ok_or_else(|| "No finished benchmark session to save".to_string())?;
+        summary.label = Some(format!("{} #{}", experiment, repetition));
+
+        let histogram_buckets = summary
+            .final_metrics
+            .histogram_buckets
+            .iter()
+            .enumerate()
+            .map(|(i, normalized_density)| {
+                let lower_us = (i as f32) * 0.5;
+                HistogramBucket {
+                    lower_us,
+                    upper_us: lower_us + 0.5,
+                    count: (*normalized_density * 1000.0) as u64,
+                }
+            })
+            .collect();
+
+        let mgr_lock = self
+            .perf_history_manager
+            .read()
+            .map_err(|e| format!("Failed to read HistoryManager: {}", e))?;
+        let mgr = mgr_lock
+            .as_ref()
+            .ok_or_else(|| "HistoryManager not initialized".to_string())?;
+
+        let record_id = mgr
+            .save_experiment_run(summary, histogram_buckets, experiment)
+            .map_err(|e| format!("Failed to save experiment run: {}", e))?;
+        self.log_event(
+            "PERFORMANCE",
+            &format!(
+                "Experiment '{}' run #{} saved: {}",
+                experiment, repetition, record_id
+            ),
+        );
+        log_info!(
+            "[PERF] [EXPERIMENT] Run #{} of '{}' persisted: {}",
+            repetition,
+            experiment,
+            record_id
+        );
+        Ok(record_id)
+    }
+
     /// Delete a performance test record from persistent storage
     ///
     /// Takes a test ID (filename from HistoryManager) and deletes the corresponding record.
@@ -5523,6 +5956,16 @@ fi
         (is_active, lifecycle)
     }
 
+    /// Get per-CPU latency of the current (or last) multi-core session
+    ///
+    /// Returns None for single-core sessions.
+    pub fn get_per_cpu_metrics(&self) -> Option<crate::system::performance::MultiCoreMetrics> {
+        self.perf_monitoring_state
+            .read()
+            .ok()
+            .and_then(|state| state.as_ref().and_then(|s| s.per_cpu_metrics()))
+    }
+
     // ========================================================================
     // HARDWARE DETECTION FOR UI DISPLAY
     // ========================================================================
@@ -5614,6 +6057,99 @@ fi
             .map_err(|e| format!("Failed to update selected audit cache: {}", e))
     }
 
+    /// Diff a built artifact's Kconfig against the running kernel (background task)
+    ///
+    /// The result lands in `artifact_config_diff` and the UI dirty flag is raised
+    /// so the Kernel Manager picks it up on the next frame.
+    pub fn compare_artifact_config_async(&self, pkg: crate::kernel::manager::KernelPackage) {
+        self.log_event(
+            "KERNEL",
+            &format!("Diffing Kconfig of {} against the running kernel", pkg.display_name()),
+        );
+
+        if let Ok(mut slot) = self.artifact_config_diff.write() {
+            *slot = None;
+        }
+
+        let slot = Arc::clone(&self.artifact_config_diff);
+        let dirty = Arc::clone(&self.atomic_ui_dirty);
+        tokio::task::spawn_blocking(move || {
+            let result = crate::kernel::config_diff::diff_artifact_against_running(&pkg)
+                .map_err(|e| e.user_message());
+            match &result {
+                Ok(diff) => log_info!(
+                    "[KernelManager] [CONFIG_DIFF] {} vs running kernel: {}",
+                    pkg.display_name(),
+                    diff.summary()
+                ),
+                Err(e) => log_info!("[KernelManager] [CONFIG_DIFF] Failed: {}", e),
+            }
+            if let Ok(mut slot) = slot.write() {
+                *slot = Some(result);
+            }
+            dirty.store(true, Ordering::Release);
+        });
+    }
+
+    /// Get the last artifact Kconfig diff (`None` while it is being computed)
+    pub fn get_artifact_config_diff(
+        &self,
+    ) -> Option<Result<crate::kernel::config_diff::ConfigDiff, String>> {
+        self.artifact_config_diff
+            .read()
+            .ok()
+            .and_then(|data| data.clone())
+    }
+
+    /// Run the post-boot check for trial kernels (background task)
+    ///
+    /// Promotes a trial kernel that booted healthy to the default entry, or
+    /// marks it failed and restores the last known-good kernel as default.
+    pub fn check_boot_success_async(&self) {
+        let system = self.system.clone();
+        let states = Arc::clone(&self.kernel_boot_states);
+        let dirty = Arc::clone(&self.atomic_ui_dirty);
+        let build_tx = self.build_tx.clone();
+        tokio::task::spawn_blocking(move || {
+            use crate::system::boot_tracking::{self, check_current_boot, BootTracker};
+
+            let path = match boot_tracking::default_state_path() {
+                Ok(path) => path,
+                Err(e) => {
+                    log_info!("[KernelManager] [BOOT_CHECK] Skipped: {}", e);
+                    return;
+                }
+            };
+            match check_current_boot(&path, system.as_ref()) {
+                Ok(Some(outcome)) => {
+                    for msg in outcome.messages() {
+                        log_info!("[KernelManager] [BOOT_CHECK] {}", msg);
+                        let _ = build_tx.try_send(BuildEvent::Log(format!("[BOOT] {}", msg)));
+                    }
+                }
+                Ok(None) => {}
+                Err(e) => log_info!("[KernelManager] [BOOT_CHECK] Post-boot check failed: {}", e),
+            }
+
+            if let Ok(tracker) = BootTracker::load(&path) {
+                if let Ok(mut slot) = states.write() {
+                    *slot = tracker.statuses();
+                }
+                dirty.store(true, Ordering::Release);
+            }
+        });
+    }
+
+    /// Get the boot-success state of tracked kernels, keyed by package base
+    pub fn get_kernel_boot_states(
+        &self,
+    ) -> HashMap<String, crate::system::boot_tracking::BootStatus> {
+        self.kernel_boot_states
+            .read()
+            .map(|states| states.clone())
+            .unwrap_or_default()
+    }
+
     /// Get cached jitter audit summary
     pub fn get_cached_jitter_summary(&self) -> Result<Option<SessionSummary>, String> {
         self.cached_jitter_summary
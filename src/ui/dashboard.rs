@@ -725,6 +725,16 @@ fn render_audit_results_two_column(
                                                 ui.monospace(summary.total_samples.to_string());
                                                 ui.end_row();
 
+                                                ui.label("Top Spike Source:");
+                                                ui.monospace(format_top_offender(&summary));
+                                                ui.end_row();
+
+                                                if let Some(ref power) = summary.power {
+                                                    ui.label("Energy:");
+                                                    ui.monospace(format_energy(power));
+                                                    ui.end_row();
+                                                }
+
                                                 ui.label("Duration:");
                                                 ui.monospace(format!(
                                                     "{:.2}s",
@@ -871,6 +881,16 @@ fn render_audit_results_two_column(
                                                 ui.monospace(summary.total_samples.to_string());
                                                 ui.end_row();
 
+                                                ui.label("Top Spike Source:");
+                                                ui.monospace(format_top_offender(&summary));
+                                                ui.end_row();
+
+                                                if let Some(ref power) = summary.power {
+                                                    ui.label("Energy:");
+                                                    ui.monospace(format_energy(power));
+                                                    ui.end_row();
+                                                }
+
                                                 ui.label("Duration:");
                                                 ui.monospace(format!(
                                                     "{:.2}s",
@@ -1016,6 +1036,16 @@ fn render_audit_results_single_column(
                             ui.monospace(summary.total_samples.to_string());
                             ui.end_row();
 
+                            ui.label("Top Spike Source:");
+                            ui.monospace(format_top_offender(&summary));
+                            ui.end_row();
+
+                            if let Some(ref power) = summary.power {
+                                ui.label("Energy:");
+                                ui.monospace(format_energy(power));
+                                ui.end_row();
+                            }
+
                             ui.label("Duration:");
                             ui.monospace(format!(
                                 "{:.2}s",
@@ -1060,6 +1090,50 @@ fn render_audit_results_single_column(
     });
 }
 
+/// Format the highest ranked spike offender of a session, e.g. "IRQ 142 (iwlwifi): 12 spikes"
+fn format_top_offender(summary: &crate::system::performance::SessionSummary) -> String {
+    match summary.top_offenders.first() {
+        Some(offender) => {
+            let device = offender
+                .detail
+                .split_whitespace()
+                .last()
+                .filter(|_| offender.kind == crate::system::performance::OffenderKind::Irq);
+            match device {
+                Some(device) => format!(
+                    "{} {} ({}): {} spikes",
+                    offender.kind.label(),
+                    offender.name,
+                    device,
+                    offender.attributed_spikes
+                ),
+                None => format!(
+                    "{} {}: {} spikes",
+                    offender.kind.label(),
+                    offender.name,
+                    offender.attributed_spikes
+                ),
+            }
+        }
+        None => "—".to_string(),
+    }
+}
+
+/// Format the energy of a benchmark session, e.g. "1450.2 J, 20.7 W avg (peak 41.3 W: The Gauntlet)"
+fn format_energy(power: &crate::system::performance::PowerMetrics) -> String {
+    let peak = power
+        .phases
+        .iter()
+        .max_by(|a, b| a.avg_watts.total_cmp(&b.avg_watts));
+    match peak {
+        Some(peak) => format!(
+            "{:.1} J, {:.1} W avg (peak {:.1} W: {})",
+            power.total_joules, power.avg_watts, peak.avg_watts, peak.phase
+        ),
+        None => format!("{:.1} J, {:.1} W avg", power.total_joules, power.avg_watts),
+    }
+}
+
 // ============================================================================
 // CACHED HEALTH REPORT WITH 60S TTL
 // ============================================================================
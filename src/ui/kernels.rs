@@ -27,7 +27,11 @@ pub fn uninstall_kernel(
         .trim()
         .to_string();
 
-    system.uninstall_package(&clean_pkg_name)
+    system.uninstall_package(&clean_pkg_name)?;
+    if let Err(e) = crate::system::boot_tracking::forget_kernel(&clean_pkg_name) {
+        eprintln!("[UI] [KERNELS] Failed to update boot tracking state: {}", e);
+    }
+    Ok(())
 }
 
 /// De-rebrand a rebranded kernel variant name to its base variant
@@ -39,6 +43,7 @@ pub fn uninstall_kernel(
 /// - `linux-goatd-mainline-*` -> `linux-mainline`
 /// - `linux-goatd-tkg-*` -> `linux-tkg`
 /// - `linux-goatd-*` -> `linux` (fallback for any other goatd variant)
+/// - `<variant>-goatd-*` -> `<variant>` (current scheme, incl. cachyos/rt and custom sources)
 /// - Otherwise returns the original string unchanged
 pub fn get_base_variant(rebranded_name: &str) -> &str {
     // Check for specific known variant mappings
@@ -55,6 +60,9 @@ pub fn get_base_variant(rebranded_name: &str) -> &str {
     } else if rebranded_name.starts_with("linux-goatd-") {
         // Fallback for any other linux-goatd-* variant
         "linux"
+    } else if let Some((base, _)) = rebranded_name.split_once("-goatd-") {
+        // linux-{variant}-goatd-{profile}: any built-in or custom variant
+        base
     } else {
         // Not a rebranded variant, return as-is
         rebranded_name
@@ -1020,6 +1028,12 @@ pub fn render_kernel_manager(
         }
     }
 
+    // Boot-success state (trial/good/failed) shown next to each installed kernel
+    let boot_states = controller
+        .try_read()
+        .map(|guard| guard.get_kernel_boot_states())
+        .unwrap_or_default();
+
     // PERSISTENT CACHE SYNC: Pull audit data from controller cache into UIState cache
     // This ensures the persistent cache is always up-to-date without clearing it
     if app.ui_state.selected_kernel_index.is_some() {
@@ -1054,7 +1068,7 @@ pub fn render_kernel_manager(
                                 // Render installed kernels as selectable list
                                 for (idx, kernel) in app.ui_state.installed_kernels.iter().enumerate() {
                                     let is_selected = app.ui_state.selected_kernel_index == Some(idx);
-                                    if ui.selectable_label(is_selected, kernel).clicked() {
+                                    if ui.selectable_label(is_selected, boot_state_label(kernel, &boot_states)).clicked() {
                                          app.ui_state.selected_kernel_index = Some(idx);
                                          app.ui_state.selected_kernel_name = kernel.clone();
                                          eprintln!("[UI] [KERNELS] Selected kernel: {} (index {})", kernel, idx);
@@ -1218,6 +1232,8 @@ pub fn render_kernel_manager(
                                              app.ui_state.selected_artifact_index = None;
                                          }
                                      }
+
+                                     render_config_diff_button(ui, app, controller);
                                  });
                              });
                         });
@@ -1248,7 +1264,7 @@ pub fn render_kernel_manager(
                     // Render installed kernels as selectable list
                     for (idx, kernel) in app.ui_state.installed_kernels.iter().enumerate() {
                         let is_selected = app.ui_state.selected_kernel_index == Some(idx);
-                        if ui.selectable_label(is_selected, kernel).clicked() {
+                        if ui.selectable_label(is_selected, boot_state_label(kernel, &boot_states)).clicked() {
                              app.ui_state.selected_kernel_index = Some(idx);
                              app.ui_state.selected_kernel_name = kernel.clone();
                              eprintln!("[UI] [KERNELS] Selected kernel: {} (index {})", kernel, idx);
@@ -1413,6 +1429,8 @@ pub fn render_kernel_manager(
                                 app.ui_state.selected_artifact_index = None;
                             }
                         }
+
+                        render_config_diff_button(ui, app, controller);
                     });
                 });
 
@@ -1430,6 +1448,139 @@ pub fn render_kernel_manager(
             });
         }
     });
+
+    render_config_diff_window(ui, app, controller);
+}
+
+/// "Diff vs Running" action for the selected built artifact.
+///
+/// Starts a background Kconfig diff of the artifact against the booted kernel
+/// and opens the diff window, which shows the result once it is ready.
+/// Installed-kernel label with its boot-success state, e.g. `linux-goatd-gaming (6.18.3) [TRIAL]`
+fn boot_state_label(
+    kernel: &str,
+    boot_states: &std::collections::HashMap<String, crate::system::boot_tracking::BootStatus>,
+) -> String {
+    let pkgbase = kernel.split('(').next().unwrap_or(kernel).trim();
+    match boot_states.get(pkgbase) {
+        Some(status) => format!("{} [{}]", kernel, status.as_str()),
+        None => kernel.to_string(),
+    }
+}
+
+fn render_config_diff_button(
+    ui: &mut egui::Ui,
+    app: &mut AppUI,
+    controller: &Arc<RwLock<AppController>>,
+) {
+    let artifact = app
+        .ui_state
+        .selected_artifact_index
+        .and_then(|idx| app.ui_state.built_artifacts.get(idx).cloned());
+
+    let button = ui
+        .add_enabled(artifact.is_some(), egui::Button::new("Diff vs Running"))
+        .on_hover_text("Compare this kernel's .config with the running kernel");
+    if button.clicked() {
+        if let Some(artifact) = artifact {
+            app.ui_state.config_diff_artifact = artifact.display_name();
+            app.ui_state.show_config_diff = true;
+
+            let controller_clone = Arc::clone(controller);
+            tokio::spawn(async move {
+                let controller = controller_clone.read().await;
+                controller.compare_artifact_config_async(artifact);
+            });
+        }
+    }
+}
+
+/// Kconfig diff window: added/removed/changed symbols grouped by subsystem.
+fn render_config_diff_window(
+    ui: &mut egui::Ui,
+    app: &mut AppUI,
+    controller: &Arc<RwLock<AppController>>,
+) {
+    if !app.ui_state.show_config_diff {
+        return;
+    }
+
+    let diff = controller
+        .try_read()
+        .ok()
+        .and_then(|guard| guard.get_artifact_config_diff());
+
+    let mut is_open = true;
+    egui::Window::new(format!("Kconfig Diff: {}", app.ui_state.config_diff_artifact))
+        .open(&mut is_open)
+        .resizable(true)
+        .default_width(700.0)
+        .show(ui.ctx(), |ui| match &diff {
+            None => {
+                ui.horizontal(|ui| {
+                    ui.spinner();
+                    ui.label("Reading kernel configs...");
+                });
+            }
+            Some(Err(e)) => {
+                ui.colored_label(egui::Color32::from_rgb(255, 100, 100), format!("✗ {}", e));
+            }
+            Some(Ok(diff)) => {
+                ui.label(format!("Old: {}", diff.old_label));
+                ui.label(format!("New: {}", diff.new_label));
+                ui.horizontal(|ui| {
+                    ui.strong(format!(
+                        "{} added, {} removed, {} changed",
+                        diff.added_count(),
+                        diff.removed_count(),
+                        diff.changed_count()
+                    ));
+                    if ui.button("📋 Copy JSON").clicked() {
+                        match diff.to_json() {
+                            Ok(json) => ui.output_mut(|o| o.copied_text = json),
+                            Err(e) => eprintln!("[UI] [KERNELS] ✗ Config diff JSON failed: {}", e),
+                        }
+                    }
+                });
+                ui.separator();
+
+                if diff.is_empty() {
+                    ui.label("No Kconfig differences.");
+                    return;
+                }
+
+                egui::ScrollArea::vertical().max_height(500.0).show(ui, |ui| {
+                    for (subsystem, changes) in &diff.subsystems {
+                        egui::CollapsingHeader::new(format!("{} ({})", subsystem, changes.len()))
+                            .id_source(("config_diff", subsystem))
+                            .show(ui, |ui| {
+                                for (symbol, value) in &changes.added {
+                                    ui.colored_label(
+                                        egui::Color32::from_rgb(100, 200, 100),
+                                        format!("+ CONFIG_{}={}", symbol, value),
+                                    );
+                                }
+                                for (symbol, value) in &changes.removed {
+                                    ui.colored_label(
+                                        egui::Color32::from_rgb(255, 100, 100),
+                                        format!("- CONFIG_{}={}", symbol, value),
+                                    );
+                                }
+                                for (symbol, change) in &changes.changed {
+                                    ui.colored_label(
+                                        egui::Color32::from_rgb(255, 200, 80),
+                                        format!("~ CONFIG_{}: {} → {}", symbol, change.old, change.new),
+                                    );
+                                }
+                            });
+                    }
+                });
+            }
+        });
+
+    if !is_open {
+        app.ui_state.show_config_diff = false;
+    }
 }
 
 /// Truncate a file path to fit within a maximum length while preserving important parts
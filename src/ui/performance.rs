@@ -1,6 +1,13 @@
 use super::widgets;
+use crate::hardware::CpuTopology;
 use crate::log_info;
-use crate::system::performance::{Intensity, MonitoringMode, StressorManager, StressorType};
+use crate::models::{CoreType, CpuSelection};
+use crate::system::performance::experiment::{self, ComparedMetric, MetricComparison};
+use crate::system::performance::scenario::{self, BenchmarkScenario};
+use crate::system::performance::{
+    ExperimentComparison, Intensity, MonitoringMode, MultiCoreMetrics, StressorManager,
+    StressorType,
+};
 use crate::ui::controller::AppController;
 /// Performance Dashboard View with Spectrum Visualization
 ///
@@ -16,6 +23,7 @@ use crate::ui::controller::AppController;
 use eframe::egui;
 use egui_extras::StripBuilder;
 use std::cell::RefCell;
+use std::sync::atomic::{AtomicBool, Ordering};
 use std::sync::Arc;
 use std::time::{Duration, Instant};
 use tokio::sync::RwLock;
@@ -118,6 +126,28 @@ struct ComparisonCacheEntry {
     p99_9_delta: f32,
     smi_delta: f32,
     stall_delta: f32,
+    // Medians, confidence intervals and significance per metric
+    comparison: ExperimentComparison,
+}
+
+/// Comparison popup row: (label, val_a, val_b, delta_percent, is_lower_better, tooltip, stats)
+type ComparisonRow<'a> = (
+    &'a str,
+    f32,
+    f32,
+    f32,
+    bool,
+    &'a str,
+    Option<&'a MetricComparison>,
+);
+
+/// Progress of a running A/B experiment (shared with the runner task)
+#[derive(Clone, Debug, Default)]
+struct ExperimentProgress {
+    running: bool,
+    completed: usize,
+    total: usize,
+    status: String,
 }
 
 /// Performance UI state
@@ -158,6 +188,20 @@ pub struct PerformanceUIState {
     benchmark_name_input: RefCell<String>,
     /// Test selected for deletion in comparison window
     test_to_delete: RefCell<Option<String>>,
+    /// A/B experiment name (runs are grouped by this name and the running kernel)
+    experiment_name_input: RefCell<String>,
+    /// Benchmark repetitions per experiment
+    experiment_repetitions: RefCell<usize>,
+    experiment_progress: Arc<std::sync::Mutex<ExperimentProgress>>,
+    experiment_cancel: Arc<AtomicBool>,
+    /// Available benchmark scenarios (id, scenario), built-in first
+    scenarios: RefCell<Vec<(String, BenchmarkScenario)>>,
+    /// Id of the scenario run by "GOATd Benchmark"
+    selected_scenario: RefCell<String>,
+    /// Last scenario load/import message
+    scenario_status: RefCell<Option<String>>,
+    /// CPU topology for the CPU selection (detected on first use)
+    cpu_topology: RefCell<Option<CpuTopology>>,
 
     /// === SPECTRUM METRICS STATE ===
     /// Performance Spectrum strips (7 metrics: Latency, Throughput, Jitter, CPU Eff, Thermal, Consistency, SMI Res)
@@ -213,6 +257,14 @@ impl PerformanceUIState {
             naming_prompt_triggered: RefCell::new(false),
             benchmark_name_input: RefCell::new(String::new()),
             test_to_delete: RefCell::new(None),
+            experiment_name_input: RefCell::new(String::new()),
+            experiment_repetitions: RefCell::new(experiment::DEFAULT_REPETITIONS),
+            experiment_progress: Arc::new(std::sync::Mutex::new(ExperimentProgress::default())),
+            experiment_cancel: Arc::new(AtomicBool::new(false)),
+            scenarios: RefCell::new(Vec::new()),
+            selected_scenario: RefCell::new(scenario::DEFAULT_SCENARIO_ID.to_string()),
+            scenario_status: RefCell::new(None),
+            cpu_topology: RefCell::new(None),
             spectrum_strips: RefCell::new(spectrum_strips),
             goat_score: RefCell::new(0),
             monitoring_start_time: RefCell::new(None),
@@ -248,11 +300,51 @@ impl PerformanceUIState {
         let seconds = *self.benchmark_duration_seconds.borrow();
         match seconds {
             0 => MonitoringMode::Continuous,
-            999 => MonitoringMode::SystemBenchmark,
+            999 => MonitoringMode::SystemBenchmark(self.selected_scenario()),
             secs => MonitoringMode::Benchmark(std::time::Duration::from_secs(secs as u64)),
         }
     }
 
+    /// Get the selected benchmark scenario, falling back to the built-in one
+    pub fn selected_scenario(&self) -> BenchmarkScenario {
+        let selected = self.selected_scenario.borrow();
+        self.scenarios
+            .borrow()
+            .iter()
+            .find(|(id, _)| *id == *selected)
+            .map(|(_, scenario)| scenario.clone())
+            .unwrap_or_default()
+    }
+
+    /// Reload scenarios from the scenario directory
+    /// Keeps the current selection if it still exists
+    pub fn reload_scenarios(&self) {
+        let load = scenario::load_scenarios(&scenario::scenarios_dir());
+        let status = load.errors.first().map(|(path, e)| {
+            format!(
+                "{} scenario file(s) skipped; {}: {}",
+                load.errors.len(),
+                path.display(),
+                e
+            )
+        });
+
+        let mut selected = self.selected_scenario.borrow_mut();
+        if !load.scenarios.iter().any(|(id, _)| *id == *selected) {
+            *selected = scenario::DEFAULT_SCENARIO_ID.to_string();
+        }
+        *self.scenarios.borrow_mut() = load.scenarios;
+        *self.scenario_status.borrow_mut() = status;
+    }
+
+    /// Get the CPU topology, detecting it on first use
+    pub fn cpu_topology(&self) -> CpuTopology {
+        self.cpu_topology
+            .borrow_mut()
+            .get_or_insert_with(crate::hardware::detect_cpu_topology)
+            .clone()
+    }
+
     /// Get selected stressors
     pub fn get_selected_stressors(&self) -> Vec<StressorType> {
         let mut stressors = Vec::new();
@@ -664,6 +756,29 @@ fn progress_bar_color(value: f32) -> egui::Color32 {
     }
 }
 
+/// Helper: Delta color for a compared metric, gray unless the change is significant
+fn get_verdict_color(
+    stats: Option<&MetricComparison>,
+    delta_percent: f32,
+    is_lower_better: bool,
+) -> egui::Color32 {
+    match stats {
+        Some(m) if !m.verdict.is_significant() => egui::Color32::from_rgb(0xa0, 0xa0, 0xa0),
+        _ => get_delta_color(delta_percent, is_lower_better),
+    }
+}
+
+/// Helper: Verdict line for a compared metric, e.g. "no significant difference (p=0.41)"
+fn format_verdict(stats: Option<&MetricComparison>) -> String {
+    match stats {
+        Some(m) => match m.p_value {
+            Some(p) => format!("{} (p={:.3})", m.verdict.label(), p),
+            None => m.verdict.label().to_string(),
+        },
+        None => String::new(),
+    }
+}
+
 /// Helper: Determine delta color based on improvement/regression
 /// For latency/SMI/stalls: negative/lower = GREEN (improvement), positive/higher = RED (regression)
 /// For throughput: positive/higher = GREEN (improvement), negative/lower = RED (regression)
@@ -1281,27 +1396,22 @@ fn render_phase_status(ui: &mut egui::Ui, controller: &Arc<RwLock<AppController>
         if let Ok(orch_lock) = ctrl.benchmark_orchestrator.read() {
             if let Some(ref orch) = *orch_lock {
                 let elapsed = orch.elapsed_secs();
-                let phase = orch.current_phase;
-                let phase_start = phase.start_time();
-                let phase_end = phase.end_time();
+                let phase_start = orch.current_phase_start();
+                let phase_end = orch.current_phase_end();
+                let phase_len = phase_end.saturating_sub(phase_start).max(1);
                 let time_in_phase = elapsed.saturating_sub(phase_start);
                 let time_remaining = phase_end.saturating_sub(elapsed);
 
-                // Determine phase number (1-6)
-                let phase_num = match phase {
-                    crate::system::performance::BenchmarkPhase::Baseline => 1,
-                    crate::system::performance::BenchmarkPhase::ComputationalHeat => 2,
-                    crate::system::performance::BenchmarkPhase::MemorySaturation => 3,
-                    crate::system::performance::BenchmarkPhase::SchedulerFlood => 4,
-                    crate::system::performance::BenchmarkPhase::GamingSimulator => 5,
-                    crate::system::performance::BenchmarkPhase::TheGauntlet => 6,
-                };
-
                 // Render phase status with colored background
                 ui.group(|ui| {
                     ui.horizontal(|ui| {
                         // Phase label
-                        let phase_label = format!("PHASE {}/6: {}", phase_num, phase);
+                        let phase_label = format!(
+                            "PHASE {}/{}: {}",
+                            orch.current_phase + 1,
+                            orch.phase_count(),
+                            orch.current_phase_name()
+                        );
                         ui.colored_label(
                             egui::Color32::from_rgb(0x51, 0xaf, 0xef), // Cyan
                             egui::RichText::new(&phase_label).monospace().strong(),
@@ -1319,11 +1429,11 @@ fn render_phase_status(ui: &mut egui::Ui, controller: &Arc<RwLock<AppController>
                     });
 
                     // Progress bar showing phase progress
-                    let progress = (time_in_phase as f32) / 10.0; // 10 seconds per phase
+                    let progress = (time_in_phase as f32) / (phase_len as f32);
                     ui.add(
                         egui::ProgressBar::new(progress.min(1.0))
                             .show_percentage()
-                            .text(format!("{}/10s", time_in_phase)),
+                            .text(format!("{}/{}s", time_in_phase, phase_len)),
                     );
                 });
             }
@@ -1414,7 +1524,7 @@ fn render_performance_spectrum(
         // Benchmark metrics are greyed out during passive Continuous monitoring
         // Only enable benchmark-specific metrics when in active benchmark mode AND state is Running
         let is_benchmark_active = if let Some(m) = metrics {
-            let mode_is_benchmark = matches!(mode, MonitoringMode::Benchmark(_) | MonitoringMode::SystemBenchmark);
+            let mode_is_benchmark = matches!(mode, MonitoringMode::Benchmark(_) | MonitoringMode::SystemBenchmark(_));
             let state_is_running = m.state == crate::system::performance::CollectionState::Running;
             mode_is_benchmark && state_is_running
         } else {
@@ -1509,22 +1619,163 @@ fn render_kpi_section(
     });
 }
 
+/// Render the benchmark scenario picker with TOML import
+fn render_scenario_picker(ui: &mut egui::Ui, state: &PerformanceUIState) {
+    if state.scenarios.borrow().is_empty() {
+        state.reload_scenarios();
+    }
+
+    ui.horizontal(|ui| {
+        ui.label("Scenario:");
+        let selected = state.selected_scenario();
+        let mut selected_id = state.selected_scenario.borrow().clone();
+        egui::ComboBox::from_id_source("benchmark_scenario_combo")
+            .selected_text(format!(
+                "{} ({}s)",
+                selected.name,
+                selected.total_duration_secs()
+            ))
+            .show_ui(ui, |ui| {
+                for (id, scenario) in state.scenarios.borrow().iter() {
+                    ui.selectable_value(
+                        &mut selected_id,
+                        id.clone(),
+                        format!("{} ({}s)", scenario.name, scenario.total_duration_secs()),
+                    )
+                    .on_hover_text(&scenario.description);
+                }
+            });
+        *state.selected_scenario.borrow_mut() = selected_id;
+
+        if ui
+            .button("Import...")
+            .on_hover_text("Import a benchmark scenario TOML file")
+            .clicked()
+        {
+            if let Some(path) = rfd::FileDialog::new()
+                .add_filter("Scenario", &["toml"])
+                .pick_file()
+            {
+                match scenario::import_scenario(&path, &scenario::scenarios_dir()) {
+                    Ok(id) => {
+                        state.reload_scenarios();
+                        *state.selected_scenario.borrow_mut() = id.clone();
+                        *state.scenario_status.borrow_mut() =
+                            Some(format!("Imported scenario '{}'", id));
+                    }
+                    Err(e) => {
+                        log_info!("[PERF] Scenario import failed: {}", e);
+                        *state.scenario_status.borrow_mut() = Some(format!("Import failed: {}", e));
+                    }
+                }
+            }
+        }
+        if ui.button("🔄").on_hover_text("Reload scenarios").clicked() {
+            state.reload_scenarios();
+        }
+    });
+
+    if let Some(status) = state.scenario_status.borrow().as_ref() {
+        ui.label(egui::RichText::new(status).small().weak());
+    }
+}
+
+/// Render the CPU selection for latency collection (persisted in settings)
+fn render_cpu_selection(
+    ui: &mut egui::Ui,
+    state: &PerformanceUIState,
+    controller: &Arc<RwLock<AppController>>,
+) {
+    let Ok(ctrl) = controller.try_read() else {
+        return;
+    };
+    let current = ctrl
+        .get_state()
+        .map(|s| s.perf_cpu_selection)
+        .unwrap_or_default();
+    let topology = state.cpu_topology();
+
+    let mut options = vec![CpuSelection::Single];
+    if topology.is_hybrid() {
+        options.push(CpuSelection::CoreType(CoreType::Performance));
+        options.push(CpuSelection::CoreType(CoreType::Efficiency));
+    }
+    options.push(CpuSelection::All);
+    if !options.contains(&current) {
+        options.push(current.clone());
+    }
+
+    let mut selected = current.clone();
+    ui.horizontal(|ui| {
+        ui.label("CPUs:");
+        egui::ComboBox::from_id_source("latency_cpu_selection_combo")
+            .selected_text(selected.label())
+            .show_ui(ui, |ui| {
+                for option in options {
+                    let count = topology.resolve_selection(&option, 0).len();
+                    let text = format!("{} ({})", option.label(), count);
+                    ui.selectable_value(&mut selected, option, text);
+                }
+            })
+            .response
+            .on_hover_text("Run one pinned latency collector per selected CPU");
+    });
+
+    if selected != current {
+        if let Err(e) = ctrl.update_state(|s| s.perf_cpu_selection = selected.clone()) {
+            log_info!("[PERF] Failed to save CPU selection: {}", e);
+        }
+    }
+}
+
+/// Render the per-core latency heatmap with merged and per-core-type P99
+fn render_per_core_latency(ui: &mut egui::Ui, per_cpu: &MultiCoreMetrics) {
+    ui.label(egui::RichText::new("🧮 Per-Core Latency (P99)").strong());
+
+    let cells: Vec<(String, f32)> = per_cpu
+        .per_cpu
+        .iter()
+        .map(|c| {
+            (
+                format!("{}{}", c.core_type.short_label(), c.cpu),
+                c.metrics.p99_us,
+            )
+        })
+        .collect();
+    widgets::cpu_latency_heatmap(ui, &cells);
+
+    let mut summary = format!("Merged P99: {:.1}µs", per_cpu.merged.p99_us);
+    if per_cpu.by_core_type.len() > 1 {
+        for (core_type, metrics) in &per_cpu.by_core_type {
+            summary.push_str(&format!(
+                " | {}: {:.1}µs",
+                core_type.label(),
+                metrics.p99_us
+            ));
+        }
+    }
+    if let Some(worst) = per_cpu.worst_cpu() {
+        summary.push_str(&format!(
+            " | Worst: {}{} ({:.1}µs max)",
+            worst.core_type.short_label(),
+            worst.cpu,
+            worst.metrics.max_us
+        ));
+    }
+    ui.label(
+        egui::RichText::new(summary)
+            .monospace()
+            .small()
+            .color(egui::Color32::LIGHT_GRAY),
+    );
+}
+
 /// Extract phase highlight logic
 fn get_phase_highlight(controller: &Arc<RwLock<AppController>>) -> Option<String> {
     if let Ok(ctrl) = controller.try_read() {
         if let Ok(orch_lock) = ctrl.benchmark_orchestrator.read() {
             if let Some(ref orch) = *orch_lock {
-                use crate::system::performance::BenchmarkPhase;
-                let phase = orch.current_phase;
-                return Some(match phase {
-                    BenchmarkPhase::Baseline => "Latency",
-                    BenchmarkPhase::ComputationalHeat => "Latency",
-                    BenchmarkPhase::MemorySaturation => "Throughput",
-                    BenchmarkPhase::SchedulerFlood => "Jitter",
-                    BenchmarkPhase::GamingSimulator => "Efficiency",
-                    BenchmarkPhase::TheGauntlet => "Consistency",
-                }
-                .to_string());
+                return orch.current_phase_focus().map(str::to_string);
             }
         }
     }
@@ -1559,11 +1810,16 @@ fn render_benchmark_controls(
                 if ui.radio(duration == 300, "5m").clicked() {
                     *state.benchmark_duration_seconds.borrow_mut() = 300;
                 }
-                if ui.radio(duration == 999, "GOATd Benchmark (60s)").clicked() {
+                if ui.radio(duration == 999, "GOATd Benchmark").clicked() {
                     *state.benchmark_duration_seconds.borrow_mut() = 999;
                 }
             });
 
+            if duration == 999 {
+                render_scenario_picker(ui, state);
+            }
+            render_cpu_selection(ui, state, &controller);
+
             ui.separator();
 
             ui.horizontal(|ui| {
@@ -1595,6 +1851,15 @@ fn render_benchmark_controls(
                 }
             });
 
+            let per_cpu = controller
+                .try_read()
+                .ok()
+                .and_then(|ctrl| ctrl.get_per_cpu_metrics());
+            if let Some(per_cpu) = per_cpu {
+                ui.separator();
+                render_per_core_latency(ui, &per_cpu);
+            }
+
             ui.separator();
 
             let (is_monitoring, _) = {
@@ -1685,7 +1950,217 @@ fn render_benchmark_controls(
                 let show_popup = *state.show_comparison_popup.borrow();
                 *state.show_comparison_popup.borrow_mut() = !show_popup;
             }
+
+            ui.separator();
+            render_experiment_controls(ui, &controller, state, is_monitoring);
+        });
+    });
+}
+
+/// A/B experiment controls: repeat the selected benchmark N times on the running kernel
+///
+/// Every run is saved as its own record tagged with the experiment name. Running
+/// the same experiment on another kernel and comparing one run of each in
+/// "Compare Results" compares the two groups of runs.
+fn render_experiment_controls(
+    ui: &mut egui::Ui,
+    controller: &Arc<RwLock<AppController>>,
+    state: &PerformanceUIState,
+    is_monitoring: bool,
+) {
+    ui.label("🧪 A/B Experiment");
+
+    let progress = state
+        .experiment_progress
+        .lock()
+        .map(|p| p.clone())
+        .unwrap_or_default();
+
+    if progress.running {
+        ui.horizontal(|ui| {
+            ui.add(
+                egui::ProgressBar::new(progress.completed as f32 / progress.total.max(1) as f32)
+                    .text(format!("{}/{} runs", progress.completed, progress.total)),
+            );
+            if ui.button("Cancel").clicked() {
+                state.experiment_cancel.store(true, Ordering::SeqCst);
+            }
         });
+        ui.label(egui::RichText::new(&progress.status).small());
+        ui.ctx().request_repaint_after(Duration::from_millis(500));
+        return;
+    }
+
+    ui.horizontal(|ui| {
+        ui.label("Name:");
+        let mut name = state.experiment_name_input.borrow().clone();
+        if ui
+            .add(egui::TextEdit::singleline(&mut name).desired_width(140.0))
+            .changed()
+        {
+            *state.experiment_name_input.borrow_mut() = name;
+        }
+
+        ui.label("Runs:");
+        let mut reps = *state.experiment_repetitions.borrow();
+        if ui
+            .add(egui::DragValue::new(&mut reps).clamp_range(experiment::MIN_RUNS..=50))
+            .changed()
+        {
+            *state.experiment_repetitions.borrow_mut() = reps;
+        }
+    });
+
+    let mode = state.get_monitoring_mode();
+    let name = state.experiment_name_input.borrow().trim().to_string();
+    let reps = *state.experiment_repetitions.borrow();
+    let can_run = !is_monitoring && !name.is_empty() && mode != MonitoringMode::Continuous;
+
+    let response = ui.add_enabled(
+        can_run,
+        egui::Button::new(format!("Run {} repetitions", reps)),
+    );
+    let response = if mode == MonitoringMode::Continuous {
+        response.on_disabled_hover_text("Select a timed benchmark duration")
+    } else {
+        response.on_hover_text("Repeat the selected benchmark on the running kernel")
+    };
+    if response.clicked() {
+        state.experiment_cancel.store(false, Ordering::SeqCst);
+        spawn_experiment_runner(
+            controller.clone(),
+            name,
+            reps,
+            mode,
+            state.get_selected_stressors(),
+            Arc::clone(&state.experiment_progress),
+            Arc::clone(&state.experiment_cancel),
+        );
+    }
+
+    if !progress.status.is_empty() {
+        ui.label(egui::RichText::new(&progress.status).small());
+    }
+}
+
+/// Run the benchmark `reps` times and save every run as an experiment record
+///
+/// Each repetition clears the cached session summary, starts the benchmark and
+/// waits until the session has auto-stopped and written a new summary, which is
+/// then persisted with [`AppController::handle_save_experiment_run`].
+fn spawn_experiment_runner(
+    controller: Arc<RwLock<AppController>>,
+    name: String,
+    reps: usize,
+    mode: MonitoringMode,
+    stressors: Vec<StressorType>,
+    progress: Arc<std::sync::Mutex<ExperimentProgress>>,
+    cancel: Arc<AtomicBool>,
+) {
+    let set_progress = move |completed: usize, running: bool, status: String| {
+        if let Ok(mut p) = progress.lock() {
+            *p = ExperimentProgress {
+                running,
+                completed,
+                total: reps,
+                status,
+            };
+        }
+    };
+    // Benchmarks auto-stop after their duration; allow time for setup and teardown
+    let run_timeout = mode.duration().unwrap_or(Duration::from_secs(60)) + Duration::from_secs(60);
+
+    tokio::spawn(async move {
+        log_info!("[PERF] [EXPERIMENT] Starting '{}' with {} runs", name, reps);
+        set_progress(0, true, format!("Starting '{}'...", name));
+
+        for rep in 1..=reps {
+            if cancel.load(Ordering::SeqCst) {
+                set_progress(
+                    rep - 1,
+                    false,
+                    format!("'{}' cancelled after {} runs", name, rep - 1),
+                );
+                return;
+            }
+
+            {
+                let ctrl = controller.read().await;
+                if let Ok(mut summary) = ctrl.perf_session_summary.write() {
+                    *summary = None;
+                }
+                if let Err(e) = ctrl.handle_trigger_monitoring(mode.clone(), stressors.clone()) {
+                    set_progress(
+                        rep - 1,
+                        false,
+                        format!("Run #{} failed to start: {}", rep, e),
+                    );
+                    return;
+                }
+            }
+            set_progress(
+                rep - 1,
+                true,
+                format!("Run #{} of {} in progress...", rep, reps),
+            );
+
+            let started = Instant::now();
+            loop {
+                tokio::time::sleep(Duration::from_millis(500)).await;
+                let ctrl = controller.read().await;
+                if cancel.load(Ordering::SeqCst) {
+                    let _ = ctrl.handle_stop_monitoring();
+                    set_progress(
+                        rep - 1,
+                        false,
+                        format!("'{}' cancelled after {} runs", name, rep - 1),
+                    );
+                    return;
+                }
+                let finished = !ctrl.perf_monitoring_active.load(Ordering::SeqCst)
+                    && ctrl
+                        .perf_session_summary
+                        .read()
+                        .map(|s| s.is_some())
+                        .unwrap_or(false);
+                if finished {
+                    break;
+                }
+                if started.elapsed() > run_timeout {
+                    let _ = ctrl.handle_stop_monitoring();
+                    set_progress(rep - 1, false, format!("Run #{} timed out", rep));
+                    return;
+                }
+            }
+
+            let saved = controller
+                .read()
+                .await
+                .handle_save_experiment_run(&name, rep);
+            if let Err(e) = saved {
+                set_progress(
+                    rep - 1,
+                    false,
+                    format!("Run #{} could not be saved: {}", rep, e),
+                );
+                return;
+            }
+            set_progress(rep, true, format!("Run #{} of {} saved", rep, reps));
+        }
+
+        log_info!(
+            "[PERF] [EXPERIMENT] '{}' finished: {} runs saved",
+            name,
+            reps
+        );
+        set_progress(
+            reps,
+            false,
+            format!(
+                "'{}' finished: {} runs saved. Run it on the other kernel, then compare.",
+                name, reps
+            ),
+        );
     });
 }
 
@@ -2297,7 +2772,7 @@ pub fn render_performance(ui: &mut egui::Ui, controller: &Arc<RwLock<AppControll
                                 tokio::spawn(async move {
                                     if let Ok(ctrl) = controller_clone.try_read() {
                                         match ctrl.handle_compare_tests_request(&a_id_copy, &b_id_copy) {
-                                            Ok((test_a, test_b, deltas)) => {
+                                            Ok((test_a, test_b, deltas, comparison)) => {
                                                 log::debug!("[COMPARE] Comparison loaded: A={} vs B={}", a_id_copy, b_id_copy);
 
                                                 // Cache the result with all metric values
@@ -2326,6 +2801,7 @@ pub fn render_performance(ui: &mut egui::Ui, controller: &Arc<RwLock<AppControll
                                                     p99_9_delta: deltas.3,
                                                     smi_delta: deltas.4,
                                                     stall_delta: deltas.5,
+                                                    comparison,
                                                 };
 
                                                 if let Ok(mut c) = cache_arc.lock() {
@@ -2346,19 +2822,40 @@ pub fn render_performance(ui: &mut egui::Ui, controller: &Arc<RwLock<AppControll
                             let cached_entry = state.comparison_result_cache.lock().ok().and_then(|guard| guard.clone());
 
                             if let Some(cached) = cached_entry {
-                                // Build metric comparison data with raw values, deltas, and tooltips
-                                // Format: (label, val_a, val_b, delta_percent, is_lower_better, tooltip)
-                                let metric_rows: Vec<(&str, f32, f32, f32, bool, &str)> = vec![
-                                    ("Min Latency (µs)", cached.min_us_a, cached.min_us_b, cached.min_delta, true, "Best-case scheduling response time"),
-                                    ("Max Latency (µs)", cached.max_us_a, cached.max_us_b, cached.max_delta, true, "Worst-case scheduling response time"),
-                                    ("Avg Latency (µs)", cached.avg_us_a, cached.avg_us_b, cached.avg_delta, true, "Mean scheduling response across all samples"),
-                                    ("P99.9 (µs)", cached.p99_9_us_a, cached.p99_9_us_b, cached.p99_9_delta, true, "99.9th percentile latency (micro-stutter detection)"),
-                                    ("SMI Count", cached.smi_count_a as f32, cached.smi_count_b as f32, cached.smi_delta, true, "System Management Interrupt occurrences"),
-                                    ("Stall Correlated", cached.stall_count_a as f32, cached.stall_count_b as f32, cached.stall_delta, true, "Latency spikes correlated to SMI events"),
+                                // Build metric comparison data with median values, deltas, significance and tooltips
+                                // Format: (label, val_a, val_b, delta_percent, is_lower_better, tooltip, stats)
+                                let stats = |metric| cached.comparison.metric(metric);
+                                let metric_rows: Vec<ComparisonRow> = vec![
+                                    ("Min Latency (µs)", cached.min_us_a, cached.min_us_b, cached.min_delta, true, "Best-case scheduling response time", stats(ComparedMetric::MinLatency)),
+                                    ("Max Latency (µs)", cached.max_us_a, cached.max_us_b, cached.max_delta, true, "Worst-case scheduling response time", stats(ComparedMetric::MaxLatency)),
+                                    ("Avg Latency (µs)", cached.avg_us_a, cached.avg_us_b, cached.avg_delta, true, "Mean scheduling response across all samples", stats(ComparedMetric::AvgLatency)),
+                                    ("P99.9 (µs)", cached.p99_9_us_a, cached.p99_9_us_b, cached.p99_9_delta, true, "99.9th percentile latency (micro-stutter detection)", stats(ComparedMetric::P999Latency)),
+                                    ("SMI Count", cached.smi_count_a as f32, cached.smi_count_b as f32, cached.smi_delta, true, "System Management Interrupt occurrences", stats(ComparedMetric::SmiCount)),
+                                    ("Stall Correlated", cached.stall_count_a as f32, cached.stall_count_b as f32, cached.stall_delta, true, "Latency spikes correlated to SMI events", stats(ComparedMetric::StallCount)),
                                 ];
 
                                 // Header row for comparison metrics
                                 ui.label("Detailed Metric Comparison:");
+                                ui.label(
+                                    egui::RichText::new(format!(
+                                        "Medians of {} vs {} runs · Mann-Whitney U, significant at p < {}",
+                                        cached.comparison.runs_a,
+                                        cached.comparison.runs_b,
+                                        experiment::SIGNIFICANCE_LEVEL
+                                    ))
+                                    .small(),
+                                );
+                                if cached.comparison.runs_a < experiment::MIN_RUNS
+                                    || cached.comparison.runs_b < experiment::MIN_RUNS
+                                {
+                                    ui.colored_label(
+                                        egui::Color32::from_rgb(0xda, 0x85, 0x48),
+                                        format!(
+                                            "Single runs are noise: run an A/B experiment with at least {} repetitions per kernel",
+                                            experiment::MIN_RUNS
+                                        ),
+                                    );
+                                }
                                 ui.separator();
 
                                 // Reduce spacing between metric cards
@@ -2371,7 +2868,7 @@ pub fn render_performance(ui: &mut egui::Ui, controller: &Arc<RwLock<AppControll
 
                                 if use_full_width_cards {
                                     // ===== WIDE LAYOUT (>600px): Full-width 4-column cards =====
-                                    for (metric_label, val_a, val_b, delta, is_lower_better, tooltip) in metric_rows {
+                                    for (metric_label, val_a, val_b, delta, is_lower_better, tooltip, stats) in metric_rows {
                                         let card_bg_color = egui::Color32::from_rgba_unmultiplied(30, 35, 40, 200);
 
                                         ui.group(|ui| {
@@ -2443,7 +2940,7 @@ pub fn render_performance(ui: &mut egui::Ui, controller: &Arc<RwLock<AppControll
 
                                             let delta_clamped = delta.max(-100.0).min(100.0);
                                             let filled_width = (delta_clamped.abs() / 100.0) * (bar_width / 2.0 - 4.0);
-                                            let bar_color = get_delta_color(delta, is_lower_better);
+                                            let bar_color = get_verdict_color(stats, delta, is_lower_better);
 
                                             let bar_rect = if delta_clamped < 0.0 {
                                                 egui::Rect::from_min_max(
@@ -2472,13 +2969,21 @@ pub fn render_performance(ui: &mut egui::Ui, controller: &Arc<RwLock<AppControll
                                                 egui::FontId::new(14.0, egui::FontFamily::Monospace),
                                                 bar_color,
                                             );
+
+                                            painter.text(
+                                                egui::pos2(bar_x, content_rect.min.y + 31.0),
+                                                egui::Align2::LEFT_TOP,
+                                                format_verdict(stats),
+                                                egui::FontId::new(10.0, egui::FontFamily::Proportional),
+                                                bar_color,
+                                            );
                                         });
 
                                         ui.separator();
                                     }
                                 } else {
                                     // ===== NARROW LAYOUT (<=600px): Stacked vertical cards =====
-                                    for (metric_label, val_a, val_b, delta, is_lower_better, tooltip) in metric_rows {
+                                    for (metric_label, val_a, val_b, delta, is_lower_better, tooltip, stats) in metric_rows {
                                         ui.group(|ui| {
                                             ui.vertical(|ui| {
                                                 // Header: Metric name with tooltip
@@ -2508,13 +3013,14 @@ pub fn render_performance(ui: &mut egui::Ui, controller: &Arc<RwLock<AppControll
                                                 // Delta row
                                                 ui.horizontal(|ui| {
                                                     ui.label("Change:");
-                                                    let bar_color = get_delta_color(delta, is_lower_better);
+                                                    let bar_color = get_verdict_color(stats, delta, is_lower_better);
                                                     ui.colored_label(
                                                         bar_color,
                                                         egui::RichText::new(format!("{:+.1}%", delta))
                                                             .monospace()
                                                             .strong(),
                                                     );
+                                                    ui.colored_label(bar_color, egui::RichText::new(format_verdict(stats)).small());
                                                 });
                                             });
                                         });
@@ -12,6 +12,8 @@ use tokio::sync::RwLock;
 pub struct SettingsUIState {
     pub workspace_path: String,
     pub secure_boot_enabled: bool,
+    pub mok_key_path: String,
+    pub mok_cert_path: String,
     pub verify_signatures: bool,
     pub prep_timeout_mins: String,
     pub config_timeout_mins: String,
@@ -25,6 +27,15 @@ pub struct SettingsUIState {
     pub save_window_state: bool,
     pub debug_logging: bool,
     pub tokio_tracing: bool,
+    // Kernel Sources: add-form fields for registering a custom source
+    pub source_name: String,
+    pub source_git_url: String,
+    pub source_pkgbuild_url: String,
+    pub source_ref_kind: usize,
+    pub source_ref_value: String,
+    pub source_error: Option<String>,
+    pub source_mirror_path: String,
+    pub offline_sources: bool,
 }
 
 /// Render the Settings tab
@@ -45,6 +56,8 @@ pub fn render_settings(
             if let Ok(state) = guard.get_state() {
                 app_ui_state.workspace_path = state.workspace_path.clone();
                 app_ui_state.secure_boot_enabled = state.secure_boot;
+                app_ui_state.mok_key_path = state.mok_key_path.clone();
+                app_ui_state.mok_cert_path = state.mok_cert_path.clone();
                 app_ui_state.verify_signatures = state.verify_signatures;
                 app_ui_state.prep_timeout_mins = "5".to_string();
                 app_ui_state.config_timeout_mins = "5".to_string();
@@ -58,6 +71,8 @@ pub fn render_settings(
                 app_ui_state.save_window_state = state.save_window_state;
                 app_ui_state.debug_logging = state.debug_logging;
                 app_ui_state.tokio_tracing = state.tokio_tracing;
+                app_ui_state.source_mirror_path = state.source_mirror_path.clone();
+                app_ui_state.offline_sources = state.offline_sources;
             }
         }
     }
@@ -191,6 +206,56 @@ pub fn render_settings(
             });
         }
 
+        // MOK key pair used to sign the kernel image and modules
+        if app_ui_state.secure_boot_enabled {
+            let mut mok_changed = false;
+            ui.horizontal(|ui| {
+                ui.label("MOK Private Key:");
+                mok_changed |= ui
+                    .text_edit_singleline(&mut app_ui_state.mok_key_path)
+                    .changed();
+                if ui.button("Browse...").clicked() {
+                    if let Some(path) = rfd::FileDialog::new().pick_file() {
+                        app_ui_state.mok_key_path = path.to_string_lossy().to_string();
+                        mok_changed = true;
+                    }
+                }
+            });
+            ui.horizontal(|ui| {
+                ui.label("MOK Certificate:");
+                mok_changed |= ui
+                    .text_edit_singleline(&mut app_ui_state.mok_cert_path)
+                    .changed();
+                if ui.button("Browse...").clicked() {
+                    if let Some(path) = rfd::FileDialog::new().pick_file() {
+                        app_ui_state.mok_cert_path = path.to_string_lossy().to_string();
+                        mok_changed = true;
+                    }
+                }
+            });
+
+            if app_ui_state.mok_key_path.is_empty() || app_ui_state.mok_cert_path.is_empty() {
+                ui.colored_label(
+                    egui::Color32::from_rgb(255, 180, 80),
+                    "⚠ No MOK key/certificate set - kernel and modules will not be signed",
+                );
+            }
+
+            if mok_changed {
+                let controller_clone = Arc::clone(controller);
+                let key = app_ui_state.mok_key_path.clone();
+                let cert = app_ui_state.mok_cert_path.clone();
+                tokio::spawn(async move {
+                    if let Ok(controller_handle) = controller_clone.try_read() {
+                        let _ = controller_handle.update_state(|state| {
+                            state.mok_key_path = key.clone();
+                            state.mok_cert_path = cert.clone();
+                        });
+                    }
+                });
+            }
+        }
+
         // Verify signatures checkbox with persistence
         if ui
             .checkbox(
@@ -215,6 +280,177 @@ pub fn render_settings(
 
     ui.separator();
 
+    // Kernel Sources: built-in variants plus user-registered git/PKGBUILD sources
+    ui.group(|ui| {
+        ui.label("Kernel Sources");
+        ui.separator();
+
+        ui.label(format!(
+            "Built-in: {}",
+            crate::kernel::sources::UI_VARIANTS.join(", ")
+        ));
+
+        let custom_sources = controller
+            .try_read()
+            .ok()
+            .and_then(|guard| guard.get_state().ok())
+            .map(|state| state.custom_sources)
+            .unwrap_or_default();
+
+        if custom_sources.is_empty() {
+            ui.label("No custom sources registered");
+        }
+        for source in &custom_sources {
+            ui.horizontal(|ui| {
+                ui.monospace(&source.name);
+                ui.label(format!("{} ({:?})", source.git_url, source.reference));
+                if ui.button("Remove").clicked() {
+                    if let Ok(guard) = controller.try_read() {
+                        if let Err(e) = guard.remove_custom_source(&source.name) {
+                            eprintln!("[UI] [SETTINGS] Failed to remove kernel source: {}", e);
+                        }
+                    }
+                }
+            });
+        }
+
+        ui.separator();
+        ui.label("Add source:");
+        egui::Grid::new("settings_kernel_source_grid")
+            .num_columns(2)
+            .show(ui, |ui| {
+                ui.label("Name:");
+                ui.add(
+                    egui::TextEdit::singleline(&mut app_ui_state.source_name)
+                        .hint_text("linux-example"),
+                );
+                ui.end_row();
+
+                ui.label("Git URL:");
+                ui.text_edit_singleline(&mut app_ui_state.source_git_url);
+                ui.end_row();
+
+                ui.label("PKGBUILD URL:");
+                ui.add(
+                    egui::TextEdit::singleline(&mut app_ui_state.source_pkgbuild_url)
+                        .hint_text("https://.../PKGBUILD (may contain {ref})"),
+                );
+                ui.end_row();
+
+                ui.label("Ref:");
+                ui.horizontal(|ui| {
+                    let kinds = ["Default branch", "Branch", "Tag", "Latest tag"];
+                    egui::ComboBox::from_id_source("settings_source_ref_combo")
+                        .selected_text(
+                            kinds
+                                .get(app_ui_state.source_ref_kind)
+                                .copied()
+                                .unwrap_or(kinds[0]),
+                        )
+                        .show_ui(ui, |ui| {
+                            for (i, kind) in kinds.iter().enumerate() {
+                                ui.selectable_value(&mut app_ui_state.source_ref_kind, i, *kind);
+                            }
+                        });
+                    if app_ui_state.source_ref_kind != 0 {
+                        let hint = if app_ui_state.source_ref_kind == 3 {
+                            "tag prefix"
+                        } else {
+                            "name"
+                        };
+                        ui.add(
+                            egui::TextEdit::singleline(&mut app_ui_state.source_ref_value)
+                                .hint_text(hint),
+                        );
+                    }
+                });
+                ui.end_row();
+            });
+
+        if ui.button("Add Source").clicked() {
+            use crate::models::{CustomKernelSource, SourceRef};
+            let value = app_ui_state.source_ref_value.trim().to_string();
+            let reference = match app_ui_state.source_ref_kind {
+                1 => SourceRef::Branch(value),
+                2 => SourceRef::Tag(value),
+                3 => SourceRef::LatestTag(value),
+                _ => SourceRef::DefaultBranch,
+            };
+            let source = CustomKernelSource {
+                name: app_ui_state.source_name.trim().to_string(),
+                git_url: app_ui_state.source_git_url.trim().to_string(),
+                pkgbuild_url: app_ui_state.source_pkgbuild_url.trim().to_string(),
+                reference,
+            };
+            let result = match controller.try_read() {
+                Ok(guard) => guard.add_custom_source(source),
+                Err(_) => Err("Controller busy, try again".to_string()),
+            };
+            match result {
+                Ok(()) => {
+                    eprintln!(
+                        "[UI] [SETTINGS] Registered kernel source: {}",
+                        app_ui_state.source_name
+                    );
+                    app_ui_state.source_name.clear();
+                    app_ui_state.source_git_url.clear();
+                    app_ui_state.source_pkgbuild_url.clear();
+                    app_ui_state.source_ref_kind = 0;
+                    app_ui_state.source_ref_value.clear();
+                    app_ui_state.source_error = None;
+                }
+                Err(e) => app_ui_state.source_error = Some(e),
+            }
+        }
+
+        if let Some(err) = &app_ui_state.source_error {
+            ui.colored_label(egui::Color32::from_rgb(255, 100, 100), format!("⚠ {}", err));
+        }
+
+        ui.separator();
+
+        // Local mirror for air-gapped builds (populated by `goatd mirror sync`)
+        let mut mirror_changed = false;
+        ui.horizontal(|ui| {
+            ui.label("Source Mirror:");
+            mirror_changed |= ui
+                .add(
+                    egui::TextEdit::singleline(&mut app_ui_state.source_mirror_path)
+                        .hint_text(crate::kernel::mirror::default_mirror_dir().to_string_lossy()),
+                )
+                .changed();
+            if ui.button("Browse...").clicked() {
+                if let Some(path) = rfd::FileDialog::new().pick_folder() {
+                    app_ui_state.source_mirror_path = path.to_string_lossy().to_string();
+                    mirror_changed = true;
+                }
+            }
+        });
+        mirror_changed |= ui
+            .checkbox(
+                &mut app_ui_state.offline_sources,
+                "Offline sources (clone and resolve versions from the mirror only)",
+            )
+            .changed();
+        ui.label("Populate the mirror while online with `goatd mirror sync`.");
+
+        if mirror_changed {
+            let controller_clone = Arc::clone(controller);
+            let path = app_ui_state.source_mirror_path.clone();
+            let offline = app_ui_state.offline_sources;
+            tokio::spawn(async move {
+                if let Ok(controller_handle) = controller_clone.try_read() {
+                    let _ = controller_handle.update_state(|state| {
+                        state.source_mirror_path = path.clone();
+                        state.offline_sources = offline;
+                    });
+                }
+            });
+        }
+    });
+
+    ui.separator();
+
     // UI Customization
     ui.group(|ui| {
         ui.label("UI Customization");
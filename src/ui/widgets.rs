@@ -4,6 +4,7 @@
 /// - RadialGauge: Circular progress indicator for KPIs
 /// - Sparkline: Compact line chart for historical data
 /// - CPUHeatmap: Temperature distribution across cores
+/// - CPULatencyHeatmap: P99 latency distribution across measured cores
 /// - TerminalViewport: Monospace log viewer
 use eframe::egui;
 use egui::{Color32, Pos2, Rect, Stroke, Vec2};
@@ -318,6 +319,108 @@ pub fn cpu_heatmap(ui: &mut egui::Ui, core_temps: &[f32], _label: &str) {
     );
 }
 
+/// Draws a per-core latency heatmap
+///
+/// # Arguments
+/// * `ui` - egui Ui context
+/// * `cells` - (core label, P99 latency in µs) per measured core, e.g. ("P3", 12.5)
+///
+/// Cells run green -> yellow -> red up to 200µs (or the worst core, if higher).
+pub fn cpu_latency_heatmap(ui: &mut egui::Ui, cells: &[(String, f32)]) {
+    if cells.is_empty() {
+        ui.label("No per-core latency data available");
+        return;
+    }
+
+    let max_p99 = cells.iter().fold(0.0_f32, |a, (_, p99)| a.max(*p99));
+    let min_p99 = cells.iter().fold(f32::INFINITY, |a, (_, p99)| a.min(*p99));
+    let scale_max = max_p99.max(200.0);
+
+    // Green (low latency) -> yellow -> red (high latency)
+    let latency_to_color = |p99: f32| -> Color32 {
+        let normalized = (p99 / scale_max).clamp(0.0, 1.0);
+
+        if normalized < 0.5 {
+            let t = normalized * 2.0;
+            Color32::from_rgb((255.0 * t) as u8, 220, 60)
+        } else {
+            let t = (normalized - 0.5) * 2.0;
+            Color32::from_rgb(255, (220.0 * (1.0 - t)) as u8, 60)
+        }
+    };
+
+    // Same layout as the thermal heatmap so both grids line up
+    let cols = (cells.len() as f32).sqrt().ceil().min(8.0) as usize;
+    let rows = cells.len().div_ceil(cols).max(1);
+
+    let available_width = ui.available_width();
+    let heatmap_width = available_width - 10.0;
+    let cell_width = (heatmap_width / cols as f32).max(40.0);
+    let cell_height = 45.0;
+    let heatmap_height = (rows as f32) * cell_height + 10.0;
+
+    let (response, painter) = ui.allocate_painter(
+        Vec2::new(heatmap_width, heatmap_height),
+        egui::Sense::hover(),
+    );
+
+    let rect = response.rect;
+    painter.rect_filled(rect, 2.0, Color32::from_gray(40));
+
+    let cell_padding = 2.0;
+
+    for (i, (label, p99)) in cells.iter().enumerate() {
+        let row = i / cols;
+        let col = i % cols;
+
+        let cell_left = rect.left() + col as f32 * cell_width + cell_padding;
+        let cell_top = rect.top() + row as f32 * cell_height + cell_padding;
+        let cell_rect = Rect::from_min_size(
+            Pos2::new(cell_left, cell_top),
+            Vec2::new(
+                cell_width - 2.0 * cell_padding,
+                cell_height - 2.0 * cell_padding,
+            ),
+        );
+
+        painter.rect_filled(cell_rect, 1.0, latency_to_color(*p99));
+        painter.rect_stroke(cell_rect, 1.0, Stroke::new(1.0, Color32::from_gray(80)));
+
+        // P99 latency in upper half
+        painter.text(
+            Pos2::new(cell_rect.center().x, cell_rect.top() + 12.0),
+            egui::Align2::CENTER_CENTER,
+            format!("{:.1}µs", p99),
+            egui::FontId::new(11.0, egui::FontFamily::Monospace),
+            Color32::BLACK,
+        );
+
+        // Core label in lower half (e.g., "P3", "E12")
+        painter.text(
+            Pos2::new(cell_rect.center().x, cell_rect.bottom() - 8.0),
+            egui::Align2::CENTER_CENTER,
+            label,
+            egui::FontId::new(8.0, egui::FontFamily::Proportional),
+            Color32::BLACK,
+        );
+    }
+
+    painter.rect_stroke(rect, 2.0, Stroke::new(2.0, Color32::from_gray(120)));
+
+    ui.separator();
+    ui.label(
+        egui::RichText::new(format!(
+            "P99 Range: {:.1}µs - {:.1}µs | Cores: {}",
+            min_p99,
+            max_p99,
+            cells.len()
+        ))
+        .monospace()
+        .color(Color32::LIGHT_GRAY)
+        .small(),
+    );
+}
+
 /// Terminal-style log viewer with monospace font
 pub fn terminal_viewport(ui: &mut egui::Ui, log_content: &str) {
     egui::ScrollArea::vertical()
@@ -13,6 +13,14 @@ fn create_dynamic_config(variant: &str) -> KernelConfig {
     KernelConfig {
         version: "latest".to_string(),
         kernel_variant: variant.to_string(),
+        mok_key_path: None,
+        mok_cert_path: None,
+        compiler_cache: Default::default(),
+        cmdline: Default::default(),
+        pgo: Default::default(),
+        build_jobs: None,
+        resources: Default::default(),
+        strict_kconfig: false,
         lto_type: goatd_kernel::models::LtoType::Thin,
         use_modprobed: false,
         use_whitelist: false,
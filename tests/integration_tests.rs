@@ -69,6 +69,14 @@ fn create_test_config() -> KernelConfig {
         native_optimizations: true,
         user_toggled_native_optimizations: false,
         kernel_variant: String::new(),
+        mok_key_path: None,
+        mok_cert_path: None,
+        compiler_cache: Default::default(),
+        cmdline: Default::default(),
+        pgo: Default::default(),
+        build_jobs: None,
+        resources: Default::default(),
+        strict_kconfig: false,
     }
 }
 
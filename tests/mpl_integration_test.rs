@@ -91,6 +91,9 @@ fn test_mpl_metadata_to_shell_format() {
         pkgver: "6.19.0".to_string(),
         pkgrel: "1".to_string(),
         profile_suffix: "-goatd-gaming".to_string(),
+        applied_patches: Vec::new(),
+        pgo_stage: String::new(),
+        pgo_profile: String::new(),
     };
 
     let shell_format = metadata.to_shell_format();
@@ -170,6 +173,12 @@ fn test_mpl_metadata_roundtrip() {
         pkgver: "6.19.0".to_string(),
         pkgrel: "1".to_string(),
         profile_suffix: "-goatd-gaming".to_string(),
+        applied_patches: vec![
+            "0001-sched-fix.patch@1a2b3c4d5e6f".to_string(),
+            "0002-amd-pstate.patch@abcdef012345".to_string(),
+        ],
+        pgo_stage: "optimize".to_string(),
+        pgo_profile: "/mnt/Optane/goatd/pgo/6.19.0/kernel.afdo".to_string(),
     };
 
     // Serialize to shell format
@@ -192,6 +201,9 @@ fn test_mpl_metadata_roundtrip() {
     assert_eq!(deserialized.pkgver, original.pkgver);
     assert_eq!(deserialized.pkgrel, original.pkgrel);
     assert_eq!(deserialized.profile_suffix, original.profile_suffix);
+    assert_eq!(deserialized.applied_patches, original.applied_patches);
+    assert_eq!(deserialized.pgo_stage, original.pgo_stage);
+    assert_eq!(deserialized.pgo_profile, original.pgo_profile);
 }
 
 /// Test 4: MPL file writing and reading
@@ -215,6 +227,9 @@ fn test_mpl_metadata_write_and_read_file() {
         pkgver: "6.19.0".to_string(),
         pkgrel: "1".to_string(),
         profile_suffix: "-goatd-gaming".to_string(),
+        applied_patches: Vec::new(),
+        pgo_stage: String::new(),
+        pgo_profile: String::new(),
     };
 
     // Write metadata to file
@@ -301,6 +316,9 @@ fn test_mpl_bash_sourcing_integration() {
         pkgver: "6.19.0".to_string(),
         pkgrel: "1".to_string(),
         profile_suffix: "-goatd-gaming".to_string(),
+        applied_patches: Vec::new(),
+        pgo_stage: String::new(),
+        pgo_profile: String::new(),
     };
 
     // STEP 2: Write metadata to workspace
@@ -449,6 +467,9 @@ fn test_mpl_cross_mount_scenario() {
         pkgver: "6.19.0".to_string(),
         pkgrel: "1".to_string(),
         profile_suffix: "-goatd-gaming".to_string(),
+        applied_patches: Vec::new(),
+        pgo_stage: String::new(),
+        pgo_profile: String::new(),
     };
 
     // Write metadata to external workspace
@@ -591,6 +612,9 @@ fn test_mpl_production_simulation_with_git_environment() {
         pkgver: "6.19.0".to_string(),
         pkgrel: "1".to_string(),
         profile_suffix: "-goatd-gaming".to_string(),
+        applied_patches: Vec::new(),
+        pgo_stage: String::new(),
+        pgo_profile: String::new(),
     };
 
     let metadata_path = workspace_path.join(".goatd_metadata");
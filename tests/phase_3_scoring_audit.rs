@@ -650,6 +650,8 @@ fn audit_reference_benchmark_impact_on_scoring() {
         task_wakeup_latency_us: 50.0,
         max_core_temp_c: 70.0, // More strict thermal
         cold_temp_c: 35.0,
+        perf_per_watt: 50.0,
+        min_perf_per_watt: 2.0,
     };
 
     let standard_scorer = PerformanceScorer::with_references(standard_benchmarks);
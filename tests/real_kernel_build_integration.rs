@@ -98,6 +98,14 @@ async fn test_async_orchestrator_timeout_with_log_capture() {
         native_optimizations: true,
         user_toggled_native_optimizations: false,
         kernel_variant: "linux".to_string(),
+        mok_key_path: None,
+        mok_cert_path: None,
+        compiler_cache: Default::default(),
+        cmdline: Default::default(),
+        pgo: Default::default(),
+        build_jobs: None,
+        resources: Default::default(),
+        strict_kconfig: false,
     };
 
     // Set test variant to avoid real git operations
@@ -437,6 +445,14 @@ async fn test_build_pipe_lifecycle_gaming() {
         native_optimizations: true,
         user_toggled_native_optimizations: false,
         kernel_variant: "linux-mainline".to_string(),
+        mok_key_path: None,
+        mok_cert_path: None,
+        compiler_cache: Default::default(),
+        cmdline: Default::default(),
+        pgo: Default::default(),
+        build_jobs: None,
+        resources: Default::default(),
+        strict_kconfig: false,
     };
 
     config.kernel_variant = "linux-mainline".to_string();